@@ -0,0 +1,54 @@
+// cleansh-core/benches/engines.rs
+//! Baselines for the engines and the work around them, on the seeded corpora
+//! from `bench_support` so runs on different machines compare:
+//!
+//! * the regex engine over the default rules on 1k and 100k log lines,
+//! * `StrippedIndexMapper::new` on ANSI-colored input,
+//! * getting compiled rules from the cache against compiling them afresh.
+
+use cleansh_core::bench_support::{ansi_corpus, log_corpus, DEFAULT_SEED};
+use cleansh_core::config::RedactionConfig;
+use cleansh_core::engine::{SanitizationEngine, SanitizeContext};
+use cleansh_core::engines::index_map::StrippedIndexMapper;
+use cleansh_core::sanitizers::compiler::{compile_rules, get_or_compile_rules};
+use cleansh_core::RegexEngine;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+fn regex_engine(c: &mut Criterion) {
+    let engine = RegexEngine::new(RedactionConfig::load_default_rules().expect("default rules")).expect("engine");
+    let mut group = c.benchmark_group("regex_engine");
+    group.sample_size(10);
+    for lines in [1_000, 100_000] {
+        let input = log_corpus(lines, DEFAULT_SEED);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::new("sanitize", lines), &input, |b, input| {
+            b.iter(|| engine.sanitize_with(black_box(input), &mut SanitizeContext::new("bench")).expect("sanitize"))
+        });
+    }
+    group.finish();
+}
+
+fn index_mapper(c: &mut Criterion) {
+    let mut group = c.benchmark_group("stripped_index_mapper");
+    for lines in [10, 1_000] {
+        let input = ansi_corpus(lines, DEFAULT_SEED);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::new("new", lines), &input, |b, input| {
+            b.iter(|| StrippedIndexMapper::new(black_box(input)))
+        });
+    }
+    group.finish();
+}
+
+fn compiled_rules_cache(c: &mut Criterion) {
+    let config = RedactionConfig::load_default_rules().expect("default rules");
+    get_or_compile_rules(&config).expect("compile");
+    let rules: Vec<_> = config.active_rules().cloned().collect();
+    let mut group = c.benchmark_group("compiled_rules");
+    group.bench_function("cache_hit", |b| b.iter(|| get_or_compile_rules(black_box(&config)).expect("compile")));
+    group.bench_function("cache_miss", |b| b.iter(|| compile_rules(black_box(rules.clone())).expect("compile")));
+    group.finish();
+}
+
+criterion_group!(benches, regex_engine, index_mapper, compiled_rules_cache);
+criterion_main!(benches);
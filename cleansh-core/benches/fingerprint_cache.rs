@@ -0,0 +1,58 @@
+// cleansh-core/benches/fingerprint_cache.rs
+//! Known-leak lookups against a large vault: the memory-mapped `FingerprintCache`
+//! against a `HashSet` of hex hashes, the in-memory representation it replaces.
+//!
+//! Heap usage of both is printed before the timings. The set's figure is an
+//! estimate (table slots plus string buffers); the cache's mapped file is page
+//! cache, not heap, and is reported separately.
+
+use std::collections::HashSet;
+
+use cleansh_core::remediation::fingerprint::salted_hash;
+use cleansh_core::remediation::fingerprint_cache::{Fingerprint, FingerprintCache};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+const SALT: &[u8] = b"bench-org-salt";
+
+fn fingerprint(i: usize) -> Fingerprint {
+    salted_hash(&format!("secret-{}", i), SALT)
+}
+
+fn hash_set_heap_bytes(set: &HashSet<String>) -> usize {
+    set.capacity() * (std::mem::size_of::<String>() + 1) + set.iter().map(String::capacity).sum::<usize>()
+}
+
+fn lookups(c: &mut Criterion) {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let mut group = c.benchmark_group("known_leak_lookup");
+    for count in [100_000, 1_000_000] {
+        let set: HashSet<String> = (0..count).map(|i| hex::encode(fingerprint(i))).collect();
+        let path = dir.path().join(format!("{}.fpc", count));
+        FingerprintCache::write(&path, (0..count).map(fingerprint)).expect("write cache");
+        let cache = FingerprintCache::open(&path).expect("open cache");
+
+        let file_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        eprintln!(
+            "{} fingerprints: HashSet<String> ~{} KiB heap; FingerprintCache {} KiB heap + {} KiB mapped",
+            count,
+            hash_set_heap_bytes(&set) / 1024,
+            cache.heap_bytes() / 1024,
+            file_bytes / 1024,
+        );
+
+        // Half hits, half misses, so the bloom filter's early exit shows up.
+        let probes: Vec<Fingerprint> = (0..1024).map(|i| fingerprint(if i % 2 == 0 { i * 97 % count } else { count + i })).collect();
+        let hex_probes: Vec<String> = probes.iter().map(hex::encode).collect();
+
+        group.bench_with_input(BenchmarkId::new("hash_set", count), &hex_probes, |b, probes| {
+            b.iter(|| probes.iter().filter(|p| set.contains(black_box(p.as_str()))).count())
+        });
+        group.bench_with_input(BenchmarkId::new("mapped_cache", count), &probes, |b, probes| {
+            b.iter(|| probes.iter().filter(|p| cache.contains(black_box(p))).count())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, lookups);
+criterion_main!(benches);
@@ -5,22 +5,449 @@
 //! writing `RedactionLog` entries to a file in a JSON Lines format. This
 //! ensures an immutable and auditable record of every sanitization action
 //! performed.
+//!
+//! Logs opened with `AuditLog::new_encrypted` seal every entry line with
+//! AES-256-GCM; `decrypt_audit_log` reads them back.
 
 use crate::redaction_match::RedactionLog;
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use chrono::Utc;
+use keyring::Entry as KeyringEntry;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
 use std::fs::{self, OpenOptions};
-use std::io::{Write, BufWriter};
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// The `prev_hash` of the very first entry in a hash chain.
+pub const CHAIN_GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// The audit format version written by this build.
+///
+/// Bump it whenever a change would make an older reader misread a file (a field
+/// changing meaning, a new record kind), then add the matching `AuditFormatVersion`
+/// variant; the exhaustive match in `read_audit_log` will not compile until the
+/// new version has a reader. Purely additive fields with serde defaults do not
+/// need a bump.
+pub const AUDIT_FORMAT_VERSION: u32 = 3;
+
+/// Every audit format version this build can read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum AuditFormatVersion {
+    /// Headerless JSON Lines of `RedactionLog`, optionally chained.
+    V1 = 1,
+    /// A `{"cleansh_audit_version": 2, ...}` header line followed by v1-style entries.
+    V2 = 2,
+    /// Like v2, but the header may mark the file encrypted, in which case every
+    /// entry line is sealed with AES-256-GCM.
+    V3 = 3,
+}
+
+impl AuditFormatVersion {
+    /// The version written by this build.
+    pub const CURRENT: Self = Self::V3;
+
+    /// Resolves a header's version number.
+    pub fn from_number(version: u32) -> Result<Self> {
+        match version {
+            1 => Ok(Self::V1),
+            2 => Ok(Self::V2),
+            3 => Ok(Self::V3),
+            other => bail!(
+                "Unsupported audit format version {} (this build reads up to {})",
+                other,
+                AUDIT_FORMAT_VERSION
+            ),
+        }
+    }
+}
+
+const _: () = assert!(
+    AuditFormatVersion::CURRENT as u32 == AUDIT_FORMAT_VERSION,
+    "AuditFormatVersion::CURRENT must match AUDIT_FORMAT_VERSION"
+);
+
+/// The first line of every v2+ audit file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditHeader {
+    pub cleansh_audit_version: u32,
+    pub created_at: String,
+    /// Whether entries in this file carry `prev_hash`/`entry_hash` links.
+    #[serde(default)]
+    pub hash_chain: bool,
+    /// [`AUDIT_ENCRYPTION`] when every entry line is encrypted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<String>,
+    /// Identifies the key the entries were encrypted with, so a wrong key is told
+    /// apart from a corrupted line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_check: Option<String>,
+    /// The `prev_hash` the first entry of a chained file links to: the genesis hash,
+    /// or the last entry of the file this one was rotated from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_start: Option<String>,
+    /// The length of a chained, unencrypted file as of its last flush. Always the
+    /// last field, so it can be rewritten in place.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sealed: Option<ChainSeal>,
+}
+
+/// How many entries a chained file held at its last flush, and the hash of the last
+/// of them, so `verify_chain` can tell when entries were cut from the end.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainSeal {
+    pub entries: u64,
+    pub last_hash: String,
+}
+
+impl ChainSeal {
+    /// Renders the seal at a fixed width, so rewriting it never moves the entries.
+    fn render(&self) -> String {
+        format!("{{\"entries\":{:<20},\"last_hash\":\"{:<64}\"}}", self.entries, self.last_hash)
+    }
+}
+
+impl AuditHeader {
+    fn current(hash_chain: bool) -> Self {
+        Self {
+            cleansh_audit_version: AUDIT_FORMAT_VERSION,
+            created_at: Utc::now().to_rfc3339(),
+            hash_chain,
+            encryption: None,
+            key_check: None,
+            chain_start: None,
+            sealed: None,
+        }
+    }
+
+    fn encrypted_with(mut self, cipher: &AuditCipher) -> Self {
+        self.encryption = Some(AUDIT_ENCRYPTION.to_string());
+        self.key_check = Some(cipher.key_check.clone());
+        self
+    }
+
+    /// Whether the entries following this header are encrypted.
+    pub fn is_encrypted(&self) -> bool {
+        self.encryption.is_some()
+    }
+
+    /// Parses `line` as a header, returning `None` when it is an entry instead.
+    fn parse(line: &str) -> Option<Self> {
+        serde_json::from_str(line).ok()
+    }
+
+    /// The header as a line without its newline, with the seal spliced in last.
+    fn to_line(&self) -> Result<String> {
+        let unsealed = Self { sealed: None, ..self.clone() };
+        let mut line = serde_json::to_string(&unsealed).context("Failed to serialize audit header")?;
+        if let Some(seal) = &self.sealed {
+            line.pop();
+            line.push_str(",\"sealed\":");
+            line.push_str(&seal.render());
+            line.push('}');
+        }
+        Ok(line)
+    }
+}
+
+/// Byte offset of the seal within a header `line`, if it carries one this log can
+/// rewrite.
+fn seal_offset(line: &str) -> Option<u64> {
+    AuditHeader::parse(line)?.sealed?;
+    let width = ChainSeal { entries: 0, last_hash: String::new() }.render().len();
+    let at = line.len().checked_sub(width + 1)?;
+    (line[..at].ends_with("\"sealed\":") && line.ends_with("}}")).then_some(at as u64)
+}
+
+/// The contents of an audit file, normalized to the current entry shape.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditFile {
+    /// The version the file was written in.
+    pub version: AuditFormatVersion,
+    pub entries: Vec<RedactionLog>,
+    /// `true` when every entry carries hash chain links.
+    pub hash_chain: bool,
+}
+
+/// Rotation, integrity, and batching settings for an `AuditLog`.
+///
+/// # Crash safety
+///
+/// Entries are buffered in memory and written in batches, so a crash can lose at
+/// most the last unflushed batch: up to `batch_entries` entries, `batch_bytes`
+/// bytes, or `batch_interval_ms` worth of appends, whichever limit is hit first.
+/// Engines flush at the end of every `sanitize` call and the log flushes on drop.
+/// Set `durable` when no entry may be lost; every append is then written and
+/// fsynced individually, at a large throughput cost.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditLogConfig {
+    /// Rotate the log once appending an entry would grow it past this many bytes.
+    /// `None` disables rotation.
+    pub max_bytes: Option<u64>,
+    /// How many rotated files to keep; older ones are deleted.
+    pub keep_rotated: usize,
+    /// Chain each entry to the previous one with a SHA-256 hash (see `verify_chain`).
+    pub hash_chain: bool,
+    /// Write the buffer once it holds this many entries.
+    pub batch_entries: usize,
+    /// Write the buffer once it holds this many bytes.
+    pub batch_bytes: usize,
+    /// Write the buffer once its oldest entry is this old, on the next append or
+    /// `flush_if_due`.
+    pub batch_interval_ms: u64,
+    /// Write and fsync every entry immediately, bypassing the batch buffer.
+    pub durable: bool,
+}
+
+impl Default for AuditLogConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: None,
+            keep_rotated: 5,
+            hash_chain: false,
+            batch_entries: 256,
+            batch_bytes: 64 * 1024,
+            batch_interval_ms: 1000,
+            durable: false,
+        }
+    }
+}
+
+/// On-disk form of a chained entry: the `RedactionLog` fields plus the chain links.
+#[derive(Serialize)]
+struct ChainedEntryRef<'a> {
+    #[serde(flatten)]
+    entry: &'a RedactionLog,
+    prev_hash: &'a str,
+    entry_hash: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ChainedEntry {
+    #[serde(flatten)]
+    entry: RedactionLog,
+    prev_hash: String,
+    entry_hash: String,
+}
+
+/// An entry as stored, with or without chain links; used when reading and migrating.
+#[derive(Serialize, Deserialize)]
+struct StoredEntry {
+    #[serde(flatten)]
+    entry: RedactionLog,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    prev_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    entry_hash: Option<String>,
+}
+
+/// Computes `SHA-256(prev_hash || entry_json)` as lowercase hex.
+fn chain_hash(prev_hash: &str, entry: &RedactionLog) -> Result<String> {
+    let content = serde_json::to_string(entry).context("Failed to serialize RedactionLog to JSON")?;
+    let mut hasher = Sha256::new();
+    hasher.update(prev_hash.as_bytes());
+    hasher.update(content.as_bytes());
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// The `encryption` an `AuditHeader` names for encrypted files.
+pub const AUDIT_ENCRYPTION: &str = "aes-256-gcm";
+
+/// Length in bytes of an audit encryption key.
+pub const AUDIT_KEY_LEN: usize = 32;
+
+/// The environment variable `cleansh audit decrypt` reads the hex key from by default.
+pub const DEFAULT_AUDIT_KEY_ENV: &str = "CLEANSH_AUDIT_KEY";
+
+const AUDIT_KEYRING_SERVICE: &str = "cleansh";
+const AUDIT_KEYRING_USERNAME: &str = "audit-encryption";
+
+/// An AES-256 key for encrypted audit logs.
+#[derive(Clone, PartialEq, Eq)]
+pub struct AuditKey([u8; AUDIT_KEY_LEN]);
+
+impl AuditKey {
+    pub fn from_bytes(bytes: [u8; AUDIT_KEY_LEN]) -> Self {
+        Self(bytes)
+    }
+
+    /// Parses a key written as 64 hex characters.
+    pub fn from_hex(hex_key: &str) -> Result<Self> {
+        let bytes = hex::decode(hex_key.trim()).context("Audit key is not valid hex")?;
+        let bytes: [u8; AUDIT_KEY_LEN] = bytes
+            .try_into()
+            .map_err(|b: Vec<u8>| anyhow!("Audit key must be {} bytes, got {}", AUDIT_KEY_LEN, b.len()))?;
+        Ok(Self(bytes))
+    }
+
+    /// A fresh random key.
+    pub fn generate() -> Result<Self> {
+        let mut bytes = [0u8; AUDIT_KEY_LEN];
+        SystemRandom::new()
+            .fill(&mut bytes)
+            .map_err(|_| anyhow!("Failed to generate an audit key"))?;
+        Ok(Self(bytes))
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    /// A short fingerprint of the key; reveals nothing about the key itself.
+    fn check_value(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(b"cleansh-audit-key-check");
+        hasher.update(self.0);
+        hex::encode(&hasher.finalize()[..8])
+    }
+}
+
+impl fmt::Debug for AuditKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AuditKey(..)")
+    }
+}
+
+/// Where an encrypted audit log's key comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditKeySource {
+    /// A hex-encoded key in this environment variable.
+    HexEnv(String),
+    /// The key kept in the OS keyring; `AuditLog::new_encrypted` creates and
+    /// stores one on first use.
+    Keyring,
+    /// A key the caller already holds.
+    Key(AuditKey),
+}
+
+impl AuditKeySource {
+    /// Reads the key, failing when there is none.
+    pub fn resolve(&self) -> Result<AuditKey> {
+        match self {
+            Self::HexEnv(var) => {
+                let value = std::env::var(var).map_err(|_| anyhow!("{} is not set; export the hex-encoded audit key", var))?;
+                AuditKey::from_hex(&value).with_context(|| format!("{} does not hold a valid audit key", var))
+            }
+            Self::Keyring => {
+                let stored = keyring_entry()?
+                    .get_password()
+                    .context("No audit key is stored in the OS keyring")?;
+                AuditKey::from_hex(&stored).context("The audit key in the OS keyring is invalid")
+            }
+            Self::Key(key) => Ok(key.clone()),
+        }
+    }
+
+    /// Like `resolve`, but creates and stores a keyring key when none exists yet.
+    /// Fails rather than encrypt with a key that could not be saved.
+    fn resolve_or_create(&self) -> Result<AuditKey> {
+        if *self != Self::Keyring || keyring_entry()?.get_password().is_ok() {
+            return self.resolve();
+        }
+        let key = AuditKey::generate()?;
+        keyring_entry()?
+            .set_password(&key.to_hex())
+            .context("Failed to store a new audit key in the OS keyring")?;
+        Ok(key)
+    }
+}
+
+fn keyring_entry() -> Result<KeyringEntry> {
+    KeyringEntry::new(AUDIT_KEYRING_SERVICE, AUDIT_KEYRING_USERNAME).context("OS keyring is unavailable")
+}
+
+/// Seals and opens single entry lines as `<nonce>.<ciphertext>`, both base64, so
+/// each line decrypts on its own and a torn write loses only that line.
+struct AuditCipher {
+    key: LessSafeKey,
+    key_check: String,
+    rng: SystemRandom,
+}
+
+impl AuditCipher {
+    fn new(key: &AuditKey) -> Result<Self> {
+        let unbound = UnboundKey::new(&AES_256_GCM, &key.0).map_err(|_| anyhow!("Invalid audit key"))?;
+        Ok(Self { key: LessSafeKey::new(unbound), key_check: key.check_value(), rng: SystemRandom::new() })
+    }
+
+    fn seal(&self, plaintext: &str) -> Result<String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng.fill(&mut nonce).map_err(|_| anyhow!("Failed to generate an audit entry nonce"))?;
+        let mut sealed = plaintext.as_bytes().to_vec();
+        self.key
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut sealed)
+            .map_err(|_| anyhow!("Failed to encrypt audit entry"))?;
+        Ok(format!("{}.{}", general_purpose::STANDARD.encode(nonce), general_purpose::STANDARD.encode(sealed)))
+    }
+
+    fn open(&self, line: &str) -> Result<String> {
+        let (nonce, sealed) = line.trim_end().split_once('.').ok_or_else(|| anyhow!("not an encrypted entry"))?;
+        let nonce: [u8; NONCE_LEN] = general_purpose::STANDARD
+            .decode(nonce)?
+            .try_into()
+            .map_err(|_| anyhow!("bad nonce length"))?;
+        let mut sealed = general_purpose::STANDARD.decode(sealed)?;
+        let plaintext = self
+            .key
+            .open_in_place(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut sealed)
+            .map_err(|_| anyhow!("authentication failed"))?;
+        Ok(String::from_utf8(plaintext.to_vec())?)
+    }
+
+    /// Fails unless `header` marks a file encrypted with this cipher's key.
+    fn check_header(&self, header: &AuditHeader, path: &Path) -> Result<()> {
+        if !header.is_encrypted() {
+            bail!("Audit log {} is not encrypted", path.display());
+        }
+        if header.key_check.as_deref() != Some(self.key_check.as_str()) {
+            bail!("Audit log {} was encrypted with a different key", path.display());
+        }
+        Ok(())
+    }
+}
+
+/// The header of the audit file at `path`, if it exists and starts with one.
+fn read_header(path: &Path) -> Result<Option<AuditHeader>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let file = fs::File::open(path)
+        .with_context(|| format!("Failed to open audit log at {}", path.display()))?;
+    let mut first = String::new();
+    BufReader::new(file).read_line(&mut first).context("Failed to read audit log")?;
+    Ok(AuditHeader::parse(first.trim_end()))
+}
 
 /// Manages an append-only audit log file for redaction events.
 ///
 /// The `AuditLog` struct provides a simple interface for writing `RedactionLog`
 /// entries to a specified file, ensuring each entry is a valid JSON object on
 /// a new line. This design is crucial for auditability and compliance.
+/// Appends are batched; see `AuditLogConfig` for the crash-safety trade-off.
 pub struct AuditLog {
     path: PathBuf,
-    // Using BufWriter for buffered writes improves performance, especially with many small writes.
-    writer: BufWriter<fs::File>,
+    config: AuditLogConfig,
+    file: fs::File,
+    // Serialized lines not yet written to `file`.
+    pending: Vec<u8>,
+    pending_entries: usize,
+    pending_since: Option<Instant>,
+    // Size of the current file including `pending`.
+    bytes_written: u64,
+    last_hash: String,
+    // Entries in the current file, including `pending`.
+    entries: u64,
+    // Where the current file's seal starts, when it has one.
+    seal_at: Option<u64>,
+    write_count: u64,
+    cipher: Option<AuditCipher>,
 }
 
 impl AuditLog {
@@ -29,41 +456,107 @@ impl AuditLog {
     ///
     /// This method is designed to be resilient. It will create the necessary
     /// parent directories if they don't exist and opens the file in a way
-    /// that new entries are always added to the end.
+    /// that new entries are always added to the end. A new (empty) file starts
+    /// with an `AuditHeader` line. When hash chaining is enabled, the chain
+    /// continues from the last entry already in the file.
     ///
     /// # Arguments
     ///
     /// * `path` - The file path for the audit log.
+    /// * `config` - Rotation and hash chain settings.
     ///
     /// # Returns
     ///
     /// A `Result` containing the `AuditLog` instance on success, or an error
     /// if the file cannot be created or opened.
-    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let path_buf = path.as_ref().to_path_buf();
+    pub fn new<P: AsRef<Path>>(path: P, config: AuditLogConfig) -> Result<Self> {
+        Self::open(path.as_ref(), config, None)
+    }
+
+    /// Like `new`, but every entry line is encrypted with AES-256-GCM under the
+    /// key from `key_source`.
+    ///
+    /// Each line holds its own nonce and ciphertext, so appends stay as crash-safe
+    /// as in a plain log: a torn write loses only the entry it was writing. The
+    /// header stays readable and records which key the file was encrypted with;
+    /// an existing file must be encrypted with the same key.
+    pub fn new_encrypted<P: AsRef<Path>>(path: P, config: AuditLogConfig, key_source: &AuditKeySource) -> Result<Self> {
+        let cipher = AuditCipher::new(&key_source.resolve_or_create()?)?;
+        Self::open(path.as_ref(), config, Some(cipher))
+    }
+
+    fn open(path: &Path, config: AuditLogConfig, cipher: Option<AuditCipher>) -> Result<Self> {
+        let path_buf = path.to_path_buf();
         if let Some(parent) = path_buf.parent() {
             fs::create_dir_all(parent).with_context(|| {
                 format!("Failed to create parent directories for {}", parent.display())
             })?;
         }
 
-        let file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&path_buf)
-            .with_context(|| format!("Failed to open or create audit log at {}", path_buf.display()))?;
+        match (read_header(&path_buf)?, &cipher) {
+            (Some(header), None) if header.is_encrypted() => {
+                bail!("Audit log {} is encrypted; open it with a key", path_buf.display())
+            }
+            (Some(header), Some(cipher)) => cipher.check_header(&header, &path_buf)?,
+            (None, Some(_)) if fs::metadata(&path_buf).is_ok_and(|m| m.len() > 0) => {
+                bail!("Audit log {} is not encrypted", path_buf.display())
+            }
+            _ => {}
+        }
 
-        Ok(Self {
+        let (last_hash, entries) = if config.hash_chain {
+            let (last_hash, entries) = chain_tail(&path_buf, cipher.as_ref())?;
+            (last_hash.unwrap_or_else(|| CHAIN_GENESIS_HASH.to_string()), entries)
+        } else {
+            (CHAIN_GENESIS_HASH.to_string(), 0)
+        };
+        let seal_at = match fs::File::open(&path_buf) {
+            Ok(file) => {
+                let mut first = String::new();
+                BufReader::new(file).read_line(&mut first).context("Failed to read audit log")?;
+                seal_offset(first.trim_end_matches('\n'))
+            }
+            Err(_) => None,
+        };
+        let (file, bytes_written) = open_for_append(&path_buf)?;
+
+        let mut audit_log = Self {
             path: path_buf,
-            writer: BufWriter::new(file),
-        })
+            config,
+            file,
+            pending: Vec::new(),
+            pending_entries: 0,
+            pending_since: None,
+            bytes_written,
+            last_hash,
+            entries,
+            seal_at,
+            write_count: 0,
+            cipher,
+        };
+        audit_log.write_header_if_empty()?;
+        Ok(audit_log)
+    }
+
+    fn write_header_if_empty(&mut self) -> Result<()> {
+        if self.bytes_written > 0 {
+            return Ok(());
+        }
+        let mut header = self.header().to_line()?;
+        self.seal_at = seal_offset(&header);
+        header.push('\n');
+        self.write_to_file(header.as_bytes()).context("Failed to write audit header")?;
+        self.bytes_written = header.len() as u64;
+        Ok(())
     }
 
     /// Appends a new `RedactionLog` entry to the audit log file.
     ///
-    /// The entry is serialized to a JSON string and written to the file,
-    /// followed by a newline character. This ensures the log remains
-    /// a stream of valid JSON Lines, which is easy to parse.
+    /// The entry is serialized to a JSON string followed by a newline
+    /// character. This ensures the log remains a stream of valid JSON Lines,
+    /// which is easy to parse. The line is buffered until a batch limit is
+    /// reached (or written at once when `durable` is set). If the line would
+    /// push the file past `max_bytes`, the file is rotated first.
     ///
     /// # Arguments
     ///
@@ -73,36 +566,448 @@ impl AuditLog {
     ///
     /// A `Result` indicating success or failure of the write operation.
     pub fn append(&mut self, log_entry: &RedactionLog) -> Result<()> {
-        let json_line = serde_json::to_string(log_entry)
+        let mut entry_hash = None;
+        let json_line = if self.config.hash_chain {
+            let hash = chain_hash(&self.last_hash, log_entry)?;
+            let line = serde_json::to_string(&ChainedEntryRef {
+                entry: log_entry,
+                prev_hash: &self.last_hash,
+                entry_hash: &hash,
+            })
             .context("Failed to serialize RedactionLog to JSON")?;
-        self.writer
-            .write_all(json_line.as_bytes())
-            .context("Failed to write log entry")?;
-        self.writer
-            .write_all(b"\n")
-            .context("Failed to write newline")?;
-        self.writer.flush().context("Failed to flush audit log after append")?;
+            entry_hash = Some(hash);
+            line
+        } else {
+            serde_json::to_string(log_entry).context("Failed to serialize RedactionLog to JSON")?
+        };
+        let json_line = match &self.cipher {
+            Some(cipher) => cipher.seal(&json_line)?,
+            None => json_line,
+        };
+
+        let line_len = json_line.len() as u64 + 1;
+        if let Some(max_bytes) = self.config.max_bytes {
+            if self.bytes_written > self.header_len() && self.bytes_written + line_len > max_bytes {
+                self.rotate()?;
+            }
+        }
+
+        self.pending.extend_from_slice(json_line.as_bytes());
+        self.pending.push(b'\n');
+        self.pending_entries += 1;
+        self.pending_since.get_or_insert_with(Instant::now);
+        self.bytes_written += line_len;
+        self.entries += 1;
+        if let Some(hash) = entry_hash {
+            self.last_hash = hash;
+        }
+
+        if self.config.durable || self.batch_full() {
+            self.flush()?;
+        }
         Ok(())
     }
 
-    /// Forces a flush of any buffered data to disk.
+    fn batch_full(&self) -> bool {
+        self.pending_entries >= self.config.batch_entries
+            || self.pending.len() >= self.config.batch_bytes
+            || self.interval_elapsed()
+    }
+
+    /// Writes any buffered entries to the file (and fsyncs when `durable` is set),
+    /// then updates the header's seal to cover them.
     ///
-    /// This can be called in long-running sessions to ensure logs are persisted
-    /// before the `AuditLog` is dropped.
+    /// Engines call this at the end of each `sanitize`; long-running callers can
+    /// also call it to bound how much a crash could lose.
     pub fn flush(&mut self) -> Result<()> {
-        self.writer.flush().context("Failed to flush audit log")
+        if !self.pending.is_empty() {
+            // The batch stays buffered until it is written, so a failed write loses
+            // nothing and the next flush tries again.
+            self.write_count += 1;
+            self.file.write_all(&self.pending).context("Failed to write audit log entries")?;
+            self.pending.clear();
+            self.pending_entries = 0;
+            self.pending_since = None;
+            if self.config.durable {
+                self.file.sync_data().context("Failed to sync audit log")?;
+            }
+            self.write_seal().context("Failed to update the audit log seal")?;
+        }
+        Ok(())
+    }
+
+    /// Rewrites the seal in the header to the entries written so far. Entries are
+    /// always on disk before the seal that counts them.
+    fn write_seal(&mut self) -> std::io::Result<()> {
+        let Some(at) = self.seal_at else {
+            return Ok(());
+        };
+        let seal = ChainSeal { entries: self.entries, last_hash: self.last_hash.clone() };
+        let mut file = OpenOptions::new().write(true).open(&self.path)?;
+        file.seek(SeekFrom::Start(at))?;
+        file.write_all(seal.render().as_bytes())?;
+        if self.config.durable {
+            file.sync_data()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the buffer if its oldest entry has waited `batch_interval_ms`, and
+    /// returns whether it did.
+    ///
+    /// The log has no thread of its own, so `append` is the only place the interval
+    /// is otherwise checked. Callers that hold a log between bursts of appends call
+    /// this from their own timer to bound how long an entry stays in memory.
+    pub fn flush_if_due(&mut self) -> Result<bool> {
+        if !self.interval_elapsed() {
+            return Ok(false);
+        }
+        self.flush()?;
+        Ok(true)
+    }
+
+    fn interval_elapsed(&self) -> bool {
+        self.pending_since.is_some_and(|since| {
+            since.elapsed() >= Duration::from_millis(self.config.batch_interval_ms)
+        })
+    }
+
+    fn write_to_file(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        self.write_count += 1;
+        self.file.write_all(bytes)
+    }
+
+    /// Number of write calls issued to the underlying file so far.
+    pub fn write_count(&self) -> u64 {
+        self.write_count
+    }
+
+    /// Byte length of the header line this log writes to a fresh file.
+    fn header_len(&self) -> u64 {
+        self.header().to_line().map(|h| h.len() as u64 + 1).unwrap_or(0)
+    }
+
+    /// The header this log writes to a fresh file. A chained file is anchored to
+    /// the current end of the chain, and sealed unless it is encrypted.
+    fn header(&self) -> AuditHeader {
+        let mut header = AuditHeader::current(self.config.hash_chain);
+        if self.config.hash_chain {
+            header.chain_start = Some(self.last_hash.clone());
+            if self.cipher.is_none() {
+                header.sealed = Some(ChainSeal { entries: 0, last_hash: self.last_hash.clone() });
+            }
+        }
+        match &self.cipher {
+            Some(cipher) => header.encrypted_with(cipher),
+            None => header,
+        }
     }
 
     /// Returns the file path of the audit log.
     pub fn path(&self) -> &Path {
         &self.path
     }
+
+    /// Returns the rotated files for this log, oldest first.
+    pub fn rotated_files(&self) -> Result<Vec<PathBuf>> {
+        let file_name = self.file_name()?;
+        let prefix = format!("{}.", file_name);
+        let dir = match self.path.parent() {
+            Some(p) if !p.as_os_str().is_empty() => p.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let mut rotated: Vec<PathBuf> = fs::read_dir(&dir)
+            .with_context(|| format!("Failed to list {}", dir.display()))?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+            .map(|entry| entry.path())
+            .collect();
+        // Timestamps are fixed-width, so lexical order is chronological order.
+        rotated.sort();
+        Ok(rotated)
+    }
+
+    fn file_name(&self) -> Result<String> {
+        self.path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .ok_or_else(|| anyhow!("Audit log path {} has no file name", self.path.display()))
+    }
+
+    /// Moves the current file aside as `<name>.<timestamp>` and starts a fresh one.
+    /// The hash chain carries over: the new file's header is anchored to the last
+    /// entry of the rotated one, and its first entry links to it.
+    fn rotate(&mut self) -> Result<()> {
+        self.flush().context("Failed to flush audit log before rotation")?;
+
+        let stamp = Utc::now().format("%Y%m%dT%H%M%S%.6fZ").to_string();
+        let base = format!("{}.{}", self.file_name()?, stamp);
+        let mut target = self.path.with_file_name(&base);
+        let mut counter = 1;
+        while target.exists() {
+            target = self.path.with_file_name(format!("{}-{}", base, counter));
+            counter += 1;
+        }
+        fs::rename(&self.path, &target)
+            .with_context(|| format!("Failed to rotate audit log to {}", target.display()))?;
+
+        let (file, bytes_written) = open_for_append(&self.path)?;
+        self.file = file;
+        self.bytes_written = bytes_written;
+        self.entries = 0;
+        self.write_header_if_empty()?;
+
+        let rotated = self.rotated_files()?;
+        let excess = rotated.len().saturating_sub(self.config.keep_rotated);
+        for old in &rotated[..excess] {
+            fs::remove_file(old)
+                .with_context(|| format!("Failed to remove old audit log {}", old.display()))?;
+        }
+        Ok(())
+    }
+}
+
+fn open_for_append(path: &Path) -> Result<(fs::File, u64)> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open or create audit log at {}", path.display()))?;
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    Ok((file, len))
+}
+
+/// Reads the `entry_hash` of the last line of an existing chained log, if any, and
+/// counts its entries, decrypting the last line with `cipher` when the log is
+/// encrypted.
+fn chain_tail(path: &Path, cipher: Option<&AuditCipher>) -> Result<(Option<String>, u64)> {
+    if !path.exists() {
+        return Ok((None, 0));
+    }
+    let file = fs::File::open(path)
+        .with_context(|| format!("Failed to open audit log at {}", path.display()))?;
+    let mut last = None;
+    let mut entries = 0;
+    for line in BufReader::new(file).lines() {
+        let line = line.context("Failed to read audit log")?;
+        if !line.trim().is_empty() && AuditHeader::parse(&line).is_none() {
+            last = Some(line);
+            entries += 1;
+        }
+    }
+    match last {
+        Some(line) => {
+            let line = match cipher {
+                Some(cipher) => cipher.open(&line).context("Failed to decrypt the last audit log entry")?,
+                None => line,
+            };
+            let chained: ChainedEntry = serde_json::from_str(&line)
+                .context("Existing audit log does not end with a chained entry")?;
+            Ok((Some(chained.entry_hash), entries))
+        }
+        None => Ok((None, 0)),
+    }
+}
+
+/// Verifies the hash chain of a single audit log file.
+///
+/// Every entry must link to the one before it and its `entry_hash` must match its
+/// content. When the header records a `chain_start`, the first entry must link to
+/// it, so entries cut from the head are reported; when it carries a seal, the file
+/// must still hold the sealed entries, ending in the sealed hash, so entries cut
+/// from the tail are reported too. Entries appended after the last seal only have
+/// to chain. A missing trailing newline is reported as truncation.
+///
+/// # Returns
+///
+/// The number of verified entries, or an error naming the first bad line.
+pub fn verify_chain<P: AsRef<Path>>(path: P) -> Result<usize> {
+    let path = path.as_ref();
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read audit log at {}", path.display()))?;
+    if !raw.is_empty() && !raw.ends_with('\n') {
+        bail!("Audit log {} is truncated: last entry is incomplete", path.display());
+    }
+
+    let mut header: Option<AuditHeader> = None;
+    let mut expected_prev: Option<String> = None;
+    let mut count = 0;
+    for (idx, line) in raw.lines().enumerate() {
+        let line_no = idx + 1;
+        if idx == 0 {
+            match AuditHeader::parse(line) {
+                Some(parsed) if parsed.is_encrypted() => bail!(encrypted_log_error(path)),
+                Some(parsed) => {
+                    header = Some(parsed);
+                    continue;
+                }
+                None => {}
+            }
+        }
+        let chained: ChainedEntry = serde_json::from_str(line)
+            .with_context(|| format!("Line {}: entry is not a valid chained record", line_no))?;
+        match (&expected_prev, header.as_ref().and_then(|h| h.chain_start.as_ref())) {
+            (Some(prev), _) if &chained.prev_hash != prev => {
+                bail!("Line {}: chain broken, entry does not link to the previous entry", line_no);
+            }
+            (None, Some(start)) if &chained.prev_hash != start => {
+                bail!("Line {}: first entry does not link to the header's chain start, entries were removed from the head", line_no);
+            }
+            _ => {}
+        }
+        let actual = chain_hash(&chained.prev_hash, &chained.entry)?;
+        if actual != chained.entry_hash {
+            bail!("Line {}: entry hash mismatch, content was modified", line_no);
+        }
+        count += 1;
+        if let Some(seal) = header.as_ref().and_then(|h| h.sealed.as_ref()) {
+            if seal.entries == count as u64 && seal.last_hash != chained.entry_hash {
+                bail!("Line {}: entry does not match the header's seal, the log was rewritten", line_no);
+            }
+        }
+        expected_prev = Some(chained.entry_hash);
+    }
+    if let Some(seal) = header.as_ref().and_then(|h| h.sealed.as_ref()) {
+        if (count as u64) < seal.entries {
+            bail!(
+                "Audit log {} is truncated: the header seals {} entries but only {} remain",
+                path.display(),
+                seal.entries,
+                count
+            );
+        }
+    }
+    Ok(count)
+}
+
+/// Reads an audit file of any supported version.
+///
+/// Files whose first line is an `AuditHeader` are read according to its version;
+/// headerless files are treated as v1.
+pub fn read_audit_log<P: AsRef<Path>>(path: P) -> Result<AuditFile> {
+    let path = path.as_ref();
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read audit log at {}", path.display()))?;
+    let mut lines = raw.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).peekable();
+
+    let version = match lines.peek().and_then(|(_, line)| AuditHeader::parse(line)) {
+        Some(header) if header.is_encrypted() => bail!(encrypted_log_error(path)),
+        Some(header) => {
+            lines.next();
+            AuditFormatVersion::from_number(header.cleansh_audit_version)?
+        }
+        None => AuditFormatVersion::V1,
+    };
+
+    // Adding a version must add an arm here.
+    let stored: Vec<StoredEntry> = match version {
+        AuditFormatVersion::V1 | AuditFormatVersion::V2 | AuditFormatVersion::V3 => lines
+            .map(|(idx, line)| {
+                serde_json::from_str(line)
+                    .with_context(|| format!("Line {}: invalid audit entry in {}", idx + 1, path.display()))
+            })
+            .collect::<Result<_>>()?,
+    };
+
+    let hash_chain = !stored.is_empty() && stored.iter().all(|s| s.prev_hash.is_some() && s.entry_hash.is_some());
+    Ok(AuditFile {
+        version,
+        entries: stored.into_iter().map(|s| s.entry).collect(),
+        hash_chain,
+    })
+}
+
+fn encrypted_log_error(path: &Path) -> String {
+    format!("Audit log {} is encrypted; decrypt it with `cleansh audit decrypt` first", path.display())
+}
+
+/// What `decrypt_audit_log` wrote.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DecryptSummary {
+    /// The number of entries decrypted.
+    pub entries: usize,
+    /// 1-based numbers of the lines that could not be decrypted and were skipped.
+    pub skipped: Vec<usize>,
+}
+
+/// Streams the entries of the encrypted audit log at `path` to `out` as JSON
+/// Lines, as a plain log would hold them (chain links included).
+///
+/// Fails up front when the file is not encrypted or was encrypted with another
+/// key. A line that does not decrypt, such as one torn by a crash, is skipped
+/// with a warning and reported in the summary.
+pub fn decrypt_audit_log<P: AsRef<Path>, W: Write>(path: P, key: &AuditKey, out: &mut W) -> Result<DecryptSummary> {
+    let path = path.as_ref();
+    let cipher = AuditCipher::new(key)?;
+    let header = read_header(path)?.ok_or_else(|| anyhow!("Audit log {} is not encrypted", path.display()))?;
+    AuditFormatVersion::from_number(header.cleansh_audit_version)?;
+    cipher.check_header(&header, path)?;
+
+    let file = fs::File::open(path)
+        .with_context(|| format!("Failed to open audit log at {}", path.display()))?;
+    let mut summary = DecryptSummary::default();
+    // Read raw bytes: a torn line may not even be valid UTF-8.
+    for (idx, line) in BufReader::new(file).split(b'\n').enumerate().skip(1) {
+        let line = line.context("Failed to read audit log")?;
+        let line = String::from_utf8_lossy(&line);
+        if line.trim().is_empty() {
+            continue;
+        }
+        match cipher.open(&line) {
+            Ok(entry) => {
+                writeln!(out, "{}", entry).context("Failed to write decrypted entry")?;
+                summary.entries += 1;
+            }
+            Err(e) => {
+                log::warn!("Line {} of {}: skipping entry that does not decrypt ({})", idx + 1, path.display(), e);
+                summary.skipped.push(idx + 1);
+            }
+        }
+    }
+    Ok(summary)
+}
+
+/// Rewrites an audit file of any supported version in the current format.
+///
+/// Entries are re-serialized with defaults filled in; chain links are kept as-is,
+/// so a chained input still passes `verify_chain` afterwards. Refuses to overwrite
+/// an existing `output`.
+///
+/// # Returns
+///
+/// The version of the input file and the number of migrated entries.
+pub fn migrate_audit_log<P: AsRef<Path>, Q: AsRef<Path>>(input: P, output: Q) -> Result<(AuditFormatVersion, usize)> {
+    let (input, output) = (input.as_ref(), output.as_ref());
+    if output.exists() {
+        bail!("Refusing to overwrite existing file {}", output.display());
+    }
+    let raw = fs::read_to_string(input)
+        .with_context(|| format!("Failed to read audit log at {}", input.display()))?;
+    let source = read_audit_log(input)?;
+
+    let mut out = String::new();
+    let mut header = AuditHeader::current(source.hash_chain);
+    if let Some(old) = read_header(input)? {
+        header.chain_start = old.chain_start;
+        header.sealed = old.sealed;
+    }
+    out.push_str(&header.to_line()?);
+    out.push('\n');
+    let mut count = 0;
+    for line in raw.lines().filter(|l| !l.trim().is_empty() && AuditHeader::parse(l).is_none()) {
+        let stored: StoredEntry = serde_json::from_str(line).context("Invalid audit entry")?;
+        out.push_str(&serde_json::to_string(&stored).context("Failed to serialize audit entry")?);
+        out.push('\n');
+        count += 1;
+    }
+    fs::write(output, out).with_context(|| format!("Failed to write {}", output.display()))?;
+    Ok((source.version, count))
 }
 
 // Ensure the buffer is flushed when the AuditLog is dropped.
 impl Drop for AuditLog {
     fn drop(&mut self) {
-        if let Err(e) = self.writer.flush() {
+        if let Err(e) = self.flush() {
             log::error!("Failed to flush audit log writer: {}", e);
         }
     }
@@ -120,9 +1025,26 @@ mod tests {
         let temp_dir = tempdir()?;
         let log_path = temp_dir.path().join("audit.log");
 
-        let mut audit_log = AuditLog::new(&log_path)?;
+        let mut audit_log = AuditLog::new(&log_path, AuditLogConfig::default())?;
+
+        let log_entry = sample_entry(10);
+
+        audit_log.append(&log_entry)?;
+        audit_log.flush()?; // Ensure it’s persisted for the test
+
+        let log_content = fs::read_to_string(&log_path)?;
+        let expected_json = serde_json::to_string(&log_entry)?;
+        let (header, entry) = log_content.split_once('\n').unwrap();
 
-        let log_entry = RedactionLog {
+        let header: AuditHeader = serde_json::from_str(header)?;
+        assert_eq!(header.cleansh_audit_version, AUDIT_FORMAT_VERSION);
+        assert_eq!(entry, format!("{}\n", expected_json));
+
+        Ok(())
+    }
+
+    fn sample_entry(start: u64) -> RedactionLog {
+        RedactionLog {
             timestamp: "2025-08-09T13:00:00Z".to_string(),
             run_id: "test-run-123".to_string(),
             file_path: "/path/to/test_file.txt".to_string(),
@@ -132,18 +1054,382 @@ mod tests {
             rule_name: "email".to_string(),
             input_hash: "hash123".to_string(),
             match_hash: "matchhash456".to_string(),
-            start: 10,
-            end: 25,
-        };
+            start,
+            end: start + 15,
+        }
+    }
 
-        audit_log.append(&log_entry)?;
-        audit_log.flush()?; // Ensure it’s persisted for the test
+    #[test]
+    fn test_rotation_at_size_boundary() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let log_path = temp_dir.path().join("audit.log");
+        let line_len = serde_json::to_string(&sample_entry(10))?.len() as u64 + 1;
+        let header_len = serde_json::to_string(&AuditHeader::current(false))?.len() as u64 + 1;
 
-        let log_content = fs::read_to_string(&log_path)?;
-        let expected_json = serde_json::to_string(&log_entry)?;
-        
-        assert_eq!(log_content, format!("{}\n", expected_json));
+        // Exactly two entries fit behind the header; the third must go to a fresh file.
+        let config = AuditLogConfig { max_bytes: Some(header_len + line_len * 2), keep_rotated: 2, ..Default::default() };
+        let mut audit_log = AuditLog::new(&log_path, config)?;
+        audit_log.append(&sample_entry(10))?;
+        audit_log.append(&sample_entry(11))?;
+        audit_log.flush()?;
+        assert!(audit_log.rotated_files()?.is_empty());
+        assert_eq!(fs::metadata(&log_path)?.len(), header_len + line_len * 2);
+
+        audit_log.append(&sample_entry(12))?;
+        audit_log.flush()?;
+        assert_eq!(audit_log.rotated_files()?.len(), 1);
+        assert_eq!(fs::metadata(&log_path)?.len(), header_len + line_len);
+
+        // Older rotations beyond `keep_rotated` are pruned.
+        for start in 13..20 {
+            audit_log.append(&sample_entry(start))?;
+        }
+        let rotated = audit_log.rotated_files()?;
+        assert_eq!(rotated.len(), 2);
+        assert!(rotated.iter().all(|p| p.file_name().unwrap().to_string_lossy().starts_with("audit.log.")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_chain_verifies_and_survives_reopen() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let log_path = temp_dir.path().join("audit.log");
+        let config = AuditLogConfig { hash_chain: true, ..Default::default() };
+
+        {
+            let mut audit_log = AuditLog::new(&log_path, config.clone())?;
+            audit_log.append(&sample_entry(10))?;
+            audit_log.append(&sample_entry(11))?;
+        }
+        // A reopened log continues the existing chain.
+        let mut audit_log = AuditLog::new(&log_path, config)?;
+        audit_log.append(&sample_entry(12))?;
+        audit_log.flush()?;
+
+        assert_eq!(verify_chain(&log_path)?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_chain_detects_tampering_and_truncation() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let log_path = temp_dir.path().join("audit.log");
+        let config = AuditLogConfig { hash_chain: true, ..Default::default() };
+        let mut audit_log = AuditLog::new(&log_path, config)?;
+        for start in 10..13 {
+            audit_log.append(&sample_entry(start))?;
+        }
+        drop(audit_log);
+        let original = fs::read(&log_path)?;
+
+        // Flip one byte inside the second entry's rule name (line 3, after the header).
+        let lines: Vec<&[u8]> = original.split_inclusive(|&b| b == b'\n').collect();
+        let mut tampered = original.clone();
+        let second_entry = lines[0].len() + lines[1].len();
+        let offset = second_entry + original[second_entry..].windows(5).position(|w| w == b"email").unwrap();
+        tampered[offset] ^= 0x01;
+        fs::write(&log_path, &tampered)?;
+        let err = verify_chain(&log_path).unwrap_err().to_string();
+        assert!(err.contains("Line 3"), "unexpected error: {}", err);
+
+        // Dropping a middle entry breaks the links.
+        fs::write(&log_path, [lines[0], lines[1], lines[3]].concat())?;
+        assert!(verify_chain(&log_path).is_err());
+
+        // Dropping whole entries from either end is caught by the header.
+        fs::write(&log_path, [lines[0], lines[2], lines[3]].concat())?;
+        let err = verify_chain(&log_path).unwrap_err().to_string();
+        assert!(err.contains("head"), "unexpected error: {}", err);
+        fs::write(&log_path, [lines[0], lines[1], lines[2]].concat())?;
+        let err = verify_chain(&log_path).unwrap_err().to_string();
+        assert!(err.contains("truncated"), "unexpected error: {}", err);
+
+        // Cutting the file mid-entry is reported as truncation.
+        fs::write(&log_path, &original[..original.len() - 10])?;
+        let err = verify_chain(&log_path).unwrap_err().to_string();
+        assert!(err.contains("truncated"), "unexpected error: {}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rotated_chain_files_are_anchored_and_sealed() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let log_path = temp_dir.path().join("audit.log");
+        let config = AuditLogConfig { hash_chain: true, max_bytes: Some(1500), keep_rotated: 5, ..Default::default() };
+        let mut audit_log = AuditLog::new(&log_path, config)?;
+        for start in 10..20 {
+            audit_log.append(&sample_entry(start))?;
+        }
+        audit_log.flush()?;
+
+        let mut files = audit_log.rotated_files()?;
+        assert!(!files.is_empty());
+        files.push(log_path.clone());
+        let mut chain_start = CHAIN_GENESIS_HASH.to_string();
+        let mut total = 0;
+        for file in &files {
+            let header = read_header(file)?.unwrap();
+            assert_eq!(header.chain_start.as_deref(), Some(chain_start.as_str()));
+            let seal = header.sealed.unwrap();
+            total += verify_chain(file)?;
+            assert_eq!(seal.entries as usize, fs::read_to_string(file)?.lines().count() - 1);
+            chain_start = seal.last_hash;
+        }
+        assert_eq!(total, 10);
+        Ok(())
+    }
+
+    /// A v1 file as written before format headers existed.
+    fn v1_fixture() -> String {
+        [
+            r#"{"timestamp":"2025-08-09T13:00:00Z","run_id":"r1","file_path":"a.txt","user_id":"u","reason_for_redaction":"PII detected","redaction_outcome":"redacted","rule_name":"email","input_hash":"h1","match_hash":"m1","start":3,"end":18}"#,
+            r#"{"timestamp":"2025-08-09T13:00:01Z","run_id":"r1","file_path":"b.txt","user_id":"u","reason_for_redaction":"PII detected","redaction_outcome":"redacted","rule_name":"ipv4","input_hash":"h2","match_hash":"m2","start":0,"end":11}"#,
+            r#"{"timestamp":"2025-08-09T13:00:02Z","run_id":"r1","file_path":"a.txt","user_id":"u","reason_for_redaction":"PII detected","redaction_outcome":"redacted","rule_name":"email","input_hash":"h3","match_hash":"m3","start":40,"end":52}"#,
+            "",
+        ]
+        .join("\n")
+    }
+
+    fn email_paths(file: &AuditFile) -> Vec<(String, u64)> {
+        file.entries
+            .iter()
+            .filter(|e| e.rule_name == "email")
+            .map(|e| (e.file_path.clone(), e.start))
+            .collect()
+    }
+
+    #[test]
+    fn test_headerless_file_reads_as_v1_and_migrates() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let v1_path = temp_dir.path().join("legacy.log");
+        fs::write(&v1_path, v1_fixture())?;
+
+        let legacy = read_audit_log(&v1_path)?;
+        assert_eq!(legacy.version, AuditFormatVersion::V1);
+        assert_eq!(legacy.entries.len(), 3);
+        assert_eq!(email_paths(&legacy), [("a.txt".to_string(), 3), ("a.txt".to_string(), 40)]);
+
+        let v2_path = temp_dir.path().join("migrated.log");
+        assert_eq!(migrate_audit_log(&v1_path, &v2_path)?, (AuditFormatVersion::V1, 3));
+        let migrated = read_audit_log(&v2_path)?;
+        assert_eq!(migrated.version, AuditFormatVersion::CURRENT);
+        assert_eq!(migrated.entries, legacy.entries);
+        assert_eq!(email_paths(&migrated), email_paths(&legacy));
+
+        // The output is never overwritten.
+        assert!(migrate_audit_log(&v1_path, &v2_path).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrated_chain_still_verifies() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let log_path = temp_dir.path().join("audit.log");
+        {
+            let mut audit_log = AuditLog::new(&log_path, AuditLogConfig { hash_chain: true, ..Default::default() })?;
+            audit_log.append(&sample_entry(10))?;
+            audit_log.append(&sample_entry(11))?;
+        }
+        // Strip the header to reproduce a chained v1 file.
+        let raw = fs::read_to_string(&log_path)?;
+        let v1_path = temp_dir.path().join("v1.log");
+        fs::write(&v1_path, raw.split_once('\n').unwrap().1)?;
+        assert!(read_audit_log(&v1_path)?.hash_chain);
+
+        let v2_path = temp_dir.path().join("v2.log");
+        migrate_audit_log(&v1_path, &v2_path)?;
+        assert_eq!(verify_chain(&v2_path)?, 2);
+        assert!(read_audit_log(&v2_path)?.hash_chain);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_future_version_is_rejected() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let path = temp_dir.path().join("future.log");
+        fs::write(&path, "{\"cleansh_audit_version\":99,\"created_at\":\"2030-01-01T00:00:00Z\"}\n")?;
+        let err = read_audit_log(&path).unwrap_err().to_string();
+        assert!(err.contains("Unsupported audit format version 99"), "unexpected error: {}", err);
+        Ok(())
+    }
+
+    fn write_entries(config: AuditLogConfig, count: u64) -> Result<(u64, String)> {
+        let temp_dir = tempdir()?;
+        let log_path = temp_dir.path().join("audit.log");
+        let mut audit_log = AuditLog::new(&log_path, config)?;
+        for start in 0..count {
+            audit_log.append(&sample_entry(start))?;
+        }
+        audit_log.flush()?;
+        Ok((audit_log.write_count(), fs::read_to_string(&log_path)?))
+    }
+
+    #[test]
+    fn test_batching_reduces_writes_without_losing_entries() -> Result<()> {
+        let unbatched = AuditLogConfig { batch_entries: 1, ..Default::default() };
+        let (unbatched_writes, unbatched_content) = write_entries(unbatched, 10_000)?;
+        let (batched_writes, batched_content) = write_entries(AuditLogConfig::default(), 10_000)?;
+
+        // One header write plus one per entry, versus one per batch (256 entries or 64 KiB).
+        assert_eq!(unbatched_writes, 10_001);
+        assert!(batched_writes <= 1 + 10_000_u64.div_ceil(200), "too many writes: {}", batched_writes);
+
+        let entries = |content: &str| content.lines().skip(1).map(str::to_string).collect::<Vec<_>>();
+        assert_eq!(entries(&batched_content).len(), 10_000);
+        assert_eq!(entries(&batched_content), entries(&unbatched_content));
+        Ok(())
+    }
+
+    #[test]
+    fn test_entries_stay_buffered_until_flush_or_drop() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let log_path = temp_dir.path().join("audit.log");
+        let mut audit_log = AuditLog::new(&log_path, AuditLogConfig::default())?;
+        audit_log.append(&sample_entry(10))?;
+        assert_eq!(fs::read_to_string(&log_path)?.lines().count(), 1, "only the header is on disk");
+
+        audit_log.append(&sample_entry(11))?;
+        drop(audit_log);
+        assert_eq!(read_audit_log(&log_path)?.entries.len(), 2);
+
+        let durable = AuditLogConfig { durable: true, ..Default::default() };
+        let mut audit_log = AuditLog::new(&log_path, durable)?;
+        audit_log.append(&sample_entry(12))?;
+        assert_eq!(read_audit_log(&log_path)?.entries.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_interval_flush_and_failed_writes_keep_the_batch() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let log_path = temp_dir.path().join("audit.log");
+        let config = AuditLogConfig { batch_interval_ms: 20, ..Default::default() };
+        let mut audit_log = AuditLog::new(&log_path, config)?;
+        audit_log.append(&sample_entry(10))?;
+        assert!(!audit_log.flush_if_due()?);
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(audit_log.flush_if_due()?);
+        assert_eq!(read_audit_log(&log_path)?.entries.len(), 1);
+
+        // A write that fails leaves the batch buffered for the next flush.
+        audit_log.append(&sample_entry(11))?;
+        let writable = std::mem::replace(&mut audit_log.file, fs::File::open(&log_path)?);
+        assert!(audit_log.flush().is_err());
+        audit_log.file = writable;
+        audit_log.flush()?;
+        assert_eq!(read_audit_log(&log_path)?.entries.len(), 2);
+        Ok(())
+    }
+
+    fn decrypt_to_entries(path: &Path, key: &AuditKey) -> Result<(Vec<StoredEntry>, DecryptSummary)> {
+        let mut out = Vec::new();
+        let summary = decrypt_audit_log(path, key, &mut out)?;
+        let entries = String::from_utf8(out)?
+            .lines()
+            .map(|line| serde_json::from_str(line).map_err(anyhow::Error::from))
+            .collect::<Result<_>>()?;
+        Ok((entries, summary))
+    }
+
+    #[test]
+    fn test_encrypted_log_round_trips_and_hides_entries() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let log_path = temp_dir.path().join("audit.log");
+        let key = AuditKey::generate()?;
+        let source = AuditKeySource::Key(key.clone());
+        let config = AuditLogConfig { hash_chain: true, ..Default::default() };
+        {
+            let mut audit_log = AuditLog::new_encrypted(&log_path, config.clone(), &source)?;
+            audit_log.append(&sample_entry(10))?;
+            audit_log.append(&sample_entry(11))?;
+        }
+        // Reopening appends to the same file and continues the chain.
+        let mut audit_log = AuditLog::new_encrypted(&log_path, config, &source)?;
+        audit_log.append(&sample_entry(12))?;
+        drop(audit_log);
+
+        let raw = fs::read_to_string(&log_path)?;
+        assert!(!raw.contains("matchhash456") && !raw.contains("test_file.txt"), "{}", raw);
+        let header = AuditHeader::parse(raw.lines().next().unwrap()).unwrap();
+        assert_eq!(header.encryption.as_deref(), Some(AUDIT_ENCRYPTION));
+
+        let (entries, summary) = decrypt_to_entries(&log_path, &key)?;
+        assert_eq!(summary, DecryptSummary { entries: 3, skipped: Vec::new() });
+        assert_eq!(entries.iter().map(|e| e.entry.start).collect::<Vec<_>>(), [10, 11, 12]);
+        assert_eq!(entries[1].prev_hash, entries[0].entry_hash);
+        assert_eq!(entries[2].prev_hash, entries[1].entry_hash);
+
+        // Plain readers refuse the file rather than misread it.
+        assert!(read_audit_log(&log_path).unwrap_err().to_string().contains("is encrypted"));
+        assert!(verify_chain(&log_path).unwrap_err().to_string().contains("is encrypted"));
+        assert!(AuditLog::new(&log_path, AuditLogConfig::default()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupted_encrypted_line_is_skipped() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let log_path = temp_dir.path().join("audit.log");
+        let key = AuditKey::generate()?;
+        let mut audit_log = AuditLog::new_encrypted(&log_path, AuditLogConfig::default(), &AuditKeySource::Key(key.clone()))?;
+        for start in 10..13 {
+            audit_log.append(&sample_entry(start))?;
+        }
+        drop(audit_log);
+
+        // Flip a ciphertext byte in the second entry (line 3) and tear off the end
+        // of the last one, as a crash mid-write would.
+        let raw = fs::read_to_string(&log_path)?;
+        let mut lines: Vec<String> = raw.lines().map(str::to_string).collect();
+        let tail = lines[2].len() - 4;
+        let flipped = if &lines[2][tail..tail + 1] == "A" { "B" } else { "A" };
+        lines[2].replace_range(tail..tail + 1, flipped);
+        let torn = lines[3].len() / 2;
+        lines[3].truncate(torn);
+        fs::write(&log_path, lines.join("\n"))?;
+
+        let (entries, summary) = decrypt_to_entries(&log_path, &key)?;
+        assert_eq!(summary, DecryptSummary { entries: 1, skipped: vec![3, 4] });
+        assert_eq!(entries[0].entry.start, 10);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wrong_audit_key_fails_cleanly() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let log_path = temp_dir.path().join("audit.log");
+        let source = AuditKeySource::Key(AuditKey::generate()?);
+        let mut audit_log = AuditLog::new_encrypted(&log_path, AuditLogConfig::default(), &source)?;
+        audit_log.append(&sample_entry(10))?;
+        drop(audit_log);
+
+        let other = AuditKey::generate()?;
+        let mut out = Vec::new();
+        let err = decrypt_audit_log(&log_path, &other, &mut out).unwrap_err().to_string();
+        assert!(err.contains("different key"), "{}", err);
+        assert!(out.is_empty());
+        assert!(AuditLog::new_encrypted(&log_path, AuditLogConfig::default(), &AuditKeySource::Key(other)).is_err());
+
+        // A plain log is neither decrypted nor appended to with encryption.
+        let plain_path = temp_dir.path().join("plain.log");
+        AuditLog::new(&plain_path, AuditLogConfig::default())?.append(&sample_entry(10))?;
+        let key = AuditKey::generate()?;
+        assert!(decrypt_audit_log(&plain_path, &key, &mut out).unwrap_err().to_string().contains("not encrypted"));
+        assert!(AuditLog::new_encrypted(&plain_path, AuditLogConfig::default(), &AuditKeySource::Key(key)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_audit_key_source_reads_hex_env() -> Result<()> {
+        let key = AuditKey::generate()?;
+        std::env::set_var("CLEANSH_TEST_AUDIT_KEY_HEX", key.to_hex());
+        assert_eq!(AuditKeySource::HexEnv("CLEANSH_TEST_AUDIT_KEY_HEX".to_string()).resolve()?, key);
 
+        std::env::set_var("CLEANSH_TEST_AUDIT_KEY_SHORT", "abcd");
+        let err = format!("{:#}", AuditKeySource::HexEnv("CLEANSH_TEST_AUDIT_KEY_SHORT".to_string()).resolve().unwrap_err());
+        assert!(err.contains("must be 32 bytes"), "{}", err);
+        assert!(AuditKeySource::HexEnv("CLEANSH_TEST_AUDIT_KEY_UNSET".to_string()).resolve().is_err());
+        assert_eq!(format!("{:?}", key), "AuditKey(..)");
         Ok(())
     }
 }
@@ -0,0 +1,333 @@
+// cleansh-core/src/cli_flags.rs
+//! Detection of secrets passed as command-line flag values.
+//!
+//! Shell history and CI logs echo commands such as `mysql -u root -pS3cr3t` or
+//! `curl -H "Authorization: Bearer ..."`, where the secret is identified by the flag
+//! in front of it rather than by its shape. Each input line is split into words the
+//! way a POSIX shell would (quotes, backslash escapes, `|`/`;`/`&` separators), and
+//! the values of configured flags are reported with their byte span in the original
+//! text, quotes excluded.
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::RedactionRule;
+
+/// How a flag's value is attached to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlagValueForm {
+    /// `-pS3cr3t`
+    Glued,
+    /// `--password S3cr3t`
+    Separate,
+    /// `--password=S3cr3t`
+    Equals,
+}
+
+fn default_forms() -> Vec<FlagValueForm> {
+    vec![FlagValueForm::Separate, FlagValueForm::Equals]
+}
+
+fn default_replace_with() -> String {
+    "[CLI_SECRET_REDACTED]".to_string()
+}
+
+/// One entry of the `cli_flag_secrets` table.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct CliFlagRule {
+    /// Rule name reported on matches; entries with the same name override each other.
+    pub name: String,
+    /// The flag itself, e.g. `-p` or `--token`.
+    pub flag: String,
+    /// Accepted ways of attaching the value. Defaults to `separate` and `equals`.
+    #[serde(default = "default_forms")]
+    pub forms: Vec<FlagValueForm>,
+    /// Only match values of the form `<header>: ...` (case-insensitive), redacting what
+    /// follows the colon minus any leading auth scheme such as `Bearer`.
+    #[serde(default)]
+    pub header: Option<String>,
+    /// Only match when one of these commands appears earlier in the same pipeline
+    /// segment, compared by file name (`/usr/bin/mysql` counts as `mysql`).
+    #[serde(default)]
+    pub commands: Option<Vec<String>>,
+    #[serde(default = "default_replace_with")]
+    pub replace_with: String,
+}
+
+impl CliFlagRule {
+    /// The rule attached to matches this entry produces.
+    pub fn to_redaction_rule(&self) -> RedactionRule {
+        RedactionRule {
+            name: self.name.clone(),
+            description: Some(format!("Value passed to the `{}` command-line flag.", self.flag)),
+            pattern_type: "cli_flag".to_string(),
+            replace_with: self.replace_with.clone(),
+            severity: Some("high".to_string()),
+            tags: Some(vec!["credentials".to_string()]),
+            ..Default::default()
+        }
+    }
+}
+
+/// Checks that every entry names a flag and at least one value form.
+pub fn validate_cli_flag_rules(rules: &[CliFlagRule]) -> Result<()> {
+    for rule in rules {
+        if rule.name.is_empty() {
+            bail!("A cli_flag_secrets entry has an empty `name` field.");
+        }
+        if !rule.flag.starts_with('-') || rule.flag.len() < 2 {
+            bail!("cli_flag_secrets entry '{}': flag '{}' must start with '-'.", rule.name, rule.flag);
+        }
+        if rule.forms.is_empty() {
+            bail!("cli_flag_secrets entry '{}' has no value `forms`.", rule.name);
+        }
+    }
+    Ok(())
+}
+
+/// Adds `extra` to `rules`, replacing entries with the same name.
+pub fn merge_cli_flag_rules(rules: &mut Vec<CliFlagRule>, extra: &[CliFlagRule]) {
+    for rule in extra {
+        match rules.iter_mut().find(|r| r.name == rule.name) {
+            Some(existing) => *existing = rule.clone(),
+            None => rules.push(rule.clone()),
+        }
+    }
+}
+
+/// A flag value found in the scanned text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlagSecret {
+    /// Index into the rules passed to [`find_flag_secrets`].
+    pub rule_index: usize,
+    /// Byte span of the value in the scanned text.
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A shell word with the byte range each of its characters came from.
+#[derive(Debug, Default)]
+struct Word {
+    text: String,
+    spans: Vec<(usize, usize)>,
+    /// A `|`, `;` or `&` run separating commands.
+    separator: bool,
+}
+
+impl Word {
+    fn push(&mut self, c: char, start: usize) {
+        self.text.push(c);
+        self.spans.push((start, start + c.len_utf8()));
+    }
+
+    fn command_name(&self) -> &str {
+        self.text.rsplit('/').next().unwrap_or(&self.text)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Quote {
+    None,
+    Single,
+    Double,
+}
+
+/// Splits one line into shell words. An unterminated quote runs to the end of the line.
+fn split_words(line: &str, base: usize) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut word = Word::default();
+    let mut in_word = false;
+    let mut quote = Quote::None;
+    let mut chars = line.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match quote {
+            Quote::Single if c == '\'' => quote = Quote::None,
+            Quote::Single => word.push(c, base + i),
+            Quote::Double if c == '"' => quote = Quote::None,
+            Quote::Double if c == '\\' && matches!(chars.peek(), Some((_, '"' | '\\' | '$' | '`'))) => {
+                if let Some((j, escaped)) = chars.next() {
+                    word.push(escaped, base + j);
+                }
+            }
+            Quote::Double => word.push(c, base + i),
+            Quote::None => match c {
+                '\'' => { quote = Quote::Single; in_word = true; }
+                '"' => { quote = Quote::Double; in_word = true; }
+                '\\' => {
+                    if let Some((j, escaped)) = chars.next() {
+                        word.push(escaped, base + j);
+                        in_word = true;
+                    }
+                }
+                '|' | ';' | '&' => {
+                    if in_word {
+                        words.push(std::mem::take(&mut word));
+                        in_word = false;
+                    }
+                    if !words.last().is_some_and(|w: &Word| w.separator) {
+                        words.push(Word { separator: true, ..Default::default() });
+                    }
+                }
+                c if c.is_whitespace() => {
+                    if in_word {
+                        words.push(std::mem::take(&mut word));
+                        in_word = false;
+                    }
+                }
+                c => { word.push(c, base + i); in_word = true; }
+            },
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+/// Character range of the secret within `value`, after any header prefix is removed.
+fn secret_range(rule: &CliFlagRule, value: &str, from: usize) -> Option<(usize, usize)> {
+    let chars: Vec<char> = value.chars().collect();
+    let mut start = from;
+    if let Some(header) = &rule.header {
+        let prefix: Vec<char> = header.chars().chain([':']).collect();
+        let candidate = chars.get(start..start + prefix.len())?;
+        if !candidate.iter().zip(&prefix).all(|(a, b)| a.eq_ignore_ascii_case(b)) {
+            return None;
+        }
+        start += prefix.len();
+        while chars.get(start).is_some_and(|c| c.is_whitespace()) {
+            start += 1;
+        }
+        // `Bearer <token>`, `Basic <credentials>`: keep the scheme, redact the credential.
+        if let Some(gap) = chars[start..].iter().position(|c| c.is_whitespace()) {
+            let after = chars[start + gap..].iter().position(|c| !c.is_whitespace());
+            if let Some(after) = after {
+                start += gap + after;
+            }
+        }
+    }
+    (start < chars.len()).then_some((start, chars.len()))
+}
+
+/// Finds the value of `rule`'s flag in `words[index]` (and the word after it).
+fn match_flag(rule: &CliFlagRule, words: &[Word], index: usize) -> Option<(usize, usize)> {
+    let word = &words[index];
+    let flag_chars = rule.flag.chars().count();
+    let (value, from) = if word.text == rule.flag {
+        if !rule.forms.contains(&FlagValueForm::Separate) {
+            return None;
+        }
+        let next = words.get(index + 1).filter(|w| !w.separator && !w.text.starts_with('-'))?;
+        (next, 0)
+    } else if let Some(rest) = word.text.strip_prefix(&rule.flag) {
+        let equals = rest.starts_with('=') && rule.forms.contains(&FlagValueForm::Equals);
+        if equals {
+            (word, flag_chars + 1)
+        } else if rule.forms.contains(&FlagValueForm::Glued) {
+            (word, flag_chars)
+        } else {
+            return None;
+        }
+    } else {
+        return None;
+    };
+
+    let (first, last) = secret_range(rule, &value.text, from)?;
+    Some((value.spans[first].0, value.spans[last - 1].1))
+}
+
+/// Finds flag values in `text`. The first rule (in table order) that matches a word wins.
+pub fn find_flag_secrets(rules: &[CliFlagRule], text: &str) -> Vec<FlagSecret> {
+    let mut found = Vec::new();
+    if rules.is_empty() {
+        return found;
+    }
+    let mut base = 0;
+    for line in text.split('\n') {
+        let words = split_words(line, base);
+        base += line.len() + 1;
+
+        let mut segment_start = 0;
+        for index in 0..words.len() {
+            if words[index].separator {
+                segment_start = index + 1;
+                continue;
+            }
+            let preceding = &words[segment_start..index];
+            let hit = rules.iter().enumerate().find_map(|(rule_index, rule)| {
+                let in_scope = rule.commands.as_ref().is_none_or(|commands| {
+                    preceding.iter().any(|w| commands.iter().any(|c| c == w.command_name()))
+                });
+                if !in_scope {
+                    return None;
+                }
+                match_flag(rule, &words, index).map(|(start, end)| FlagSecret { rule_index, start, end })
+            });
+            found.extend(hit);
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, flag: &str, forms: &[FlagValueForm]) -> CliFlagRule {
+        CliFlagRule {
+            name: name.to_string(),
+            flag: flag.to_string(),
+            forms: forms.to_vec(),
+            header: None,
+            commands: None,
+            replace_with: default_replace_with(),
+        }
+    }
+
+    fn secrets<'a>(rules: &[CliFlagRule], text: &'a str) -> Vec<&'a str> {
+        find_flag_secrets(rules, text).iter().map(|s| &text[s.start..s.end]).collect()
+    }
+
+    #[test]
+    fn test_split_words_handles_quotes_and_escapes() {
+        let line = r#"echo 'a b' "c \"d\"" e\ f|g"#;
+        let words: Vec<String> = split_words(line, 0).into_iter()
+            .map(|w| if w.separator { "|".to_string() } else { w.text })
+            .collect();
+        assert_eq!(words, ["echo", "a b", r#"c "d""#, "e f", "|", "g"]);
+    }
+
+    #[test]
+    fn test_forms_are_matched_only_when_enabled() {
+        let glued = [rule("p", "-p", &[FlagValueForm::Glued])];
+        assert_eq!(secrets(&glued, "mysql -u root -pS3cr3t db"), ["S3cr3t"]);
+        assert!(secrets(&glued, "mysql -p db").is_empty());
+
+        let long = [rule("password", "--password", &default_forms())];
+        assert_eq!(secrets(&long, "tool --password hunter2 --verbose"), ["hunter2"]);
+        assert_eq!(secrets(&long, "tool --password='hunter 2'"), ["hunter 2"]);
+        assert!(secrets(&long, "tool --passwordless").is_empty());
+        assert!(secrets(&long, "tool --password --verbose").is_empty());
+    }
+
+    #[test]
+    fn test_command_scope_is_per_pipeline_segment() {
+        let mut scoped = rule("p", "-p", &[FlagValueForm::Glued]);
+        scoped.commands = Some(vec!["mysql".to_string()]);
+        let rules = [scoped];
+        assert_eq!(secrets(&rules, "sudo /usr/bin/mysql -pXyz"), ["Xyz"]);
+        assert!(secrets(&rules, "mysql -e 'show tables' | grep -pattern").is_empty());
+    }
+
+    #[test]
+    fn test_header_value_skips_name_and_scheme() {
+        let mut header = rule("auth", "-H", &[FlagValueForm::Separate]);
+        header.header = Some("Authorization".to_string());
+        let rules = [header];
+        let line = r#"curl -H "Accept: */*" -H "authorization: Bearer abc.def" https://x"#;
+        assert_eq!(secrets(&rules, line), ["abc.def"]);
+        assert_eq!(secrets(&rules, "curl -H 'Authorization: s3cr3t'"), ["s3cr3t"]);
+    }
+}
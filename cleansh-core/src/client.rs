@@ -0,0 +1,283 @@
+// cleansh-core/src/client.rs
+//! `CleanshClient`: the high-level API for embedding cleansh.
+//!
+//! The builder loads the rules, applies a profile, compiles ignore patterns, and
+//! constructs the engine in the order the CLI does, so callers need not know
+//! about `RedactionConfig`, `EngineOptions`, or the individual engines.
+//!
+//! ```
+//! use cleansh_core::{CleanshClient, EngineKind};
+//!
+//! let client = CleanshClient::builder()
+//!     .with_default_rules()?
+//!     .engine(EngineKind::Regex)
+//!     .ignore_pattern(r"@example\.org$")
+//!     .build()?;
+//!
+//! let report = client.sanitize("mail admin@example.com or test@example.org")?;
+//! assert!(!report.sanitized.contains("admin@example.com"));
+//! assert!(report.sanitized.contains("test@example.org"));
+//! assert_eq!(client.scan("admin@example.com")?.len(), 1);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use anyhow::{anyhow, bail, Context, Result};
+use regex::RegexSet;
+use std::fmt;
+use std::str::FromStr;
+use tokio::sync::mpsc;
+
+use crate::config::RedactionConfig;
+use crate::engine::SanitizationEngine;
+use crate::engines::composite_engine::CompositeEngine;
+use crate::engines::entropy_engine::EntropyEngine;
+use crate::engines::regex_engine::RegexEngine;
+use crate::headless::{sanitize_with_report, SanitizeReport};
+use crate::profiles::{apply_profile_to_config, load_profile_with_keys, EngineOptions, ProfileKeys};
+use crate::redaction_match::{ensure_match_hashes, RedactionMatch};
+
+/// Source id given to matches when the builder sets none.
+pub const DEFAULT_CLIENT_SOURCE_ID: &str = "input";
+
+/// Which engine a [`CleanshClient`] runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum EngineKind {
+    /// The configured rules. The default.
+    #[default]
+    Regex,
+    /// Statistical detection of high-entropy secrets.
+    Entropy,
+    /// Both, with entropy corroborating regex matches.
+    Hybrid,
+}
+
+impl EngineKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Regex => "regex",
+            Self::Entropy => "entropy",
+            Self::Hybrid => "hybrid",
+        }
+    }
+}
+
+impl fmt::Display for EngineKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for EngineKind {
+    type Err = anyhow::Error;
+
+    /// Parses one of the profile engine types (`regex`, `entropy`, `hybrid`).
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "regex" => Ok(Self::Regex),
+            "entropy" => Ok(Self::Entropy),
+            "hybrid" => Ok(Self::Hybrid),
+            _ => Err(anyhow!("unknown engine type '{}'. Expected one of: regex, entropy, hybrid", s)),
+        }
+    }
+}
+
+/// The engine a client owns, kept concrete so a remediation channel can still be
+/// attached after `build()`.
+enum ClientEngine {
+    Regex(Box<RegexEngine>),
+    Entropy(Box<EntropyEngine>),
+    Hybrid(Box<CompositeEngine>),
+}
+
+impl ClientEngine {
+    fn as_dyn(&self) -> &dyn SanitizationEngine {
+        match self {
+            Self::Regex(engine) => engine.as_ref(),
+            Self::Entropy(engine) => engine.as_ref(),
+            Self::Hybrid(engine) => engine.as_ref(),
+        }
+    }
+}
+
+/// Builds a [`CleanshClient`]. Nothing is loaded from a profile or compiled until
+/// [`Self::build`], so every error about the finished setup surfaces there.
+#[derive(Debug, Clone, Default)]
+pub struct CleanshClientBuilder {
+    config: Option<RedactionConfig>,
+    profile: Option<String>,
+    profile_keys: Option<ProfileKeys>,
+    engine: Option<EngineKind>,
+    ignore_patterns: Vec<String>,
+    options: EngineOptions,
+    source_id: Option<String>,
+}
+
+impl CleanshClientBuilder {
+    /// Adds the built-in rules.
+    pub fn with_default_rules(self) -> Result<Self> {
+        let defaults = RedactionConfig::load_default_rules()?;
+        Ok(self.with_rules(defaults))
+    }
+
+    /// Adds the named built-in rule packs (see [`crate::rule_pack_names`]).
+    pub fn with_rule_packs<S: AsRef<str>>(self, names: &[S]) -> Result<Self> {
+        let packs = RedactionConfig::load_rule_packs(names)?;
+        Ok(self.with_rules(packs))
+    }
+
+    /// Adds `config`'s rules. Rules added later win on a name clash.
+    pub fn with_rules(mut self, config: RedactionConfig) -> Self {
+        self.config = Some(crate::config::merge_rules(self.config.take().unwrap_or_default(), Some(config)));
+        self
+    }
+
+    /// Applies a profile, by name or path, to the rules when the client is built.
+    /// The profile is looked up by [`Self::build`], not here.
+    pub fn with_profile(mut self, name_or_path: &str) -> Result<Self> {
+        if name_or_path.trim().is_empty() {
+            bail!("profile name must not be empty");
+        }
+        self.profile = Some(name_or_path.to_string());
+        Ok(self)
+    }
+
+    /// Verifies the profile's signatures against `keys` instead of the keys named
+    /// by the environment.
+    pub fn with_profile_keys(mut self, keys: ProfileKeys) -> Self {
+        self.profile_keys = Some(keys);
+        self
+    }
+
+    /// Selects the engine. Without one, the profile's engine type is used, else
+    /// [`EngineKind::Regex`].
+    pub fn engine(mut self, kind: EngineKind) -> Self {
+        self.engine = Some(kind);
+        self
+    }
+
+    /// Leaves values matching the regex `pattern` unredacted, whatever rule
+    /// matched them. Patterns are compiled by [`Self::build`].
+    pub fn ignore_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.ignore_patterns.push(pattern.into());
+        self
+    }
+
+    /// Starts from `options` (ignored hashes, remediation channel mode, ...)
+    /// instead of the defaults.
+    pub fn with_options(mut self, options: EngineOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// The `source_id` of reported matches; [`DEFAULT_CLIENT_SOURCE_ID`] by default.
+    pub fn source_id(mut self, source_id: impl Into<String>) -> Self {
+        self.source_id = Some(source_id.into());
+        self
+    }
+
+    /// Loads and applies the profile, compiles the ignore patterns, and builds the
+    /// engine.
+    pub fn build(self) -> Result<CleanshClient> {
+        let mut config = self.config
+            .context("CleanshClient has no rules; call with_default_rules(), with_rule_packs(), or with_rules()")?;
+        let mut options = self.options;
+        let mut engine = self.engine;
+
+        if let Some(name) = &self.profile {
+            let keys = match self.profile_keys {
+                Some(keys) => keys,
+                None => ProfileKeys::from_env()?,
+            };
+            let profile = load_profile_with_keys(name, &keys)
+                .with_context(|| format!("Failed to load profile '{}'", name))?;
+            profile.validate(&config)?;
+            config = apply_profile_to_config(&profile, config);
+            if engine.is_none() {
+                engine = profile.engine_type()
+                    .map(|kind| kind.parse().with_context(|| format!("Invalid engine type in profile '{}'", name)))
+                    .transpose()?;
+            }
+            options.post_processing = profile.post_processing.clone();
+            options.samples_config = profile.samples.clone();
+            options.dedupe_config = profile.dedupe.clone();
+            options.profile_meta.profile_name = profile.profile_name.clone();
+            options.profile_meta.version = profile.version.clone();
+        }
+
+        if !self.ignore_patterns.is_empty() {
+            let patterns = RegexSet::new(&self.ignore_patterns).context("Invalid ignore pattern")?;
+            options = options.with_ignore_patterns(patterns);
+        }
+
+        let kind = engine.unwrap_or_default();
+        let engine = match kind {
+            EngineKind::Regex => ClientEngine::Regex(Box::new(RegexEngine::with_options(config, options)?)),
+            EngineKind::Entropy => ClientEngine::Entropy(Box::new(EntropyEngine::with_options(config, options)?)),
+            EngineKind::Hybrid => ClientEngine::Hybrid(Box::new(CompositeEngine::with_options(config, options)?)),
+        };
+        Ok(CleanshClient {
+            engine,
+            kind,
+            source_id: self.source_id.unwrap_or_else(|| DEFAULT_CLIENT_SOURCE_ID.to_string()),
+        })
+    }
+}
+
+/// A configured engine behind a small, stable API. Build one with
+/// [`CleanshClient::builder`].
+pub struct CleanshClient {
+    engine: ClientEngine,
+    kind: EngineKind,
+    source_id: String,
+}
+
+impl CleanshClient {
+    pub fn builder() -> CleanshClientBuilder {
+        CleanshClientBuilder::default()
+    }
+
+    /// Redacts `content`, returning the sanitized text with what was redacted.
+    pub fn sanitize(&self, content: &str) -> Result<SanitizeReport> {
+        sanitize_with_report(self.engine.as_dyn(), content, &self.source_id)
+    }
+
+    /// Finds the matches in `content` without redacting it. Each match carries
+    /// its `sample_hash`.
+    pub fn scan(&self, content: &str) -> Result<Vec<RedactionMatch>> {
+        let mut matches = self.engine.as_dyn().find_matches_for_ui(content, &self.source_id)?;
+        ensure_match_hashes(&mut matches);
+        Ok(matches)
+    }
+
+    /// Sends every match found from now on to `tx`, e.g. a
+    /// [`SelfHealingEngine`](crate::SelfHealingEngine) listening on the other end.
+    ///
+    /// ```
+    /// use cleansh_core::CleanshClient;
+    /// use tokio::sync::mpsc;
+    ///
+    /// let (tx, mut rx) = mpsc::channel(16);
+    /// let client = CleanshClient::builder().with_default_rules()?.build()?.attach_remediation(tx);
+    /// client.scan("mail admin@example.com")?;
+    /// assert_eq!(rx.try_recv()?.original_string, "admin@example.com");
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn attach_remediation(self, tx: mpsc::Sender<RedactionMatch>) -> Self {
+        let engine = match self.engine {
+            ClientEngine::Regex(engine) => ClientEngine::Regex(Box::new(engine.with_remediation_tx(tx))),
+            ClientEngine::Entropy(engine) => ClientEngine::Entropy(Box::new(engine.with_remediation_tx(tx))),
+            ClientEngine::Hybrid(engine) => ClientEngine::Hybrid(Box::new(engine.with_remediation_tx(tx))),
+        };
+        Self { engine, ..self }
+    }
+
+    /// The engine the client runs.
+    pub fn engine_kind(&self) -> EngineKind {
+        self.kind
+    }
+
+    /// The underlying engine, for the lower-level [`SanitizationEngine`] API.
+    pub fn engine(&self) -> &dyn SanitizationEngine {
+        self.engine.as_dyn()
+    }
+}
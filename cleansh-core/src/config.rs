@@ -9,12 +9,19 @@
 use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use log::{debug, info, warn};
 use std::fmt;
 use regex::Regex;
 use std::hash::{Hash, Hasher};
 
+use crate::cli_flags::{merge_cli_flag_rules, validate_cli_flag_rules, CliFlagRule};
+use crate::config_format::ConfigFormat;
+use crate::profiles::{format_token, summary_sample_limits, EngineOptions, DEFAULT_TOKEN_FORMAT};
+use crate::sanitizers::compiler::check_repetition_bounds;
+use crate::severity::Severity;
+use cleansh_entropy::engine::AUTO_WINDOW_SIZE;
+
 /// Maximum allowed length for a regex pattern string.
 pub const MAX_PATTERN_LENGTH: usize = 500;
 
@@ -50,6 +57,49 @@ pub struct RedactionRule {
     pub severity: Option<String>,
     /// Metadata tags for categorization.
     pub tags: Option<Vec<String>>,
+    /// If true, the composite engine adjusts this rule's match confidence by the
+    /// entropy of the matched span, downgrading low-entropy placeholders.
+    pub entropy_corroboration: bool,
+    /// Regex marking the first line of a multi-line block (e.g. `-----BEGIN .* PRIVATE KEY-----`).
+    /// Line-by-line readers buffer from this line through the `block_end` line and hand the
+    /// engine the whole block at once.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_start: Option<String>,
+    /// Regex marking the last line of a block begun by `block_start`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_end: Option<String>,
+    /// Redacts only this capture group of each match, keeping the rest of the match
+    /// verbatim (e.g. `1` keeps `Authorization: Bearer ` and redacts the token). A
+    /// match in which the group does not participate is left alone.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redact_group: Option<CaptureGroup>,
+}
+
+/// `RedactionRule.redact_group`: a capture group by number or by name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum CaptureGroup {
+    Index(usize),
+    Name(String),
+}
+
+impl CaptureGroup {
+    /// The group's index in `regex`, if `regex` has such a group.
+    pub fn resolve(&self, regex: &Regex) -> Option<usize> {
+        match self {
+            CaptureGroup::Index(index) => Some(*index).filter(|&index| index < regex.captures_len()),
+            CaptureGroup::Name(name) => regex.capture_names().position(|n| n == Some(name.as_str())),
+        }
+    }
+}
+
+impl fmt::Display for CaptureGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaptureGroup::Index(index) => write!(f, "{}", index),
+            CaptureGroup::Name(name) => f.write_str(name),
+        }
+    }
 }
 
 impl Hash for RedactionRule {
@@ -69,6 +119,17 @@ impl Hash for RedactionRule {
         self.programmatic_validation.hash(state);
         self.enabled.hash(state);
         self.severity.hash(state);
+        self.entropy_corroboration.hash(state);
+        self.block_start.hash(state);
+        self.block_end.hash(state);
+        self.redact_group.hash(state);
+    }
+}
+
+impl RedactionRule {
+    /// Whether engines run the rule: explicitly enabled, or neither disabled nor `opt_in`.
+    pub fn is_active(&self) -> bool {
+        self.enabled == Some(true) || (self.enabled != Some(false) && !self.opt_in)
     }
 }
 
@@ -91,6 +152,10 @@ impl Default for RedactionRule {
             enabled: None,
             severity: None,
             tags: None,
+            entropy_corroboration: false,
+            block_start: None,
+            block_end: None,
+            redact_group: None,
         }
     }
 }
@@ -102,7 +167,194 @@ pub struct EntropyConfig {
     pub threshold: Option<f64>,
     /// The size of the scanning window in bytes (default: 24).
     /// Smaller windows are more aggressive; larger windows reduce noise.
+    /// `auto` (stored as `0`) sizes the windows from each input's token lengths.
+    #[serde(default, deserialize_with = "deserialize_window_size", serialize_with = "serialize_window_size")]
     pub window_size: Option<usize>,
+    /// Extra keywords that boost confidence when they label a token (e.g. `licencia=`).
+    /// Matched case-sensitively on word boundaries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_keywords: Option<Vec<String>>,
+    /// When true, `context_keywords` replaces the built-in list instead of extending it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replace_default_keywords: Option<bool>,
+    /// Extra known-benign labels that lower confidence when they label a token
+    /// (e.g. `span_id=`). Matched like `context_keywords`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub negative_context_keywords: Option<Vec<String>>,
+    /// When true, `negative_context_keywords` replaces the built-in list instead of extending it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replace_default_negative_keywords: Option<bool>,
+    /// How much a negative keyword lowers confidence (default: 2.0).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub negative_keyword_weight: Option<f64>,
+    /// Scores tokens by shape (hex, base64, base58, UUID) in addition to entropy (default: true).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structural_scoring: Option<bool>,
+    /// Per-script multipliers for the scoring weights, chosen by the detected text profile.
+    /// Unset scripts and fields keep the built-in multipliers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language_adjustments: Option<LanguageAdjustmentsConfig>,
+    /// Ascending entropy cutoffs for the TUI heatmap's moderate, high, and critical
+    /// bands (default: `[2.5, 3.5, 4.5]`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heat_bands: Option<[f64; 3]>,
+    /// Matches shorter than this many bytes are dropped (default: 6).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_secret_len: Option<usize>,
+    /// Matches longer than this many bytes are handled by `long_secret_policy`
+    /// (default: no maximum).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_secret_len: Option<usize>,
+    /// What to do with matches longer than `max_secret_len` (default: `redact_all`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub long_secret_policy: Option<LongSecretPolicy>,
+    /// Inputs longer than this many bytes get no heatmap, and of any line longer
+    /// than this the entropy scanner reads only its first `max_line_bytes` and its
+    /// last 64 KiB; shorter lines are always read whole (default: 1 MiB).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_line_bytes: Option<usize>,
+    /// Fewest chunks of surrounding text a window's z-score is computed from
+    /// (default: 2). With fewer, as when a secret fills a short line, the window
+    /// is judged by `absolute_entropy_floor` instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_baseline_samples: Option<usize>,
+    /// Entropy in bits per byte at which a window without enough baseline counts
+    /// as an anomaly (default: 4.0).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub absolute_entropy_floor: Option<f64>,
+}
+
+/// `EntropyConfig.window_size` as written: a number of bytes or `auto`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WindowSizeValue {
+    Bytes(usize),
+    Name(String),
+}
+
+fn deserialize_window_size<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<usize>, D::Error> {
+    match Option::<WindowSizeValue>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(WindowSizeValue::Bytes(bytes)) => Ok(Some(bytes)),
+        Some(WindowSizeValue::Name(name)) if name.eq_ignore_ascii_case("auto") => Ok(Some(AUTO_WINDOW_SIZE)),
+        Some(WindowSizeValue::Name(name)) => Err(serde::de::Error::custom(format!(
+            "invalid window_size '{}': expected a number of bytes or \"auto\"", name
+        ))),
+    }
+}
+
+fn serialize_window_size<S: serde::Serializer>(window_size: &Option<usize>, serializer: S) -> Result<S::Ok, S::Error> {
+    match window_size {
+        Some(AUTO_WINDOW_SIZE) => serializer.serialize_str("auto"),
+        Some(bytes) => serializer.serialize_some(bytes),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// `EntropyConfig.long_secret_policy`.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum LongSecretPolicy {
+    /// Leave the match unredacted, e.g. for inline images and other large blobs.
+    Skip,
+    /// Redact the whole match.
+    #[default]
+    RedactAll,
+    /// Redact only the first `max_secret_len` bytes.
+    RedactPrefix,
+}
+
+impl From<LongSecretPolicy> for cleansh_entropy::engine::LongSecretPolicy {
+    fn from(policy: LongSecretPolicy) -> Self {
+        match policy {
+            LongSecretPolicy::Skip => Self::Skip,
+            LongSecretPolicy::RedactAll => Self::RedactAll,
+            LongSecretPolicy::RedactPrefix => Self::RedactPrefix,
+        }
+    }
+}
+
+/// Default `EntropyConfig.heat_bands`.
+pub const DEFAULT_HEAT_BANDS: [f64; 3] = [2.5, 3.5, 4.5];
+
+/// Default `EntropyConfig.max_line_bytes`.
+pub const DEFAULT_MAX_LINE_BYTES: usize = 1024 * 1024;
+
+/// Scoring multipliers for one script; unset fields keep the built-in value.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq)]
+pub struct ScriptAdjustmentConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub z_score_weight: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normalization_divisor: Option<f64>,
+}
+
+impl ScriptAdjustmentConfig {
+    fn apply_to(&self, adjustment: &mut cleansh_entropy::scoring::LanguageAdjustment) {
+        if let Some(weight) = self.z_score_weight {
+            adjustment.z_score_weight = weight;
+        }
+        if let Some(divisor) = self.normalization_divisor {
+            adjustment.normalization_divisor = divisor;
+        }
+    }
+
+    fn merge_from(&mut self, other: &ScriptAdjustmentConfig) {
+        self.z_score_weight = other.z_score_weight.or(self.z_score_weight);
+        self.normalization_divisor = other.normalization_divisor.or(self.normalization_divisor);
+    }
+}
+
+impl Hash for ScriptAdjustmentConfig {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.z_score_weight.map(f64::to_bits).hash(state);
+        self.normalization_divisor.map(f64::to_bits).hash(state);
+    }
+}
+
+/// Per-script overrides for `EntropyConfig.language_adjustments`.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Hash)]
+#[serde(default)]
+pub struct LanguageAdjustmentsConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latin: Option<ScriptAdjustmentConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cyrillic: Option<ScriptAdjustmentConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cjk: Option<ScriptAdjustmentConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mixed: Option<ScriptAdjustmentConfig>,
+}
+
+impl LanguageAdjustmentsConfig {
+    fn scripts_mut(&mut self) -> [&mut Option<ScriptAdjustmentConfig>; 4] {
+        [&mut self.latin, &mut self.cyrillic, &mut self.cjk, &mut self.mixed]
+    }
+
+    fn scripts(&self) -> [&Option<ScriptAdjustmentConfig>; 4] {
+        [&self.latin, &self.cyrillic, &self.cjk, &self.mixed]
+    }
+
+    /// Overlays every script and field that is set in `other` onto `self`.
+    pub fn merge_from(&mut self, other: &LanguageAdjustmentsConfig) {
+        for (mine, theirs) in self.scripts_mut().into_iter().zip(other.scripts()) {
+            if let Some(theirs) = theirs {
+                mine.get_or_insert_with(Default::default).merge_from(theirs);
+            }
+        }
+    }
+
+    /// The built-in multipliers with these overrides applied.
+    pub fn resolve(&self) -> cleansh_entropy::scoring::LanguageAdjustments {
+        let mut resolved = cleansh_entropy::scoring::LanguageAdjustments::default();
+        let targets = [&mut resolved.latin, &mut resolved.cyrillic, &mut resolved.cjk, &mut resolved.mixed];
+        for (target, config) in targets.into_iter().zip(self.scripts()) {
+            if let Some(config) = config {
+                config.apply_to(target);
+            }
+        }
+        resolved
+    }
 }
 
 impl Hash for EntropyConfig {
@@ -113,14 +365,228 @@ impl Hash for EntropyConfig {
             0u64.hash(state);
         }
         self.window_size.hash(state);
+        self.context_keywords.hash(state);
+        self.replace_default_keywords.hash(state);
+        self.negative_context_keywords.hash(state);
+        self.replace_default_negative_keywords.hash(state);
+        self.negative_keyword_weight.map(f64::to_bits).hash(state);
+        self.structural_scoring.hash(state);
+        self.language_adjustments.hash(state);
+        self.heat_bands.map(|bands| bands.map(f64::to_bits)).hash(state);
+        self.min_secret_len.hash(state);
+        self.max_secret_len.hash(state);
+        self.long_secret_policy.hash(state);
+        self.min_baseline_samples.hash(state);
+        self.absolute_entropy_floor.map(f64::to_bits).hash(state);
+    }
+}
+
+impl EntropyConfig {
+    /// Overlays every field that is set in `other` onto `self`.
+    pub fn merge_from(&mut self, other: &EntropyConfig) {
+        if let Some(threshold) = other.threshold {
+            debug!("Overriding entropy threshold with user value: {}", threshold);
+            self.threshold = Some(threshold);
+        }
+        if let Some(window) = other.window_size {
+            debug!("Overriding entropy window size with user value: {}", window);
+            self.window_size = Some(window);
+        }
+        if let Some(keywords) = &other.context_keywords {
+            debug!("Overriding entropy context keywords with {} user value(s)", keywords.len());
+            self.context_keywords = Some(keywords.clone());
+        }
+        if let Some(replace) = other.replace_default_keywords {
+            self.replace_default_keywords = Some(replace);
+        }
+        if let Some(keywords) = &other.negative_context_keywords {
+            debug!("Overriding entropy negative context keywords with {} user value(s)", keywords.len());
+            self.negative_context_keywords = Some(keywords.clone());
+        }
+        if let Some(replace) = other.replace_default_negative_keywords {
+            self.replace_default_negative_keywords = Some(replace);
+        }
+        if let Some(weight) = other.negative_keyword_weight {
+            debug!("Overriding entropy negative keyword weight with user value: {}", weight);
+            self.negative_keyword_weight = Some(weight);
+        }
+        if let Some(structural) = other.structural_scoring {
+            debug!("Overriding entropy structural scoring with user value: {}", structural);
+            self.structural_scoring = Some(structural);
+        }
+        if let Some(adjustments) = &other.language_adjustments {
+            debug!("Overriding entropy language adjustments with user values");
+            self.language_adjustments.get_or_insert_with(Default::default).merge_from(adjustments);
+        }
+        if let Some(bands) = other.heat_bands {
+            debug!("Overriding heatmap bands with user values: {:?}", bands);
+            self.heat_bands = Some(bands);
+        }
+        if let Some(min) = other.min_secret_len {
+            debug!("Overriding entropy minimum secret length with user value: {}", min);
+            self.min_secret_len = Some(min);
+        }
+        if let Some(max) = other.max_secret_len {
+            debug!("Overriding entropy maximum secret length with user value: {}", max);
+            self.max_secret_len = Some(max);
+        }
+        if let Some(policy) = other.long_secret_policy {
+            debug!("Overriding entropy long secret policy with user value: {:?}", policy);
+            self.long_secret_policy = Some(policy);
+        }
+        if let Some(max) = other.max_line_bytes {
+            debug!("Overriding entropy maximum line length with user value: {}", max);
+            self.max_line_bytes = Some(max);
+        }
+        if let Some(min) = other.min_baseline_samples {
+            debug!("Overriding entropy minimum baseline samples with user value: {}", min);
+            self.min_baseline_samples = Some(min);
+        }
+        if let Some(floor) = other.absolute_entropy_floor {
+            debug!("Overriding entropy absolute floor with user value: {}", floor);
+            self.absolute_entropy_floor = Some(floor);
+        }
+    }
+
+    /// Returns the heatmap band cutoffs, falling back to `DEFAULT_HEAT_BANDS` (with a
+    /// warning) when the configured ones are not finite and strictly ascending.
+    pub fn resolved_heat_bands(&self) -> [f64; 3] {
+        match self.heat_bands {
+            Some(bands) if bands.iter().all(|b| b.is_finite()) && bands[0] < bands[1] && bands[1] < bands[2] => bands,
+            Some(bands) => {
+                warn!("Ignoring heat_bands {:?}: expected three ascending numbers.", bands);
+                DEFAULT_HEAT_BANDS
+            }
+            None => DEFAULT_HEAT_BANDS,
+        }
+    }
+
+    /// Returns `max_line_bytes`, or `DEFAULT_MAX_LINE_BYTES` when it is unset or 0.
+    pub fn resolved_max_line_bytes(&self) -> usize {
+        self.max_line_bytes.filter(|&max| max > 0).unwrap_or(DEFAULT_MAX_LINE_BYTES)
+    }
+
+    /// Returns the keyword list the context scanner should use, or `None` when
+    /// no custom keywords are configured and the built-in list applies unchanged.
+    ///
+    /// Keywords are trimmed; empty and duplicate entries are dropped with a warning.
+    pub fn resolved_context_keywords(&self) -> Option<Vec<String>> {
+        resolve_keywords(
+            self.context_keywords.as_ref()?,
+            self.replace_default_keywords.unwrap_or(false),
+            cleansh_entropy::context::DEFAULT_KEYWORDS,
+            "context keyword",
+            "keyword boosting",
+        )
+    }
+
+    /// Like [`Self::resolved_context_keywords`], for the negative keywords that
+    /// demote known-benign labels.
+    pub fn resolved_negative_context_keywords(&self) -> Option<Vec<String>> {
+        resolve_keywords(
+            self.negative_context_keywords.as_ref()?,
+            self.replace_default_negative_keywords.unwrap_or(false),
+            cleansh_entropy::context::DEFAULT_NEGATIVE_KEYWORDS,
+            "negative context keyword",
+            "benign-label demotion",
+        )
     }
 }
 
+/// Extends `defaults` with `custom` (or uses `custom` alone when `replace`),
+/// dropping empty and duplicate entries with a warning naming `kind`. An empty
+/// result disables `effect`.
+fn resolve_keywords(custom: &[String], replace: bool, defaults: &[&str], kind: &str, effect: &str) -> Option<Vec<String>> {
+    let mut keywords: Vec<String> = if replace {
+        Vec::new()
+    } else {
+        defaults.iter().map(|k| k.to_string()).collect()
+    };
+
+    for raw in custom {
+        let keyword = raw.trim();
+        if keyword.is_empty() {
+            warn!("Ignoring empty entropy {}.", kind);
+        } else if keywords.iter().any(|k| k == keyword) {
+            warn!("Ignoring duplicate entropy {} '{}'.", kind, keyword);
+        } else {
+            keywords.push(keyword.to_string());
+        }
+    }
+
+    if keywords.is_empty() {
+        warn!("No entropy {}s remain; {} is disabled.", kind, effect);
+    }
+    Some(keywords)
+}
+
 /// Container for all engine-specific configurations.
 #[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Hash)]
 #[serde(default)]
 pub struct EngineConfig {
     pub entropy: EntropyConfig,
+    pub structured: StructuredConfig,
+    /// Wall-clock budget per regex rule and input, in milliseconds. A rule still
+    /// matching when it runs out is stopped for that input, keeping the matches
+    /// found so far. Unset or `0` means no budget.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule_timeout_ms: Option<u64>,
+}
+
+impl EngineConfig {
+    /// Overlays every field that is set in `other` onto `self`.
+    pub fn merge_from(&mut self, other: &EngineConfig) {
+        self.entropy.merge_from(&other.entropy);
+        if let Some(enabled) = other.structured.enabled {
+            debug!("Overriding structured scanning with user value: {}", enabled);
+            self.structured.enabled = Some(enabled);
+        }
+        if let Some(timeout) = other.rule_timeout_ms {
+            debug!("Overriding rule timeout with user value: {} ms", timeout);
+            self.rule_timeout_ms = Some(timeout);
+        }
+    }
+}
+
+/// Settings for the key-aware JSON/YAML pre-pass (see `sanitizers::structured`).
+/// Keys are matched against the entropy engine's context keywords.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+pub struct StructuredConfig {
+    /// Scan inputs that parse as JSON or YAML by key as well (default: false).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+}
+
+/// Where remediation events are announced (see `remediation::notifier`).
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    /// URLs each event is POSTed to as JSON.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<String>,
+    /// Delivery attempts per event and webhook (default: 3).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attempts: Option<u32>,
+    /// Host name reported in events instead of this machine's.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+}
+
+impl NotificationsConfig {
+    /// Overlays every field that is set in `other` onto `self`; webhooks are added.
+    pub fn merge_from(&mut self, other: &NotificationsConfig) {
+        for url in &other.webhooks {
+            if !self.webhooks.contains(url) {
+                self.webhooks.push(url.clone());
+            }
+        }
+        if other.attempts.is_some() {
+            self.attempts = other.attempts;
+        }
+        if other.host.is_some() {
+            self.host = other.host.clone();
+        }
+    }
 }
 
 /// Represents the top-level configuration structure for CleanSH.
@@ -131,6 +597,17 @@ pub struct RedactionConfig {
     /// Engine-specific settings (e.g., entropy thresholds and windows).
     #[serde(default)]
     pub engines: EngineConfig,
+    /// Flags whose values are secrets, e.g. `mysql -p<password>` (see `cli_flags`).
+    #[serde(default)]
+    pub cli_flag_secrets: Vec<CliFlagRule>,
+    /// Webhooks told about detections and remediations.
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    /// Other configs to layer underneath this one: paths relative to the including
+    /// file, or built-in rule packs such as `builtin:cloud`. Resolved, and emptied,
+    /// by [`RedactionConfig::load_from_file`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
 }
 
 /// Represents a single item in the redaction summary for the UI.
@@ -140,6 +617,50 @@ pub struct RedactionSummaryItem {
     pub occurrences: usize,
     pub original_texts: Vec<String>,
     pub sanitized_texts: Vec<String>,
+    /// How many of `occurrences` were repeats suppressed by `DedupeConfig`.
+    pub deduplicated: usize,
+    /// The highest severity among the matches: the rule's own, or for entropy
+    /// matches the band of their confidence.
+    pub severity: Severity,
+    /// Whether `original_texts` and `sanitized_texts` are a sample that leaves
+    /// some of the `occurrences` out (see `profiles::sampled_summary`).
+    pub truncated: bool,
+}
+
+/// Adds `items` to `summary`, combining entries for the same rule. The example
+/// texts are then capped again by the sample limits of `options` (see
+/// [`sampled_summary`](crate::profiles::sampled_summary)), keeping the earliest,
+/// so a summary merged from many lines stays as bounded as one of them.
+pub fn merge_summary(summary: &mut Vec<RedactionSummaryItem>, items: Vec<RedactionSummaryItem>, options: &EngineOptions) {
+    for item in items {
+        match summary.iter_mut().find(|s| s.rule_name == item.rule_name) {
+            Some(existing) => {
+                existing.occurrences += item.occurrences;
+                existing.deduplicated += item.deduplicated;
+                existing.severity = existing.severity.max(item.severity);
+                existing.truncated |= item.truncated;
+                existing.original_texts.extend(item.original_texts);
+                existing.sanitized_texts.extend(item.sanitized_texts);
+            }
+            None => summary.push(item),
+        }
+    }
+
+    // Rules draw on the overall limit in name order, as in `sampled_summary`.
+    let (max_per_rule, max_total) = summary_sample_limits(options);
+    let mut budget = if max_total == 0 { usize::MAX } else { max_total };
+    let mut order: Vec<usize> = (0..summary.len()).collect();
+    order.sort_by(|&a, &b| summary[a].rule_name.cmp(&summary[b].rule_name));
+    for index in order {
+        let item = &mut summary[index];
+        let keep = item.original_texts.len().min(max_per_rule).min(budget);
+        budget -= keep;
+        if keep < item.original_texts.len() {
+            item.original_texts.truncate(keep);
+            item.sanitized_texts.truncate(keep);
+            item.truncated = true;
+        }
+    }
 }
 
 /// Error type for missing rule configurations.
@@ -156,33 +677,101 @@ impl fmt::Display for RuleConfigNotFoundError {
 
 impl std::error::Error for RuleConfigNotFoundError {}
 
+/// Prefix of an `include` entry naming a built-in rule pack.
+pub const BUILTIN_PACK_PREFIX: &str = "builtin:";
+
+/// How deep `include` chains may nest before loading gives up.
+pub const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// The built-in rule packs, in the order the default rules combine them. Where two
+/// rules match at the same offset the one declared first wins, so the order matters.
+const RULE_PACKS: &[(&str, &str)] = &[
+    ("pii", include_str!("../config/packs/pii.yaml")),
+    ("network", include_str!("../config/packs/network.yaml")),
+    ("cloud", include_str!("../config/packs/cloud.yaml")),
+    ("credentials", include_str!("../config/packs/credentials.yaml")),
+    ("financial", include_str!("../config/packs/financial.yaml")),
+    ("filesystem", include_str!("../config/packs/filesystem.yaml")),
+];
+
+/// The names of the built-in rule packs, e.g. `cloud` for `builtin:cloud`.
+pub fn rule_pack_names() -> impl Iterator<Item = &'static str> {
+    RULE_PACKS.iter().map(|(name, _)| *name)
+}
+
+fn unknown_rule_pack(name: &str) -> anyhow::Error {
+    let known: Vec<&str> = rule_pack_names().collect();
+    anyhow!("Unknown rule pack '{}'. Expected one of: {}", name, known.join(", "))
+}
+
 impl RedactionConfig {
-    /// Loads redaction rules from a YAML file.
+    /// Loads redaction rules from a YAML, TOML, or JSON file (by extension, see
+    /// [`ConfigFormat`]), layering it over everything it `include`s.
+    ///
+    /// Includes are merged in order with [`merge_rules`], so a later include
+    /// overrides an earlier one and the file's own rules and settings override them
+    /// all. Include cycles and chains deeper than [`MAX_INCLUDE_DEPTH`] are errors.
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let path = path.as_ref();
+        Self::load_from_file_as(path, None)
+    }
+
+    /// Like [`Self::load_from_file`], reading files whose extension names no
+    /// format (the file itself and its includes) as `format`.
+    pub fn load_from_file_as<P: AsRef<Path>>(path: P, format: Option<ConfigFormat>) -> Result<Self> {
+        load_with_includes(path.as_ref(), format, &mut Vec::new())
+    }
+
+    /// Parses one file without resolving its includes.
+    fn parse_file(path: &Path, format: Option<ConfigFormat>) -> Result<Self> {
         info!("Loading custom rules from: {}", path.display());
         let text = std::fs::read_to_string(path)
             .with_context(|| format!("Failed to read config file {}", path.display()))?;
-        let config: RedactionConfig = serde_yml::from_str(&text)
-            .with_context(|| format!("Failed to parse config file {}", path.display()))?;
+        let format = ConfigFormat::detect(path, format);
+        let config: RedactionConfig = format.parse(&text)
+            .with_context(|| format!("Failed to parse config file {} as {}", path.display(), format.name()))?;
 
         validate_rules(&config.rules)?;
+        validate_cli_flag_rules(&config.cli_flag_secrets)?;
         info!("Loaded {} rules from file {}.", config.rules.len(), path.display());
         
         Ok(config)
     }
 
-    /// Loads default redaction rules from the embedded configuration.
+    /// Loads default redaction rules: every built-in rule pack, combined.
     pub fn load_default_rules() -> Result<Self> {
-        debug!("Loading default rules from embedded string...");
-        let default_yaml = include_str!("../config/default_rules.yaml");
-        let config: RedactionConfig = serde_yml::from_str(default_yaml)
-            .context("Failed to parse default rules")?;
-
+        debug!("Loading default rules from the embedded rule packs...");
+        let names: Vec<&str> = rule_pack_names().collect();
+        let config = Self::load_rule_packs(&names)?;
         debug!("Loaded {} default rules.", config.rules.len());
         Ok(config)
     }
 
+    /// Loads the built-in rule pack `name` (without the `builtin:` prefix).
+    pub fn load_rule_pack(name: &str) -> Result<Self> {
+        let (_, yaml) = RULE_PACKS.iter().find(|(pack, _)| *pack == name).ok_or_else(|| unknown_rule_pack(name))?;
+        serde_yml::from_str(yaml).with_context(|| format!("Failed to parse rule pack '{}'", name))
+    }
+
+    /// Combines the built-in rule packs `names` in their built-in order, whatever
+    /// order they are given in, so the same rule wins an overlap either way.
+    pub fn load_rule_packs<S: AsRef<str>>(names: &[S]) -> Result<Self> {
+        let names: Vec<&str> = names.iter().map(AsRef::as_ref).collect();
+        if let Some(unknown) = names.iter().find(|name| !rule_pack_names().any(|pack| pack == **name)) {
+            return Err(unknown_rule_pack(unknown));
+        }
+        let mut config = RedactionConfig::default();
+        for name in rule_pack_names().filter(|pack| names.contains(pack)) {
+            config = merge_rules(config, Some(Self::load_rule_pack(name)?));
+        }
+        Ok(config)
+    }
+
+    /// The rules engines compile and run (see [`RedactionRule::is_active`]), in
+    /// config order. Severities are the rules' own, with any profile override applied.
+    pub fn active_rules(&self) -> impl Iterator<Item = &RedactionRule> {
+        self.rules.iter().filter(|rule| rule.is_active())
+    }
+
     /// Filters active rules based on enable/disable lists provided via CLI.
     pub fn set_active_rules(&mut self, enable_rules: &[String], disable_rules: &[String]) {
         let enable_set: HashSet<&str> = enable_rules.iter().map(String::as_str).collect();
@@ -204,48 +793,349 @@ impl RedactionConfig {
             let rule_name_str = rule.name.as_str();
             !disable_set.contains(rule_name_str) && (!rule.opt_in || enable_set.contains(rule_name_str))
         });
+        // Record explicit enables so later tag filtering cannot drop them.
+        for rule in self.rules.iter_mut().filter(|rule| enable_set.contains(rule.name.as_str())) {
+            rule.enabled = Some(true);
+        }
 
         debug!("Final active rules count after filtering: {}", self.rules.len());
     }
+
+    /// Filters active rules by their `tags`, on top of any explicit `enabled` state.
+    ///
+    /// Precedence, highest first: explicit disable (`enabled: false`) > exclude tag >
+    /// explicit enable (`enabled: true`, including rules named in `set_active_rules`) >
+    /// include tag > the `opt_in` default. With an empty `include` list, rules without
+    /// an explicit state fall back to `!opt_in`; otherwise they need at least one
+    /// include tag. Tags compare case-insensitively.
+    pub fn set_active_rules_by_tags(&mut self, include: &[String], exclude: &[String]) {
+        let normalize = |tags: &[String]| -> HashSet<String> {
+            tags.iter().map(|t| t.trim().to_ascii_lowercase()).filter(|t| !t.is_empty()).collect()
+        };
+        let include_set = normalize(include);
+        let exclude_set = normalize(exclude);
+
+        let known_tags: HashSet<String> = self.rules.iter()
+            .flat_map(|r| r.tags.iter().flatten())
+            .map(|t| t.trim().to_ascii_lowercase())
+            .collect();
+        for tag in include_set.union(&exclude_set).filter(|t| !known_tags.contains(*t)) {
+            warn!("Tag '{}' is not carried by any rule.", tag);
+        }
+
+        debug!("Initial rules count before tag filtering: {}", self.rules.len());
+        self.rules.retain(|rule| {
+            let has_tag_in = |set: &HashSet<String>| {
+                rule.tags.iter().flatten().any(|t| set.contains(&t.trim().to_ascii_lowercase()))
+            };
+            match rule.enabled {
+                Some(false) => false,
+                _ if has_tag_in(&exclude_set) => false,
+                Some(true) => true,
+                None if include_set.is_empty() => !rule.opt_in,
+                None => has_tag_in(&include_set),
+            }
+        });
+        debug!("Final active rules count after tag filtering: {}", self.rules.len());
+    }
+
+    /// Cross-rule check run over the merged, enabled rule set (i.e. after profile application).
+    ///
+    /// Flags any rule whose `replace_with` (or rendered token, when `token_format` is set) would
+    /// itself be matched by another enabled rule, and any replacement that intrudes on the
+    /// deterministic token namespace (e.g. `[email:`). Each finding is logged as a warning and
+    /// returned; with `strict` set, any finding is an error instead.
+    pub fn validate_replacements(&self, token_format: Option<&str>, strict: bool) -> Result<Vec<String>> {
+        let findings = replacement_conflicts(&self.rules, token_format);
+        if strict && !findings.is_empty() {
+            return Err(anyhow!("Rule replacement check failed:\n{}", findings.join("\n")));
+        }
+        for finding in &findings {
+            warn!("{}", finding);
+        }
+        Ok(findings)
+    }
+}
+
+/// Reads `engines.entropy.threshold` from a config file written by [`save_entropy_threshold`],
+/// ignoring everything else in it.
+pub fn load_saved_entropy_threshold<P: AsRef<Path>>(path: P) -> Result<Option<f64>> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    let root = ConfigFormat::detect(path, None).parse_value(&text)
+        .with_context(|| format!("Failed to parse config file {}", path.display()))?;
+    match root.get("engines").and_then(|e| e.get("entropy")).and_then(|e| e.get("threshold")) {
+        None => Ok(None),
+        Some(value) => value.as_f64().map(Some)
+            .ok_or_else(|| anyhow!("engines.entropy.threshold in {} is not a number", path.display())),
+    }
+}
+
+/// Writes `engines.entropy.threshold` into a config file, in the format its extension names
+/// (see [`ConfigFormat`]), keeping everything else in it.
+///
+/// The file (and its parent directories) are created if missing.
+pub fn save_entropy_threshold<P: AsRef<Path>>(path: P, threshold: f64) -> Result<()> {
+    use serde_yml::{Mapping, Value};
+
+    let path = path.as_ref();
+    let format = ConfigFormat::detect(path, None);
+    let mut root = if path.exists() {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        format.parse_value(&text)
+            .with_context(|| format!("Failed to parse config file {}", path.display()))?
+    } else {
+        Value::Mapping(Mapping::new())
+    };
+    if root.is_null() {
+        root = Value::Mapping(Mapping::new());
+    }
+    // `rules` is mandatory when the file is loaded back, so seed it for new files.
+    if let Some(map) = root.as_mapping_mut() {
+        map.entry(Value::String("rules".to_string()))
+            .or_insert_with(|| Value::Sequence(Vec::new()));
+    }
+
+    let mut node = &mut root;
+    for key in ["engines", "entropy"] {
+        let map = node.as_mapping_mut()
+            .ok_or_else(|| anyhow!("Config file {} has an unexpected structure", path.display()))?;
+        node = map.entry(Value::String(key.to_string()))
+            .or_insert_with(|| Value::Mapping(Mapping::new()));
+        if node.is_null() {
+            *node = Value::Mapping(Mapping::new());
+        }
+    }
+    node.as_mapping_mut()
+        .ok_or_else(|| anyhow!("Config file {} has an unexpected structure", path.display()))?
+        .insert(Value::String("threshold".to_string()), Value::from(threshold));
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+    }
+    let text = format.to_string(&root).context("Failed to serialize config")?;
+    std::fs::write(path, text)
+        .with_context(|| format!("Failed to write config file {}", path.display()))?;
+    info!("Saved entropy threshold {} to {}.", threshold, path.display());
+    Ok(())
+}
+
+/// Appends `rule` to the `rules` list of the YAML, TOML, or JSON file at `path`, creating the
+/// file if needed.
+///
+/// The rule is validated together with the rules already in the file, so a bad
+/// pattern or a name clash never reaches disk. An existing rule with the same name
+/// is replaced when `force` is set and is an error otherwise. Fields left at their
+/// defaults are not written, keeping hand-edited files readable.
+pub fn append_rule<P: AsRef<Path>>(path: P, rule: &RedactionRule, force: bool) -> Result<()> {
+    use serde_yml::{Mapping, Value};
+
+    let path = path.as_ref();
+    let format = ConfigFormat::detect(path, None);
+    if let Some(pattern) = &rule.pattern {
+        if pattern.len() > MAX_PATTERN_LENGTH {
+            return Err(anyhow!(
+                "Rule '{}': pattern is {} characters long, exceeding the maximum of {}.",
+                rule.name, pattern.len(), MAX_PATTERN_LENGTH
+            ));
+        }
+    }
+
+    let mut root = if path.exists() {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read rules file {}", path.display()))?;
+        format.parse_value(&text)
+            .with_context(|| format!("Failed to parse rules file {}", path.display()))?
+    } else {
+        Value::Mapping(Mapping::new())
+    };
+    if root.is_null() {
+        root = Value::Mapping(Mapping::new());
+    }
+    let rules = root.as_mapping_mut()
+        .ok_or_else(|| anyhow!("Rules file {} has an unexpected structure", path.display()))?
+        .entry(Value::String("rules".to_string()))
+        .or_insert_with(|| Value::Sequence(Vec::new()));
+    if rules.is_null() {
+        *rules = Value::Sequence(Vec::new());
+    }
+    let rules = rules.as_sequence_mut()
+        .ok_or_else(|| anyhow!("`rules` in {} is not a list", path.display()))?;
+
+    let mut entry = serde_yml::to_value(rule).context("Failed to serialize rule")?;
+    let defaults = serde_yml::to_value(RedactionRule::default()).context("Failed to serialize rule")?;
+    if let (Some(map), Some(defaults)) = (entry.as_mapping_mut(), defaults.as_mapping()) {
+        map.retain(|key, value| {
+            matches!(key.as_str(), Some("name" | "pattern" | "replace_with")) || defaults.get(key) != Some(value)
+        });
+    }
+
+    let is_same_rule = |value: &Value| value.get("name").and_then(Value::as_str) == Some(rule.name.as_str());
+    match rules.iter().position(is_same_rule) {
+        Some(_) if !force => {
+            return Err(anyhow!(
+                "Rule '{}' already exists in {}; use --force to replace it.",
+                rule.name, path.display()
+            ));
+        }
+        Some(index) => rules[index] = entry,
+        None => rules.push(entry),
+    }
+
+    let parsed: Vec<RedactionRule> = serde_yml::from_value(Value::Sequence(rules.clone()))
+        .with_context(|| format!("Failed to parse rules in {}", path.display()))?;
+    validate_rules(&parsed)?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+    }
+    let text = format.to_string(&root).context("Failed to serialize rules file")?;
+    std::fs::write(path, text)
+        .with_context(|| format!("Failed to write rules file {}", path.display()))?;
+    info!("Saved rule '{}' to {}.", rule.name, path.display());
+    Ok(())
+}
+
+/// Loads `path` and, depth first, everything it includes. `stack` holds the files
+/// being loaded, outermost first, to catch cycles.
+fn load_with_includes(path: &Path, format: Option<ConfigFormat>, stack: &mut Vec<PathBuf>) -> Result<RedactionConfig> {
+    let canonical = path.canonicalize()
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    if let Some(start) = stack.iter().position(|p| *p == canonical) {
+        let cycle: Vec<String> = stack[start..].iter().chain([&canonical])
+            .map(|p| p.display().to_string())
+            .collect();
+        return Err(anyhow!("Include cycle: {}", cycle.join(" -> ")));
+    }
+    if stack.len() >= MAX_INCLUDE_DEPTH {
+        return Err(anyhow!(
+            "Including {} exceeds the maximum include depth of {}.",
+            path.display(), MAX_INCLUDE_DEPTH
+        ));
+    }
+
+    let mut own = RedactionConfig::parse_file(path, format)?;
+    let includes = std::mem::take(&mut own.include);
+    if includes.is_empty() {
+        return Ok(own);
+    }
+
+    stack.push(canonical);
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let mut base = RedactionConfig::default();
+    for include in &includes {
+        let included = match include.strip_prefix(BUILTIN_PACK_PREFIX) {
+            Some(pack) => RedactionConfig::load_rule_pack(pack),
+            None => load_with_includes(&base_dir.join(include), format, stack),
+        }
+        .with_context(|| format!("Failed to include '{}' from {}", include, path.display()))?;
+        base = merge_rules(base, Some(included));
+    }
+    stack.pop();
+    Ok(merge_rules(base, Some(own)))
 }
 
 /// Merges user-defined rules and engine settings with defaults.
+///
+/// Rules keep the order they were first declared in; a user rule with a default
+/// rule's name replaces it in place, and new user rules follow the defaults.
 pub fn merge_rules(
     default_config: RedactionConfig,
     user_config: Option<RedactionConfig>,
 ) -> RedactionConfig {
     debug!("merge_rules called. Initial default rules count: {}", default_config.rules.len());
     
-    let mut final_rules_map: HashMap<String, RedactionRule> = default_config.rules.into_iter()
-        .map(|rule| (rule.name.clone(), rule))
+    let mut final_rules = default_config.rules;
+    let mut positions: HashMap<String, usize> = final_rules.iter()
+        .enumerate()
+        .map(|(i, rule)| (rule.name.clone(), i))
         .collect();
 
     let mut final_engines = default_config.engines;
+    let mut final_cli_flags = default_config.cli_flag_secrets;
+    let mut final_notifications = default_config.notifications;
 
     if let Some(user_cfg) = user_config {
         debug!("User config provided. Merging {} user rules.", user_cfg.rules.len());
         for user_rule in user_cfg.rules {
-            final_rules_map.insert(user_rule.name.clone(), user_rule);
+            match positions.get(&user_rule.name) {
+                Some(&index) => final_rules[index] = user_rule,
+                None => {
+                    positions.insert(user_rule.name.clone(), final_rules.len());
+                    final_rules.push(user_rule);
+                }
+            }
         }
         
-        if let Some(user_threshold) = user_cfg.engines.entropy.threshold {
-             debug!("Overriding entropy threshold with user value: {}", user_threshold);
-             final_engines.entropy.threshold = Some(user_threshold);
-        }
-
-        if let Some(user_window) = user_cfg.engines.entropy.window_size {
-            debug!("Overriding entropy window size with user value: {}", user_window);
-            final_engines.entropy.window_size = Some(user_window);
-        }
+        final_engines.merge_from(&user_cfg.engines);
+        merge_cli_flag_rules(&mut final_cli_flags, &user_cfg.cli_flag_secrets);
+        final_notifications.merge_from(&user_cfg.notifications);
     }
 
-    let final_rules: Vec<RedactionRule> = final_rules_map.into_values().collect();
     debug!("Final total rules after merge: {}", final_rules.len());
 
     RedactionConfig { 
         rules: final_rules,
         engines: final_engines,
+        cli_flag_secrets: final_cli_flags,
+        notifications: final_notifications,
+        include: Vec::new(),
+    }
+}
+
+/// Collects replacement collisions between enabled rules. See `RedactionConfig::validate_replacements`.
+fn replacement_conflicts(rules: &[RedactionRule], token_format: Option<&str>) -> Vec<String> {
+    // Opt-in rules only take part once something has explicitly switched them on.
+    let enabled: Vec<&RedactionRule> = rules.iter().filter(|r| r.is_active()).collect();
+    let compiled: Vec<(&RedactionRule, Regex)> = enabled.iter()
+        .filter(|r| r.pattern_type == "regex")
+        .filter_map(|r| r.pattern.as_deref().and_then(|p| Regex::new(p).ok()).map(|re| (*r, re)))
+        .collect();
+
+    // The literal text every token for a rule starts with, e.g. "[email:" for the default format.
+    let namespace_format = token_format.unwrap_or(DEFAULT_TOKEN_FORMAT);
+    let namespaces: Vec<(&str, String)> = enabled.iter()
+        .filter_map(|r| {
+            let rendered = format_token(namespace_format, &r.name, "\u{0}").ok()?;
+            let prefix = rendered.split('\u{0}').next().unwrap_or_default().to_string();
+            (!prefix.is_empty() && prefix.len() < rendered.len()).then_some((r.name.as_str(), prefix))
+        })
+        .collect();
+
+    let mut findings = Vec::new();
+    for rule in &enabled {
+        let mut outputs = vec![("replacement", rule.replace_with.clone())];
+        if let Some(fmt) = token_format {
+            if let Ok(token) = format_token(fmt, &rule.name, &"0".repeat(64)) {
+                outputs.push(("token", token));
+            }
+        }
+
+        for (kind, output) in &outputs {
+            for (other, regex) in &compiled {
+                if other.name != rule.name && regex.is_match(output) {
+                    findings.push(format!(
+                        "Rule '{}': {} '{}' is itself matched by rule '{}'.",
+                        rule.name, kind, output, other.name
+                    ));
+                }
+            }
+        }
+
+        for (owner, prefix) in &namespaces {
+            if rule.replace_with.contains(prefix.as_str()) {
+                findings.push(format!(
+                    "Rule '{}': replacement '{}' collides with the token namespace '{}' of rule '{}'.",
+                    rule.name, rule.replace_with, prefix, owner
+                ));
+            }
+        }
     }
+    findings
 }
 
 /// Validates rule integrity (regex compilation, capture groups).
@@ -274,9 +1164,18 @@ fn validate_rules(rules: &[RedactionRule]) -> Result<()> {
                 errors.push(format!("Rule '{}' has an empty `pattern` field.", rule.name));
             }
             
-            if let Err(e) = Regex::new(pattern) {
-                errors.push(format!("Rule '{}' has an invalid regex pattern: {}", rule.name, e));
-                continue;
+            let regex = match Regex::new(pattern) {
+                Ok(regex) => regex,
+                Err(e) => {
+                    errors.push(format!("Rule '{}' has an invalid regex pattern: {}", rule.name, e));
+                    continue;
+                }
+            };
+            if let Err(reason) = check_repetition_bounds(pattern) {
+                errors.push(format!("Rule '{}': pattern is too complex: {}.", rule.name, reason));
+            }
+            if let Some(group) = rule.redact_group.as_ref().filter(|group| group.resolve(&regex).is_none()) {
+                errors.push(format!("Rule '{}': redact_group '{}' is not a capture group of its pattern.", rule.name, group));
             }
             
             let mut group_count = 0;
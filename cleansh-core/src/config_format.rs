@@ -0,0 +1,112 @@
+// cleansh-core/src/config_format.rs
+//! The file formats configs, rules, profiles, and themes can be written in.
+//!
+//! A file's format comes from its extension: `.yaml`/`.yml`, `.toml`, or `.json`.
+//! Files with any other extension, or none, use the format given explicitly (the
+//! CLI's `--config-format`), else YAML.
+
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Extensions recognized as config files, in the order lookups try them.
+pub const CONFIG_EXTENSIONS: [&str; 4] = ["yaml", "yml", "toml", "json"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ConfigFormat {
+    #[default]
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl ConfigFormat {
+    /// The format `path`'s extension names, if it names one.
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "yaml" | "yml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    /// The format of `path`: by extension, else `fallback`, else YAML.
+    pub fn detect(path: &Path, fallback: Option<Self>) -> Self {
+        Self::from_extension(path).or(fallback).unwrap_or_default()
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Yaml => "YAML",
+            Self::Toml => "TOML",
+            Self::Json => "JSON",
+        }
+    }
+
+    /// Parses `text`. Errors say which parser failed and why.
+    pub fn parse<T: DeserializeOwned>(self, text: &str) -> Result<T> {
+        let parsed = match self {
+            Self::Yaml => serde_yml::from_str(text).map_err(anyhow::Error::from),
+            Self::Json => serde_json::from_str(text).map_err(anyhow::Error::from),
+            Self::Toml => toml::from_str(text).map_err(anyhow::Error::from),
+        };
+        parsed.map_err(|e| anyhow!("{} parser: {}", self.name(), e))
+    }
+
+    /// Parses `text` into a YAML value, whatever its format, for code that edits
+    /// or canonicalizes documents.
+    pub fn parse_value(self, text: &str) -> Result<serde_yml::Value> {
+        match self {
+            Self::Yaml => self.parse(text),
+            Self::Toml | Self::Json => {
+                let value: serde_json::Value = self.parse(text)?;
+                serde_yml::to_value(value).map_err(|e| anyhow!("{} parser: {}", self.name(), e))
+            }
+        }
+    }
+
+    /// Writes `value` in this format.
+    pub fn to_string<T: Serialize>(self, value: &T) -> Result<String> {
+        let written = match self {
+            Self::Yaml => serde_yml::to_string(value).map_err(anyhow::Error::from),
+            Self::Json => serde_json::to_string_pretty(value).map(|mut text| {
+                text.push('\n');
+                text
+            }).map_err(anyhow::Error::from),
+            Self::Toml => toml::to_string(value).map_err(anyhow::Error::from),
+        };
+        written.map_err(|e| anyhow!("{} writer: {}", self.name(), e))
+    }
+}
+
+impl fmt::Display for ConfigFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name().to_ascii_lowercase())
+    }
+}
+
+impl FromStr for ConfigFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "yaml" | "yml" => Ok(Self::Yaml),
+            "toml" => Ok(Self::Toml),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("unknown config format '{}'. Expected one of: yaml, toml, json", s)),
+        }
+    }
+}
+
+/// The first existing `<stem>.<ext>` in `dir` over [`CONFIG_EXTENSIONS`], or
+/// `<stem>.yaml` when there is none.
+pub fn find_config_file(dir: &Path, stem: &str) -> PathBuf {
+    CONFIG_EXTENSIONS.iter()
+        .map(|ext| dir.join(format!("{}.{}", stem, ext)))
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| dir.join(format!("{}.yaml", stem)))
+}
@@ -0,0 +1,221 @@
+// cleansh-core/src/corpus.rs
+//! Snapshot corpora: directories of paired `<case>.input` / `<case>.expected`
+//! files that pin down what an engine redacts, so detection changes between
+//! releases show up as diffs instead of bug reports.
+//!
+//! [`run_corpus`] sanitizes every input and compares the result line by line
+//! with its expected file. A differing line is classified by the redaction
+//! placeholders (`[EMAIL_REDACTED]`, `[email:1a2b3c4d]`, ...) on each side:
+//! fewer than expected is a missed redaction, more is an over-redaction, and
+//! the same number (a different replacement or span) is a changed redaction.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::engine::{SanitizationEngine, SanitizeContext};
+
+/// Extension of a case's input file.
+pub const INPUT_EXTENSION: &str = "input";
+
+/// Extension of a case's expected output file.
+pub const EXPECTED_EXTENSION: &str = "expected";
+
+/// A bracketed replacement such as `[EMAIL_REDACTED]` or a `[rule:shorthash]` token.
+static PLACEHOLDER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\[[A-Za-z0-9_]+(?::[^\]\s]*)?\]").expect("placeholder pattern is valid")
+});
+
+/// How an output line differs from the expected one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DiffKind {
+    /// The output has fewer redactions than expected: a secret leaked through.
+    MissedRedaction,
+    /// The output has more redactions than expected: harmless text was redacted.
+    OverRedaction,
+    /// As many redactions as expected, but a different replacement or span.
+    Changed,
+}
+
+impl fmt::Display for DiffKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::MissedRedaction => "missed redaction",
+            Self::OverRedaction => "over-redaction",
+            Self::Changed => "changed redaction",
+        })
+    }
+}
+
+/// One line whose output does not match the expected file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineDiff {
+    /// 1-based line number.
+    pub line: usize,
+    pub kind: DiffKind,
+    /// The expected line; empty when the output has extra lines.
+    pub expected: String,
+    /// The output line; empty when the output is missing lines.
+    pub actual: String,
+}
+
+/// The outcome of one case.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaseResult {
+    /// The file name shared by the case's files, without the extension.
+    pub name: String,
+    pub diffs: Vec<LineDiff>,
+    /// Why the case could not be run (an unreadable file or a missing pair).
+    pub error: Option<String>,
+}
+
+impl CaseResult {
+    pub fn passed(&self) -> bool {
+        self.diffs.is_empty() && self.error.is_none()
+    }
+}
+
+/// The outcome of every case in a corpus, sorted by case name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CorpusReport {
+    pub cases: Vec<CaseResult>,
+}
+
+impl CorpusReport {
+    /// Whether every case ran and matched its expected output. An empty corpus passes.
+    pub fn passed(&self) -> bool {
+        self.cases.iter().all(CaseResult::passed)
+    }
+
+    pub fn failed_cases(&self) -> impl Iterator<Item = &CaseResult> {
+        self.cases.iter().filter(|case| !case.passed())
+    }
+
+    /// How many differing lines of `kind` the corpus has.
+    pub fn count(&self, kind: DiffKind) -> usize {
+        self.cases.iter().flat_map(|case| &case.diffs).filter(|diff| diff.kind == kind).count()
+    }
+}
+
+/// Each failing case with its differing lines, then a summary line such as
+/// `30 cases: 28 passed, 2 failed (1 missed, 1 over-redacted, 0 changed, 0 errors)`.
+impl fmt::Display for CorpusReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for case in self.failed_cases() {
+            writeln!(f, "FAIL {}", case.name)?;
+            if let Some(error) = &case.error {
+                writeln!(f, "  error: {}", error)?;
+            }
+            for diff in &case.diffs {
+                writeln!(f, "  line {}: {}", diff.line, diff.kind)?;
+                writeln!(f, "    expected: {}", diff.expected)?;
+                writeln!(f, "    actual:   {}", diff.actual)?;
+            }
+        }
+        let failed = self.failed_cases().count();
+        let errors = self.cases.iter().filter(|case| case.error.is_some()).count();
+        write!(
+            f,
+            "{} cases: {} passed, {} failed ({} missed, {} over-redacted, {} changed, {} errors)",
+            self.cases.len(),
+            self.cases.len() - failed,
+            failed,
+            self.count(DiffKind::MissedRedaction),
+            self.count(DiffKind::OverRedaction),
+            self.count(DiffKind::Changed),
+            errors,
+        )
+    }
+}
+
+/// Runs every case in `dir` through `engine` and compares the sanitized output
+/// with the case's expected file. Subdirectories are not searched. Problems with
+/// a case, such as a missing `.expected` file, are reported on that case; a
+/// directory that cannot be listed yields a single failing case named after it.
+pub fn run_corpus(dir: &Path, engine: &dyn SanitizationEngine) -> CorpusReport {
+    let pairs = match case_files(dir) {
+        Ok(pairs) => pairs,
+        Err(e) => {
+            return CorpusReport {
+                cases: vec![CaseResult { name: dir.display().to_string(), diffs: Vec::new(), error: Some(e) }],
+            };
+        }
+    };
+    let cases = pairs.into_iter()
+        .map(|(name, (input, expected))| match (input, expected) {
+            (Some(input), Some(expected)) => run_case(name, &input, &expected, engine),
+            (Some(_), None) => failed_case(name, format!("no .{} file", EXPECTED_EXTENSION)),
+            (None, _) => failed_case(name, format!("no .{} file", INPUT_EXTENSION)),
+        })
+        .collect();
+    CorpusReport { cases }
+}
+
+/// A case's input and expected files, either of which may be missing.
+type CaseFiles = (Option<PathBuf>, Option<PathBuf>);
+
+/// The case files in `dir`, keyed and sorted by case name.
+fn case_files(dir: &Path) -> Result<BTreeMap<String, CaseFiles>, String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read corpus directory {}: {}", dir.display(), e))?;
+    let mut pairs: BTreeMap<String, CaseFiles> = BTreeMap::new();
+    for entry in entries {
+        let path = entry.map_err(|e| format!("Failed to read corpus directory {}: {}", dir.display(), e))?.path();
+        let (Some(stem), Some(extension)) = (path.file_stem().and_then(|s| s.to_str()), path.extension()) else { continue };
+        let slot = pairs.entry(stem.to_string()).or_default();
+        if extension == INPUT_EXTENSION {
+            slot.0 = Some(path);
+        } else if extension == EXPECTED_EXTENSION {
+            slot.1 = Some(path);
+        }
+    }
+    pairs.retain(|_, (input, expected)| input.is_some() || expected.is_some());
+    Ok(pairs)
+}
+
+fn failed_case(name: String, error: String) -> CaseResult {
+    CaseResult { name, diffs: Vec::new(), error: Some(error) }
+}
+
+fn run_case(name: String, input: &Path, expected: &Path, engine: &dyn SanitizationEngine) -> CaseResult {
+    let read = |path: &Path| fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e));
+    let (input_text, expected_text) = match (read(input), read(expected)) {
+        (Ok(input_text), Ok(expected_text)) => (input_text, expected_text),
+        (Err(e), _) | (_, Err(e)) => return failed_case(name, e),
+    };
+    let source_id = input.display().to_string();
+    match engine.sanitize_with(&input_text, &mut SanitizeContext::new(&source_id)) {
+        Ok((actual, _)) => CaseResult { diffs: diff_lines(&expected_text, &actual), name, error: None },
+        Err(e) => failed_case(name, format!("sanitize failed: {:#}", e)),
+    }
+}
+
+/// The lines of `actual` that differ from `expected`, classified.
+pub fn diff_lines(expected: &str, actual: &str) -> Vec<LineDiff> {
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    let mut diffs = Vec::new();
+    for line in 1.. {
+        let (expected, actual) = match (expected_lines.next(), actual_lines.next()) {
+            (None, None) => break,
+            (expected, actual) => (expected.unwrap_or_default(), actual.unwrap_or_default()),
+        };
+        if expected != actual {
+            diffs.push(LineDiff { line, kind: classify(expected, actual), expected: expected.to_string(), actual: actual.to_string() });
+        }
+    }
+    diffs
+}
+
+fn classify(expected: &str, actual: &str) -> DiffKind {
+    let expected_count = PLACEHOLDER.find_iter(expected).count();
+    let actual_count = PLACEHOLDER.find_iter(actual).count();
+    match actual_count.cmp(&expected_count) {
+        std::cmp::Ordering::Less => DiffKind::MissedRedaction,
+        std::cmp::Ordering::Greater => DiffKind::OverRedaction,
+        std::cmp::Ordering::Equal => DiffKind::Changed,
+    }
+}
@@ -0,0 +1,229 @@
+// cleansh-core/src/crash_report.rs
+//! A panic hook that never prints the secret being processed.
+//!
+//! The standard hook prints the panic message as given, and a message built from
+//! the line being sanitized can hold the very secret cleansh was hiding. The hook
+//! installed by [`install_safe_panic_hook`] prints the message only after
+//! [`scrub_panic_message`] has masked anything that looks sensitive, and writes a
+//! crash report holding the version, engine, profile, and the last few lines as
+//! they were *sanitized* (see [`record_sanitized_line`]), never raw input. Until
+//! the hook is installed no lines are kept.
+//!
+//! Frontends that change the terminal register a cleanup with a [`CleanupGuard`]:
+//! the hook runs it before printing, so the message lands on a usable terminal,
+//! and the guard runs it again when dropped, on unwinding or a normal return.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::io::Write;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use anyhow::{Context, Result};
+use cleansh_entropy::entropy::calculate_shannon_entropy;
+
+use crate::redaction_match::redact_sensitive;
+
+/// Environment variable naming the directory crash reports are written to.
+pub const CRASH_DIR_ENV: &str = "CLEANSH_CRASH_DIR";
+
+/// How many sanitized lines a crash report keeps.
+pub const CRASH_REPORT_LINES: usize = 20;
+
+/// Tokens shorter than this are never masked, so messages stay readable.
+const MIN_SENSITIVE_LEN: usize = 8;
+
+/// Bits per character above which a token is taken for random, and so secret.
+const SENSITIVE_ENTROPY: f64 = 3.5;
+
+/// What a crash report says about the session.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CrashContext {
+    pub version: String,
+    pub engine: String,
+    /// `None` when no profile was selected.
+    pub profile: Option<String>,
+}
+
+static CONTEXT: Mutex<Option<CrashContext>> = Mutex::new(None);
+static RECENT_LINES: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+static CLEANUPS: Mutex<Vec<fn()>> = Mutex::new(Vec::new());
+/// Numbers reports, so two panics in the same millisecond get a file each.
+static REPORTS: AtomicUsize = AtomicUsize::new(0);
+/// Whether the hook is installed, and so whether lines are kept for a report.
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// Sets what crash reports say about the session.
+pub fn set_crash_context(context: CrashContext) {
+    *CONTEXT.lock().unwrap_or_else(PoisonError::into_inner) = Some(context);
+}
+
+/// Records that the session switched to `engine`.
+pub fn set_crash_engine(engine: &str) {
+    CONTEXT.lock().unwrap_or_else(PoisonError::into_inner).get_or_insert_with(CrashContext::default).engine = engine.to_string();
+}
+
+/// Keeps `line` for the next crash report, dropping the oldest past
+/// [`CRASH_REPORT_LINES`]. Pass only sanitized text: it is written as given.
+/// Does nothing until the hook is installed.
+pub fn record_sanitized_line(line: &str) {
+    if !INSTALLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let mut lines = RECENT_LINES.lock().unwrap_or_else(PoisonError::into_inner);
+    if lines.len() >= CRASH_REPORT_LINES {
+        lines.pop_front();
+    }
+    lines.push_back(line.to_string());
+}
+
+/// Whether a token of a panic message could be a secret: long enough, and an
+/// email address, a mix of letters and digits, or random-looking.
+fn looks_sensitive(token: &str) -> bool {
+    if token.chars().count() < MIN_SENSITIVE_LEN {
+        return false;
+    }
+    let has_alpha = token.chars().any(char::is_alphabetic);
+    let has_digit = token.chars().any(|c| c.is_ascii_digit());
+    token.contains('@')
+        || (has_alpha && has_digit)
+        || calculate_shannon_entropy(token.as_bytes()) >= SENSITIVE_ENTROPY
+}
+
+/// `message` with every word that looks sensitive replaced as debug logs mask
+/// matches (`[REDACTED: 32 chars]`). In `key=value` and `key: value` words only
+/// the value is judged, and quotes and brackets around a word are kept.
+pub fn scrub_panic_message(message: &str) -> String {
+    let is_wrapper = |c: char| matches!(c, '"' | '\'' | '`' | '(' | ')' | '[' | ']' | '{' | '}' | '<' | '>' | ',' | ';' | '.');
+    message
+        .split_inclusive(char::is_whitespace)
+        .map(|word| {
+            let body = word.trim_end_matches(char::is_whitespace);
+            let trailing = &word[body.len()..];
+            let inner = body.trim_matches(is_wrapper);
+            let lead = &body[..body.len() - body.trim_start_matches(is_wrapper).len()];
+            let tail = &body[lead.len() + inner.len()..];
+            let (key, value) = match inner.find(['=', ':']) {
+                Some(i) if i + 1 < inner.len() => inner.split_at(i + 1),
+                _ => ("", inner),
+            };
+            let quote = &value[..value.len() - value.trim_start_matches(is_wrapper).len()];
+            let value = &value[quote.len()..];
+            if looks_sensitive(value) {
+                format!("{}{}{}{}{}{}", lead, key, quote, redact_sensitive(value), tail, trailing)
+            } else {
+                word.to_string()
+            }
+        })
+        .collect()
+}
+
+fn panic_message(info: &PanicHookInfo<'_>) -> String {
+    let payload = info.payload();
+    payload.downcast_ref::<&str>().map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Box<dyn Any>".to_string())
+}
+
+/// The directory crash reports go to by default: `CLEANSH_CRASH_DIR`, or
+/// `cleansh/crashes` in the platform cache directory.
+pub fn default_crash_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os(CRASH_DIR_ENV) {
+        return Some(dir.into());
+    }
+    dirs::cache_dir().map(|dir| dir.join("cleansh").join("crashes"))
+}
+
+/// Writes a crash report for a panic with `message` (already scrubbed) at
+/// `location` to `dir`, returning its path.
+pub fn write_crash_report(dir: &Path, message: &str, location: &str) -> Result<PathBuf> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create crash directory {}", dir.display()))?;
+    let now = chrono::Utc::now();
+    let path = dir.join(format!(
+        "crash-{}-{}-{}.txt",
+        now.format("%Y%m%dT%H%M%S%.3fZ"), std::process::id(), REPORTS.fetch_add(1, Ordering::Relaxed),
+    ));
+    let context = CONTEXT.lock().unwrap_or_else(PoisonError::into_inner).clone().unwrap_or_default();
+    let lines: Vec<String> = RECENT_LINES.lock().unwrap_or_else(PoisonError::into_inner).iter().cloned().collect();
+
+    let mut report = String::new();
+    report.push_str("cleansh crash report\n");
+    report.push_str(&format!("time: {}\n", now.to_rfc3339()));
+    report.push_str(&format!("version: {}\n", if context.version.is_empty() { env!("CARGO_PKG_VERSION") } else { &context.version }));
+    report.push_str(&format!("engine: {}\n", if context.engine.is_empty() { "unknown" } else { &context.engine }));
+    report.push_str(&format!("profile: {}\n", context.profile.as_deref().unwrap_or("default")));
+    report.push_str(&format!("thread: {}\n", std::thread::current().name().unwrap_or("unnamed")));
+    report.push_str(&format!("location: {}\n", location));
+    report.push_str(&format!("message: {}\n", message));
+    report.push_str(&format!("\nlast {} sanitized lines:\n", lines.len()));
+    for line in &lines {
+        report.push_str(line);
+        report.push('\n');
+    }
+
+    let mut file = fs::File::create(&path).with_context(|| format!("Failed to create crash report {}", path.display()))?;
+    file.write_all(report.as_bytes())?;
+    Ok(path)
+}
+
+fn run_cleanups() {
+    let cleanups = CLEANUPS.lock().unwrap_or_else(PoisonError::into_inner).clone();
+    for cleanup in cleanups {
+        cleanup();
+    }
+}
+
+/// Replaces the panic hook with one that runs the registered cleanups, prints a
+/// scrubbed message to stderr, and writes a crash report to [`default_crash_dir`].
+pub fn install_safe_panic_hook() {
+    install_hook(default_crash_dir());
+}
+
+/// Like [`install_safe_panic_hook`], writing crash reports to `dir`.
+pub fn install_safe_panic_hook_in(dir: impl Into<PathBuf>) {
+    install_hook(Some(dir.into()));
+}
+
+fn install_hook(dir: Option<PathBuf>) {
+    INSTALLED.store(true, Ordering::Relaxed);
+    std::panic::set_hook(Box::new(move |info| {
+        run_cleanups();
+        let message = scrub_panic_message(&panic_message(info));
+        let location = info.location().map(|l| l.to_string()).unwrap_or_else(|| "unknown".to_string());
+        let mut stderr = std::io::stderr().lock();
+        let _ = writeln!(stderr, "cleansh crashed at {}: {}", location, message);
+        match dir.as_deref().map(|dir| write_crash_report(dir, &message, &location)) {
+            Some(Ok(path)) => { let _ = writeln!(stderr, "A sanitized crash report was written to {}", path.display()); }
+            Some(Err(e)) => { let _ = writeln!(stderr, "Could not write a crash report: {:#}", e); }
+            None => {}
+        }
+    }));
+}
+
+/// Registers `cleanup` with the panic hook while alive and runs it when dropped,
+/// so state such as a raw-mode terminal is restored however the holder exits.
+/// `cleanup` may run twice (from the hook, then on unwinding) and must allow it.
+pub struct CleanupGuard {
+    cleanup: fn(),
+}
+
+impl CleanupGuard {
+    pub fn new(cleanup: fn()) -> Self {
+        CLEANUPS.lock().unwrap_or_else(PoisonError::into_inner).push(cleanup);
+        Self { cleanup }
+    }
+}
+
+impl Drop for CleanupGuard {
+    fn drop(&mut self) {
+        {
+            let mut cleanups = CLEANUPS.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Some(i) = cleanups.iter().rposition(|c| std::ptr::fn_addr_eq(*c, self.cleanup)) {
+                cleanups.remove(i);
+            }
+        }
+        (self.cleanup)();
+    }
+}
@@ -8,15 +8,137 @@
 //!
 //! License: MIT OR APACHE 2.0
 
+use std::ops::Range;
+use std::sync::Arc;
+
 use anyhow::Result;
-use tokio::sync::mpsc;
 
 // Publicly exposed types from other modules
 use crate::config::{RedactionConfig, RedactionSummaryItem};
 use crate::profiles::EngineOptions;
 use crate::sanitizers::compiler::CompiledRules;
 use crate::audit_log::AuditLog;
-use crate::redaction_match::RedactionMatch;
+use crate::redaction_match::{offset_from_index, RedactionLog, RedactionMatch};
+use crate::metrics::EngineMetricsSnapshot;
+use crate::remediation::fingerprint::SecretFingerprint;
+use crate::remediation::fingerprint_cache::FingerprintCache;
+
+/// Describes one `sanitize` call: where the content came from, why it is being
+/// sanitized, and the audit log that should record each redaction.
+///
+/// Every field but `source_id` defaults to empty, so callers only name what they
+/// know:
+///
+/// ```
+/// use cleansh_core::engine::SanitizeContext;
+///
+/// let ctx = SanitizeContext::new("app.log").with_reason("sanitize").with_outcome("success");
+/// assert_eq!(ctx.run_id, "");
+/// ```
+#[derive(Default)]
+pub struct SanitizeContext<'a> {
+    /// The name or identifier of the source being processed.
+    pub source_id: &'a str,
+    /// A unique identifier for the current sanitization run.
+    pub run_id: &'a str,
+    /// A hash of the original input content for integrity checks.
+    pub input_hash: &'a str,
+    /// The user ID associated with the sanitization run.
+    pub user_id: &'a str,
+    /// The reason for the sanitization.
+    pub reason: &'a str,
+    /// The outcome of the sanitization.
+    pub outcome: &'a str,
+    /// Receives one entry per applied redaction when set.
+    pub audit_log: Option<&'a mut AuditLog>,
+    /// 1-based line number of the content's first line within its source. When set,
+    /// matches carry the `line_number` they start on.
+    pub first_line: Option<u64>,
+    /// Receives a copy of every match the call redacts, with offsets into the
+    /// content as given, when set.
+    pub applied_matches: Option<&'a mut Vec<RedactionMatch>>,
+}
+
+impl<'a> SanitizeContext<'a> {
+    pub fn new(source_id: &'a str) -> Self {
+        Self { source_id, ..Default::default() }
+    }
+
+    pub fn with_run_id(mut self, run_id: &'a str) -> Self {
+        self.run_id = run_id;
+        self
+    }
+
+    pub fn with_input_hash(mut self, input_hash: &'a str) -> Self {
+        self.input_hash = input_hash;
+        self
+    }
+
+    pub fn with_user_id(mut self, user_id: &'a str) -> Self {
+        self.user_id = user_id;
+        self
+    }
+
+    pub fn with_reason(mut self, reason: &'a str) -> Self {
+        self.reason = reason;
+        self
+    }
+
+    pub fn with_outcome(mut self, outcome: &'a str) -> Self {
+        self.outcome = outcome;
+        self
+    }
+
+    pub fn with_audit_log(mut self, audit_log: &'a mut AuditLog) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    pub fn with_first_line(mut self, first_line: u64) -> Self {
+        self.first_line = Some(first_line);
+        self
+    }
+
+    pub fn with_applied_matches(mut self, applied_matches: &'a mut Vec<RedactionMatch>) -> Self {
+        self.applied_matches = Some(applied_matches);
+        self
+    }
+
+    /// The same description without the audit log or match sink, for a pass whose
+    /// redactions are recorded elsewhere.
+    pub fn without_audit_log(&self) -> SanitizeContext<'a> {
+        SanitizeContext { audit_log: None, applied_matches: None, ..*self }
+    }
+
+    /// The same description and audit log, with applied matches sent to `applied`.
+    pub(crate) fn collecting_into<'b>(&'b mut self, applied: &'b mut Vec<RedactionMatch>) -> SanitizeContext<'b> {
+        let described = self.without_audit_log();
+        SanitizeContext { audit_log: self.audit_log.as_deref_mut(), applied_matches: Some(applied), ..described }
+    }
+
+    /// Appends an entry for `m`, which replaced the bytes `span` of the content, to
+    /// the audit log when one is set. A failed append does not fail the call.
+    pub(crate) fn log_redaction(&mut self, m: &RedactionMatch, span: Range<usize>) {
+        if let Some(log) = self.audit_log.as_mut() {
+            let _ = log.append(&RedactionLog {
+                timestamp: m.timestamp.clone().unwrap_or_default(),
+                run_id: self.run_id.to_string(), file_path: self.source_id.to_string(),
+                user_id: self.user_id.to_string(), reason_for_redaction: self.reason.to_string(),
+                redaction_outcome: self.outcome.to_string(), rule_name: m.rule_name.clone(),
+                input_hash: self.input_hash.to_string(), match_hash: m.sample_hash.clone().unwrap_or_default(),
+                start: offset_from_index(span.start), end: offset_from_index(span.end),
+            });
+        }
+    }
+
+    /// Writes the audit log's batch, as engines do at the end of each call.
+    pub(crate) fn flush_audit_log(&mut self) -> Result<()> {
+        match self.audit_log.as_mut() {
+            Some(log) => log.flush(),
+            None => Ok(()),
+        }
+    }
+}
 
 /// A trait that defines the core functionality of a sanitization engine.
 ///
@@ -32,13 +154,14 @@ pub trait SanitizationEngine: Send + Sync {
     ///
     /// # Arguments
     /// * `content` - The input string to sanitize.
-    /// * `source_id` - The name or identifier of the source being processed.
-    /// * `run_id` - A unique identifier for the current sanitization run.
-    /// * `input_hash` - A hash of the original input content for integrity checks.
-    /// * `user_id` - The user ID associated with the sanitization run.
-    /// * `reason` - The reason for the sanitization.
-    /// * `outcome` - The outcome of the sanitization.
-    /// * `audit_log` - An optional mutable reference to an `AuditLog` instance for logging events.
+    /// * `ctx` - Where the content came from and why it is being sanitized, plus
+    ///   the audit log (if any) that should record each redaction.
+    fn sanitize_with(&self, content: &str, ctx: &mut SanitizeContext<'_>) -> Result<(String, Vec<RedactionSummaryItem>)>;
+
+    /// The positional form of [`sanitize_with`](Self::sanitize_with), kept so
+    /// existing callers keep compiling while they migrate.
+    #[deprecated(since = "0.1.7", note = "build a `SanitizeContext` and call `sanitize_with` instead")]
+    #[allow(clippy::too_many_arguments)]
     fn sanitize(
         &self,
         content: &str,
@@ -49,7 +172,20 @@ pub trait SanitizationEngine: Send + Sync {
         reason: &str,
         outcome: &str,
         audit_log: Option<&mut AuditLog>,
-    ) -> Result<(String, Vec<RedactionSummaryItem>)>;
+    ) -> Result<(String, Vec<RedactionSummaryItem>)> {
+        let mut ctx = SanitizeContext {
+            source_id,
+            run_id,
+            input_hash,
+            user_id,
+            reason,
+            outcome,
+            audit_log,
+            first_line: None,
+            applied_matches: None,
+        };
+        self.sanitize_with(content, &mut ctx)
+    }
 
     /// Analyzes the provided content for sensitive data without performing redaction.
     ///
@@ -71,8 +207,9 @@ pub trait SanitizationEngine: Send + Sync {
     /// * `source_id` - An identifier for the source of the content (e.g., a file path).
     fn find_matches_for_ui(&self, content: &str, source_id: &str) -> Result<Vec<RedactionMatch>>;
 
-    /// Returns the statistical "heat" (entropy) for each character in the input.
-    /// This allows the UI to render heatmaps via dependency inversion.
+    /// Returns the "heat" of each byte of `content`: entropy for the entropy
+    /// engine, match severity for the regex engine. Bytes of ANSI escapes score
+    /// zero. This allows the UI to render heatmaps via dependency inversion.
     fn get_heat_scores(&self, content: &str) -> Vec<f64>;
 
     /// Returns a reference to the `CompiledRules` used by the engine.
@@ -82,13 +219,41 @@ pub trait SanitizationEngine: Send + Sync {
     /// to recompile them.
     fn compiled_rules(&self) -> &CompiledRules;
 
-    /// Returns a reference to the engine's configuration.
-    fn get_rules(&self) -> &RedactionConfig;
+    /// Returns the engine's configuration, with any entropy threshold tuned since
+    /// it was built.
+    fn get_rules(&self) -> Arc<RedactionConfig>;
 
     /// Returns a reference to the engine's options.
     fn get_options(&self) -> &EngineOptions;
 
-    /// Sets the remediation channel for the self-healing orchestrator.
-    /// This enables v0.2.0 "Tee-Logic" where matches are sent asynchronously for healing.
-    fn set_remediation_tx(&mut self, tx: mpsc::Sender<RedactionMatch>);
+    /// Returns a copy of the engine's runtime counters: per-rule match counts and
+    /// time, bytes and lines scanned, and remediation channel sends. Engines that
+    /// keep no counters report none.
+    fn metrics(&self) -> EngineMetricsSnapshot {
+        EngineMetricsSnapshot::default()
+    }
+
+    /// Returns the active entropy confidence threshold, or `None` for engines
+    /// that do not use one.
+    fn entropy_threshold(&self) -> Option<f64> {
+        None
+    }
+
+    /// Adjusts the entropy confidence threshold in place. Returns `false` if the
+    /// engine has no entropy stage, in which case nothing changes.
+    ///
+    /// Takes `&self` so a threshold can be tuned on an engine shared through an
+    /// [`EngineHandle`](crate::engine_handle::EngineHandle).
+    fn set_entropy_threshold(&self, _threshold: f64) -> bool {
+        false
+    }
+
+    /// Adds fingerprints of secrets already remediated elsewhere, as synced from
+    /// the global vault. Matches on them are not teed to the remediation channel
+    /// again. Engines without a fingerprint cache ignore them.
+    fn update_fingerprints(&self, _fingerprints: Vec<SecretFingerprint>) {}
+
+    /// Swaps in a memory-mapped cache of the full vault, replacing fingerprints
+    /// added through `update_fingerprints`. Engines without a fingerprint cache ignore it.
+    fn set_fingerprint_cache(&self, _cache: Arc<FingerprintCache>) {}
 }
\ No newline at end of file
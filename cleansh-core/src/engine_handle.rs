@@ -0,0 +1,56 @@
+// cleansh-core/src/engine_handle.rs
+//! A cheaply clonable, swappable reference to the active sanitization engine.
+//!
+//! Engines are immutable once built (runtime tuning such as the entropy threshold
+//! goes through `&self` methods), so the TUI, background tasks, and the
+//! orchestrator's sync loop can all share one [`EngineHandle`]. Replacing the
+//! engine is a single atomic pointer swap: callers that loaded the old engine
+//! finish their work on it, and every later load sees the new one.
+
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+
+use crate::engine::SanitizationEngine;
+
+/// Shared handle to the current engine. Clones refer to the same slot.
+#[derive(Clone)]
+pub struct EngineHandle {
+    current: Arc<ArcSwap<Arc<dyn SanitizationEngine>>>,
+}
+
+impl EngineHandle {
+    pub fn new(engine: Arc<dyn SanitizationEngine>) -> Self {
+        Self { current: Arc::new(ArcSwap::from_pointee(engine)) }
+    }
+
+    /// Wraps a freshly built engine.
+    pub fn from_engine<E: SanitizationEngine + 'static>(engine: E) -> Self {
+        Self::new(Arc::new(engine))
+    }
+
+    /// Returns the current engine. The returned `Arc` stays valid, and keeps
+    /// pointing at the same engine, across later swaps.
+    pub fn load(&self) -> Arc<dyn SanitizationEngine> {
+        Arc::clone(&self.current.load())
+    }
+
+    /// Runs `f` against the current engine. Everything inside `f` sees the same
+    /// engine even if another task swaps it concurrently.
+    pub fn with<R>(&self, f: impl FnOnce(&dyn SanitizationEngine) -> R) -> R {
+        let engine = self.current.load();
+        f(engine.as_ref().as_ref())
+    }
+
+    /// Installs `engine` and returns the one it replaced.
+    pub fn swap(&self, engine: Arc<dyn SanitizationEngine>) -> Arc<dyn SanitizationEngine> {
+        let previous = self.current.swap(Arc::new(engine));
+        Arc::clone(&previous)
+    }
+}
+
+impl std::fmt::Debug for EngineHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EngineHandle").finish_non_exhaustive()
+    }
+}
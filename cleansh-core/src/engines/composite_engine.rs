@@ -0,0 +1,274 @@
+// cleansh-core/src/engines/composite_engine.rs
+//! A `SanitizationEngine` that runs the regex and entropy engines together.
+//!
+//! Regex matches are cross-checked against the entropy heat map: a high-entropy
+//! span corroborates the pattern and upgrades the match's confidence, while a
+//! near-zero span (e.g. `ghp_xxxxxxxx...`) marks a likely placeholder and, for
+//! rules with `entropy_corroboration` enabled, downgrades it to `Low` so the
+//! remediation orchestrator leaves it alone. Entropy findings that no regex
+//! rule covers are reported alongside.
+
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::Result;
+use strip_ansi_escapes::strip;
+use tokio::sync::mpsc;
+
+use crate::config::{RedactionConfig, RedactionSummaryItem};
+use crate::engine::{SanitizationEngine, SanitizeContext};
+use crate::engines::entropy_engine::EntropyEngine;
+use crate::engines::index_map::{line_number_at, replacement_for};
+use crate::engines::match_context::{attach_context, match_masks};
+use crate::engines::regex_engine::RegexEngine;
+use crate::metrics::{EngineMetrics, EngineMetricsSnapshot};
+use crate::profiles::EngineOptions;
+use crate::remediation::channel::RemediationTx;
+use crate::remediation::fingerprint::SecretFingerprint;
+use crate::remediation::fingerprint_cache::FingerprintCache;
+use crate::redaction_match::{offset_from_index, OffsetBasis, RedactionMatch};
+use crate::sanitizers::compiler::CompiledRules;
+
+/// Mean heat (bits per byte over the 9-byte heat window) at or above which a
+/// regex match counts as corroborated.
+pub const CORROBORATION_HIGH_HEAT: f64 = 2.5;
+
+/// Mean heat at or below which a regex match is treated as a placeholder.
+pub const CORROBORATION_LOW_HEAT: f64 = 1.0;
+
+/// Confidence assigned to corroborated matches; maps to `ConfidenceLevel::High`.
+pub const CORROBORATED_CONFIDENCE: f64 = 1.0;
+
+/// Confidence assigned to downgraded placeholders; maps to `ConfidenceLevel::Low`.
+pub const UNCORROBORATED_CONFIDENCE: f64 = 0.2;
+
+#[derive(Debug)]
+pub struct CompositeEngine {
+    regex: RegexEngine,
+    entropy: EntropyEngine,
+    remediation_tx: Option<RemediationTx>,
+    /// Scan volume and remediation sends; rule counters live in the inner engines.
+    metrics: EngineMetrics,
+}
+
+impl CompositeEngine {
+    pub fn new(config: RedactionConfig) -> Result<Self> {
+        Self::with_options(config, EngineOptions::default())
+    }
+
+    pub fn with_options(config: RedactionConfig, options: EngineOptions) -> Result<Self> {
+        Ok(Self {
+            regex: RegexEngine::with_options(config.clone(), options.clone())?,
+            entropy: EntropyEngine::with_options(config, options)?,
+            remediation_tx: None,
+            metrics: EngineMetrics::new(std::iter::empty::<String>()),
+        })
+    }
+
+    /// Tees every detected match, after corroboration, to the self-healing
+    /// orchestrator's channel. The inner engines are never wired, so nothing is sent twice.
+    pub fn with_remediation_tx(mut self, tx: mpsc::Sender<RedactionMatch>) -> Self {
+        self.remediation_tx = Some(RemediationTx::new(tx, self.regex.get_options().remediation_tx_mode));
+        self
+    }
+
+    /// Sets the organization salt vault fingerprints are computed with. Known leaks
+    /// are tracked by the entropy stage and checked here before teeing.
+    pub fn with_fingerprint_salt(mut self, salt: Vec<u8>) -> Self {
+        self.entropy = self.entropy.with_fingerprint_salt(salt);
+        self
+    }
+
+    /// Regex matches with corroboration applied, followed by entropy matches that
+    /// do not overlap any of them, sorted by start offset.
+    ///
+    /// With `record` the inner engines count the matches kept; without it the pass
+    /// is left out of the metrics, for when `sanitize` scans the same input again.
+    fn detect(&self, content: &str, source_id: &str, first_line: Option<u64>, record: bool) -> Result<Vec<RedactionMatch>> {
+        let heat = self.entropy.stripped_heat_scores(content);
+        let mut matches = self.regex.find_numbered_matches(content, source_id, first_line, record)?;
+        for m in &mut matches {
+            corroborate(m, &heat);
+        }
+
+        let started = Instant::now();
+        let entropy_only: Vec<RedactionMatch> = self
+            .entropy
+            .find_numbered_matches(content, source_id, first_line, false)
+            .into_iter()
+            .filter(|e| !matches.iter().any(|r| e.start < r.end && r.start < e.end))
+            .collect();
+        if record {
+            self.entropy.record_matches(entropy_only.len() as u64, started.elapsed());
+        }
+        matches.extend(entropy_only);
+        matches.sort_by_key(|m| m.start);
+        if self.capture_context() {
+            let masks = match_masks(&matches);
+            attach_context(&mut matches, &stripped_text(content), masks);
+        }
+        Ok(matches)
+    }
+
+    fn capture_context(&self) -> bool {
+        self.regex.get_options().capture_context
+    }
+}
+
+fn stripped_text(content: &str) -> String {
+    String::from_utf8_lossy(&strip(content.as_bytes())).into_owned()
+}
+
+/// Attaches the mean heat over the match span and adjusts its confidence.
+///
+/// `heat` holds one entropy score per byte of the ANSI-stripped content, indexed
+/// like the match offsets. For content without escapes that is exactly what
+/// `EntropyEngine::get_heat_scores` returns.
+pub fn corroborate(m: &mut RedactionMatch, heat: &[f64]) {
+    let span = m.span();
+    let start = span.start.min(heat.len());
+    let end = span.end.min(heat.len());
+    if start >= end {
+        return;
+    }
+    let mean = heat[start..end].iter().sum::<f64>() / (end - start) as f64;
+    m.corroborating_entropy = Some(mean);
+
+    if mean >= CORROBORATION_HIGH_HEAT {
+        m.confidence = Some(m.confidence.unwrap_or(0.0).max(CORROBORATED_CONFIDENCE));
+    } else if mean <= CORROBORATION_LOW_HEAT && m.rule.entropy_corroboration {
+        m.confidence = Some(UNCORROBORATED_CONFIDENCE);
+    }
+}
+
+/// The bytes of `content` each applied regex match replaced, with the length of
+/// what was written in their place (see `replacement_for`), in order.
+fn regex_edits(applied: &[RedactionMatch], content: &str) -> Vec<(Range<usize>, usize)> {
+    let mut last_end = 0;
+    applied
+        .iter()
+        .map(|m| {
+            let span = m.document_span();
+            let start = span.start.max(last_end);
+            last_end = span.end;
+            (start..span.end, replacement_for(content, start..span.end, &m.sanitized_string).len())
+        })
+        .collect()
+}
+
+/// The input byte that byte `index` of the regex pass output came from. Indices
+/// inside a replacement map to the start (or, for an `end`, the end) of what it replaced.
+fn input_index(index: usize, edits: &[(Range<usize>, usize)], is_end: bool) -> usize {
+    let (mut input, mut output) = (0, 0);
+    for (replaced, len) in edits {
+        let output_start = output + (replaced.start - input);
+        if index < output_start || (is_end && index == output_start) {
+            break;
+        }
+        if index < output_start + len || (is_end && index == output_start + len) {
+            return if is_end { replaced.end } else { replaced.start };
+        }
+        input = replaced.end;
+        output = output_start + len;
+    }
+    input + (index - output)
+}
+
+/// Re-anchors a match the entropy pass found in the regex pass output onto `content`.
+fn move_onto_input(m: &mut RedactionMatch, edits: &[(Range<usize>, usize)], content: &str, first_line: Option<u64>) {
+    let span = m.document_span();
+    let start = input_index(span.start, edits, false);
+    let end = input_index(span.end, edits, true);
+    m.start = offset_from_index(start);
+    m.end = offset_from_index(end);
+    m.offset_basis = OffsetBasis::Original;
+    m.original_start = Some(m.start);
+    m.original_end = Some(m.end);
+    m.line_number = first_line.map(|first| line_number_at(content, start, first));
+}
+
+impl SanitizationEngine for CompositeEngine {
+    fn sanitize_with(&self, content: &str, ctx: &mut SanitizeContext<'_>) -> Result<(String, Vec<RedactionSummaryItem>)> {
+        self.metrics.record_scan(content.as_bytes());
+        let needs_detect = self.remediation_tx.is_some() || (self.capture_context() && ctx.applied_matches.is_some());
+        let detected = if needs_detect { self.detect(content, ctx.source_id, ctx.first_line, false)? } else { Vec::new() };
+        if let Some(tx) = &self.remediation_tx {
+            for m in &detected {
+                if !self.entropy.is_known_leak(&m.original_string) {
+                    tx.send(m.clone(), &self.metrics);
+                }
+            }
+        }
+
+        // Entropy matches are found in the regex pass output; placing them on the
+        // input, for the caller or the audit log, needs the regex pass's matches.
+        let track = ctx.applied_matches.is_some() || ctx.audit_log.is_some();
+        let mut applied = Vec::new();
+        let (after_regex, mut summary) = if track {
+            self.regex.sanitize_with(content, &mut ctx.collecting_into(&mut applied))?
+        } else {
+            self.regex.sanitize_with(content, ctx)?
+        };
+        // The regex pass only scrubbed its matches' context of regex secrets; the
+        // entropy pass runs on its output, so its own context needs nothing more.
+        if ctx.applied_matches.is_some() && self.capture_context() {
+            attach_context(&mut applied, &stripped_text(content), match_masks(&detected));
+        }
+        let mut entropy_matches = Vec::new();
+        let mut entropy_ctx = ctx.without_audit_log();
+        if track {
+            entropy_ctx = entropy_ctx.with_applied_matches(&mut entropy_matches);
+        }
+        let (sanitized, entropy_summary) = self.entropy.sanitize_with(&after_regex, &mut entropy_ctx)?;
+        summary.extend(entropy_summary);
+
+        if track {
+            let edits = regex_edits(&applied, content);
+            for mut m in entropy_matches {
+                move_onto_input(&mut m, &edits, content, ctx.first_line);
+                ctx.log_redaction(&m, m.document_span());
+                applied.push(m);
+            }
+            ctx.flush_audit_log()?;
+        }
+        if let Some(out) = ctx.applied_matches.as_mut() {
+            applied.sort_by_key(|m| m.document_span().start);
+            out.extend(applied);
+        }
+        Ok((sanitized, summary))
+    }
+
+    fn analyze_for_stats(&self, content: &str, source_id: &str) -> Result<Vec<RedactionSummaryItem>> {
+        self.metrics.record_scan(content.as_bytes());
+        let (after_regex, mut summary) = self.regex.sanitize_with(content, &mut SanitizeContext::new(source_id))?;
+        summary.extend(self.entropy.analyze_for_stats(&after_regex, source_id)?);
+        Ok(summary)
+    }
+
+    fn find_matches_for_ui(&self, content: &str, source_id: &str) -> Result<Vec<RedactionMatch>> {
+        self.metrics.record_scan(content.as_bytes());
+        self.detect(content, source_id, None, true)
+    }
+
+    fn get_heat_scores(&self, content: &str) -> Vec<f64> {
+        self.entropy.get_heat_scores(content)
+    }
+
+    fn compiled_rules(&self) -> &CompiledRules { self.regex.compiled_rules() }
+    fn get_rules(&self) -> Arc<RedactionConfig> { self.entropy.get_rules() }
+    fn get_options(&self) -> &EngineOptions { self.regex.get_options() }
+
+    /// Scan volume is counted once per call; rule counters are the inner engines',
+    /// which count each match once: the entropy pass only sees what regex left.
+    fn metrics(&self) -> EngineMetricsSnapshot {
+        let mut snapshot = self.metrics.snapshot();
+        snapshot.rules.extend(self.regex.metrics().rules);
+        snapshot.rules.extend(self.entropy.metrics().rules);
+        snapshot
+    }
+    fn entropy_threshold(&self) -> Option<f64> { self.entropy.entropy_threshold() }
+    fn set_entropy_threshold(&self, threshold: f64) -> bool { self.entropy.set_entropy_threshold(threshold) }
+    fn update_fingerprints(&self, fingerprints: Vec<SecretFingerprint>) { self.entropy.update_fingerprints(fingerprints) }
+    fn set_fingerprint_cache(&self, cache: Arc<FingerprintCache>) { self.entropy.set_fingerprint_cache(cache) }
+}
@@ -1,12 +1,16 @@
 // cleansh-core/src/engines/entropy_engine.rs
 //! A `SanitizationEngine` implementation utilizing Shannon entropy.
 //! Features v0.2.0 Self-Healing Integration and Contiguous Match Merging.
+//! Candidates an active regex rule also matches are reported under that rule.
 //!
 //! FIXED: Implemented 'Look-Ahead Stitcher' to prevent window fractures on long secrets.
 
-use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
-use anyhow::Result;
+use std::collections::HashSet;
+use std::ops::Range;
+use std::sync::{Arc, Mutex, PoisonError, RwLock, RwLockReadGuard};
+use std::time::{Duration, Instant};
+use anyhow::{Context, Result};
+use arc_swap::ArcSwap;
 use strip_ansi_escapes::strip;
 use sha2::{Digest, Sha256};
 use hex;
@@ -14,56 +18,92 @@ use chrono::Utc;
 use tokio::sync::mpsc;
 
 use crate::config::{RedactionConfig, RedactionSummaryItem, RedactionRule};
-use crate::redaction_match::{RedactionMatch, ensure_match_hashes};
-use crate::profiles::EngineOptions;
-use crate::engine::SanitizationEngine;
-use crate::sanitizers::compiler::{get_or_compile_rules, CompiledRules};
+use crate::redaction_match::{RedactionMatch, OffsetBasis, ensure_match_hashes, offset_from_index};
+use crate::profiles::{EngineOptions, format_token, sampled_summary};
+use crate::engine::{SanitizationEngine, SanitizeContext};
+use crate::explain::Decision;
+use crate::engines::index_map::{ceil_char_boundary, floor_char_boundary, replacement_for, sampled_ranges, ScannedText, StrippedIndexMapper};
+use crate::engines::match_context::{attach_context, match_masks, rule_masks};
+use crate::metrics::{EngineMetrics, EngineMetricsSnapshot};
+use crate::sanitizers::compiler::{get_or_compile_rules, CompiledRule, CompiledRules};
+use crate::remediation::channel::RemediationTx;
 use crate::remediation::fingerprint::SecretFingerprint;
-use cleansh_entropy::engine::EntropyEngine as LowLevelEntropyEngine;
+use crate::remediation::fingerprint_cache::{FingerprintCache, KnownLeaks};
+use crate::remediation::state::REVOKED_PLACEHOLDER;
+use cleansh_entropy::context::ContextScanner;
+use cleansh_entropy::scanner::AnomalyScannerConfig;
+use cleansh_entropy::statistics::{TextProfile, PROFILE_SAMPLE_BYTES};
+use cleansh_entropy::engine::{EntropyEngine as LowLevelEntropyEngine, ScanEvent, DEFAULT_MIN_SECRET_LEN, DEFAULT_WINDOW_SIZE};
 
-/// Improved Mapper: Handles ANSI escape offsets to prevent partial redaction.
 #[derive(Debug)]
-struct StrippedIndexMapper {
-    map: Vec<usize>,
+pub struct EntropyEngine {
+    /// Swapped as a whole when the threshold is tuned, so `get_rules` reports it.
+    config: ArcSwap<RedactionConfig>,
+    options: EngineOptions,
+    /// Behind a lock so the threshold can be tuned through a shared engine.
+    inner_engine: RwLock<LowLevelEntropyEngine>,
+    compiled_rules: Arc<CompiledRules>,
+    remediation_tx: Option<RemediationTx>,
+    known_leaks: KnownLeaks,
+    metrics: EngineMetrics,
+    /// `EntropyConfig::max_line_bytes`, resolved.
+    max_line_bytes: usize,
 }
 
-impl StrippedIndexMapper {
-    fn new(original: &str) -> Self {
-        let stripped_bytes = strip(original.as_bytes());
-        let stripped_str = String::from_utf8_lossy(&stripped_bytes);
-        
-        let mut map: Vec<usize> = Vec::with_capacity(stripped_str.len() + 1);
-        let mut orig_char_indices = original.char_indices().peekable();
-        
-        for stripped_char in stripped_str.chars() {
-            while let Some(&(orig_index, orig_char)) = orig_char_indices.peek() {
-                if orig_char == stripped_char {
-                    map.push(orig_index);
-                    let _ = orig_char_indices.next();
-                    break;
-                } else {
-                    let _ = orig_char_indices.next();
-                }
-            }
-        }
-        map.push(original.len());
-        Self { map }
-    }
+/// The script profile of a stream of inputs from one document, such as the lines
+/// of a log fed to the TUI or `watch`. Profiling each line on its own would let a
+/// short ASCII line move a Cyrillic log onto Latin scoring, so the profile is
+/// detected over everything seen until `PROFILE_SAMPLE_BYTES` are sampled, and then
+/// kept. Clones share the profile.
+#[derive(Debug, Clone, Default)]
+pub struct StreamProfile {
+    state: Arc<Mutex<StreamSample>>,
+}
 
-    fn map_index(&self, stripped_index: usize) -> usize {
-        let idx = stripped_index.min(self.map.len().saturating_sub(1));
-        self.map[idx]
+#[derive(Debug, Default)]
+struct StreamSample {
+    sample: Vec<u8>,
+    /// Set once the sample is full.
+    profile: Option<TextProfile>,
+}
+
+impl StreamProfile {
+    /// Adds the head of `input` to the sample while it is not full, and returns the
+    /// profile to score `input` against.
+    pub fn observe(&self, input: &[u8]) -> TextProfile {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(profile) = state.profile {
+            return profile;
+        }
+        let room = PROFILE_SAMPLE_BYTES - state.sample.len();
+        state.sample.extend_from_slice(&input[..input.len().min(room)]);
+        let profile = TextProfile::detect(&state.sample);
+        if state.sample.len() < PROFILE_SAMPLE_BYTES {
+            state.sample.push(b'\n');
+        } else {
+            state.profile = Some(profile);
+            state.sample = Vec::new();
+        }
+        profile
     }
 }
 
-#[derive(Debug)]
-pub struct EntropyEngine {
-    config: RedactionConfig,
-    options: EngineOptions,
-    inner_engine: LowLevelEntropyEngine,
-    compiled_rules: Arc<CompiledRules>,
-    remediation_tx: Option<mpsc::Sender<RedactionMatch>>,
-    fingerprint_cache: HashSet<String>,
+/// Name of the pseudo-rule the entropy scanner reports under when no regex rule
+/// confirms a candidate.
+const ENTROPY_RULE_NAME: &str = "high_entropy_secret";
+
+/// Confidence of a candidate that a regex rule also matches exactly (and whose
+/// validator, if any, passed); maps to `ConfidenceLevel::High`.
+pub const RULE_CONFIRMED_CONFIDENCE: f64 = 1.0;
+
+/// The pseudo-rule of candidates no regex rule confirms.
+fn entropy_rule() -> RedactionRule {
+    RedactionRule {
+        name: ENTROPY_RULE_NAME.to_string(),
+        replace_with: "[ENTROPY_REDACTED]".to_string(),
+        pattern_type: "entropy".to_string(),
+        ..Default::default()
+    }
 }
 
 impl EntropyEngine {
@@ -73,95 +113,365 @@ impl EntropyEngine {
 
     pub fn with_options(config: RedactionConfig, options: EngineOptions) -> Result<Self> {
         let threshold = config.engines.entropy.threshold.unwrap_or(0.5);
-        let window_size = config.engines.entropy.window_size.unwrap_or(24);
-        let inner_engine = LowLevelEntropyEngine::new(threshold, window_size);
+        let window_size = config.engines.entropy.window_size.unwrap_or(DEFAULT_WINDOW_SIZE);
+        let structural_scoring = config.engines.entropy.structural_scoring.unwrap_or(true);
+        let min_secret_len = config.engines.entropy.min_secret_len.unwrap_or(DEFAULT_MIN_SECRET_LEN);
+        let long_secret_policy = config.engines.entropy.long_secret_policy.unwrap_or_default();
+        let mut inner_engine = LowLevelEntropyEngine::new(threshold, window_size)
+            .with_structural_scoring(structural_scoring)
+            .with_secret_len(min_secret_len, config.engines.entropy.max_secret_len)
+            .with_long_secret_policy(long_secret_policy.into());
+        let scanner_defaults = AnomalyScannerConfig::default();
+        inner_engine = inner_engine.with_baseline_fallback(
+            config.engines.entropy.min_baseline_samples.unwrap_or(scanner_defaults.min_baseline_samples),
+            config.engines.entropy.absolute_entropy_floor.unwrap_or(scanner_defaults.absolute_entropy_floor),
+        );
+        if let Some(adjustments) = &config.engines.entropy.language_adjustments {
+            inner_engine = inner_engine.with_language_adjustments(adjustments.resolve());
+        }
+        let keywords = config.engines.entropy.resolved_context_keywords();
+        let negative_keywords = config.engines.entropy.resolved_negative_context_keywords();
+        if keywords.is_some() || negative_keywords.is_some() {
+            let mut scanner = match &keywords {
+                Some(keywords) => ContextScanner::with_keywords(&keywords.iter().map(String::as_str).collect::<Vec<_>>()),
+                None => ContextScanner::new(),
+            };
+            if let Some(negative) = &negative_keywords {
+                scanner = scanner.with_negative_keywords(&negative.iter().map(String::as_str).collect::<Vec<_>>());
+            }
+            inner_engine = inner_engine.with_context_scanner(scanner);
+        }
+        if let Some(weight) = config.engines.entropy.negative_keyword_weight {
+            inner_engine = inner_engine.with_negative_keyword_weight(weight);
+        }
         let compiled_rules = get_or_compile_rules(&config)?;
+        let max_line_bytes = config.engines.entropy.resolved_max_line_bytes();
+        if let Some(pp) = options.post_processing.as_ref().filter(|pp| pp.replace_with_token) {
+            format_token(pp.effective_token_format(), "rule", "")
+                .context("Invalid post_processing.token_format")?;
+        }
         Ok(Self { 
-            config, 
+            config: ArcSwap::from_pointee(config), 
             options, 
-            inner_engine, 
+            inner_engine: RwLock::new(inner_engine), 
             compiled_rules, 
             remediation_tx: None, 
-            fingerprint_cache: HashSet::new() 
+            known_leaks: KnownLeaks::default(),
+            metrics: EngineMetrics::new([ENTROPY_RULE_NAME]),
+            max_line_bytes,
         })
     }
 
-    pub fn update_fingerprints(&mut self, fingerprints: Vec<SecretFingerprint>) {
-        for fp in fingerprints { 
-            self.fingerprint_cache.insert(fp.hash); 
+    /// Tees every detected match to the self-healing orchestrator's channel. When it
+    /// is full, `EngineOptions::remediation_tx_mode` decides what happens; set the
+    /// options first.
+    pub fn with_remediation_tx(mut self, tx: mpsc::Sender<RedactionMatch>) -> Self {
+        self.remediation_tx = Some(RemediationTx::new(tx, self.options.remediation_tx_mode));
+        self
+    }
+
+    /// Sets the organization salt vault fingerprints are computed with, so synced
+    /// fingerprints can be matched against detected secrets.
+    pub fn with_fingerprint_salt(mut self, salt: Vec<u8>) -> Self {
+        self.known_leaks = KnownLeaks::new(salt);
+        self
+    }
+
+    /// Whether `secret` is in the synced fingerprint set, i.e. already remediated elsewhere.
+    pub fn is_known_leak(&self, secret: &str) -> bool {
+        self.known_leaks.contains_secret(secret)
+    }
+
+    fn scanner(&self) -> RwLockReadGuard<'_, LowLevelEntropyEngine> {
+        self.inner_engine.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the confidence threshold used by the statistical scanner.
+    pub fn threshold(&self) -> f64 {
+        self.scanner().threshold()
+    }
+
+    /// Updates the detection threshold without recompiling any rules, and records
+    /// it in the engine's config.
+    ///
+    /// Scans already in progress finish with the old value.
+    pub fn set_threshold(&self, threshold: f64) {
+        self.inner_engine.write().unwrap_or_else(PoisonError::into_inner).set_threshold(threshold);
+        self.config.rcu(|config| {
+            let mut config = RedactionConfig::clone(config);
+            config.engines.entropy.threshold = Some(threshold);
+            config
+        });
+    }
+
+    pub fn update_fingerprints(&self, fingerprints: Vec<SecretFingerprint>) {
+        self.known_leaks.extend(fingerprints);
+    }
+
+    pub fn set_fingerprint_cache(&self, cache: Arc<FingerprintCache>) {
+        self.known_leaks.replace_cache(cache);
+    }
+
+    /// Shannon entropy of the 9-byte window around each byte of the ANSI-stripped
+    /// input, indexed like match offsets. `get_heat_scores` projects these onto the
+    /// original content. Inputs longer than `max_line_bytes` get no scores.
+    pub(crate) fn stripped_heat_scores(&self, content: &str) -> Vec<f64> {
+        if content.len() > self.max_line_bytes {
+            return Vec::new();
         }
+        let stripped_bytes = strip(content.as_bytes());
+        (0..stripped_bytes.len())
+            .map(|i| {
+                let start = i.saturating_sub(4);
+                let end = std::cmp::min(stripped_bytes.len(), i + 5);
+                cleansh_entropy::entropy::calculate_shannon_entropy(&stripped_bytes[start..end])
+            })
+            .collect()
+    }
+
+    /// The regex rule that confirms the candidate `start..end`: among the active
+    /// rules with a match on the candidate's line whose redacted part covers it,
+    /// the first declared whose programmatic validator accepts that part. Returns
+    /// the rule, the span it redacts (which may extend past the trimmed
+    /// candidate, e.g. to a `ghp_` prefix), and the replacement.
+    fn confirming_rule(&self, stripped: &str, start: usize, end: usize, source_id: &str) -> Option<(RedactionRule, Range<usize>, String)> {
+        let line_start = stripped[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = stripped[end..].find('\n').map_or(stripped.len(), |i| end + i);
+        let line = &stripped[line_start..line_end];
+        let candidate = (start - line_start)..(end - line_start);
+        let config = self.config.load();
+        let mut confirmed: Vec<(usize, &RedactionRule, &CompiledRule, Range<usize>, String)> = self.compiled_rules.candidates(line)
+            .into_iter()
+            .filter_map(|index| {
+                let compiled_rule = &self.compiled_rules.rules[index];
+                let (rank, rule) = config.rules.iter().enumerate().find(|(_, rule)| rule.name == compiled_rule.name)?;
+                let (span, replacement) = compiled_rule.covering_match(line, candidate.clone())?;
+                Some((rank, rule, compiled_rule, (span.start + line_start)..(span.end + line_start), replacement))
+            })
+            .collect();
+        confirmed.sort_by_key(|(rank, ..)| *rank);
+        confirmed.into_iter().find_map(|(_, rule, compiled_rule, span, replacement)| {
+            let passed = compiled_rule.passes_validation(&stripped[span.clone()]);
+            if compiled_rule.programmatic_validation {
+                self.options.explain(source_id, span.start, span.end, || Decision::Validator { rule_name: rule.name.clone(), passed });
+            }
+            passed.then(|| (rule.clone(), span, replacement))
+        })
     }
 
-    fn create_redaction_match(&self, original: &str, start: u64, end: u64, source_id: &str) -> RedactionMatch {
+    fn create_redaction_match(&self, start: usize, end: usize, text: &ScannedText<'_>, source_id: &str, rule: RedactionRule, replacement: String) -> RedactionMatch {
+        let original = &text.stripped[start..end];
+        let original_span = text.original_span(start, end);
         let mut sample_hash = None;
-        if self.options.post_processing.as_ref().map_or(false, |pp| pp.replace_with_token) {
+        let mut match_context_hash = None;
+        let dedupe = self.options.dedupe_config.as_ref().filter(|dedupe| dedupe.use_hash);
+        if self.options.post_processing.as_ref().is_some_and(|pp| pp.replace_with_token) || dedupe.is_some() {
             let mut hasher = Sha256::new();
             hasher.update(original.as_bytes());
             sample_hash = Some(hex::encode(hasher.finalize()));
         }
-        let rule = RedactionRule {
-            name: "high_entropy_secret".to_string(),
-            replace_with: "[ENTROPY_REDACTED]".to_string(),
-            pattern_type: "entropy".to_string(),
-            ..Default::default()
+        if let Some(dedupe) = dedupe {
+            let ctx_start = original_span.start.saturating_sub(dedupe.window_bytes);
+            let ctx_end = std::cmp::min(text.original.len(), original_span.end.saturating_add(dedupe.window_bytes));
+            let mut hasher = Sha256::new();
+            hasher.update(&text.original.as_bytes()[ctx_start..ctx_end]);
+            match_context_hash = Some(hex::encode(hasher.finalize()));
+        }
+        // Deterministic tokenization: the same secret always renders to the same token,
+        // unless remediation has revoked it.
+        let sanitized_string = match (self.options.post_processing.as_ref(), sample_hash.as_deref()) {
+            _ if self.options.remediation_state.is_revoked(original) => REVOKED_PLACEHOLDER.to_string(),
+            (Some(pp), Some(hash)) if pp.replace_with_token => {
+                format_token(pp.effective_token_format(), &rule.name, &pp.token_hash(hash)).unwrap_or(replacement)
+            }
+            _ => replacement,
         };
         RedactionMatch {
             rule_name: rule.name.clone(), 
             original_string: original.to_string(),
-            sanitized_string: rule.replace_with.clone(), 
-            start, 
-            end, 
+            sanitized_string, 
+            start: offset_from_index(start), 
+            end: offset_from_index(end), 
+            offset_basis: OffsetBasis::Stripped,
+            original_start: Some(offset_from_index(original_span.start)),
+            original_end: Some(offset_from_index(original_span.end)),
             sample_hash,
-            match_context_hash: None, 
+            match_context_hash, 
             timestamp: Some(Utc::now().to_rfc3339()), 
             rule,
             source_id: source_id.to_string(), 
-            line_number: None,
+            line_number: text.line_number(original_span.start),
+            confidence: None,
+            entropy: None,
+            corroborating_entropy: None,
+            context_before: None,
+            context_after: None,
         }
     }
 
-    fn find_matches_internal(&self, content: &str, source_id: &str) -> Vec<RedactionMatch> {
+    /// Returns `(first_sightings, repeats)`. Repeats are suppressed by `DedupeConfig` and are
+    /// never teed to the remediation channel, but `sanitize` still redacts them.
+    /// `first_line` numbers the lines of `content` for the matches' `line_number`.
+    fn find_matches_internal(&self, content: &str, source_id: &str, first_line: Option<u64>, metrics: &EngineMetrics) -> (Vec<RedactionMatch>, Vec<RedactionMatch>) {
         let stripped_bytes = strip(content.as_bytes());
         let stripped_input = String::from_utf8_lossy(&stripped_bytes);
+        metrics.record_scan(&stripped_bytes);
+        let started = Instant::now();
         
-        let entropy_matches = self.inner_engine.scan(stripped_input.as_bytes());
-        if entropy_matches.is_empty() { return vec![]; }
+        // Oversized lines are sampled: their head and tail are scanned, the middle is not.
+        let ranges = sampled_ranges(&stripped_input, self.max_line_bytes);
+        if ranges.len() > 1 {
+            log::debug!("Entropy scan sampled {} of {} bytes of input with oversized lines.", ranges.iter().map(ExactSizeIterator::len).sum::<usize>(), stripped_input.len());
+        }
+        let stream_profile = self.options.stream_profile.as_ref().map(|stream| stream.observe(stripped_input.as_bytes()));
+        let entropy_matches: Vec<_> = {
+            let scanner = self.scanner();
+            ranges.into_iter()
+                .flat_map(|range| {
+                    let offset = range.start;
+                    let bytes = &stripped_input.as_bytes()[range];
+                    let profile = stream_profile.unwrap_or_else(|| TextProfile::detect(bytes));
+                    let found = if self.options.explain {
+                        scanner.scan_traced_with_profile(bytes, &profile, &mut |event| self.explain_scan_event(event, offset, source_id))
+                    } else {
+                        scanner.scan_with_profile(bytes, &profile)
+                    };
+                    found.into_iter().map(move |mut m| {
+                        m.start += offset;
+                        m.end += offset;
+                        m
+                    })
+                })
+                .collect()
+        };
+        if entropy_matches.is_empty() {
+            metrics.record_rule(0, 0, started.elapsed());
+            return (vec![], vec![]);
+        }
 
         // --- MERGE LOGIC START ---
         let mut sorted_intervals = entropy_matches;
-        sorted_intervals.sort_by(|a, b| a.start.cmp(&b.start));
+        sorted_intervals.sort_by_key(|a| a.start);
 
         let mut merged_intervals = Vec::new();
         let mut current_start = sorted_intervals[0].start;
         let mut current_end = sorted_intervals[0].end;
+        let mut current_confidence = sorted_intervals[0].confidence;
 
         for m in sorted_intervals.into_iter().skip(1) {
             if m.start <= current_end {
                 current_end = std::cmp::max(current_end, m.end);
+                current_confidence = current_confidence.max(m.confidence);
             } else {
-                merged_intervals.push((current_start, current_end));
+                merged_intervals.push((current_start, current_end, current_confidence));
                 current_start = m.start;
                 current_end = m.end;
+                current_confidence = m.confidence;
             }
         }
-        merged_intervals.push((current_start, current_end));
+        merged_intervals.push((current_start, current_end, current_confidence));
         // --- MERGE LOGIC END ---
 
-        merged_intervals.into_iter().map(|(start, end)| {
+        let text = ScannedText::new(content, &stripped_input, first_line);
+
+        let mut unique = Vec::new();
+        let mut repeats = Vec::new();
+        let mut seen: HashSet<String> = HashSet::new();
+        // End of the last reported span; a confirming rule can widen a candidate over the next one.
+        let mut reported_end = 0;
+
+        for (start, end, confidence) in merged_intervals {
             // Apply refined surgical extraction AND Look-Ahead Stitcher
             let (refined_start, refined_end) = self.extract_secret_core_indices(&stripped_input, start, end);
-            
-            let m = self.create_redaction_match(
-                &stripped_input[refined_start..refined_end], 
-                refined_start as u64, 
-                refined_end as u64, 
-                source_id
-            );
-            if let Some(tx) = &self.remediation_tx { 
-                let _ = tx.try_send(m.clone()); 
+            if (refined_start, refined_end) != (start, end) {
+                self.options.explain(source_id, refined_start, refined_end, || Decision::Trimmed { raw_start: start, raw_end: end });
+            }
+            let Some((refined_start, refined_end)) = self.scanner().bound_secret_len(refined_start, refined_end) else {
+                self.options.explain(source_id, refined_start, refined_end, || Decision::LengthRejected { len: refined_end - refined_start });
+                continue;
+            };
+            // A redacted prefix can end mid-character.
+            let refined_end = floor_char_boundary(&stripped_input, refined_end);
+            if refined_start >= refined_end { continue; }
+            // A candidate a regex rule also matches is reported as that rule's.
+            let (rule, refined_start, refined_end, replacement, confidence) = match self.confirming_rule(&stripped_input, refined_start, refined_end, source_id) {
+                Some((rule, span, replacement)) => (rule, span.start, span.end, replacement, confidence.max(RULE_CONFIRMED_CONFIDENCE)),
+                None => {
+                    let rule = entropy_rule();
+                    let replacement = rule.replace_with.clone();
+                    (rule, refined_start, refined_end, replacement, confidence)
+                }
+            };
+            if refined_start < reported_end {
+                continue;
             }
-            m
-        }).collect()
+            if self.options.is_ignored(&rule.name, &stripped_input[refined_start..refined_end]) {
+                self.options.explain(source_id, refined_start, refined_end, || Decision::Ignored { rule_name: rule.name.clone() });
+                continue;
+            }
+            self.options.explain(source_id, refined_start, refined_end, || Decision::Reported { rule_name: rule.name.clone() });
+            reported_end = refined_end;
+
+            let mut m = self.create_redaction_match(refined_start, refined_end, &text, source_id, rule, replacement);
+            m.confidence = Some(confidence);
+            m.entropy = Some(cleansh_entropy::entropy::calculate_shannon_entropy(
+                &stripped_input.as_bytes()[refined_start..refined_end],
+            ));
+            let is_repeat = self.options.dedupe_config.as_ref()
+                .and_then(|dedupe| dedupe.dedupe_key(&m))
+                .is_some_and(|key| !seen.insert(key.to_string()));
+            if is_repeat {
+                repeats.push(m);
+                continue;
+            }
+            let remediated = self.is_known_leak(&m.original_string) || self.options.remediation_state.is_revoked(&m.original_string);
+            if let Some(tx) = self.remediation_tx.as_ref().filter(|_| !remediated) {
+                tx.send(m.clone(), &self.metrics);
+            }
+            unique.push(m);
+        }
+        metrics.record_rule(0, (unique.len() + repeats.len()) as u64, started.elapsed());
+        if self.options.capture_context {
+            let mut masks = rule_masks(&self.compiled_rules, &stripped_input);
+            masks.extend(match_masks(unique.iter().chain(&repeats)));
+            attach_context(unique.iter_mut().chain(&mut repeats), &stripped_input, masks);
+        }
+        (unique, repeats)
+    }
+
+    /// Records a decision of the statistical scanner, whose offsets are relative
+    /// to the scanned range starting at `offset`.
+    fn explain_scan_event(&self, event: ScanEvent, offset: usize, source_id: &str) {
+        match event {
+            ScanEvent::Scored(score) => {
+                self.options.explain(source_id, offset + score.start, offset + score.end, || Decision::from_score(&score));
+            }
+            ScanEvent::LengthRejected { start, end } => {
+                self.options.explain(source_id, offset + start, offset + end, || Decision::LengthRejected { len: end - start });
+            }
+        }
+    }
+
+    /// [`SanitizationEngine::find_matches_for_ui`] with `line_number` set on every
+    /// match when `first_line` is given. Without `record` the pass is left out of
+    /// the engine's metrics.
+    pub(crate) fn find_numbered_matches(&self, content: &str, source_id: &str, first_line: Option<u64>, record: bool) -> Vec<RedactionMatch> {
+        let unrecorded;
+        let metrics = if record {
+            &self.metrics
+        } else {
+            unrecorded = EngineMetrics::new(std::iter::empty::<String>());
+            &unrecorded
+        };
+        let (mut matches, _) = self.find_matches_internal(content, source_id, first_line, metrics);
+        ensure_match_hashes(&mut matches);
+        matches.sort_by_key(|m| m.start);
+        matches
+    }
+
+    /// Counts `matches` found by a pass made without `record`, as one scan.
+    pub(crate) fn record_matches(&self, matches: u64, elapsed: Duration) {
+        self.metrics.record_rule(0, matches, elapsed);
     }
 
     /// Heat-Seeker: Refines the match by anchoring to delimiters.
@@ -183,6 +493,7 @@ impl EntropyEngine {
 
         // 2. Character-Class Trimming (Leading)
         while start < end && (
+            !bytes[start].is_ascii() ||
             bytes[start].is_ascii_whitespace() || 
             matches!(bytes[start], b'"' | b'\'' | b'[' | b'{' | b'<' | b'(' | b'-' | b'_')
         ) {
@@ -196,7 +507,8 @@ impl EntropyEngine {
             let b = bytes[end];
             // Stop ONLY if we hit a hard delimiter or whitespace.
             // If it's alphanumeric, underscore, or common secret chars (+, /, -), we extend.
-            if b.is_ascii_whitespace() || matches!(b, b'"' | b'\'' | b',' | b';' | b']' | b'}' | b')' | b'>') {
+            // Non-ASCII bytes (emoji, CJK) are never part of an encoded secret.
+            if !b.is_ascii() || b.is_ascii_whitespace() || matches!(b, b'"' | b'\'' | b',' | b';' | b']' | b'}' | b')' | b'>') {
                 break;
             }
             end += 1;
@@ -206,7 +518,8 @@ impl EntropyEngine {
         // Backtrack from the new end if we accidentally ate a trailing quote or punctuation.
         while (end - start) > 2 {
             let tail_byte = bytes[end - 1];
-            if tail_byte.is_ascii_whitespace() 
+            if !tail_byte.is_ascii()
+                || tail_byte.is_ascii_whitespace() 
                 || matches!(tail_byte, b'.' | b',' | b'!' | b'?' | b']' | b'}' | b'>' | b')' | b'"' | b'\'' | b';') 
             {
                 end -= 1;
@@ -215,94 +528,70 @@ impl EntropyEngine {
             }
         }
 
-        (start, end)
+        // 5. Windows are byte-aligned; shrink onto whole characters so the match never
+        // splits an emoji or CJK character.
+        let start = ceil_char_boundary(text, start);
+        (start, floor_char_boundary(text, end).max(start))
     }
 }
 
 impl SanitizationEngine for EntropyEngine {
-    fn sanitize(
-        &self, 
-        content: &str, 
-        source_id: &str, 
-        _run_id: &str, 
-        _input_hash: &str, 
-        _user_id: &str, 
-        _reason: &str, 
-        _outcome: &str, 
-        _audit_log: Option<&mut crate::audit_log::AuditLog>
-    ) -> Result<(String, Vec<RedactionSummaryItem>)> {
-        let matches = self.find_matches_internal(content, source_id);
-        let mapper = StrippedIndexMapper::new(content);
+    fn sanitize_with(&self, content: &str, ctx: &mut SanitizeContext<'_>) -> Result<(String, Vec<RedactionSummaryItem>)> {
+        let (matches, repeats) = self.find_matches_internal(content, ctx.source_id, ctx.first_line, &self.metrics);
         let mut sanitized = String::with_capacity(content.len());
         let mut last_end = 0usize;
-        let mut summary_map: HashMap<String, RedactionSummaryItem> = HashMap::new();
-        let mut sorted = matches;
+        let mut summarized = Vec::new();
+        let mut sorted: Vec<(RedactionMatch, bool)> = matches.into_iter().map(|m| (m, false))
+            .chain(repeats.into_iter().map(|m| (m, true)))
+            .collect();
         
-        sorted.sort_by_key(|m| m.start);
+        sorted.sort_by_key(|(m, _)| m.start);
 
-        for m in &sorted {
-            let original_start = mapper.map_index(m.start as usize);
-            let original_end = mapper.map_index(m.end as usize);
+        for (m, is_repeat) in &sorted {
+            let Range { start: original_start, end: original_end } = m.document_span();
             
             if original_end <= last_end { continue; }
             
-            sanitized.push_str(&content[last_end..original_start.max(last_end)]);
-            sanitized.push_str(&m.sanitized_string);
+            let replaced_start = original_start.max(last_end);
+            sanitized.push_str(&content[last_end..replaced_start]);
+            sanitized.push_str(&replacement_for(content, replaced_start..original_end, &m.sanitized_string));
             last_end = original_end;
+            if let Some(applied) = ctx.applied_matches.as_mut() {
+                applied.push(m.clone());
+            }
+            ctx.log_redaction(m, original_start..original_end);
 
-            let entry = summary_map.entry(m.rule_name.clone()).or_insert_with(|| RedactionSummaryItem {
-                rule_name: m.rule_name.clone(), 
-                occurrences: 0, 
-                original_texts: Vec::new(), 
-                sanitized_texts: Vec::new(),
-            });
-            entry.occurrences += 1;
-            entry.original_texts.push(m.original_string.clone());
-            entry.sanitized_texts.push(m.sanitized_string.clone());
+            summarized.push((m, *is_repeat));
         }
         
         sanitized.push_str(&content[last_end..]);
-        Ok((sanitized, summary_map.into_values().collect()))
+        ctx.flush_audit_log()?;
+        Ok((sanitized, sampled_summary(summarized, &self.options)))
     }
 
     fn analyze_for_stats(&self, content: &str, source_id: &str) -> Result<Vec<RedactionSummaryItem>> {
-        let matches = self.find_matches_internal(content, source_id);
-        let mut summary_map: HashMap<String, RedactionSummaryItem> = HashMap::new();
-        for m in matches {
-            let entry = summary_map.entry(m.rule_name.clone()).or_insert_with(|| RedactionSummaryItem {
-                rule_name: m.rule_name.clone(), 
-                occurrences: 0, 
-                original_texts: Vec::new(), 
-                sanitized_texts: Vec::new(),
-            });
-            entry.occurrences += 1;
-        }
-        Ok(summary_map.into_values().collect())
+        let (matches, repeats) = self.find_matches_internal(content, source_id, None, &self.metrics);
+        let tagged = matches.iter().map(|m| (m, false)).chain(repeats.iter().map(|m| (m, true)));
+        Ok(sampled_summary(tagged, &self.options))
     }
 
     fn find_matches_for_ui(&self, content: &str, source_id: &str) -> Result<Vec<RedactionMatch>> {
-        let mut matches = self.find_matches_internal(content, source_id);
-        ensure_match_hashes(&mut matches);
-        matches.sort_by_key(|m| m.start);
-        Ok(matches)
+        Ok(self.find_numbered_matches(content, source_id, None, true))
     }
 
     fn get_heat_scores(&self, content: &str) -> Vec<f64> {
-        let stripped_bytes = strip(content.as_bytes());
-        let mut scores = Vec::with_capacity(content.len());
-        
-        for i in 0..stripped_bytes.len() {
-            let start = i.saturating_sub(4);
-            let end = std::cmp::min(stripped_bytes.len(), i + 5);
-            scores.push(cleansh_entropy::entropy::calculate_shannon_entropy(&stripped_bytes[start..end]));
+        if content.len() > self.max_line_bytes {
+            return Vec::new();
         }
-        
-        while scores.len() < content.len() { scores.push(0.0); }
-        scores
+        StrippedIndexMapper::new(content).project_scores(content, &self.stripped_heat_scores(content))
     }
 
     fn compiled_rules(&self) -> &CompiledRules { &self.compiled_rules }
-    fn get_rules(&self) -> &RedactionConfig { &self.config }
+    fn get_rules(&self) -> Arc<RedactionConfig> { self.config.load_full() }
     fn get_options(&self) -> &EngineOptions { &self.options }
-    fn set_remediation_tx(&mut self, tx: mpsc::Sender<RedactionMatch>) { self.remediation_tx = Some(tx); }
+    fn metrics(&self) -> EngineMetricsSnapshot { self.metrics.snapshot() }
+    fn entropy_threshold(&self) -> Option<f64> { Some(self.threshold()) }
+    fn set_entropy_threshold(&self, threshold: f64) -> bool { self.set_threshold(threshold); true }
+    fn update_fingerprints(&self, fingerprints: Vec<SecretFingerprint>) { EntropyEngine::update_fingerprints(self, fingerprints) }
+    fn set_fingerprint_cache(&self, cache: Arc<FingerprintCache>) { EntropyEngine::set_fingerprint_cache(self, cache) }
 }
\ No newline at end of file
@@ -0,0 +1,336 @@
+// cleansh-core/src/engines/index_map.rs
+//! Offset helpers shared by the engines.
+//!
+//! Engines scan an ANSI-stripped copy of the input, and the entropy scanner works
+//! on raw bytes, so a match offset can point into another coordinate space or into
+//! the middle of a multi-byte character. Everything here snaps to `char` boundaries
+//! so callers can slice without panicking.
+//!
+//! A secret can also be interrupted by colour codes (`ghp_\x1b[32mABCD\x1b[0m1234`).
+//! The mapper never lets the secret's characters match bytes of an escape, and
+//! [`replacement_for`] keeps the escapes a redacted span contained, so terminal
+//! styling after the secret is what it would have been.
+
+use std::borrow::Cow;
+use std::ops::Range;
+use strip_ansi_escapes::strip;
+
+use crate::redaction_match::offset_from_index;
+
+/// Largest char boundary of `s` at or below `index` (stable `str::floor_char_boundary`).
+pub(crate) fn floor_char_boundary(s: &str, index: usize) -> usize {
+    let mut index = index.min(s.len());
+    while !s.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Smallest char boundary of `s` at or above `index` (stable `str::ceil_char_boundary`).
+pub(crate) fn ceil_char_boundary(s: &str, index: usize) -> usize {
+    let mut index = index.min(s.len());
+    while !s.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+/// How much of the end of an oversized input the entropy scanner still reads, so a
+/// secret appended to a large blob is not missed.
+pub(crate) const TAIL_WINDOW_BYTES: usize = 64 * 1024;
+
+/// The byte ranges of `text` to scan when lines may be `max_bytes` long. Lines
+/// within the cap are scanned whole; of a longer line only its first `max_bytes`
+/// and its last `TAIL_WINDOW_BYTES` (at most `max_bytes`) are, each snapped onto
+/// whole characters. Adjacent ranges are merged, so input without oversized lines
+/// is one range however large it is.
+pub(crate) fn sampled_ranges(text: &str, max_bytes: usize) -> Vec<Range<usize>> {
+    let tail = TAIL_WINDOW_BYTES.min(max_bytes);
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    let mut push = |range: Range<usize>| match ranges.last_mut() {
+        Some(last) if last.end == range.start => last.end = range.end,
+        _ => ranges.push(range),
+    };
+    let mut start = 0;
+    for line in text.split_inclusive('\n') {
+        let end = start + line.len();
+        if line.len() <= max_bytes.saturating_add(tail) {
+            push(start..end);
+        } else {
+            push(start..floor_char_boundary(text, start + max_bytes));
+            push(ceil_char_boundary(text, end - tail)..end);
+        }
+        start = end;
+    }
+    if ranges.is_empty() {
+        ranges.push(0..0);
+    }
+    ranges
+}
+
+/// The line byte `index` of `text` is on, when its first line is `first_line`.
+pub(crate) fn line_number_at(text: &str, index: usize, first_line: u64) -> u64 {
+    let end = floor_char_boundary(text, index);
+    first_line + offset_from_index(text.as_bytes()[..end].iter().filter(|&&b| b == b'\n').count())
+}
+
+/// Length in bytes of the ANSI escape sequence starting at `bytes[0]`, if one does:
+/// CSI (`ESC [ ... final`), OSC/DCS/SOS/PM/APC strings ended by BEL or `ESC \`,
+/// and two-byte escapes. A sequence cut off by the end of `bytes` runs to the end.
+pub(crate) fn escape_len(bytes: &[u8]) -> Option<usize> {
+    if bytes.first() != Some(&0x1b) {
+        return None;
+    }
+    Some(match bytes.get(1) {
+        None => 1,
+        Some(b'[') => {
+            // Parameter and intermediate bytes, then one final byte.
+            let params = bytes[2..].iter().take_while(|&&b| (0x20..=0x3f).contains(&b)).count();
+            match bytes.get(2 + params) {
+                Some(0x40..=0x7e) => 3 + params,
+                _ => 2 + params,
+            }
+        }
+        Some(b']' | b'P' | b'X' | b'^' | b'_') => {
+            let mut i = 2;
+            loop {
+                match bytes.get(i) {
+                    None => break bytes.len(),
+                    Some(0x07) => break i + 1,
+                    Some(0x1b) if bytes.get(i + 1) == Some(&b'\\') => break i + 2,
+                    Some(_) => i += 1,
+                }
+            }
+        }
+        Some(0x20..=0x2f) => {
+            // Intermediate bytes, then one final byte (`ESC ( B`).
+            let intermediates = bytes[1..].iter().take_while(|&&b| (0x20..=0x2f).contains(&b)).count();
+            (2 + intermediates).min(bytes.len())
+        }
+        Some(_) => 2,
+    })
+}
+
+/// The ANSI escape sequences in `text`, in order.
+pub(crate) fn ansi_escapes(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut escapes = String::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match escape_len(&bytes[i..]) {
+            Some(len) => {
+                escapes.push_str(&String::from_utf8_lossy(&bytes[i..i + len]));
+                i += len;
+            }
+            None => i += 1,
+        }
+    }
+    escapes
+}
+
+/// What sanitized output writes in place of `original[span]`: `replacement`, then
+/// the ANSI escapes the span contained, so colours switched on or off inside a
+/// redacted secret still apply to the text after it.
+pub(crate) fn replacement_for<'a>(original: &str, span: Range<usize>, replacement: &'a str) -> Cow<'a, str> {
+    let replaced = &original[span];
+    if !replaced.contains('\x1b') {
+        return Cow::Borrowed(replacement);
+    }
+    Cow::Owned(format!("{}{}", replacement, ansi_escapes(replaced)))
+}
+
+/// Maps byte offsets in the ANSI-stripped input back to byte offsets in the original.
+#[derive(Debug)]
+pub struct StrippedIndexMapper {
+    map: Vec<usize>,
+}
+
+impl StrippedIndexMapper {
+    pub fn new(original: &str) -> Self {
+        let stripped_bytes = strip(original.as_bytes());
+        let stripped_str = String::from_utf8_lossy(&stripped_bytes);
+
+        let mut map: Vec<usize> = Vec::with_capacity(stripped_str.len() + 1);
+        let mut orig_char_indices = original.char_indices();
+        let mut current = orig_char_indices.next();
+        for stripped_char in stripped_str.chars() {
+            while let Some((orig_index, orig_char)) = current {
+                // Skip a whole escape, so its parameters (`3`, `2`, `m` of `ESC[32m`)
+                // are never taken for the stripped text's characters.
+                if orig_char == '\x1b' && stripped_char != '\x1b' {
+                    let end = orig_index + escape_len(&original.as_bytes()[orig_index..]).unwrap_or(1);
+                    while current.is_some_and(|(index, _)| index < end) {
+                        current = orig_char_indices.next();
+                    }
+                    continue;
+                }
+                current = orig_char_indices.next();
+                if orig_char == stripped_char {
+                    // Offsets are bytes, so a multi-byte character needs one entry per byte.
+                    map.extend(orig_index..orig_index + orig_char.len_utf8());
+                    break;
+                }
+            }
+        }
+        map.push(original.len());
+        Self { map }
+    }
+
+    fn map_index(&self, stripped_index: usize) -> usize {
+        let idx = stripped_index.min(self.map.len().saturating_sub(1));
+        self.map[idx]
+    }
+
+    /// Spreads scores indexed by byte of the stripped input over the original,
+    /// leaving the bytes of ANSI escapes at zero.
+    pub(crate) fn project_scores(&self, original: &str, stripped_scores: &[f64]) -> Vec<f64> {
+        let mut projected = vec![0.0; original.len()];
+        for (&original_index, &score) in self.map.iter().zip(stripped_scores) {
+            if let Some(slot) = projected.get_mut(original_index) {
+                *slot = score;
+            }
+        }
+        projected
+    }
+
+    /// Maps a match start, moving back onto the start of a split character.
+    pub(crate) fn map_start(&self, original: &str, stripped_index: usize) -> usize {
+        floor_char_boundary(original, self.map_index(stripped_index))
+    }
+
+    /// Maps a match end, moving forward past the rest of a split character. The end
+    /// follows the match's last byte, so stripped bytes after it (a `\r` before the
+    /// newline, a colour reset) stay outside the match.
+    pub(crate) fn map_end(&self, original: &str, stripped_index: usize) -> usize {
+        if stripped_index == 0 {
+            return ceil_char_boundary(original, self.map_index(0));
+        }
+        ceil_char_boundary(original, self.map_index(stripped_index - 1) + 1)
+    }
+
+    /// The bytes of `original` that `stripped` spans of the ANSI-stripped input.
+    pub fn map_range(&self, original: &str, stripped: Range<usize>) -> Range<usize> {
+        self.map_start(original, stripped.start)..self.map_end(original, stripped.end)
+    }
+}
+
+/// An input next to the ANSI-stripped copy an engine scans, so matches found in
+/// the copy can be placed in the input.
+#[derive(Debug)]
+pub(crate) struct ScannedText<'a> {
+    pub(crate) original: &'a str,
+    pub(crate) stripped: &'a str,
+    mapper: StrippedIndexMapper,
+    /// Line number of the input's first line, when the caller knows it.
+    first_line: Option<u64>,
+}
+
+impl<'a> ScannedText<'a> {
+    /// `stripped` must be `original` with its ANSI escapes removed.
+    pub(crate) fn new(original: &'a str, stripped: &'a str, first_line: Option<u64>) -> Self {
+        Self { original, stripped, mapper: StrippedIndexMapper::new(original), first_line }
+    }
+
+    /// The line a match starting at byte `original_index` of the input is on,
+    /// counted from `first_line`.
+    pub(crate) fn line_number(&self, original_index: usize) -> Option<u64> {
+        self.first_line.map(|first| line_number_at(self.original, original_index, first))
+    }
+
+    /// The bytes of the input that `start..end` of the stripped copy came from.
+    pub(crate) fn original_span(&self, start: usize, end: usize) -> Range<usize> {
+        self.mapper.map_start(self.original, start)..self.mapper.map_end(self.original, end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_scores_skips_escape_bytes() {
+        let original = "\x1b[31mab\x1b[0mc";
+        let mapper = StrippedIndexMapper::new(original);
+        let projected = mapper.project_scores(original, &[1.0, 2.0, 3.0]);
+        assert_eq!(projected.len(), original.len());
+        assert_eq!((projected[5], projected[6], projected[11]), (1.0, 2.0, 3.0));
+        assert_eq!(projected.iter().sum::<f64>(), 6.0);
+    }
+
+    #[test]
+    fn test_sampled_ranges_keep_head_and_tail_of_oversized_input() {
+        let whole = sampled_ranges("short line", 5);
+        assert_eq!((whole.len(), &whole[0]), (1, &(0..10)));
+        assert_eq!(sampled_ranges("short line", 4), vec![0..4, 6..10]);
+
+        let text = format!("{}{}", "a".repeat(300_000), "🚀".repeat(50_000));
+        let ranges = sampled_ranges(&text, 100_001);
+        assert_eq!(ranges[0], 0..100_001);
+        // The tail starts on the first whole rocket inside the last 64 KiB.
+        assert_eq!(ranges[1].end, text.len());
+        assert!(text.is_char_boundary(ranges[1].start));
+        assert!(text.len() - ranges[1].start <= TAIL_WINDOW_BYTES);
+        assert!(text.len() - ranges[1].start > TAIL_WINDOW_BYTES - 4);
+    }
+
+    #[test]
+    fn test_sampled_ranges_cap_each_line_not_the_input() {
+        // Many short lines over the cap in total are still scanned whole.
+        let text = "short line\n".repeat(100);
+        assert_eq!(sampled_ranges(&text, 20), vec![0..text.len()]);
+
+        // Only the oversized line in the middle is sampled.
+        let text = format!("head\n{}\ntail\n", "x".repeat(100));
+        assert_eq!(sampled_ranges(&text, 10), vec![0..15, 96..text.len()]);
+    }
+
+    #[test]
+    fn test_char_boundaries_snap_around_multibyte_characters() {
+        let s = "a🚀b";
+        assert_eq!((floor_char_boundary(s, 3), ceil_char_boundary(s, 3)), (1, 5));
+        assert_eq!((floor_char_boundary(s, 5), ceil_char_boundary(s, 5)), (5, 5));
+        assert_eq!(ceil_char_boundary(s, 100), s.len());
+    }
+
+    #[test]
+    fn test_mapper_skips_ansi_and_keeps_byte_offsets() {
+        let original = "\x1b[31m日志\x1b[0m key";
+        let mapper = StrippedIndexMapper::new(original);
+        // "日志 key": the space is stripped byte 6.
+        assert_eq!(mapper.map_start(original, 6), original.find(' ').unwrap());
+        // An offset inside '志' snaps to either side of it.
+        assert_eq!(mapper.map_start(original, 4), original.find('志').unwrap());
+        assert_eq!(mapper.map_end(original, 4), original.find('志').unwrap() + 3);
+    }
+
+    #[test]
+    fn test_match_end_stops_before_stripped_bytes() {
+        let original = "key\r\nnext";
+        let mapper = StrippedIndexMapper::new(original);
+        assert_eq!(mapper.map_end(original, 3), 3);
+
+        let original = "\x1b[31mkey\x1b[0m!";
+        let mapper = StrippedIndexMapper::new(original);
+        assert_eq!(mapper.map_end(original, 3), original.find("\x1b[0m").unwrap());
+    }
+
+    #[test]
+    fn test_escape_parameters_are_not_taken_for_text() {
+        // Stripped "ab32m": its `3`, `2`, and `m` must map past the escape.
+        let original = "ab\x1b[32m32m";
+        let mapper = StrippedIndexMapper::new(original);
+        assert_eq!(mapper.map_start(original, 2), original.rfind('3').unwrap());
+        assert_eq!(mapper.map_end(original, 5), original.len());
+    }
+
+    #[test]
+    fn test_escape_lengths() {
+        assert_eq!(escape_len(b"\x1b[38;5;208mx"), Some(11));
+        assert_eq!(escape_len(b"\x1b]8;;http://a\x07x"), Some(14));
+        assert_eq!(escape_len(b"\x1b]0;title\x1b\\x"), Some(11));
+        assert_eq!(escape_len(b"\x1b(Bx"), Some(3));
+        assert_eq!(escape_len(b"\x1b[31"), Some(4));
+        assert_eq!(escape_len(b"x"), None);
+        assert_eq!(ansi_escapes("a\x1b[1mb\x1b[0mc"), "\x1b[1m\x1b[0m");
+    }
+}
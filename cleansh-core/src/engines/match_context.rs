@@ -0,0 +1,100 @@
+// cleansh-core/src/engines/match_context.rs
+//! The text around a match, for reviewers triaging findings without the input.
+//!
+//! With `EngineOptions::capture_context`, the engines fill in each match's
+//! `context_before` and `context_after`: up to [`CONTEXT_CHARS`] characters of the
+//! ANSI-stripped input on either side, stopping at the start or end of the input
+//! and at line breaks. Context is shown where secrets must not be, so every other
+//! match overlapping a window is replaced by its sanitized text first.
+
+use std::ops::Range;
+
+use crate::redaction_match::{OffsetBasis, RedactionMatch};
+use crate::sanitizers::compiler::CompiledRules;
+
+/// Most characters captured on each side of a match.
+pub const CONTEXT_CHARS: usize = 40;
+
+/// A span of the stripped text and what it is redacted to.
+pub type Mask = (Range<usize>, String);
+
+/// The spans of `matches` that count bytes of the stripped text, as masks.
+pub fn match_masks<'a>(matches: impl IntoIterator<Item = &'a RedactionMatch>) -> Vec<Mask> {
+    matches
+        .into_iter()
+        .filter(|m| m.offset_basis == OffsetBasis::Stripped)
+        .map(|m| (m.span(), m.sanitized_string.clone()))
+        .collect()
+}
+
+/// Every match of every rule in `rules` on `stripped`, as masks, so context can be
+/// scrubbed of secrets the engine that found the match does not look for.
+pub fn rule_masks(rules: &CompiledRules, stripped: &str) -> Vec<Mask> {
+    rules
+        .candidates(stripped)
+        .into_iter()
+        .flat_map(|index| {
+            let rule = &rules.rules[index];
+            rule.regex.captures_iter(stripped).filter_map(move |caps| {
+                let redacted = rule.redacted_span(&caps)?;
+                Some((redacted.range(), rule.replacement(&caps)))
+            })
+        })
+        .collect()
+}
+
+/// Start of the window before `at`: back at most `CONTEXT_CHARS` characters, but
+/// not past a line break.
+fn window_start(text: &str, at: usize) -> usize {
+    text[..at]
+        .char_indices()
+        .rev()
+        .take(CONTEXT_CHARS)
+        .take_while(|&(_, c)| c != '\n' && c != '\r')
+        .last()
+        .map_or(at, |(i, _)| i)
+}
+
+/// End of the window after `at`: forward at most `CONTEXT_CHARS` characters, but
+/// not past a line break.
+fn window_end(text: &str, at: usize) -> usize {
+    text[at..]
+        .char_indices()
+        .take(CONTEXT_CHARS)
+        .take_while(|&(_, c)| c != '\n' && c != '\r')
+        .last()
+        .map_or(at, |(i, c)| at + i + c.len_utf8())
+}
+
+/// `text[window]` with every mask that overlaps it replaced, whole, by its
+/// redaction. `masks` must be sorted by start.
+fn scrub(text: &str, window: Range<usize>, masks: &[Mask]) -> String {
+    let mut out = String::with_capacity(window.len());
+    let mut at = window.start;
+    for (span, redacted) in masks.iter().filter(|(span, _)| span.start < window.end && window.start < span.end) {
+        if span.end <= at {
+            continue;
+        }
+        let start = span.start.max(at);
+        out.push_str(&text[at..start]);
+        out.push_str(redacted);
+        at = span.end.min(window.end);
+    }
+    out.push_str(&text[at..window.end]);
+    out
+}
+
+/// Sets the context of every match in `matches` from `stripped`, scrubbed of
+/// `masks` (see [`match_masks`] and [`rule_masks`]). The match's own span never
+/// falls inside its windows, so masks of other rules on the same span do no harm.
+pub fn attach_context<'a>(matches: impl IntoIterator<Item = &'a mut RedactionMatch>, stripped: &str, mut masks: Vec<Mask>) {
+    masks.sort_by_key(|(span, _)| (span.start, span.end));
+    for m in matches.into_iter().filter(|m| m.offset_basis == OffsetBasis::Stripped) {
+        let span = m.span();
+        if span.end > stripped.len() || !stripped.is_char_boundary(span.start) || !stripped.is_char_boundary(span.end) {
+            continue;
+        }
+        m.context_before = Some(scrub(stripped, window_start(stripped, span.start)..span.start, &masks));
+        m.context_after = Some(scrub(stripped, span.end..window_end(stripped, span.end), &masks));
+    }
+}
@@ -12,4 +12,14 @@
 //! MIT OR APACHE 2.0
 
 pub mod regex_engine;
-pub mod entropy_engine;
\ No newline at end of file
+pub mod entropy_engine;
+pub mod composite_engine;
+pub mod match_context;
+/// Public only so the benchmarks can time the offset mapping.
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod index_map;
+#[cfg(not(feature = "bench"))]
+pub(crate) mod index_map;
+
+pub use index_map::StrippedIndexMapper;
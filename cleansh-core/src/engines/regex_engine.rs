@@ -3,9 +3,11 @@
 //! to identify and redact sensitive data.
 //! License: MIT OR APACHE 2.0
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
 use std::sync::Arc;
-use anyhow::{Result, Context, anyhow};
+use std::time::{Duration, Instant};
+use anyhow::{Result, Context};
 use strip_ansi_escapes::strip;
 use sha2::{Digest, Sha256};
 use hex;
@@ -13,54 +15,99 @@ use chrono::Utc;
 use tokio::sync::mpsc;
 
 use crate::config::{RedactionConfig, RedactionSummaryItem, RedactionRule};
-use crate::redaction_match::{RedactionMatch, RedactionLog, ensure_match_hashes};
-use crate::profiles::EngineOptions;
-use crate::engine::SanitizationEngine;
+use crate::redaction_match::{RedactionMatch, OffsetBasis, ensure_match_hashes, offset_from_index};
+use crate::profiles::{EngineOptions, format_token, sampled_summary};
+use crate::engine::{SanitizationEngine, SanitizeContext};
+use crate::explain::Decision;
+use crate::engines::index_map::{ceil_char_boundary, replacement_for, ScannedText, StrippedIndexMapper};
+use crate::engines::match_context::{attach_context, match_masks};
+use crate::metrics::{EngineMetrics, EngineMetricsSnapshot};
 use crate::sanitizers::compiler::{get_or_compile_rules, CompiledRules, CompiledRule};
-use crate::validators;
+use crate::severity::Severity;
+use crate::cli_flags::{find_flag_secrets, FlagSecret};
+use crate::remediation::channel::RemediationTx;
+use crate::remediation::fingerprint::SecretFingerprint;
+use crate::remediation::fingerprint_cache::{FingerprintCache, KnownLeaks};
+use crate::remediation::state::REVOKED_PLACEHOLDER;
 
-/// A mapper to convert byte indices from a stripped string back to the original string.
-#[derive(Debug)]
-struct StrippedIndexMapper {
-    map: Vec<usize>,
+pub const BATCH_SIZE: usize = 4096;
+
+/// With the `parallel` feature, inputs at least this large run their candidate
+/// rules on several threads; below it, thread start-up costs more than it saves.
+#[cfg(feature = "parallel")]
+pub const PARALLEL_MIN_BYTES: usize = 64 * 1024;
+
+/// Confidence for matches whose rule has a programmatic validator (e.g. Luhn) that passed.
+pub const VALIDATED_CONFIDENCE: f64 = 1.0;
+/// Confidence for matches backed by the pattern alone.
+pub const PATTERN_CONFIDENCE: f64 = 0.8;
+/// Confidence for values of a configured secret flag; maps to `ConfidenceLevel::High`.
+pub const CLI_FLAG_CONFIDENCE: f64 = 1.0;
+
+/// Bytes a rule with a time budget searches for a match to start in before the
+/// clock is checked again. Each search reads up to twice this, so matches up to
+/// this long are found exactly as without a budget.
+pub const SEARCH_WINDOW: usize = 16 * 1024;
+
+/// One rule's accepted matches: `(start, end, replacement)` in stripped-text bytes.
+type RuleHits = Vec<(usize, usize, String)>;
+
+/// What running one rule over an input produced.
+struct RuleRun {
+    hits: RuleHits,
+    elapsed: Duration,
+    /// The rule ran out of its time budget; `hits` are the matches found before that.
+    timed_out: bool,
 }
 
-impl StrippedIndexMapper {
-    fn new(original: &str) -> Self {
-        let stripped_bytes = strip(original.as_bytes());
-        let stripped_str = String::from_utf8_lossy(&stripped_bytes);
-        let mut map: Vec<usize> = Vec::with_capacity(stripped_str.len() + 1);
-        let mut original_char_indices = original.char_indices();
-        let stripped_chars = stripped_str.chars();
-        let mut current_orig_char = original_char_indices.next();
-        for stripped_char in stripped_chars {
-            while let Some((orig_index, orig_char)) = current_orig_char {
-                if orig_char == stripped_char {
-                    map.push(orig_index);
-                    current_orig_char = original_char_indices.next();
-                    break;
-                }
-                current_orig_char = original_char_indices.next();
-            }
-        }
-        map.push(original.len());
-        Self { map }
-    }
+/// How many characters on each side of a match get fading heat, so the heatmap
+/// brackets the secret.
+pub const HEAT_FALLOFF_CHARS: usize = 2;
 
-    fn map_index(&self, stripped_index: usize) -> usize {
-        let idx = stripped_index.min(self.map.len().saturating_sub(1));
-        self.map[idx]
+/// Heatmap score for characters inside a match of a rule with this severity.
+/// Rules without a recognized severity count as medium.
+pub fn severity_heat(severity: Option<&str>) -> f64 {
+    match Severity::from_rule(severity) {
+        Severity::Critical => 8.0,
+        Severity::High => 6.0,
+        Severity::Medium => 4.0,
+        Severity::Low | Severity::Info => 2.0,
     }
 }
 
-pub const BATCH_SIZE: usize = 4096;
+/// Raises `heat[span]` to `score`, and the `HEAT_FALLOFF_CHARS` characters on
+/// either side to a linearly fading fraction of it.
+fn paint_heat(heat: &mut [f64], text: &str, start: usize, end: usize, score: f64) {
+    let mut raise = |from: usize, to: usize, value: f64| {
+        for slot in &mut heat[from..to] {
+            *slot = slot.max(value);
+        }
+    };
+    raise(start, end, score);
+    let fade = |step: usize| score * (HEAT_FALLOFF_CHARS + 1 - step) as f64 / (HEAT_FALLOFF_CHARS + 1) as f64;
+    for (step, (at, c)) in text[..start].char_indices().rev().take(HEAT_FALLOFF_CHARS).enumerate() {
+        raise(at, at + c.len_utf8(), fade(step + 1));
+    }
+    for (step, (at, c)) in text[end..].char_indices().take(HEAT_FALLOFF_CHARS).enumerate() {
+        raise(end + at, end + at + c.len_utf8(), fade(step + 1));
+    }
+}
 
 #[derive(Debug)]
 pub struct RegexEngine {
     compiled_rules: Arc<CompiledRules>,
-    config: RedactionConfig,
+    config: Arc<RedactionConfig>,
     options: EngineOptions,
-    remediation_tx: Option<mpsc::Sender<RedactionMatch>>,
+    /// One rule per `cli_flag_secrets` entry, attached to the matches it produces.
+    cli_flag_rules: Vec<RedactionRule>,
+    remediation_tx: Option<RemediationTx>,
+    known_leaks: KnownLeaks,
+    /// Rule slots: compiled rules first, then `cli_flag_rules`.
+    metrics: EngineMetrics,
+    /// Whether the compiled rules' prefilter picks which rules run on an input.
+    prefilter: bool,
+    /// `engines.rule_timeout_ms`: how long one rule may keep matching on one input.
+    rule_timeout: Option<Duration>,
 }
 
 impl RegexEngine {
@@ -71,45 +118,251 @@ impl RegexEngine {
     pub fn with_options(config: RedactionConfig, options: EngineOptions) -> Result<Self> {
         let compiled_rules = get_or_compile_rules(&config)
             .context("Failed to compile redaction rules for RegexEngine")?;
-            
+        if let Some(pp) = options.post_processing.as_ref().filter(|pp| pp.replace_with_token) {
+            format_token(pp.effective_token_format(), "rule", "")
+                .context("Invalid post_processing.token_format")?;
+        }
+
+        let cli_flag_rules: Vec<RedactionRule> = config.cli_flag_secrets.iter().map(|r| r.to_redaction_rule()).collect();
+        let metrics = EngineMetrics::new(
+            compiled_rules.rules.iter().map(|rule| rule.name.clone())
+                .chain(cli_flag_rules.iter().map(|rule| rule.name.clone())),
+        );
+        let rule_timeout = config.engines.rule_timeout_ms.filter(|&ms| ms > 0).map(Duration::from_millis);
         Ok(Self {
             compiled_rules,
-            config,
+            config: Arc::new(config),
             options,
+            cli_flag_rules,
             remediation_tx: None,
+            known_leaks: KnownLeaks::default(),
+            metrics,
+            prefilter: true,
+            rule_timeout,
         })
     }
 
-    fn run_programmatic_validator(&self, compiled_rule: &CompiledRule, original_str: &str) -> bool {
-        if !compiled_rule.programmatic_validation {
-            return true;
+    /// Tees every detected match to the self-healing orchestrator's channel. When it
+    /// is full, `EngineOptions::remediation_tx_mode` decides what happens; set the
+    /// options first.
+    pub fn with_remediation_tx(mut self, tx: mpsc::Sender<RedactionMatch>) -> Self {
+        self.remediation_tx = Some(RemediationTx::new(tx, self.options.remediation_tx_mode));
+        self
+    }
+
+    /// Sets the organization salt vault fingerprints are computed with, so synced
+    /// fingerprints can be matched against detected secrets.
+    pub fn with_fingerprint_salt(mut self, salt: Vec<u8>) -> Self {
+        self.known_leaks = KnownLeaks::new(salt);
+        self
+    }
+
+    /// Tees `m` to the remediation channel unless it was already remediated, here
+    /// or elsewhere.
+    fn send_for_remediation(&self, m: &RedactionMatch) {
+        let remediated = |m: &RedactionMatch| {
+            self.known_leaks.contains_secret(&m.original_string) || self.options.remediation_state.is_revoked(&m.original_string)
+        };
+        if let Some(tx) = self.remediation_tx.as_ref().filter(|_| !remediated(m)) {
+            tx.send(m.clone(), &self.metrics);
         }
-        match compiled_rule.name.as_str() {
-            "us_ssn" => validators::is_valid_ssn_programmatically(original_str),
-            "uk_nino" => validators::is_valid_uk_nino_programmatically(original_str),
-            "visa_card" | "mastercard_card" | "amex_card" | "discover_card" => {
-                validators::is_valid_credit_card_programmatically(original_str)
+    }
+
+    /// Heat over the ANSI-stripped input: every active rule's matches (and secret
+    /// flag values) scored by severity. Unlike `find_matches`, this touches neither
+    /// the metrics nor the remediation channel, since it only feeds the heatmap.
+    fn stripped_heat_scores(&self, stripped: &str) -> Vec<f64> {
+        let mut heat = vec![0.0; stripped.len()];
+        let rules: HashMap<&str, &RedactionRule> = self.config.rules.iter()
+            .map(|rule| (rule.name.as_str(), rule)).collect();
+        for index in self.candidate_rules(stripped) {
+            let compiled_rule = &self.compiled_rules.rules[index];
+            let Some(rule) = rules.get(compiled_rule.name.as_str()) else { continue };
+            let score = severity_heat(rule.severity.as_deref());
+            let started = Instant::now();
+            for caps in compiled_rule.regex.captures_iter(stripped) {
+                if let Some(m) = compiled_rule.redacted_span(&caps) {
+                    if !self.options.is_ignored(&compiled_rule.name, m.as_str()) && compiled_rule.passes_validation(m.as_str()) {
+                        paint_heat(&mut heat, stripped, m.start(), m.end(), score);
+                    }
+                }
+                if self.out_of_time(started) {
+                    break;
+                }
+            }
+        }
+        for secret in find_flag_secrets(&self.config.cli_flag_secrets, stripped) {
+            let rule = &self.cli_flag_rules[secret.rule_index];
+            if !self.options.is_ignored(&rule.name, &stripped[secret.start..secret.end]) {
+                let score = severity_heat(rule.severity.as_deref());
+                paint_heat(&mut heat, stripped, secret.start, secret.end, score);
+            }
+        }
+        heat
+    }
+
+    /// Turns the rule prefilter on or off (it is on by default). Matches are the
+    /// same either way; without it every enabled rule runs on every input.
+    pub fn with_prefilter(mut self, enabled: bool) -> Self {
+        self.prefilter = enabled;
+        self
+    }
+
+    /// [`SanitizationEngine::find_matches_for_ui`] with `line_number` set on every
+    /// match when `first_line` is given. Without `record` the pass is left out of
+    /// the engine's metrics.
+    pub(crate) fn find_numbered_matches(&self, content: &str, source_id: &str, first_line: Option<u64>, record: bool) -> Result<Vec<RedactionMatch>> {
+        let unrecorded;
+        let metrics = if record {
+            &self.metrics
+        } else {
+            unrecorded = EngineMetrics::new(std::iter::empty::<String>());
+            &unrecorded
+        };
+        let (all_map, _) = self.find_matches(content, source_id, first_line, metrics)?;
+        let mut out: Vec<RedactionMatch> = all_map.into_values().flatten().collect();
+        ensure_match_hashes(&mut out);
+        self.sort_matches(&mut out);
+        Ok(out)
+    }
+
+    /// Indices of the compiled rules worth running on `stripped`.
+    fn candidate_rules(&self, stripped: &str) -> Vec<usize> {
+        if self.prefilter {
+            self.compiled_rules.candidates(stripped)
+        } else {
+            (0..self.compiled_rules.rules.len()).collect()
+        }
+    }
+
+    /// Whether a rule that started matching at `started` has used up its time budget.
+    fn out_of_time(&self, started: Instant) -> bool {
+        self.rule_timeout.is_some_and(|budget| started.elapsed() > budget)
+    }
+
+    /// One rule's validated matches on `stripped` as `(start, end, replacement)`,
+    /// spanning only the `redact_group` when the rule has one. A match lying
+    /// entirely inside a secret flag value is left to the flag rule.
+    ///
+    /// With a time budget the rule searches `stripped` a window at a time (see
+    /// [`SEARCH_WINDOW`]) and the clock is checked before each search, so a rule
+    /// that scans a long input without matching is stopped too.
+    fn rule_hits(&self, compiled_rule: &CompiledRule, stripped: &str, flag_secrets: &[FlagSecret], source_id: &str) -> RuleRun {
+        let started = Instant::now();
+        let mut hits = Vec::new();
+        if self.rule_timeout.is_none() {
+            for caps in compiled_rule.regex.captures_iter(stripped) {
+                hits.extend(self.rule_hit(compiled_rule, &caps, flag_secrets, source_id));
+            }
+            return RuleRun { hits, elapsed: started.elapsed(), timed_out: false };
+        }
+
+        let regex = &compiled_rule.regex;
+        let mut timed_out = false;
+        let mut at = 0;
+        let mut last_end = None;
+        while at <= stripped.len() {
+            if self.out_of_time(started) {
+                timed_out = true;
+                break;
             }
-            _ => true,
+            let window_end = ceil_char_boundary(stripped, at.saturating_add(2 * SEARCH_WINDOW));
+            let Some(mut caps) = regex.captures_at(&stripped[..window_end], at) else {
+                if window_end == stripped.len() {
+                    break;
+                }
+                at = ceil_char_boundary(stripped, at + SEARCH_WINDOW);
+                continue;
+            };
+            let mut whole = caps.get(0).map_or(at..at, |m| m.range());
+            if whole.end == window_end && window_end < stripped.len() {
+                // The window's end may have cut the match short, or let `$` or `\b` match there.
+                match regex.captures_at(stripped, whole.start) {
+                    Some(full) => caps = full,
+                    None => break,
+                }
+                whole = caps.get(0).map_or(at..at, |m| m.range());
+            }
+            // As with `captures_iter`, no empty match right where the last one ended.
+            if !(whole.is_empty() && last_end == Some(whole.start)) {
+                hits.extend(self.rule_hit(compiled_rule, &caps, flag_secrets, source_id));
+                last_end = Some(whole.end);
+            }
+            if !whole.is_empty() {
+                at = whole.end;
+            } else if whole.end < stripped.len() {
+                at = ceil_char_boundary(stripped, whole.end + 1);
+            } else {
+                break;
+            }
+        }
+        RuleRun { hits, elapsed: started.elapsed(), timed_out }
+    }
+
+    /// `caps` as one of `rule_hits`, unless it is ignored, fails validation, or
+    /// lies inside a secret flag value.
+    fn rule_hit(&self, compiled_rule: &CompiledRule, caps: &regex::Captures<'_>, flag_secrets: &[FlagSecret], source_id: &str) -> Option<(usize, usize, String)> {
+        let whole = caps.get(0)?;
+        let redacted = compiled_rule.redacted_span(caps)?;
+        if flag_secrets.iter().any(|s| s.start <= whole.start() && whole.end() <= s.end) {
+            return None;
+        }
+        let rule_name = || compiled_rule.name.clone();
+        if self.options.is_ignored(&compiled_rule.name, redacted.as_str()) {
+            self.options.explain(source_id, redacted.start(), redacted.end(), || Decision::Ignored { rule_name: rule_name() });
+            return None;
+        }
+        let passed = compiled_rule.passes_validation(redacted.as_str());
+        if compiled_rule.programmatic_validation {
+            self.options.explain(source_id, redacted.start(), redacted.end(), || Decision::Validator { rule_name: rule_name(), passed });
+        }
+        passed.then(|| (redacted.start(), redacted.end(), compiled_rule.replacement(caps)))
+    }
+
+    /// Runs `rule_hits` for every candidate, returning each one's run in candidate
+    /// order. With the `parallel` feature, inputs of at least `PARALLEL_MIN_BYTES`
+    /// spread the candidates over the available cores.
+    fn run_candidates(
+        &self,
+        candidates: &[(usize, &CompiledRule, &RedactionRule)],
+        stripped: &str,
+        flag_secrets: &[FlagSecret],
+        source_id: &str,
+    ) -> Vec<RuleRun> {
+        let run = |compiled_rule: &CompiledRule| self.rule_hits(compiled_rule, stripped, flag_secrets, source_id);
+        #[cfg(feature = "parallel")]
+        if stripped.len() >= PARALLEL_MIN_BYTES && candidates.len() > 1 {
+            let threads = std::thread::available_parallelism().map_or(1, |n| n.get()).min(candidates.len());
+            let chunk_size = candidates.len().div_ceil(threads);
+            return std::thread::scope(|scope| {
+                let workers: Vec<_> = candidates.chunks(chunk_size)
+                    .map(|chunk| scope.spawn(move || chunk.iter().map(|&(_, rule, _)| run(rule)).collect::<Vec<_>>()))
+                    .collect();
+                workers.into_iter()
+                    .flat_map(|worker| worker.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+                    .collect()
+            });
         }
+        candidates.iter().map(|&(_, rule, _)| run(rule)).collect()
     }
 
     fn create_redaction_match(
         &self,
         rule_config: &RedactionRule,
-        original_match_str: &str,
-        start: u64,
-        end: u64,
+        start: usize,
+        end: usize,
         replacement: String,
-        stripped_input: &str,
+        text: &ScannedText<'_>,
         source_id: &str,
-        line_number: Option<u64>,
     ) -> RedactionMatch {
+        let original_match_str = &text.stripped[start..end];
+        let original_span = text.original_span(start, end);
         let mut sample_hash = None;
         let mut match_context_hash = None;
-        let needs_sample_hash = self.options.post_processing.as_ref().map_or(false, |pp| pp.replace_with_token) ||
-            self.options.samples_config.is_some();
-        let needs_context_hash = self.options.dedupe_config.as_ref().map_or(false, |dedupe| dedupe.use_hash);
+        let needs_sample_hash = self.options.post_processing.as_ref().is_some_and(|pp| pp.replace_with_token) ||
+            self.options.samples_config.is_some() ||
+            self.options.dedupe_config.as_ref().is_some_and(|dedupe| dedupe.use_hash);
+        let needs_context_hash = self.options.dedupe_config.as_ref().is_some_and(|dedupe| dedupe.use_hash);
 
         if needs_sample_hash || needs_context_hash {
             let mut hasher = Sha256::new();
@@ -119,137 +372,214 @@ impl RegexEngine {
             }
             if needs_context_hash {
                 let window = self.options.dedupe_config.as_ref().map(|d| d.window_bytes).unwrap_or(0);
-                let ctx_start = (start as usize).saturating_sub(window);
-                let ctx_end = std::cmp::min(stripped_input.len(), (end as usize).saturating_add(window));
-                let ctx = &stripped_input[ctx_start..ctx_end];
-                hasher.update(ctx.as_bytes());
+                let ctx_start = original_span.start.saturating_sub(window);
+                let ctx_end = std::cmp::min(text.original.len(), original_span.end.saturating_add(window));
+                hasher.update(&text.original.as_bytes()[ctx_start..ctx_end]);
                 match_context_hash = Some(hex::encode(hasher.finalize()));
             }
         }
 
+        // Deterministic tokenization: the same secret always renders to the same token,
+        // unless remediation has revoked it.
+        let sanitized_string = match (self.options.post_processing.as_ref(), sample_hash.as_deref()) {
+            _ if self.options.remediation_state.is_revoked(original_match_str) => REVOKED_PLACEHOLDER.to_string(),
+            (Some(pp), Some(hash)) if pp.replace_with_token => {
+                format_token(pp.effective_token_format(), &rule_config.name, &pp.token_hash(hash)).unwrap_or(replacement)
+            }
+            _ => replacement,
+        };
+
         RedactionMatch {
             rule_name: rule_config.name.clone(),
             original_string: original_match_str.to_string(),
-            sanitized_string: replacement,
-            start,
-            end,
+            sanitized_string,
+            start: offset_from_index(start),
+            end: offset_from_index(end),
+            offset_basis: OffsetBasis::Stripped,
+            original_start: Some(offset_from_index(original_span.start)),
+            original_end: Some(offset_from_index(original_span.end)),
             sample_hash,
             match_context_hash,
             timestamp: Some(Utc::now().to_rfc3339()),
             rule: rule_config.clone(),
             source_id: source_id.to_string(),
-            line_number,
+            line_number: text.line_number(original_span.start),
+            confidence: None,
+            entropy: None,
+            corroborating_entropy: None,
+            context_before: None,
+            context_after: None,
         }
     }
 
-    fn find_matches(&self, content: &str, source_id: &str) -> Result<HashMap<String, Vec<RedactionMatch>>> {
+    /// Orders matches by start offset; where several rules match at the same offset,
+    /// the one declared first (config rules, then CLI flag rules) comes first and
+    /// is the one `sanitize` applies. The compiled rule set is shared between configs
+    /// with the same rules, so its order is not the declaration order.
+    fn sort_matches<M: std::borrow::Borrow<RedactionMatch>>(&self, matches: &mut [M]) {
+        let rank: HashMap<&str, usize> = self.config.rules.iter().map(|r| r.name.as_str())
+            .chain(self.cli_flag_rules.iter().map(|r| r.name.as_str()))
+            .enumerate()
+            .map(|(i, name)| (name, i))
+            .collect();
+        matches.sort_by_key(|m| {
+            let m = m.borrow();
+            (m.start, rank.get(m.rule_name.as_str()).copied().unwrap_or(usize::MAX))
+        });
+    }
+
+    /// Finds all matches, split into first sightings and repeats suppressed by `DedupeConfig`.
+    ///
+    /// Only first sightings are teed to the remediation channel; repeats are still returned
+    /// so that `sanitize` redacts every occurrence and the summary counts stay accurate.
+    /// A regex match lying entirely inside a secret flag value is dropped in favor of the
+    /// flag match.
+    /// `first_line` numbers the lines of `content` for the matches' `line_number`.
+    fn find_matches(&self, content: &str, source_id: &str, first_line: Option<u64>, metrics: &EngineMetrics) -> Result<(RuleMatches, RuleMatches)> {
         let stripped_bytes = strip(content.as_bytes());
         let stripped_input = String::from_utf8_lossy(&stripped_bytes);
+        let text = ScannedText::new(content, &stripped_input, first_line);
         let original_rules_map: HashMap<&str, &RedactionRule> = self.config.rules.iter()
             .map(|rule| (rule.name.as_str(), rule)).collect();
-        let mut all_matches: HashMap<String, Vec<RedactionMatch>> = HashMap::new();
+        let mut all_matches: RuleMatches = HashMap::new();
+        let mut duplicates: RuleMatches = HashMap::new();
+        metrics.record_scan(&stripped_bytes);
+
+        let flags_started = Instant::now();
+        let flag_secrets = find_flag_secrets(&self.config.cli_flag_secrets, &stripped_input);
+        let flags_elapsed = flags_started.elapsed();
     
-        for compiled_rule in &self.compiled_rules.rules {
-            if let Some(rule_config) = original_rules_map.get(compiled_rule.name.as_str()) {
-                if let Some(false) = rule_config.enabled { continue; }
-                for caps in compiled_rule.regex.captures_iter(&stripped_input) {
-                    let original_match = caps.get(0).ok_or_else(|| anyhow!("Regex capture failed"))?;
-                    if self.run_programmatic_validator(compiled_rule, original_match.as_str()) {
-                        let mut replacement = compiled_rule.replace_with.clone();
-                        for i in 1..caps.len() {
-                            if let Some(group) = caps.get(i) {
-                                replacement = replacement.replace(&format!("${}", i), group.as_str());
-                            }
-                        }
-                        let m = self.create_redaction_match(
-                            rule_config, original_match.as_str(), original_match.start() as u64,
-                            original_match.end() as u64, replacement, &stripped_input, source_id, None,
-                        );
-                        if let Some(tx) = &self.remediation_tx { let _ = tx.try_send(m.clone()); }
-                        all_matches.entry(compiled_rule.name.clone()).or_default().push(m);
-                    }
+        let candidates: Vec<(usize, &CompiledRule, &RedactionRule)> = self.candidate_rules(&stripped_input)
+            .into_iter()
+            .filter_map(|index| {
+                let compiled_rule = &self.compiled_rules.rules[index];
+                let rule_config = original_rules_map.get(compiled_rule.name.as_str())?;
+                Some((index, compiled_rule, *rule_config))
+            })
+            .collect();
+        let hits = self.run_candidates(&candidates, &stripped_input, &flag_secrets, source_id);
+
+        for ((index, compiled_rule, rule_config), run) in candidates.into_iter().zip(hits) {
+            let mut seen: HashSet<String> = HashSet::new();
+            let rule_matches = run.hits.len() as u64;
+            if run.timed_out {
+                metrics.record_timeout(index);
+            }
+            for (start, end, replacement) in run.hits {
+                self.options.explain(source_id, start, end, || Decision::Reported { rule_name: compiled_rule.name.clone() });
+                let mut m = self.create_redaction_match(rule_config, start, end, replacement, &text, source_id);
+                m.confidence = Some(if compiled_rule.programmatic_validation {
+                    VALIDATED_CONFIDENCE
+                } else {
+                    PATTERN_CONFIDENCE
+                });
+                let is_repeat = self.options.dedupe_config.as_ref()
+                    .and_then(|dedupe| dedupe.dedupe_key(&m))
+                    .is_some_and(|key| !seen.insert(key.to_string()));
+                if is_repeat {
+                    duplicates.entry(compiled_rule.name.clone()).or_default().push(m);
+                    continue;
                 }
+                self.send_for_remediation(&m);
+                all_matches.entry(compiled_rule.name.clone()).or_default().push(m);
             }
+            metrics.record_rule(index, rule_matches, run.elapsed);
         }
-        Ok(all_matches)
+
+        let mut flag_counts = vec![0u64; self.cli_flag_rules.len()];
+        let mut seen: HashSet<String> = HashSet::new();
+        for secret in &flag_secrets {
+            let rule = &self.cli_flag_rules[secret.rule_index];
+            if self.options.is_ignored(&rule.name, &stripped_input[secret.start..secret.end]) {
+                self.options.explain(source_id, secret.start, secret.end, || Decision::Ignored { rule_name: rule.name.clone() });
+                continue;
+            }
+            self.options.explain(source_id, secret.start, secret.end, || Decision::Reported { rule_name: rule.name.clone() });
+            let mut m = self.create_redaction_match(
+                rule, secret.start, secret.end, rule.replace_with.clone(), &text, source_id,
+            );
+            m.confidence = Some(CLI_FLAG_CONFIDENCE);
+            flag_counts[secret.rule_index] += 1;
+            let is_repeat = self.options.dedupe_config.as_ref()
+                .and_then(|dedupe| dedupe.dedupe_key(&m))
+                .is_some_and(|key| !seen.insert(key.to_string()));
+            if is_repeat {
+                duplicates.entry(rule.name.clone()).or_default().push(m);
+                continue;
+            }
+            self.send_for_remediation(&m);
+            all_matches.entry(rule.name.clone()).or_default().push(m);
+        }
+        // The flag rules share one tokenizing pass; its time is split evenly between them.
+        let per_flag_rule = flags_elapsed / flag_counts.len().max(1) as u32;
+        for (i, count) in flag_counts.into_iter().enumerate() {
+            metrics.record_rule(self.compiled_rules.rules.len() + i, count, per_flag_rule);
+        }
+        if self.options.capture_context {
+            let masks = match_masks(all_matches.values().chain(duplicates.values()).flatten());
+            attach_context(all_matches.values_mut().chain(duplicates.values_mut()).flatten(), &stripped_input, masks);
+        }
+        Ok((all_matches, duplicates))
     }
 }
 
+/// Matches grouped by rule name.
+type RuleMatches = HashMap<String, Vec<RedactionMatch>>;
+
+fn build_summary(all_matches: &RuleMatches, duplicates: &RuleMatches, options: &EngineOptions) -> Vec<RedactionSummaryItem> {
+    let kept = all_matches.values().flatten().map(|m| (m, false));
+    sampled_summary(kept.chain(duplicates.values().flatten().map(|m| (m, true))), options)
+}
+
 impl SanitizationEngine for RegexEngine {
-    fn sanitize(
-        &self,
-        content: &str,
-        source_id: &str,
-        run_id: &str,
-        input_hash: &str,
-        user_id: &str,
-        reason: &str,
-        outcome: &str,
-        mut audit_log: Option<&mut crate::audit_log::AuditLog>,
-    ) -> Result<(String, Vec<RedactionSummaryItem>)> {
-        let all_matches = self.find_matches(content, source_id)?;
-        let mut sorted_matches: Vec<&RedactionMatch> = all_matches.values().flatten().collect();
-        sorted_matches.sort_by_key(|m| m.start);
-        let mapper = StrippedIndexMapper::new(content);
+    fn sanitize_with(&self, content: &str, ctx: &mut SanitizeContext<'_>) -> Result<(String, Vec<RedactionSummaryItem>)> {
+        let (all_matches, duplicates) = self.find_matches(content, ctx.source_id, ctx.first_line, &self.metrics)?;
+        let mut sorted_matches: Vec<&RedactionMatch> = all_matches.values().flatten()
+            .chain(duplicates.values().flatten()).collect();
+        self.sort_matches(&mut sorted_matches);
         let mut sanitized_content = String::with_capacity(content.len());
         let mut last_end = 0usize;
 
         for m in sorted_matches.iter() {
-            let original_start_byte = mapper.map_index(m.start as usize);
-            let original_end_byte = mapper.map_index(m.end as usize);
+            let Range { start: original_start_byte, end: original_end_byte } = m.document_span();
             if original_end_byte <= last_end { continue; }
             let current_start = original_start_byte.max(last_end);
             sanitized_content.push_str(&content[last_end..current_start]);
-            sanitized_content.push_str(&m.sanitized_string);
+            sanitized_content.push_str(&replacement_for(content, current_start..original_end_byte, &m.sanitized_string));
             last_end = original_end_byte;
-
-            if let Some(log) = audit_log.as_mut() {
-                let _ = log.append(&RedactionLog {
-                    timestamp: m.timestamp.clone().unwrap_or_default(),
-                    run_id: run_id.to_string(), file_path: source_id.to_string(),
-                    user_id: user_id.to_string(), reason_for_redaction: reason.to_string(),
-                    redaction_outcome: outcome.to_string(), rule_name: m.rule_name.clone(),
-                    input_hash: input_hash.to_string(), match_hash: m.sample_hash.clone().unwrap_or_default(),
-                    start: m.start, end: m.end,
-                });
+            if let Some(applied) = ctx.applied_matches.as_mut() {
+                applied.push((*m).clone());
             }
+
+            ctx.log_redaction(m, original_start_byte..original_end_byte);
         }
         sanitized_content.push_str(&content[last_end..]);
-        let mut summary = Vec::new();
-        for (rule_name, matches) in all_matches.iter() {
-            summary.push(RedactionSummaryItem {
-                rule_name: rule_name.clone(), occurrences: matches.len(),
-                original_texts: matches.iter().map(|m| m.original_string.clone()).collect(),
-                sanitized_texts: matches.iter().map(|m| m.sanitized_string.clone()).collect(),
-            });
-        }
-        Ok((sanitized_content, summary))
+        ctx.flush_audit_log()?;
+        Ok((sanitized_content, build_summary(&all_matches, &duplicates, &self.options)))
     }
 
     fn analyze_for_stats(&self, content: &str, source_id: &str) -> Result<Vec<RedactionSummaryItem>> {
-        let all_matches = self.find_matches(content, source_id)?;
-        let mut summary = Vec::new();
-        for (rule_name, matches) in all_matches.iter() {
-            summary.push(RedactionSummaryItem {
-                rule_name: rule_name.clone(), occurrences: matches.len(),
-                original_texts: matches.iter().map(|m| m.original_string.clone()).collect(),
-                sanitized_texts: matches.iter().map(|m| m.sanitized_string.clone()).collect(),
-            });
-        }
-        Ok(summary)
+        let (all_matches, duplicates) = self.find_matches(content, source_id, None, &self.metrics)?;
+        Ok(build_summary(&all_matches, &duplicates, &self.options))
     }
 
     fn find_matches_for_ui(&self, content: &str, source_id: &str) -> Result<Vec<RedactionMatch>> {
-        let all_map = self.find_matches(content, source_id)?;
-        let mut out: Vec<RedactionMatch> = all_map.into_values().flatten().collect();
-        ensure_match_hashes(&mut out);
-        out.sort_by_key(|m| m.start);
-        Ok(out)
+        self.find_numbered_matches(content, source_id, None, true)
+    }
+
+    fn get_heat_scores(&self, content: &str) -> Vec<f64> {
+        if content.len() > self.config.engines.entropy.resolved_max_line_bytes() {
+            return Vec::new();
+        }
+        let stripped_bytes = strip(content.as_bytes());
+        let stripped = String::from_utf8_lossy(&stripped_bytes);
+        StrippedIndexMapper::new(content).project_scores(content, &self.stripped_heat_scores(&stripped))
     }
 
-    fn get_heat_scores(&self, content: &str) -> Vec<f64> { vec![0.0; content.len()] }
     fn compiled_rules(&self) -> &CompiledRules { &self.compiled_rules }
-    fn get_rules(&self) -> &RedactionConfig { &self.config }
+    fn get_rules(&self) -> Arc<RedactionConfig> { Arc::clone(&self.config) }
     fn get_options(&self) -> &EngineOptions { &self.options }
-    fn set_remediation_tx(&mut self, tx: mpsc::Sender<RedactionMatch>) { self.remediation_tx = Some(tx); }
+    fn metrics(&self) -> EngineMetricsSnapshot { self.metrics.snapshot() }
+    fn update_fingerprints(&self, fingerprints: Vec<SecretFingerprint>) { self.known_leaks.extend(fingerprints) }
+    fn set_fingerprint_cache(&self, cache: Arc<FingerprintCache>) { self.known_leaks.replace_cache(cache) }
 }
\ No newline at end of file
@@ -21,6 +21,12 @@ pub enum CleanshError {
     #[error("Rule '{0}': pattern length ({1}) exceeds maximum allowed ({2})")]
     PatternLengthExceeded(String, usize, usize),
 
+    #[error("Rule '{0}': redact_group '{1}' is not a capture group of its pattern")]
+    RedactGroupNotFound(String, String),
+
+    #[error("Rule '{0}': pattern is too complex: {1}")]
+    PatternTooComplex(String, String),
+
     #[error("Failed to serialize configuration for hashing: {0}")]
     SerializationError(String),
 
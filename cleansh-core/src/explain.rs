@@ -0,0 +1,222 @@
+// cleansh-core/src/explain.rs
+//! Decision traces for debugging false negatives.
+//!
+//! With [`EngineOptions::explain`](crate::profiles::EngineOptions) set, the
+//! engines report why each candidate span was kept or dropped: the entropy
+//! scanner's scores against the confidence threshold, the trimming done by the
+//! entropy engine, secret length bounds, ignore list hits, and the outcome of
+//! each rule's programmatic validator. Records go to an [`ExplainLog`], which
+//! collects them in memory or streams them to an [`ExplainSink`] such as
+//! [`JsonlExplainSink`].
+
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use cleansh_entropy::context::ContextSignal;
+use cleansh_entropy::engine::{CandidateScore, CandidateStage};
+
+/// One decision an engine made about a span of its input.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExplainRecord {
+    pub source_id: String,
+    /// Byte span in the ANSI-stripped input the decision is about.
+    pub start: usize,
+    pub end: usize,
+    #[serde(flatten)]
+    pub decision: Decision,
+}
+
+/// What was decided, and the numbers behind it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "decision", rename_all = "snake_case")]
+pub enum Decision {
+    /// The entropy scanner compared a candidate's confidence with the threshold.
+    EntropyCandidate {
+        stage: ExplainStage,
+        entropy: f64,
+        z_score: Option<f64>,
+        keyword: KeywordSignal,
+        confidence: f64,
+        threshold: f64,
+        accepted: bool,
+    },
+    /// The entropy engine trimmed the candidate `raw_start..raw_end` to the record's span.
+    Trimmed { raw_start: usize, raw_end: usize },
+    /// The span fell outside the configured secret length bounds and was dropped.
+    LengthRejected { len: usize },
+    /// The span is on the ignore list, in the ignore store, or matches an ignore pattern.
+    Ignored { rule_name: String },
+    /// A rule's programmatic validator accepted or rejected the span.
+    Validator { rule_name: String, passed: bool },
+    /// The span was reported as a match.
+    Reported { rule_name: String },
+}
+
+/// Which pass of the entropy scanner scored a candidate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExplainStage {
+    /// A sliding window; rejected windows are reported as the closest miss
+    /// between accepted ones.
+    Window,
+    /// A trimmed match re-scored for a benign label such as `trace_id=`.
+    BenignLabel,
+    /// A delimited token scored by its shape (hex, base64, ...).
+    TokenShape,
+}
+
+/// What the text before a candidate said about it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeywordSignal {
+    Positive,
+    Negative,
+    None,
+}
+
+impl From<CandidateStage> for ExplainStage {
+    fn from(stage: CandidateStage) -> Self {
+        match stage {
+            CandidateStage::Window => Self::Window,
+            CandidateStage::BenignLabel => Self::BenignLabel,
+            CandidateStage::TokenShape => Self::TokenShape,
+        }
+    }
+}
+
+impl From<ContextSignal> for KeywordSignal {
+    fn from(signal: ContextSignal) -> Self {
+        match signal {
+            ContextSignal::Positive => Self::Positive,
+            ContextSignal::Negative => Self::Negative,
+            ContextSignal::None => Self::None,
+        }
+    }
+}
+
+impl Decision {
+    /// The decision for a scanner score, with its span left to the caller.
+    pub(crate) fn from_score(score: &CandidateScore) -> Self {
+        Decision::EntropyCandidate {
+            stage: score.stage.into(),
+            entropy: score.entropy,
+            z_score: score.z_score,
+            keyword: score.context.into(),
+            confidence: score.confidence,
+            threshold: score.threshold,
+            accepted: score.accepted(),
+        }
+    }
+}
+
+impl fmt::Display for ExplainStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Window => "window",
+            Self::BenignLabel => "benign label",
+            Self::TokenShape => "token shape",
+        })
+    }
+}
+
+impl fmt::Display for KeywordSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Positive => "positive",
+            Self::Negative => "negative",
+            Self::None => "none",
+        })
+    }
+}
+
+/// One line of the human-readable trace, e.g.
+/// `4..28 entropy window: entropy 4.12, z-score 1.30, keyword none, confidence 0.42 < threshold 0.50 -> rejected`.
+impl fmt::Display for ExplainRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{} ", self.start, self.end)?;
+        match &self.decision {
+            Decision::EntropyCandidate { stage, entropy, z_score, keyword, confidence, threshold, accepted } => {
+                write!(f, "entropy {}: entropy {:.2}, ", stage, entropy)?;
+                if let Some(z_score) = z_score {
+                    write!(f, "z-score {:.2}, ", z_score)?;
+                }
+                let (cmp, outcome) = if *accepted { (">=", "accepted") } else { ("<", "rejected") };
+                write!(f, "keyword {}, confidence {:.2} {} threshold {:.2} -> {}", keyword, confidence, cmp, threshold, outcome)
+            }
+            Decision::Trimmed { raw_start, raw_end } => write!(f, "trimmed from {}..{}", raw_start, raw_end),
+            Decision::LengthRejected { len } => write!(f, "dropped: {} bytes is outside the secret length bounds", len),
+            Decision::Ignored { rule_name } => write!(f, "{}: ignored", rule_name),
+            Decision::Validator { rule_name, passed } => {
+                write!(f, "{}: validator {}", rule_name, if *passed { "passed" } else { "failed -> rejected" })
+            }
+            Decision::Reported { rule_name } => write!(f, "{}: reported", rule_name),
+        }
+    }
+}
+
+/// Receives explain records as the engines make decisions.
+pub trait ExplainSink: Send + Sync + fmt::Debug {
+    fn record(&self, record: &ExplainRecord);
+}
+
+/// Writes each record as one line of JSON.
+#[derive(Debug)]
+pub struct JsonlExplainSink {
+    writer: Mutex<BufWriter<File>>,
+}
+
+impl JsonlExplainSink {
+    /// Creates (or truncates) the file at `path`.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::create(path).with_context(|| format!("Failed to create explain log {}", path.display()))?;
+        Ok(Self { writer: Mutex::new(BufWriter::new(file)) })
+    }
+}
+
+impl ExplainSink for JsonlExplainSink {
+    fn record(&self, record: &ExplainRecord) {
+        let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        // Records are a debugging aid; a failed write must not fail the scan.
+        if let Err(e) = serde_json::to_writer(&mut *writer, record).map_err(std::io::Error::from).and_then(|_| writeln!(writer)) {
+            log::warn!("Failed to write explain record: {}", e);
+        }
+        let _ = writer.flush();
+    }
+}
+
+/// A shared handle the engines report to: clones see the same records, so the
+/// records of every engine built from one `EngineOptions` end up together.
+#[derive(Debug, Clone, Default)]
+pub struct ExplainLog {
+    records: Arc<Mutex<Vec<ExplainRecord>>>,
+    /// Where records are streamed instead of being collected.
+    sink: Option<Arc<dyn ExplainSink>>,
+}
+
+impl ExplainLog {
+    /// A log that streams every record to `sink` and keeps none.
+    pub fn streaming(sink: Arc<dyn ExplainSink>) -> Self {
+        Self { records: Arc::default(), sink: Some(sink) }
+    }
+
+    /// Returns the records collected so far and clears them.
+    pub fn take(&self) -> Vec<ExplainRecord> {
+        std::mem::take(&mut *self.records.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+impl ExplainSink for ExplainLog {
+    fn record(&self, record: &ExplainRecord) {
+        match &self.sink {
+            Some(sink) => sink.record(record),
+            None => self.records.lock().unwrap_or_else(PoisonError::into_inner).push(record.clone()),
+        }
+    }
+}
@@ -0,0 +1,224 @@
+// cleansh-core/src/ignore_file.rs
+//! The `.cleanshignore` file: matches to stop redacting, identified by hash.
+//!
+//! Each entry is the hex SHA-256 of a matched value ([`ignore_hash`]), optionally
+//! followed by `expires=<YYYY-MM-DD>` and `reason="..."`. Unlike a match's
+//! `sample_hash`, which also covers the rule name, an entry silences the value
+//! whichever rule finds it. Comments (`#`) and blank lines are kept verbatim when the file
+//! is rewritten.
+//!
+//! ```text
+//! # test fixtures
+//! 9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08 expires=2025-12-31 reason="sandbox key"
+//! ```
+//!
+//! An entry stays active through its expiry date. Expired entries are ignored by
+//! [`IgnoreFile::active_hashes`] rather than silently honoured forever, so a test
+//! token that gets promoted to production is redacted again.
+
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// The file name looked up in the working directory.
+pub const IGNORE_FILE_NAME: &str = ".cleanshignore";
+
+/// The hash an ignore entry uses to identify `value`.
+pub fn ignore_hash(value: &str) -> String {
+    hex::encode(Sha256::digest(value.as_bytes()))
+}
+
+/// One ignored hash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IgnoreEntry {
+    pub hash: String,
+    /// Last day the entry applies; `None` never expires.
+    pub expires: Option<NaiveDate>,
+    pub reason: Option<String>,
+}
+
+impl IgnoreEntry {
+    /// Whether the entry no longer applies on `today`.
+    pub fn is_expired(&self, today: NaiveDate) -> bool {
+        self.expires.is_some_and(|expires| expires < today)
+    }
+
+    /// The entry as a line of the ignore file.
+    pub fn to_line(&self) -> String {
+        let mut line = self.hash.clone();
+        if let Some(expires) = self.expires {
+            let _ = write!(line, " expires={}", expires);
+        }
+        if let Some(reason) = &self.reason {
+            let _ = write!(line, " reason=\"{}\"", reason.replace('\\', "\\\\").replace('"', "\\\""));
+        }
+        line
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Line {
+    Entry(IgnoreEntry),
+    /// A comment or blank line, kept as written.
+    Other(String),
+}
+
+/// A parsed ignore file that can be edited and written back without losing comments.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreFile {
+    lines: Vec<Line>,
+}
+
+/// Splits `key=value` pairs, where a value may be double-quoted with `\"` and `\\` escapes.
+fn parse_attributes(rest: &str) -> Result<Vec<(String, String)>> {
+    let mut attributes = Vec::new();
+    let mut chars = rest.chars().peekable();
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        if chars.peek().is_none() {
+            return Ok(attributes);
+        }
+        let key: String = std::iter::from_fn(|| chars.next_if(|&c| c != '=' && !c.is_whitespace())).collect();
+        if chars.next() != Some('=') {
+            bail!("expected `key=value`, found '{}'", key);
+        }
+        let mut value = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => value.extend(chars.next()),
+                    Some(c) => value.push(c),
+                    None => bail!("unterminated quote in '{}' value", key),
+                }
+            }
+        } else {
+            value.extend(std::iter::from_fn(|| chars.next_if(|c| !c.is_whitespace())));
+        }
+        attributes.push((key, value));
+    }
+}
+
+fn parse_entry(line: &str) -> Result<IgnoreEntry> {
+    let (hash, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("'{}' is not a 64-character hex SHA-256 hash", hash);
+    }
+    let mut entry = IgnoreEntry { hash: hash.to_ascii_lowercase(), expires: None, reason: None };
+    for (key, value) in parse_attributes(rest)? {
+        match key.as_str() {
+            "expires" => {
+                let date = NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+                    .with_context(|| format!("invalid expiry date '{}' (expected YYYY-MM-DD)", value))?;
+                entry.expires = Some(date);
+            }
+            "reason" => entry.reason = Some(value),
+            other => bail!("unknown attribute '{}'", other),
+        }
+    }
+    Ok(entry)
+}
+
+impl IgnoreFile {
+    /// Parses ignore-file text. Errors name the offending line.
+    pub fn parse(text: &str) -> Result<Self> {
+        let lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, raw)| {
+                let trimmed = raw.trim();
+                if trimmed.is_empty() || trimmed.starts_with('#') {
+                    Ok(Line::Other(raw.to_string()))
+                } else {
+                    parse_entry(trimmed).map(Line::Entry).with_context(|| format!("line {}", i + 1))
+                }
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { lines })
+    }
+
+    /// Reads `path`; a missing file is an empty ignore list.
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).with_context(|| format!("Invalid ignore file {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read ignore file {}", path.display())),
+        }
+    }
+
+    /// Writes the file back, comments included.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_string()).with_context(|| format!("Failed to write ignore file {}", path.display()))
+    }
+
+    /// Every entry, in file order.
+    pub fn entries(&self) -> impl Iterator<Item = &IgnoreEntry> {
+        self.lines.iter().filter_map(|line| match line {
+            Line::Entry(entry) => Some(entry),
+            Line::Other(_) => None,
+        })
+    }
+
+    /// The hashes still in effect on `today`, and the entries that have expired.
+    pub fn active_hashes(&self, today: NaiveDate) -> (HashSet<String>, Vec<&IgnoreEntry>) {
+        let (expired, active): (Vec<&IgnoreEntry>, Vec<&IgnoreEntry>) =
+            self.entries().partition(|entry| entry.is_expired(today));
+        (active.into_iter().map(|entry| entry.hash.clone()).collect(), expired)
+    }
+
+    /// Adds `entry`, replacing an existing entry for the same hash in place.
+    /// Returns whether an entry was replaced.
+    pub fn add(&mut self, entry: IgnoreEntry) -> bool {
+        for line in &mut self.lines {
+            if let Line::Entry(existing) = line {
+                if existing.hash == entry.hash {
+                    *existing = entry;
+                    return true;
+                }
+            }
+        }
+        self.lines.push(Line::Entry(entry));
+        false
+    }
+
+    /// Removes the entry for `hash` and returns it. Comments are kept.
+    pub fn remove(&mut self, hash: &str) -> Option<IgnoreEntry> {
+        let hash = hash.to_ascii_lowercase();
+        let index = self.lines.iter().position(|line| matches!(line, Line::Entry(entry) if entry.hash == hash))?;
+        match self.lines.remove(index) {
+            Line::Entry(entry) => Some(entry),
+            Line::Other(_) => None,
+        }
+    }
+
+    /// Removes entries expired on `today` and returns them. Comments are kept.
+    pub fn prune(&mut self, today: NaiveDate) -> Vec<IgnoreEntry> {
+        let mut removed = Vec::new();
+        self.lines.retain(|line| match line {
+            Line::Entry(entry) if entry.is_expired(today) => {
+                removed.push(entry.clone());
+                false
+            }
+            _ => true,
+        });
+        removed
+    }
+}
+
+impl std::fmt::Display for IgnoreFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            match line {
+                Line::Entry(entry) => writeln!(f, "{}", entry.to_line())?,
+                Line::Other(raw) => writeln!(f, "{}", raw)?,
+            }
+        }
+        Ok(())
+    }
+}
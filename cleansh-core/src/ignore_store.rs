@@ -0,0 +1,94 @@
+// cleansh-core/src/ignore_store.rs
+//! The user's "never flag this again" list: canonical sample hashes (see
+//! [`canonical_sample_hash`]) kept as a JSON array in `ignored_hashes.json`.
+//!
+//! Unlike the `.cleanshignore` file, which holds plain value hashes per project,
+//! an entry here is scoped to the rule that matched and is added from the TUI.
+//! An [`IgnoreStore`] is a shared handle: engines holding a clone see hashes
+//! added after they were built, so a secret stays quiet from the next line on.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, PoisonError, RwLock};
+
+use crate::redaction_match::canonical_sample_hash;
+
+/// The file name used in the user's cleansh config directory.
+pub const IGNORE_STORE_FILE_NAME: &str = "ignored_hashes.json";
+
+/// A shared, optionally file-backed set of ignored sample hashes.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreStore {
+    /// Where changes are written; `None` keeps them in memory.
+    path: Option<PathBuf>,
+    hashes: Arc<RwLock<BTreeSet<String>>>,
+}
+
+impl IgnoreStore {
+    /// Reads the store at `path`; a missing file is an empty store that is
+    /// created on the first insert.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let hashes = match fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str::<BTreeSet<String>>(&text)
+                .with_context(|| format!("Invalid ignore store {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeSet::new(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read ignore store {}", path.display())),
+        };
+        Ok(Self { path: Some(path), hashes: Arc::new(RwLock::new(hashes)) })
+    }
+
+    /// The file this store writes to, if any.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.read().unwrap_or_else(PoisonError::into_inner).is_empty()
+    }
+
+    /// Every stored hash, sorted.
+    pub fn hashes(&self) -> Vec<String> {
+        self.hashes.read().unwrap_or_else(PoisonError::into_inner).iter().cloned().collect()
+    }
+
+    pub fn contains(&self, sample_hash: &str) -> bool {
+        self.hashes.read().unwrap_or_else(PoisonError::into_inner).contains(sample_hash)
+    }
+
+    /// Whether a match of `rule_name` on `value` is ignored.
+    pub fn contains_match(&self, rule_name: &str, value: &str) -> bool {
+        !self.is_empty() && self.contains(&canonical_sample_hash(rule_name, value))
+    }
+
+    /// Adds `sample_hash` and writes the store. Returns whether it was new.
+    pub fn insert(&self, sample_hash: &str) -> Result<bool> {
+        let mut hashes = self.hashes.write().unwrap_or_else(PoisonError::into_inner);
+        if !hashes.insert(sample_hash.to_ascii_lowercase()) {
+            return Ok(false);
+        }
+        self.save(&hashes)?;
+        Ok(true)
+    }
+
+    /// Removes `sample_hash` and writes the store. Returns whether it was present.
+    pub fn remove(&self, sample_hash: &str) -> Result<bool> {
+        let mut hashes = self.hashes.write().unwrap_or_else(PoisonError::into_inner);
+        if !hashes.remove(&sample_hash.to_ascii_lowercase()) {
+            return Ok(false);
+        }
+        self.save(&hashes)?;
+        Ok(true)
+    }
+
+    fn save(&self, hashes: &BTreeSet<String>) -> Result<()> {
+        let Some(path) = &self.path else { return Ok(()) };
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let json = serde_json::to_string_pretty(hashes).context("Failed to serialize the ignore store")?;
+        fs::write(path, json).with_context(|| format!("Failed to write ignore store {}", path.display()))
+    }
+}
@@ -18,15 +18,23 @@
 //!
 //! ## Modules
 //!
+//! * `client`: `CleanshClient`, the high-level builder API for embedding cleansh.
 //! * `config`: Defines `RedactionRule`s and `RedactionConfig` for specifying sensitive patterns.
+//! * `corpus`: Runs snapshot corpora of paired input and expected files to catch detection regressions.
+//! * `crash_report`: A panic hook that scrubs secrets from panic messages and writes sanitized crash reports.
+//! * `cli_flags`: Finds secrets passed as command-line flag values (`-p<password>`, `--token=...`).
 //! * `sanitizers`: Contains engine-specific logic for compiling rules.
 //! * `validators`: Provides programmatic validation for specific data types.
 //! * `redaction_match`: Defines data structures for detailed reporting of redaction events.
 //! * `engine`: Defines the `SanitizationEngine` trait, enabling a modular design.
+//! * `engine_handle`: A swappable, shareable reference to the active engine.
+//! * `ignore_file`: Parses `.cleanshignore`, the list of match hashes (with optional expiry) to leave unredacted.
 //! * `profiles`: Defines data structures for user-specified profiles and post-processing.
 //! * `audit_log`: Defines the structure and logic for writing redaction events to a log file.
 //! * `engines`: Contains concrete implementations of the `SanitizationEngine` trait.
+//! * `explain`: Decision traces recording why the engines kept or dropped each candidate.
 //! * `headless`: Convenience wrappers for using core engines in a non-interactive mode.
+//! * `lossy`: Decodes input that is not valid UTF-8, keeping the replaced bytes for output.
 //! * `remediation`: **(v0.2.0)** The Self-Healing framework, including providers and orchestrators.
 //!
 //! ## Usage Example (Proactive Healing)
@@ -34,23 +42,23 @@
 //! ```rust
 //! use cleansh_core::{RedactionConfig, EntropyEngine, HeadlessEngineType, SanitizationEngine}; // <--- Fixed: Added SanitizationEngine trait import
 //! use cleansh_core::remediation::orchestrator::SelfHealingEngine;
+//! use cleansh_core::remediation::providers::{build_providers, ProviderSettings, ProvidersConfig};
 //! use tokio::sync::mpsc;
 //! use std::sync::Arc;
 //!
 //! #[tokio::main]
 //! async fn main() -> anyhow::Result<()> {
 //!     let config = RedactionConfig::load_default_rules()?;
-//!     let mut engine = EntropyEngine::new(config)?;
 //!
-//!     // 1. Setup the Remediation Channel
+//!     // 1. Setup the Remediation Channel and wire it in when building the engine
 //!     let (tx, rx) = mpsc::channel(100);
-//!     
-//!     // This method requires the SanitizationEngine trait to be in scope
-//!     engine.set_remediation_tx(tx);
+//!     let engine = EntropyEngine::new(config)?.with_remediation_tx(tx);
+//!     let _ = engine.find_matches_for_ui("no secrets here", "doc")?;
 //!
-//!     // 2. Initialize the Self-Healing Orchestrator
+//!     // 2. Initialize the Self-Healing Orchestrator with the configured providers
 //!     // We wrap it in an Arc as required by the 'listen' method for async safety.
-//!     let orchestrator = Arc::new(SelfHealingEngine::new(vec![], None, 5, true, vec![0u8; 32]));
+//!     let providers = build_providers(&ProvidersConfig::default().with_provider("github", ProviderSettings::default()))?;
+//!     let orchestrator = Arc::new(SelfHealingEngine::new(providers, None, 5, true, vec![0u8; 32], false));
 //!     
 //!     // 3. Start the background listener
 //!     orchestrator.listen(rx);
@@ -64,30 +72,60 @@
 
 // Module declarations
 pub mod audit_log;
+pub mod cli_flags;
+pub mod client;
 pub mod config;
+pub mod config_format;
+pub mod corpus;
+pub mod crash_report;
 pub mod engine;
+pub mod engine_handle;
 pub mod engines;
+pub mod explain;
 pub mod headless;
+pub mod ignore_file;
+pub mod ignore_store;
+pub mod lossy;
 pub mod profiles;
 pub mod redaction_match;
 pub mod sanitizers;
+pub mod severity;
 pub mod validators;
 pub mod errors;
+pub mod metrics;
 pub mod remediation;
 
+/// Seeded corpora shared by the benchmarks and `cleansh bench`.
+#[cfg(feature = "bench")]
+pub use cleansh_entropy::bench_support;
+
 // Re-exports
 pub use config::{
+    append_rule,
+    load_saved_entropy_threshold,
     merge_rules,
+    rule_pack_names,
     RedactionConfig,
     RedactionRule,
     RedactionSummaryItem,
     RuleConfigNotFoundError,
+    save_entropy_threshold,
+    BUILTIN_PACK_PREFIX,
+    MAX_INCLUDE_DEPTH,
     MAX_PATTERN_LENGTH,
 };
+pub use client::{CleanshClient, CleanshClientBuilder, EngineKind, DEFAULT_CLIENT_SOURCE_ID};
+pub use config_format::ConfigFormat;
+pub use crash_report::install_safe_panic_hook;
 pub use errors::CleanshError;
-pub use engine::SanitizationEngine;
+pub use severity::Severity;
+pub use metrics::{DecodeMetrics, DecodeMetricsSnapshot, EngineMetrics, EngineMetricsSnapshot, RuleMetrics};
+pub use lossy::LossyText;
+pub use engine::{SanitizationEngine, SanitizeContext};
+pub use engine_handle::EngineHandle;
 pub use engines::regex_engine::RegexEngine;
-pub use engines::entropy_engine::EntropyEngine;
+pub use engines::entropy_engine::{EntropyEngine, StreamProfile};
+pub use engines::composite_engine::CompositeEngine;
 pub use redaction_match::{RedactionLog, RedactionMatch, redact_sensitive};
 pub use profiles::{
     apply_profile_to_config,
@@ -96,8 +134,11 @@ pub use profiles::{
     EngineOptions,
     format_token,
     load_profile_by_name,
+    load_profile_with_keys,
     PostProcessingConfig,
     ProfileConfig,
+    ProfileEngine,
+    ProfileKeys,
     ProfileRule,
     profile_candidate_paths,
     ReportingConfig,
@@ -105,8 +146,12 @@ pub use profiles::{
     sample_score_hex,
     select_samples_for_rule,
 };
-pub use audit_log::AuditLog;
-pub use headless::{headless_sanitize_string, HeadlessEngineType};
+pub use audit_log::{
+    decrypt_audit_log, migrate_audit_log, read_audit_log, verify_chain, AuditFile, AuditFormatVersion, AuditHeader,
+    AuditKey, AuditKeySource, AuditLog, AuditLogConfig, ChainSeal, DecryptSummary, AUDIT_FORMAT_VERSION, DEFAULT_AUDIT_KEY_ENV,
+};
+pub use headless::{headless_sanitize_bytes, headless_sanitize_file, headless_sanitize_string, headless_sanitize_with_report, HeadlessEngineType, SanitizeReport};
+pub use sanitizers::block::BlockAssembler;
 pub use sanitizers::compiler::{compile_rules, CompiledRule, CompiledRules};
 
 // Remediation re-exports for easy access
@@ -0,0 +1,97 @@
+// cleansh-core/src/lossy.rs
+//! Input that is not valid UTF-8.
+//!
+//! Logs with mixed encodings or binary noise are still scanned: [`LossyText`]
+//! replaces each invalid sequence with U+FFFD, as `String::from_utf8_lossy` does,
+//! but remembers the bytes it replaced so sanitized output can carry them again
+//! wherever no redaction covers them.
+
+use std::ops::Range;
+
+use crate::engines::index_map::replacement_for;
+use crate::redaction_match::RedactionMatch;
+
+/// Decoded input, with the invalid sequences it replaced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LossyText {
+    /// The input with every invalid sequence replaced by U+FFFD.
+    pub text: String,
+    /// Where each U+FFFD standing for an invalid sequence sits in `text`, and the
+    /// sequence's bytes.
+    invalid: Vec<(usize, Vec<u8>)>,
+}
+
+impl LossyText {
+    pub fn new(bytes: &[u8]) -> Self {
+        let mut text = String::with_capacity(bytes.len());
+        let mut invalid = Vec::new();
+        for chunk in bytes.utf8_chunks() {
+            text.push_str(chunk.valid());
+            if !chunk.invalid().is_empty() {
+                invalid.push((text.len(), chunk.invalid().to_vec()));
+                text.push(char::REPLACEMENT_CHARACTER);
+            }
+        }
+        Self { text, invalid }
+    }
+
+    /// Whether the input was valid UTF-8, so `text` is exactly its bytes.
+    pub fn is_lossless(&self) -> bool {
+        self.invalid.is_empty()
+    }
+
+    /// Invalid bytes of the input that were replaced.
+    pub fn replaced_bytes(&self) -> usize {
+        self.invalid.iter().map(|(_, bytes)| bytes.len()).sum()
+    }
+
+    /// Appends `self.text[range]` to `out` as the input's bytes, with the invalid
+    /// sequences put back.
+    fn push_original(&self, out: &mut Vec<u8>, range: Range<usize>) {
+        let mut at = range.start;
+        let first = self.invalid.partition_point(|(index, _)| *index < range.start);
+        for (index, bytes) in self.invalid[first..].iter().take_while(|(index, _)| *index < range.end) {
+            out.extend_from_slice(&self.text.as_bytes()[at..*index]);
+            out.extend_from_slice(bytes);
+            at = index + char::REPLACEMENT_CHARACTER.len_utf8();
+        }
+        out.extend_from_slice(&self.text.as_bytes()[at..range.end]);
+    }
+
+    /// The output of sanitizing `text`, rebuilt as bytes from the matches the engine
+    /// applied, in the order it applied them: each redaction as the engine wrote it,
+    /// and the input's own bytes, invalid sequences included, everywhere else. An
+    /// invalid sequence inside a redacted span goes with the rest of the span.
+    pub fn restore(&self, applied: &[RedactionMatch]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.text.len());
+        let mut last_end = 0usize;
+        for m in applied {
+            let Range { start, end } = m.document_span();
+            if end <= last_end || end > self.text.len() {
+                continue;
+            }
+            let start = start.max(last_end);
+            self.push_original(&mut out, last_end..start);
+            out.extend_from_slice(replacement_for(&self.text, start..end, &m.sanitized_string).as_bytes());
+            last_end = end;
+        }
+        self.push_original(&mut out, last_end..self.text.len());
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_sequences_are_replaced_and_remembered() {
+        let lossy = LossyText::new(b"caf\xe9 ok \xff\xfe end");
+        assert_eq!(lossy.text, "caf\u{FFFD} ok \u{FFFD}\u{FFFD} end");
+        assert_eq!(lossy.replaced_bytes(), 3);
+        assert!(!lossy.is_lossless());
+        // Nothing applied: the input comes back byte for byte.
+        assert_eq!(lossy.restore(&[]), b"caf\xe9 ok \xff\xfe end");
+        assert!(LossyText::new("naïve".as_bytes()).is_lossless());
+    }
+}
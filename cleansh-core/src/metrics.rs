@@ -0,0 +1,205 @@
+// cleansh-core/src/metrics.rs
+//! Lock-free runtime counters shared by the sanitization engines.
+//!
+//! Every engine owns an [`EngineMetrics`] and bumps it from the scan loop with
+//! relaxed atomic increments only; rule slots are allocated once at construction,
+//! so recording never allocates. [`EngineMetrics::snapshot`] copies the counters
+//! into a plain [`EngineMetricsSnapshot`] for display.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use tokio::sync::mpsc::error::TrySendError;
+
+use crate::lossy::LossyText;
+
+#[derive(Debug)]
+struct RuleCounters {
+    name: String,
+    matches: AtomicU64,
+    nanos: AtomicU64,
+    timeouts: AtomicU64,
+}
+
+/// Live counters for one engine instance.
+#[derive(Debug)]
+pub struct EngineMetrics {
+    started: Instant,
+    bytes_scanned: AtomicU64,
+    lines_scanned: AtomicU64,
+    remediation_sends: AtomicU64,
+    dropped_sends: AtomicU64,
+    coalesced_sends: AtomicU64,
+    rules: Vec<RuleCounters>,
+}
+
+impl EngineMetrics {
+    /// Creates counters with one slot per rule, addressed by position in `rule_names`.
+    pub fn new<I, S>(rule_names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            started: Instant::now(),
+            bytes_scanned: AtomicU64::new(0),
+            lines_scanned: AtomicU64::new(0),
+            remediation_sends: AtomicU64::new(0),
+            dropped_sends: AtomicU64::new(0),
+            coalesced_sends: AtomicU64::new(0),
+            rules: rule_names
+                .into_iter()
+                .map(|name| RuleCounters {
+                    name: name.into(),
+                    matches: AtomicU64::new(0),
+                    nanos: AtomicU64::new(0),
+                    timeouts: AtomicU64::new(0),
+                })
+                .collect(),
+        }
+    }
+
+    /// Records one pass over `content`. A trailing line without a newline still counts.
+    pub fn record_scan(&self, content: &[u8]) {
+        let newlines = content.iter().filter(|&&b| b == b'\n').count() as u64;
+        let lines = newlines + u64::from(content.last().is_some_and(|&b| b != b'\n'));
+        self.bytes_scanned.fetch_add(content.len() as u64, Ordering::Relaxed);
+        self.lines_scanned.fetch_add(lines, Ordering::Relaxed);
+    }
+
+    /// Adds `matches` and `elapsed` to the rule at `index`. Unknown indices are ignored.
+    pub fn record_rule(&self, index: usize, matches: u64, elapsed: Duration) {
+        if let Some(rule) = self.rules.get(index) {
+            rule.matches.fetch_add(matches, Ordering::Relaxed);
+            rule.nanos.fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// Records that the rule at `index` ran out of its time budget on one input.
+    pub fn record_timeout(&self, index: usize) {
+        if let Some(rule) = self.rules.get(index) {
+            rule.timeouts.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Records the outcome of a `try_send` to the remediation channel.
+    pub fn record_send<T>(&self, result: &Result<(), TrySendError<T>>) {
+        self.record_delivery(result.is_ok());
+    }
+
+    /// Records a match handed to the remediation channel (or its queue), or dropped.
+    pub fn record_delivery(&self, delivered: bool) {
+        let counter = if delivered { &self.remediation_sends } else { &self.dropped_sends };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a match merged into an identical one still waiting to be delivered.
+    pub fn record_coalesced(&self) {
+        self.coalesced_sends.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> EngineMetricsSnapshot {
+        EngineMetricsSnapshot {
+            bytes_scanned: self.bytes_scanned.load(Ordering::Relaxed),
+            lines_scanned: self.lines_scanned.load(Ordering::Relaxed),
+            remediation_sends: self.remediation_sends.load(Ordering::Relaxed),
+            dropped_sends: self.dropped_sends.load(Ordering::Relaxed),
+            coalesced_sends: self.coalesced_sends.load(Ordering::Relaxed),
+            elapsed: self.started.elapsed(),
+            rules: self
+                .rules
+                .iter()
+                .map(|rule| RuleMetrics {
+                    rule_name: rule.name.clone(),
+                    matches: rule.matches.load(Ordering::Relaxed),
+                    time_spent: Duration::from_nanos(rule.nanos.load(Ordering::Relaxed)),
+                    timeouts: rule.timeouts.load(Ordering::Relaxed),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Invalid UTF-8 replaced while reading input. Readers decode lines before any
+/// engine sees them, so these are kept apart from [`EngineMetrics`] and survive
+/// switching engines.
+#[derive(Debug, Default)]
+pub struct DecodeMetrics {
+    lines: AtomicU64,
+    replaced_bytes: AtomicU64,
+}
+
+impl DecodeMetrics {
+    /// Records one decoded line; valid UTF-8 is not counted.
+    pub fn record(&self, line: &LossyText) {
+        if !line.is_lossless() {
+            self.lines.fetch_add(1, Ordering::Relaxed);
+            self.replaced_bytes.fetch_add(line.replaced_bytes() as u64, Ordering::Relaxed);
+        }
+    }
+
+    pub fn snapshot(&self) -> DecodeMetricsSnapshot {
+        DecodeMetricsSnapshot {
+            lines: self.lines.load(Ordering::Relaxed),
+            replaced_bytes: self.replaced_bytes.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A point-in-time copy of [`DecodeMetrics`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DecodeMetricsSnapshot {
+    /// Lines that held invalid UTF-8.
+    pub lines: u64,
+    /// Invalid bytes replaced with U+FFFD across those lines.
+    pub replaced_bytes: u64,
+}
+
+/// Per-rule totals within a snapshot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleMetrics {
+    pub rule_name: String,
+    pub matches: u64,
+    pub time_spent: Duration,
+    /// Inputs on which the rule was stopped for exceeding `engines.rule_timeout_ms`.
+    pub timeouts: u64,
+}
+
+/// A point-in-time copy of an engine's counters.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EngineMetricsSnapshot {
+    pub bytes_scanned: u64,
+    pub lines_scanned: u64,
+    /// Matches handed to the remediation channel, or queued for it in `Block` and
+    /// `Coalesce` mode.
+    pub remediation_sends: u64,
+    /// Matches dropped because the remediation channel was full or closed.
+    pub dropped_sends: u64,
+    /// Matches merged into an identical one still queued (`Coalesce` mode).
+    pub coalesced_sends: u64,
+    /// Time since the engine was created.
+    pub elapsed: Duration,
+    pub rules: Vec<RuleMetrics>,
+}
+
+impl EngineMetricsSnapshot {
+    pub fn total_matches(&self) -> u64 {
+        self.rules.iter().map(|rule| rule.matches).sum()
+    }
+
+    /// Average throughput since the engine was created.
+    pub fn lines_per_sec(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 { self.lines_scanned as f64 / secs } else { 0.0 }
+    }
+
+    /// The rules that were stopped on at least one input for exceeding their time budget.
+    pub fn timed_out_rules(&self) -> impl Iterator<Item = &RuleMetrics> {
+        self.rules.iter().filter(|rule| rule.timeouts > 0)
+    }
+
+    /// Looks up a rule's totals by name.
+    pub fn rule(&self, name: &str) -> Option<&RuleMetrics> {
+        self.rules.iter().find(|rule| rule.rule_name == name)
+    }
+}
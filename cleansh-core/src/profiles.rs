@@ -19,16 +19,30 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::collections::{HashSet, HashMap};
+use std::sync::{Arc, OnceLock};
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
 use hex;
 use tinytemplate::TinyTemplate;
 use log::{debug, warn};
 use chrono::NaiveDate;
+use regex::RegexSet;
 use serde_yml::Value; 
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{Ed25519KeyPair, UnparsedPublicKey, ED25519};
 
-use crate::config::{RedactionConfig, RedactionRule};
-use crate::redaction_match::RedactionMatch;
+use crate::cli_flags::{merge_cli_flag_rules, validate_cli_flag_rules, CliFlagRule};
+use crate::config::{EngineConfig, EntropyConfig, RedactionConfig, RedactionRule, RedactionSummaryItem};
+use crate::config_format::{ConfigFormat, CONFIG_EXTENSIONS};
+use crate::engines::entropy_engine::StreamProfile;
+use crate::explain::{Decision, ExplainLog, ExplainRecord, ExplainSink};
+use crate::ignore_file::ignore_hash;
+use crate::ignore_store::IgnoreStore;
+use crate::remediation::state::RemediationStateMap;
+use crate::redaction_match::{offset_from_index, RedactionMatch};
+use crate::severity::Severity;
+use crate::remediation::channel::RemediationTxMode;
 
 type HmacSha256 = Hmac<Sha256>;
 
@@ -36,6 +50,27 @@ type HmacSha256 = Hmac<Sha256>;
 // This replaces the usage of dynamic strings as cryptographic keys, resolving CodeQL security alerts.
 const SEED_GENERATION_SALT: &[u8] = b"cleansh-run-seed-generation-v1-salt";
 
+/// How many `extends` links a profile chain may follow before loading gives up.
+pub const MAX_PROFILE_DEPTH: usize = 8;
+
+/// Token template used when `post_processing.replace_with_token` is set without a `token_format`.
+pub const DEFAULT_TOKEN_FORMAT: &str = "[{rule}:{shorthash}]";
+
+/// The values a profile's `engine.type` may take.
+pub const PROFILE_ENGINE_TYPES: [&str; 3] = ["regex", "entropy", "hybrid"];
+
+/// A profile's `engine` section, e.g. `engine: { type: hybrid, entropy: { threshold: 0.8 } }`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct ProfileEngine {
+    /// One of [`PROFILE_ENGINE_TYPES`]; unset leaves the choice to the command line.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub engine_type: Option<String>,
+    /// Entropy settings (`threshold`, `window_size`, ...) applied over `engines.entropy`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entropy: Option<EntropyConfig>,
+}
+
 /// The top-level structure representing a redaction profile configuration.
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "snake_case", default)]
@@ -55,6 +90,19 @@ pub struct ProfileConfig {
     pub dedupe: Option<DedupeConfig>,
     pub post_processing: Option<PostProcessingConfig>,
     pub reporting: Option<ReportingConfig>,
+    /// Engine overrides layered on top of the active configuration (e.g. entropy keywords).
+    pub engines: Option<EngineConfig>,
+    /// The engine the profile must run on, and its settings.
+    pub engine: Option<ProfileEngine>,
+    /// Keep only rules carrying at least one of these tags (see `set_active_rules_by_tags`).
+    pub include_tags: Option<Vec<String>>,
+    /// Drop rules carrying any of these tags.
+    pub exclude_tags: Option<Vec<String>>,
+    /// Extra `cli_flag_secrets` entries; an entry named like an existing one replaces it.
+    pub cli_flag_secrets: Option<Vec<CliFlagRule>>,
+    /// Name or path of a parent profile whose settings this one builds on. A relative
+    /// path is resolved against this file's directory first.
+    pub extends: Option<String>,
 }
 
 impl ProfileConfig {
@@ -71,6 +119,20 @@ impl ProfileConfig {
             }
         }
 
+        self.deduplicated_rules()?;
+
+        if let Some(engine_type) = self.engine.as_ref().and_then(|e| e.engine_type.as_deref()) {
+            if !PROFILE_ENGINE_TYPES.contains(&engine_type) {
+                bail!("Profile '{}' validation failed: unknown engine type '{}'. Expected one of: {}.",
+                    self.profile_name, engine_type, PROFILE_ENGINE_TYPES.join(", "));
+            }
+        }
+
+        if let Some(cli_flags) = &self.cli_flag_secrets {
+            validate_cli_flag_rules(cli_flags)
+                .with_context(|| format!("Profile '{}' validation failed", self.profile_name))?;
+        }
+
         if let Some(samples) = &self.samples {
             if samples.max_per_rule == 0 {
                 bail!("Profile '{}' validation failed: 'samples.max_per_rule' must be greater than 0.", self.profile_name);
@@ -89,50 +151,367 @@ impl ProfileConfig {
         Ok(())
     }
 
-    /// Verifies the HMAC-SHA256 signature of the profile against the provided secret key.
+    /// The engine the profile demands through `engine.type`, if any.
+    pub fn engine_type(&self) -> Option<&str> {
+        self.engine.as_ref()?.engine_type.as_deref()
+    }
+
+    /// Returns the rule overrides with repeated entries collapsed.
+    ///
+    /// A rule listed more than once with identical values is collapsed with a warning.
+    /// A rule listed more than once with different `enabled`/`severity` values is an
+    /// error that names every conflicting entry.
+    pub fn deduplicated_rules(&self) -> Result<Vec<ProfileRule>> {
+        let (rules, duplicates) = self.normalize_rules();
+        let mut conflicts = Vec::new();
+        for (name, entries) in duplicates {
+            let first = &self.rules[entries[0]];
+            if entries.iter().all(|&i| self.rules[i] == *first) {
+                warn!("Profile '{}': rule '{}' is listed {} times with identical values; collapsing.",
+                    self.profile_name, name, entries.len());
+            } else {
+                let listed: Vec<String> = entries.iter()
+                    .map(|&i| format!("  entry #{}: enabled={:?}, severity={:?}, tags={:?}",
+                        i + 1, self.rules[i].enabled, self.rules[i].severity, self.rules[i].tags))
+                    .collect();
+                conflicts.push(format!("rule '{}' has conflicting overrides:\n{}", name, listed.join("\n")));
+            }
+        }
+        if !conflicts.is_empty() {
+            bail!("Profile '{}' validation failed: {}", self.profile_name, conflicts.join("\n"));
+        }
+        Ok(rules)
+    }
+
+    /// Collapses overrides by rule name, in order of first appearance. When entries
+    /// disagree the last one wins. Also returns the entry indices of every rule that
+    /// appears more than once.
+    fn normalize_rules(&self) -> (Vec<ProfileRule>, Vec<(String, Vec<usize>)>) {
+        let mut order: Vec<&str> = Vec::new();
+        let mut positions: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (i, rule) in self.rules.iter().enumerate() {
+            let entry = positions.entry(rule.name.as_str()).or_default();
+            if entry.is_empty() {
+                order.push(rule.name.as_str());
+            }
+            entry.push(i);
+        }
+
+        let mut rules = Vec::with_capacity(order.len());
+        let mut duplicates = Vec::new();
+        for name in order {
+            let entries = &positions[name];
+            rules.push(self.rules[*entries.last().unwrap()].clone());
+            if entries.len() > 1 {
+                duplicates.push((name.to_string(), entries.clone()));
+            }
+        }
+        (rules, duplicates)
+    }
+
+    /// Flags overrides that do nothing because they restate the rule's default
+    /// `enabled` or `severity`. Used by `--lint-profile`; these are not errors.
+    pub fn lint(&self, default_config: &RedactionConfig) -> Vec<String> {
+        let defaults: HashMap<&str, &RedactionRule> = default_config.rules.iter()
+            .map(|r| (r.name.as_str(), r))
+            .collect();
+        let (rules, _) = self.normalize_rules();
+
+        let mut findings = Vec::new();
+        for rule_override in &rules {
+            let Some(default_rule) = defaults.get(rule_override.name.as_str()) else { continue };
+            let default_enabled = default_rule.enabled.unwrap_or(!default_rule.opt_in);
+            if rule_override.enabled == Some(default_enabled) {
+                findings.push(format!("Profile '{}': rule '{}' sets enabled={}, which is already the default.",
+                    self.profile_name, rule_override.name, default_enabled));
+            }
+            if rule_override.severity.is_some() && rule_override.severity == default_rule.severity {
+                findings.push(format!("Profile '{}': rule '{}' sets severity='{}', which is already the default.",
+                    self.profile_name, rule_override.name, rule_override.severity.as_deref().unwrap_or_default()));
+            }
+        }
+        findings
+    }
+
+    /// Layers `self` on top of `parent`.
+    ///
+    /// Rule overrides are merged per rule and per field, so a child that only sets
+    /// `severity` keeps the parent's `enabled`. `samples`, `dedupe`,
+    /// `post_processing`, `reporting`, and the tag filters are taken whole from the
+    /// child when it sets them; engine settings and `cli_flag_secrets` entries are
+    /// overlaid, and a child's `engine.type` replaces the parent's. Identity and
+    /// signature fields are the child's own.
+    pub fn compose_over(self, parent: ProfileConfig) -> Result<ProfileConfig> {
+        let mut rules = parent.deduplicated_rules()?;
+        for rule in self.deduplicated_rules()? {
+            match rules.iter_mut().find(|r| r.name == rule.name) {
+                Some(inherited) => {
+                    inherited.enabled = rule.enabled.or(inherited.enabled);
+                    inherited.severity = rule.severity.or(inherited.severity.take());
+                    inherited.tags = rule.tags.or(inherited.tags.take());
+                }
+                None => rules.push(rule),
+            }
+        }
+
+        let engines = match (parent.engines, self.engines) {
+            (Some(mut inherited), Some(own)) => {
+                inherited.merge_from(&own);
+                Some(inherited)
+            }
+            (inherited, own) => own.or(inherited),
+        };
+
+        let engine = match (parent.engine, self.engine) {
+            (Some(inherited), Some(own)) => {
+                let entropy = match (inherited.entropy, own.entropy) {
+                    (Some(mut inherited), Some(own)) => {
+                        inherited.merge_from(&own);
+                        Some(inherited)
+                    }
+                    (inherited, own) => own.or(inherited),
+                };
+                Some(ProfileEngine { engine_type: own.engine_type.or(inherited.engine_type), entropy })
+            }
+            (inherited, own) => own.or(inherited),
+        };
+
+        let cli_flag_secrets = match (parent.cli_flag_secrets, self.cli_flag_secrets) {
+            (Some(mut inherited), Some(own)) => {
+                merge_cli_flag_rules(&mut inherited, &own);
+                Some(inherited)
+            }
+            (inherited, own) => own.or(inherited),
+        };
+
+        Ok(ProfileConfig {
+            version: if self.version.trim().is_empty() { parent.version } else { self.version },
+            rules,
+            samples: self.samples.or(parent.samples),
+            dedupe: self.dedupe.or(parent.dedupe),
+            post_processing: self.post_processing.or(parent.post_processing),
+            reporting: self.reporting.or(parent.reporting),
+            engines,
+            engine,
+            include_tags: self.include_tags.or(parent.include_tags),
+            exclude_tags: self.exclude_tags.or(parent.exclude_tags),
+            cli_flag_secrets,
+            ..self
+        })
+    }
+
+    /// Verifies the profile's signature with the matching key from `keys`.
     ///
     /// This method is crucial for ensuring the integrity and authenticity of a profile
-    /// loaded from disk. It recalculates the signature from the profile's content
-    /// (excluding the signature field itself) and compares it with the stored signature.
-    /// The `raw_bytes` argument is the full content of the YAML file.
+    /// loaded from disk. It recalculates the signature input from the profile's content
+    /// (excluding the signature fields themselves) and checks the stored signature:
+    /// `hmac-sha256` against the shared secret, `ed25519` against any trusted public key.
+    ///
+    /// Returns `Ok(false)` when the profile is signed but no key for its algorithm is
+    /// available, in which case verification is skipped with a warning. Unsigned
+    /// profiles and unverifiable ones are errors when `keys.require_signed` is set.
     ///
     /// # Arguments
     /// * `raw_bytes` - The complete raw bytes of the YAML file, used to recompute the signature.
-    /// * `key` - The secret key used to generate the HMAC signature.
-    pub fn verify_signature(&self, raw_bytes: &[u8], key: &[u8]) -> Result<bool> {
-        if self.signature.is_none() {
+    /// * `keys` - The HMAC secret and Ed25519 public keys to verify against.
+    pub fn verify_signature(&self, raw_bytes: &[u8], keys: &ProfileKeys) -> Result<bool> {
+        let Some(stored_signature) = self.signature.as_ref() else {
+            if keys.require_signed {
+                bail!("Profile '{}' is unsigned, but signed profiles are required.", self.profile_name);
+            }
             debug!("Profile '{}' is unsigned, skipping signature verification.", self.profile_name);
             return Ok(true);
-        }
+        };
 
-        let stored_signature = self.signature.as_ref().unwrap();
-        
-        if self.signature_alg.as_deref() != Some("hmac-sha256") {
-            bail!("Profile '{}' signature verification failed: Unsupported signature algorithm '{}'. Only 'hmac-sha256' is supported.",
-                self.profile_name, self.signature_alg.as_deref().unwrap_or("none"));
+        let alg = self.signature_alg.as_deref().unwrap_or("none");
+        let key_available = match alg {
+            SIGNATURE_ALG_HMAC => keys.hmac_key.is_some(),
+            SIGNATURE_ALG_ED25519 => !keys.public_keys.is_empty(),
+            _ => bail!("Profile '{}' signature verification failed: Unsupported signature algorithm '{}'. Supported algorithms are '{}' and '{}'.",
+                self.profile_name, alg, SIGNATURE_ALG_HMAC, SIGNATURE_ALG_ED25519),
+        };
+        if !key_available {
+            let source = if alg == SIGNATURE_ALG_HMAC {
+                "CLEANSH_PROFILE_KEY environment variable is not set"
+            } else {
+                "no public key is set in CLEANSH_PROFILE_PUBKEY or found in a trusted_keys directory"
+            };
+            if keys.require_signed {
+                bail!("Profile '{}' is signed with {}, but {}; signed profiles are required.", self.profile_name, alg, source);
+            }
+            warn!("Profile '{}' is signed with {}, but {}. Signature verification skipped.", self.profile_name, alg, source);
+            return Ok(false);
         }
 
-        debug!("Profile '{}': Verifying signature...", self.profile_name);
-        
+        debug!("Profile '{}': Verifying {} signature...", self.profile_name, alg);
+
         let raw_for_signing = get_raw_profile_for_signature(raw_bytes)?;
-        
-        let mut mac = HmacSha256::new_from_slice(key)
-            .map_err(|e| anyhow!("Failed to initialize HMAC-SHA256 with key: {}", e))?;
-        mac.update(&raw_for_signing);
 
-        let computed_signature = hex::encode(mac.finalize().into_bytes());
+        let verified = if alg == SIGNATURE_ALG_HMAC {
+            let key = keys.hmac_key.as_deref().unwrap_or_default();
+            let computed_signature = hmac_signature(key, &raw_for_signing)?;
+            let matches = computed_signature.eq_ignore_ascii_case(stored_signature);
+            if !matches {
+                warn!("Profile '{}' signature verification failed. Stored: '{}', Computed: '{}'",
+                    self.profile_name, stored_signature, computed_signature);
+            }
+            matches
+        } else {
+            let signature = hex::decode(stored_signature.trim())
+                .with_context(|| format!("Profile '{}' has a malformed ed25519 signature.", self.profile_name))?;
+            keys.public_keys.iter().any(|public_key| {
+                UnparsedPublicKey::new(&ED25519, public_key).verify(&raw_for_signing, &signature).is_ok()
+            })
+        };
 
-        if computed_signature.eq_ignore_ascii_case(stored_signature) {
+        if verified {
             debug!("Profile '{}' signature verification succeeded.", self.profile_name);
             Ok(true)
         } else {
-            warn!("Profile '{}' signature verification failed. Stored: '{}', Computed: '{}'",
-                self.profile_name, stored_signature, computed_signature);
             Err(anyhow!("Profile signature verification failed for profile '{}'. The profile may have been tampered with.", self.profile_name))
         }
     }
 }
 
+/// Signature algorithm name for profiles signed with a shared HMAC-SHA256 secret.
+pub const SIGNATURE_ALG_HMAC: &str = "hmac-sha256";
+
+/// Signature algorithm name for profiles signed with an Ed25519 private key.
+pub const SIGNATURE_ALG_ED25519: &str = "ed25519";
+
+/// Key material profile signatures are verified against.
+#[derive(Debug, Clone, Default)]
+pub struct ProfileKeys {
+    /// The shared secret for `hmac-sha256` signatures.
+    pub hmac_key: Option<Vec<u8>>,
+    /// Raw 32-byte Ed25519 public keys; an `ed25519` signature from any of them is accepted.
+    pub public_keys: Vec<Vec<u8>>,
+    /// Rejects unsigned profiles, and signed ones no available key can verify.
+    pub require_signed: bool,
+}
+
+impl ProfileKeys {
+    /// Loads keys from `CLEANSH_PROFILE_KEY` (hex HMAC secret), `CLEANSH_PROFILE_PUBKEY`
+    /// (an Ed25519 public key as PEM or hex, or a path to one), and every key file in
+    /// the `trusted_keys` directories (see [`trusted_keys_dirs`]).
+    pub fn from_env() -> Result<Self> {
+        let hmac_key = match std::env::var("CLEANSH_PROFILE_KEY") {
+            Ok(key_hex) => Some(hex::decode(&key_hex)
+                .context("Failed to decode CLEANSH_PROFILE_KEY from hex. Make sure it's a valid hex string.")?),
+            Err(_) => None,
+        };
+
+        let mut public_keys = Vec::new();
+        if let Ok(value) = std::env::var("CLEANSH_PROFILE_PUBKEY") {
+            let path = Path::new(value.trim());
+            let text = if path.is_file() {
+                fs::read_to_string(path).with_context(|| format!("reading public key file {}", path.display()))?
+            } else {
+                value
+            };
+            public_keys.push(parse_ed25519_public_key(&text).context("Invalid CLEANSH_PROFILE_PUBKEY")?);
+        }
+        for dir in trusted_keys_dirs() {
+            public_keys.extend(read_trusted_keys_dir(&dir));
+        }
+
+        Ok(Self { hmac_key, public_keys, require_signed: false })
+    }
+
+    /// Sets whether unsigned or unverifiable profiles are rejected.
+    pub fn with_require_signed(mut self, require_signed: bool) -> Self {
+        self.require_signed = require_signed;
+        self
+    }
+}
+
+/// Directories whose key files are trusted for `ed25519` profile signatures.
+pub fn trusted_keys_dirs() -> Vec<PathBuf> {
+    [
+        dirs::home_dir().map(|p| p.join(".cleansh").join("trusted_keys")),
+        dirs::config_dir().map(|p| p.join("cleansh").join("trusted_keys")),
+        Some(PathBuf::from("/etc/cleansh/trusted_keys")),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Reads every public key in `dir`, skipping (with a warning) files that are not one.
+fn read_trusted_keys_dir(dir: &Path) -> Vec<Vec<u8>> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).filter(|p| p.is_file()).collect();
+    paths.sort();
+    paths.into_iter()
+        .filter_map(|path| {
+            let parsed = fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|text| parse_ed25519_public_key(&text));
+            match parsed {
+                Ok(key) => Some(key),
+                Err(e) => {
+                    warn!("Ignoring trusted key file {}: {:#}", path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// DER-encoded SubjectPublicKeyInfo prefix of an Ed25519 public key; the raw key follows.
+const ED25519_SPKI_PREFIX: [u8; 12] = [0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00];
+
+/// Decodes the base64 body of a PEM block.
+fn pem_to_der(pem: &str) -> Result<Vec<u8>> {
+    let body: String = pem
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .flat_map(|line| line.split_whitespace())
+        .collect();
+    STANDARD.decode(body).context("PEM body is not valid base64")
+}
+
+/// Parses an Ed25519 public key given as a `PUBLIC KEY` PEM block or 32 hex-encoded bytes.
+pub fn parse_ed25519_public_key(text: &str) -> Result<Vec<u8>> {
+    let text = text.trim();
+    let key = if text.starts_with("-----BEGIN") {
+        let der = pem_to_der(text)?;
+        match der.strip_prefix(&ED25519_SPKI_PREFIX[..]) {
+            Some(key) => key.to_vec(),
+            None => bail!("PEM public key is not an Ed25519 key"),
+        }
+    } else {
+        hex::decode(text).context("Public key is neither PEM nor hex")?
+    };
+    if key.len() != 32 {
+        bail!("Ed25519 public keys are 32 bytes, got {}", key.len());
+    }
+    Ok(key)
+}
+
+/// Parses an Ed25519 private key given as a PKCS#8 `PRIVATE KEY` PEM block or a
+/// hex-encoded 32-byte seed.
+fn parse_ed25519_private_key(text: &str) -> Result<Ed25519KeyPair> {
+    let text = text.trim();
+    if text.starts_with("-----BEGIN") {
+        let der = pem_to_der(text)?;
+        return Ed25519KeyPair::from_pkcs8_maybe_unchecked(&der)
+            .map_err(|e| anyhow!("Invalid Ed25519 PKCS#8 private key: {}", e));
+    }
+    let seed = hex::decode(text).context("Private key is neither PEM nor hex")?;
+    Ed25519KeyPair::from_seed_unchecked(&seed)
+        .map_err(|e| anyhow!("Invalid Ed25519 private key seed (expected 32 bytes): {}", e))
+}
+
+fn hmac_signature(key: &[u8], message: &[u8]) -> Result<String> {
+    let mut mac = HmacSha256::new_from_slice(key)
+        .map_err(|e| anyhow!("Failed to initialize HMAC-SHA256 with key: {}", e))?;
+    mac.update(message);
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
 /// A helper function to parse the raw YAML bytes and re-serialize the profile
 /// with the `signature` field removed.
 fn get_raw_profile_for_signature(raw_bytes: &[u8]) -> Result<Vec<u8>> {
@@ -140,11 +519,11 @@ fn get_raw_profile_for_signature(raw_bytes: &[u8]) -> Result<Vec<u8>> {
         .context("Failed to parse profile YAML for signature verification.")?;
 
     if let Value::Mapping(mapping) = &mut profile_value {
-        if mapping.contains_key(&Value::String("signature".to_string())) {
-            mapping.remove(&Value::String("signature".to_string()));
+        if mapping.contains_key(Value::String("signature".to_string())) {
+            mapping.remove(Value::String("signature".to_string()));
         }
-        if mapping.contains_key(&Value::String("signature_alg".to_string())) {
-            mapping.remove(&Value::String("signature_alg".to_string()));
+        if mapping.contains_key(Value::String("signature_alg".to_string())) {
+            mapping.remove(Value::String("signature_alg".to_string()));
         }
     }
 
@@ -154,12 +533,14 @@ fn get_raw_profile_for_signature(raw_bytes: &[u8]) -> Result<Vec<u8>> {
 }
 
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub struct ProfileRule {
     pub name: String,
     pub enabled: Option<bool>,
     pub severity: Option<String>,
+    /// Replaces the rule's tags before tag filtering runs.
+    pub tags: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -176,11 +557,56 @@ pub struct DedupeConfig {
     pub use_hash: bool,
 }
 
+impl DedupeConfig {
+    /// Returns the key identifying repeats of a match, or `None` when hash dedupe is off.
+    ///
+    /// With a non-zero `window_bytes` the surrounding context is part of the key, so the
+    /// same secret in different contexts is still reported separately.
+    pub fn dedupe_key<'a>(&self, m: &'a RedactionMatch) -> Option<&'a str> {
+        if !self.use_hash {
+            return None;
+        }
+        if self.window_bytes > 0 {
+            m.match_context_hash.as_deref()
+        } else {
+            m.sample_hash.as_deref()
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "snake_case")]
 pub struct PostProcessingConfig {
     pub replace_with_token: bool,
     pub token_format: Option<String>,
+    /// Key for `{shorthash}`. Without one, tokens only stay the same within a run.
+    #[serde(default)]
+    pub token_salt: Option<String>,
+}
+
+impl PostProcessingConfig {
+    /// Returns the configured token template, falling back to `DEFAULT_TOKEN_FORMAT`.
+    pub fn effective_token_format(&self) -> &str {
+        self.token_format.as_deref().unwrap_or(DEFAULT_TOKEN_FORMAT)
+    }
+
+    /// The hash behind `{shorthash}` for the secret with `sample_hash_hex`: an HMAC
+    /// keyed by `token_salt`, or by a key drawn once per process, so a token cannot
+    /// be matched against hashes of guessed values.
+    pub fn token_hash(&self, sample_hash_hex: &str) -> String {
+        static PROCESS_KEY: OnceLock<[u8; 32]> = OnceLock::new();
+        let key: &[u8] = match &self.token_salt {
+            Some(salt) => salt.as_bytes(),
+            None => PROCESS_KEY.get_or_init(|| {
+                let mut key = [0u8; 32];
+                SystemRandom::new().fill(&mut key).expect("system random source failed");
+                key
+            }),
+        };
+        let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(sample_hash_hex.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -189,6 +615,9 @@ pub struct ReportingConfig {
     pub include_rule_version: bool,
     pub include_engine_version: bool,
     pub include_byte_hash_of_input: bool,
+    /// Capture the text around each match for reviewers (see `EngineOptions::capture_context`).
+    #[serde(default)]
+    pub capture_context: bool,
 }
 
 pub fn profile_candidate_paths(name: &str) -> Vec<PathBuf> {
@@ -202,42 +631,107 @@ pub fn profile_candidate_paths(name: &str) -> Vec<PathBuf> {
 
     base_dirs.into_iter()
         .flatten()
-        .map(|dir| dir.join(format!("{}.yaml", name)))
+        .flat_map(|dir| CONFIG_EXTENSIONS.map(|ext| dir.join(format!("{}.{}", name, ext))))
         .collect()
-    
+
 }
 
+/// Finds the file for a profile name or path. Paths are tried as given, then
+/// relative to `relative_to` (the directory of the profile that named it), then
+/// the name is looked up in the candidate profile directories.
+fn resolve_profile_path(name_or_path: &str, relative_to: Option<&Path>) -> Option<PathBuf> {
+    let path = Path::new(name_or_path);
+    if path.is_file() {
+        debug!("Input is a valid file path. Loading directly from: {}", path.display());
+        return Some(path.to_path_buf());
+    }
+    if let Some(dir) = relative_to {
+        let sibling = dir.join(path);
+        if sibling.is_file() {
+            return Some(sibling);
+        }
+    }
+    profile_candidate_paths(name_or_path)
+        .into_iter()
+        .find(|p| p.exists())
+}
+
+/// Reads a profile file as YAML bytes: TOML and JSON profiles (by extension) are
+/// converted, so signatures cover the same canonical form whatever the format.
+fn read_profile_yaml(path: &Path) -> Result<(ConfigFormat, Vec<u8>)> {
+    let raw_bytes = fs::read(path)
+        .with_context(|| format!("reading profile file {}", path.display()))?;
+    let format = ConfigFormat::detect(path, None);
+    if format == ConfigFormat::Yaml {
+        return Ok((format, raw_bytes));
+    }
+    let value = format.parse_value(&String::from_utf8_lossy(&raw_bytes))
+        .with_context(|| format!("parsing profile {} {}", format.name(), path.display()))?;
+    let yaml = serde_yml::to_string(&value).context("Failed to convert profile to YAML.")?;
+    Ok((format, yaml.into_bytes()))
+}
+
+/// Reads one profile file and checks its own signature, without following `extends`.
+pub fn read_profile_file(path: &Path, keys: &ProfileKeys) -> Result<ProfileConfig> {
+    let (format, raw_bytes) = read_profile_yaml(path)?;
+
+    let cfg: ProfileConfig = serde_yml::from_slice(&raw_bytes)
+        .with_context(|| format!("parsing profile {} {}", format.name(), path.display()))?;
+
+    cfg.verify_signature(&raw_bytes, keys)
+        .with_context(|| format!("verifying profile file {}", path.display()))?;
+    Ok(cfg)
+}
+
+/// Loads a profile and everything it `extends`, returning the composed result.
+///
+/// Parents are applied first and each child on top (see [`ProfileConfig::compose_over`]).
+/// Every file in the chain has its signature checked on its own. Chains longer than
+/// [`MAX_PROFILE_DEPTH`], cycles, and missing parents are errors naming the chain.
+/// Signatures are verified against [`ProfileKeys::from_env`].
 pub fn load_profile_by_name(name_or_path: &str) -> Result<ProfileConfig> {
+    load_profile_with_keys(name_or_path, &ProfileKeys::from_env()?)
+}
+
+/// Like [`load_profile_by_name`], verifying every file in the chain against `keys`.
+pub fn load_profile_with_keys(name_or_path: &str, keys: &ProfileKeys) -> Result<ProfileConfig> {
     debug!("Attempting to load profile from: '{}'", name_or_path);
-    
-    let path_to_load = {
-        let path = Path::new(name_or_path);
-        if path.exists() && path.is_file() {
-            debug!("Input is a valid file path. Loading directly from: {}", path.display());
-            Some(path.to_path_buf())
-        } else {
-            profile_candidate_paths(name_or_path)
-                .into_iter()
-                .find(|p| p.exists())
+
+    let mut chain: Vec<(String, PathBuf)> = Vec::new();
+    let mut profiles = Vec::new();
+    let mut next = Some(name_or_path.to_string());
+    while let Some(name) = next.take() {
+        let describe = |chain: &[(String, PathBuf)]| {
+            chain.iter().map(|(n, _)| n.as_str()).chain([name.as_str()]).collect::<Vec<_>>().join(" -> ")
+        };
+        let relative_to = chain.last().and_then(|(_, p)| p.parent());
+        let Some(path) = resolve_profile_path(&name, relative_to) else {
+            if chain.is_empty() {
+                bail!("Profile not found. It is not a valid file path, and was not found in expected locations.");
+            }
+            bail!("Profile '{}' was not found (extends chain: {}).", name, describe(&chain));
+        };
+        let canonical = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        if chain.iter().any(|(_, p)| *p == canonical) {
+            bail!("Profile inheritance cycle detected: {}.", describe(&chain));
         }
-    }.context("Profile not found. It is not a valid file path, and was not found in expected locations.")?;
-    
-    let raw_bytes = fs::read(&path_to_load)
-        .with_context(|| format!("reading profile file {}", path_to_load.display()))?;
-    
-    let cfg: ProfileConfig = serde_yml::from_slice(&raw_bytes)
-        .with_context(|| format!("parsing profile YAML {}", path_to_load.display()))?;
-    
-    if let Some(key_hex) = std::env::var("CLEANSH_PROFILE_KEY").ok() {
-        let key_bytes = hex::decode(&key_hex)
-            .context("Failed to decode CLEANSH_PROFILE_KEY from hex. Make sure it's a valid hex string.")?;
-        cfg.verify_signature(&raw_bytes, &key_bytes)?;
-    } else if cfg.signature.is_some() {
-        warn!("Profile '{}' is signed, but CLEANSH_PROFILE_KEY environment variable is not set. Signature verification skipped.", cfg.profile_name);
+        if chain.len() >= MAX_PROFILE_DEPTH {
+            bail!("Profile inheritance is deeper than {} levels: {}.", MAX_PROFILE_DEPTH, describe(&chain));
+        }
+
+        let profile = read_profile_file(&path, keys)?;
+        next = profile.extends.clone();
+        chain.push((name, canonical));
+        profiles.push(profile);
     }
-    
+
+    let mut composed = profiles.pop().context("Profile not found.")?;
+    while let Some(child) = profiles.pop() {
+        composed = child.compose_over(composed)?;
+    }
+
     debug!("Successfully loaded profile '{}'.", name_or_path);
-    Ok(cfg)
+    Ok(composed)
 }
 
 /// Signs a profile file using an HMAC-SHA256 key and updates the file in place.
@@ -247,29 +741,47 @@ pub fn load_profile_by_name(name_or_path: &str) -> Result<ProfileConfig> {
 /// * `path` - The path to the profile YAML file to sign.
 /// * `key` - The secret key used to generate the HMAC signature.
 pub fn sign_profile(path: &Path, key: &[u8]) -> Result<()> {
+    sign_profile_with(path, SIGNATURE_ALG_HMAC, |message| hmac_signature(key, message))
+}
+
+/// Signs a profile file with an Ed25519 private key and updates the file in place.
+/// Machines loading the profile only need the public key to verify it.
+///
+/// # Arguments
+/// * `path` - The path to the profile YAML file to sign.
+/// * `private_key` - A PKCS#8 `PRIVATE KEY` PEM block or a hex-encoded 32-byte seed.
+pub fn sign_profile_ed25519(path: &Path, private_key: &str) -> Result<()> {
+    let key_pair = parse_ed25519_private_key(private_key)?;
+    sign_profile_with(path, SIGNATURE_ALG_ED25519, |message| Ok(hex::encode(key_pair.sign(message))))
+}
+
+/// Signs the canonical bytes of the profile at `path` and writes the signature fields
+/// back. The rest of the file's content is kept as parsed, so the signed bytes are
+/// exactly what [`ProfileConfig::verify_signature`] recomputes.
+fn sign_profile_with(path: &Path, alg: &str, sign: impl FnOnce(&[u8]) -> Result<String>) -> Result<()> {
     debug!("Signing profile file: {}", path.display());
-    
-    let raw_bytes = fs::read(path)
-        .with_context(|| format!("reading profile file {}", path.display()))?;
-    
+
+    let (format, raw_bytes) = read_profile_yaml(path)?;
+    let cfg: ProfileConfig = serde_yml::from_slice(&raw_bytes)
+        .with_context(|| format!("parsing profile {} for signing {}", format.name(), path.display()))?;
+
     let raw_for_signing = get_raw_profile_for_signature(&raw_bytes)?;
-    
-    let mut mac = HmacSha256::new_from_slice(key)
-        .map_err(|e| anyhow!("Failed to initialize HMAC-SHA256 for signing: {}", e))?;
-    mac.update(&raw_for_signing);
-    let signature = hex::encode(mac.finalize().into_bytes());
+    let signature = sign(&raw_for_signing)?;
 
-    let mut cfg: ProfileConfig = serde_yml::from_slice(&raw_bytes)
+    let mut profile_value: Value = serde_yml::from_slice(&raw_bytes)
         .with_context(|| format!("parsing profile YAML for signing {}", path.display()))?;
-    cfg.signature = Some(signature);
-    cfg.signature_alg = Some("hmac-sha256".to_string());
-    
-    let updated_yaml = serde_yml::to_string(&cfg)
+    let Value::Mapping(mapping) = &mut profile_value else {
+        bail!("Profile file {} is not a YAML mapping.", path.display());
+    };
+    mapping.insert(Value::String("signature".to_string()), Value::String(signature));
+    mapping.insert(Value::String("signature_alg".to_string()), Value::String(alg.to_string()));
+
+    let updated = format.to_string(&profile_value)
         .context("Failed to re-serialize signed profile.")?;
-    fs::write(path, updated_yaml)
+    fs::write(path, updated)
         .with_context(|| format!("writing signed profile to file {}", path.display()))?;
 
-    debug!("Successfully signed profile '{}'.", cfg.profile_name);
+    debug!("Successfully signed profile '{}' with {}.", cfg.profile_name, alg);
     Ok(())
 }
 
@@ -280,7 +792,8 @@ pub fn apply_profile_to_config(profile: &ProfileConfig, mut default: RedactionCo
         .map(|r| (r.name.clone(), r))
         .collect();
 
-    for profile_rule_override in &profile.rules {
+    let (rule_overrides, _) = profile.normalize_rules();
+    for profile_rule_override in &rule_overrides {
         if let Some(rule_to_update) = default_rules_map.get_mut(&profile_rule_override.name) {
             if let Some(enabled) = profile_rule_override.enabled {
                 debug!("Applying enabled={} override for rule '{}'", enabled, &profile_rule_override.name);
@@ -290,11 +803,33 @@ pub fn apply_profile_to_config(profile: &ProfileConfig, mut default: RedactionCo
                 debug!("Applying severity='{}' override for rule '{}'", severity, &profile_rule_override.name);
                 rule_to_update.severity = Some(severity);
             }
+            if let Some(tags) = profile_rule_override.tags.clone() {
+                debug!("Applying tags={:?} override for rule '{}'", tags, &profile_rule_override.name);
+                rule_to_update.tags = Some(tags);
+            }
         } else {
             warn!("Profile rule '{}' not found in default configuration. It will be ignored.", profile_rule_override.name);
         }
     }
-    
+
+    if let Some(engines) = &profile.engines {
+        default.engines.merge_from(engines);
+    }
+    if let Some(entropy) = profile.engine.as_ref().and_then(|e| e.entropy.as_ref()) {
+        default.engines.entropy.merge_from(entropy);
+    }
+
+    if let Some(cli_flags) = &profile.cli_flag_secrets {
+        merge_cli_flag_rules(&mut default.cli_flag_secrets, cli_flags);
+    }
+
+    if profile.include_tags.is_some() || profile.exclude_tags.is_some() {
+        default.set_active_rules_by_tags(
+            profile.include_tags.as_deref().unwrap_or_default(),
+            profile.exclude_tags.as_deref().unwrap_or_default(),
+        );
+    }
+
     debug!("Finished applying profile. Final rule count: {}", default.rules.len());
     default
 }
@@ -347,9 +882,19 @@ pub fn sample_score_bytes(run_seed: &[u8], source_id: &str, start: u64, end: u64
 }
 
 pub fn select_samples_for_rule(matches: &[RedactionMatch], run_seed: &[u8], max_per_rule: usize) -> Vec<RedactionMatch> {
-    let mut scored: Vec<(Vec<u8>, &RedactionMatch)> = matches.iter()
+    select_sample_refs(matches, run_seed, max_per_rule).into_iter().cloned().collect()
+}
+
+/// [`select_samples_for_rule`] without cloning the chosen matches.
+fn select_sample_refs<'a>(
+    matches: impl IntoIterator<Item = &'a RedactionMatch>,
+    run_seed: &[u8],
+    max_per_rule: usize,
+) -> Vec<&'a RedactionMatch> {
+    let mut scored: Vec<(Vec<u8>, &RedactionMatch)> = matches.into_iter()
         .filter_map(|m| {
-            sample_score_bytes(run_seed, &m.source_id, m.start, m.end)
+            let span = m.document_span();
+            sample_score_bytes(run_seed, &m.source_id, offset_from_index(span.start), offset_from_index(span.end))
                 .ok()
                 .map(|s| (s, m))
         })
@@ -357,7 +902,7 @@ pub fn select_samples_for_rule(matches: &[RedactionMatch], run_seed: &[u8], max_
     
     scored.sort_by(|a, b| b.0.cmp(&a.0));
     
-    let mut out: Vec<RedactionMatch> = Vec::new();
+    let mut out: Vec<&RedactionMatch> = Vec::new();
     let mut seen_hashes = HashSet::new();
     let mut seen_coords = HashSet::new();
     
@@ -367,16 +912,72 @@ pub fn select_samples_for_rule(matches: &[RedactionMatch], run_seed: &[u8], max_
         let is_duplicate = if let Some(h) = &m.sample_hash {
             !seen_hashes.insert(h.clone())
         } else {
-            !seen_coords.insert((m.source_id.clone(), m.start, m.end))
+            !seen_coords.insert((m.source_id.clone(), m.document_span()))
         };
         
         if !is_duplicate {
-            out.push(m.clone());
+            out.push(m);
         }
     }
     out
 }
 
+/// Example texts kept per rule in a summary when no profile sets `samples`.
+pub const DEFAULT_SUMMARY_SAMPLES_PER_RULE: usize = 20;
+
+/// Chooses summary samples when the engine options carry no `run_seed`.
+const DEFAULT_SUMMARY_SEED: &[u8] = b"cleansh-summary-samples";
+
+/// Example texts a summary keeps per rule and in all (`0`: no overall limit).
+pub fn summary_sample_limits(options: &EngineOptions) -> (usize, usize) {
+    match &options.samples_config {
+        Some(samples) => (samples.max_per_rule, samples.max_total),
+        None => (DEFAULT_SUMMARY_SAMPLES_PER_RULE, 0),
+    }
+}
+
+/// Summarizes `matches` per rule, each paired with whether dedupe suppressed it.
+///
+/// Occurrence, repeat, and severity figures count every match, but the example
+/// texts are a deterministic sample (see [`select_samples_for_rule`]) capped by
+/// the options' `samples_config`, or [`DEFAULT_SUMMARY_SAMPLES_PER_RULE`] per
+/// rule without one, so a huge input cannot fill memory with copies of its
+/// secrets. Items whose texts leave matches out are marked `truncated`.
+pub fn sampled_summary<'a>(
+    matches: impl IntoIterator<Item = (&'a RedactionMatch, bool)>,
+    options: &EngineOptions,
+) -> Vec<RedactionSummaryItem> {
+    let mut by_rule: HashMap<&str, (Vec<&RedactionMatch>, usize)> = HashMap::new();
+    for (m, is_repeat) in matches {
+        let (rule_matches, repeats) = by_rule.entry(m.rule_name.as_str()).or_default();
+        rule_matches.push(m);
+        *repeats += usize::from(is_repeat);
+    }
+    let (max_per_rule, max_total) = summary_sample_limits(options);
+    let seed = options.run_seed.as_deref().unwrap_or(DEFAULT_SUMMARY_SEED);
+
+    // Rules draw on `max_total` in name order, so the same input keeps the same samples.
+    let mut rules: Vec<_> = by_rule.into_iter().collect();
+    rules.sort_by(|a, b| a.0.cmp(b.0));
+    let mut budget = if max_total == 0 { usize::MAX } else { max_total };
+    rules.into_iter()
+        .map(|(rule_name, (rule_matches, repeats))| {
+            let mut samples = select_sample_refs(rule_matches.iter().copied(), seed, max_per_rule.min(budget));
+            budget -= samples.len();
+            samples.sort_by(|a, b| (&a.source_id, a.document_span().start).cmp(&(&b.source_id, b.document_span().start)));
+            RedactionSummaryItem {
+                rule_name: rule_name.to_string(),
+                occurrences: rule_matches.len(),
+                original_texts: samples.iter().map(|m| m.original_string.clone()).collect(),
+                sanitized_texts: samples.iter().map(|m| m.sanitized_string.clone()).collect(),
+                deduplicated: repeats,
+                severity: rule_matches.iter().copied().map(Severity::of_match).max().unwrap_or_default(),
+                truncated: samples.len() < rule_matches.len(),
+            }
+        })
+        .collect()
+}
+
 pub fn format_token(token_fmt: &str, rule: &str, sample_hash_hex: &str) -> Result<String> {
     let mut tt = TinyTemplate::new();
     tt.add_template("t", token_fmt)
@@ -404,10 +1005,52 @@ pub struct EngineOptions {
     
     pub run_id: Option<String>,
     pub input_hash: Option<String>,
+
+    /// Matches whose value hashes to one of these (see `ignore_file::ignore_hash`) are not reported.
+    #[serde(default)]
+    pub ignored_hashes: HashSet<String>,
+
+    /// What engines do with matches when the remediation channel is full.
+    #[serde(default)]
+    pub remediation_tx_mode: RemediationTxMode,
+
+    /// Sample hashes the user asked never to be flagged again; shared, so hashes
+    /// added while the engine runs apply from the next scan on.
+    #[serde(skip)]
+    pub ignore_store: IgnoreStore,
+
+    /// Matches whose whole value matches one of these patterns are not reported.
+    #[serde(skip)]
+    pub ignore_patterns: Option<RegexSet>,
+
+    /// Secrets remediation has revoked; shared with the orchestrator, so later
+    /// occurrences are redacted as `[REVOKED_SECRET]` and not sent again.
+    #[serde(skip)]
+    pub remediation_state: RemediationStateMap,
+
+    /// Record why each candidate was kept or dropped (see `explain`).
+    #[serde(default)]
+    pub explain: bool,
+
+    /// Where explain records go; shared, so the records of every engine built
+    /// from these options end up together.
+    #[serde(skip)]
+    pub explain_log: ExplainLog,
+
+    /// Fill in each match's `context_before` and `context_after`, scrubbed of other
+    /// secrets (see `engines::match_context`).
+    #[serde(default)]
+    pub capture_context: bool,
+
+    /// Score every input against one profile carried across the stream, instead
+    /// of profiling each input on its own (see `StreamProfile`).
+    #[serde(skip)]
+    pub stream_profile: Option<StreamProfile>,
 }
 
 impl From<ProfileConfig> for EngineOptions {
     fn from(profile: ProfileConfig) -> Self {
+        let capture_context = profile.reporting.as_ref().is_some_and(|reporting| reporting.capture_context);
         Self {
             post_processing: profile.post_processing,
             samples_config: profile.samples,
@@ -420,6 +1063,15 @@ impl From<ProfileConfig> for EngineOptions {
             },
             run_id: None,
             input_hash: None,
+            ignored_hashes: HashSet::new(),
+            remediation_tx_mode: RemediationTxMode::default(),
+            ignore_store: IgnoreStore::default(),
+            ignore_patterns: None,
+            remediation_state: RemediationStateMap::default(),
+            explain: false,
+            explain_log: ExplainLog::default(),
+            capture_context,
+            stream_profile: None,
         }
     }
 }
@@ -445,6 +1097,74 @@ impl EngineOptions {
         self.engine_version = Some(ver);
         self
     }
+
+    pub fn with_ignored_hashes(mut self, hashes: HashSet<String>) -> Self {
+        self.ignored_hashes = hashes;
+        self
+    }
+
+    pub fn with_remediation_tx_mode(mut self, mode: RemediationTxMode) -> Self {
+        self.remediation_tx_mode = mode;
+        self
+    }
+
+    pub fn with_ignore_store(mut self, store: IgnoreStore) -> Self {
+        self.ignore_store = store;
+        self
+    }
+
+    pub fn with_ignore_patterns(mut self, patterns: RegexSet) -> Self {
+        self.ignore_patterns = Some(patterns);
+        self
+    }
+
+    pub fn with_remediation_state(mut self, state: RemediationStateMap) -> Self {
+        self.remediation_state = state;
+        self
+    }
+
+    pub fn with_stream_profile(mut self, profile: StreamProfile) -> Self {
+        self.stream_profile = Some(profile);
+        self
+    }
+
+    pub fn with_explain(mut self, enabled: bool) -> Self {
+        self.explain = enabled;
+        self
+    }
+
+    pub fn with_capture_context(mut self, enabled: bool) -> Self {
+        self.capture_context = enabled;
+        self
+    }
+
+    /// Turns on explain records and streams them to `sink` instead of collecting them.
+    pub fn with_explain_sink(mut self, sink: Arc<dyn ExplainSink>) -> Self {
+        self.explain = true;
+        self.explain_log = ExplainLog::streaming(sink);
+        self
+    }
+
+    /// The explain records collected since the last call, oldest first.
+    pub fn take_explain_records(&self) -> Vec<ExplainRecord> {
+        self.explain_log.take()
+    }
+
+    /// Records the decision `decision()` about `start..end` when explain is on;
+    /// the record is not built otherwise.
+    pub(crate) fn explain(&self, source_id: &str, start: usize, end: usize, decision: impl FnOnce() -> Decision) {
+        if self.explain {
+            self.explain_log.record(&ExplainRecord { source_id: source_id.to_string(), start, end, decision: decision() });
+        }
+    }
+
+    /// Whether a match of `rule_name` on `value` is suppressed by the ignore list,
+    /// the ignore store, or an ignore pattern.
+    pub fn is_ignored(&self, rule_name: &str, value: &str) -> bool {
+        (!self.ignored_hashes.is_empty() && self.ignored_hashes.contains(&ignore_hash(value)))
+            || self.ignore_store.contains_match(rule_name, value)
+            || self.ignore_patterns.as_ref().is_some_and(|patterns| patterns.is_match(value))
+    }
 }
 // -----------------------------------------------------------------------
 
@@ -455,9 +1175,12 @@ pub struct ProfileSummary {
     pub version: String,
     pub description: Option<String>,
     pub path: Option<PathBuf>,
+    /// Whether the file carries a signature; it is not verified here.
+    #[serde(default)]
+    pub signed: bool,
 }
 
-/// List available profiles by scanning candidate profile directories for `*.yaml`.
+/// List available profiles by scanning candidate profile directories for YAML, TOML, and JSON files.
 /// This is a best-effort helper used by interactive UI to show available profiles.
 pub fn list_available_profiles() -> Vec<ProfileSummary> {
     let mut out = Vec::new();
@@ -476,12 +1199,14 @@ pub fn list_available_profiles() -> Vec<ProfileSummary> {
             for entry in entries.flatten() {
                 let path = entry.path();
                 
-                if path.extension().and_then(|s| s.to_str()) == Some("yaml") && seen_paths.insert(path.clone()) {
+                let format = ConfigFormat::from_extension(&path);
+                if let Some(format) = format.filter(|_| seen_paths.insert(path.clone())) {
                     debug!("Found potential profile at: {}", path.display());
                     match fs::read_to_string(&path) {
                         Ok(s) => {
-                            if let Ok(cfg) = serde_yml::from_str::<ProfileConfig>(&s) {
+                            if let Ok(cfg) = format.parse::<ProfileConfig>(&s) {
                                 out.push(ProfileSummary {
+                                    signed: cfg.signature.is_some(),
                                     profile_name: cfg.profile_name,
                                     display_name: cfg.display_name,
                                     version: cfg.version,
@@ -489,7 +1214,7 @@ pub fn list_available_profiles() -> Vec<ProfileSummary> {
                                     path: Some(path),
                                 });
                             } else {
-                                warn!("Failed to parse YAML for profile at: {}", path.display());
+                                warn!("Failed to parse {} for profile at: {}", format.name(), path.display());
                             }
                         }
                         Err(e) => {
@@ -4,6 +4,7 @@
 
 use serde::{Serialize, Deserialize};
 use log::debug;
+use std::ops::Range;
 use crate::config::RedactionRule;
 
 use lazy_static::lazy_static;
@@ -19,15 +20,46 @@ lazy_static! {
     };
 }
 
+/// The text a match's `start` and `end` count bytes in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OffsetBasis {
+    /// The ANSI-stripped copy of the input that the engines scan.
+    #[default]
+    Stripped,
+    /// The input exactly as given.
+    Original,
+}
+
+/// Converts a byte index into the `u64` offset stored in matches and logs.
+pub fn offset_from_index(index: usize) -> u64 {
+    u64::try_from(index).expect("byte index does not fit in a u64 offset")
+}
+
+/// Converts a stored `u64` offset back into a byte index. Saturates on targets
+/// where it does not fit, so callers clamping to the text length stay in bounds.
+pub fn index_from_offset(offset: u64) -> usize {
+    usize::try_from(offset).unwrap_or(usize::MAX)
+}
+
 /// Represents a single instance of a matched and potentially redacted string.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct RedactionMatch {
     pub rule_name: String,
     pub original_string: String,
     pub sanitized_string: String,
+    /// Byte offsets into the text named by `offset_basis`.
     pub start: u64,
     pub end: u64,
     #[serde(default)]
+    pub offset_basis: OffsetBasis,
+    /// Byte offsets into the input exactly as given, ANSI escapes included. Set by
+    /// the engines; `None` on records written before they were.
+    #[serde(default)]
+    pub original_start: Option<u64>,
+    #[serde(default)]
+    pub original_end: Option<u64>,
+    #[serde(default)]
     pub line_number: Option<u64>,
     #[serde(default)]
     pub sample_hash: Option<String>,
@@ -39,10 +71,65 @@ pub struct RedactionMatch {
     pub rule: RedactionRule,
     #[serde(default)]
     pub source_id: String,
+    /// Detection confidence (0.0 - 1.0+). Regex matches score 1.0 when a programmatic
+    /// validator confirmed them; entropy matches carry the scanner's score.
+    #[serde(default)]
+    pub confidence: Option<f64>,
+    /// Shannon entropy of the matched text, in bits per byte (entropy engine only).
+    #[serde(default)]
+    pub entropy: Option<f64>,
+    /// Mean entropy heat over the match span, set when the composite engine
+    /// cross-checked a regex match against the entropy scanner.
+    #[serde(default)]
+    pub corroborating_entropy: Option<f64>,
+    /// Text just before the match, other secrets in it redacted. Only set with
+    /// `EngineOptions::capture_context` (see `engines::match_context`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_before: Option<String>,
+    /// Text just after the match, like `context_before`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_after: Option<String>,
+}
+
+impl RedactionMatch {
+    /// `start..end` as byte indices into the `offset_basis` text.
+    pub fn span(&self) -> Range<usize> {
+        index_from_offset(self.start)..index_from_offset(self.end)
+    }
+
+    /// The match's bytes in the input as given, if known.
+    pub fn original_span(&self) -> Option<Range<usize>> {
+        match (self.original_start, self.original_end) {
+            (Some(start), Some(end)) => Some(index_from_offset(start)..index_from_offset(end)),
+            _ if self.offset_basis == OffsetBasis::Original => Some(self.span()),
+            _ => None,
+        }
+    }
+
+    /// The captured context with the sanitized value in the middle, e.g.
+    /// `password=[PASSWORD] for admin`; `None` unless context capture was on.
+    pub fn context_line(&self) -> Option<String> {
+        if self.context_before.is_none() && self.context_after.is_none() {
+            return None;
+        }
+        Some(format!(
+            "{}{}{}",
+            self.context_before.as_deref().unwrap_or_default(),
+            self.sanitized_string,
+            self.context_after.as_deref().unwrap_or_default(),
+        ))
+    }
+
+    /// [`Self::original_span`], falling back to [`Self::span`] for records that
+    /// predate original offsets. Use this for anything shown or stored against
+    /// the document.
+    pub fn document_span(&self) -> Range<usize> {
+        self.original_span().unwrap_or_else(|| self.span())
+    }
 }
 
 /// Represents a single, auditable log entry for a redaction event.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RedactionLog {
     pub timestamp: String,
     pub run_id: String,
@@ -53,6 +140,7 @@ pub struct RedactionLog {
     pub rule_name: String,
     pub input_hash: String,
     pub match_hash: String,
+    /// Byte offsets of the match in the input as given.
     pub start: u64,
     pub end: u64,
 }
@@ -66,6 +154,11 @@ pub fn redact_sensitive(s: &str) -> String {
     }
 }
 
+/// Whether `CLEANSH_ALLOW_DEBUG_PII=true` allows original matched text to be shown.
+pub fn pii_debug_allowed() -> bool {
+    *PII_DEBUG_ALLOWED
+}
+
 fn get_loggable_content(sensitive_content: &str) -> String {
     if *PII_DEBUG_ALLOWED {
         sensitive_content.to_string()
@@ -160,4 +253,22 @@ mod tests {
         let h2 = canonical_sample_hash("email", "test@example.com");
         assert_eq!(h1, h2);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_confidence_and_entropy_round_trip() {
+        let m = RedactionMatch {
+            rule_name: "high_entropy_secret".to_string(),
+            confidence: Some(2.75),
+            entropy: Some(3.9),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&m).unwrap();
+        let back: RedactionMatch = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, m);
+
+        // Records written before these fields existed still load.
+        let legacy = r#"{"rule_name":"email","original_string":"a@b.c","sanitized_string":"[EMAIL]","start":0,"end":5}"#;
+        let old: RedactionMatch = serde_json::from_str(legacy).unwrap();
+        assert_eq!((old.confidence, old.entropy), (None, None));
+    }
+}
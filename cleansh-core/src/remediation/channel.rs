@@ -0,0 +1,121 @@
+// cleansh-core/src/remediation/channel.rs
+//! The engines' end of the remediation channel.
+//!
+//! Engines scan synchronously, usually on an async runtime thread, so they can
+//! never wait for room on the bounded channel to the orchestrator themselves.
+//! [`RemediationTx`] applies the configured [`RemediationTxMode`] instead: it
+//! either drops what does not fit, or hands matches to a worker thread that does
+//! the waiting.
+
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::{mpsc as std_mpsc, Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::metrics::EngineMetrics;
+use crate::redaction_match::{canonical_sample_hash, RedactionMatch};
+
+/// What an engine does with a match when the remediation channel is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemediationTxMode {
+    /// Drops the match and counts it in `dropped_sends`.
+    #[default]
+    Drop,
+    /// Queues every match; a worker thread waits for capacity, so all of them are
+    /// delivered while the orchestrator is listening.
+    Block,
+    /// Like `Block`, but a match whose sample hash is already waiting in the queue
+    /// is merged into it and counted in `coalesced_sends`.
+    Coalesce,
+}
+
+impl FromStr for RemediationTxMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "drop" => Ok(Self::Drop),
+            "block" => Ok(Self::Block),
+            "coalesce" => Ok(Self::Coalesce),
+            other => Err(format!("unknown remediation tx mode '{}' (expected drop, block, or coalesce)", other)),
+        }
+    }
+}
+
+/// Sample hashes queued but not yet delivered, for `Coalesce`.
+type PendingHashes = Arc<Mutex<HashSet<String>>>;
+
+#[derive(Debug)]
+enum Route {
+    Direct(mpsc::Sender<RedactionMatch>),
+    Queued {
+        queue: std_mpsc::Sender<RedactionMatch>,
+        pending: Option<PendingHashes>,
+    },
+}
+
+/// A remediation channel sender with a backpressure policy.
+#[derive(Debug)]
+pub struct RemediationTx {
+    route: Route,
+}
+
+fn match_hash(m: &RedactionMatch) -> String {
+    m.sample_hash.clone().unwrap_or_else(|| canonical_sample_hash(&m.rule_name, &m.original_string))
+}
+
+impl RemediationTx {
+    /// Wraps `tx`. `Block` and `Coalesce` start a worker thread that forwards
+    /// queued matches and exits once this sender is dropped and the queue drained,
+    /// or as soon as the orchestrator's receiver is gone.
+    pub fn new(tx: mpsc::Sender<RedactionMatch>, mode: RemediationTxMode) -> Self {
+        if mode == RemediationTxMode::Drop {
+            return Self { route: Route::Direct(tx) };
+        }
+        let pending = (mode == RemediationTxMode::Coalesce).then(PendingHashes::default);
+        let (queue, queued) = std_mpsc::channel::<RedactionMatch>();
+        let worker_pending = pending.clone();
+        let spawned = std::thread::Builder::new()
+            .name("cleansh-remediation-tx".to_string())
+            .spawn(move || {
+                for m in queued {
+                    let hash = worker_pending.as_ref().map(|_| match_hash(&m));
+                    let delivered = tx.blocking_send(m).is_ok();
+                    if let (Some(pending), Some(hash)) = (&worker_pending, hash) {
+                        pending.lock().unwrap_or_else(|e| e.into_inner()).remove(&hash);
+                    }
+                    if !delivered {
+                        break;
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            log::error!("Failed to start remediation worker ({}); matches will be dropped", e);
+        }
+        Self { route: Route::Queued { queue, pending } }
+    }
+
+    /// Hands `m` on according to the mode and records the outcome in `metrics`.
+    pub fn send(&self, m: RedactionMatch, metrics: &EngineMetrics) {
+        match &self.route {
+            Route::Direct(tx) => metrics.record_send(&tx.try_send(m)),
+            Route::Queued { queue, pending: None } => metrics.record_delivery(queue.send(m).is_ok()),
+            Route::Queued { queue, pending: Some(pending) } => {
+                let hash = match_hash(&m);
+                let mut pending = pending.lock().unwrap_or_else(|e| e.into_inner());
+                if !pending.insert(hash.clone()) {
+                    metrics.record_coalesced();
+                    return;
+                }
+                let queued = queue.send(m).is_ok();
+                if !queued {
+                    pending.remove(&hash);
+                }
+                metrics.record_delivery(queued);
+            }
+        }
+    }
+}
@@ -11,13 +11,18 @@ pub struct SecretFingerprint {
     pub severity: String,      // "high", "critical"
 }
 
+/// The salted SHA-256 of `secret`, in binary form. `SecretFingerprint::hash` is its hex encoding.
+pub fn salted_hash(secret: &str, salt: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(salt);
+    hasher.update(secret.as_bytes());
+    hasher.finalize().into()
+}
+
 impl SecretFingerprint {
     /// Creates a fingerprint from a raw secret string using a shared organization salt.
     pub fn from_secret(secret: &str, provider: &str, salt: &[u8]) -> Self {
-        let mut hasher = Sha256::new();
-        hasher.update(salt);
-        hasher.update(secret.as_bytes());
-        let hash = hex::encode(hasher.finalize());
+        let hash = hex::encode(salted_hash(secret, salt));
 
         Self {
             hash,
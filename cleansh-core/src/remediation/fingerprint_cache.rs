@@ -0,0 +1,330 @@
+// cleansh-core/src/remediation/fingerprint_cache.rs
+//! Known-leak lookups that scale to millions of vault fingerprints.
+//!
+//! A [`FingerprintCache`] is a file of sorted, fixed-width binary SHA-256
+//! fingerprints that is memory-mapped and binary-searched in place, so its size
+//! counts against the page cache rather than the agent's heap. An in-memory
+//! [`BloomFilter`] sits in front of it and answers most misses without touching
+//! the mapping.
+//!
+//! # File layout
+//!
+//! Salted SHA-256 output does not compress, so the payload is stored verbatim in a
+//! single zstd *skippable frame*: the file is a well-formed zstd stream that
+//! standard tooling recognises, while the sorted array stays directly addressable.
+//! All integers are little-endian.
+//!
+//! ```text
+//! offset  size  field
+//! 0       4     0x184D2A50 (zstd skippable frame magic)
+//! 4       4     frame size (everything after this field)
+//! 8       4     "CLFP"
+//! 12      4     format version (1)
+//! 16      8     fingerprint count
+//! 24      32*n  fingerprints, strictly ascending
+//! ```
+//!
+//! Caches are only ever replaced with [`FingerprintCache::write`], which writes a
+//! sibling temporary file and renames it over the old one. Readers that still map
+//! the old file keep a valid view of it until they drop their cache.
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, PoisonError, RwLock};
+
+use anyhow::{bail, Context, Result};
+use memmap2::Mmap;
+
+use crate::remediation::fingerprint::{salted_hash, SecretFingerprint};
+
+/// Width of one stored fingerprint in bytes.
+pub const FINGERPRINT_LEN: usize = 32;
+
+/// A binary salted SHA-256 fingerprint.
+pub type Fingerprint = [u8; FINGERPRINT_LEN];
+
+/// Bloom filter size used by [`FingerprintCache::open`]; about a 1% false-positive rate.
+pub const DEFAULT_BLOOM_BITS_PER_KEY: usize = 10;
+
+const SKIPPABLE_FRAME_MAGIC: u32 = 0x184D_2A50;
+const CACHE_MAGIC: &[u8; 4] = b"CLFP";
+const CACHE_VERSION: u32 = 1;
+const HEADER_LEN: usize = 24;
+
+/// Decodes the hex `hash` of a [`SecretFingerprint`].
+pub fn parse_fingerprint(hash: &str) -> Option<Fingerprint> {
+    let mut fingerprint = [0u8; FINGERPRINT_LEN];
+    hex::decode_to_slice(hash, &mut fingerprint).ok()?;
+    Some(fingerprint)
+}
+
+/// A fixed-size bloom filter keyed by fingerprints.
+///
+/// Fingerprints are already uniformly distributed, so probe positions are
+/// derived from their bytes directly (double hashing) instead of rehashing.
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    hashes: u32,
+}
+
+impl BloomFilter {
+    /// A filter sized for `capacity` keys at `bits_per_key` bits each.
+    pub fn new(capacity: usize, bits_per_key: usize) -> Self {
+        let num_bits = (capacity.max(1) * bits_per_key.max(1)).next_multiple_of(64) as u64;
+        let hashes = ((bits_per_key as f64) * std::f64::consts::LN_2).round().clamp(1.0, 16.0) as u32;
+        Self { bits: vec![0; (num_bits / 64) as usize], num_bits, hashes }
+    }
+
+    /// Bit index of the `i`th probe for a key, from two words of the fingerprint.
+    fn probe(&self, fingerprint: &Fingerprint, i: u64) -> u64 {
+        let h1 = u64::from_le_bytes(fingerprint[0..8].try_into().unwrap_or_default());
+        let h2 = u64::from_le_bytes(fingerprint[8..16].try_into().unwrap_or_default()) | 1;
+        h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits
+    }
+
+    pub fn insert(&mut self, fingerprint: &Fingerprint) {
+        for i in 0..self.hashes as u64 {
+            let bit = self.probe(fingerprint, i);
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+    }
+
+    /// `false` means definitely absent; `true` means possibly present.
+    pub fn may_contain(&self, fingerprint: &Fingerprint) -> bool {
+        (0..self.hashes as u64).all(|i| {
+            let bit = self.probe(fingerprint, i);
+            self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0
+        })
+    }
+
+    /// Heap memory held by the filter.
+    pub fn heap_bytes(&self) -> usize {
+        self.bits.len() * std::mem::size_of::<u64>()
+    }
+}
+
+impl std::fmt::Debug for BloomFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BloomFilter").field("num_bits", &self.num_bits).field("hashes", &self.hashes).finish()
+    }
+}
+
+/// A memory-mapped, sorted fingerprint file fronted by a bloom filter.
+pub struct FingerprintCache {
+    map: Mmap,
+    count: usize,
+    bloom: BloomFilter,
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    path.with_file_name(format!(".{}.tmp.{}", name, std::process::id()))
+}
+
+impl FingerprintCache {
+    /// Writes `fingerprints` (sorted and deduplicated here) to `path`, atomically
+    /// replacing any existing cache. Returns the number of fingerprints written.
+    pub fn write(path: &Path, fingerprints: impl IntoIterator<Item = Fingerprint>) -> Result<usize> {
+        let mut sorted: Vec<Fingerprint> = fingerprints.into_iter().collect();
+        sorted.sort_unstable();
+        sorted.dedup();
+        let frame_len = u32::try_from(HEADER_LEN - 8 + sorted.len() * FINGERPRINT_LEN)
+            .context("Too many fingerprints for a single cache file")?;
+
+        let temp = temp_path(path);
+        let written = (|| -> Result<()> {
+            let mut out = BufWriter::new(File::create(&temp)?);
+            out.write_all(&SKIPPABLE_FRAME_MAGIC.to_le_bytes())?;
+            out.write_all(&frame_len.to_le_bytes())?;
+            out.write_all(CACHE_MAGIC)?;
+            out.write_all(&CACHE_VERSION.to_le_bytes())?;
+            out.write_all(&(sorted.len() as u64).to_le_bytes())?;
+            for fingerprint in &sorted {
+                out.write_all(fingerprint)?;
+            }
+            out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+            fs::rename(&temp, path)?;
+            Ok(())
+        })();
+        if let Err(e) = written {
+            let _ = fs::remove_file(&temp);
+            return Err(e).with_context(|| format!("Failed to write fingerprint cache {}", path.display()));
+        }
+        Ok(sorted.len())
+    }
+
+    /// Rewrites the cache at `path` from a vault snapshot and maps the result.
+    /// Hashes that are not 64 hex characters are skipped.
+    pub fn rebuild(path: &Path, fingerprints: &[SecretFingerprint]) -> Result<Self> {
+        let parsed = fingerprints.iter().filter_map(|fp| {
+            let fingerprint = parse_fingerprint(&fp.hash);
+            if fingerprint.is_none() {
+                log::warn!("Skipping malformed vault fingerprint from '{}'", fp.provider);
+            }
+            fingerprint
+        });
+        Self::write(path, parsed)?;
+        Self::open(path)
+    }
+
+    /// Maps the cache at `path` with a [`DEFAULT_BLOOM_BITS_PER_KEY`] filter.
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::open_with_bloom(path, DEFAULT_BLOOM_BITS_PER_KEY)
+    }
+
+    /// Maps the cache at `path`, validates it, and builds a bloom filter with
+    /// `bits_per_key` bits per fingerprint.
+    pub fn open_with_bloom(path: &Path, bits_per_key: usize) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("Failed to open fingerprint cache {}", path.display()))?;
+        // SAFETY: cache files are replaced by rename and never modified in place
+        // (see `write`), so the mapped bytes cannot change underneath us.
+        let map = unsafe { Mmap::map(&file) }
+            .with_context(|| format!("Failed to map fingerprint cache {}", path.display()))?;
+        let count = Self::validate(&map).with_context(|| format!("Invalid fingerprint cache {}", path.display()))?;
+
+        let mut bloom = BloomFilter::new(count, bits_per_key);
+        for chunk in map[HEADER_LEN..].chunks_exact(FINGERPRINT_LEN) {
+            if let Ok(fingerprint) = chunk.try_into() {
+                bloom.insert(fingerprint);
+            }
+        }
+        Ok(Self { map, count, bloom })
+    }
+
+    fn validate(bytes: &[u8]) -> Result<usize> {
+        if bytes.len() < HEADER_LEN {
+            bail!("file is {} bytes, shorter than the {}-byte header", bytes.len(), HEADER_LEN);
+        }
+        let u32_at = |at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap_or_default());
+        if u32_at(0) != SKIPPABLE_FRAME_MAGIC || &bytes[8..12] != CACHE_MAGIC {
+            bail!("not a cleansh fingerprint cache");
+        }
+        if u32_at(12) != CACHE_VERSION {
+            bail!("unsupported format version {}", u32_at(12));
+        }
+        let count = u64::from_le_bytes(bytes[16..24].try_into().unwrap_or_default()) as usize;
+        let expected = count.checked_mul(FINGERPRINT_LEN).and_then(|n| n.checked_add(HEADER_LEN));
+        if expected != Some(bytes.len()) || u32_at(4) as usize != bytes.len() - 8 {
+            bail!("header declares {} fingerprints but the file is {} bytes", count, bytes.len());
+        }
+        let body = &bytes[HEADER_LEN..];
+        if body.chunks_exact(FINGERPRINT_LEN).zip(body.chunks_exact(FINGERPRINT_LEN).skip(1)).any(|(a, b)| a >= b) {
+            bail!("fingerprints are not strictly ascending");
+        }
+        Ok(count)
+    }
+
+    fn get(&self, index: usize) -> &Fingerprint {
+        let start = HEADER_LEN + index * FINGERPRINT_LEN;
+        self.map[start..start + FINGERPRINT_LEN].try_into().unwrap_or(&[0; FINGERPRINT_LEN])
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Whether the bloom filter lets `fingerprint` through to the binary search.
+    pub fn bloom_may_contain(&self, fingerprint: &Fingerprint) -> bool {
+        self.bloom.may_contain(fingerprint)
+    }
+
+    /// Exact membership: the bloom filter first, then a binary search of the mapping.
+    pub fn contains(&self, fingerprint: &Fingerprint) -> bool {
+        if !self.bloom.may_contain(fingerprint) {
+            return false;
+        }
+        let (mut low, mut high) = (0, self.count);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self.get(mid).cmp(fingerprint) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => return true,
+            }
+        }
+        false
+    }
+
+    /// Heap memory held by the cache. The mapped fingerprints are not included.
+    pub fn heap_bytes(&self) -> usize {
+        self.bloom.heap_bytes()
+    }
+}
+
+impl std::fmt::Debug for FingerprintCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FingerprintCache").field("count", &self.count).field("bloom", &self.bloom).finish()
+    }
+}
+
+#[derive(Debug, Default)]
+struct LeakIndex {
+    cache: Option<Arc<FingerprintCache>>,
+    /// Fingerprints synced individually since the cache was last swapped in.
+    recent: HashSet<Fingerprint>,
+}
+
+/// The set of secrets already remediated elsewhere, as seen by one engine.
+///
+/// Matches whose salted fingerprint is in the set are not teed to the
+/// remediation channel again. The salt must be the organization salt the
+/// orchestrator publishes fingerprints with.
+#[derive(Debug, Default)]
+pub struct KnownLeaks {
+    salt: Vec<u8>,
+    index: RwLock<LeakIndex>,
+}
+
+impl KnownLeaks {
+    pub fn new(salt: Vec<u8>) -> Self {
+        Self { salt, index: RwLock::default() }
+    }
+
+    /// Number of fingerprints in the set.
+    pub fn len(&self) -> usize {
+        let index = self.index.read().unwrap_or_else(PoisonError::into_inner);
+        index.cache.as_ref().map_or(0, |c| c.len()) + index.recent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, fingerprint: &Fingerprint) -> bool {
+        let index = self.index.read().unwrap_or_else(PoisonError::into_inner);
+        index.recent.contains(fingerprint) || index.cache.as_ref().is_some_and(|c| c.contains(fingerprint))
+    }
+
+    /// Whether `secret` was already remediated elsewhere.
+    pub fn contains_secret(&self, secret: &str) -> bool {
+        !self.is_empty() && self.contains(&salted_hash(secret, &self.salt))
+    }
+
+    /// Adds individually synced fingerprints. Hashes that are not 64 hex characters are skipped.
+    pub fn extend(&self, fingerprints: Vec<SecretFingerprint>) {
+        let mut index = self.index.write().unwrap_or_else(PoisonError::into_inner);
+        for fp in fingerprints {
+            match parse_fingerprint(&fp.hash) {
+                Some(fingerprint) => {
+                    index.recent.insert(fingerprint);
+                }
+                None => log::warn!("Skipping malformed vault fingerprint from '{}'", fp.provider),
+            }
+        }
+    }
+
+    /// Swaps in a freshly written cache. It holds the full vault contents, so
+    /// individually synced fingerprints are dropped.
+    pub fn replace_cache(&self, cache: Arc<FingerprintCache>) {
+        let mut index = self.index.write().unwrap_or_else(PoisonError::into_inner);
+        index.cache = Some(cache);
+        index.recent.clear();
+    }
+}
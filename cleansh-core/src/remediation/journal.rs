@@ -0,0 +1,242 @@
+// cleansh-core/src/remediation/journal.rs
+//! A record of what the self-healing orchestrator did, or would have done.
+//!
+//! In dry-run mode the orchestrator verifies matches as usual but journals each
+//! decision instead of calling `remediate`, so operators can review what
+//! auto-revocation would do before enabling it. Outside dry-run, every real
+//! remediation is journaled with its result, failures included.
+//!
+//! Journals never contain secrets: entries identify the match by rule and sample hash.
+
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::redaction_match::{canonical_sample_hash, RedactionMatch};
+use crate::remediation::ConfidenceLevel;
+
+/// Environment variable naming the JSONL file the orchestrator journals to.
+pub const JOURNAL_PATH_ENV: &str = "CLEANSH_REMEDIATION_JOURNAL";
+
+/// How many entries an in-memory journal keeps.
+pub const DEFAULT_MEMORY_JOURNAL_CAPACITY: usize = 1000;
+
+/// How far back from the end of a journal file `recent` reads.
+const RECENT_TAIL_BYTES: u64 = 64 * 1024;
+
+/// What came of one orchestrator decision.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum JournalOutcome {
+    /// Dry-run: `remediate` would have been called.
+    WouldRemediate,
+    /// Dry-run: the provider would not have acted, for `reason`.
+    WouldSkip { reason: String },
+    /// `remediate` ran and succeeded.
+    Remediated { message: String },
+    /// `remediate` ran and failed.
+    Failed { error: String },
+    /// The provider's rate limit denied the action. It was put back for one more
+    /// try (`requeued`) or, on that try or with the retry queue full, dropped.
+    Throttled { requeued: bool },
+}
+
+/// One journaled decision for one provider and match.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub timestamp: String,
+    pub provider: String,
+    pub rule_name: String,
+    /// Identifies the secret without revealing it.
+    pub sample_hash: String,
+    pub confidence: ConfidenceLevel,
+    /// Whether the live check confirmed the secret.
+    pub verified_live: bool,
+    pub dry_run: bool,
+    /// Whether the orchestrator decided to remediate (dry-run or not) and the
+    /// rate limit let it.
+    pub would_have_acted: bool,
+    #[serde(flatten)]
+    pub outcome: JournalOutcome,
+}
+
+impl JournalEntry {
+    pub fn new(
+        provider: &str,
+        redaction: &RedactionMatch,
+        confidence: ConfidenceLevel,
+        verified_live: bool,
+        dry_run: bool,
+        outcome: JournalOutcome,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now().to_rfc3339(),
+            provider: provider.to_string(),
+            rule_name: redaction.rule_name.clone(),
+            sample_hash: redaction.sample_hash.clone()
+                .unwrap_or_else(|| canonical_sample_hash(&redaction.rule_name, &redaction.original_string)),
+            confidence,
+            verified_live,
+            dry_run,
+            would_have_acted: !matches!(outcome, JournalOutcome::WouldSkip { .. } | JournalOutcome::Throttled { .. }),
+            outcome,
+        }
+    }
+}
+
+/// Where journal entries go. Implementations must be cheap to call from the
+/// orchestrator's task; `recent` feeds dashboards.
+pub trait RemediationJournal: Send + Sync {
+    fn record(&self, entry: &JournalEntry) -> Result<()>;
+
+    /// Up to `limit` of the newest entries, oldest first.
+    fn recent(&self, limit: usize) -> Result<Vec<JournalEntry>>;
+}
+
+/// Keeps the newest entries in memory. The orchestrator's default journal.
+#[derive(Debug)]
+pub struct MemoryJournal {
+    capacity: usize,
+    entries: Mutex<VecDeque<JournalEntry>>,
+}
+
+impl MemoryJournal {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity: capacity.max(1), entries: Mutex::new(VecDeque::new()) }
+    }
+
+    /// Every entry still held, oldest first.
+    pub fn entries(&self) -> Vec<JournalEntry> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect()
+    }
+}
+
+impl Default for MemoryJournal {
+    fn default() -> Self {
+        Self::new(DEFAULT_MEMORY_JOURNAL_CAPACITY)
+    }
+}
+
+impl RemediationJournal for MemoryJournal {
+    fn record(&self, entry: &JournalEntry) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry.clone());
+        Ok(())
+    }
+
+    fn recent(&self, limit: usize) -> Result<Vec<JournalEntry>> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(entries.iter().skip(entries.len().saturating_sub(limit)).cloned().collect())
+    }
+}
+
+/// Appends entries to a JSON Lines file, one object per line, flushed as they are
+/// written. `recent` reads the end of the file, so it also sees entries another
+/// process appended.
+#[derive(Debug)]
+pub struct JsonlJournal {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl JsonlJournal {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create journal directory {}", parent.display()))?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)
+            .with_context(|| format!("Failed to open remediation journal {}", path.display()))?;
+        Ok(Self { path, file: Mutex::new(file) })
+    }
+
+    /// Opens the journal named by `CLEANSH_REMEDIATION_JOURNAL`, if set.
+    pub fn from_env() -> Result<Option<Self>> {
+        match std::env::var_os(JOURNAL_PATH_ENV) {
+            Some(path) if !path.is_empty() => Self::open(PathBuf::from(path)).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl RemediationJournal for JsonlJournal {
+    fn record(&self, entry: &JournalEntry) -> Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        file.write_all(line.as_bytes())
+            .and_then(|()| file.flush())
+            .with_context(|| format!("Failed to write remediation journal {}", self.path.display()))
+    }
+
+    fn recent(&self, limit: usize) -> Result<Vec<JournalEntry>> {
+        let mut file = File::open(&self.path)
+            .with_context(|| format!("Failed to read remediation journal {}", self.path.display()))?;
+        let len = file.metadata()?.len();
+        let start = len.saturating_sub(RECENT_TAIL_BYTES);
+        file.seek(SeekFrom::Start(start))?;
+        let mut tail = String::new();
+        file.read_to_string(&mut tail)?;
+
+        // A tail that starts mid-file starts mid-line; its first line is partial.
+        let lines = tail.lines().skip(usize::from(start > 0));
+        let mut entries: Vec<JournalEntry> = lines.filter_map(|line| serde_json::from_str(line).ok()).collect();
+        entries.drain(..entries.len().saturating_sub(limit));
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(rule: &str, outcome: JournalOutcome) -> JournalEntry {
+        let redaction = RedactionMatch { rule_name: rule.to_string(), original_string: "sk_live_123".to_string(), ..Default::default() };
+        JournalEntry::new("mock", &redaction, ConfidenceLevel::High, true, true, outcome)
+    }
+
+    #[test]
+    fn test_memory_journal_keeps_the_newest_entries() -> Result<()> {
+        let journal = MemoryJournal::new(2);
+        for rule in ["a", "b", "c"] {
+            journal.record(&entry(rule, JournalOutcome::WouldRemediate))?;
+        }
+        let rules: Vec<String> = journal.recent(5)?.into_iter().map(|e| e.rule_name).collect();
+        assert_eq!(rules, ["b", "c"]);
+        assert_eq!(journal.recent(1)?[0].rule_name, "c");
+        Ok(())
+    }
+
+    #[test]
+    fn test_jsonl_journal_round_trips_without_the_secret() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let journal = JsonlJournal::open(dir.path().join("nested/journal.jsonl"))?;
+        journal.record(&entry("a", JournalOutcome::WouldSkip { reason: "below threshold".to_string() }))?;
+        journal.record(&entry("b", JournalOutcome::Failed { error: "403".to_string() }))?;
+
+        let raw = std::fs::read_to_string(journal.path())?;
+        assert!(!raw.contains("sk_live_123"));
+        assert!(raw.lines().next().unwrap().contains(r#""status":"would_skip""#));
+
+        let recent = journal.recent(1)?;
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].outcome, JournalOutcome::Failed { error: "403".to_string() });
+        assert!(recent[0].would_have_acted);
+        assert!(!journal.recent(2)?[0].would_have_acted);
+        Ok(())
+    }
+}
@@ -1,22 +1,81 @@
 // cleansh-core/src/remediation/mod.rs
 use anyhow::Result;
 use async_trait::async_trait;
+use crate::config::RedactionRule;
 use crate::redaction_match::RedactionMatch;
+use crate::severity::Severity;
 use serde::{Deserialize, Serialize};
 
+pub mod channel;
 pub mod fingerprint;
+pub mod fingerprint_cache;
 pub mod vault;
 pub mod providers;
 pub mod orchestrator;
+pub mod journal;
+pub mod notifier;
+pub mod state;
+pub mod sync;
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub enum ConfidenceLevel {
+    #[serde(alias = "low")]
     Low,      // Statistical anomaly only (e.g. random hex in a log)
+    #[serde(alias = "medium")]
     Medium,   // Anomaly + Contextual Keywords (e.g. "key: <random>")
+    #[serde(alias = "high")]
     High,     // Regex Match + High Entropy (e.g. ghp_...)
+    #[serde(alias = "critical")]
     Critical, // Verified Live via API check
 }
 
+impl ConfidenceLevel {
+    /// Maps a match's own confidence score onto a level. Only a live check reaches `Critical`.
+    pub fn from_score(score: f64) -> Self {
+        if score >= 1.0 {
+            ConfidenceLevel::High
+        } else if score >= 0.5 {
+            ConfidenceLevel::Medium
+        } else {
+            ConfidenceLevel::Low
+        }
+    }
+}
+
+/// The confidence a detection is acted on with. A provider's live check makes
+/// it `Critical`; otherwise it starts from the match's score band (without a
+/// score, `High` for regex rules and `Medium` for anything else) and the rule's
+/// severity moves it:
+///
+/// | severity            | effect          |
+/// |---------------------|-----------------|
+/// | `critical`          | at least `High` |
+/// | `high`              | at least `Medium` |
+/// | `medium` (or unset) | none            |
+/// | `low`               | at most `Medium` |
+/// | `info`              | at most `Low`   |
+///
+/// Severity alone never reaches `Critical`.
+pub fn confidence_from(rule: &RedactionRule, score: Option<f64>, is_live: bool) -> ConfidenceLevel {
+    if is_live {
+        return ConfidenceLevel::Critical;
+    }
+    let base = match score {
+        Some(score) => ConfidenceLevel::from_score(score),
+        None if rule.pattern_type == "regex" => ConfidenceLevel::High,
+        None => ConfidenceLevel::Medium,
+    };
+    let at_least = |floor: ConfidenceLevel| if base < floor { floor } else { base };
+    let at_most = |cap: ConfidenceLevel| if base > cap { cap } else { base };
+    match Severity::from_rule(rule.severity.as_deref()) {
+        Severity::Critical => at_least(ConfidenceLevel::High),
+        Severity::High => at_least(ConfidenceLevel::Medium),
+        Severity::Medium => base,
+        Severity::Low => at_most(ConfidenceLevel::Medium),
+        Severity::Info => at_most(ConfidenceLevel::Low),
+    }
+}
+
 #[async_trait]
 pub trait Remediator: Send + Sync {
     fn name(&self) -> &str;
@@ -33,11 +92,202 @@ pub trait Remediator: Send + Sync {
     fn auto_remediation_threshold(&self) -> ConfidenceLevel;
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Operator credentials a provider revokes secrets with, e.g. an OAuth app's
+/// client ID and secret.
+#[derive(Clone, PartialEq, Eq)]
+pub struct OperatorCredentials {
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+impl std::fmt::Debug for OperatorCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OperatorCredentials")
+            .field("client_id", &self.client_id)
+            .field("client_secret", &"..")
+            .finish()
+    }
+}
+
+/// Settings for providers built with a `with_config` constructor.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RemediatorConfig {
+    /// API origin to call instead of the provider's public one (e.g. a mock server).
+    pub base_url: Option<String>,
+    /// Credentials to revoke with. Without them the provider only reports.
+    pub credentials: Option<OperatorCredentials>,
+    /// Verify and report live secrets, but never revoke them.
+    pub report_only: bool,
+}
+
+impl RemediatorConfig {
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into().trim_end_matches('/').to_string());
+        self
+    }
+
+    pub fn with_credentials(mut self, client_id: impl Into<String>, client_secret: impl Into<String>) -> Self {
+        self.credentials = Some(OperatorCredentials { client_id: client_id.into(), client_secret: client_secret.into() });
+        self
+    }
+
+    pub fn with_report_only(mut self, report_only: bool) -> Self {
+        self.report_only = report_only;
+        self
+    }
+
+    /// Credentials from the environment variables `id_env` and `secret_env`, when
+    /// both are set and non-empty.
+    pub fn credentials_from_env(id_env: &str, secret_env: &str) -> Option<OperatorCredentials> {
+        let read = |var: &str| std::env::var(var).ok().filter(|v| !v.is_empty());
+        Some(OperatorCredentials { client_id: read(id_env)?, client_secret: read(secret_env)? })
+    }
+}
+
+/// Default for [`RemediationSettings::max_actions`]: actions per provider per minute.
+pub const DEFAULT_MAX_ACTIONS: usize = 5;
+
+fn default_max_actions() -> usize {
+    DEFAULT_MAX_ACTIONS
+}
+
+/// The `remediation` section of a remediation config file: which providers the
+/// TUI's orchestrator runs, and how.
+///
+/// ```toml
+/// [remediation]
+/// dry_run = false
+///
+/// [remediation.providers.github]
+/// threshold = "critical"
+///
+/// [remediation.providers.stripe]
+/// report_only = true
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemediationSettings {
+    pub providers: providers::ProvidersConfig,
+    /// Verify and journal, but never remediate.
+    pub dry_run: bool,
+    /// Actions per provider per minute, for providers without their own `max_actions`.
+    #[serde(default = "default_max_actions")]
+    pub max_actions: usize,
+    /// Salt for match fingerprints, shared across an organization so they agree.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub org_salt: Option<String>,
+    /// The organization whose fingerprints a Redis vault holds
+    /// (default: `default`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub org: Option<String>,
+}
+
+/// Default for [`RemediationSettings::org`].
+pub const DEFAULT_ORG: &str = "default";
+
+impl Default for RemediationSettings {
+    fn default() -> Self {
+        Self { providers: Default::default(), dry_run: false, max_actions: DEFAULT_MAX_ACTIONS, org_salt: None, org: None }
+    }
+}
+
+#[derive(Deserialize)]
+struct RemediationFile {
+    #[serde(default)]
+    remediation: RemediationSettings,
+}
+
+impl RemediationSettings {
+    /// Reads the `remediation` section of `path`, in the format its extension names.
+    pub fn load_from_file(path: &std::path::Path) -> Result<Self> {
+        use anyhow::Context;
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read remediation config {}", path.display()))?;
+        let file: RemediationFile = crate::config_format::ConfigFormat::detect(path, None)
+            .parse(&text)
+            .with_context(|| format!("Invalid remediation config {}", path.display()))?;
+        Ok(file.remediation)
+    }
+
+    /// The vault `CLEANSH_VAULT_URL` names (see [`vault::from_url`]), or else the
+    /// Redis vault at `CLEANSH_REDIS_URL`, for `org`; `None` when neither is set.
+    pub async fn vault_from_env(&self) -> Result<Option<std::sync::Arc<dyn vault::FingerprintVault>>> {
+        let org = self.org.as_deref().unwrap_or(DEFAULT_ORG);
+        let url = [vault::VAULT_URL_ENV, vault::REDIS_URL_ENV]
+            .into_iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|url| !url.trim().is_empty());
+        match url {
+            Some(url) => vault::from_url(url.trim(), org).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// The governor limits: `max_actions` for every provider, overridden per provider.
+    pub fn governor(&self) -> orchestrator::GovernorConfig {
+        let mut governor = orchestrator::GovernorConfig::new(self.max_actions);
+        for (name, settings) in &self.providers.providers {
+            if let Some(limit) = settings.max_actions {
+                governor = governor.with_provider_limit(name.to_ascii_lowercase(), limit);
+            }
+        }
+        governor
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RemediationOutcome {
     pub provider: String,
     pub action: String,
     pub successful: bool,
     pub message: String,
     pub confidence_boost: bool, // True if verification moved confidence to 'Critical'
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ConfidenceLevel::{Critical, High, Low, Medium};
+
+    fn rule(pattern_type: &str, severity: Option<&str>) -> RedactionRule {
+        RedactionRule {
+            name: "rule".to_string(),
+            pattern_type: pattern_type.to_string(),
+            severity: severity.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn confidence_from_covers_severity_pattern_type_and_liveness() {
+        // (severity, regex, entropy) for matches without a score and no live check.
+        let table = [
+            (None, High, Medium),
+            (Some("info"), Low, Low),
+            (Some("low"), Medium, Medium),
+            (Some("medium"), High, Medium),
+            (Some("high"), High, Medium),
+            (Some("critical"), High, High),
+            (Some("bogus"), High, Medium),
+        ];
+        for (severity, regex, entropy) in table {
+            assert_eq!(confidence_from(&rule("regex", severity), None, false), regex, "regex {:?}", severity);
+            assert_eq!(confidence_from(&rule("entropy", severity), None, false), entropy, "entropy {:?}", severity);
+            for pattern_type in ["regex", "entropy"] {
+                assert_eq!(confidence_from(&rule(pattern_type, severity), None, true), Critical, "{} {:?} live", pattern_type, severity);
+            }
+        }
+    }
+
+    #[test]
+    fn confidence_from_moves_the_score_band() {
+        // A plain pattern match (0.8) is Medium on its own.
+        assert_eq!(confidence_from(&rule("regex", None), Some(0.8), false), Medium);
+        assert_eq!(confidence_from(&rule("regex", Some("critical")), Some(0.8), false), High);
+        assert_eq!(confidence_from(&rule("regex", Some("high")), Some(0.2), false), Medium);
+        assert_eq!(confidence_from(&rule("regex", Some("low")), Some(1.0), false), Medium);
+        assert_eq!(confidence_from(&rule("regex", Some("info")), Some(1.0), false), Low);
+        assert_eq!(confidence_from(&rule("entropy", Some("critical")), Some(2.5), false), High);
+        assert_eq!(confidence_from(&rule("entropy", Some("info")), Some(0.2), true), Critical);
+    }
+}
@@ -0,0 +1,681 @@
+// cleansh-core/src/remediation/providers/azure.rs
+//! Remediation provider for leaked Azure storage account keys and SAS tokens.
+//!
+//! A storage key is verified with a Shared Key signed List Containers call and
+//! rotated through the management API's `regenerateKey`, using an operator token
+//! obtained with the `AZURE_TENANT_ID`/`AZURE_CLIENT_ID`/`AZURE_CLIENT_SECRET`
+//! service principal (or `AZURE_REMEDIATION_TOKEN`). Which of `key1`/`key2`
+//! leaked is found by re-verifying after each rotation. A SAS token cannot be
+//! revoked on its own, so it is reported as needing a key rotation.
+
+use async_trait::async_trait;
+use crate::remediation::{Remediator, RemediationOutcome, ConfidenceLevel};
+use crate::redaction_match::RedactionMatch;
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use hmac::{Hmac, Mac};
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use sha2::Sha256;
+
+/// Default Microsoft Entra ID (login) origin.
+pub const AZURE_LOGIN_BASE: &str = "https://login.microsoftonline.com";
+/// Default Azure Resource Manager origin.
+pub const AZURE_MANAGEMENT_BASE: &str = "https://management.azure.com";
+/// Endpoint suffix used when a connection string does not name one.
+pub const AZURE_DEFAULT_ENDPOINT_SUFFIX: &str = "core.windows.net";
+
+/// Environment variable holding an operator access token, checked before the service principal.
+pub const REMEDIATION_TOKEN_ENV: &str = "AZURE_REMEDIATION_TOKEN";
+pub const TENANT_ID_ENV: &str = "AZURE_TENANT_ID";
+pub const CLIENT_ID_ENV: &str = "AZURE_CLIENT_ID";
+pub const CLIENT_SECRET_ENV: &str = "AZURE_CLIENT_SECRET";
+pub const SUBSCRIPTION_ID_ENV: &str = "AZURE_SUBSCRIPTION_ID";
+/// Optional; without it the account is looked up across the subscription.
+pub const RESOURCE_GROUP_ENV: &str = "AZURE_RESOURCE_GROUP";
+/// Account name used for a bare key matched without its connection string.
+pub const STORAGE_ACCOUNT_ENV: &str = "AZURE_STORAGE_ACCOUNT";
+
+/// Storage REST API version the probe is signed for.
+const STORAGE_API_VERSION: &str = "2021-08-06";
+/// Resource Manager API version for `Microsoft.Storage`.
+const MANAGEMENT_API_VERSION: &str = "2023-01-01";
+const MANAGEMENT_SCOPE: &str = "https://management.azure.com/.default";
+/// An account has two keys; either may be the one that leaked.
+const KEY_NAMES: [&str; 2] = ["key1", "key2"];
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// A storage account key and the account it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageKey {
+    pub account: String,
+    pub key: String,
+    pub endpoint_suffix: String,
+}
+
+impl StorageKey {
+    /// Parses `AccountName=...;AccountKey=...` pairs from a connection string or
+    /// fragment, or a bare key belonging to `default_account`.
+    pub fn parse(secret: &str, default_account: Option<&str>) -> Option<Self> {
+        let secret = secret.trim();
+        let field = |name: &str| {
+            secret.split(';').find_map(|pair| {
+                let (key, value) = pair.split_once('=')?;
+                key.trim().eq_ignore_ascii_case(name).then(|| value.trim().to_string())
+            })
+        };
+        let (account, key) = match field("AccountKey") {
+            Some(key) => (field("AccountName").or_else(|| default_account.map(str::to_string))?, key),
+            None => (default_account?.to_string(), secret.to_string()),
+        };
+        if account.is_empty() || STANDARD.decode(&key).is_err() {
+            return None;
+        }
+        Some(Self {
+            account,
+            key,
+            endpoint_suffix: field("EndpointSuffix").unwrap_or_else(|| AZURE_DEFAULT_ENDPOINT_SUFFIX.to_string()),
+        })
+    }
+
+    /// The Shared Key `Authorization` header for a `GET` of `/?comp=list&maxresults=1`.
+    fn list_containers_authorization(&self, date: &str) -> Result<String> {
+        let string_to_sign = list_containers_string_to_sign(&self.account, date);
+        let key = STANDARD.decode(&self.key).context("Storage key is not valid base64")?;
+        let mut mac = HmacSha256::new_from_slice(&key).map_err(|e| anyhow!("Invalid storage key: {}", e))?;
+        mac.update(string_to_sign.as_bytes());
+        Ok(format!("SharedKey {}:{}", self.account, STANDARD.encode(mac.finalize().into_bytes())))
+    }
+}
+
+/// The Blob service string-to-sign for List Containers: the verb, eleven empty
+/// standard headers, the `x-ms-*` headers, and the canonicalized resource.
+fn list_containers_string_to_sign(account: &str, date: &str) -> String {
+    format!(
+        "GET\n\n\n\n\n\n\n\n\n\n\n\nx-ms-date:{}\nx-ms-version:{}\n/{}/\ncomp:list\nmaxresults:1",
+        date, STORAGE_API_VERSION, account,
+    )
+}
+
+/// What a match contains.
+enum LeakedSecret {
+    StorageKey(StorageKey),
+    SasToken,
+}
+
+impl LeakedSecret {
+    fn from_match(redaction: &RedactionMatch, default_account: Option<&str>) -> Option<Self> {
+        if redaction.rule_name == "azure_sas_token" || is_sas_token(&redaction.original_string) {
+            return Some(Self::SasToken);
+        }
+        StorageKey::parse(&redaction.original_string, default_account).map(Self::StorageKey)
+    }
+}
+
+fn is_sas_token(secret: &str) -> bool {
+    secret.contains("sv=") && secret.contains("sig=")
+}
+
+/// Outcome of probing a leaked key.
+enum KeyStatus {
+    Live,
+    Revoked,
+    Unknown(StatusCode),
+}
+
+/// The service principal rotations are made with.
+#[derive(Debug, Clone)]
+pub struct ServicePrincipal {
+    pub tenant_id: String,
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+impl ServicePrincipal {
+    /// Reads `AZURE_TENANT_ID`, `AZURE_CLIENT_ID` and `AZURE_CLIENT_SECRET`; `None` unless all are set.
+    pub fn from_env() -> Option<Self> {
+        let var = |name| std::env::var(name).ok().filter(|v| !v.is_empty());
+        Some(Self { tenant_id: var(TENANT_ID_ENV)?, client_id: var(CLIENT_ID_ENV)?, client_secret: var(CLIENT_SECRET_ENV)? })
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct StorageAccountList {
+    value: Vec<StorageAccountResource>,
+}
+
+#[derive(Deserialize)]
+struct StorageAccountResource {
+    id: String,
+    name: String,
+}
+
+pub struct AzureRemediator {
+    client: Client,
+    /// Replaces `https://<account>.blob.<suffix>` when set.
+    blob_base: Option<String>,
+    login_base: String,
+    management_base: String,
+    operator_token: Option<String>,
+    service_principal: Option<ServicePrincipal>,
+    subscription_id: Option<String>,
+    resource_group: Option<String>,
+    default_account: Option<String>,
+}
+
+impl Default for AzureRemediator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AzureRemediator {
+    /// Creates a provider against the public Azure endpoints, reading the
+    /// operator credentials and subscription from the `AZURE_*` variables.
+    pub fn new() -> Self {
+        let var = |name| std::env::var(name).ok().filter(|v: &String| !v.is_empty());
+        Self {
+            client: Client::new(),
+            blob_base: None,
+            login_base: AZURE_LOGIN_BASE.to_string(),
+            management_base: AZURE_MANAGEMENT_BASE.to_string(),
+            operator_token: var(REMEDIATION_TOKEN_ENV),
+            service_principal: ServicePrincipal::from_env(),
+            subscription_id: var(SUBSCRIPTION_ID_ENV),
+            resource_group: var(RESOURCE_GROUP_ENV),
+            default_account: var(STORAGE_ACCOUNT_ENV),
+        }
+    }
+
+    /// Points every endpoint (blob, login, management) at one origin, e.g. a local mock server.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        let base = base_url.into().trim_end_matches('/').to_string();
+        self.blob_base = Some(base.clone());
+        self.login_base = base.clone();
+        self.management_base = base;
+        self
+    }
+
+    /// Overrides the operator access token instead of using the service principal.
+    pub fn with_operator_token(mut self, token: impl Into<String>) -> Self {
+        self.operator_token = Some(token.into());
+        self
+    }
+
+    /// Overrides the service principal read from the environment.
+    pub fn with_service_principal(mut self, principal: ServicePrincipal) -> Self {
+        self.service_principal = Some(principal);
+        self
+    }
+
+    /// Sets the subscription, and optionally the resource group, holding the account.
+    pub fn with_subscription(mut self, subscription_id: impl Into<String>, resource_group: Option<String>) -> Self {
+        self.subscription_id = Some(subscription_id.into());
+        self.resource_group = resource_group;
+        self
+    }
+
+    /// The account a bare key, matched without its connection string, belongs to.
+    pub fn with_storage_account(mut self, account: impl Into<String>) -> Self {
+        self.default_account = Some(account.into());
+        self
+    }
+
+    fn blob_endpoint(&self, key: &StorageKey) -> String {
+        match &self.blob_base {
+            Some(base) => base.clone(),
+            None => format!("https://{}.blob.{}", key.account, key.endpoint_suffix),
+        }
+    }
+
+    /// Lists at most one container with the leaked key.
+    async fn probe(&self, key: &StorageKey) -> Result<KeyStatus> {
+        let date = chrono::Utc::now().format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        let resp = self.client
+            .get(format!("{}/", self.blob_endpoint(key)))
+            .query(&[("comp", "list"), ("maxresults", "1")])
+            .header("x-ms-date", &date)
+            .header("x-ms-version", STORAGE_API_VERSION)
+            .header("Authorization", key.list_containers_authorization(&date)?)
+            .header("User-Agent", "CleanSH-Proactive-Engine")
+            .send()
+            .await
+            .context("Azure List Containers request failed")?;
+
+        Ok(match resp.status() {
+            s if s.is_success() => KeyStatus::Live,
+            // `AuthenticationFailed`: the signature no longer matches either key.
+            StatusCode::FORBIDDEN => KeyStatus::Revoked,
+            s => KeyStatus::Unknown(s),
+        })
+    }
+
+    /// The operator's management token, or `None` when no credentials are configured.
+    async fn operator_token(&self) -> Result<Option<String>> {
+        if let Some(token) = &self.operator_token {
+            return Ok(Some(token.clone()));
+        }
+        let Some(principal) = &self.service_principal else { return Ok(None) };
+        let resp = self.client
+            .post(format!("{}/{}/oauth2/v2.0/token", self.login_base, principal.tenant_id))
+            .header("User-Agent", "CleanSH-Proactive-Engine")
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", &principal.client_id),
+                ("client_secret", &principal.client_secret),
+                ("scope", MANAGEMENT_SCOPE),
+            ])
+            .send()
+            .await
+            .context("Azure token request failed")?;
+        if !resp.status().is_success() {
+            bail!("Operator credentials were rejected: {}", resp.status());
+        }
+        Ok(Some(resp.json::<TokenResponse>().await.context("Malformed token response")?.access_token))
+    }
+
+    /// The storage account's resource id, looked up in the subscription when no
+    /// resource group is configured.
+    async fn account_resource_id(&self, subscription_id: &str, account: &str, token: &str) -> Result<Option<String>> {
+        if let Some(group) = &self.resource_group {
+            return Ok(Some(format!(
+                "/subscriptions/{}/resourceGroups/{}/providers/Microsoft.Storage/storageAccounts/{}",
+                subscription_id, group, account,
+            )));
+        }
+        let resp = self.client
+            .get(format!("{}/subscriptions/{}/providers/Microsoft.Storage/storageAccounts", self.management_base, subscription_id))
+            .query(&[("api-version", MANAGEMENT_API_VERSION)])
+            .bearer_auth(token)
+            .header("User-Agent", "CleanSH-Proactive-Engine")
+            .send()
+            .await
+            .context("Azure storage account lookup failed")?;
+        if !resp.status().is_success() {
+            bail!("Azure storage account lookup returned {}", resp.status());
+        }
+        let accounts = resp.json::<StorageAccountList>().await.context("Malformed storage account list")?;
+        Ok(accounts.value.into_iter().find(|a| a.name.eq_ignore_ascii_case(account)).map(|a| a.id))
+    }
+
+    async fn regenerate(&self, resource_id: &str, key_name: &str, token: &str) -> Result<StatusCode> {
+        let resp = self.client
+            .post(format!("{}{}/regenerateKey", self.management_base, resource_id))
+            .query(&[("api-version", MANAGEMENT_API_VERSION)])
+            .bearer_auth(token)
+            .header("User-Agent", "CleanSH-Proactive-Engine")
+            .json(&serde_json::json!({ "keyName": key_name }))
+            .send()
+            .await
+            .context("Azure key regeneration request failed")?;
+        Ok(resp.status())
+    }
+
+    async fn remediate_key(&self, key: &StorageKey) -> Result<RemediationOutcome> {
+        let account = key.account.as_str();
+
+        // Step 1: Prove it's real. A key Azure already rejects needs no action.
+        match self.probe(key).await? {
+            KeyStatus::Live => {}
+            KeyStatus::Revoked => {
+                return Ok(self.outcome(
+                    "ALREADY_DEAD",
+                    true,
+                    format!("Azure storage key for '{}' is already rotated; no action needed.", account),
+                    false,
+                ));
+            }
+            KeyStatus::Unknown(status) => {
+                return Ok(self.outcome(
+                    "ABORT_REMEDIATION",
+                    false,
+                    format!("Could not verify Azure storage key for '{}': List Containers returned {}.", account, status),
+                    false,
+                ));
+            }
+        }
+
+        // Step 2: Find the account with the operator's credentials.
+        let Some(token) = self.operator_token().await? else {
+            return Ok(self.outcome(
+                "ABORT_REMEDIATION",
+                false,
+                format!(
+                    "Live Azure storage key for '{}' detected, but neither {} nor {}/{}/{} are set.",
+                    account, REMEDIATION_TOKEN_ENV, TENANT_ID_ENV, CLIENT_ID_ENV, CLIENT_SECRET_ENV,
+                ),
+                true,
+            ));
+        };
+        let Some(subscription_id) = self.subscription_id.as_deref() else {
+            return Ok(self.outcome(
+                "ABORT_REMEDIATION",
+                false,
+                format!("Live Azure storage key for '{}' detected, but {} is not set.", account, SUBSCRIPTION_ID_ENV),
+                true,
+            ));
+        };
+        let Some(resource_id) = self.account_resource_id(subscription_id, account, &token).await? else {
+            return Ok(self.outcome(
+                "ABORT_REMEDIATION",
+                false,
+                format!("Live Azure storage key detected, but account '{}' is not in subscription {}.", account, subscription_id),
+                true,
+            ));
+        };
+
+        // Step 3: Rotate key1, then key2 if the leaked key still works.
+        for key_name in KEY_NAMES {
+            log::info!("Verified live Azure storage key for '{}'. Regenerating {}...", account, key_name);
+            let status = self.regenerate(&resource_id, key_name, &token).await?;
+            if !status.is_success() {
+                return Ok(self.outcome(
+                    "ROTATION_FAILED",
+                    false,
+                    format!("Azure rejected regenerating {} of '{}': {}.", key_name, account, status),
+                    true,
+                ));
+            }
+            match self.probe(key).await? {
+                KeyStatus::Live => continue,
+                KeyStatus::Revoked => {
+                    return Ok(self.outcome(
+                        "KEY_ROTATED",
+                        true,
+                        format!("Live Azure storage key detected; {} of '{}' regenerated.", key_name, account),
+                        true,
+                    ));
+                }
+                KeyStatus::Unknown(status) => {
+                    return Ok(self.outcome(
+                        "ROTATION_UNVERIFIED",
+                        false,
+                        format!("Regenerated {} of '{}', but re-verification returned {}.", key_name, account, status),
+                        true,
+                    ));
+                }
+            }
+        }
+        Ok(self.outcome(
+            "ROTATION_FAILED",
+            false,
+            format!("Regenerated both keys of '{}', but the leaked key still authenticates.", account),
+            true,
+        ))
+    }
+
+    fn outcome(&self, action: &str, successful: bool, message: String, confidence_boost: bool) -> RemediationOutcome {
+        RemediationOutcome {
+            provider: self.name().to_string(),
+            action: action.to_string(),
+            successful,
+            message,
+            confidence_boost,
+        }
+    }
+}
+
+#[async_trait]
+impl Remediator for AzureRemediator {
+    fn name(&self) -> &str { "azure" }
+
+    fn can_handle(&self, redaction: &RedactionMatch) -> bool {
+        matches!(redaction.rule_name.as_str(), "azure_storage_key" | "azure_sas_token")
+    }
+
+    fn auto_remediation_threshold(&self) -> ConfidenceLevel {
+        // Regenerating a key breaks every client using it; only act on verified keys.
+        ConfidenceLevel::Critical
+    }
+
+    async fn verify_live_status(&self, secret: &str) -> Result<bool> {
+        // A SAS token names no account, so there is nothing to probe it against.
+        if is_sas_token(secret) {
+            return Ok(false);
+        }
+        let Some(key) = StorageKey::parse(secret, self.default_account.as_deref()) else { return Ok(false) };
+        Ok(matches!(self.probe(&key).await?, KeyStatus::Live))
+    }
+
+    async fn remediate(&self, redaction: &RedactionMatch) -> Result<RemediationOutcome> {
+        match LeakedSecret::from_match(redaction, self.default_account.as_deref()) {
+            Some(LeakedSecret::StorageKey(key)) => self.remediate_key(&key).await,
+            Some(LeakedSecret::SasToken) => Ok(self.outcome(
+                "REQUIRES_KEY_ROTATION",
+                false,
+                "Azure SAS tokens cannot be revoked individually. Regenerate the storage account key that \
+                 signed it, or revoke its stored access policy, or wait for its expiry (se=)."
+                    .to_string(),
+                false,
+            )),
+            None => Ok(self.outcome(
+                "ABORT_REMEDIATION",
+                false,
+                format!("Match is not an Azure storage key with a known account (set {} for bare keys).", STORAGE_ACCOUNT_ENV),
+                false,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RedactionConfig;
+    use crate::engines::regex_engine::RegexEngine;
+    use crate::engine::SanitizationEngine;
+    use mockito::Matcher;
+
+    const ACCOUNT: &str = "demostore";
+    const SAS: &str = "sv=2022-11-02&ss=b&srt=co&sp=rl&se=2026-12-31T00:00:00Z&sig=Zm9vYmFyYmF6cXV4MTIzNDU2Nzg5MA%3D";
+
+    /// A throwaway 64-byte key in the 88-character form Azure shows.
+    fn test_key() -> String {
+        STANDARD.encode([7u8; 64])
+    }
+
+    fn connection_string() -> String {
+        format!("DefaultEndpointsProtocol=https;AccountName={};AccountKey={};EndpointSuffix=core.windows.net", ACCOUNT, test_key())
+    }
+
+    fn azure_match(rule_name: &str, secret: &str) -> RedactionMatch {
+        RedactionMatch {
+            rule_name: rule_name.to_string(),
+            original_string: secret.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn provider(server: &mockito::Server) -> AzureRemediator {
+        AzureRemediator::new()
+            .with_base_url(server.url())
+            .with_operator_token("eyJ.operator")
+            .with_subscription("sub-1", Some("rg-1".to_string()))
+    }
+
+    const REGENERATE_PATH: &str = "/subscriptions/sub-1/resourceGroups/rg-1/providers/Microsoft.Storage/storageAccounts/demostore/regenerateKey";
+
+    #[test]
+    fn test_default_rules_match_connection_strings_and_sas_tokens() -> Result<()> {
+        let log = format!("conn={}\nurl=https://demostore.blob.core.windows.net/c?{}\n", connection_string(), SAS);
+        let engine = RegexEngine::new(RedactionConfig::load_default_rules()?)?;
+        let matches = engine.find_matches_for_ui(&log, "app.log")?;
+        let key_match = matches.iter().find(|m| m.rule_name == "azure_storage_key").expect("storage key should match");
+        assert_eq!(StorageKey::parse(&key_match.original_string, None).map(|k| k.account).as_deref(), Some(ACCOUNT));
+        assert!(matches.iter().any(|m| m.rule_name == "azure_sas_token" && m.original_string == SAS));
+        Ok(())
+    }
+
+    #[test]
+    fn test_storage_key_parses_connection_strings_and_bare_keys() {
+        let key = StorageKey::parse(&connection_string(), None).expect("connection string");
+        assert_eq!((key.account.as_str(), key.key.as_str(), key.endpoint_suffix.as_str()), (ACCOUNT, test_key().as_str(), "core.windows.net"));
+        assert_eq!(StorageKey::parse(&test_key(), Some("other")).map(|k| k.account).as_deref(), Some("other"));
+        assert!(StorageKey::parse(&test_key(), None).is_none());
+        assert!(StorageKey::parse("not base64!", Some("other")).is_none());
+    }
+
+    #[test]
+    fn test_list_containers_is_signed_with_the_leaked_key() -> Result<()> {
+        let key = StorageKey::parse(&connection_string(), None).expect("connection string");
+        let date = "Mon, 02 Mar 2026 10:00:00 GMT";
+        let string_to_sign = list_containers_string_to_sign(ACCOUNT, date);
+        assert!(string_to_sign.starts_with("GET\n\n\n\n\n\n\n\n\n\n\n\nx-ms-date:"));
+        assert!(string_to_sign.ends_with("/demostore/\ncomp:list\nmaxresults:1"));
+
+        let mut mac = HmacSha256::new_from_slice(&[7u8; 64]).unwrap();
+        mac.update(string_to_sign.as_bytes());
+        let expected = format!("SharedKey demostore:{}", STANDARD.encode(mac.finalize().into_bytes()));
+        assert_eq!(key.list_containers_authorization(date)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_can_handle_azure_rules_only() {
+        let provider = AzureRemediator::new();
+        assert!(provider.can_handle(&azure_match("azure_storage_key", "")));
+        assert!(provider.can_handle(&azure_match("azure_sas_token", "")));
+        assert!(!provider.can_handle(&azure_match("generic_token", &connection_string())));
+    }
+
+    #[tokio::test]
+    async fn test_sas_token_requires_key_rotation_without_calling_azure() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        let get = server.mock("GET", Matcher::Any).expect(0).create_async().await;
+        let post = server.mock("POST", Matcher::Any).expect(0).create_async().await;
+
+        let provider = provider(&server);
+        assert!(!provider.verify_live_status(SAS).await?);
+        let outcome = provider.remediate(&azure_match("azure_sas_token", SAS)).await?;
+        assert_eq!(outcome.action, "REQUIRES_KEY_ROTATION");
+        assert!(!outcome.successful && !outcome.confidence_boost);
+        assert!(outcome.message.contains("cannot be revoked individually"));
+        get.assert_async().await;
+        post.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rotated_storage_key_is_already_dead() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        let probe = server.mock("GET", "/")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("comp".into(), "list".into()),
+                Matcher::UrlEncoded("maxresults".into(), "1".into()),
+            ]))
+            .match_header("authorization", Matcher::Regex("^SharedKey demostore:".into()))
+            .match_header("x-ms-version", STORAGE_API_VERSION)
+            .with_status(403)
+            .expect(2)
+            .create_async()
+            .await;
+        let regenerate = server.mock("POST", Matcher::Any).expect(0).create_async().await;
+
+        let provider = provider(&server);
+        assert!(!provider.verify_live_status(&connection_string()).await?);
+        let outcome = provider.remediate(&azure_match("azure_storage_key", &connection_string())).await?;
+        assert_eq!(outcome.action, "ALREADY_DEAD");
+        assert!(outcome.successful);
+        probe.assert_async().await;
+        regenerate.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_live_key2_is_found_by_reverifying_after_rotation() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        // Live before rotation and after key1 is regenerated; dead once key2 is.
+        let live = server.mock("GET", "/").match_query(Matcher::Any).with_status(200).expect(2).create_async().await;
+        let dead = server.mock("GET", "/").match_query(Matcher::Any).with_status(403).expect(1).create_async().await;
+        let key1 = server.mock("POST", REGENERATE_PATH)
+            .match_query(Matcher::UrlEncoded("api-version".into(), MANAGEMENT_API_VERSION.into()))
+            .match_header("authorization", "Bearer eyJ.operator")
+            .match_body(Matcher::Json(serde_json::json!({ "keyName": "key1" })))
+            .with_status(200)
+            .create_async()
+            .await;
+        let key2 = server.mock("POST", REGENERATE_PATH)
+            .match_query(Matcher::Any)
+            .match_body(Matcher::Json(serde_json::json!({ "keyName": "key2" })))
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let outcome = provider(&server).remediate(&azure_match("azure_storage_key", &connection_string())).await?;
+        assert_eq!(outcome.action, "KEY_ROTATED");
+        assert!(outcome.successful && outcome.confidence_boost);
+        assert!(outcome.message.contains("key2"), "{}", outcome.message);
+        for mock in [live, dead, key1, key2] {
+            mock.assert_async().await;
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_service_principal_token_and_account_lookup() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/").match_query(Matcher::Any).with_status(200).expect(1).create_async().await;
+        server.mock("GET", "/").match_query(Matcher::Any).with_status(403).create_async().await;
+        let token = server.mock("POST", "/tenant-1/oauth2/v2.0/token")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("grant_type".into(), "client_credentials".into()),
+                Matcher::UrlEncoded("client_id".into(), "app-1".into()),
+                Matcher::UrlEncoded("scope".into(), MANAGEMENT_SCOPE.into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{"access_token":"eyJ.principal","token_type":"Bearer","expires_in":3599}"#)
+            .create_async()
+            .await;
+        server.mock("GET", "/subscriptions/sub-1/providers/Microsoft.Storage/storageAccounts")
+            .match_query(Matcher::Any)
+            .match_header("authorization", "Bearer eyJ.principal")
+            .with_status(200)
+            .with_body(r#"{"value":[{"id":"/subscriptions/sub-1/resourceGroups/found-rg/providers/Microsoft.Storage/storageAccounts/demostore","name":"demostore"}]}"#)
+            .create_async()
+            .await;
+        let regenerate = server.mock("POST", "/subscriptions/sub-1/resourceGroups/found-rg/providers/Microsoft.Storage/storageAccounts/demostore/regenerateKey")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mut provider = AzureRemediator::new()
+            .with_base_url(server.url())
+            .with_service_principal(ServicePrincipal {
+                tenant_id: "tenant-1".to_string(),
+                client_id: "app-1".to_string(),
+                client_secret: "s3cret".to_string(),
+            })
+            .with_subscription("sub-1", None);
+        provider.operator_token = None;
+        let outcome = provider.remediate(&azure_match("azure_storage_key", &connection_string())).await?;
+        assert_eq!(outcome.action, "KEY_ROTATED");
+        assert!(outcome.message.contains("key1"));
+        token.assert_async().await;
+        regenerate.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_live_key_without_operator_credentials_aborts() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/").match_query(Matcher::Any).with_status(200).create_async().await;
+
+        let mut provider = AzureRemediator::new().with_base_url(server.url()).with_subscription("sub-1", None);
+        provider.operator_token = None;
+        provider.service_principal = None;
+        let outcome = provider.remediate(&azure_match("azure_storage_key", &connection_string())).await?;
+        assert_eq!(outcome.action, "ABORT_REMEDIATION");
+        assert!(!outcome.successful && outcome.confidence_boost);
+        assert!(outcome.message.contains(TENANT_ID_ENV));
+        Ok(())
+    }
+}
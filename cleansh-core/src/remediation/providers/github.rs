@@ -1,17 +1,125 @@
 // cleansh-core/src/remediation/providers/github.rs
+//! Remediation provider for leaked GitHub tokens.
+//!
+//! OAuth and user-to-server tokens (`gho_`, `ghu_`) are revoked through the OAuth
+//! app endpoint `DELETE /applications/{client_id}/token`, authenticated as the
+//! operator's OAuth app. Personal access tokens (`ghp_`, `github_pat_`) and any
+//! other token go to the credential revocation API, `POST /credentials/revoke`.
+//! Either way revocation needs operator credentials (`GITHUB_REMEDIATION_CLIENT_ID`
+//! and `GITHUB_REMEDIATION_CLIENT_SECRET`); without them the provider only
+//! reports. Success is reported only once the token no longer authenticates.
+
 use async_trait::async_trait;
-use crate::remediation::{Remediator, RemediationOutcome, ConfidenceLevel};
+use crate::remediation::{Remediator, RemediationOutcome, RemediatorConfig, ConfidenceLevel};
 use crate::redaction_match::RedactionMatch;
-use anyhow::{Result};
-use reqwest::Client;
+use anyhow::{Context, Result};
+use reqwest::{Client, StatusCode};
+
+/// Default GitHub API origin. Overridable through `RemediatorConfig::base_url` for tests.
+pub const GITHUB_API_BASE: &str = "https://api.github.com";
+
+/// Environment variables holding the operator OAuth app's client ID and secret.
+pub const REMEDIATION_CLIENT_ID_ENV: &str = "GITHUB_REMEDIATION_CLIENT_ID";
+pub const REMEDIATION_CLIENT_SECRET_ENV: &str = "GITHUB_REMEDIATION_CLIENT_SECRET";
+
+/// Credential revocation endpoint for personal access tokens.
+const CREDENTIAL_REVOKE_PATH: &str = "/credentials/revoke";
+
+const USER_AGENT: &str = "CleanSH-Proactive-Engine";
+
+/// Outcome of probing a token against `GET /user`.
+enum TokenStatus {
+    Live,
+    Revoked,
+    Unknown(StatusCode),
+}
 
 pub struct GitHubRemediator {
     client: Client,
+    base_url: String,
+    config: RemediatorConfig,
+}
+
+impl Default for GitHubRemediator {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl GitHubRemediator {
+    /// Creates a provider against the public GitHub API, reading the operator
+    /// credentials from `GITHUB_REMEDIATION_CLIENT_ID`/`_SECRET`.
     pub fn new() -> Self {
-        Self { client: Client::new() }
+        Self::with_config(RemediatorConfig {
+            credentials: RemediatorConfig::credentials_from_env(REMEDIATION_CLIENT_ID_ENV, REMEDIATION_CLIENT_SECRET_ENV),
+            ..Default::default()
+        })
+    }
+
+    /// Creates a provider from explicit settings; nothing is read from the environment.
+    pub fn with_config(config: RemediatorConfig) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: config.base_url.clone().unwrap_or_else(|| GITHUB_API_BASE.to_string()),
+            config,
+        }
+    }
+
+    /// Whether `secret` is an OAuth app or user-to-server token rather than a PAT.
+    fn is_oauth_token(secret: &str) -> bool {
+        secret.starts_with("gho_") || secret.starts_with("ghu_")
+    }
+
+    fn token_kind(secret: &str) -> &'static str {
+        if Self::is_oauth_token(secret) { "OAuth token" } else { "personal access token" }
+    }
+
+    async fn probe(&self, secret: &str) -> Result<TokenStatus> {
+        // Zero-privilege call: any valid token may read its own user.
+        let resp = self.client
+            .get(format!("{}/user", self.base_url))
+            .bearer_auth(secret)
+            .header("User-Agent", USER_AGENT)
+            .send()
+            .await
+            .context("GitHub user lookup failed")?;
+
+        Ok(match resp.status() {
+            s if s.is_success() => TokenStatus::Live,
+            StatusCode::UNAUTHORIZED => TokenStatus::Revoked,
+            s => TokenStatus::Unknown(s),
+        })
+    }
+
+    /// Sends the revocation request, returning the status GitHub answered with.
+    async fn revoke(&self, secret: &str, client_id: &str, client_secret: &str) -> Result<StatusCode> {
+        let request = if Self::is_oauth_token(secret) {
+            self.client
+                .delete(format!("{}/applications/{}/token", self.base_url, client_id))
+                .basic_auth(client_id, Some(client_secret))
+                .json(&serde_json::json!({ "access_token": secret }))
+        } else {
+            self.client
+                .post(format!("{}{}", self.base_url, CREDENTIAL_REVOKE_PATH))
+                .json(&serde_json::json!({ "credentials": [secret] }))
+        };
+        let resp = request
+            .header("User-Agent", USER_AGENT)
+            .header("Accept", "application/vnd.github+json")
+            .send()
+            .await
+            .context("GitHub revocation request failed")?;
+        Ok(resp.status())
+    }
+
+    fn outcome(&self, action: &str, successful: bool, message: String, confidence_boost: bool) -> RemediationOutcome {
+        RemediationOutcome {
+            provider: self.name().to_string(),
+            action: action.to_string(),
+            successful,
+            message,
+            confidence_boost,
+        }
     }
 }
 
@@ -29,41 +137,191 @@ impl Remediator for GitHubRemediator {
     }
 
     async fn verify_live_status(&self, secret: &str) -> Result<bool> {
-        // Perform a Zero-Privilege verification call
-        let resp = self.client
-            .get("https://api.github.com/user")
-            .bearer_auth(secret)
-            .header("User-Agent", "CleanSH-Proactive-Engine")
-            .send()
-            .await?;
-
-        Ok(resp.status().is_success())
+        Ok(matches!(self.probe(secret).await?, TokenStatus::Live))
     }
 
     async fn remediate(&self, redaction: &RedactionMatch) -> Result<RemediationOutcome> {
-        // Step 1: Prove it's real
-        if !self.verify_live_status(&redaction.original_string).await? {
-            return Ok(RemediationOutcome {
-                provider: self.name().to_string(),
-                action: "ABORT_REMEDIATION".to_string(),
-                successful: false,
-                message: "Secret verification failed: token is inactive or invalid.".to_string(),
-                confidence_boost: false,
-            });
+        let secret = redaction.original_string.as_str();
+        let kind = Self::token_kind(secret);
+
+        // Step 1: Prove it's real. A token GitHub already rejects needs no action.
+        match self.probe(secret).await? {
+            TokenStatus::Live => {}
+            TokenStatus::Revoked => {
+                return Ok(self.outcome(
+                    "ALREADY_DEAD",
+                    true,
+                    format!("GitHub {} is already revoked or expired; no action needed.", kind),
+                    false,
+                ));
+            }
+            TokenStatus::Unknown(status) => {
+                return Ok(self.outcome(
+                    "ABORT_REMEDIATION",
+                    false,
+                    format!("Could not verify GitHub {}: user lookup returned {}.", kind, status),
+                    false,
+                ));
+            }
         }
 
-        // Step 2: Neutralize
-        // Note: Actual revocation usually requires an OAuth App Admin token 
-        // or a specific Scoped Admin PAT configured in CleanSH.
-        log::info!("Verified live GitHub PAT. Proceeding with revocation...");
-        
-        // Final Action (Conceptual GitHub API call)
-        Ok(RemediationOutcome {
-            provider: self.name().to_string(),
-            action: "REVOKED".to_string(),
-            successful: true,
-            message: "Live GitHub PAT detected and neutralized.".to_string(),
-            confidence_boost: true,
-        })
+        // Step 2: Neutralize, if the operator allows it.
+        let credentials = match (&self.config.credentials, self.config.report_only) {
+            (_, true) => {
+                return Ok(self.outcome(
+                    "REPORT_ONLY",
+                    false,
+                    format!("Live GitHub {} detected; not revoked because the provider is report-only.", kind),
+                    true,
+                ));
+            }
+            (None, false) => {
+                return Ok(self.outcome(
+                    "REPORT_ONLY",
+                    false,
+                    format!(
+                        "Live GitHub {} detected; not revoked because {} and {} are not set.",
+                        kind, REMEDIATION_CLIENT_ID_ENV, REMEDIATION_CLIENT_SECRET_ENV
+                    ),
+                    true,
+                ));
+            }
+            (Some(credentials), false) => credentials,
+        };
+
+        log::info!("Verified live GitHub {}. Revoking...", kind);
+        let status = self.revoke(secret, &credentials.client_id, &credentials.client_secret).await?;
+        if !status.is_success() {
+            return Ok(self.outcome(
+                "REVOKE_FAILED",
+                false,
+                format!("GitHub rejected the revocation request for a {}: {}.", kind, status),
+                true,
+            ));
+        }
+
+        // Step 3: Only a token that no longer authenticates counts as revoked.
+        if self.verify_live_status(secret).await? {
+            return Ok(self.outcome(
+                "REVOKE_UNCONFIRMED",
+                false,
+                format!("GitHub accepted the revocation of a {}, but the token still authenticates.", kind),
+                true,
+            ));
+        }
+        Ok(self.outcome("REVOKED", true, format!("Live GitHub {} detected and revoked.", kind), true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    fn github_match(secret: &str) -> RedactionMatch {
+        RedactionMatch {
+            rule_name: "github_pat".to_string(),
+            original_string: secret.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn provider(server: &mockito::Server) -> GitHubRemediator {
+        GitHubRemediator::with_config(
+            RemediatorConfig::default().with_base_url(server.url()).with_credentials("Iv1.operator", "operator-secret"),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_live_pat_is_revoked_and_confirmed_dead() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        // Live on the first lookup, rejected once revoked.
+        let live = server.mock("GET", "/user")
+            .match_header("authorization", "Bearer ghp_leaked")
+            .with_status(200)
+            .with_body(r#"{"login":"octocat"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let revoke = server.mock("POST", CREDENTIAL_REVOKE_PATH)
+            .match_body(Matcher::Json(serde_json::json!({ "credentials": ["ghp_leaked"] })))
+            .with_status(202)
+            .create_async()
+            .await;
+
+        let dead = server.mock("GET", "/user").with_status(401).create_async().await;
+
+        let outcome = provider(&server).remediate(&github_match("ghp_leaked")).await?;
+        assert_eq!(outcome.action, "REVOKED");
+        assert!(outcome.successful && outcome.confidence_boost);
+        live.assert_async().await;
+        revoke.assert_async().await;
+        dead.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_oauth_token_is_revoked_through_the_app_endpoint() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/user").with_status(200).expect(1).create_async().await;
+        let revoke = server.mock("DELETE", "/applications/Iv1.operator/token")
+            .match_header("authorization", Matcher::Regex("^Basic ".to_string()))
+            .match_body(Matcher::Json(serde_json::json!({ "access_token": "gho_leaked" })))
+            .with_status(204)
+            .create_async()
+            .await;
+        server.mock("GET", "/user").with_status(401).create_async().await;
+
+        let outcome = provider(&server).remediate(&github_match("gho_leaked")).await?;
+        assert_eq!(outcome.action, "REVOKED");
+        assert!(outcome.message.contains("OAuth token"));
+        revoke.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_already_revoked_token_is_not_revoked_again() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/user").with_status(401).create_async().await;
+        let revoke = server.mock("POST", CREDENTIAL_REVOKE_PATH).expect(0).create_async().await;
+
+        let outcome = provider(&server).remediate(&github_match("ghp_revoked")).await?;
+        assert_eq!(outcome.action, "ALREADY_DEAD");
+        assert!(outcome.successful && !outcome.confidence_boost);
+        revoke.assert_async().await;
+        Ok(())
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_missing_operator_credentials_downgrade_to_report_only() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/user").with_status(200).create_async().await;
+        let revoke = server.mock("POST", CREDENTIAL_REVOKE_PATH).expect(0).create_async().await;
+
+        let provider = GitHubRemediator::with_config(RemediatorConfig::default().with_base_url(server.url()));
+        let outcome = provider.remediate(&github_match("ghp_leaked")).await?;
+        assert_eq!(outcome.action, "REPORT_ONLY");
+        assert!(!outcome.successful && outcome.confidence_boost);
+        assert!(outcome.message.contains(REMEDIATION_CLIENT_ID_ENV), "{}", outcome.message);
+
+        // Report-only wins even with credentials.
+        let provider = GitHubRemediator::with_config(
+            RemediatorConfig::default().with_base_url(server.url()).with_credentials("id", "secret").with_report_only(true),
+        );
+        assert_eq!(provider.remediate(&github_match("ghp_leaked")).await?.action, "REPORT_ONLY");
+        revoke.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_token_that_still_authenticates_is_not_reported_revoked() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/user").with_status(200).expect(2).create_async().await;
+        server.mock("POST", CREDENTIAL_REVOKE_PATH).with_status(202).create_async().await;
+
+        let outcome = provider(&server).remediate(&github_match("ghp_stubborn")).await?;
+        assert_eq!(outcome.action, "REVOKE_UNCONFIRMED");
+        assert!(!outcome.successful);
+        Ok(())
+    }
+}
@@ -1 +1,305 @@
-pub mod github;
\ No newline at end of file
+// cleansh-core/src/remediation/providers/mod.rs
+//! The remediation providers, and the registry that builds the enabled ones from
+//! a [`ProvidersConfig`].
+//!
+//! Each provider reads its operator credentials from its own environment
+//! variables (see the provider modules); the config only says which providers run
+//! and how.
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::redaction_match::RedactionMatch;
+use crate::remediation::{ConfidenceLevel, RemediationOutcome, Remediator, RemediatorConfig};
+
+pub mod azure;
+pub mod gcp;
+pub mod github;
+pub mod stripe;
+
+/// Names `build_providers` accepts, in the order providers are tried.
+pub const PROVIDER_NAMES: [&str; 4] = ["azure", "gcp", "github", "stripe"];
+
+/// Comma-separated provider names enabled with default settings, on top of the config.
+pub const PROVIDERS_ENV: &str = "CLEANSH_REMEDIATION_PROVIDERS";
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// How one provider runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProviderSettings {
+    /// Set to `false` to keep a configured provider from running.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Confidence a match must reach before the provider acts without approval,
+    /// instead of the provider's own threshold.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<ConfidenceLevel>,
+    /// Verify and report live secrets, but never remediate them.
+    pub report_only: bool,
+    /// API origin to call instead of the provider's public one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// Actions per governor window, instead of the orchestrator-wide limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_actions: Option<usize>,
+}
+
+impl Default for ProviderSettings {
+    fn default() -> Self {
+        Self { enabled: true, threshold: None, report_only: false, base_url: None, max_actions: None }
+    }
+}
+
+impl ProviderSettings {
+    pub fn with_threshold(mut self, threshold: ConfidenceLevel) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    pub fn with_report_only(mut self, report_only: bool) -> Self {
+        self.report_only = report_only;
+        self
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+}
+
+/// The `remediation.providers` section: settings by provider name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ProvidersConfig {
+    pub providers: BTreeMap<String, ProviderSettings>,
+}
+
+impl ProvidersConfig {
+    pub fn with_provider(mut self, name: impl Into<String>, settings: ProviderSettings) -> Self {
+        self.providers.insert(name.into(), settings);
+        self
+    }
+
+    /// Adds the providers named in `CLEANSH_REMEDIATION_PROVIDERS` that the config
+    /// does not already set, with default settings.
+    pub fn with_env(mut self) -> Self {
+        if let Ok(names) = std::env::var(PROVIDERS_ENV) {
+            for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                self.providers.entry(name.to_ascii_lowercase()).or_default();
+            }
+        }
+        self
+    }
+
+    /// Whether any provider is enabled.
+    pub fn any_enabled(&self) -> bool {
+        self.providers.values().any(|settings| settings.enabled)
+    }
+}
+
+/// A provider with the threshold and report-only setting its config gave it.
+struct ConfiguredProvider {
+    inner: Arc<dyn Remediator>,
+    threshold: Option<ConfidenceLevel>,
+    report_only: bool,
+}
+
+#[async_trait]
+impl Remediator for ConfiguredProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn can_handle(&self, redaction: &RedactionMatch) -> bool {
+        self.inner.can_handle(redaction)
+    }
+
+    async fn verify_live_status(&self, secret: &str) -> Result<bool> {
+        self.inner.verify_live_status(secret).await
+    }
+
+    async fn remediate(&self, redaction: &RedactionMatch) -> Result<RemediationOutcome> {
+        if self.report_only {
+            return Ok(RemediationOutcome {
+                provider: self.name().to_string(),
+                action: "REPORT_ONLY".to_string(),
+                successful: false,
+                message: format!("Not remediated: the {} provider is configured report-only.", self.name()),
+                confidence_boost: true,
+            });
+        }
+        self.inner.remediate(redaction).await
+    }
+
+    fn auto_remediation_threshold(&self) -> ConfidenceLevel {
+        self.threshold.unwrap_or_else(|| self.inner.auto_remediation_threshold())
+    }
+}
+
+/// Instantiates the provider called `name`, or fails naming the known ones.
+fn instantiate(name: &str, settings: &ProviderSettings) -> Result<Arc<dyn Remediator>> {
+    let base_url = settings.base_url.as_deref();
+    Ok(match name {
+        "azure" => {
+            let provider = azure::AzureRemediator::new();
+            Arc::new(match base_url {
+                Some(url) => provider.with_base_url(url),
+                None => provider,
+            })
+        }
+        "gcp" => {
+            let provider = gcp::GcpRemediator::new();
+            Arc::new(match base_url {
+                Some(url) => provider.with_base_url(url),
+                None => provider,
+            })
+        }
+        "github" => {
+            let mut config = RemediatorConfig {
+                credentials: RemediatorConfig::credentials_from_env(
+                    github::REMEDIATION_CLIENT_ID_ENV,
+                    github::REMEDIATION_CLIENT_SECRET_ENV,
+                ),
+                ..Default::default()
+            };
+            if let Some(url) = base_url {
+                config = config.with_base_url(url);
+            }
+            Arc::new(github::GitHubRemediator::with_config(config))
+        }
+        "stripe" => {
+            let provider = stripe::StripeRemediator::new();
+            Arc::new(match base_url {
+                Some(url) => provider.with_base_url(url),
+                None => provider,
+            })
+        }
+        _ => bail!(
+            "unknown remediation provider '{}'. Expected one of: {}",
+            name,
+            PROVIDER_NAMES.join(", ")
+        ),
+    })
+}
+
+/// The enabled providers of `config`, in name order, each wrapped so its
+/// configured threshold and report-only setting apply. Fails on an unknown
+/// provider name, enabled or not.
+pub fn build_providers(config: &ProvidersConfig) -> Result<Vec<Arc<dyn Remediator>>> {
+    let mut providers = Vec::new();
+    for (name, settings) in &config.providers {
+        let name = name.to_ascii_lowercase();
+        let inner = instantiate(&name, settings)?;
+        if !settings.enabled {
+            continue;
+        }
+        providers.push(Arc::new(ConfiguredProvider {
+            inner,
+            threshold: settings.threshold,
+            report_only: settings.report_only,
+        }) as Arc<dyn Remediator>);
+    }
+    Ok(providers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::remediation::RemediationSettings;
+
+    fn names(providers: &[Arc<dyn Remediator>]) -> Vec<&str> {
+        providers.iter().map(|p| p.name()).collect()
+    }
+
+    #[test]
+    fn test_two_providers_yield_two_remediators() -> Result<()> {
+        let config = ProvidersConfig::default()
+            .with_provider("stripe", ProviderSettings::default())
+            .with_provider("github", ProviderSettings::default());
+        let providers = build_providers(&config)?;
+        assert_eq!(names(&providers), ["github", "stripe"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_provider_is_a_descriptive_error() {
+        let config = ProvidersConfig::default()
+            .with_provider("github", ProviderSettings::default())
+            .with_provider("slack", ProviderSettings { enabled: false, ..Default::default() });
+        let err = build_providers(&config).err().expect("unknown provider accepted").to_string();
+        assert!(err.contains("'slack'"), "{}", err);
+        assert!(err.contains("azure, gcp, github, stripe"), "{}", err);
+    }
+
+    #[test]
+    fn test_disabled_provider_is_skipped_and_threshold_overrides() -> Result<()> {
+        let config = ProvidersConfig::default()
+            .with_provider("azure", ProviderSettings { enabled: false, ..Default::default() })
+            .with_provider("gcp", ProviderSettings::default().with_threshold(ConfidenceLevel::Medium))
+            .with_provider("stripe", ProviderSettings::default());
+        let providers = build_providers(&config)?;
+        assert_eq!(names(&providers), ["gcp", "stripe"]);
+        assert_eq!(providers[0].auto_remediation_threshold(), ConfidenceLevel::Medium);
+        assert_eq!(
+            providers[1].auto_remediation_threshold(),
+            stripe::StripeRemediator::new().auto_remediation_threshold()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_report_only_never_calls_the_provider() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        let any_call = server.mock("POST", mockito::Matcher::Any).expect(0).create_async().await;
+
+        let config = ProvidersConfig::default()
+            .with_provider("stripe", ProviderSettings::default().with_report_only(true).with_base_url(server.url()));
+        let providers = build_providers(&config)?;
+        let redaction = RedactionMatch {
+            rule_name: "stripe_secret".to_string(),
+            original_string: "sk_live_leaked".to_string(),
+            ..Default::default()
+        };
+        let outcome = providers[0].remediate(&redaction).await?;
+        assert_eq!(outcome.action, "REPORT_ONLY");
+        assert!(!outcome.successful);
+        any_call.assert_async().await;
+        Ok(())
+    }
+
+    #[test]
+    fn test_settings_load_the_toml_providers_section() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("remediation.toml");
+        std::fs::write(&path, r#"
+[remediation]
+max_actions = 3
+
+[remediation.providers.github]
+threshold = "critical"
+max_actions = 1
+
+[remediation.providers.stripe]
+report_only = true
+"#)?;
+        let settings = RemediationSettings::load_from_file(&path)?;
+        assert_eq!(settings.max_actions, 3);
+        assert!(!settings.dry_run);
+        assert_eq!(settings.providers.providers["github"].threshold, Some(ConfidenceLevel::Critical));
+        assert!(settings.providers.providers["github"].enabled);
+        assert!(settings.providers.providers["stripe"].report_only);
+
+        let governor = settings.governor();
+        assert_eq!(governor.max_actions_for("github"), 1);
+        assert_eq!(governor.max_actions_for("stripe"), 3);
+        assert_eq!(build_providers(&settings.providers)?.len(), 2);
+        Ok(())
+    }
+}
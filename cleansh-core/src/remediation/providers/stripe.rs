@@ -0,0 +1,217 @@
+// cleansh-core/src/remediation/providers/stripe.rs
+//! Remediation provider for live Stripe secret (`sk_live_`) and restricted (`rk_live_`) keys.
+//!
+//! Stripe's API has no endpoint for rolling or deleting API keys; that is only done
+//! from the Dashboard. The provider verifies a leaked key and reports it, so a live
+//! key reaches an operator without anything being changed.
+
+use async_trait::async_trait;
+use crate::remediation::{Remediator, RemediationOutcome, ConfidenceLevel};
+use crate::redaction_match::RedactionMatch;
+use anyhow::{Context, Result};
+use reqwest::{Client, StatusCode};
+
+/// Default Stripe API origin. Overridable via `with_base_url` for tests.
+pub const STRIPE_API_BASE: &str = "https://api.stripe.com";
+
+/// Where a leaked key is rolled by hand.
+const DASHBOARD_API_KEYS_URL: &str = "https://dashboard.stripe.com/apikeys";
+
+/// Outcome of probing a key against `GET /v1/account`.
+enum KeyStatus {
+    Live,
+    Revoked,
+    Unknown(StatusCode),
+}
+
+pub struct StripeRemediator {
+    client: Client,
+    base_url: String,
+}
+
+impl Default for StripeRemediator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StripeRemediator {
+    /// Creates a provider against the public Stripe API.
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+            base_url: STRIPE_API_BASE.to_string(),
+        }
+    }
+
+    /// Points the provider at a different API origin (e.g. a local mock server).
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    fn key_kind(secret: &str) -> &'static str {
+        if secret.starts_with("rk_") { "restricted key" } else { "full secret key" }
+    }
+
+    async fn probe(&self, secret: &str) -> Result<KeyStatus> {
+        let resp = self.client
+            .get(format!("{}/v1/account", self.base_url))
+            .bearer_auth(secret)
+            .header("User-Agent", "CleanSH-Proactive-Engine")
+            .send()
+            .await
+            .context("Stripe account lookup failed")?;
+
+        Ok(match resp.status() {
+            s if s.is_success() => KeyStatus::Live,
+            StatusCode::UNAUTHORIZED => KeyStatus::Revoked,
+            s => KeyStatus::Unknown(s),
+        })
+    }
+
+    fn outcome(&self, action: &str, successful: bool, message: String, confidence_boost: bool) -> RemediationOutcome {
+        RemediationOutcome {
+            provider: self.name().to_string(),
+            action: action.to_string(),
+            successful,
+            message,
+            confidence_boost,
+        }
+    }
+}
+
+#[async_trait]
+impl Remediator for StripeRemediator {
+    fn name(&self) -> &str { "stripe" }
+
+    fn can_handle(&self, redaction: &RedactionMatch) -> bool {
+        redaction.rule_name.starts_with("stripe_secret")
+            || redaction.original_string.starts_with("sk_live_")
+            || redaction.original_string.starts_with("rk_live_")
+    }
+
+    fn auto_remediation_threshold(&self) -> ConfidenceLevel {
+        // Only report keys Stripe has confirmed are live.
+        ConfidenceLevel::Critical
+    }
+
+    async fn verify_live_status(&self, secret: &str) -> Result<bool> {
+        Ok(matches!(self.probe(secret).await?, KeyStatus::Live))
+    }
+
+    async fn remediate(&self, redaction: &RedactionMatch) -> Result<RemediationOutcome> {
+        let secret = redaction.original_string.as_str();
+        let kind = Self::key_kind(secret);
+
+        // Step 1: Prove it's real. A key Stripe already rejects needs no action.
+        match self.probe(secret).await? {
+            KeyStatus::Live => {}
+            KeyStatus::Revoked => {
+                return Ok(self.outcome(
+                    "ALREADY_DEAD",
+                    true,
+                    format!("Stripe {} is already revoked; no action needed.", kind),
+                    false,
+                ));
+            }
+            KeyStatus::Unknown(status) => {
+                return Ok(self.outcome(
+                    "ABORT_REMEDIATION",
+                    false,
+                    format!("Could not verify Stripe {}: account lookup returned {}.", kind, status),
+                    false,
+                ));
+            }
+        }
+
+        // Step 2: Report it. Stripe only rolls keys from the Dashboard.
+        log::info!("Verified live Stripe {}. Reporting for a manual roll.", kind);
+        Ok(self.outcome(
+            "REPORT_ONLY",
+            false,
+            format!(
+                "Live Stripe {} detected; Stripe's API cannot roll keys, so roll it at {}.",
+                kind, DASHBOARD_API_KEYS_URL
+            ),
+            true,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    fn stripe_match(secret: &str) -> RedactionMatch {
+        RedactionMatch {
+            rule_name: "stripe_secret".to_string(),
+            original_string: secret.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_can_handle_rule_name_or_live_prefix() {
+        let provider = StripeRemediator::new();
+        assert!(provider.can_handle(&stripe_match("anything")));
+        assert!(provider.can_handle(&RedactionMatch {
+            rule_name: "stripe_secret_key".to_string(),
+            ..Default::default()
+        }));
+        assert!(provider.can_handle(&RedactionMatch {
+            rule_name: "high_entropy_secret".to_string(),
+            original_string: "sk_live_abc123".to_string(),
+            ..Default::default()
+        }));
+        assert!(!provider.can_handle(&RedactionMatch {
+            rule_name: "high_entropy_secret".to_string(),
+            original_string: "sk_test_abc123".to_string(),
+            ..Default::default()
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_revoked_key_is_already_dead() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        let account = server.mock("GET", "/v1/account")
+            .match_header("authorization", "Bearer sk_live_revoked")
+            .with_status(401)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let provider = StripeRemediator::new().with_base_url(server.url());
+        assert!(!provider.verify_live_status("sk_live_revoked").await?);
+
+        let outcome = provider.remediate(&stripe_match("sk_live_revoked")).await?;
+        assert_eq!(outcome.action, "ALREADY_DEAD");
+        assert!(outcome.successful);
+        account.assert_async().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_live_restricted_key_is_reported_for_a_manual_roll() -> Result<()> {
+        let mut server = mockito::Server::new_async().await;
+        let account = server.mock("GET", "/v1/account")
+            .match_header("authorization", "Bearer rk_live_leaked")
+            .with_status(200)
+            .with_body(r#"{"id":"acct_123"}"#)
+            .create_async()
+            .await;
+        // Nothing but the account lookup may be called.
+        let other = server.mock("POST", Matcher::Any).expect(0).create_async().await;
+
+        let provider = StripeRemediator::new().with_base_url(server.url());
+        let outcome = provider.remediate(&stripe_match("rk_live_leaked")).await?;
+        assert_eq!(outcome.action, "REPORT_ONLY");
+        assert!(!outcome.successful && outcome.confidence_boost);
+        assert!(outcome.message.contains("restricted key"));
+        assert!(outcome.message.contains(DASHBOARD_API_KEYS_URL));
+        account.assert_async().await;
+        other.assert_async().await;
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+// cleansh-core/src/remediation/state.rs
+//! Which secrets remediation has already neutralized, shared between the
+//! orchestrator and the engines.
+//!
+//! A [`RemediationStateMap`] is a shared handle like
+//! [`IgnoreStore`](crate::ignore_store::IgnoreStore): the orchestrator marks a
+//! secret once a provider revokes it, and engines holding a clone (through
+//! `EngineOptions::remediation_state`) redact later occurrences as
+//! [`REVOKED_PLACEHOLDER`], so a tail of the sanitized log shows the secret is dead.
+//! Only hashes of the secrets are kept.
+
+use std::collections::HashSet;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use crate::ignore_file::ignore_hash;
+
+/// What engines replace a secret with once it has been revoked.
+pub const REVOKED_PLACEHOLDER: &str = "[REVOKED_SECRET]";
+
+/// A shared set of revoked secrets, by value hash.
+#[derive(Debug, Clone, Default)]
+pub struct RemediationStateMap {
+    revoked: Arc<RwLock<HashSet<String>>>,
+}
+
+impl RemediationStateMap {
+    /// Records that `secret` was revoked. Returns whether it was new.
+    pub fn mark_revoked(&self, secret: &str) -> bool {
+        self.revoked.write().unwrap_or_else(PoisonError::into_inner).insert(ignore_hash(secret))
+    }
+
+    /// Whether `secret` was revoked.
+    pub fn is_revoked(&self, secret: &str) -> bool {
+        let revoked = self.revoked.read().unwrap_or_else(PoisonError::into_inner);
+        !revoked.is_empty() && revoked.contains(&ignore_hash(secret))
+    }
+
+    pub fn len(&self) -> usize {
+        self.revoked.read().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,187 @@
+// cleansh-core/src/remediation/sync.rs
+//! Where the orchestrator's sync loop gets vault fingerprints from.
+//!
+//! An [`IntervalSource`] polls the vault on a timer. A [`PushSource`] also listens
+//! to the vault's subscription, so a fingerprint published anywhere in the fleet
+//! reaches the engine immediately; its timer keeps running as a full resync that
+//! covers anything missed while the subscription was down.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Interval, MissedTickBehavior};
+
+use crate::engine_handle::EngineHandle;
+use crate::remediation::fingerprint::SecretFingerprint;
+use crate::remediation::fingerprint_cache::FingerprintCache;
+use crate::remediation::vault::{FingerprintStream, FingerprintVault};
+
+/// Default time between full vault snapshots.
+pub const DEFAULT_SYNC_INTERVAL: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone)]
+pub enum SyncUpdate {
+    /// Every fingerprint in the vault.
+    Snapshot(Vec<SecretFingerprint>),
+    /// Fingerprints published since the last update.
+    Added(Vec<SecretFingerprint>),
+}
+
+/// A source of vault updates for the sync loop.
+#[async_trait]
+pub trait SyncSource: Send {
+    /// Waits for the next update. `None` ends the sync loop.
+    async fn next_update(&mut self) -> Option<SyncUpdate>;
+}
+
+/// Fetches a full snapshot every `period`, starting immediately. Failed fetches
+/// are logged and retried on the next tick.
+pub struct IntervalSource {
+    vault: Arc<dyn FingerprintVault>,
+    ticks: Interval,
+}
+
+impl IntervalSource {
+    pub fn new(vault: Arc<dyn FingerprintVault>, period: Duration) -> Self {
+        let mut ticks = interval(period);
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self { vault, ticks }
+    }
+}
+
+#[async_trait]
+impl SyncSource for IntervalSource {
+    async fn next_update(&mut self) -> Option<SyncUpdate> {
+        loop {
+            self.ticks.tick().await;
+            match self.vault.fetch_all().await {
+                Ok(fingerprints) => return Some(SyncUpdate::Snapshot(fingerprints)),
+                Err(e) => log::warn!("Failed to fetch fingerprints from the vault: {:#}", e),
+            }
+        }
+    }
+}
+
+/// Pushed fingerprints as they arrive, plus an [`IntervalSource`] snapshot every
+/// `period`. A subscription that ends is re-opened on the next snapshot.
+pub struct PushSource {
+    snapshots: IntervalSource,
+    vault: Arc<dyn FingerprintVault>,
+    pushed: Option<FingerprintStream>,
+}
+
+impl PushSource {
+    /// Subscribes to `vault`, failing if it cannot push or the subscription fails.
+    pub async fn subscribe(vault: Arc<dyn FingerprintVault>, period: Duration) -> anyhow::Result<Self> {
+        let pushed = match vault.subscribe().await? {
+            Some(stream) => stream,
+            None => anyhow::bail!("this vault cannot push fingerprints"),
+        };
+        Ok(Self { snapshots: IntervalSource::new(Arc::clone(&vault), period), vault, pushed: Some(pushed) })
+    }
+
+    async fn resubscribe(&mut self) {
+        match self.vault.subscribe().await {
+            Ok(stream) => self.pushed = stream,
+            Err(e) => log::warn!("Failed to resubscribe to the vault: {:#}", e),
+        }
+    }
+}
+
+#[async_trait]
+impl SyncSource for PushSource {
+    async fn next_update(&mut self) -> Option<SyncUpdate> {
+        loop {
+            let Self { snapshots, pushed, .. } = self;
+            let received = async {
+                match pushed {
+                    Some(stream) => stream.recv().await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                update = snapshots.next_update() => {
+                    if self.pushed.is_none() {
+                        self.resubscribe().await;
+                    }
+                    return update;
+                }
+                fingerprint = received => match fingerprint {
+                    Some(first) => {
+                        let mut added = vec![first];
+                        if let Some(stream) = &mut self.pushed {
+                            while let Ok(next) = stream.try_recv() {
+                                added.push(next);
+                            }
+                        }
+                        return Some(SyncUpdate::Added(added));
+                    }
+                    None => {
+                        log::warn!("Vault subscription ended; relying on periodic sync until it is re-opened");
+                        self.pushed = None;
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// A [`PushSource`] when `vault` can push fingerprints, otherwise an [`IntervalSource`].
+pub async fn source_for(vault: Arc<dyn FingerprintVault>, period: Duration) -> Box<dyn SyncSource> {
+    match PushSource::subscribe(Arc::clone(&vault), period).await {
+        Ok(source) => Box::new(source),
+        Err(e) => {
+            log::debug!("Polling the vault every {:?}: {:#}", period, e);
+            Box::new(IntervalSource::new(vault, period))
+        }
+    }
+}
+
+/// Keeps whichever engine `engine` holds up to date with the updates from
+/// `source`, until it runs dry. An engine swapped in meanwhile picks up the next
+/// update.
+///
+/// With a `cache_path`, each snapshot is written atomically to the cache file and
+/// the new mapping is swapped into the engine; if that fails the fingerprints are
+/// added in memory instead. Pushed fingerprints are added in memory until the
+/// next snapshot.
+pub fn spawn_sync_loop(engine: EngineHandle, mut source: Box<dyn SyncSource>, cache_path: Option<PathBuf>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        while let Some(update) = source.next_update().await {
+            let fingerprints = match update {
+                SyncUpdate::Added(fingerprints) => {
+                    engine.with(|e| e.update_fingerprints(fingerprints));
+                    continue;
+                }
+                SyncUpdate::Snapshot(fingerprints) => fingerprints,
+            };
+            match &cache_path {
+                Some(path) => match refresh_cache(path.clone(), fingerprints).await {
+                    Ok(cache) => engine.with(|e| e.set_fingerprint_cache(cache)),
+                    Err((e, fingerprints)) => {
+                        log::error!("Failed to refresh fingerprint cache: {:#}", e);
+                        engine.with(|e| e.update_fingerprints(fingerprints));
+                    }
+                },
+                None => engine.with(|e| e.update_fingerprints(fingerprints)),
+            }
+        }
+    })
+}
+
+/// Rebuilds the cache file off the async runtime. On failure the snapshot is
+/// handed back so it can still be applied in memory.
+async fn refresh_cache(
+    path: PathBuf,
+    fingerprints: Vec<SecretFingerprint>,
+) -> Result<Arc<FingerprintCache>, (anyhow::Error, Vec<SecretFingerprint>)> {
+    tokio::task::spawn_blocking(move || match FingerprintCache::rebuild(&path, &fingerprints) {
+        Ok(cache) => Ok(Arc::new(cache)),
+        Err(e) => Err((e, fingerprints)),
+    })
+    .await
+    .unwrap_or_else(|e| Err((anyhow::anyhow!("cache refresh task failed: {}", e), Vec::new())))
+}
@@ -1,13 +1,120 @@
 // cleansh-core/src/remediation/vault.rs
-use anyhow::Result;
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
 use async_trait::async_trait;
+use tokio::sync::mpsc;
 use crate::remediation::fingerprint::SecretFingerprint;
 
+pub mod file;
+pub mod object;
+#[cfg(feature = "redis")]
+pub mod redis;
+#[cfg(feature = "s3")]
+pub mod s3;
+
+/// Names the vault agents share, as a URL (see [`from_url`]).
+pub const VAULT_URL_ENV: &str = "CLEANSH_VAULT_URL";
+
+/// Environment variable holding the Redis URL, e.g. `rediss://:password@redis.internal:6380/0`,
+/// read when [`VAULT_URL_ENV`] is unset.
+pub const REDIS_URL_ENV: &str = "CLEANSH_REDIS_URL";
+
+/// Fingerprints a vault pushes as they are published. The sender is dropped when
+/// the subscription ends.
+pub type FingerprintStream = mpsc::Receiver<SecretFingerprint>;
+
 #[async_trait]
 pub trait FingerprintVault: Send + Sync {
+    /// What the dashboard calls the vault, e.g. `Redis`.
+    fn name(&self) -> &str {
+        "Vault"
+    }
+
     /// Pushes a new fingerprint to the organization-wide store.
     async fn publish(&self, fingerprint: SecretFingerprint) -> Result<()>;
 
+    /// Pushes several fingerprints. Vaults that can store them in one round trip
+    /// override this; the default publishes them one at a time.
+    async fn publish_batch(&self, fingerprints: Vec<SecretFingerprint>) -> Result<()> {
+        for fingerprint in fingerprints {
+            self.publish(fingerprint).await?;
+        }
+        Ok(())
+    }
+
     /// Fetches all active fingerprints for the local instance to use.
     async fn fetch_all(&self) -> Result<Vec<SecretFingerprint>>;
-}
\ No newline at end of file
+
+    /// Streams fingerprints published from now on, if the vault can push them.
+    /// Vaults that can only be polled return `None`.
+    async fn subscribe(&self) -> Result<Option<FingerprintStream>> {
+        Ok(None)
+    }
+}
+
+/// The vault `url` names, by its scheme:
+///
+/// * `redis://` or `rediss://`: a `redis::RedisVault` holding `org`'s fingerprints,
+///   when built with the `redis` feature;
+/// * `s3://<bucket>/<key>`: the object `key` in `bucket`, when built with the `s3` feature;
+/// * `file://<path>`: a [`file::FileVault`].
+pub async fn from_url(url: &str, org: &str) -> Result<Arc<dyn FingerprintVault>> {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        bail!("Vault URL '{}' has no scheme (expected redis://, rediss://, s3:// or file://)", url);
+    };
+    match scheme.to_ascii_lowercase().as_str() {
+        "redis" | "rediss" => redis_vault(url, org),
+        "s3" => s3_vault(rest).await,
+        "file" if !rest.is_empty() => Ok(Arc::new(file::FileVault::new(rest))),
+        "file" => bail!("Vault URL '{}' names no file", url),
+        other => bail!("Unsupported vault scheme '{}' (expected redis://, rediss://, s3:// or file://)", other),
+    }
+}
+
+#[cfg(feature = "redis")]
+fn redis_vault(url: &str, org: &str) -> Result<Arc<dyn FingerprintVault>> {
+    Ok(Arc::new(redis::RedisVault::new(url, org)?))
+}
+
+#[cfg(not(feature = "redis"))]
+fn redis_vault(_url: &str, _org: &str) -> Result<Arc<dyn FingerprintVault>> {
+    bail!("Redis vaults need cleansh built with the `redis` feature")
+}
+
+#[cfg(feature = "s3")]
+async fn s3_vault(location: &str) -> Result<Arc<dyn FingerprintVault>> {
+    match location.split_once('/') {
+        Some((bucket, key)) if !bucket.is_empty() && !key.is_empty() => Ok(Arc::new(s3::S3Vault::from_env(bucket, key).await)),
+        _ => bail!("S3 vault URL must be s3://<bucket>/<key>, got 's3://{}'", location),
+    }
+}
+
+#[cfg(not(feature = "s3"))]
+async fn s3_vault(_location: &str) -> Result<Arc<dyn FingerprintVault>> {
+    bail!("S3 vaults need cleansh built with the `s3` feature")
+}
+
+/// Reads a stored fingerprint list: JSON Lines, one fingerprint per line, or the
+/// single JSON array that vaults wrote before.
+pub fn decode_fingerprints(bytes: &[u8]) -> Result<Vec<SecretFingerprint>> {
+    if bytes.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'[') {
+        return serde_json::from_slice(bytes).context("Invalid fingerprint array");
+    }
+    bytes.split(|&b| b == b'\n')
+        .enumerate()
+        .filter(|(_, line)| !line.trim_ascii().is_empty())
+        .map(|(i, line)| serde_json::from_slice(line).with_context(|| format!("Invalid fingerprint on line {}", i + 1)))
+        .collect()
+}
+
+/// Writes `fingerprints` as JSON Lines, so another writer can append to the list
+/// without rewriting it.
+pub fn encode_fingerprints(fingerprints: &[SecretFingerprint]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    for fingerprint in fingerprints {
+        serde_json::to_writer(&mut out, fingerprint)?;
+        out.push(b'\n');
+    }
+    Ok(out)
+}
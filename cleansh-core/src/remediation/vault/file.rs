@@ -0,0 +1,50 @@
+// cleansh-core/src/remediation/vault/file.rs
+//! Local-file implementation of the FingerprintVault.
+//! Stores the same JSON Lines as the S3 vault, for single-host setups, shared
+//! mounts, and exports pulled from the central store. Files holding the JSON
+//! array older versions wrote are still read, and rewritten as JSON Lines on the
+//! next publish.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use crate::remediation::fingerprint::SecretFingerprint;
+use crate::remediation::vault::{decode_fingerprints, encode_fingerprints, FingerprintVault};
+use std::path::PathBuf;
+
+pub struct FileVault {
+    path: PathBuf,
+}
+
+impl FileVault {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl FingerprintVault for FileVault {
+    fn name(&self) -> &str {
+        "File"
+    }
+
+    /// Adds the fingerprint unless its hash is already stored.
+    async fn publish(&self, fingerprint: SecretFingerprint) -> Result<()> {
+        let mut all = self.fetch_all().await?;
+        if !all.iter().any(|f| f.hash == fingerprint.hash) {
+            all.push(fingerprint);
+            tokio::fs::write(&self.path, encode_fingerprints(&all)?).await
+                .with_context(|| format!("Failed to write fingerprint vault {}", self.path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Reads every stored fingerprint; a missing file is an empty vault.
+    async fn fetch_all(&self) -> Result<Vec<SecretFingerprint>> {
+        match tokio::fs::read(&self.path).await {
+            Ok(bytes) => decode_fingerprints(&bytes)
+                .with_context(|| format!("Invalid fingerprint vault {}", self.path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read fingerprint vault {}", self.path.display())),
+        }
+    }
+}
@@ -0,0 +1,270 @@
+// cleansh-core/src/remediation/vault/object.rs
+//! Fingerprint vaults kept as a single object in a store with conditional writes,
+//! such as the S3 vault.
+//!
+//! The fingerprints are stored as JSON Lines. Publishing is optimistic: the object
+//! is read with its ETag, the new fingerprints are merged in, and the result is
+//! written back only if the ETag still matches (`If-Match`), or, when there was no
+//! object, only if there still is none (`If-None-Match: *`). If another instance
+//! wrote in between, the write fails its precondition and the publish re-reads,
+//! re-merges, and tries again after a jittered backoff, so neither instance's
+//! fingerprints are lost.
+//!
+//! The object seen by the last [`FingerprintVault::fetch_all`] is kept with its
+//! ETag, so a publish right after a sync needs no read. Hashes known to be stored
+//! are remembered in a small LRU, and publishing one of them again costs no round
+//! trip at all.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::remediation::fingerprint::SecretFingerprint;
+use crate::remediation::vault::{decode_fingerprints, encode_fingerprints, FingerprintVault};
+
+/// Default number of writes a publish attempts before giving up on conflicts.
+pub const DEFAULT_PUBLISH_ATTEMPTS: u32 = 5;
+
+/// Default delay before the first retry after a conflict; doubled for each one
+/// after it, and jittered by up to half either way.
+pub const DEFAULT_CONFLICT_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Default number of published hashes remembered to skip repeat publishes.
+pub const DEFAULT_PUBLISHED_CACHE: usize = 4096;
+
+/// An object as read from the store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredObject {
+    pub body: Vec<u8>,
+    pub etag: String,
+}
+
+/// When a write may go ahead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriteCondition {
+    /// Only if the object does not exist yet.
+    Absent,
+    /// Only if the object still has this ETag.
+    Matches(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriteOutcome {
+    /// The object was replaced; holds its new ETag when the store reports one.
+    Written(Option<String>),
+    /// The condition no longer held because someone else wrote first (HTTP 412).
+    PreconditionFailed,
+}
+
+/// A key-value object store with ETags and conditional writes.
+#[async_trait]
+pub trait ObjectStore: Send + Sync {
+    /// What the dashboard calls the vault, e.g. `AWS S3`.
+    fn name(&self) -> &str;
+
+    /// Reads the object at `key`; `None` when it does not exist.
+    async fn get(&self, key: &str) -> Result<Option<StoredObject>>;
+
+    /// Writes `body` to `key` if `condition` holds.
+    async fn put(&self, key: &str, body: Vec<u8>, condition: WriteCondition) -> Result<WriteOutcome>;
+}
+
+/// The stored list as last read or written. `etag` is `None` when there was no object.
+struct Snapshot {
+    etag: Option<String>,
+    fingerprints: Vec<SecretFingerprint>,
+}
+
+/// Hashes known to be stored, dropping the least recently used past `capacity`.
+struct RecentHashes {
+    capacity: usize,
+    tick: u64,
+    used: HashMap<String, u64>,
+}
+
+impl RecentHashes {
+    fn new(capacity: usize) -> Self {
+        Self { capacity, tick: 0, used: HashMap::new() }
+    }
+
+    fn touch(&mut self, hash: &str) -> bool {
+        self.tick += 1;
+        match self.used.get_mut(hash) {
+            Some(used) => {
+                *used = self.tick;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn insert(&mut self, hash: &str) {
+        if self.capacity == 0 || self.touch(hash) {
+            return;
+        }
+        if self.used.len() >= self.capacity {
+            if let Some(oldest) = self.used.iter().min_by_key(|(_, used)| **used).map(|(hash, _)| hash.clone()) {
+                self.used.remove(&oldest);
+            }
+        }
+        self.used.insert(hash.to_string(), self.tick);
+    }
+}
+
+/// A [`FingerprintVault`] holding every fingerprint in one object of `store`.
+pub struct ObjectVault<S> {
+    store: S,
+    key: String,
+    max_attempts: u32,
+    backoff: Duration,
+    snapshot: Mutex<Option<Snapshot>>,
+    published: Mutex<RecentHashes>,
+}
+
+impl<S: ObjectStore> ObjectVault<S> {
+    pub fn new(store: S, key: impl Into<String>) -> Self {
+        Self {
+            store,
+            key: key.into(),
+            max_attempts: DEFAULT_PUBLISH_ATTEMPTS,
+            backoff: DEFAULT_CONFLICT_BACKOFF,
+            snapshot: Mutex::new(None),
+            published: Mutex::new(RecentHashes::new(DEFAULT_PUBLISHED_CACHE)),
+        }
+    }
+
+    /// Attempts each publish up to `max_attempts` writes (at least one).
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Waits about `backoff` before the first retry, doubling it for each later one.
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Remembers up to `capacity` published hashes; zero disables the cache.
+    pub fn with_published_cache(mut self, capacity: usize) -> Self {
+        self.published = Mutex::new(RecentHashes::new(capacity));
+        self
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// The ETag of the object as last read or written, if it existed.
+    pub fn last_etag(&self) -> Option<String> {
+        self.snapshot.lock().unwrap_or_else(PoisonError::into_inner).as_ref().and_then(|s| s.etag.clone())
+    }
+
+    async fn read(&self) -> Result<Snapshot> {
+        Ok(match self.store.get(&self.key).await? {
+            Some(object) => Snapshot {
+                fingerprints: decode_fingerprints(&object.body)
+                    .with_context(|| format!("Invalid fingerprint vault object '{}'", self.key))?,
+                etag: Some(object.etag),
+            },
+            None => Snapshot { etag: None, fingerprints: Vec::new() },
+        })
+    }
+
+    fn remember(&self, hashes: impl IntoIterator<Item = String>) {
+        let mut published = self.published.lock().unwrap_or_else(PoisonError::into_inner);
+        for hash in hashes {
+            published.insert(&hash);
+        }
+    }
+
+    /// `backoff` doubled `retry` times, scaled by a random 0.5–1.5.
+    fn jittered_backoff(&self, retry: u32) -> Duration {
+        let mut bytes = [0u8; 8];
+        let fraction = match SystemRandom::new().fill(&mut bytes) {
+            Ok(()) => u64::from_le_bytes(bytes) as f64 / u64::MAX as f64,
+            Err(_) => 0.5,
+        };
+        self.backoff.saturating_mul(1 << retry.min(16)).mul_f64(0.5 + fraction)
+    }
+}
+
+#[async_trait]
+impl<S: ObjectStore> FingerprintVault for ObjectVault<S> {
+    fn name(&self) -> &str {
+        self.store.name()
+    }
+
+    async fn publish(&self, fingerprint: SecretFingerprint) -> Result<()> {
+        self.publish_batch(vec![fingerprint]).await
+    }
+
+    /// Merges every fingerprint not already stored in one conditional write,
+    /// retrying on conflicts.
+    async fn publish_batch(&self, fingerprints: Vec<SecretFingerprint>) -> Result<()> {
+        let mut seen = HashSet::new();
+        let fresh: Vec<SecretFingerprint> = {
+            let mut published = self.published.lock().unwrap_or_else(PoisonError::into_inner);
+            fingerprints.into_iter()
+                .filter(|f| !published.touch(&f.hash) && seen.insert(f.hash.clone()))
+                .collect()
+        };
+        if fresh.is_empty() {
+            return Ok(());
+        }
+
+        for attempt in 1..=self.max_attempts {
+            let cached = self.snapshot.lock().unwrap_or_else(PoisonError::into_inner).take();
+            let Snapshot { etag, mut fingerprints } = match cached {
+                Some(snapshot) => snapshot,
+                None => self.read().await?,
+            };
+            let stored: HashSet<&str> = fingerprints.iter().map(|f| f.hash.as_str()).collect();
+            let missing: Vec<SecretFingerprint> = fresh.iter().filter(|f| !stored.contains(f.hash.as_str())).cloned().collect();
+            if missing.is_empty() {
+                *self.snapshot.lock().unwrap_or_else(PoisonError::into_inner) = Some(Snapshot { etag, fingerprints });
+                self.remember(fresh.into_iter().map(|f| f.hash));
+                return Ok(());
+            }
+            fingerprints.extend(missing);
+
+            let condition = match etag {
+                Some(etag) => WriteCondition::Matches(etag),
+                None => WriteCondition::Absent,
+            };
+            let body = encode_fingerprints(&fingerprints)?;
+            match self.store.put(&self.key, body, condition).await
+                .with_context(|| format!("Failed to write fingerprints to {}", self.store.name()))?
+            {
+                WriteOutcome::Written(etag) => {
+                    if etag.is_some() {
+                        *self.snapshot.lock().unwrap_or_else(PoisonError::into_inner) = Some(Snapshot { etag, fingerprints });
+                    }
+                    self.remember(fresh.into_iter().map(|f| f.hash));
+                    return Ok(());
+                }
+                WriteOutcome::PreconditionFailed if attempt < self.max_attempts => {
+                    log::debug!("Fingerprint vault '{}' changed while publishing (attempt {}), retrying", self.key, attempt);
+                    tokio::time::sleep(self.jittered_backoff(attempt - 1)).await;
+                }
+                WriteOutcome::PreconditionFailed => {}
+            }
+        }
+        bail!(
+            "Gave up publishing to {} after {} attempts: '{}' kept changing underneath",
+            self.store.name(), self.max_attempts, self.key
+        )
+    }
+
+    /// Reads every stored fingerprint, keeping the object's ETag for the next publish.
+    async fn fetch_all(&self) -> Result<Vec<SecretFingerprint>> {
+        let snapshot = self.read().await?;
+        let fingerprints = snapshot.fingerprints.clone();
+        *self.snapshot.lock().unwrap_or_else(PoisonError::into_inner) = Some(snapshot);
+        Ok(fingerprints)
+    }
+}
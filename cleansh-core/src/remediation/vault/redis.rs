@@ -0,0 +1,383 @@
+// cleansh-core/src/remediation/vault/redis.rs
+//! Redis-backed implementation of the FingerprintVault, for fleets that need a new
+//! fingerprint everywhere within seconds rather than on the next poll.
+//!
+//! Each organization gets three keys: the set `cleansh:fingerprints:<org>` of
+//! hashes (`SADD` / `SMEMBERS`), the hash `cleansh:fingerprints:<org>:meta` holding
+//! each fingerprint's JSON, and the pub/sub channel
+//! `cleansh:fingerprints:<org>:events`, on which every newly added fingerprint is
+//! announced.
+//!
+//! `rediss://` URLs connect over TLS, verified against the system's trusted roots,
+//! so a private CA installed there is accepted.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures::StreamExt;
+use redis::aio::MultiplexedConnection;
+use redis::{AsyncConnectionConfig, Client};
+use tokio::sync::{mpsc, Mutex};
+
+use crate::remediation::fingerprint::SecretFingerprint;
+use crate::remediation::vault::{FingerprintStream, FingerprintVault};
+
+const KEY_PREFIX: &str = "cleansh:fingerprints";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
+/// Hashes looked up per `HMGET`.
+const META_BATCH: usize = 1000;
+/// Pushed fingerprints held for a sync loop that has fallen behind.
+const SUBSCRIPTION_CAPACITY: usize = 1024;
+
+/// Drops the credentials from `url` for error messages.
+fn redact_url(url: &str) -> String {
+    match (url.find("://"), url.rfind('@')) {
+        (Some(scheme_end), Some(at)) if at > scheme_end => format!("{}***{}", &url[..scheme_end + 3], &url[at..]),
+        _ => url.to_string(),
+    }
+}
+
+/// Fingerprints for one organization in Redis, announced over pub/sub as they are added.
+pub struct RedisVault {
+    client: Client,
+    org: String,
+    conn: Mutex<Option<MultiplexedConnection>>,
+}
+
+impl RedisVault {
+    /// Parses `redis[s]://[[user]:password@]host[:port][/db]` and connects lazily,
+    /// on first use.
+    pub fn new(url: &str, org: &str) -> Result<Self> {
+        // rustls needs a process-wide crypto provider; another caller may already have installed one.
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let client = Client::open(url).with_context(|| format!("Invalid Redis URL '{}'", redact_url(url)))?;
+        Ok(Self { client, org: org.to_string(), conn: Mutex::new(None) })
+    }
+
+    /// The set holding this organization's fingerprint hashes.
+    pub fn set_key(&self) -> String {
+        format!("{}:{}", KEY_PREFIX, self.org)
+    }
+
+    fn meta_key(&self) -> String {
+        format!("{}:meta", self.set_key())
+    }
+
+    /// The pub/sub channel new fingerprints are announced on.
+    pub fn channel(&self) -> String {
+        format!("{}:events", self.set_key())
+    }
+
+    /// Runs `pipe` as one round trip, connecting first if needed. The connection
+    /// is dropped after a failure and re-opened next time.
+    async fn query<T: redis::FromRedisValue>(&self, pipe: &redis::Pipeline) -> Result<T> {
+        let mut guard = self.conn.lock().await;
+        let mut conn = match guard.take() {
+            Some(conn) => conn,
+            None => {
+                let config = AsyncConnectionConfig::new()
+                    .set_connection_timeout(Some(CONNECT_TIMEOUT))
+                    .set_response_timeout(Some(COMMAND_TIMEOUT));
+                self.client.get_multiplexed_async_connection_with_config(&config).await
+                    .context("Failed to connect to Redis")?
+            }
+        };
+        let reply = pipe.query_async(&mut conn).await.context("Redis command failed")?;
+        *guard = Some(conn);
+        Ok(reply)
+    }
+}
+
+/// A hash that was added without metadata, e.g. by `SADD` from another tool.
+fn bare_fingerprint(hash: &str) -> SecretFingerprint {
+    SecretFingerprint {
+        hash: hash.to_string(),
+        provider: "unknown".to_string(),
+        detected_at: String::new(),
+        severity: "high".to_string(),
+    }
+}
+
+#[async_trait]
+impl FingerprintVault for RedisVault {
+    fn name(&self) -> &str {
+        "Redis"
+    }
+
+    /// Adds the hash to the set and, if it was not there yet, stores its metadata
+    /// and announces it to subscribers.
+    async fn publish(&self, fingerprint: SecretFingerprint) -> Result<()> {
+        let (set, meta, channel) = (self.set_key(), self.meta_key(), self.channel());
+        let json = serde_json::to_string(&fingerprint)?;
+        let hash = fingerprint.hash.as_str();
+        let (added, _): (i64, i64) = self.query(redis::pipe().sadd(&set, hash).hset_nx(&meta, hash, &json)).await?;
+        if added == 1 {
+            let _: (i64,) = self.query(redis::pipe().publish(&channel, &json)).await?;
+        }
+        Ok(())
+    }
+
+    async fn fetch_all(&self) -> Result<Vec<SecretFingerprint>> {
+        let (set, meta) = (self.set_key(), self.meta_key());
+        let (hashes,): (Vec<String>,) = self.query(redis::pipe().smembers(&set)).await?;
+
+        let mut fingerprints = Vec::with_capacity(hashes.len());
+        for batch in hashes.chunks(META_BATCH) {
+            let (values,): (Vec<Option<Vec<u8>>>,) = self.query(redis::pipe().cmd("HMGET").arg(&meta).arg(batch)).await?;
+            for (hash, value) in batch.iter().zip(values) {
+                fingerprints.push(match value {
+                    Some(json) => serde_json::from_slice(&json).unwrap_or_else(|_| bare_fingerprint(hash)),
+                    None => bare_fingerprint(hash),
+                });
+            }
+        }
+        Ok(fingerprints)
+    }
+
+    /// Subscribes to the events channel on a connection of its own. The stream
+    /// ends when that connection fails.
+    async fn subscribe(&self) -> Result<Option<FingerprintStream>> {
+        let channel = self.channel();
+        let subscribe = async {
+            let mut pubsub = self.client.get_async_pubsub().await?;
+            pubsub.subscribe(&channel).await?;
+            redis::RedisResult::Ok(pubsub)
+        };
+        let pubsub = tokio::time::timeout(CONNECT_TIMEOUT + COMMAND_TIMEOUT, subscribe).await
+            .context("Timed out subscribing to Redis")?
+            .context("Failed to subscribe to Redis")?;
+
+        let (tx, rx) = mpsc::channel(SUBSCRIPTION_CAPACITY);
+        tokio::spawn(async move {
+            let mut messages = pubsub.into_on_message();
+            loop {
+                let message = tokio::select! {
+                    message = messages.next() => message,
+                    _ = tx.closed() => return,
+                };
+                let Some(message) = message else {
+                    log::warn!("Redis fingerprint subscription on '{}' ended", channel);
+                    return;
+                };
+                match serde_json::from_slice::<SecretFingerprint>(message.get_payload_bytes()) {
+                    Ok(fingerprint) => {
+                        if tx.send(fingerprint).await.is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => log::warn!("Ignoring malformed fingerprint on '{}': {}", channel, e),
+                }
+            }
+        });
+        Ok(Some(rx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{BTreeSet, HashMap};
+    use std::sync::Arc;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    /// A RESP2 value, as the fake server reads and writes them.
+    enum Reply {
+        Status(String),
+        Error(String),
+        Integer(i64),
+        Bulk(Option<Vec<u8>>),
+        Array(Option<Vec<Reply>>),
+    }
+
+    impl Reply {
+        fn encode(&self, out: &mut Vec<u8>) {
+            match self {
+                Reply::Status(s) => out.extend_from_slice(format!("+{}\r\n", s).as_bytes()),
+                Reply::Error(s) => out.extend_from_slice(format!("-{}\r\n", s).as_bytes()),
+                Reply::Integer(n) => out.extend_from_slice(format!(":{}\r\n", n).as_bytes()),
+                Reply::Bulk(None) => out.extend_from_slice(b"$-1\r\n"),
+                Reply::Bulk(Some(b)) => {
+                    out.extend_from_slice(format!("${}\r\n", b.len()).as_bytes());
+                    out.extend_from_slice(b);
+                    out.extend_from_slice(b"\r\n");
+                }
+                Reply::Array(None) => out.extend_from_slice(b"*-1\r\n"),
+                Reply::Array(Some(items)) => {
+                    out.extend_from_slice(format!("*{}\r\n", items.len()).as_bytes());
+                    items.iter().for_each(|item| item.encode(out));
+                }
+            }
+        }
+    }
+
+    /// Reads one command, sent as an array of bulk strings.
+    async fn read_command<R: tokio::io::AsyncBufRead + Unpin>(reader: &mut R) -> Option<Vec<String>> {
+        async fn line<R: tokio::io::AsyncBufRead + Unpin>(reader: &mut R) -> Option<String> {
+            let mut line = String::new();
+            (reader.read_line(&mut line).await.ok()? > 0).then(|| line.trim_end().to_string())
+        }
+        let count: usize = line(reader).await?.strip_prefix('*')?.parse().ok()?;
+        let mut args = Vec::with_capacity(count);
+        for _ in 0..count {
+            let len: usize = line(reader).await?.strip_prefix('$')?.parse().ok()?;
+            let mut buf = vec![0; len + 2];
+            reader.read_exact(&mut buf).await.ok()?;
+            buf.truncate(len);
+            args.push(String::from_utf8(buf).ok()?);
+        }
+        Some(args)
+    }
+
+
+    /// Just enough of a Redis server for the vault: sets, hashes and pub/sub.
+    #[derive(Default)]
+    struct FakeRedis {
+        sets: HashMap<String, BTreeSet<String>>,
+        hashes: HashMap<String, HashMap<String, String>>,
+        subscribers: Vec<(String, mpsc::UnboundedSender<Vec<u8>>)>,
+        commands: Vec<Vec<String>>,
+    }
+
+    fn bulk(s: &str) -> Reply {
+        Reply::Bulk(Some(s.as_bytes().to_vec()))
+    }
+
+    impl FakeRedis {
+        fn apply(&mut self, args: &[String], conn_tx: &mpsc::UnboundedSender<Vec<u8>>) -> Reply {
+            self.commands.push(args.to_vec());
+            let a: Vec<&str> = args.iter().map(String::as_str).collect();
+            match a.as_slice() {
+                ["AUTH", ..] | ["SELECT", _] | ["CLIENT", ..] => Reply::Status("OK".to_string()),
+                ["SADD", key, members @ ..] => {
+                    let set = self.sets.entry(key.to_string()).or_default();
+                    Reply::Integer(members.iter().filter(|m| set.insert(m.to_string())).count() as i64)
+                }
+                ["SMEMBERS", key] => Reply::Array(Some(
+                    self.sets.get(*key).into_iter().flatten().map(|m| bulk(m)).collect(),
+                )),
+                ["HSETNX", key, field, value] => {
+                    let hash = self.hashes.entry(key.to_string()).or_default();
+                    let added = !hash.contains_key(*field);
+                    hash.entry(field.to_string()).or_insert_with(|| value.to_string());
+                    Reply::Integer(added as i64)
+                }
+                ["HMGET", key, fields @ ..] => Reply::Array(Some(
+                    fields.iter()
+                        .map(|f| Reply::Bulk(self.hashes.get(*key).and_then(|h| h.get(*f)).map(|v| v.as_bytes().to_vec())))
+                        .collect(),
+                )),
+                ["PUBLISH", channel, payload] => {
+                    let message = Reply::Array(Some(vec![bulk("message"), bulk(channel), bulk(payload)]));
+                    let mut out = Vec::new();
+                    message.encode(&mut out);
+                    self.subscribers.retain(|(c, tx)| c != channel || tx.send(out.clone()).is_ok());
+                    Reply::Integer(self.subscribers.iter().filter(|(c, _)| c == channel).count() as i64)
+                }
+                ["SUBSCRIBE", channel] => {
+                    self.subscribers.push((channel.to_string(), conn_tx.clone()));
+                    Reply::Array(Some(vec![bulk("subscribe"), bulk(channel), Reply::Integer(1)]))
+                }
+                _ => Reply::Error(format!("ERR unknown command '{}'", a.first().unwrap_or(&""))),
+            }
+        }
+    }
+
+    async fn serve(state: Arc<std::sync::Mutex<FakeRedis>>) -> Result<String> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("redis://:s3cret@{}", listener.local_addr()?);
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                let state = Arc::clone(&state);
+                tokio::spawn(async move {
+                    let (reader, mut writer) = socket.into_split();
+                    let mut reader = BufReader::new(reader);
+                    let (tx, mut rx) = mpsc::unbounded_channel::<Vec<u8>>();
+                    loop {
+                        tokio::select! {
+                            request = read_command(&mut reader) => {
+                                let Some(args) = request else { return };
+                                let reply = state.lock().unwrap().apply(&args, &tx);
+                                let mut out = Vec::new();
+                                reply.encode(&mut out);
+                                let _ = tx.send(out);
+                            }
+                            Some(out) = rx.recv() => {
+                                if writer.write_all(&out).await.is_err() {
+                                    return;
+                                }
+                            }
+                        }
+                    }
+                });
+            }
+        });
+        Ok(url)
+    }
+
+    fn fingerprint(hash: &str) -> SecretFingerprint {
+        SecretFingerprint {
+            hash: hash.to_string(),
+            provider: "github".to_string(),
+            detected_at: "2025-10-20T10:00:00Z".to_string(),
+            severity: "critical".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_and_fetch_round_trip_through_the_org_set() -> Result<()> {
+        let state = Arc::new(std::sync::Mutex::new(FakeRedis::default()));
+        let vault = RedisVault::new(&serve(Arc::clone(&state)).await?, "acme")?;
+
+        vault.publish(fingerprint("aaaa")).await?;
+        vault.publish(fingerprint("aaaa")).await?;
+        vault.publish(fingerprint("bbbb")).await?;
+        // A hash added by another tool, without metadata.
+        state.lock().unwrap().sets.get_mut("cleansh:fingerprints:acme").unwrap().insert("cccc".to_string());
+
+        let mut all = vault.fetch_all().await?;
+        all.sort_by(|a, b| a.hash.cmp(&b.hash));
+        let summary: Vec<(&str, &str)> = all.iter().map(|f| (f.hash.as_str(), f.provider.as_str())).collect();
+        assert_eq!(summary, [("aaaa", "github"), ("bbbb", "github"), ("cccc", "unknown")]);
+
+        let state = state.lock().unwrap();
+        let commands: Vec<&str> = state.commands.iter().map(|c| c[0].as_str()).collect();
+        // One connection, authenticated once; the duplicate is not announced again.
+        assert_eq!(commands.iter().filter(|&&c| c == "AUTH").count(), 1);
+        assert_eq!(commands.iter().filter(|&&c| c == "PUBLISH").count(), 2);
+        assert_eq!(state.commands[0], ["AUTH", "s3cret"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_subscribers_receive_newly_published_fingerprints() -> Result<()> {
+        let state = Arc::new(std::sync::Mutex::new(FakeRedis::default()));
+        let url = serve(state).await?;
+        let (listener, publisher) = (RedisVault::new(&url, "acme")?, RedisVault::new(&url, "acme")?);
+        let other_org = RedisVault::new(&url, "other")?;
+
+        let mut stream = listener.subscribe().await?.expect("redis vaults push");
+        other_org.publish(fingerprint("elsewhere")).await?;
+        publisher.publish(fingerprint("dddd")).await?;
+        publisher.publish(fingerprint("dddd")).await?;
+        publisher.publish(fingerprint("eeee")).await?;
+
+        for expected in ["dddd", "eeee"] {
+            let received = tokio::time::timeout(Duration::from_secs(5), stream.recv()).await?;
+            assert_eq!(received.map(|f| f.hash).as_deref(), Some(expected));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_urls_are_checked_and_credentials_kept_out_of_errors() {
+        assert!(RedisVault::new("redis://cache.internal", "acme").is_ok());
+        assert!(RedisVault::new("rediss://ops:p%40ss@[::1]:6380/2", "acme").is_ok());
+
+        let err = RedisVault::new("http://:hunter2@cache", "acme").err().unwrap();
+        assert!(!format!("{:#}", err).contains("hunter2"), "{:#}", err);
+        assert_eq!(redact_url("rediss://ops:pw@cache:6380/1"), "rediss://***@cache:6380/1");
+    }
+}
@@ -1,76 +1,144 @@
 // cleansh-core/src/remediation/vault/s3.rs
 //! S3-backed implementation of the FingerprintVault.
-//! Provides organization-wide secret ubiquity using a central JSON store.
+//! Provides organization-wide secret ubiquity using a central JSON Lines object,
+//! published with S3 conditional writes (see [`super::object`]) so concurrent
+//! instances do not overwrite each other's fingerprints.
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use aws_sdk_s3::Client;
-use crate::remediation::fingerprint::SecretFingerprint;
-use crate::remediation::vault::FingerprintVault;
-use std::sync::Arc;
+use crate::remediation::vault::object::{ObjectStore, ObjectVault, StoredObject, WriteCondition, WriteOutcome};
 
-pub struct S3Vault {
+/// S3 answers a write whose `If-Match` or `If-None-Match` no longer holds with 412,
+/// and one racing another conditional write to the same key with 409.
+const CONFLICT_STATUSES: [u16; 2] = [412, 409];
+
+pub struct S3Store {
     client: Client,
     bucket: String,
-    key: String,
+}
+
+pub type S3Vault = ObjectVault<S3Store>;
+
+impl S3Store {
+    pub fn new(client: Client, bucket: impl Into<String>) -> Self {
+        Self { client, bucket: bucket.into() }
+    }
 }
 
 impl S3Vault {
-    pub async fn new(bucket: &str, key: &str) -> Self {
+    /// The vault at `key` in `bucket`, with credentials and region from the
+    /// standard AWS environment, profile, and instance metadata chain.
+    pub async fn from_env(bucket: &str, key: &str) -> Self {
         let config = aws_config::load_from_env().await;
-        let client = Client::new(&config);
-        Self {
-            client,
-            bucket: bucket.to_string(),
-            key: key.to_string(),
-        }
+        ObjectVault::new(S3Store::new(Client::new(&config), bucket), key)
     }
 }
 
 #[async_trait]
-impl FingerprintVault for S3Vault {
-    /// Publishes a new fingerprint by fetching, merging, and re-uploading.
-    /// Note: In a high-concurrency environment, this should use S3 conditional writes (ETags).
-    async fn publish(&self, fingerprint: SecretFingerprint) -> Result<()> {
-        let mut all = self.fetch_all().await.unwrap_or_default();
-        
-        // Only add if it's a new unique hash
-        if !all.iter().any(|f| f.hash == fingerprint.hash) {
-            all.push(fingerprint);
-            let json = serde_json::to_vec(&all)?;
+impl ObjectStore for S3Store {
+    fn name(&self) -> &str {
+        "AWS S3"
+    }
 
-            self.client
-                .put_object()
-                .bucket(&self.bucket)
-                .key(&self.key)
-                .body(json.into())
-                .content_type("application/json")
-                .send()
-                .await
-                .context("Failed to upload updated fingerprints to S3")?;
-        }
-        Ok(())
+    async fn get(&self, key: &str) -> Result<Option<StoredObject>> {
+        let output = match self.client.get_object().bucket(&self.bucket).key(key).send().await {
+            Ok(output) => output,
+            // If the object doesn't exist yet, the vault is empty.
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_no_such_key()) => return Ok(None),
+            Err(e) => return Err(e).context("Failed to fetch fingerprints from S3"),
+        };
+        let etag = output.e_tag().unwrap_or_default().to_string();
+        let body = output.body.collect().await.context("Failed to read fingerprints from S3")?.to_vec();
+        Ok(Some(StoredObject { body, etag }))
     }
 
-    /// Fetches the global list of fingerprints for ubiquitous masking.
-    async fn fetch_all(&self) -> Result<Vec<SecretFingerprint>> {
-        let resp = self.client
-            .get_object()
+    async fn put(&self, key: &str, body: Vec<u8>, condition: WriteCondition) -> Result<WriteOutcome> {
+        let request = self.client
+            .put_object()
             .bucket(&self.bucket)
-            .key(&self.key)
-            .send()
+            .key(key)
+            .body(body.into())
+            .content_type("application/x-ndjson");
+        let request = match condition {
+            WriteCondition::Absent => request.if_none_match("*"),
+            WriteCondition::Matches(etag) => request.if_match(etag),
+        };
+        match request.send().await {
+            Ok(output) => Ok(WriteOutcome::Written(output.e_tag().map(str::to_string))),
+            Err(e) if e.raw_response().is_some_and(|r| CONFLICT_STATUSES.contains(&r.status().as_u16())) => {
+                Ok(WriteOutcome::PreconditionFailed)
+            }
+            Err(e) => Err(e).context("Failed to upload updated fingerprints to S3"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::remediation::fingerprint::SecretFingerprint;
+    use crate::remediation::vault::{encode_fingerprints, FingerprintVault};
+    use aws_sdk_s3::config::{BehaviorVersion, Credentials, Region};
+    use std::time::Duration;
+
+    fn vault(url: &str) -> S3Vault {
+        let config = aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .endpoint_url(url)
+            .force_path_style(true)
+            .region(Region::new("us-east-1"))
+            .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+            .build();
+        ObjectVault::new(S3Store::new(Client::from_conf(config), "bucket"), "fingerprints.jsonl")
+            .with_backoff(Duration::from_millis(1))
+    }
+
+    #[tokio::test]
+    async fn test_a_412_from_s3_re_reads_and_retries_against_the_new_etag() -> Result<()> {
+        let rival = SecretFingerprint::from_secret("sk_rival_222222", "stripe", b"salt");
+        let ours = SecretFingerprint::from_secret("sk_ours_1111111", "stripe", b"salt");
+        let mut server = mockito::Server::new_async().await;
+        // No object yet, but another instance creates it before our write lands.
+        let missing = server.mock("GET", "/bucket/fingerprints.jsonl")
+            .match_query(mockito::Matcher::Any)
+            .with_status(404)
+            .with_body("<Error><Code>NoSuchKey</Code><Message>missing</Message></Error>")
+            .expect(1)
+            .create_async()
+            .await;
+        let create = server.mock("PUT", "/bucket/fingerprints.jsonl")
+            .match_query(mockito::Matcher::Any)
+            .match_header("if-none-match", "*")
+            .with_status(412)
+            .with_body("<Error><Code>PreconditionFailed</Code><Message>exists</Message></Error>")
+            .create_async()
+            .await;
+        let reread = server.mock("GET", "/bucket/fingerprints.jsonl")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("etag", "\"rival\"")
+            .with_body(encode_fingerprints(std::slice::from_ref(&rival))?)
+            .create_async()
+            .await;
+        let update = server.mock("PUT", "/bucket/fingerprints.jsonl")
+            .match_query(mockito::Matcher::Any)
+            .match_header("if-match", "\"rival\"")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex(rival.hash.clone()),
+                mockito::Matcher::Regex(ours.hash.clone()),
+            ]))
+            .with_status(200)
+            .with_header("etag", "\"merged\"")
+            .create_async()
             .await;
 
-        match resp {
-            Ok(output) => {
-                let bytes = output.body.collect().await?.to_vec();
-                let fingerprints: Vec<SecretFingerprint> = serde_json::from_slice(&bytes)?;
-                Ok(fingerprints)
-            }
-            Err(_) => {
-                // If file doesn't exist yet, return empty list
-                Ok(Vec::new())
-            }
+        let vault = vault(&server.url());
+        vault.publish(ours).await?;
+        for mock in [missing, create, reread, update] {
+            mock.assert_async().await;
         }
+        assert_eq!(vault.last_etag().as_deref(), Some("\"merged\""));
+        Ok(())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,112 @@
+// cleansh-core/src/sanitizers/block.rs
+//! Reassembly of multi-line secrets, such as PEM private keys, from line-by-line input.
+//!
+//! The TUI and `cleansh watch` hand the engine one line at a time, so a `multiline`
+//! rule never sees a block that spans several lines. Rules that declare
+//! `block_start`/`block_end` markers fix that: [`BlockAssembler`] holds back the
+//! lines from a start marker through the matching end marker and releases them as
+//! one unit, joined with `\n`. Every other line passes straight through.
+
+use regex::Regex;
+
+use crate::config::RedactionRule;
+use crate::errors::CleanshError;
+
+/// An unterminated block is released once it reaches this many lines, so a stray
+/// start marker cannot hold back the rest of the stream.
+pub const DEFAULT_MAX_BLOCK_LINES: usize = 256;
+
+#[derive(Debug, Clone)]
+struct BlockMarkers {
+    rule_name: String,
+    start: Regex,
+    end: Regex,
+}
+
+/// Buffers the lines of marked blocks so the engine sees each block whole.
+#[derive(Debug, Clone)]
+pub struct BlockAssembler {
+    markers: Vec<BlockMarkers>,
+    /// Index into `markers` of the block being buffered.
+    open: Option<usize>,
+    pending: Vec<String>,
+    max_lines: usize,
+}
+
+impl BlockAssembler {
+    /// Collects the markers of every enabled rule that sets both `block_start` and
+    /// `block_end`. A rule that sets only one of them is skipped with a warning.
+    pub fn from_rules(rules: &[RedactionRule]) -> Result<Self, CleanshError> {
+        let mut markers = Vec::new();
+        for rule in rules.iter().filter(|r| r.enabled != Some(false)) {
+            match (&rule.block_start, &rule.block_end) {
+                (Some(start), Some(end)) => markers.push(BlockMarkers {
+                    rule_name: rule.name.clone(),
+                    start: Regex::new(start).map_err(|e| CleanshError::RuleCompilationError(rule.name.clone(), e))?,
+                    end: Regex::new(end).map_err(|e| CleanshError::RuleCompilationError(rule.name.clone(), e))?,
+                }),
+                (None, None) => {}
+                _ => log::warn!("Rule '{}' needs both block_start and block_end; ignoring its block markers.", rule.name),
+            }
+        }
+        Ok(Self { markers, open: None, pending: Vec::new(), max_lines: DEFAULT_MAX_BLOCK_LINES })
+    }
+
+    /// Releases an unterminated block once it holds `max_lines` lines (at least 2).
+    pub fn with_max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = max_lines.max(2);
+        self
+    }
+
+    /// True when no rule declares block markers, so every line passes through as-is.
+    pub fn is_empty(&self) -> bool {
+        self.markers.is_empty()
+    }
+
+    /// True while a block has started but not yet ended.
+    pub fn is_buffering(&self) -> bool {
+        self.open.is_some()
+    }
+
+    /// Feeds one line (without its line ending) and returns the units ready for the
+    /// engine: nothing while a block is open, the joined block once it ends, and
+    /// otherwise the line itself.
+    pub fn push(&mut self, line: String) -> Vec<String> {
+        if let Some(index) = self.open {
+            let ended = self.markers[index].end.is_match(&line);
+            self.pending.push(line);
+            if !ended && self.pending.len() < self.max_lines {
+                return Vec::new();
+            }
+            if !ended {
+                log::debug!(
+                    "Block for rule '{}' not closed within {} lines; releasing it as-is.",
+                    self.markers[index].rule_name, self.max_lines
+                );
+            }
+            return self.flush().into_iter().collect();
+        }
+
+        let opened = self.markers.iter().position(|m| match m.start.find(&line) {
+            Some(start) => m.end.find_at(&line, start.end()).is_none(),
+            None => false,
+        });
+        match opened {
+            Some(index) => {
+                self.open = Some(index);
+                self.pending.push(line);
+                Vec::new()
+            }
+            None => vec![line],
+        }
+    }
+
+    /// Releases whatever block is still open, joined with `\n`; called at end of input.
+    pub fn flush(&mut self) -> Option<String> {
+        self.open = None;
+        if self.pending.is_empty() {
+            return None;
+        }
+        Some(std::mem::take(&mut self.pending).join("\n"))
+    }
+}
@@ -9,8 +9,10 @@
 
 use anyhow::Result;
 use log::{debug, warn};
-use regex::{Regex, RegexBuilder};
+use regex::{Regex, RegexBuilder, RegexSet, RegexSetBuilder};
+use regex_syntax::ast::{self, Ast, RepetitionKind, RepetitionRange};
 use lazy_static::lazy_static;
+use std::ops::Range;
 use std::sync::{Arc, RwLock};
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
@@ -18,6 +20,7 @@ use std::collections::hash_map::DefaultHasher;
 
 use crate::config::{RedactionRule, RedactionConfig, MAX_PATTERN_LENGTH};
 use crate::errors::CleanshError;
+use crate::validators;
 
 /// Represents a single compiled redaction rule.
 ///
@@ -33,6 +36,51 @@ pub struct CompiledRule {
     pub name: String,
     /// A flag indicating if this rule requires additional programmatic validation.
     pub programmatic_validation: bool,
+    /// Index of the capture group that is redacted, or `None` for the whole match.
+    pub redact_group: Option<usize>,
+}
+
+impl CompiledRule {
+    /// The part of a match that is redacted: the `redact_group`, or the whole match.
+    /// `None` when the group did not participate.
+    pub fn redacted_span<'h>(&self, caps: &regex::Captures<'h>) -> Option<regex::Match<'h>> {
+        caps.get(self.redact_group.unwrap_or(0))
+    }
+
+    /// `replace_with` with `$1`, `$2`, ... filled in from the match's groups.
+    pub fn replacement(&self, caps: &regex::Captures<'_>) -> String {
+        let mut replacement = self.replace_with.clone();
+        for i in 1..caps.len() {
+            if let Some(group) = caps.get(i) {
+                replacement = replacement.replace(&format!("${}", i), group.as_str());
+            }
+        }
+        replacement
+    }
+
+    /// The redacted span and replacement of the first of the rule's matches in
+    /// `text` whose redacted part covers `span`, so a candidate found some other
+    /// way can be confirmed as this rule's.
+    pub fn covering_match(&self, text: &str, span: Range<usize>) -> Option<(Range<usize>, String)> {
+        self.regex.captures_iter(text).find_map(|caps| {
+            let redacted = self.redacted_span(&caps)?;
+            (redacted.start() <= span.start && span.end <= redacted.end()).then(|| (redacted.range(), self.replacement(&caps)))
+        })
+    }
+
+    /// The programmatic validator run on this rule's matches, if any.
+    pub fn validator(&self) -> Option<validators::Validator> {
+        if !self.programmatic_validation {
+            return None;
+        }
+        validators::validator_for_rule(&self.name)
+    }
+
+    /// Whether `text`, a match of this rule, passes its programmatic validator
+    /// (e.g. the Luhn check for cards). Rules without one always pass.
+    pub fn passes_validation(&self, text: &str) -> bool {
+        self.validator().is_none_or(|validator| (validator.check)(text))
+    }
 }
 
 /// Represents a collection of all compiled rules for efficient sanitization.
@@ -43,6 +91,100 @@ pub struct CompiledRule {
 pub struct CompiledRules {
     /// A vector of `CompiledRule` instances ready for application.
     pub rules: Vec<CompiledRule>,
+    /// Every rule's pattern in one set, index for index with `rules`, so a single
+    /// pass over the input finds the rules that can match it. `None` when the set
+    /// could not be built (e.g. it exceeds the size limit).
+    pub prefilter: Option<RegexSet>,
+}
+
+impl CompiledRules {
+    /// Indices into `rules` of the rules that match somewhere in `text`, in order.
+    /// Without a prefilter every rule is a candidate.
+    pub fn candidates(&self, text: &str) -> Vec<usize> {
+        match &self.prefilter {
+            Some(set) => set.matches(text).into_iter().collect(),
+            None => (0..self.rules.len()).collect(),
+        }
+    }
+
+    /// The compiled rule named `name`.
+    pub fn find_rule(&self, name: &str) -> Option<&CompiledRule> {
+        self.rules.iter().find(|rule| rule.name == name)
+    }
+}
+
+/// Size limit for one rule's compiled regex.
+pub const RULE_SIZE_LIMIT: usize = 10 * (1 << 20);
+
+/// Largest count a single repetition may name, as in `a{1000}` or `a{2,1000}`.
+pub const MAX_REPETITION: u32 = 1000;
+
+/// Largest product of the counts of nested repetitions, as in `(a{100}){100}`,
+/// each of which multiplies the size of the compiled regex.
+pub const MAX_NESTED_REPETITION: u64 = 10_000;
+
+/// Checks the counted repetitions of `pattern` against `MAX_REPETITION` and
+/// `MAX_NESTED_REPETITION`, describing the first one over. A pattern that does
+/// not parse passes, leaving the syntax error to the regex compiler.
+pub fn check_repetition_bounds(pattern: &str) -> Result<(), String> {
+    match ast::parse::Parser::new().parse(pattern) {
+        Ok(ast) => check_repetitions(&ast, 1),
+        Err(_) => Ok(()),
+    }
+}
+
+/// Walks `ast`, which sits inside repetitions allowing `outer` repeats in all.
+fn check_repetitions(ast: &Ast, outer: u64) -> Result<(), String> {
+    match ast {
+        Ast::Repetition(repetition) => {
+            let (count, text) = match &repetition.op.kind {
+                RepetitionKind::Range(RepetitionRange::Exactly(n)) => (*n, format!("{{{}}}", n)),
+                RepetitionKind::Range(RepetitionRange::AtLeast(n)) => (*n, format!("{{{},}}", n)),
+                RepetitionKind::Range(RepetitionRange::Bounded(n, m)) => (*m, format!("{{{},{}}}", n, m)),
+                _ => (1, String::new()),
+            };
+            if count > MAX_REPETITION {
+                return Err(format!("repetition {} exceeds the maximum count of {}", text, MAX_REPETITION));
+            }
+            let total = outer.saturating_mul(u64::from(count.max(1)));
+            if total > MAX_NESTED_REPETITION {
+                return Err(format!(
+                    "nested repetitions ending in {} allow {} repeats, exceeding the maximum of {}",
+                    text, total, MAX_NESTED_REPETITION
+                ));
+            }
+            check_repetitions(&repetition.ast, total)
+        }
+        Ast::Group(group) => check_repetitions(&group.ast, outer),
+        Ast::Alternation(alternation) => alternation.asts.iter().try_for_each(|ast| check_repetitions(ast, outer)),
+        Ast::Concat(concat) => concat.asts.iter().try_for_each(|ast| check_repetitions(ast, outer)),
+        _ => Ok(()),
+    }
+}
+
+/// Size limit for the prefilter set, which holds every pattern at once.
+const PREFILTER_SIZE_LIMIT: usize = 64 * (1 << 20);
+
+/// `pattern` with the rule's flags inlined, since a `RegexSet` shares one set of builder flags.
+fn prefilter_pattern(rule: &RedactionRule, pattern: &str) -> String {
+    let flags = match (rule.multiline, rule.dot_matches_new_line) {
+        (true, true) => "(?ms)",
+        (true, false) => "(?m)",
+        (false, true) => "(?s)",
+        (false, false) => "",
+    };
+    format!("{}{}", flags, pattern)
+}
+
+/// Builds the prefilter over `patterns`, or `None` (with a warning) if it cannot be built.
+fn build_prefilter(patterns: &[String]) -> Option<RegexSet> {
+    match RegexSetBuilder::new(patterns).size_limit(PREFILTER_SIZE_LIMIT).build() {
+        Ok(set) => Some(set),
+        Err(e) => {
+            warn!("Matching every rule on every input; the rule prefilter could not be built: {}", e);
+            None
+        }
+    }
 }
 
 lazy_static! {
@@ -53,10 +195,11 @@ lazy_static! {
 
 /// Hashes the `RedactionConfig` to create a stable, unique key for the cache.
 ///
-/// To ensure determinism, the rules are sorted by name before hashing.
+/// Only active rules are hashed, since only they are compiled. To ensure
+/// determinism, they are sorted by name before hashing.
 fn hash_config(config: &RedactionConfig) -> u64 {
     let mut hasher = DefaultHasher::new();
-    let mut rules_to_hash = config.rules.clone();
+    let mut rules_to_hash: Vec<&RedactionRule> = config.active_rules().collect();
     
     // Sort rules to ensure a deterministic hash key.
     rules_to_hash.sort_by(|a, b| a.name.cmp(&b.name));
@@ -72,6 +215,7 @@ pub fn compile_rules(rules_to_compile: Vec<RedactionRule>) -> Result<CompiledRul
     debug!("Starting compilation of {} rules.", rules_to_compile.len());
 
     let mut compiled_rules = Vec::new();
+    let mut prefilter_patterns = Vec::new();
     let mut compilation_errors = Vec::new();
 
     for rule in rules_to_compile {
@@ -90,27 +234,49 @@ pub fn compile_rules(rules_to_compile: Vec<RedactionRule>) -> Result<CompiledRul
                     ));
                     continue;
                 }
+                if let Err(reason) = check_repetition_bounds(pattern) {
+                    compilation_errors.push(CleanshError::PatternTooComplex(rule.name, reason));
+                    continue;
+                }
 
                 let regex_result = RegexBuilder::new(pattern)
                     .multi_line(rule.multiline)
                     .dot_matches_new_line(rule.dot_matches_new_line)
-                    .size_limit(10 * (1 << 20)) // 10 MB limit for compiled regex
+                    .size_limit(RULE_SIZE_LIMIT)
                     .build();
 
                 match regex_result {
                     Ok(regex) => {
+                        let redact_group = match &rule.redact_group {
+                            Some(group) => match group.resolve(&regex) {
+                                Some(index) => Some(index),
+                                None => {
+                                    compilation_errors.push(CleanshError::RedactGroupNotFound(rule.name, group.to_string()));
+                                    continue;
+                                }
+                            },
+                            None => None,
+                        };
                         log::debug!(
                             target: "cleansh_core::sanitizer",
                             "Rule '{}' compiled successfully.",
                             &rule.name
                         );
+                        prefilter_patterns.push(prefilter_pattern(&rule, pattern));
                         compiled_rules.push(CompiledRule {
                             regex,
                             replace_with: rule.replace_with,
                             name: rule.name,
                             programmatic_validation: rule.programmatic_validation,
+                            redact_group,
                         });
                     }
+                    Err(regex::Error::CompiledTooBig(limit)) => {
+                        compilation_errors.push(CleanshError::PatternTooComplex(
+                            rule.name,
+                            format!("its compiled form exceeds the {} MiB size limit; use smaller repetition counts or fewer alternatives", limit >> 20),
+                        ));
+                    }
                     Err(e) => {
                         compilation_errors.push(CleanshError::RuleCompilationError(rule.name, e));
                     }
@@ -135,14 +301,15 @@ pub fn compile_rules(rules_to_compile: Vec<RedactionRule>) -> Result<CompiledRul
             "Finished compiling rules. Total compiled: {}.",
             compiled_rules.len()
         );
-        Ok(CompiledRules { rules: compiled_rules })
+        Ok(CompiledRules { rules: compiled_rules, prefilter: build_prefilter(&prefilter_patterns) })
     }
 }
 
 /// Gets a `CompiledRules` instance from the cache or compiles them if not found.
 ///
 /// This is the public entry point for retrieving compiled rules. It returns an `Arc`
-/// to a `CompiledRules` instance, allowing for cheap sharing.
+/// to a `CompiledRules` instance, allowing for cheap sharing. Disabled and
+/// not-enabled `opt_in` rules are left out (see `RedactionConfig::active_rules`).
 pub fn get_or_compile_rules(config: &RedactionConfig) -> Result<Arc<CompiledRules>> {
     let cache_key = hash_config(config);
     
@@ -157,7 +324,7 @@ pub fn get_or_compile_rules(config: &RedactionConfig) -> Result<Arc<CompiledRule
 
     // Not in cache, so we compile.
     debug!("Compiled rules not found in cache. Compiling now.");
-    let compiled = compile_rules(config.rules.clone())?;
+    let compiled = compile_rules(config.active_rules().cloned().collect())?;
     let compiled_arc = Arc::new(compiled);
 
     // Acquire a write lock to insert the new rules.
@@ -9,4 +9,6 @@
 //! This module works closely with `config` (for rule definitions), `validators` (for
 //! advanced pattern validation), and `redaction_match` (for logging and result types).
 
-pub mod compiler;
\ No newline at end of file
+pub mod block;
+pub mod compiler;
+pub mod structured;
@@ -0,0 +1,402 @@
+// cleansh-core/src/sanitizers/structured.rs
+//! Key-aware scanning of JSON and YAML documents.
+//!
+//! Flat-text context scanning only looks a few bytes back from a token, so it
+//! misses a secret whose key sits on another line, as in pretty-printed JSON or a
+//! YAML block scalar. When the input parses as JSON or YAML, this pre-pass walks
+//! it instead and flags every string value whose key names one of the context
+//! keywords (`password`, `token`, `secret`, ...).
+//!
+//! Neither `serde_json` nor `serde_yml` reports spans, so the parsers only confirm
+//! that the input is well-formed; a small tokenizer then walks the original text
+//! to find each value's byte offsets. Input that parses as neither yields no
+//! matches, leaving it to the regular engines.
+
+use std::ops::Range;
+
+use serde::Deserialize;
+
+use crate::config::{RedactionConfig, RedactionRule};
+use crate::redaction_match::{ensure_match_hashes, offset_from_index, OffsetBasis, RedactionMatch};
+
+/// The rule name carried by structured matches.
+pub const STRUCTURED_RULE_NAME: &str = "structured_secret";
+
+/// Confidence assigned to structured matches: the key names what the value is.
+pub const STRUCTURED_CONFIDENCE: f64 = 0.9;
+
+/// The document formats the pre-pass understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructuredFormat {
+    Json,
+    Yaml,
+}
+
+/// Returns the format `content` parses as, or `None` for anything else. Plain text
+/// is technically a YAML scalar, so YAML only counts when at least one document is
+/// a mapping or a sequence.
+pub fn detect_format(content: &str) -> Option<StructuredFormat> {
+    let trimmed = content.trim_start();
+    if (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(content).is_ok()
+    {
+        return Some(StructuredFormat::Json);
+    }
+
+    let mut structured = false;
+    for document in serde_yml::Deserializer::from_str(content) {
+        match serde_yml::Value::deserialize(document) {
+            Ok(serde_yml::Value::Mapping(_) | serde_yml::Value::Sequence(_)) => structured = true,
+            Ok(_) => {}
+            Err(_) => return None,
+        }
+    }
+    structured.then_some(StructuredFormat::Yaml)
+}
+
+/// Decides whether a key labels a secret.
+///
+/// Keys are split into words on punctuation and camelCase boundaries and compared
+/// case-insensitively, so `db_password`, `apiKey`, and `X-Auth-Token` all match,
+/// while `monkey` does not match `key`. A trailing plural `s` is ignored.
+#[derive(Debug, Clone)]
+pub struct KeyMatcher {
+    keywords: Vec<String>,
+}
+
+impl KeyMatcher {
+    pub fn new<S: AsRef<str>>(keywords: &[S]) -> Self {
+        let mut unique: Vec<String> = Vec::with_capacity(keywords.len());
+        for keyword in keywords {
+            let keyword = keyword.as_ref().trim().to_lowercase();
+            if !keyword.is_empty() && !unique.contains(&keyword) {
+                unique.push(keyword);
+            }
+        }
+        Self { keywords: unique }
+    }
+
+    /// A matcher for the entropy context keywords `config` resolves to.
+    pub fn from_config(config: &RedactionConfig) -> Self {
+        match config.engines.entropy.resolved_context_keywords() {
+            Some(keywords) => Self::new(&keywords),
+            None => Self::new(cleansh_entropy::context::DEFAULT_KEYWORDS),
+        }
+    }
+
+    pub fn matches(&self, key: &str) -> bool {
+        key_words(key).iter().any(|word| {
+            let singular = word.strip_suffix('s').unwrap_or(word);
+            self.keywords.iter().any(|k| k == word || k == singular)
+        })
+    }
+}
+
+/// Splits a key into lowercase words on non-alphanumeric characters and on
+/// lower-to-upper case transitions.
+fn key_words(key: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut previous_lower = false;
+    for c in key.chars() {
+        if !c.is_alphanumeric() {
+            words.extend((!current.is_empty()).then(|| std::mem::take(&mut current)));
+            previous_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && previous_lower {
+            words.push(std::mem::take(&mut current));
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.extend(c.to_lowercase());
+    }
+    words.extend((!current.is_empty()).then_some(current));
+    words
+}
+
+/// Finds values labelled by a secret key when `content` is JSON or YAML.
+///
+/// Offsets are bytes into `content`, covering the value without its quotes.
+/// Returns no matches when `content` is neither format.
+pub fn find_structured_matches(content: &str, source_id: &str, matcher: &KeyMatcher) -> Vec<RedactionMatch> {
+    let spans = match detect_format(content) {
+        Some(StructuredFormat::Json) => json_value_spans(content, matcher),
+        Some(StructuredFormat::Yaml) => yaml_value_spans(content, matcher),
+        None => return Vec::new(),
+    };
+
+    let rule = RedactionRule {
+        name: STRUCTURED_RULE_NAME.to_string(),
+        description: Some("String value under a key that names a secret".to_string()),
+        pattern_type: "structured".to_string(),
+        ..Default::default()
+    };
+    let mut matches: Vec<RedactionMatch> = spans
+        .into_iter()
+        .filter(|span| !span.is_empty())
+        .map(|span| RedactionMatch {
+            rule_name: rule.name.clone(),
+            original_string: content[span.clone()].to_string(),
+            sanitized_string: rule.replace_with.clone(),
+            start: offset_from_index(span.start),
+            end: offset_from_index(span.end),
+            offset_basis: OffsetBasis::Original,
+            original_start: Some(offset_from_index(span.start)),
+            original_end: Some(offset_from_index(span.end)),
+            line_number: Some(content[..span.start].matches('\n').count() as u64 + 1),
+            rule: rule.clone(),
+            source_id: source_id.to_string(),
+            confidence: Some(STRUCTURED_CONFIDENCE),
+            ..Default::default()
+        })
+        .collect();
+    ensure_match_hashes(&mut matches);
+    matches
+}
+
+/// Adds the structured matches that do not overlap one of `matches`, keeping the
+/// result in start order. Engine matches win: they carry a more specific rule.
+pub fn merge_structured_matches(matches: &mut Vec<RedactionMatch>, structured: Vec<RedactionMatch>) {
+    let existing: Vec<Range<usize>> = matches.iter().map(RedactionMatch::document_span).collect();
+    matches.extend(structured.into_iter().filter(|s| {
+        let span = s.document_span();
+        !existing.iter().any(|e| span.start < e.end && e.start < span.end)
+    }));
+    matches.sort_by_key(|m| {
+        let span = m.document_span();
+        (span.start, span.end)
+    });
+}
+
+/// One open JSON container and the key its values are labelled with.
+struct JsonFrame {
+    is_object: bool,
+    key: Option<String>,
+    expecting_key: bool,
+}
+
+/// Walks already-validated JSON, returning the spans of string values whose key
+/// (or, inside an array, the array's key) matches.
+fn json_value_spans(content: &str, matcher: &KeyMatcher) -> Vec<Range<usize>> {
+    let bytes = content.as_bytes();
+    let mut spans = Vec::new();
+    let mut stack: Vec<JsonFrame> = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' | b'[' => {
+                let key = stack.last().and_then(|f| f.key.clone());
+                stack.push(JsonFrame { is_object: bytes[i] == b'{', key, expecting_key: bytes[i] == b'{' });
+                i += 1;
+            }
+            b'}' | b']' => {
+                stack.pop();
+                i += 1;
+            }
+            b',' => {
+                if let Some(frame) = stack.last_mut().filter(|f| f.is_object) {
+                    frame.expecting_key = true;
+                    frame.key = None;
+                }
+                i += 1;
+            }
+            b'"' => {
+                let end = json_string_end(bytes, i);
+                let raw = &content[i..end];
+                match stack.last_mut() {
+                    Some(frame) if frame.is_object && frame.expecting_key => {
+                        frame.key = serde_json::from_str::<String>(raw).ok();
+                        frame.expecting_key = false;
+                    }
+                    Some(frame) if frame.key.as_deref().is_some_and(|k| matcher.matches(k)) => {
+                        spans.push(i + 1..end - 1);
+                    }
+                    _ => {}
+                }
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+    spans
+}
+
+/// The index just past the closing quote of the JSON string starting at `start`.
+fn json_string_end(bytes: &[u8], start: usize) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// A YAML key whose value continues on the following, more indented lines.
+struct YamlParent {
+    indent: usize,
+    key: String,
+}
+
+/// Walks already-validated YAML line by line, returning the spans of scalar values
+/// whose key matches. Handles block mappings, sequences of scalars under a key,
+/// quoted and plain scalars, and `|`/`>` block scalars; flow collections are skipped.
+fn yaml_value_spans(content: &str, matcher: &KeyMatcher) -> Vec<Range<usize>> {
+    let lines: Vec<(usize, &str)> = content
+        .split_inclusive('\n')
+        .scan(0, |offset, line| {
+            let start = *offset;
+            *offset += line.len();
+            Some((start, line.trim_end_matches(['\n', '\r'])))
+        })
+        .collect();
+
+    let mut spans = Vec::new();
+    let mut parents: Vec<YamlParent> = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let (line_start, line) = lines[index];
+        index += 1;
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') || line.starts_with("---") || line.starts_with("...") {
+            if line.starts_with("---") {
+                parents.clear();
+            }
+            continue;
+        }
+
+        // Sequence dashes count as indentation for whatever follows them.
+        let mut indent = line.len() - trimmed.len();
+        let mut rest = trimmed;
+        let mut is_item = false;
+        while let Some(after) = rest.strip_prefix("- ").or_else(|| (rest == "-").then_some("")) {
+            let after_trimmed = after.trim_start();
+            indent += rest.len() - after_trimmed.len();
+            rest = after_trimmed;
+            is_item = true;
+        }
+        while parents.last().is_some_and(|p| p.indent >= indent) {
+            parents.pop();
+        }
+        let rest_start = line_start + (line.len() - rest.len());
+
+        let Some((key, value_offset)) = yaml_key(rest) else {
+            // A bare sequence item inherits the key it is listed under.
+            if is_item && parents.last().is_some_and(|p| matcher.matches(&p.key)) {
+                spans.extend(yaml_scalar(rest).map(|r| rest_start + r.start..rest_start + r.end));
+            }
+            continue;
+        };
+        let value = &rest[value_offset..];
+        let value_start = rest_start + value_offset;
+
+        if value.is_empty() {
+            parents.push(YamlParent { indent, key });
+        } else if value.starts_with('|') || value.starts_with('>') {
+            let body = yaml_block_body(&lines, &mut index, indent);
+            if matcher.matches(&key) {
+                spans.extend(body);
+            }
+        } else if matcher.matches(&key) {
+            spans.extend(yaml_scalar(value).map(|r| value_start + r.start..value_start + r.end));
+        }
+    }
+    spans
+}
+
+/// Splits `key: value`, returning the unquoted key and the offset of the value
+/// (past the separator and spaces). `None` when the line is not a mapping entry.
+fn yaml_key(text: &str) -> Option<(String, usize)> {
+    let (key, separator_end) = if let Some(quote @ ('"' | '\'')) = text.chars().next() {
+        let close = text[1..].find(quote)? + 1;
+        let after = &text[close + 1..];
+        let after = after.strip_prefix(':')?;
+        (text[1..close].to_string(), text.len() - after.len())
+    } else {
+        let colon = text.find(": ").or_else(|| text.strip_suffix(':').map(|k| k.len()))?;
+        let key = &text[..colon];
+        if key.starts_with(['{', '[', '&', '*', '!', '|', '>']) {
+            return None;
+        }
+        (key.trim_end().to_string(), colon + 1)
+    };
+    let value_offset = separator_end + (text[separator_end..].len() - text[separator_end..].trim_start().len());
+    Some((key, value_offset))
+}
+
+/// The span of a one-line scalar value, without quotes, anchors, tags, or a
+/// trailing comment. `None` for nulls, booleans, aliases, and flow collections.
+fn yaml_scalar(value: &str) -> Option<Range<usize>> {
+    let mut start = 0;
+    let mut rest = value;
+    while rest.starts_with(['&', '!']) {
+        let token_end = rest.find(' ')?;
+        let next = rest[token_end..].trim_start();
+        start += rest.len() - next.len();
+        rest = next;
+    }
+    match rest.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let close = rest[1..].find(quote)? + 1;
+            Some(start + 1..start + close)
+        }
+        '*' | '{' | '[' | '#' => None,
+        _ => {
+            let end = rest.find(" #").unwrap_or(rest.len());
+            let scalar = rest[..end].trim_end();
+            if matches!(scalar, "~" | "null" | "Null" | "NULL" | "true" | "false" | "True" | "False") {
+                return None;
+            }
+            Some(start..start + scalar.len())
+        }
+    }
+}
+
+/// Consumes the lines of a block scalar indented deeper than `indent`, returning
+/// the span from its first content character to the end of its last line.
+fn yaml_block_body(lines: &[(usize, &str)], index: &mut usize, indent: usize) -> Option<Range<usize>> {
+    let mut span: Option<Range<usize>> = None;
+    while let Some(&(line_start, line)) = lines.get(*index) {
+        let trimmed = line.trim_start();
+        if !trimmed.is_empty() && line.len() - trimmed.len() <= indent {
+            break;
+        }
+        *index += 1;
+        if trimmed.is_empty() {
+            continue;
+        }
+        let content_start = line_start + (line.len() - trimmed.len());
+        let content_end = line_start + line.trim_end().len();
+        span = Some(span.map_or(content_start, |s| s.start)..content_end);
+    }
+    span
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_words_split_on_case_and_punctuation() {
+        assert_eq!(key_words("apiKey"), ["api", "key"]);
+        assert_eq!(key_words("X-Auth-Token"), ["x", "auth", "token"]);
+        assert_eq!(key_words("db_password2"), ["db", "password2"]);
+
+        let matcher = KeyMatcher::new(&["key", "password", "credential"]);
+        assert!(matcher.matches("apiKey"));
+        assert!(matcher.matches("credentials"));
+        assert!(!matcher.matches("monkey"));
+        assert!(!matcher.matches("db_password2"));
+    }
+
+    #[test]
+    fn test_yaml_scalar_strips_decoration() {
+        assert_eq!(yaml_scalar("hunter2 # note"), Some(0..7));
+        assert_eq!(yaml_scalar("'quoted value'"), Some(1..13));
+        assert_eq!(yaml_scalar("&anchor !!str abc"), Some(14..17));
+        assert_eq!(yaml_scalar("null"), None);
+        assert_eq!(yaml_scalar("*alias"), None);
+    }
+}
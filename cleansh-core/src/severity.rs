@@ -0,0 +1,98 @@
+// cleansh-core/src/severity.rs
+//! Ordered severity levels for rules, matches and summaries.
+//!
+//! Rules spell their severity as free-form strings in YAML; everything that
+//! compares severities (CI exit codes, heatmap intensity, report mappings) goes
+//! through [`Severity`] so that `critical > high > medium > low > info` holds
+//! regardless of spelling.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::redaction_match::RedactionMatch;
+use crate::remediation::ConfidenceLevel;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Low,
+    /// Also the level of rules that set no severity, or one that is not recognized.
+    #[default]
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 5] = [Severity::Info, Severity::Low, Severity::Medium, Severity::High, Severity::Critical];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+
+    /// A rule's `severity` as a level. Missing and unrecognized values count as
+    /// [`Severity::Medium`], so a typo neither silences a finding nor escalates it.
+    pub fn from_rule(severity: Option<&str>) -> Self {
+        match severity.map(str::parse::<Severity>) {
+            Some(Ok(level)) => level,
+            Some(Err(_)) => {
+                log::debug!("Unrecognized rule severity {:?}; treating it as medium", severity.unwrap_or_default());
+                Severity::Medium
+            }
+            None => Severity::Medium,
+        }
+    }
+
+    /// The level of a match scored only by confidence (the entropy engine), using
+    /// the same bands as [`ConfidenceLevel::from_score`]. A match without a score
+    /// counts as medium.
+    pub fn from_confidence(confidence: Option<f64>) -> Self {
+        match confidence.map(ConfidenceLevel::from_score) {
+            Some(ConfidenceLevel::Low) => Severity::Low,
+            Some(ConfidenceLevel::Medium) | None => Severity::Medium,
+            Some(ConfidenceLevel::High) => Severity::High,
+            Some(ConfidenceLevel::Critical) => Severity::Critical,
+        }
+    }
+
+    /// A match's level: its rule's severity when the rule sets one, otherwise the
+    /// confidence band for entropy matches, otherwise medium.
+    pub fn of_match(m: &RedactionMatch) -> Self {
+        match m.rule.severity.as_deref() {
+            Some(severity) => Severity::from_rule(Some(severity)),
+            None if m.rule.pattern_type == "entropy" => Severity::from_confidence(m.confidence),
+            None => Severity::Medium,
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    /// Case-insensitive; also accepts `informational` and `moderate`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "info" | "informational" => Ok(Severity::Info),
+            "low" => Ok(Severity::Low),
+            "medium" | "moderate" => Ok(Severity::Medium),
+            "high" => Ok(Severity::High),
+            "critical" => Ok(Severity::Critical),
+            other => Err(format!("unknown severity '{}' (expected info, low, medium, high or critical)", other)),
+        }
+    }
+}
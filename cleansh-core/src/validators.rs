@@ -2,14 +2,17 @@
 //! Programmatic validation functions for specific sensitive data types.
 //!
 //! This module provides additional validation logic beyond regular expression matching
-//! for sensitive information such as SSN and UK NINO. These functions help reduce
+//! for sensitive information such as SSN, UK NINO, IBAN, US ABA routing numbers,
+//! Bitcoin WIF private keys, and BIP-39 mnemonic phrases.
+//! These functions help reduce
 //! false positives by applying structural and known invalid pattern checks.
 //!
 //! License: MIT OR APACHE 2.0
 
 use std::borrow::Cow;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256};
 
 /// Helper function to validate SSN based on US Social Security Administration rules.
 ///
@@ -181,4 +184,199 @@ pub fn is_valid_credit_card_programmatically(cc_number: &str) -> bool {
         return false;
     }
     is_valid_luhn(&digits)
-}
\ No newline at end of file
+}
+
+// IBAN lengths by country code, from the SWIFT IBAN registry.
+static IBAN_LENGTHS: Lazy<HashMap<&'static str, usize>> = Lazy::new(|| {
+    HashMap::from([
+        ("AD", 24), ("AE", 23), ("AL", 28), ("AT", 20), ("AZ", 28), ("BA", 20), ("BE", 16),
+        ("BG", 22), ("BH", 22), ("BI", 27), ("BR", 29), ("BY", 28), ("CH", 21), ("CR", 22),
+        ("CY", 28), ("CZ", 24), ("DE", 22), ("DJ", 27), ("DK", 18), ("DO", 28), ("EE", 20),
+        ("EG", 29), ("ES", 24), ("FI", 18), ("FK", 18), ("FO", 18), ("FR", 27), ("GB", 22),
+        ("GE", 22), ("GI", 23), ("GL", 18), ("GR", 27), ("GT", 28), ("HR", 21), ("HU", 28),
+        ("IE", 22), ("IL", 23), ("IQ", 23), ("IS", 26), ("IT", 27), ("JO", 30), ("KW", 30),
+        ("KZ", 20), ("LB", 28), ("LC", 32), ("LI", 21), ("LT", 20), ("LU", 20), ("LV", 21),
+        ("LY", 25), ("MC", 27), ("MD", 24), ("ME", 22), ("MK", 19), ("MN", 20), ("MR", 27),
+        ("MT", 31), ("MU", 30), ("NI", 28), ("NL", 18), ("NO", 15), ("OM", 23), ("PK", 24),
+        ("PL", 28), ("PS", 29), ("PT", 25), ("QA", 29), ("RO", 24), ("RS", 22), ("RU", 33),
+        ("SA", 24), ("SC", 31), ("SD", 18), ("SE", 24), ("SI", 19), ("SK", 24), ("SM", 27),
+        ("SO", 23), ("ST", 25), ("SV", 28), ("TL", 23), ("TN", 24), ("TR", 26), ("UA", 29),
+        ("VA", 22), ("VG", 24), ("XK", 20), ("YE", 30),
+    ])
+});
+
+/// Helper function to validate an International Bank Account Number (IBAN).
+///
+/// Spaces are ignored, so both the electronic and the grouped print format are
+/// accepted. The length must match the registered length for the country code, and
+/// the ISO 7064 mod-97 check (country code and check digits moved to the end,
+/// letters as 10-35) must leave a remainder of 1.
+///
+/// # Arguments
+///
+/// * `iban` - The IBAN string slice to validate, e.g. "DE89 3704 0044 0532 0130 00".
+///
+/// # Returns
+///
+/// `true` if the IBAN has its country's length and a valid checksum, `false` otherwise.
+pub fn is_valid_iban_programmatically(iban: &str) -> bool {
+    let compact: String = iban.chars().filter(|c| *c != ' ').collect::<String>().to_ascii_uppercase();
+    if !compact.is_ascii() || compact.len() < 4 {
+        return false;
+    }
+    let Some(&expected_len) = IBAN_LENGTHS.get(&compact[..2]) else { return false; };
+    if compact.len() != expected_len || !compact[2..4].chars().all(|c| c.is_ascii_digit()) {
+        return false;
+    }
+
+    let rearranged = compact[4..].chars().chain(compact[..4].chars());
+    let mut remainder: u32 = 0;
+    for c in rearranged {
+        let Some(value) = c.to_digit(36) else { return false; };
+        // Letters stand for two digits, digits for one.
+        remainder = if value >= 10 { (remainder * 100 + value) % 97 } else { (remainder * 10 + value) % 97 };
+    }
+    remainder == 1
+}
+
+/// Helper function to validate a US ABA routing transit number.
+///
+/// The number must be nine digits, with a first two digits in one of the ranges the
+/// Federal Reserve assigns (00-12, 21-32, 61-72, or 80), and the digits weighted
+/// 3, 7, 1, 3, 7, 1, 3, 7, 1 must sum to a multiple of 10.
+///
+/// # Arguments
+///
+/// * `routing` - The routing number string slice to validate. Expected format "#########".
+///
+/// # Returns
+///
+/// `true` if the routing number passes the prefix and checksum checks, `false` otherwise.
+pub fn is_valid_aba_routing_programmatically(routing: &str) -> bool {
+    const WEIGHTS: [u32; 9] = [3, 7, 1, 3, 7, 1, 3, 7, 1];
+
+    let digits: Vec<u32> = routing.chars().filter_map(|c| c.to_digit(10)).collect();
+    if digits.len() != 9 || routing.chars().count() != 9 {
+        return false;
+    }
+
+    let prefix = digits[0] * 10 + digits[1];
+    if !matches!(prefix, 0..=12 | 21..=32 | 61..=72 | 80) {
+        return false;
+    }
+
+    digits.iter().zip(WEIGHTS).map(|(d, w)| d * w).sum::<u32>() % 10 == 0
+}
+
+/// The Base58 alphabet Bitcoin uses: no `0`, `O`, `I`, or `l`.
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Decodes a Base58 string, or `None` if it contains a character outside the alphabet.
+fn decode_base58(input: &str) -> Option<Vec<u8>> {
+    // Little-endian base-256 digits of the value read so far.
+    let mut bytes: Vec<u8> = Vec::with_capacity(input.len());
+    for c in input.bytes() {
+        let mut carry = BASE58_ALPHABET.iter().position(|&a| a == c)? as u32;
+        for byte in bytes.iter_mut() {
+            carry += u32::from(*byte) * 58;
+            *byte = (carry & 0xff) as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push((carry & 0xff) as u8);
+            carry >>= 8;
+        }
+    }
+    // Each leading '1' stands for a leading zero byte.
+    let zeros = input.bytes().take_while(|&c| c == b'1').count();
+    bytes.extend(std::iter::repeat_n(0, zeros));
+    bytes.reverse();
+    Some(bytes)
+}
+
+/// Helper function to validate a Bitcoin private key in Wallet Import Format (WIF).
+///
+/// The key must Base58-decode to a version byte (`0x80` for mainnet, `0xEF` for
+/// testnet), the 32-byte key, an optional `0x01` compression flag, and a 4-byte
+/// checksum equal to the start of the payload's double SHA-256.
+///
+/// # Arguments
+///
+/// * `wif` - The WIF string slice to validate, e.g. "5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTJ".
+///
+/// # Returns
+///
+/// `true` if the key has a WIF layout and a valid checksum, `false` otherwise.
+pub fn is_valid_wif_programmatically(wif: &str) -> bool {
+    let Some(decoded) = decode_base58(wif) else { return false; };
+    let (payload, checksum) = match decoded.len() {
+        37 | 38 => decoded.split_at(decoded.len() - 4),
+        _ => return false,
+    };
+    if !matches!(payload[0], 0x80 | 0xEF) || (payload.len() == 34 && payload[33] != 0x01) {
+        return false;
+    }
+    let digest = Sha256::digest(Sha256::digest(payload));
+    digest[..4] == *checksum
+}
+
+/// How many consecutive wordlist words make a probable mnemonic: the shortest
+/// BIP-39 phrase.
+pub const MIN_MNEMONIC_WORDS: usize = 12;
+
+/// The 2048 words of the BIP-39 English wordlist.
+static BIP39_ENGLISH: Lazy<HashSet<&'static str>> = Lazy::new(|| {
+    include_str!("../config/bip39_english.txt").lines().collect()
+});
+
+/// Helper function to recognize a probable BIP-39 mnemonic (seed) phrase.
+///
+/// The checksum word is not verified, since a phrase with a mistyped word is still
+/// a leaked wallet; instead the text must contain at least
+/// [`MIN_MNEMONIC_WORDS`] consecutive words of the English wordlist, so ordinary
+/// prose that happens to be lowercase is left alone.
+///
+/// # Arguments
+///
+/// * `phrase` - The whitespace-separated words to check, in lowercase.
+///
+/// # Returns
+///
+/// `true` if the text holds a run of twelve or more wordlist words, `false` otherwise.
+pub fn is_probable_bip39_mnemonic(phrase: &str) -> bool {
+    let mut run = 0;
+    for word in phrase.split_whitespace() {
+        run = if BIP39_ENGLISH.contains(word) { run + 1 } else { 0 };
+        if run >= MIN_MNEMONIC_WORDS {
+            return true;
+        }
+    }
+    false
+}
+
+/// A programmatic validator as attached to a rule: a name for display and the check.
+#[derive(Debug, Clone, Copy)]
+pub struct Validator {
+    pub name: &'static str,
+    pub check: fn(&str) -> bool,
+}
+
+/// The built-in rules that have a programmatic validator, with the validator each uses.
+const RULE_VALIDATORS: &[(&str, Validator)] = &[
+    ("us_ssn", Validator { name: "ssn", check: is_valid_ssn_programmatically }),
+    ("uk_nino", Validator { name: "uk_nino", check: is_valid_uk_nino_programmatically }),
+    ("iban", Validator { name: "iban_checksum", check: is_valid_iban_programmatically }),
+    ("aba_routing", Validator { name: "aba_checksum", check: is_valid_aba_routing_programmatically }),
+    ("bitcoin_wif_key", Validator { name: "wif_checksum", check: is_valid_wif_programmatically }),
+    ("bip39_mnemonic", Validator { name: "bip39_wordlist", check: is_probable_bip39_mnemonic }),
+    ("visa_card", Validator { name: "luhn", check: is_valid_credit_card_programmatically }),
+    ("mastercard_card", Validator { name: "luhn", check: is_valid_credit_card_programmatically }),
+    ("amex_card", Validator { name: "luhn", check: is_valid_credit_card_programmatically }),
+    ("discover_card", Validator { name: "luhn", check: is_valid_credit_card_programmatically }),
+];
+
+/// The validator run on matches of the rule named `rule_name` when the rule sets
+/// `programmatic_validation`, or `None` if there is none for that name.
+pub fn validator_for_rule(rule_name: &str) -> Option<Validator> {
+    RULE_VALIDATORS.iter().find(|(name, _)| *name == rule_name).map(|(_, validator)| *validator)
+}
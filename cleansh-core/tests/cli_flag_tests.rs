@@ -0,0 +1,76 @@
+// cleansh-core/tests/cli_flag_tests.rs
+use anyhow::Result;
+use cleansh_core::cli_flags::{CliFlagRule, FlagValueForm};
+use cleansh_core::config::RedactionConfig;
+use cleansh_core::engines::regex_engine::CLI_FLAG_CONFIDENCE;
+use cleansh_core::{apply_profile_to_config, ProfileConfig, RegexEngine, SanitizationEngine, SanitizeContext};
+
+fn sanitize(engine: &RegexEngine, input: &str) -> Result<String> {
+    Ok(engine.sanitize_with(input, &mut SanitizeContext::new("history"))?.0)
+}
+
+#[test]
+fn test_glued_mysql_password_is_redacted() -> Result<()> {
+    let engine = RegexEngine::new(RedactionConfig::load_default_rules()?)?;
+    let matches = engine.find_matches_for_ui("mysql -u root -pS3cr3t shop", "history")?;
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].rule_name, "mysql_password_flag");
+    assert_eq!(matches[0].original_string, "S3cr3t");
+    assert_eq!(matches[0].confidence, Some(CLI_FLAG_CONFIDENCE));
+    assert_eq!(sanitize(&engine, "mysql -u root -pS3cr3t shop")?, "mysql -u root -p[CLI_SECRET_REDACTED] shop");
+
+    // -p glued to something that is not a mysql client is left alone.
+    assert!(engine.find_matches_for_ui("find . -print", "history")?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_quoted_header_value_keeps_quotes_and_scheme() -> Result<()> {
+    let engine = RegexEngine::new(RedactionConfig::load_default_rules()?)?;
+    let line = r#"curl -s -H "Authorization: Bearer abc123opaque" https://api.example.net/v1"#;
+    let matches = engine.find_matches_for_ui(line, "history")?;
+    assert_eq!(matches.iter().map(|m| m.rule_name.as_str()).collect::<Vec<_>>(), ["authorization_header_flag"]);
+    assert_eq!(
+        sanitize(&engine, line)?,
+        r#"curl -s -H "Authorization: Bearer [CLI_SECRET_REDACTED]" https://api.example.net/v1"#
+    );
+
+    let line = "deploy --token='tok en' --api-key=k3y";
+    assert_eq!(sanitize(&engine, line)?, "deploy --token='[CLI_SECRET_REDACTED]' --api-key=[CLI_SECRET_REDACTED]");
+    Ok(())
+}
+
+#[test]
+fn test_flags_without_value_do_not_match() -> Result<()> {
+    let engine = RegexEngine::new(RedactionConfig::load_default_rules()?)?;
+    for line in ["mysql -u root -p", "psql --password", "curl -H", "cli --token=", "cli --password --verbose"] {
+        assert!(engine.find_matches_for_ui(line, "history")?.is_empty(), "unexpected match in {line:?}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_profiles_extend_the_flag_table() -> Result<()> {
+    let profile: ProfileConfig = serde_yml::from_str(concat!(
+        "profile_name: ops\nversion: v1\n",
+        "cli_flag_secrets:\n",
+        "  - name: vault_token_flag\n    flag: -t\n    forms: [separate]\n    commands: [vault]\n",
+    ))?;
+    let defaults = RedactionConfig::load_default_rules()?;
+    profile.validate(&defaults)?;
+    let config = apply_profile_to_config(&profile, defaults);
+    assert!(config.cli_flag_secrets.iter().any(|r| r.name == "password_flag"));
+    assert_eq!(config.cli_flag_secrets.last().map(|r| r.forms.clone()), Some(vec![FlagValueForm::Separate]));
+
+    let engine = RegexEngine::new(config)?;
+    assert_eq!(sanitize(&engine, "vault login -t s.abcdef")?, "vault login -t [CLI_SECRET_REDACTED]");
+
+    let invalid = ProfileConfig {
+        profile_name: "bad".to_string(),
+        version: "v1".to_string(),
+        cli_flag_secrets: Some(vec![CliFlagRule { flag: "token".to_string(), ..profile.cli_flag_secrets.unwrap()[0].clone() }]),
+        ..Default::default()
+    };
+    assert!(invalid.validate(&RedactionConfig::load_default_rules()?).is_err());
+    Ok(())
+}
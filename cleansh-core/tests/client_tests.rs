@@ -0,0 +1,102 @@
+// cleansh-core/tests/client_tests.rs
+use anyhow::Result;
+use cleansh_core::{CleanshClient, EngineKind, ProfileKeys};
+use std::fs;
+
+const EMAIL_TEXT: &str = "mail admin@example.com now";
+
+fn write_profile(dir: &tempfile::TempDir, body: &str) -> Result<String> {
+    let path = dir.path().join("client_profile.yaml");
+    fs::write(&path, body)?;
+    Ok(path.display().to_string())
+}
+
+#[test]
+fn test_client_redacts_and_scans_with_default_rules() -> Result<()> {
+    let client = CleanshClient::builder().with_default_rules()?.source_id("doc").build()?;
+    assert_eq!(client.engine_kind(), EngineKind::Regex);
+
+    let report = client.sanitize(EMAIL_TEXT)?;
+    assert!(!report.sanitized.contains("admin@example.com"));
+    let matches = client.scan(EMAIL_TEXT)?;
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].source_id, "doc");
+    assert!(matches[0].sample_hash.is_some());
+    Ok(())
+}
+
+#[test]
+fn test_missing_profile_surfaces_at_build() -> Result<()> {
+    // Naming the profile is accepted; looking it up is build()'s job.
+    let builder = CleanshClient::builder()
+        .with_default_rules()?
+        .with_profile("no_such_client_profile")?
+        .with_profile_keys(ProfileKeys::default());
+    let err = builder.build().err().expect("unknown profile must fail the build");
+    assert!(format!("{:#}", err).contains("no_such_client_profile"), "{:#}", err);
+    Ok(())
+}
+
+#[test]
+fn test_builder_rejects_incomplete_or_invalid_setups() -> Result<()> {
+    let err = CleanshClient::builder().build().err().expect("no rules");
+    assert!(err.to_string().contains("no rules"), "{}", err);
+
+    assert!(CleanshClient::builder().with_profile("  ").is_err());
+
+    let err = CleanshClient::builder().with_default_rules()?.ignore_pattern("([unclosed").build().err().expect("bad pattern");
+    assert!(err.to_string().contains("Invalid ignore pattern"), "{}", err);
+    Ok(())
+}
+
+#[test]
+fn test_profile_is_applied_and_picks_the_engine() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let profile = write_profile(&dir, "profile_name: client_test\nversion: \"1\"\nrules:\n  - name: email\n    enabled: false\nengine:\n  type: hybrid\n")?;
+
+    let client = CleanshClient::builder().with_default_rules()?.with_profile(&profile)?.with_profile_keys(ProfileKeys::default()).build()?;
+    assert_eq!(client.engine_kind(), EngineKind::Hybrid);
+    assert!(client.scan(EMAIL_TEXT)?.iter().all(|m| m.rule_name != "email"));
+
+    // An explicit engine wins over the profile's.
+    let client = CleanshClient::builder()
+        .with_default_rules()?
+        .with_profile(&profile)?
+        .with_profile_keys(ProfileKeys::default())
+        .engine(EngineKind::Regex)
+        .build()?;
+    assert_eq!(client.engine_kind(), EngineKind::Regex);
+
+    // A profile naming a rule the client does not have fails validation at build.
+    let err = CleanshClient::builder()
+        .with_rule_packs(&["network"])?
+        .with_profile(&profile)?
+        .with_profile_keys(ProfileKeys::default())
+        .build()
+        .err()
+        .expect("rule missing from the packs");
+    assert!(err.to_string().contains("email"), "{}", err);
+    Ok(())
+}
+
+#[test]
+fn test_ignore_patterns_leave_matching_values_alone() -> Result<()> {
+    let client = CleanshClient::builder()
+        .with_default_rules()?
+        .ignore_pattern(r"^admin@")
+        .build()?;
+    let report = client.sanitize("admin@example.com bob@example.com")?;
+    assert!(report.sanitized.contains("admin@example.com"));
+    assert!(!report.sanitized.contains("bob@example.com"));
+    assert!(report.matches.iter().all(|m| m.original_string != "admin@example.com"));
+    Ok(())
+}
+
+#[test]
+fn test_engine_kind_parses_profile_engine_types() {
+    for kind in [EngineKind::Regex, EngineKind::Entropy, EngineKind::Hybrid] {
+        assert_eq!(kind.to_string().parse::<EngineKind>().unwrap(), kind);
+    }
+    assert_eq!("HYBRID".parse::<EngineKind>().unwrap(), EngineKind::Hybrid);
+    assert!("fuzzy".parse::<EngineKind>().is_err());
+}
@@ -5,6 +5,7 @@ use std::io::Write;
 
 // Import the specific types and functions needed from the main crate's config module
 use cleansh_core::config::{self, RedactionConfig, RedactionRule};
+use cleansh_core::ConfigFormat;
 
 #[test]
 fn test_load_default_rules() {
@@ -42,6 +43,107 @@ rules:
     Ok(())
 }
 
+#[test]
+fn test_default_rules_combine_every_rule_pack() -> Result<()> {
+    let defaults = RedactionConfig::load_default_rules()?;
+    let mut pack_rules = 0;
+    for name in config::rule_pack_names() {
+        pack_rules += RedactionConfig::load_rule_pack(name)?.rules.len();
+    }
+    assert_eq!(defaults.rules.len(), pack_rules);
+
+    let subset = RedactionConfig::load_rule_packs(&["cloud", "pii"])?;
+    assert!(subset.rules.iter().any(|r| r.name == "aws_access_key"));
+    assert!(subset.rules.iter().any(|r| r.name == "email"));
+    assert!(!subset.rules.iter().any(|r| r.name == "visa_card"));
+    assert!(subset.cli_flag_secrets.is_empty());
+    let reversed = RedactionConfig::load_rule_packs(&["pii", "cloud", "pii"])?;
+    let names = |config: &RedactionConfig| config.rules.iter().map(|r| r.name.clone()).collect::<Vec<_>>();
+    assert_eq!(names(&reversed), names(&subset), "packs combine in their built-in order");
+
+    let err = RedactionConfig::load_rule_packs(&["crypto"]).unwrap_err();
+    assert!(err.to_string().contains("Unknown rule pack 'crypto'"), "{}", err);
+    Ok(())
+}
+
+#[test]
+fn test_relative_includes_are_resolved_from_the_including_file() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    std::fs::create_dir(dir.path().join("team"))?;
+    std::fs::write(dir.path().join("team/shared.yaml"), concat!(
+        "include: [\"extra.yaml\"]\n",
+        "rules:\n  - name: shared_token\n    pattern: 'SHARED-[0-9]{4}'\n    replace_with: '[SHARED]'\n",
+    ))?;
+    std::fs::write(dir.path().join("team/extra.yaml"), concat!(
+        "engines:\n  entropy:\n    threshold: 0.4\n",
+        "rules:\n  - name: extra_token\n    pattern: 'EXTRA-[0-9]{4}'\n    replace_with: '[EXTRA]'\n",
+    ))?;
+    let main = dir.path().join("config.yaml");
+    std::fs::write(&main, concat!(
+        "include: [\"team/shared.yaml\"]\n",
+        "rules:\n  - name: own_token\n    pattern: 'OWN-[0-9]{4}'\n    replace_with: '[OWN]'\n",
+    ))?;
+
+    let config = RedactionConfig::load_from_file(&main)?;
+    let names: Vec<&str> = config.rules.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["extra_token", "shared_token", "own_token"]);
+    assert_eq!(config.engines.entropy.threshold, Some(0.4));
+    assert!(config.include.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_builtin_includes_load_the_named_pack() -> Result<()> {
+    let mut file = NamedTempFile::new()?;
+    file.write_all(b"include: [\"builtin:network\"]\nrules: []\n")?;
+    let config = RedactionConfig::load_from_file(file.path())?;
+    let names: Vec<&str> = config.rules.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["ipv4_address", "ipv6_address"]);
+
+    let mut file = NamedTempFile::new()?;
+    file.write_all(b"include: [\"builtin:crypto\"]\nrules: []\n")?;
+    let err = RedactionConfig::load_from_file(file.path()).unwrap_err();
+    assert!(format!("{:#}", err).contains("Unknown rule pack 'crypto'"), "{:#}", err);
+    Ok(())
+}
+
+#[test]
+fn test_later_includes_and_the_including_file_win_name_clashes() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let token_rule = |replacement: &str| format!(
+        "rules:\n  - name: token\n    pattern: 'TOK-[0-9]{{4}}'\n    replace_with: '{}'\n", replacement
+    );
+    std::fs::write(dir.path().join("first.yaml"), token_rule("[FIRST]") + "  - name: first_only\n    pattern: 'F1'\n")?;
+    std::fs::write(dir.path().join("second.yaml"), token_rule("[SECOND]"))?;
+    let main = dir.path().join("config.yaml");
+
+    std::fs::write(&main, "include: [\"first.yaml\", \"second.yaml\"]\nrules: []\n")?;
+    let config = RedactionConfig::load_from_file(&main)?;
+    let names: Vec<&str> = config.rules.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["token", "first_only"]);
+    assert_eq!(config.rules[0].replace_with, "[SECOND]");
+
+    std::fs::write(&main, "include: [\"first.yaml\", \"second.yaml\"]\n".to_string() + &token_rule("[OWN]"))?;
+    let config = RedactionConfig::load_from_file(&main)?;
+    assert_eq!(config.rules[0].replace_with, "[OWN]");
+    Ok(())
+}
+
+#[test]
+fn test_include_cycles_are_rejected() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    std::fs::write(dir.path().join("a.yaml"), "include: [\"b.yaml\"]\nrules: []\n")?;
+    std::fs::write(dir.path().join("b.yaml"), "include: [\"a.yaml\"]\nrules: []\n")?;
+    let err = RedactionConfig::load_from_file(dir.path().join("a.yaml")).unwrap_err();
+    let message = format!("{:#}", err);
+    assert!(message.contains("Include cycle:"), "{}", message);
+    assert!(message.contains("a.yaml -> "), "{}", message);
+
+    std::fs::write(dir.path().join("self.yaml"), "include: [\"self.yaml\"]\nrules: []\n")?;
+    assert!(RedactionConfig::load_from_file(dir.path().join("self.yaml")).is_err());
+    Ok(())
+}
+
 #[test]
 fn test_load_from_file_programmatic_validation_default() -> Result<()> {
     let yaml_content = r#"
@@ -86,9 +188,16 @@ fn test_merge_rules_no_user_config() {
                 enabled: None,
                 severity: None,
                 tags: None,
+                entropy_corroboration: false,
+                block_start: None,
+                block_end: None,
+                redact_group: None,
             },
         ],
         engines: Default::default(), // Added
+        cli_flag_secrets: Vec::new(),
+        notifications: Default::default(),
+        include: Vec::new(),
     };
     let merged = config::merge_rules(default_config.clone(), None);
     assert_eq!(merged.rules.len(), 1);
@@ -118,6 +227,10 @@ fn test_merge_rules_override() {
                 enabled: None,
                 severity: None,
                 tags: None,
+                entropy_corroboration: false,
+                block_start: None,
+                block_end: None,
+                redact_group: None,
             },
             RedactionRule {
                 name: "ipv4_address".to_string(),
@@ -136,9 +249,16 @@ fn test_merge_rules_override() {
                 enabled: None,
                 severity: None,
                 tags: None,
+                entropy_corroboration: false,
+                block_start: None,
+                block_end: None,
+                redact_group: None,
             },
         ],
         engines: Default::default(), // Added
+        cli_flag_secrets: Vec::new(),
+        notifications: Default::default(),
+        include: Vec::new(),
     };
     let user_config = RedactionConfig {
         rules: vec![
@@ -159,9 +279,16 @@ fn test_merge_rules_override() {
                 enabled: None,
                 severity: Some("medium".to_string()),
                 tags: Some(vec!["user".to_string()]),
+                entropy_corroboration: false,
+                block_start: None,
+                block_end: None,
+                redact_group: None,
             },
         ],
         engines: Default::default(), // Added
+        cli_flag_secrets: Vec::new(),
+        notifications: Default::default(),
+        include: Vec::new(),
     };
     let merged = config::merge_rules(default_config, Some(user_config));
     assert_eq!(merged.rules.len(), 2);
@@ -195,9 +322,16 @@ fn test_merge_rules_add_new() {
                 enabled: None,
                 severity: None,
                 tags: None,
+                entropy_corroboration: false,
+                block_start: None,
+                block_end: None,
+                redact_group: None,
             },
         ],
         engines: Default::default(), // Added
+        cli_flag_secrets: Vec::new(),
+        notifications: Default::default(),
+        include: Vec::new(),
     };
     let user_config = RedactionConfig {
         rules: vec![
@@ -218,9 +352,16 @@ fn test_merge_rules_add_new() {
                 enabled: None,
                 severity: None,
                 tags: None,
+                entropy_corroboration: false,
+                block_start: None,
+                block_end: None,
+                redact_group: None,
             },
         ],
         engines: Default::default(), // Added
+        cli_flag_secrets: Vec::new(),
+        notifications: Default::default(),
+        include: Vec::new(),
     };
     let merged = config::merge_rules(default_config, Some(user_config));
     assert_eq!(merged.rules.len(), 2);
@@ -250,6 +391,10 @@ fn test_merge_rules_with_opt_in() {
                 enabled: None,
                 severity: None,
                 tags: None,
+                entropy_corroboration: false,
+                block_start: None,
+                block_end: None,
+                redact_group: None,
             },
             RedactionRule {
                 name: "default_non_opt_in".to_string(),
@@ -268,9 +413,16 @@ fn test_merge_rules_with_opt_in() {
                 enabled: None,
                 severity: None,
                 tags: None,
+                entropy_corroboration: false,
+                block_start: None,
+                block_end: None,
+                redact_group: None,
             },
         ],
         engines: Default::default(), // Added
+        cli_flag_secrets: Vec::new(),
+        notifications: Default::default(),
+        include: Vec::new(),
     };
     let user_config = RedactionConfig {
         rules: vec![
@@ -291,6 +443,10 @@ fn test_merge_rules_with_opt_in() {
                 enabled: None,
                 severity: None,
                 tags: Some(vec!["user".to_string()]),
+                entropy_corroboration: false,
+                block_start: None,
+                block_end: None,
+                redact_group: None,
             },
             RedactionRule {
                 name: "default_opt_in".to_string(),
@@ -309,9 +465,16 @@ fn test_merge_rules_with_opt_in() {
                 enabled: Some(true),
                 severity: Some("high".to_string()),
                 tags: Some(vec!["user".to_string()]),
+                entropy_corroboration: false,
+                block_start: None,
+                block_end: None,
+                redact_group: None,
             },
         ],
         engines: Default::default(), // Added
+        cli_flag_secrets: Vec::new(),
+        notifications: Default::default(),
+        include: Vec::new(),
     };
     let merged = config::merge_rules(default_config, Some(user_config));
     assert_eq!(merged.rules.len(), 3);
@@ -324,4 +487,305 @@ fn test_merge_rules_with_opt_in() {
 
     assert!(merged.rules.iter().any(|r| r.name == "user_opt_in"));
     assert!(merged.rules.iter().any(|r| r.name == "default_non_opt_in"));
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_default_rules_have_no_replacement_conflicts() -> Result<()> {
+    let config = RedactionConfig::load_default_rules()?;
+    let findings = config.validate_replacements(None, true)?;
+    assert!(findings.is_empty(), "unexpected findings: {:?}", findings);
+    Ok(())
+}
+
+#[test]
+fn test_replacement_matched_by_other_rule_is_flagged() -> Result<()> {
+    let mut config = RedactionConfig::load_default_rules()?;
+    let ipv4 = config.rules.iter_mut().find(|r| r.name == "ipv4_address").unwrap();
+    ipv4.replace_with = "contact admin@corp.example".to_string();
+
+    let findings = config.validate_replacements(None, false)?;
+    assert_eq!(findings.len(), 1, "unexpected findings: {:?}", findings);
+    assert!(findings[0].contains("'ipv4_address'") && findings[0].contains("'email'"));
+
+    // Strict mode turns the same finding into an error.
+    assert!(config.validate_replacements(None, true).is_err());
+
+    // Disabling the matching rule clears the conflict.
+    config.rules.iter_mut().find(|r| r.name == "email").unwrap().enabled = Some(false);
+    assert!(config.validate_replacements(None, true)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_replacement_in_token_namespace_is_flagged() -> Result<()> {
+    let yaml_content = r#"
+rules:
+  - name: api_key
+    pattern: "sk_[a-z0-9]{8}"
+    replace_with: "[api_key:redacted]"
+  - name: session
+    pattern: "sess_[a-z0-9]{8}"
+    replace_with: "[SESSION]"
+"#;
+    let mut file = NamedTempFile::new()?;
+    file.write_all(yaml_content.as_bytes())?;
+    let config = RedactionConfig::load_from_file(file.path())?;
+
+    let findings = config.validate_replacements(None, false)?;
+    assert_eq!(findings.len(), 1, "unexpected findings: {:?}", findings);
+    assert!(findings[0].contains("token namespace '[api_key:'"));
+    Ok(())
+}
+
+#[test]
+fn test_save_entropy_threshold_preserves_rules() -> Result<()> {
+    let yaml_content = r#"
+rules:
+  - name: company_token
+    pattern: "RELAY-[A-Z0-9]{16}"
+    replace_with: "[INTERNAL_TOKEN]"
+engines:
+  entropy:
+    threshold: 0.5
+    window_size: 32
+"#;
+    let mut file = NamedTempFile::new()?;
+    file.write_all(yaml_content.as_bytes())?;
+
+    config::save_entropy_threshold(file.path(), 0.7)?;
+    let reloaded = RedactionConfig::load_from_file(file.path())?;
+    assert_eq!(reloaded.engines.entropy.threshold, Some(0.7));
+    assert_eq!(reloaded.engines.entropy.window_size, Some(32));
+    assert_eq!(reloaded.rules.len(), 1);
+    assert_eq!(reloaded.rules[0].name, "company_token");
+    Ok(())
+}
+
+#[test]
+fn test_save_entropy_threshold_creates_missing_file() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("cleansh").join("config.yaml");
+
+    config::save_entropy_threshold(&path, 1.2)?;
+    let reloaded = RedactionConfig::load_from_file(&path)?;
+    assert_eq!(reloaded.engines.entropy.threshold, Some(1.2));
+    assert!(reloaded.rules.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_load_saved_entropy_threshold_reads_only_the_threshold() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("config.yaml");
+    // Rules that would not load are not looked at.
+    std::fs::write(&path, "rules:\n  - name: broken\nengines:\n  entropy:\n    threshold: 0.9\n")?;
+    assert_eq!(config::load_saved_entropy_threshold(&path)?, Some(0.9));
+
+    std::fs::write(&path, "rules: []\n")?;
+    assert_eq!(config::load_saved_entropy_threshold(&path)?, None);
+    std::fs::write(&path, "engines: {entropy: {threshold: high}}\n")?;
+    assert!(config::load_saved_entropy_threshold(&path).is_err());
+    Ok(())
+}
+
+fn tagged_rule(name: &str, tags: &[&str], opt_in: bool) -> RedactionRule {
+    RedactionRule {
+        name: name.to_string(),
+        pattern: Some(format!("{}_[0-9]+", name)),
+        opt_in,
+        tags: (!tags.is_empty()).then(|| tags.iter().map(|t| t.to_string()).collect()),
+        ..Default::default()
+    }
+}
+
+fn tagged_config() -> RedactionConfig {
+    RedactionConfig {
+        rules: vec![
+            tagged_rule("card", &["pii", "financial"], false),
+            tagged_rule("email", &["PII"], false),
+            tagged_rule("token", &["credentials"], false),
+            tagged_rule("plain", &[], false),
+            tagged_rule("nino", &["pii"], true),
+        ],
+        ..Default::default()
+    }
+}
+
+fn active_names(config: &RedactionConfig) -> Vec<&str> {
+    config.rules.iter().map(|r| r.name.as_str()).collect()
+}
+
+fn tags(list: &[&str]) -> Vec<String> {
+    list.iter().map(|t| t.to_string()).collect()
+}
+
+#[test]
+fn test_include_tags_match_any_tag_of_multi_tag_rules() {
+    let mut config = tagged_config();
+    config.set_active_rules_by_tags(&tags(&["financial", "credentials"]), &[]);
+    assert_eq!(active_names(&config), ["card", "token"]);
+
+    // Tags compare case-insensitively, and an include tag activates opt-in rules.
+    let mut config = tagged_config();
+    config.set_active_rules_by_tags(&tags(&["pii"]), &[]);
+    assert_eq!(active_names(&config), ["card", "email", "nino"]);
+}
+
+#[test]
+fn test_exclude_tag_beats_include_tag_and_enable() {
+    let mut config = tagged_config();
+    config.set_active_rules_by_tags(&tags(&["pii"]), &tags(&["financial"]));
+    assert_eq!(active_names(&config), ["email", "nino"]);
+
+    // Explicit disable > exclude tag > explicit enable > include tag.
+    let mut config = tagged_config();
+    config.rules[0].enabled = Some(true); // card: enabled, but carries an excluded tag
+    config.rules[1].enabled = Some(false); // email: disabled despite its include tag
+    config.rules[3].enabled = Some(true); // plain: enabled without any include tag
+    config.set_active_rules_by_tags(&tags(&["pii"]), &tags(&["financial"]));
+    assert_eq!(active_names(&config), ["plain", "nino"]);
+}
+
+#[test]
+fn test_tag_filters_layer_on_set_active_rules() {
+    let mut config = tagged_config();
+    config.set_active_rules(&tags(&["plain"]), &tags(&["email"]));
+    config.set_active_rules_by_tags(&tags(&["pii"]), &[]);
+    // `set_active_rules` already dropped the opt-in "nino"; the enabled "plain" survives.
+    assert_eq!(active_names(&config), ["card", "plain"]);
+
+    // Without include tags the opt_in default decides.
+    let mut config = tagged_config();
+    config.set_active_rules_by_tags(&[], &tags(&["credentials"]));
+    assert_eq!(active_names(&config), ["card", "email", "plain"]);
+}
+
+#[test]
+fn test_notifications_section_loads_and_merges() -> Result<()> {
+    let yaml_content = r#"
+rules: []
+notifications:
+  webhooks:
+    - https://hooks.example.com/soc
+  attempts: 5
+"#;
+    let mut file = NamedTempFile::new()?;
+    file.write_all(yaml_content.as_bytes())?;
+    let user = RedactionConfig::load_from_file(file.path())?;
+    assert_eq!(user.notifications.webhooks, ["https://hooks.example.com/soc"]);
+    assert_eq!(user.notifications.attempts, Some(5));
+
+    let mut defaults = RedactionConfig::default();
+    defaults.notifications.webhooks.push("https://hooks.example.com/team".to_string());
+    defaults.notifications.host = Some("ci-runner".to_string());
+    let merged = config::merge_rules(defaults, Some(user));
+    assert_eq!(merged.notifications.webhooks, ["https://hooks.example.com/team", "https://hooks.example.com/soc"]);
+    assert_eq!(merged.notifications.attempts, Some(5));
+    assert_eq!(merged.notifications.host.as_deref(), Some("ci-runner"));
+    Ok(())
+}
+
+const RULES_YAML: &str = r#"
+rules:
+  - name: ticket
+    pattern: 'TICKET-\d{4}'
+    replace_with: "[TICKET]"
+    severity: high
+    tags: [internal]
+    programmatic_validation: false
+"#;
+
+const RULES_TOML: &str = r#"
+# Same rule set as RULES_YAML.
+[[rules]]
+name = "ticket"
+pattern = 'TICKET-\d{4}'
+replace_with = "[TICKET]"
+severity = "high"
+tags = ["internal"]
+programmatic_validation = false
+"#;
+
+const RULES_JSON: &str = r#"{
+  "rules": [
+    {
+      "name": "ticket",
+      "pattern": "TICKET-\\d{4}",
+      "replace_with": "[TICKET]",
+      "severity": "high",
+      "tags": ["internal"],
+      "programmatic_validation": false
+    }
+  ]
+}"#;
+
+#[test]
+fn test_yaml_toml_and_json_configs_load_identically() -> Result<()> {
+    use cleansh_core::sanitizers::compiler::get_or_compile_rules;
+    let dir = tempfile::tempdir()?;
+    let mut loaded = Vec::new();
+    for (name, text) in [("rules.yaml", RULES_YAML), ("rules.toml", RULES_TOML), ("rules.json", RULES_JSON)] {
+        let path = dir.path().join(name);
+        std::fs::write(&path, text)?;
+        loaded.push(RedactionConfig::load_from_file(&path)?);
+    }
+    assert_eq!(loaded[0], loaded[1]);
+    assert_eq!(loaded[0], loaded[2]);
+    assert_eq!(loaded[0].rules[0].pattern.as_deref(), Some(r"TICKET-\d{4}"));
+
+    // Equal configs share one compiled rule set.
+    let compiled = get_or_compile_rules(&loaded[0])?;
+    assert!(std::sync::Arc::ptr_eq(&compiled, &get_or_compile_rules(&loaded[1])?));
+    assert!(std::sync::Arc::ptr_eq(&compiled, &get_or_compile_rules(&loaded[2])?));
+    Ok(())
+}
+
+#[test]
+fn test_extensionless_config_uses_the_given_format() -> Result<()> {
+    let mut file = NamedTempFile::new()?;
+    file.write_all(RULES_TOML.as_bytes())?;
+    let config = RedactionConfig::load_from_file_as(file.path(), Some(ConfigFormat::Toml))?;
+    assert_eq!(config.rules[0].name, "ticket");
+
+    // An extension that names a format wins over the fallback.
+    let dir = tempfile::tempdir()?;
+    let json = dir.path().join("rules.json");
+    std::fs::write(&json, RULES_JSON)?;
+    assert_eq!(RedactionConfig::load_from_file_as(&json, Some(ConfigFormat::Toml))?.rules.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_config_parse_errors_name_the_parser() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    for (name, text, parser) in [
+        ("bad.toml", "[[rules]\nname = \"x\"\n", "TOML parser"),
+        ("bad.json", "{\"rules\": [", "JSON parser"),
+        ("bad.yaml", "rules: [\n", "YAML parser"),
+    ] {
+        let path = dir.path().join(name);
+        std::fs::write(&path, text)?;
+        let err = format!("{:#}", RedactionConfig::load_from_file(&path).unwrap_err());
+        assert!(err.contains(parser), "{}", err);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_includes_may_mix_formats() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    std::fs::write(dir.path().join("extra.toml"), RULES_TOML)?;
+    let main = dir.path().join("main.json");
+    std::fs::write(&main, r#"{"include": ["extra.toml"], "rules": []}"#)?;
+    let config = RedactionConfig::load_from_file(&main)?;
+    assert_eq!(config.rules.iter().map(|r| r.name.as_str()).collect::<Vec<_>>(), ["ticket"]);
+    Ok(())
+}
+
+#[test]
+fn test_config_format_parses_flag_values() {
+    assert_eq!("TOML".parse::<ConfigFormat>(), Ok(ConfigFormat::Toml));
+    assert_eq!("yml".parse::<ConfigFormat>(), Ok(ConfigFormat::Yaml));
+    assert!("ini".parse::<ConfigFormat>().unwrap_err().contains("yaml, toml, json"));
+    assert_eq!(ConfigFormat::detect(std::path::Path::new("rules"), None), ConfigFormat::Yaml);
+}
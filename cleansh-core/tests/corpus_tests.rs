@@ -0,0 +1,73 @@
+// cleansh-core/tests/corpus_tests.rs
+//! Runs the shipped snapshot corpora (`tests/corpus/<engine>/`) and checks the
+//! harness's diff reporting.
+use anyhow::Result;
+use std::path::Path;
+
+use cleansh_core::config::RedactionConfig;
+use cleansh_core::corpus::{diff_lines, run_corpus, DiffKind};
+use cleansh_core::{EntropyEngine, RegexEngine};
+
+fn corpus_dir(engine: &str) -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("corpus").join(engine)
+}
+
+#[test]
+fn test_regex_corpus() -> Result<()> {
+    let engine = RegexEngine::new(RedactionConfig::load_default_rules()?)?;
+    let report = run_corpus(&corpus_dir("regex"), &engine);
+    assert!(report.cases.len() >= 20, "{}", report);
+    assert!(report.passed(), "{}", report);
+    Ok(())
+}
+
+#[test]
+fn test_entropy_corpus() -> Result<()> {
+    let engine = EntropyEngine::new(RedactionConfig::load_default_rules()?)?;
+    let report = run_corpus(&corpus_dir("entropy"), &engine);
+    assert!(!report.cases.is_empty(), "{}", report);
+    assert!(report.passed(), "{}", report);
+    Ok(())
+}
+
+#[test]
+fn test_corpus_report_separates_missed_and_over_redactions() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    std::fs::write(dir.path().join("missed.input"), "mail bob@example.com\n")?;
+    std::fs::write(dir.path().join("missed.expected"), "mail [EMAIL_REDACTED]\n")?;
+    std::fs::write(dir.path().join("over.input"), "contact: jane.doe@example.com\n")?;
+    std::fs::write(dir.path().join("over.expected"), "contact: jane.doe@example.com\n")?;
+    std::fs::write(dir.path().join("orphan.input"), "no expected file\n")?;
+
+    // Without the email rule the first case leaks and the second, which expects
+    // the address to be kept, passes; with the default rules it is the reverse.
+    let mut config = RedactionConfig::load_default_rules()?;
+    config.set_active_rules(&[], &["email".to_string()]);
+    let report = run_corpus(dir.path(), &RegexEngine::new(config)?);
+    let names: Vec<(&str, bool)> = report.cases.iter().map(|c| (c.name.as_str(), c.passed())).collect();
+    assert_eq!(names, vec![("missed", false), ("orphan", false), ("over", true)]);
+    assert_eq!(report.count(DiffKind::MissedRedaction), 1);
+    assert_eq!(report.cases[0].diffs[0].actual, "mail bob@example.com");
+    assert_eq!(report.cases[1].error.as_deref(), Some("no .expected file"));
+
+    let report = run_corpus(dir.path(), &RegexEngine::new(RedactionConfig::load_default_rules()?)?);
+    assert_eq!(report.count(DiffKind::MissedRedaction), 0);
+    assert_eq!(report.count(DiffKind::OverRedaction), 1);
+    let text = report.to_string();
+    assert!(text.contains("FAIL over\n  line 1: over-redaction"), "{}", text);
+    assert!(text.ends_with("3 cases: 1 passed, 2 failed (0 missed, 1 over-redacted, 0 changed, 1 errors)"), "{}", text);
+    Ok(())
+}
+
+#[test]
+fn test_diff_lines_classifies_by_placeholder_count() {
+    let diffs = diff_lines("a [EMAIL_REDACTED]\nb\nc [x:1a2b]\n", "a bob@example.com\nb [IPV4_REDACTED]\nc [y:3c4d]\nextra\n");
+    let kinds: Vec<(usize, DiffKind)> = diffs.iter().map(|d| (d.line, d.kind)).collect();
+    assert_eq!(kinds, vec![
+        (1, DiffKind::MissedRedaction),
+        (2, DiffKind::OverRedaction),
+        (3, DiffKind::Changed),
+        (4, DiffKind::Changed),
+    ]);
+    assert_eq!(diffs[3].expected, "");
+}
@@ -0,0 +1,23 @@
+// cleansh-core/tests/crash_report_opt_in_tests.rs
+//! Lines are only kept for crash reports once the hook is installed. In a file of
+//! its own, since the hook and the kept lines are process-wide.
+use anyhow::Result;
+use cleansh_core::crash_report::{install_safe_panic_hook_in, record_sanitized_line};
+
+#[test]
+fn test_lines_recorded_before_the_hook_is_installed_are_not_kept() -> Result<()> {
+    record_sanitized_line("before=[EMAIL_REDACTED]");
+
+    let dir = tempfile::tempdir()?;
+    install_safe_panic_hook_in(dir.path());
+    record_sanitized_line("after=[EMAIL_REDACTED]");
+    assert!(std::thread::spawn(|| panic!("deliberate")).join().is_err());
+    let _ = std::panic::take_hook();
+
+    let reports: Vec<_> = std::fs::read_dir(dir.path())?.collect::<Result<_, _>>()?;
+    assert_eq!(reports.len(), 1);
+    let report = std::fs::read_to_string(reports[0].path())?;
+    assert!(report.contains("last 1 sanitized lines:\nafter=[EMAIL_REDACTED]\n"), "{}", report);
+    assert!(!report.contains("before="), "{}", report);
+    Ok(())
+}
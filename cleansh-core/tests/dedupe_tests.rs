@@ -0,0 +1,110 @@
+// cleansh-core/tests/dedupe_tests.rs
+use anyhow::Result;
+use cleansh_core::config::{RedactionConfig, RedactionRule};
+use cleansh_core::engine::{SanitizationEngine, SanitizeContext};
+use cleansh_core::engines::regex_engine::RegexEngine;
+use cleansh_core::profiles::{DedupeConfig, EngineOptions};
+use tokio::sync::mpsc;
+
+fn api_key_config() -> RedactionConfig {
+    RedactionConfig {
+        rules: vec![RedactionRule {
+            name: "api_key".to_string(),
+            pattern: Some(r"sk_[a-z0-9]{8}".to_string()),
+            pattern_type: "regex".to_string(),
+            replace_with: "[API_KEY]".to_string(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+fn dedupe_options(window_bytes: usize) -> EngineOptions {
+    EngineOptions {
+        dedupe_config: Some(DedupeConfig { window_bytes, use_hash: true }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_repeated_token_on_one_line_is_deduplicated() -> Result<()> {
+    let (tx, mut rx) = mpsc::channel(16);
+    let engine = RegexEngine::with_options(api_key_config(), dedupe_options(0))?.with_remediation_tx(tx);
+
+    let input = "sk_aaaa1111 sk_aaaa1111 sk_aaaa1111";
+    let (sanitized, summary) = engine.sanitize_with(input, &mut SanitizeContext::new("test"))?;
+
+    // Every occurrence is still redacted in the output.
+    assert_eq!(sanitized, "[API_KEY] [API_KEY] [API_KEY]");
+
+    // The summary keeps the real count and records how many were suppressed.
+    assert_eq!(summary.len(), 1);
+    assert_eq!(summary[0].occurrences, 3);
+    assert_eq!(summary[0].deduplicated, 2);
+
+    // The remediation tee fires once per unique secret.
+    assert!(rx.try_recv().is_ok());
+    assert!(rx.try_recv().is_err());
+
+    // The UI only sees the first sighting.
+    assert_eq!(engine.find_matches_for_ui(input, "test")?.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_same_token_in_different_contexts_with_window() -> Result<()> {
+    let input = "alpha=sk_aaaa1111\nomega=sk_aaaa1111";
+
+    let windowed = RegexEngine::with_options(api_key_config(), dedupe_options(6))?;
+    let summary = windowed.analyze_for_stats(input, "test")?;
+    assert_eq!(summary[0].occurrences, 2);
+    assert_eq!(summary[0].deduplicated, 0);
+    assert_eq!(windowed.find_matches_for_ui(input, "test")?.len(), 2);
+
+    // Without a window the context is ignored and the second sighting is a repeat.
+    let hashed = RegexEngine::with_options(api_key_config(), dedupe_options(0))?;
+    let summary = hashed.analyze_for_stats(input, "test")?;
+    assert_eq!(summary[0].occurrences, 2);
+    assert_eq!(summary[0].deduplicated, 1);
+    Ok(())
+}
+
+#[test]
+fn test_dedupe_disabled_without_use_hash() -> Result<()> {
+    let options = EngineOptions {
+        dedupe_config: Some(DedupeConfig { window_bytes: 0, use_hash: false }),
+        ..Default::default()
+    };
+    let engine = RegexEngine::with_options(api_key_config(), options)?;
+    let summary = engine.analyze_for_stats("sk_aaaa1111 sk_aaaa1111", "test")?;
+    assert_eq!(summary[0].occurrences, 2);
+    assert_eq!(summary[0].deduplicated, 0);
+    Ok(())
+}
+
+#[test]
+fn test_rules_matching_at_the_same_offset_resolve_in_declaration_order() -> Result<()> {
+    let rule = |name: &str, pattern: &str| RedactionRule {
+        name: name.to_string(),
+        pattern: Some(pattern.to_string()),
+        pattern_type: "regex".to_string(),
+        replace_with: format!("[{}]", name.to_uppercase()),
+        ..Default::default()
+    };
+    for (first, second) in [("long_token", "short_token"), ("short_token", "long_token")] {
+        let pattern = |name: &str| if name == "long_token" { r"tok_[a-z0-9]{12}" } else { r"tok_[a-z0-9]{4}" };
+        let config = RedactionConfig {
+            rules: vec![rule(first, pattern(first)), rule(second, pattern(second))],
+            ..Default::default()
+        };
+        let engine = RegexEngine::new(config)?;
+        let input = "key=tok_abcd1234efgh";
+
+        let (sanitized, _) = engine.sanitize_with(input, &mut SanitizeContext::new("test"))?;
+        assert!(sanitized.starts_with(&format!("key=[{}]", first.to_uppercase())), "{}: {}", first, sanitized);
+
+        let names: Vec<String> = engine.find_matches_for_ui(input, "test")?.into_iter().map(|m| m.rule_name).collect();
+        assert_eq!(names, [first, second]);
+    }
+    Ok(())
+}
@@ -0,0 +1,95 @@
+// cleansh-core/tests/explain_tests.rs
+use anyhow::Result;
+use std::sync::Arc;
+
+use cleansh_core::config::RedactionConfig;
+use cleansh_core::engine::SanitizationEngine;
+use cleansh_core::explain::{Decision, ExplainRecord, ExplainStage, JsonlExplainSink};
+use cleansh_core::{EngineOptions, EntropyEngine, RegexEngine};
+
+const SECRET_LINE: &str = "session=Zk9qW2xR7vBn4TmP1sLc8yHdQe3u";
+
+fn entropy_engine(threshold: f64, options: EngineOptions) -> Result<EntropyEngine> {
+    let mut config = RedactionConfig::default();
+    config.engines.entropy.threshold = Some(threshold);
+    EntropyEngine::with_options(config, options)
+}
+
+#[test]
+fn test_rejected_candidate_records_the_threshold_comparison() -> Result<()> {
+    let engine = entropy_engine(50.0, EngineOptions::default().with_explain(true))?;
+    assert!(engine.find_matches_for_ui(SECRET_LINE, "doc")?.is_empty());
+
+    let records = engine.get_options().take_explain_records();
+    let rejected: Vec<&ExplainRecord> = records.iter()
+        .filter(|r| matches!(r.decision, Decision::EntropyCandidate { accepted: false, .. }))
+        .collect();
+    assert!(!rejected.is_empty(), "{:?}", records);
+    for record in &rejected {
+        let Decision::EntropyCandidate { confidence, threshold, .. } = record.decision else { unreachable!() };
+        assert!(confidence < threshold);
+        assert_eq!(threshold, 50.0);
+        assert_eq!(record.source_id, "doc");
+        assert!(record.to_string().contains("< threshold 50.00 -> rejected"), "{}", record);
+    }
+    assert!(records.iter().any(|r| matches!(r.decision, Decision::EntropyCandidate { stage: ExplainStage::TokenShape, .. })));
+    assert!(!records.iter().any(|r| matches!(r.decision, Decision::Reported { .. })));
+    // Taking the records clears them.
+    assert!(engine.get_options().take_explain_records().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_accepted_candidate_is_recorded_as_reported() -> Result<()> {
+    let engine = entropy_engine(0.5, EngineOptions::default().with_explain(true))?;
+    let matches = engine.find_matches_for_ui(SECRET_LINE, "doc")?;
+    assert_eq!(matches.len(), 1);
+
+    let records = engine.get_options().take_explain_records();
+    assert!(records.iter().any(|r| matches!(r.decision, Decision::EntropyCandidate { accepted: true, .. })));
+    let reported = records.iter().find(|r| matches!(r.decision, Decision::Reported { .. })).expect("reported record");
+    assert_eq!(&SECRET_LINE[reported.start..reported.end], "Zk9qW2xR7vBn4TmP1sLc8yHdQe3u");
+    Ok(())
+}
+
+#[test]
+fn test_explain_is_off_by_default() -> Result<()> {
+    let engine = entropy_engine(50.0, EngineOptions::default())?;
+    engine.find_matches_for_ui(SECRET_LINE, "doc")?;
+    assert!(engine.get_options().take_explain_records().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_regex_engine_records_validator_outcomes() -> Result<()> {
+    let config = RedactionConfig::load_default_rules()?;
+    let engine = RegexEngine::with_options(config, EngineOptions::default().with_explain(true))?;
+    let line = "cards 4111111111111111 and 4111111111111112";
+    engine.find_matches_for_ui(line, "doc")?;
+
+    let outcomes: Vec<(&str, bool)> = engine.get_options().take_explain_records().iter()
+        .filter_map(|r| match &r.decision {
+            Decision::Validator { rule_name, passed } if rule_name == "visa_card" => Some((&line[r.start..r.end], *passed)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(outcomes, vec![("4111111111111111", true), ("4111111111111112", false)]);
+    Ok(())
+}
+
+#[test]
+fn test_jsonl_sink_streams_records() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("explain.jsonl");
+    let options = EngineOptions::default().with_explain_sink(Arc::new(JsonlExplainSink::create(&path)?));
+    let engine = entropy_engine(50.0, options)?;
+    engine.find_matches_for_ui(SECRET_LINE, "doc")?;
+
+    // Streamed records are not kept in memory.
+    assert!(engine.get_options().take_explain_records().is_empty());
+    let text = std::fs::read_to_string(&path)?;
+    let records: Vec<ExplainRecord> = text.lines().map(serde_json::from_str).collect::<Result<_, _>>()?;
+    assert!(!records.is_empty());
+    assert!(text.contains(r#""decision":"entropy_candidate""#), "{}", text);
+    Ok(())
+}
@@ -0,0 +1,213 @@
+// cleansh-core/tests/fingerprint_cache_tests.rs
+use anyhow::Result;
+use cleansh_core::config::{RedactionConfig, RedactionRule};
+use cleansh_core::remediation::fingerprint::{salted_hash, SecretFingerprint};
+use cleansh_core::remediation::fingerprint_cache::{Fingerprint, FingerprintCache};
+use cleansh_core::remediation::sync::PushSource;
+use cleansh_core::remediation::vault::{file::FileVault, FingerprintStream, FingerprintVault};
+use cleansh_core::{EngineHandle, RegexEngine, SanitizationEngine, SanitizeContext, SelfHealingEngine};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+const SALT: &[u8] = b"org-salt";
+
+fn fingerprint(i: usize) -> Fingerprint {
+    salted_hash(&format!("secret-{i}"), SALT)
+}
+
+#[test]
+fn test_hits_and_misses_after_write() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("fingerprints.cache");
+    // Duplicates and unsorted input are normalized on write.
+    let written = FingerprintCache::write(&path, (0..500).rev().chain(0..10).map(fingerprint))?;
+    assert_eq!(written, 500);
+
+    let cache = FingerprintCache::open(&path)?;
+    assert_eq!(cache.len(), 500);
+    assert!((0..500).all(|i| cache.contains(&fingerprint(i))));
+    assert!((500..1500).all(|i| !cache.contains(&fingerprint(i))));
+
+    let empty = dir.path().join("empty.cache");
+    FingerprintCache::write(&empty, std::iter::empty())?;
+    assert!(!FingerprintCache::open(&empty)?.contains(&fingerprint(0)));
+    Ok(())
+}
+
+#[test]
+fn test_bloom_false_positives_fall_through_to_binary_search() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("fingerprints.cache");
+    FingerprintCache::write(&path, (0..2000).map(fingerprint))?;
+
+    // One bit per key saturates the filter, so most misses pass it.
+    let cache = FingerprintCache::open_with_bloom(&path, 1)?;
+    let false_positives: Vec<Fingerprint> = (2000..4000).map(fingerprint).filter(|fp| cache.bloom_may_contain(fp)).collect();
+    assert!(!false_positives.is_empty());
+    assert!(false_positives.iter().all(|fp| !cache.contains(fp)));
+    assert!((0..2000).all(|i| cache.contains(&fingerprint(i))));
+    Ok(())
+}
+
+#[test]
+fn test_rewrite_leaves_existing_mappings_intact() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("fingerprints.cache");
+    FingerprintCache::write(&path, (0..100).map(fingerprint))?;
+    let old = FingerprintCache::open(&path)?;
+
+    FingerprintCache::write(&path, (100..300).map(fingerprint))?;
+    let new = FingerprintCache::open(&path)?;
+    assert!(old.contains(&fingerprint(5)) && !old.contains(&fingerprint(150)));
+    assert!(!new.contains(&fingerprint(5)) && new.contains(&fingerprint(150)));
+    // No temporary files are left behind.
+    assert_eq!(std::fs::read_dir(dir.path())?.count(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_corrupt_files_are_rejected() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("fingerprints.cache");
+    FingerprintCache::write(&path, (0..4).map(fingerprint))?;
+    let bytes = std::fs::read(&path)?;
+
+    std::fs::write(&path, &bytes[..bytes.len() - 1])?;
+    let err = FingerprintCache::open(&path).unwrap_err();
+    assert!(format!("{err:#}").contains("header declares 4 fingerprints"), "{err:#}");
+
+    let mut unsorted = bytes.clone();
+    unsorted[24..56].copy_from_slice(&bytes[56..88]);
+    std::fs::write(&path, &unsorted)?;
+    let err = FingerprintCache::open(&path).unwrap_err();
+    assert!(format!("{err:#}").contains("not strictly ascending"), "{err:#}");
+
+    std::fs::write(&path, b"{\"not\": \"a cache\"} padding padding")?;
+    let err = FingerprintCache::open(&path).unwrap_err();
+    assert!(format!("{err:#}").contains("not a cleansh fingerprint cache"), "{err:#}");
+    Ok(())
+}
+
+fn wired_engine(tx: mpsc::Sender<cleansh_core::RedactionMatch>) -> Result<RegexEngine> {
+    let config = RedactionConfig {
+        rules: vec![RedactionRule {
+            name: "api_key".to_string(),
+            pattern: Some(r"sk_[a-z0-9]{8}".to_string()),
+            replace_with: "[KEY]".to_string(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    Ok(RegexEngine::new(config)?.with_remediation_tx(tx).with_fingerprint_salt(SALT.to_vec()))
+}
+
+#[test]
+fn test_known_leaks_are_redacted_but_not_sent_for_remediation() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("fingerprints.cache");
+    FingerprintCache::write(&path, [salted_hash("sk_aaaa1111", SALT)])?;
+
+    let (tx, mut rx) = mpsc::channel(8);
+    let engine = wired_engine(tx)?;
+    engine.set_fingerprint_cache(Arc::new(FingerprintCache::open(&path)?));
+
+    let (output, _) = engine.sanitize_with("a=sk_aaaa1111 b=sk_bbbb2222", &mut SanitizeContext::new("test"))?;
+    assert_eq!(output, "a=[KEY] b=[KEY]");
+    assert_eq!(rx.try_recv()?.original_string, "sk_bbbb2222");
+    assert!(rx.try_recv().is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_loop_writes_cache_and_swaps_it_into_the_engine() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let vault = Arc::new(FileVault::new(dir.path().join("vault.json")));
+    vault.publish(SecretFingerprint::from_secret("sk_aaaa1111", "stripe", SALT)).await?;
+    let cache_path = dir.path().join("fingerprints.cache");
+
+    let (tx, mut rx) = mpsc::channel(8);
+    let handle = EngineHandle::from_engine(wired_engine(tx)?);
+    let orchestrator = SelfHealingEngine::new(vec![], Some(vault), 5, false, SALT.to_vec(), false)
+        .with_fingerprint_cache(cache_path.clone());
+    orchestrator.start_sync_loop(handle.clone()).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    assert_eq!(FingerprintCache::open(&cache_path)?.len(), 1);
+    handle.with(|e| e.find_matches_for_ui("sk_aaaa1111", "test"))?;
+    assert!(rx.try_recv().is_err(), "known leak was sent for remediation");
+    Ok(())
+}
+
+/// A file vault that also pushes each published fingerprint to its subscribers.
+struct PushVault {
+    file: FileVault,
+    subscribers: std::sync::Mutex<Vec<mpsc::Sender<SecretFingerprint>>>,
+    subscriptions: std::sync::atomic::AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl FingerprintVault for PushVault {
+    async fn publish(&self, fingerprint: SecretFingerprint) -> Result<()> {
+        self.file.publish(fingerprint.clone()).await?;
+        let subscribers = self.subscribers.lock().unwrap().clone();
+        for tx in subscribers {
+            let _ = tx.send(fingerprint.clone()).await;
+        }
+        Ok(())
+    }
+
+    async fn fetch_all(&self) -> Result<Vec<SecretFingerprint>> {
+        self.file.fetch_all().await
+    }
+
+    async fn subscribe(&self) -> Result<Option<FingerprintStream>> {
+        let (tx, rx) = mpsc::channel(8);
+        self.subscribers.lock().unwrap().push(tx);
+        self.subscriptions.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Ok(Some(rx))
+    }
+}
+
+#[tokio::test]
+async fn test_pushed_fingerprints_reach_the_engine_before_the_next_snapshot() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let vault = Arc::new(PushVault {
+        file: FileVault::new(dir.path().join("vault.json")),
+        subscribers: Default::default(),
+        subscriptions: Default::default(),
+    });
+
+    let (tx, mut rx) = mpsc::channel(8);
+    let handle = EngineHandle::from_engine(wired_engine(tx)?);
+    let orchestrator = SelfHealingEngine::new(vec![], Some(vault.clone()), 5, false, SALT.to_vec(), false)
+        .with_sync_interval(Duration::from_secs(3600));
+    orchestrator.start_sync_loop(handle.clone()).await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    vault.publish(SecretFingerprint::from_secret("sk_aaaa1111", "stripe", SALT)).await?;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    handle.with(|e| e.find_matches_for_ui("sk_aaaa1111 sk_bbbb2222", "test"))?;
+    assert_eq!(rx.try_recv()?.original_string, "sk_bbbb2222");
+    assert!(rx.try_recv().is_err(), "pushed leak was sent for remediation");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ended_subscription_is_reopened_on_the_next_snapshot() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let vault = Arc::new(PushVault {
+        file: FileVault::new(dir.path().join("vault.json")),
+        subscribers: Default::default(),
+        subscriptions: Default::default(),
+    });
+    let source = PushSource::subscribe(vault.clone(), Duration::from_millis(50)).await?;
+    let (tx, _rx) = mpsc::channel(8);
+    let orchestrator = SelfHealingEngine::new(vec![], None, 5, false, SALT.to_vec(), false);
+    orchestrator.start_sync_loop_from(EngineHandle::from_engine(wired_engine(tx)?), Box::new(source));
+
+    vault.subscribers.lock().unwrap().clear();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(vault.subscriptions.load(std::sync::atomic::Ordering::SeqCst), 2);
+    Ok(())
+}
@@ -0,0 +1,109 @@
+// cleansh-core/tests/ignore_file_tests.rs
+use anyhow::Result;
+use chrono::NaiveDate;
+use cleansh_core::config::RedactionConfig;
+use cleansh_core::ignore_file::{ignore_hash, IgnoreEntry, IgnoreFile};
+use cleansh_core::{EngineOptions, RegexEngine, SanitizationEngine};
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+#[test]
+fn test_expired_entries_are_not_active() -> Result<()> {
+    let (sandbox, permanent, stale) = (ignore_hash("sandbox"), ignore_hash("permanent"), ignore_hash("stale"));
+    let text = format!(
+        "# fixtures\n{} expires=2025-12-31 reason=\"sandbox key\"\n{}\n\n{} expires=2025-06-30 reason=\"old \\\"demo\\\" token\"\n",
+        sandbox, permanent, stale,
+    );
+    let file = IgnoreFile::parse(&text)?;
+    assert_eq!(file.entries().count(), 3);
+
+    // An entry still applies on its expiry date.
+    let (active, expired) = file.active_hashes(date("2025-12-31"));
+    assert!(active.contains(&sandbox) && active.contains(&permanent) && !active.contains(&stale));
+    assert_eq!(expired.len(), 1);
+    assert_eq!(expired[0].reason.as_deref(), Some("old \"demo\" token"));
+
+    let (active, expired) = file.active_hashes(date("2026-01-01"));
+    assert_eq!(active.len(), 1);
+    assert_eq!(expired.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_malformed_lines_are_rejected_with_line_numbers() {
+    let valid = ignore_hash("x");
+    for (text, expected) in [
+        ("# ok\nnot-a-hash\n".to_string(), "line 2"),
+        (format!("{} expires=31/12/2025\n", valid), "invalid expiry date"),
+        (format!("{} owner=me\n", valid), "unknown attribute 'owner'"),
+        (format!("{} reason=\"unterminated\n", valid), "unterminated quote"),
+    ] {
+        let err = format!("{:#}", IgnoreFile::parse(&text).unwrap_err());
+        assert!(err.contains(expected), "{}", err);
+    }
+}
+
+#[test]
+fn test_prune_removes_only_expired_lines_and_keeps_comments() -> Result<()> {
+    let (keep, drop) = (ignore_hash("keep"), ignore_hash("drop"));
+    let text = format!("# Managed by the platform team\n\n{} reason=\"ci fixture\"\n# expired below\n{} expires=2024-01-01\n", keep, drop);
+    let mut file = IgnoreFile::parse(&text)?;
+
+    let removed = file.prune(date("2025-01-01"));
+    assert_eq!(removed, vec![IgnoreEntry { hash: drop, expires: Some(date("2024-01-01")), reason: None }]);
+    assert_eq!(
+        file.to_string(),
+        format!("# Managed by the platform team\n\n{} reason=\"ci fixture\"\n# expired below\n", keep),
+    );
+    assert!(file.prune(date("2025-01-01")).is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_engine_skips_ignored_values() -> Result<()> {
+    let config = RedactionConfig::load_default_rules()?;
+    let input = "contact jane.doe@example.com or ops@example.com";
+    let options = EngineOptions::default().with_ignored_hashes([ignore_hash("ops@example.com")].into());
+    let engine = RegexEngine::with_options(config, options)?;
+    let matches = engine.find_matches_for_ui(input, "test")?;
+    let found: Vec<&str> = matches.iter().map(|m| m.original_string.as_str()).collect();
+    assert_eq!(found, ["jane.doe@example.com"]);
+    Ok(())
+}
+
+#[test]
+fn test_ignore_store_silences_a_secret_until_the_hash_is_removed() -> Result<()> {
+    use cleansh_core::engine::SanitizeContext;
+    use cleansh_core::ignore_store::IgnoreStore;
+    use cleansh_core::redaction_match::canonical_sample_hash;
+    use cleansh_core::EntropyEngine;
+
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("cleansh").join("ignored_hashes.json");
+    let store = IgnoreStore::load(&path)?;
+    let config = RedactionConfig::load_default_rules()?;
+    let regex = RegexEngine::with_options(config.clone(), EngineOptions::default().with_ignore_store(store.clone()))?;
+    let entropy = EntropyEngine::with_options(config, EngineOptions::default().with_ignore_store(store.clone()))?;
+
+    let email = "mail ops@example.com";
+    let token = "Deployed the release build from commit 9fceb02d0ae598e95dc970b74767f19372d61af8 to staging today.";
+    assert_eq!(regex.find_matches_for_ui(email, "test")?.len(), 1);
+    let entropy_match = entropy.find_matches_for_ui(token, "test")?.remove(0);
+
+    // Hashes added after the engines were built apply to their next scan.
+    assert!(store.insert(&canonical_sample_hash("email", "ops@example.com"))?);
+    assert!(store.insert(&canonical_sample_hash(&entropy_match.rule_name, &entropy_match.original_string))?);
+    let (sanitized, summary) = regex.sanitize_with(email, &mut SanitizeContext::new("test"))?;
+    assert_eq!((sanitized.as_str(), summary.len()), (email, 0));
+    assert!(entropy.find_matches_for_ui(token, "test")?.is_empty());
+
+    // The store is persisted, and removing a hash restores detection.
+    let reloaded = IgnoreStore::load(&path)?;
+    assert_eq!(reloaded.hashes(), store.hashes());
+    assert!(store.remove(&canonical_sample_hash("email", "ops@example.com"))?);
+    assert_eq!(regex.find_matches_for_ui(email, "test")?.len(), 1);
+    assert_eq!(IgnoreStore::load(&path)?.hashes().len(), 1);
+    Ok(())
+}
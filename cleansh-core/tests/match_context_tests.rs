@@ -0,0 +1,106 @@
+// cleansh-core/tests/match_context_tests.rs
+//! Context windows captured around matches for reviewers.
+use anyhow::Result;
+use cleansh_core::config::{RedactionConfig, RedactionRule};
+use cleansh_core::engine::SanitizationEngine;
+use cleansh_core::engines::composite_engine::CompositeEngine;
+use cleansh_core::engines::entropy_engine::EntropyEngine;
+use cleansh_core::engines::match_context::CONTEXT_CHARS;
+use cleansh_core::engines::regex_engine::RegexEngine;
+use cleansh_core::profiles::EngineOptions;
+use cleansh_core::RedactionMatch;
+
+const API_KEY: &str = "sk_live_4f9a8b7c";
+const EMAIL: &str = "jane.doe@example.com";
+
+fn rule(name: &str, pattern: &str, replace_with: &str) -> RedactionRule {
+    RedactionRule {
+        name: name.to_string(),
+        pattern: Some(pattern.to_string()),
+        pattern_type: "regex".to_string(),
+        replace_with: replace_with.to_string(),
+        ..Default::default()
+    }
+}
+
+fn config() -> RedactionConfig {
+    RedactionConfig {
+        rules: vec![
+            rule("api_key", r"sk_live_[0-9a-f]{8}", "[API_KEY]"),
+            rule("email", r"[a-z.]+@[a-z]+\.com", "[EMAIL]"),
+        ],
+        ..Default::default()
+    }
+}
+
+fn regex_matches(input: &str, capture_context: bool) -> Result<Vec<RedactionMatch>> {
+    let options = EngineOptions::default().with_capture_context(capture_context);
+    RegexEngine::with_options(config(), options)?.find_matches_for_ui(input, "test")
+}
+
+fn find<'a>(matches: &'a [RedactionMatch], rule_name: &str) -> &'a RedactionMatch {
+    matches.iter().find(|m| m.rule_name == rule_name).expect("rule matched")
+}
+
+#[test]
+fn test_context_is_clamped_at_content_and_line_boundaries() -> Result<()> {
+    let matches = regex_matches(&format!("{} is the key\nnext line", API_KEY), true)?;
+    let m = find(&matches, "api_key");
+    assert_eq!(m.context_before.as_deref(), Some(""));
+    assert_eq!(m.context_after.as_deref(), Some(" is the key"));
+    assert_eq!(m.context_line().as_deref(), Some("[API_KEY] is the key"));
+
+    // Long neighbors are cut to CONTEXT_CHARS characters, counted as characters.
+    let padding = "é".repeat(CONTEXT_CHARS + 10);
+    let matches = regex_matches(&format!("{}{}{}", padding, API_KEY, padding), true)?;
+    let m = find(&matches, "api_key");
+    assert_eq!(m.context_before.as_deref(), Some("é".repeat(CONTEXT_CHARS).as_str()));
+    assert_eq!(m.context_after.as_deref(), Some("é".repeat(CONTEXT_CHARS).as_str()));
+    Ok(())
+}
+
+#[test]
+fn test_context_never_shows_another_rules_secret() -> Result<()> {
+    let input = format!("owner {} key {} done", EMAIL, API_KEY);
+    let matches = regex_matches(&input, true)?;
+    let key = find(&matches, "api_key");
+    assert_eq!(key.context_before.as_deref(), Some("owner [EMAIL] key "));
+    let email = find(&matches, "email");
+    assert_eq!(email.context_after.as_deref(), Some(" key [API_KEY] done"));
+
+    // A secret cut by the edge of the window is redacted whole.
+    let input = format!("{}{}{}", API_KEY, " ".repeat(CONTEXT_CHARS - 4), EMAIL);
+    let matches = regex_matches(&input, true)?;
+    let after = find(&matches, "api_key").context_after.clone().unwrap();
+    assert!(after.ends_with("[EMAIL]") && !after.contains("jane"), "{}", after);
+    Ok(())
+}
+
+#[test]
+fn test_entropy_and_hybrid_context_is_scrubbed_of_rule_secrets() -> Result<()> {
+    let token = "Zx9Qm2Lr7Tk4Vb8Np3Hw6Yc1";
+    let input = format!("owner {} token={}", EMAIL, token);
+    let options = EngineOptions::default().with_capture_context(true);
+
+    let entropy = EntropyEngine::with_options(config(), options.clone())?;
+    let matches = entropy.find_matches_for_ui(&input, "test")?;
+    let m = matches.iter().find(|m| m.original_string == token).expect("token found");
+    assert_eq!(m.context_before.as_deref(), Some("owner [EMAIL] token="));
+
+    let hybrid = CompositeEngine::with_options(config(), options)?;
+    let matches = hybrid.find_matches_for_ui(&input, "test")?;
+    let m = matches.iter().find(|m| m.original_string == token).expect("token found");
+    assert_eq!(m.context_before.as_deref(), Some("owner [EMAIL] token="));
+    let email = find(&matches, "email");
+    assert!(!email.context_after.as_deref().unwrap().contains(token));
+    Ok(())
+}
+
+#[test]
+fn test_context_is_absent_when_the_flag_is_off() -> Result<()> {
+    let matches = regex_matches(&format!("owner {} key {}", EMAIL, API_KEY), false)?;
+    assert!(matches.iter().all(|m| m.context_before.is_none() && m.context_after.is_none() && m.context_line().is_none()));
+    let json = serde_json::to_string(&matches[0])?;
+    assert!(!json.contains("context_before"));
+    Ok(())
+}
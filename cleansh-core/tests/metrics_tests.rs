@@ -0,0 +1,95 @@
+// cleansh-core/tests/metrics_tests.rs
+use anyhow::Result;
+use cleansh_core::config::{RedactionConfig, RedactionRule};
+use cleansh_core::engine::{SanitizationEngine, SanitizeContext};
+use cleansh_core::engines::composite_engine::CompositeEngine;
+use cleansh_core::engines::entropy_engine::EntropyEngine;
+use cleansh_core::engines::regex_engine::RegexEngine;
+use tokio::sync::mpsc;
+
+const CORPUS: &str = "user=alice@example.com key=sk_aaaa1111\nkey=sk_bbbb2222\nnothing here\nbob@example.org";
+
+fn corpus_config() -> RedactionConfig {
+    let rule = |name: &str, pattern: &str| RedactionRule {
+        name: name.to_string(),
+        pattern: Some(pattern.to_string()),
+        pattern_type: "regex".to_string(),
+        ..Default::default()
+    };
+    RedactionConfig {
+        rules: vec![
+            rule("api_key", r"sk_[a-z0-9]{8}"),
+            rule("email", r"[a-z]+@[a-z]+\.[a-z]+"),
+            rule("unused", r"never_[0-9]{4}"),
+        ],
+        ..Default::default()
+    }
+}
+
+fn sanitize(engine: &dyn SanitizationEngine, input: &str) -> Result<String> {
+    Ok(engine.sanitize_with(input, &mut SanitizeContext::new("test"))?.0)
+}
+
+#[test]
+fn test_regex_metrics_after_known_corpus() -> Result<()> {
+    let engine = RegexEngine::new(corpus_config())?;
+    sanitize(&engine, CORPUS)?;
+    sanitize(&engine, CORPUS)?;
+
+    let snapshot = engine.metrics();
+    assert_eq!(snapshot.bytes_scanned, 2 * CORPUS.len() as u64);
+    assert_eq!(snapshot.lines_scanned, 8);
+    assert_eq!(snapshot.rule("api_key").unwrap().matches, 4);
+    assert_eq!(snapshot.rule("email").unwrap().matches, 4);
+    assert_eq!(snapshot.rule("unused").unwrap().matches, 0);
+    assert_eq!(snapshot.total_matches(), 8);
+    assert_eq!((snapshot.remediation_sends, snapshot.dropped_sends), (0, 0));
+    Ok(())
+}
+
+#[test]
+fn test_full_remediation_channel_counts_drops() -> Result<()> {
+    let (tx, _rx) = mpsc::channel(1);
+    let engine = RegexEngine::new(corpus_config())?.with_remediation_tx(tx);
+    sanitize(&engine, CORPUS)?;
+
+    let snapshot = engine.metrics();
+    assert_eq!(snapshot.remediation_sends, 1);
+    assert_eq!(snapshot.dropped_sends, 3);
+    Ok(())
+}
+
+#[test]
+fn test_entropy_metrics_after_known_corpus() -> Result<()> {
+    let engine = EntropyEngine::new(RedactionConfig::default())?;
+    let input = concat!(
+        "[INFO] 2025-10-20 10:00:00 Service started successfully.\n",
+        "[INFO] 2025-10-20 10:00:01 Loading configuration modules...\n",
+        "[DEBUG] AUTH_TOKEN=7f8a9b2c3d4e5f6a7b8c9d0e1f2a3b4c\n",
+        "[INFO] 2025-10-20 10:00:03 Request processed in 45ms.\n",
+    );
+    let (_, summary) = engine.sanitize_with(input, &mut SanitizeContext::new("test"))?;
+    assert_eq!(summary.iter().map(|item| item.occurrences).sum::<usize>(), 1);
+
+    let snapshot = engine.metrics();
+    assert_eq!(snapshot.lines_scanned, 4);
+    assert_eq!(snapshot.bytes_scanned, input.len() as u64);
+    assert_eq!(snapshot.rule("high_entropy_secret").unwrap().matches, 1);
+    Ok(())
+}
+
+#[test]
+fn test_composite_metrics_count_each_input_once() -> Result<()> {
+    // With remediation wired, `sanitize` makes an extra detection pass that must not be counted.
+    let (tx, _rx) = mpsc::channel(16);
+    let engine = CompositeEngine::new(corpus_config())?.with_remediation_tx(tx);
+    let (_, summary) = engine.sanitize_with(CORPUS, &mut SanitizeContext::new("test"))?;
+
+    let snapshot = engine.metrics();
+    assert_eq!(snapshot.bytes_scanned, CORPUS.len() as u64);
+    assert_eq!(snapshot.lines_scanned, 4);
+    assert_eq!(snapshot.rule("api_key").unwrap().matches, 2);
+    assert_eq!(snapshot.rule("email").unwrap().matches, 2);
+    assert_eq!(snapshot.total_matches(), summary.iter().map(|item| item.occurrences as u64).sum::<u64>());
+    Ok(())
+}
@@ -0,0 +1,141 @@
+// cleansh-core/tests/object_vault_tests.rs
+//! Conditional-write publishing against an in-memory object store.
+use anyhow::Result;
+use async_trait::async_trait;
+use cleansh_core::remediation::fingerprint::SecretFingerprint;
+use cleansh_core::remediation::vault::object::{ObjectStore, ObjectVault, StoredObject, WriteCondition, WriteOutcome};
+use cleansh_core::remediation::vault::{decode_fingerprints, encode_fingerprints, FingerprintVault};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+const SALT: &[u8] = b"org-salt";
+const KEY: &str = "fingerprints.jsonl";
+
+fn fingerprint(secret: &str) -> SecretFingerprint {
+    SecretFingerprint::from_secret(secret, "stripe", SALT)
+}
+
+/// One object whose ETag is its write count. `rivals` fingerprints are written by
+/// "another instance" just before each of our next writes, making them conflict.
+#[derive(Default)]
+struct MockStore {
+    object: Mutex<Option<(Vec<u8>, u32)>>,
+    rivals: Mutex<Vec<SecretFingerprint>>,
+    gets: AtomicUsize,
+    puts: AtomicUsize,
+}
+
+impl MockStore {
+    fn stored(&self) -> Vec<SecretFingerprint> {
+        self.object.lock().unwrap().as_ref().map(|(body, _)| decode_fingerprints(body).unwrap()).unwrap_or_default()
+    }
+
+    fn stored_hashes(&self) -> Vec<String> {
+        self.stored().into_iter().map(|f| f.hash).collect()
+    }
+}
+
+#[async_trait]
+impl ObjectStore for MockStore {
+    fn name(&self) -> &str {
+        "Mock"
+    }
+
+    async fn get(&self, _: &str) -> Result<Option<StoredObject>> {
+        self.gets.fetch_add(1, Ordering::SeqCst);
+        Ok(self.object.lock().unwrap().as_ref().map(|(body, version)| StoredObject { body: body.clone(), etag: version.to_string() }))
+    }
+
+    async fn put(&self, _: &str, body: Vec<u8>, condition: WriteCondition) -> Result<WriteOutcome> {
+        self.puts.fetch_add(1, Ordering::SeqCst);
+        if let Some(rival) = self.rivals.lock().unwrap().pop() {
+            let mut all = self.stored();
+            all.push(rival);
+            let mut object = self.object.lock().unwrap();
+            let version = object.as_ref().map_or(1, |(_, version)| version + 1);
+            *object = Some((encode_fingerprints(&all)?, version));
+        }
+        let mut object = self.object.lock().unwrap();
+        let holds = match (&condition, object.as_ref()) {
+            (WriteCondition::Absent, None) => true,
+            (WriteCondition::Matches(etag), Some((_, version))) => *etag == version.to_string(),
+            _ => false,
+        };
+        if !holds {
+            return Ok(WriteOutcome::PreconditionFailed);
+        }
+        let version = object.as_ref().map_or(1, |(_, version)| version + 1);
+        *object = Some((body, version));
+        Ok(WriteOutcome::Written(Some(version.to_string())))
+    }
+}
+
+fn vault() -> ObjectVault<MockStore> {
+    ObjectVault::new(MockStore::default(), KEY).with_backoff(Duration::from_millis(1))
+}
+
+#[tokio::test]
+async fn test_publish_retries_a_conflicting_write_without_losing_either_fingerprint() -> Result<()> {
+    let vault = vault();
+    vault.store().rivals.lock().unwrap().push(fingerprint("sk_rival_222222"));
+
+    vault.publish(fingerprint("sk_ours_1111111")).await?;
+    // The first write lost to the rival's; the second re-read and merged.
+    assert_eq!(vault.store().puts.load(Ordering::SeqCst), 2);
+    assert_eq!(vault.store().gets.load(Ordering::SeqCst), 2);
+    assert_eq!(vault.store().stored_hashes(), [fingerprint("sk_rival_222222").hash, fingerprint("sk_ours_1111111").hash]);
+
+    // The object is JSON Lines, one fingerprint per line.
+    let body = vault.store().object.lock().unwrap().clone().expect("written").0;
+    assert_eq!(String::from_utf8(body)?.lines().count(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_publish_gives_up_after_the_configured_attempts() -> Result<()> {
+    let vault = vault().with_max_attempts(3);
+    vault.store().rivals.lock().unwrap().extend((0..5).map(|i| fingerprint(&format!("sk_rival_{i}_22222"))));
+
+    let error = vault.publish(fingerprint("sk_ours_1111111")).await.unwrap_err();
+    assert!(format!("{:#}", error).contains("after 3 attempts"), "{:#}", error);
+    assert_eq!(vault.store().puts.load(Ordering::SeqCst), 3);
+    assert!(!vault.store().stored_hashes().contains(&fingerprint("sk_ours_1111111").hash));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_repeat_publishes_and_fetched_etags_skip_round_trips() -> Result<()> {
+    let vault = vault();
+    vault.publish(fingerprint("sk_ours_1111111")).await?;
+    let (gets, puts) = (vault.store().gets.load(Ordering::SeqCst), vault.store().puts.load(Ordering::SeqCst));
+
+    // A hash already published never reaches the store again.
+    vault.publish(fingerprint("sk_ours_1111111")).await?;
+    assert_eq!(vault.store().gets.load(Ordering::SeqCst), gets);
+    assert_eq!(vault.store().puts.load(Ordering::SeqCst), puts);
+
+    // After a fetch, the next publish writes against the fetched ETag without re-reading.
+    assert_eq!(vault.fetch_all().await?.len(), 1);
+    assert_eq!(vault.last_etag().as_deref(), Some("1"));
+    let gets = vault.store().gets.load(Ordering::SeqCst);
+    vault.publish_batch(vec![fingerprint("sk_two_2222222"), fingerprint("sk_three_333333"), fingerprint("sk_two_2222222")]).await?;
+    assert_eq!(vault.store().gets.load(Ordering::SeqCst), gets);
+    assert_eq!(vault.store().puts.load(Ordering::SeqCst), puts + 1, "a batch is one write");
+    assert_eq!(vault.store().stored().len(), 3);
+    assert_eq!(vault.last_etag().as_deref(), Some("2"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_vault_reads_the_legacy_json_array() -> Result<()> {
+    let vault = vault();
+    let legacy = serde_json::to_vec(&vec![fingerprint("sk_old_1111111")])?;
+    *vault.store().object.lock().unwrap() = Some((legacy, 7));
+
+    assert_eq!(vault.fetch_all().await?[0].hash, fingerprint("sk_old_1111111").hash);
+    vault.publish(fingerprint("sk_new_2222222")).await?;
+    assert_eq!(vault.store().stored().len(), 2);
+    assert!(decode_fingerprints(b"{not json}\n").is_err());
+    Ok(())
+}
@@ -0,0 +1,70 @@
+// cleansh-core/tests/regex_heat_tests.rs
+use anyhow::Result;
+use cleansh_core::config::{RedactionConfig, RedactionRule};
+use cleansh_core::engine::{SanitizationEngine, SanitizeContext};
+use cleansh_core::engines::regex_engine::{severity_heat, HEAT_FALLOFF_CHARS};
+use cleansh_core::RegexEngine;
+
+fn api_key_engine(severity: &str) -> Result<RegexEngine> {
+    RegexEngine::new(RedactionConfig {
+        rules: vec![RedactionRule {
+            name: "api_key".to_string(),
+            pattern: Some(r"sk_[a-z0-9]{8}".to_string()),
+            pattern_type: "regex".to_string(),
+            severity: Some(severity.to_string()),
+            ..Default::default()
+        }],
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_heat_covers_exactly_the_match_with_falloff() -> Result<()> {
+    let engine = api_key_engine("high")?;
+    let line = "request with key sk_abcd1234 failed";
+    let (sanitized, _) = engine.sanitize_with(line, &mut SanitizeContext::new("test"))?;
+    assert_eq!(sanitized, "request with key [REDACTED] failed");
+
+    let heat = engine.get_heat_scores(line);
+    assert_eq!(heat.len(), line.len());
+    let (start, end) = (line.find("sk_").unwrap(), line.find(" failed").unwrap());
+    assert!(heat[start..end].iter().all(|&h| h == 6.0), "{:?}", &heat[start..end]);
+
+    // Two fading characters bracket the match; everything further out is cold.
+    assert_eq!((heat[start - 1], heat[start - 2]), (4.0, 2.0));
+    assert_eq!((heat[end], heat[end + 1]), (4.0, 2.0));
+    let far = (0..start - HEAT_FALLOFF_CHARS).chain(end + HEAT_FALLOFF_CHARS..line.len());
+    assert!(far.map(|i| heat[i]).all(|h| h == 0.0));
+    Ok(())
+}
+
+#[test]
+fn test_heat_is_aligned_to_the_unstripped_line() -> Result<()> {
+    let engine = api_key_engine("critical")?;
+    let line = "\x1b[1mkey\x1b[0m sk_abcd1234";
+    let heat = engine.get_heat_scores(line);
+    assert_eq!(heat.len(), line.len());
+    let start = line.find("sk_").unwrap();
+    assert!(heat[start..].iter().all(|&h| h == 8.0));
+    // The falloff lands on the space and the `y`, never on the escape before it.
+    let reset = line.find("\x1b[0m").unwrap();
+    assert_eq!(heat[start - 1], 8.0 * 2.0 / 3.0);
+    assert!(heat[reset..reset + 4].iter().all(|&h| h == 0.0));
+    assert!(heat[reset - 1] > 0.0);
+    Ok(())
+}
+
+#[test]
+fn test_heat_scores_by_severity_and_leaves_metrics_alone() -> Result<()> {
+    assert_eq!(
+        ["critical", "HIGH", "medium", "low", "unknown"].map(|s| severity_heat(Some(s))),
+        [8.0, 6.0, 4.0, 2.0, 4.0]
+    );
+    assert_eq!(severity_heat(None), 4.0);
+
+    let engine = api_key_engine("low")?;
+    let heat = engine.get_heat_scores("sk_abcd1234");
+    assert!(heat.iter().all(|&h| h == 2.0));
+    assert_eq!(engine.metrics().total_matches(), 0);
+    Ok(())
+}
@@ -0,0 +1,93 @@
+// cleansh-core/tests/remediation_channel_tests.rs
+use anyhow::Result;
+use cleansh_core::config::{RedactionConfig, RedactionRule};
+use cleansh_core::engine::{SanitizationEngine, SanitizeContext};
+use cleansh_core::profiles::EngineOptions;
+use cleansh_core::remediation::channel::RemediationTxMode;
+use cleansh_core::{RedactionMatch, RegexEngine};
+use tokio::sync::mpsc;
+
+fn api_key_config() -> RedactionConfig {
+    RedactionConfig {
+        rules: vec![RedactionRule {
+            name: "api_key".to_string(),
+            pattern: Some(r"sk_[a-z0-9]{8}".to_string()),
+            pattern_type: "regex".to_string(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+/// An engine wired to a 1-slot channel whose slot is already taken, so every
+/// match it tees meets a full channel.
+fn engine_on_full_channel(mode: RemediationTxMode) -> Result<(RegexEngine, mpsc::Receiver<RedactionMatch>)> {
+    let (tx, rx) = mpsc::channel(1);
+    tx.try_send(RedactionMatch { original_string: "filler".to_string(), ..Default::default() })?;
+    let options = EngineOptions::default().with_remediation_tx_mode(mode);
+    Ok((RegexEngine::with_options(api_key_config(), options)?.with_remediation_tx(tx), rx))
+}
+
+fn sanitize(engine: &RegexEngine, input: &str) -> Result<()> {
+    engine.sanitize_with(input, &mut SanitizeContext::new("test"))?;
+    Ok(())
+}
+
+/// Drains `rx` until every sender, including the worker's, is gone.
+fn received(mut rx: mpsc::Receiver<RedactionMatch>) -> Vec<String> {
+    std::iter::from_fn(|| rx.blocking_recv()).map(|m| m.original_string).collect()
+}
+
+#[test]
+fn test_drop_mode_counts_what_does_not_fit() -> Result<()> {
+    let (engine, rx) = engine_on_full_channel(RemediationTxMode::Drop)?;
+    sanitize(&engine, "a=sk_aaaa1111 b=sk_bbbb2222")?;
+
+    let snapshot = engine.metrics();
+    assert_eq!((snapshot.remediation_sends, snapshot.dropped_sends, snapshot.coalesced_sends), (0, 2, 0));
+    drop(engine);
+    assert_eq!(received(rx), ["filler"]);
+    Ok(())
+}
+
+#[test]
+fn test_block_mode_delivers_everything() -> Result<()> {
+    let (engine, rx) = engine_on_full_channel(RemediationTxMode::Block)?;
+    sanitize(&engine, "a=sk_aaaa1111 b=sk_bbbb2222")?;
+    sanitize(&engine, "a=sk_aaaa1111 c=sk_cccc3333")?;
+
+    let snapshot = engine.metrics();
+    assert_eq!((snapshot.remediation_sends, snapshot.dropped_sends), (4, 0));
+    drop(engine);
+    assert_eq!(received(rx), ["filler", "sk_aaaa1111", "sk_bbbb2222", "sk_aaaa1111", "sk_cccc3333"]);
+    Ok(())
+}
+
+#[test]
+fn test_coalesce_mode_merges_duplicates_waiting_in_the_queue() -> Result<()> {
+    let (engine, rx) = engine_on_full_channel(RemediationTxMode::Coalesce)?;
+    for _ in 0..5 {
+        sanitize(&engine, "a=sk_aaaa1111")?;
+    }
+    sanitize(&engine, "b=sk_bbbb2222")?;
+
+    let snapshot = engine.metrics();
+    assert_eq!((snapshot.remediation_sends, snapshot.dropped_sends, snapshot.coalesced_sends), (2, 0, 4));
+    drop(engine);
+    assert_eq!(received(rx), ["filler", "sk_aaaa1111", "sk_bbbb2222"]);
+    Ok(())
+}
+
+#[test]
+fn test_queued_modes_count_drops_once_the_orchestrator_is_gone() -> Result<()> {
+    let (engine, rx) = engine_on_full_channel(RemediationTxMode::Block)?;
+    drop(rx);
+    sanitize(&engine, "a=sk_aaaa1111")?;
+    // The worker notices the closed channel on its first delivery and stops.
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    sanitize(&engine, "b=sk_bbbb2222")?;
+
+    let snapshot = engine.metrics();
+    assert_eq!((snapshot.remediation_sends, snapshot.dropped_sends), (1, 1));
+    Ok(())
+}
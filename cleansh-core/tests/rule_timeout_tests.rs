@@ -0,0 +1,99 @@
+// cleansh-core/tests/rule_timeout_tests.rs
+//! Per-rule time budgets, and the repetition limits `compile_rules` enforces.
+use anyhow::Result;
+use cleansh_core::config::{EngineConfig, RedactionConfig, RedactionRule};
+use cleansh_core::engine::{SanitizationEngine, SanitizeContext};
+use cleansh_core::engines::regex_engine::RegexEngine;
+use cleansh_core::sanitizers::compiler::{check_repetition_bounds, compile_rules};
+
+const SECRET: &str = "sk_live_4f9a8b7c";
+
+fn rule(name: &str, pattern: &str) -> RedactionRule {
+    RedactionRule {
+        name: name.to_string(),
+        pattern: Some(pattern.to_string()),
+        pattern_type: "regex".to_string(),
+        replace_with: format!("[{}]", name.to_uppercase()),
+        ..Default::default()
+    }
+}
+
+fn config(rule_timeout_ms: Option<u64>) -> RedactionConfig {
+    RedactionConfig {
+        // Two-character matches over the whole input: hundreds of thousands of them.
+        rules: vec![rule("slow", r"(\w)(\w)"), rule("api_key", r"sk_live_[0-9a-f]{8}")],
+        engines: EngineConfig { rule_timeout_ms, ..Default::default() },
+        ..Default::default()
+    }
+}
+
+fn long_input() -> String {
+    format!("{} {}\n", "ab ".repeat(100_000), SECRET)
+}
+
+#[test]
+fn test_a_rule_over_budget_is_stopped_and_the_others_still_match() -> Result<()> {
+    let engine = RegexEngine::new(config(Some(20)))?;
+    let input = long_input();
+    let (sanitized, summary) = engine.sanitize_with(&input, &mut SanitizeContext::new("stream"))?;
+
+    assert!(!sanitized.contains(SECRET), "the other rule still redacts");
+    assert!(sanitized.ends_with("[API_KEY]\n"));
+    let slow = summary.iter().find(|item| item.rule_name == "slow").map_or(0, |item| item.occurrences);
+    assert!(slow < 100_000, "the slow rule stopped early, after {} matches", slow);
+    assert!(sanitized.contains("ab ab "), "matches past the budget are left alone");
+
+    let metrics = engine.metrics();
+    assert_eq!(metrics.rule("slow").unwrap().timeouts, 1);
+    assert_eq!(metrics.rule("api_key").unwrap().timeouts, 0);
+    assert_eq!(metrics.timed_out_rules().map(|rule| rule.rule_name.as_str()).collect::<Vec<_>>(), ["slow"]);
+    Ok(())
+}
+
+#[test]
+fn test_without_a_budget_every_match_is_found() -> Result<()> {
+    for timeout in [None, Some(0)] {
+        let engine = RegexEngine::new(config(timeout))?;
+        let summary = engine.analyze_for_stats(&long_input(), "stream")?;
+        let slow = summary.iter().find(|item| item.rule_name == "slow").expect("slow matched");
+        // Every "ab", and the eight pairs of characters in the key.
+        assert_eq!(slow.occurrences, 100_008);
+        assert_eq!(engine.metrics().timed_out_rules().count(), 0);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_compile_rules_rejects_oversized_repetitions() {
+    assert!(check_repetition_bounds(r"[a-z]{3,8}(?:\s+[a-z]{3,8}){11,23}").is_ok());
+    assert!(check_repetition_bounds(r"unbalanced(").is_ok(), "syntax errors are left to the compiler");
+
+    let error = compile_rules(vec![rule("huge", r"x{2,5000}")]).unwrap_err().to_string();
+    assert!(error.contains("Rule 'huge': pattern is too complex: repetition {2,5000} exceeds the maximum count of 1000"), "{}", error);
+
+    let error = compile_rules(vec![rule("nested", r"(?:(?:ab){200}c){100}")]).unwrap_err().to_string();
+    assert!(error.contains("Rule 'nested': pattern is too complex: nested repetitions ending in {200} allow 20000 repeats"), "{}", error);
+
+    assert!(compile_rules(vec![rule("fine", r"(?:ab{20}){100}")]).is_ok());
+}
+
+
+#[test]
+fn test_a_slow_rule_that_never_matches_is_stopped() -> Result<()> {
+    // Each position starts a 200-character attempt that fails at the end.
+    let config = RedactionConfig {
+        rules: vec![rule("stuck", r"[\w\s]{200}\d"), rule("api_key", r"sk_live_[0-9a-f]{8}")],
+        engines: EngineConfig { rule_timeout_ms: Some(20), ..Default::default() },
+        ..Default::default()
+    };
+    // The prefilter would drop a rule that matches nothing before it ran.
+    let engine = RegexEngine::new(config)?.with_prefilter(false);
+    let input = format!("{} {}\n", "ab ".repeat(100_000), SECRET);
+    let (sanitized, _) = engine.sanitize_with(&input, &mut SanitizeContext::new("stream"))?;
+
+    assert!(sanitized.ends_with("[API_KEY]\n"));
+    let metrics = engine.metrics();
+    assert_eq!(metrics.rule("stuck").unwrap().timeouts, 1);
+    assert_eq!(metrics.rule("api_key").unwrap().timeouts, 0);
+    Ok(())
+}
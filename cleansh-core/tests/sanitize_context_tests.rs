@@ -0,0 +1,151 @@
+// cleansh-core/tests/sanitize_context_tests.rs
+use anyhow::Result;
+use cleansh_core::config::{RedactionConfig, RedactionRule};
+use cleansh_core::engine::{SanitizationEngine, SanitizeContext};
+use cleansh_core::{read_audit_log, AuditLog, AuditLogConfig, CompositeEngine, EntropyEngine, RegexEngine};
+
+fn api_key_config() -> RedactionConfig {
+    RedactionConfig {
+        rules: vec![RedactionRule {
+            name: "api_key".to_string(),
+            pattern: Some(r"sk_[a-z0-9]{8}".to_string()),
+            pattern_type: "regex".to_string(),
+            replace_with: "[KEY]".to_string(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_audit_log_receives_context_fields() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("audit.log");
+    let engine = RegexEngine::new(api_key_config())?;
+    {
+        let mut log = AuditLog::new(&path, AuditLogConfig::default())?;
+        let mut ctx = SanitizeContext::new("deploy.log")
+            .with_run_id("run-42")
+            .with_input_hash("abc123")
+            .with_user_id("ci")
+            .with_reason("pre-upload")
+            .with_outcome("redacted")
+            .with_audit_log(&mut log);
+        let (sanitized, _) = engine.sanitize_with("key=sk_aaaa1111", &mut ctx)?;
+        assert_eq!(sanitized, "key=[KEY]");
+    }
+
+    let entries = read_audit_log(&path)?.entries;
+    assert_eq!(entries.len(), 1);
+    let entry = &entries[0];
+    assert_eq!(
+        (entry.file_path.as_str(), entry.run_id.as_str(), entry.input_hash.as_str(), entry.user_id.as_str()),
+        ("deploy.log", "run-42", "abc123", "ci")
+    );
+    assert_eq!((entry.reason_for_redaction.as_str(), entry.redaction_outcome.as_str()), ("pre-upload", "redacted"));
+    assert_eq!((entry.rule_name.as_str(), entry.start, entry.end), ("api_key", 4, 15));
+    Ok(())
+}
+
+#[test]
+fn test_audit_log_offsets_point_into_the_colored_input() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("audit.log");
+    let engine = RegexEngine::new(api_key_config())?;
+    let input = "\x1b[36mkey\x1b[0m=\x1b[1msk_aaaa1111\x1b[0m";
+    {
+        let mut log = AuditLog::new(&path, AuditLogConfig::default())?;
+        engine.sanitize_with(input, &mut SanitizeContext::new("tty.log").with_audit_log(&mut log))?;
+    }
+
+    let entries = read_audit_log(&path)?.entries;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(&input[entries[0].start as usize..entries[0].end as usize], "sk_aaaa1111");
+    Ok(())
+}
+
+#[test]
+fn test_hybrid_engine_audits_through_its_regex_pass() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("audit.log");
+    let engine = CompositeEngine::new(api_key_config())?;
+    {
+        let mut log = AuditLog::new(&path, AuditLogConfig::default())?;
+        let mut ctx = SanitizeContext::new("app.log").with_run_id("run-7").with_audit_log(&mut log);
+        engine.sanitize_with("a=sk_aaaa1111 b=sk_bbbb2222", &mut ctx)?;
+    }
+
+    let entries = read_audit_log(&path)?.entries;
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|e| e.file_path == "app.log" && e.run_id == "run-7"));
+    Ok(())
+}
+
+const HIGH_ENTROPY_TOKEN: &str = "7f8a9b2c3d4e5f6a7b8c9d0e1f2a3b4c";
+
+#[test]
+fn test_entropy_engine_writes_to_the_audit_log() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("audit.log");
+    let engine = EntropyEngine::new(RedactionConfig::default())?;
+    let input = format!("[DEBUG] AUTH_TOKEN={}\n", HIGH_ENTROPY_TOKEN);
+    {
+        let mut log = AuditLog::new(&path, AuditLogConfig::default())?;
+        engine.sanitize_with(&input, &mut SanitizeContext::new("app.log").with_run_id("run-9").with_audit_log(&mut log))?;
+    }
+
+    let entries = read_audit_log(&path)?.entries;
+    assert_eq!(entries.len(), 1);
+    assert_eq!((entries[0].rule_name.as_str(), entries[0].run_id.as_str()), ("high_entropy_secret", "run-9"));
+    assert_eq!(&input[entries[0].start as usize..entries[0].end as usize], HIGH_ENTROPY_TOKEN);
+    Ok(())
+}
+
+#[test]
+fn test_hybrid_engine_audits_entropy_matches_at_input_offsets() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("audit.log");
+    let engine = CompositeEngine::new(api_key_config())?;
+    // The regex redaction before the token shifts it in the regex pass output.
+    let input = format!("a=sk_aaaa1111 AUTH_TOKEN={}\n", HIGH_ENTROPY_TOKEN);
+    {
+        let mut log = AuditLog::new(&path, AuditLogConfig::default())?;
+        engine.sanitize_with(&input, &mut SanitizeContext::new("app.log").with_audit_log(&mut log))?;
+    }
+
+    let entries = read_audit_log(&path)?.entries;
+    let spans: Vec<(&str, &str)> = entries
+        .iter()
+        .map(|e| (e.rule_name.as_str(), &input[e.start as usize..e.end as usize]))
+        .collect();
+    assert_eq!(spans, [("api_key", "sk_aaaa1111"), ("high_entropy_secret", HIGH_ENTROPY_TOKEN)]);
+    Ok(())
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_positional_shim_matches_context_call() -> Result<()> {
+    let engine = RegexEngine::new(api_key_config())?;
+    let input = "token sk_cccc3333 here";
+    let (legacy, _) = engine.sanitize(input, "test", "run1", "", "user1", "test", "success", None)?;
+    let (current, _) = engine.sanitize_with(input, &mut SanitizeContext::new("test"))?;
+    assert_eq!(legacy, current);
+    assert_eq!(current, "token [KEY] here");
+    Ok(())
+}
+
+#[test]
+fn test_first_line_numbers_teed_matches() -> Result<()> {
+    let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+    let engine = RegexEngine::new(api_key_config())?.with_remediation_tx(tx);
+    let mut ctx = SanitizeContext::new("stdin").with_first_line(10);
+    engine.sanitize_with("boot\nkey=sk_aaaa1111", &mut ctx)?;
+
+    let m = rx.try_recv()?;
+    assert_eq!((m.source_id.as_str(), m.line_number), ("stdin", Some(11)));
+
+    // Without a first line, the line number stays unknown.
+    engine.sanitize_with("key=sk_aaaa1111", &mut SanitizeContext::new("tui"))?;
+    assert_eq!(rx.try_recv()?.line_number, None);
+    Ok(())
+}
@@ -0,0 +1,87 @@
+// cleansh-core/tests/severity_tests.rs
+use anyhow::Result;
+use cleansh_core::config::{RedactionConfig, RedactionRule};
+use cleansh_core::engine::SanitizationEngine;
+use cleansh_core::{EntropyEngine, RedactionMatch, RegexEngine, Severity};
+
+fn rule(name: &str, pattern: &str, severity: Option<&str>) -> RedactionRule {
+    RedactionRule {
+        name: name.to_string(),
+        pattern: Some(pattern.to_string()),
+        pattern_type: "regex".to_string(),
+        severity: severity.map(str::to_string),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_levels_are_ordered_and_parse_case_insensitively() {
+    assert!(Severity::ALL.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(Severity::ALL.map(|s| s.to_string().parse::<Severity>()), Severity::ALL.map(Ok));
+    assert_eq!("HIGH".parse::<Severity>(), Ok(Severity::High));
+    assert_eq!(" Informational ".parse::<Severity>(), Ok(Severity::Info));
+    assert_eq!("moderate".parse::<Severity>(), Ok(Severity::Medium));
+    assert!("severe".parse::<Severity>().unwrap_err().contains("unknown severity 'severe'"));
+}
+
+#[test]
+fn test_unknown_and_missing_rule_severities_count_as_medium() {
+    assert_eq!(Severity::from_rule(Some("Critical")), Severity::Critical);
+    assert_eq!(Severity::from_rule(Some("urgent")), Severity::Medium);
+    assert_eq!(Severity::from_rule(Some("")), Severity::Medium);
+    assert_eq!(Severity::from_rule(None), Severity::Medium);
+}
+
+#[test]
+fn test_entropy_matches_fall_back_to_confidence_bands() {
+    let entropy = |confidence: Option<f64>| RedactionMatch {
+        rule: RedactionRule { pattern_type: "entropy".to_string(), ..Default::default() },
+        confidence,
+        ..Default::default()
+    };
+    assert_eq!(Severity::of_match(&entropy(Some(0.1))), Severity::Low);
+    assert_eq!(Severity::of_match(&entropy(Some(0.7))), Severity::Medium);
+    assert_eq!(Severity::of_match(&entropy(Some(2.5))), Severity::High);
+    assert_eq!(Severity::of_match(&entropy(None)), Severity::Medium);
+
+    // A rule's own severity wins over its confidence.
+    let mut rated = entropy(Some(2.5));
+    rated.rule.severity = Some("low".to_string());
+    assert_eq!(Severity::of_match(&rated), Severity::Low);
+}
+
+#[test]
+fn test_regex_summary_carries_the_highest_rule_severity() -> Result<()> {
+    let engine = RegexEngine::new(RedactionConfig {
+        rules: vec![
+            rule("relay_token", r"RELAY-[A-Z0-9]{8}", Some("critical")),
+            rule("build_id", r"BUILD-[0-9]{6}", Some("low")),
+            rule("ticket", r"TICKET-[0-9]{4}", None),
+        ],
+        ..Default::default()
+    })?;
+    let summary = engine.analyze_for_stats("BUILD-123456 TICKET-0042 RELAY-ABCD1234", "test")?;
+    let level = |name: &str| summary.iter().find(|s| s.rule_name == name).map(|s| s.severity);
+    assert_eq!(level("relay_token"), Some(Severity::Critical));
+    assert_eq!(level("build_id"), Some(Severity::Low));
+    assert_eq!(level("ticket"), Some(Severity::Medium));
+    Ok(())
+}
+
+#[test]
+fn test_entropy_summary_severity_matches_its_strongest_match() -> Result<()> {
+    let engine = EntropyEngine::new(RedactionConfig::default())?;
+    let input = "
+        [INFO] 2025-10-20 10:00:00 Service started successfully.
+        [INFO] 2025-10-20 10:00:01 Loading configuration modules...
+        [DEBUG] AUTH_TOKEN=7f8a9b2c3d4e5f6a7b8c9d0e1f2a3b4c
+        [INFO] 2025-10-20 10:00:03 Request processed in 45ms.
+    ";
+    let matches = engine.find_matches_for_ui(input, "test")?;
+    assert!(!matches.is_empty());
+    let strongest = matches.iter().map(Severity::of_match).max().unwrap();
+
+    let summary = engine.analyze_for_stats(input, "test")?;
+    assert_eq!(summary.iter().map(|s| s.severity).max(), Some(strongest));
+    Ok(())
+}
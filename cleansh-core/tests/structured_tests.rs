@@ -0,0 +1,93 @@
+// cleansh-core/tests/structured_tests.rs
+use cleansh_core::config::{EngineConfig, RedactionConfig};
+use cleansh_core::sanitizers::structured::{
+    detect_format, find_structured_matches, merge_structured_matches, KeyMatcher, StructuredFormat, STRUCTURED_RULE_NAME,
+};
+use cleansh_core::RedactionMatch;
+
+fn matcher() -> KeyMatcher {
+    KeyMatcher::from_config(&RedactionConfig::default())
+}
+
+/// The flagged values, checked against their offsets into `content`.
+fn flagged(content: &str) -> Vec<String> {
+    find_structured_matches(content, "test", &matcher())
+        .into_iter()
+        .map(|m| {
+            assert_eq!(m.rule_name, STRUCTURED_RULE_NAME);
+            assert_eq!(&content[m.start as usize..m.end as usize], m.original_string);
+            m.original_string
+        })
+        .collect()
+}
+
+#[test]
+fn test_nested_json_values_are_found_by_key() {
+    let content = r#"{
+  "service": "billing",
+  "database": {
+    "host": "db.internal",
+    "password":
+      "hunter2\"quoted"
+  },
+  "apiKeys": ["k-one", "k-two"],
+  "users": [{"name": "alice", "authToken": "t0k3n"}]
+}"#;
+    assert_eq!(detect_format(content), Some(StructuredFormat::Json));
+    assert_eq!(flagged(content), [r#"hunter2\"quoted"#, "k-one", "k-two", "t0k3n"]);
+
+    let matches = find_structured_matches(content, "test", &matcher());
+    assert_eq!(matches[0].line_number, Some(6));
+}
+
+#[test]
+fn test_yaml_multi_document_values_are_found_by_key() {
+    let content = "\
+service: billing
+db:
+  host: db.internal
+  password: 'hunter2' # rotated monthly
+---
+tokens:
+  - abc123
+  - def456
+secret: null
+certificate_key: |
+  -----BEGIN KEY-----
+  MIIBVwIBADANBg
+  -----END KEY-----
+name: done
+";
+    assert_eq!(detect_format(content), Some(StructuredFormat::Yaml));
+    assert_eq!(
+        flagged(content),
+        ["hunter2", "abc123", "def456", "-----BEGIN KEY-----\n  MIIBVwIBADANBg\n  -----END KEY-----"]
+    );
+}
+
+#[test]
+fn test_unparseable_input_falls_back_silently() {
+    for content in ["password = hunter2", "{\"password\": \"unterminated", "key: [unclosed", ""] {
+        assert_eq!(detect_format(content), None, "{content:?}");
+        assert!(flagged(content).is_empty(), "{content:?}");
+    }
+}
+
+#[test]
+fn test_engine_matches_win_over_overlapping_structured_ones() {
+    let content = r#"{"token": "sk_live_abc", "password": "hunter2"}"#;
+    let engine_match = RedactionMatch { rule_name: "stripe".to_string(), start: 11, end: 22, ..Default::default() };
+    let mut matches = vec![engine_match];
+    merge_structured_matches(&mut matches, find_structured_matches(content, "test", &matcher()));
+    let rules: Vec<&str> = matches.iter().map(|m| m.rule_name.as_str()).collect();
+    assert_eq!(rules, ["stripe", STRUCTURED_RULE_NAME]);
+}
+
+#[test]
+fn test_structured_flag_merges_from_user_config() -> anyhow::Result<()> {
+    let user: RedactionConfig = serde_yml::from_str("rules: []\nengines:\n  structured:\n    enabled: true\n")?;
+    let mut engines = EngineConfig::default();
+    engines.merge_from(&user.engines);
+    assert_eq!(engines.structured.enabled, Some(true));
+    Ok(())
+}
@@ -0,0 +1,88 @@
+// cleansh-core/tests/summary_sampling_tests.rs
+//! Summaries keep exact counts but only a bounded sample of example texts.
+use anyhow::Result;
+use cleansh_core::config::{merge_summary, RedactionConfig, RedactionSummaryItem};
+use cleansh_core::engine::{SanitizationEngine, SanitizeContext};
+use cleansh_core::profiles::{EngineOptions, SamplesConfig, DEFAULT_SUMMARY_SAMPLES_PER_RULE};
+use cleansh_core::RegexEngine;
+
+fn many_emails(count: usize) -> String {
+    (0..count).map(|i| format!("mail user{}@example.com\n", i)).collect()
+}
+
+fn email_summary(engine: &RegexEngine, input: &str) -> Result<RedactionSummaryItem> {
+    let (_, summary) = engine.sanitize_with(input, &mut SanitizeContext::new("test"))?;
+    Ok(summary.into_iter().find(|item| item.rule_name == "email").expect("emails were redacted"))
+}
+
+#[test]
+fn test_summary_samples_are_capped_by_default_and_deterministic() -> Result<()> {
+    let input = many_emails(10_000);
+    let engine = RegexEngine::new(RedactionConfig::load_default_rules()?)?;
+    let item = email_summary(&engine, &input)?;
+    assert_eq!(item.occurrences, 10_000);
+    assert!(item.truncated);
+    assert_eq!(item.original_texts.len(), DEFAULT_SUMMARY_SAMPLES_PER_RULE);
+    assert_eq!(item.sanitized_texts.len(), DEFAULT_SUMMARY_SAMPLES_PER_RULE);
+
+    let again = email_summary(&RegexEngine::new(RedactionConfig::load_default_rules()?)?, &input)?;
+    assert_eq!(again.original_texts, item.original_texts);
+
+    let reseeded = RegexEngine::with_options(
+        RedactionConfig::load_default_rules()?,
+        EngineOptions::default().with_run_seed(b"another run".to_vec()),
+    )?;
+    assert_ne!(email_summary(&reseeded, &input)?.original_texts, item.original_texts);
+
+    let small = email_summary(&engine, &many_emails(3))?;
+    assert!(!small.truncated);
+    assert_eq!(small.original_texts, ["user0@example.com", "user1@example.com", "user2@example.com"]);
+    Ok(())
+}
+
+#[test]
+fn test_profile_samples_config_bounds_summary_texts() -> Result<()> {
+    let options = EngineOptions {
+        samples_config: Some(SamplesConfig { max_per_rule: 5, max_total: 7 }),
+        ..Default::default()
+    };
+    let engine = RegexEngine::with_options(RedactionConfig::load_default_rules()?, options)?;
+    let input = format!("{}{}", many_emails(50), (0..50).map(|i| format!("from 10.0.0.{}\n", i)).collect::<String>());
+    let (_, summary) = engine.sanitize_with(&input, &mut SanitizeContext::new("test"))?;
+
+    let total: usize = summary.iter().map(|item| item.original_texts.len()).sum();
+    assert_eq!(total, 7);
+    for item in &summary {
+        assert_eq!(item.occurrences, 50, "{}", item.rule_name);
+        assert!(item.original_texts.len() <= 5 && item.truncated, "{}", item.rule_name);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_merging_many_lines_keeps_the_sample_cap() -> Result<()> {
+    let engine = RegexEngine::new(RedactionConfig::load_default_rules()?)?;
+    let mut summary = Vec::new();
+    for line in many_emails(500).lines() {
+        let (_, items) = engine.sanitize_with(line, &mut SanitizeContext::new("test"))?;
+        merge_summary(&mut summary, items, engine.get_options());
+    }
+    let item = summary.iter().find(|item| item.rule_name == "email").expect("emails were redacted");
+    assert_eq!(item.occurrences, 500);
+    assert!(item.truncated);
+    assert_eq!(item.original_texts.len(), DEFAULT_SUMMARY_SAMPLES_PER_RULE);
+    assert_eq!(item.sanitized_texts.len(), DEFAULT_SUMMARY_SAMPLES_PER_RULE);
+
+    let bounded = EngineOptions { samples_config: Some(SamplesConfig { max_per_rule: 3, max_total: 4 }), ..Default::default() };
+    let mut summary = Vec::new();
+    for line in ["mail a@example.com", "from 10.0.0.1", "mail b@example.com", "from 10.0.0.2", "mail c@example.com", "from 10.0.0.3"] {
+        let (_, items) = engine.sanitize_with(line, &mut SanitizeContext::new("test"))?;
+        merge_summary(&mut summary, items, &bounded);
+    }
+    // Rules draw on the overall limit in name order.
+    let kept: Vec<(&str, usize, bool)> = summary.iter()
+        .map(|item| (item.rule_name.as_str(), item.original_texts.len(), item.truncated))
+        .collect();
+    assert_eq!(kept, [("email", 3, false), ("ipv4_address", 1, true)]);
+    Ok(())
+}
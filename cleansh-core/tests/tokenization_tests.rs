@@ -0,0 +1,138 @@
+// cleansh-core/tests/tokenization_tests.rs
+use anyhow::Result;
+use cleansh_core::config::{RedactionConfig, RedactionRule};
+use cleansh_core::engine::{SanitizationEngine, SanitizeContext};
+use cleansh_core::engines::entropy_engine::EntropyEngine;
+use cleansh_core::engines::regex_engine::RegexEngine;
+use cleansh_core::profiles::{EngineOptions, PostProcessingConfig};
+
+fn token_rule_config() -> RedactionConfig {
+    RedactionConfig {
+        rules: vec![RedactionRule {
+            name: "api_key".to_string(),
+            pattern: Some(r"sk_[a-z0-9]{8}".to_string()),
+            pattern_type: "regex".to_string(),
+            replace_with: "[API_KEY]".to_string(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+fn token_options(token_format: Option<&str>) -> EngineOptions {
+    EngineOptions {
+        post_processing: Some(PostProcessingConfig {
+            replace_with_token: true,
+            token_format: token_format.map(str::to_string),
+            token_salt: None,
+        }),
+        ..Default::default()
+    }
+}
+
+fn sanitized_texts(engine: &dyn SanitizationEngine, input: &str) -> Result<(String, Vec<String>)> {
+    let (sanitized, summary) = engine.sanitize_with(input, &mut SanitizeContext::new("test"))?;
+    let texts = summary.into_iter().flat_map(|item| item.sanitized_texts).collect();
+    Ok((sanitized, texts))
+}
+
+#[test]
+fn test_regex_tokens_are_deterministic() -> Result<()> {
+    let engine = RegexEngine::with_options(token_rule_config(), token_options(None))?;
+    let input = "first sk_aaaa1111 then sk_bbbb2222 and sk_aaaa1111 again";
+    let (sanitized, _) = sanitized_texts(&engine, input)?;
+
+    let matches = engine.find_matches_for_ui(input, "test")?;
+    assert_eq!(matches.len(), 3);
+    assert_eq!(matches[0].sanitized_string, matches[2].sanitized_string);
+    assert_ne!(matches[0].sanitized_string, matches[1].sanitized_string);
+    assert!(matches[0].sanitized_string.starts_with("[api_key:"));
+    assert_eq!(matches[0].sanitized_string.len(), "[api_key:]".len() + 8);
+
+    assert!(!sanitized.contains("sk_"));
+    assert!(sanitized.contains(&matches[1].sanitized_string));
+    Ok(())
+}
+
+#[test]
+fn test_token_hash_is_keyed_by_the_token_salt() -> Result<()> {
+    let salted = |salt: &str| {
+        let mut options = token_options(None);
+        options.post_processing.as_mut().unwrap().token_salt = Some(salt.to_string());
+        options
+    };
+    let input = "key=sk_aaaa1111";
+    let (_, first) = sanitized_texts(&RegexEngine::with_options(token_rule_config(), salted("org-a"))?, input)?;
+    let (_, again) = sanitized_texts(&RegexEngine::with_options(token_rule_config(), salted("org-a"))?, input)?;
+    let (_, other) = sanitized_texts(&RegexEngine::with_options(token_rule_config(), salted("org-b"))?, input)?;
+    assert_eq!(first, again, "the same salt gives the same token in every run");
+    assert_ne!(first, other);
+
+    // The token does not carry a prefix of the unkeyed hash.
+    let unkeyed = cleansh_core::redaction_match::canonical_sample_hash("api_key", "sk_aaaa1111");
+    assert!(!first[0].contains(&unkeyed[..8]), "{}", first[0]);
+    Ok(())
+}
+
+#[test]
+fn test_regex_custom_token_format() -> Result<()> {
+    let engine = RegexEngine::with_options(token_rule_config(), token_options(Some("<{rule}-{shorthash}>")))?;
+    let (sanitized, texts) = sanitized_texts(&engine, "key=sk_aaaa1111")?;
+    assert_eq!(texts.len(), 1);
+    assert!(texts[0].starts_with("<api_key-") && texts[0].ends_with('>'));
+    assert_eq!(sanitized, format!("key={}", texts[0]));
+    Ok(())
+}
+
+#[test]
+fn test_regex_without_tokenization_uses_replace_with() -> Result<()> {
+    let engine = RegexEngine::new(token_rule_config())?;
+    let (sanitized, _) = sanitized_texts(&engine, "key=sk_aaaa1111")?;
+    assert_eq!(sanitized, "key=[API_KEY]");
+    Ok(())
+}
+
+#[test]
+fn test_invalid_token_format_is_rejected() {
+    let options = token_options(Some("[{unknown_field}]"));
+    assert!(RegexEngine::with_options(token_rule_config(), options.clone()).is_err());
+    assert!(EntropyEngine::with_options(RedactionConfig::default(), options).is_err());
+}
+
+fn entropy_log_with_secret(secret: &str) -> String {
+    format!(
+        "
+        [INFO] 2025-10-20 10:00:00 Service started successfully.
+        [INFO] 2025-10-20 10:00:01 Loading configuration modules...
+        [INFO] 2025-10-20 10:00:02 Database connection established.
+        [DEBUG] AUTH_TOKEN={}
+        [INFO] 2025-10-20 10:00:03 Request processed in 45ms.
+        [INFO] 2025-10-20 10:00:04 Cache refreshed.
+    ",
+        secret
+    )
+}
+
+#[test]
+fn test_entropy_tokens_are_deterministic() -> Result<()> {
+    let engine = EntropyEngine::with_options(RedactionConfig::default(), token_options(None))?;
+    let token_for = |secret: &str| -> Result<String> {
+        let matches = engine.find_matches_for_ui(&entropy_log_with_secret(secret), "test")?;
+        let m = matches
+            .iter()
+            .find(|m| m.original_string == secret)
+            .unwrap_or_else(|| panic!("secret {} was not caught: {:?}", secret, matches));
+        Ok(m.sanitized_string.clone())
+    };
+
+    let first = token_for("7f8a9b2c3d4e5f6a7b8c9d0e1f2a3b4c")?;
+    let repeated = token_for("7f8a9b2c3d4e5f6a7b8c9d0e1f2a3b4c")?;
+    let other = token_for("0e1f2a3b4c7f8a9b2c3d4e5f6a7b8c9d")?;
+    assert_eq!(first, repeated);
+    assert_ne!(first, other);
+    assert!(first.starts_with("[high_entropy_secret:"));
+
+    let (sanitized, _) = sanitized_texts(&engine, &entropy_log_with_secret("7f8a9b2c3d4e5f6a7b8c9d0e1f2a3b4c"))?;
+    assert!(sanitized.contains(&format!("AUTH_TOKEN={}", first)), "unexpected output: {}", sanitized);
+    Ok(())
+}
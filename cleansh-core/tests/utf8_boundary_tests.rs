@@ -0,0 +1,97 @@
+// cleansh-core/tests/utf8_boundary_tests.rs
+use anyhow::Result;
+use cleansh_core::config::{RedactionConfig, RedactionRule};
+use cleansh_core::engine::{SanitizationEngine, SanitizeContext};
+use cleansh_core::engines::entropy_engine::EntropyEngine;
+use cleansh_core::engines::regex_engine::RegexEngine;
+use cleansh_core::redaction_match::{OffsetBasis, RedactionMatch};
+
+const SECRET: &str = "7f8a9b2c3d4e5f6a7b8c9d0e1f2a3b4c";
+
+fn sanitize(engine: &dyn SanitizationEngine, input: &str) -> Result<String> {
+    Ok(engine.sanitize_with(input, &mut SanitizeContext::new("test"))?.0)
+}
+
+fn hex_rule_config() -> RedactionConfig {
+    RedactionConfig {
+        rules: vec![RedactionRule {
+            name: "hex_token".to_string(),
+            pattern: Some(r"[0-9a-f]{32}".to_string()),
+            pattern_type: "regex".to_string(),
+            replace_with: "[HEX]".to_string(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_entropy_secret_adjacent_to_emoji() -> Result<()> {
+    let engine = EntropyEngine::new(RedactionConfig::default())?;
+    let input = format!("deploy 🚀{}🔑 done\n", SECRET);
+    assert_eq!(sanitize(&engine, &input)?, "deploy 🚀[ENTROPY_REDACTED]🔑 done\n");
+    Ok(())
+}
+
+#[test]
+fn test_entropy_secret_inside_cjk_prose() -> Result<()> {
+    let engine = EntropyEngine::new(RedactionConfig::default())?;
+    let input = format!("服务器已启动，会话密钥{}已发放给用户。连接正在等待中。\n", SECRET);
+    assert_eq!(
+        sanitize(&engine, &input)?,
+        "服务器已启动，会话密钥[ENTROPY_REDACTED]已发放给用户。连接正在等待中。\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_regex_offsets_map_through_multibyte_text() -> Result<()> {
+    let engine = RegexEngine::new(hex_rule_config())?;
+    let input = format!("\x1b[31m日志🚀\x1b[0m {}🔑 東京\n", SECRET);
+    assert_eq!(sanitize(&engine, &input)?, "\x1b[31m日志🚀\x1b[0m [HEX]🔑 東京\n");
+
+    let matches = engine.find_matches_for_ui(&input, "test")?;
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].original_string, SECRET);
+    Ok(())
+}
+
+#[test]
+fn test_regex_matches_carry_original_offsets_through_ansi_colors() -> Result<()> {
+    let engine = RegexEngine::new(hex_rule_config())?;
+    let input = format!("\x1b[1;32mINFO\x1b[0m token \x1b[33m{}\x1b[0m issued\n", SECRET);
+    let matches = engine.find_matches_for_ui(&input, "test")?;
+    assert_eq!(matches.len(), 1);
+    let m = &matches[0];
+    assert_eq!(m.offset_basis, OffsetBasis::Stripped);
+    assert_eq!(m.span().start, "INFO token ".len());
+    let original = m.original_span().expect("engines set original offsets");
+    assert_eq!(&input[original.clone()], SECRET);
+    assert_eq!(m.document_span(), original);
+    Ok(())
+}
+
+#[test]
+fn test_entropy_matches_carry_original_offsets_through_ansi_colors() -> Result<()> {
+    let engine = EntropyEngine::new(RedactionConfig::default())?;
+    let input = format!("\x1b[2m2025-01-01\x1b[0m session=\x1b[31m{}\x1b[0m\n", SECRET);
+    let matches = engine.find_matches_for_ui(&input, "test")?;
+    assert_eq!(matches.len(), 1);
+    let original = matches[0].original_span().expect("engines set original offsets");
+    assert_eq!(&input[original], SECRET);
+    assert_eq!(sanitize(&engine, &input)?, "\x1b[2m2025-01-01\x1b[0m session=\x1b[31m[ENTROPY_REDACTED]\x1b[0m\n");
+    Ok(())
+}
+
+#[test]
+fn test_legacy_matches_fall_back_to_their_recorded_offsets() -> Result<()> {
+    let legacy: RedactionMatch = serde_json::from_str(
+        r#"{"rule_name":"email","original_string":"a@b.c","sanitized_string":"[EMAIL]","start":3,"end":8}"#,
+    )?;
+    assert_eq!((legacy.offset_basis, legacy.original_span()), (OffsetBasis::Stripped, None));
+    assert_eq!(legacy.document_span(), 3..8);
+
+    let original = RedactionMatch { start: 5, end: 9, offset_basis: OffsetBasis::Original, ..Default::default() };
+    assert_eq!(original.original_span(), Some(5..9));
+    Ok(())
+}
@@ -0,0 +1,115 @@
+// cleansh-core/tests/validator_tests.rs
+use anyhow::Result;
+use cleansh_core::config::RedactionConfig;
+use cleansh_core::engine::{SanitizationEngine, SanitizeContext};
+use cleansh_core::validators::{
+    is_probable_bip39_mnemonic, is_valid_aba_routing_programmatically, is_valid_iban_programmatically,
+    is_valid_wif_programmatically,
+};
+use cleansh_core::{compile_rules, RegexEngine};
+
+#[test]
+fn test_iban_validator_checks_country_length_and_mod_97() {
+    // Published example IBANs, in print and electronic format.
+    assert!(is_valid_iban_programmatically("GB82 WEST 1234 5698 7654 32"));
+    assert!(is_valid_iban_programmatically("DE89370400440532013000"));
+    assert!(is_valid_iban_programmatically("NO9386011117947"));
+
+    assert!(!is_valid_iban_programmatically("GB82 WEST 1234 5698 7654 33"), "bad checksum");
+    assert!(!is_valid_iban_programmatically("DE8937040044053201300"), "one digit short for DE");
+    assert!(!is_valid_iban_programmatically("ZZ82WEST12345698765432"), "unknown country");
+    assert!(!is_valid_iban_programmatically("GBXX WEST 1234 5698 7654 32"), "letters as check digits");
+}
+
+#[test]
+fn test_aba_validator_checks_prefix_and_3_7_1_weights() {
+    assert!(is_valid_aba_routing_programmatically("011000015"));
+    assert!(is_valid_aba_routing_programmatically("021000021"));
+
+    assert!(!is_valid_aba_routing_programmatically("021000022"), "bad checksum");
+    assert!(!is_valid_aba_routing_programmatically("131000021"), "checksum passes but 13 is not an assigned prefix");
+    assert!(!is_valid_aba_routing_programmatically("02100002"), "eight digits");
+    assert!(!is_valid_aba_routing_programmatically("02100-0021"), "not all digits");
+}
+
+#[test]
+fn test_only_checksum_valid_iban_and_routing_numbers_are_redacted() -> Result<()> {
+    let mut config = RedactionConfig::load_default_rules()?;
+    config.set_active_rules(&["aba_routing".to_string()], &[]);
+    let engine = RegexEngine::new(config)?;
+
+    let input = "refund to GB82 WEST 1234 5698 7654 32 via 021000021; typo GB82 WEST 1234 5698 7654 33 via 021000022";
+    let (sanitized, _) = engine.sanitize_with(input, &mut SanitizeContext::new("test"))?;
+    assert_eq!(
+        sanitized,
+        "refund to [IBAN_REDACTED] via [ABA_ROUTING_REDACTED]; typo GB82 WEST 1234 5698 7654 33 via 021000022"
+    );
+    Ok(())
+}
+
+/// The Bitcoin wiki's example key in WIF, uncompressed and compressed.
+const WIF_UNCOMPRESSED: &str = "5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTJ";
+const WIF_COMPRESSED: &str = "KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP98617";
+
+/// The BIP-39 test vector for all-zero entropy.
+const MNEMONIC_12: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+#[test]
+fn test_wif_validator_checks_layout_and_base58check() {
+    assert!(is_valid_wif_programmatically(WIF_UNCOMPRESSED));
+    assert!(is_valid_wif_programmatically(WIF_COMPRESSED));
+    assert!(is_valid_wif_programmatically("cMzLdeGd5vEqxB8B6VFQoRopQ3sLAAvEzDAoQgvX54xwofSWj1fx"), "testnet, compressed");
+
+    assert!(!is_valid_wif_programmatically("5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTK"), "bad checksum");
+    assert!(!is_valid_wif_programmatically("KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP98618"), "bad checksum");
+    assert!(!is_valid_wif_programmatically("5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyT0"), "0 is not Base58");
+    assert!(!is_valid_wif_programmatically("5HueCGU8rMjxEXxiPuD5BDku4Mk"), "too short");
+}
+
+#[test]
+fn test_mnemonic_validator_needs_twelve_wordlist_words_in_a_row() {
+    assert!(is_probable_bip39_mnemonic(MNEMONIC_12));
+    assert!(is_probable_bip39_mnemonic("legal winner thank year wave sausage worth useful legal winner thank yellow"));
+
+    let eleven = "legal winner thank year wave sausage worth useful legal winner thank";
+    assert!(!is_probable_bip39_mnemonic(eleven));
+    assert!(!is_probable_bip39_mnemonic(&format!("seeds {}", eleven)), "'seeds' is not on the wordlist");
+    assert!(!is_probable_bip39_mnemonic("abandon abandon abandon abandon abandon abandon xyzzy abandon abandon abandon abandon about"));
+}
+
+#[test]
+fn test_crypto_wallet_secrets_are_redacted_only_when_valid() -> Result<()> {
+    let engine = RegexEngine::new(RedactionConfig::load_default_rules()?)?;
+    let redact = |input: &str| -> Result<String> {
+        Ok(engine.sanitize_with(input, &mut SanitizeContext::new("test"))?.0)
+    };
+
+    assert_eq!(redact(&format!("import {} now", WIF_UNCOMPRESSED))?, "import [BITCOIN_WIF_KEY_REDACTED] now");
+    assert_eq!(redact(&format!("wif={}", WIF_COMPRESSED))?, "wif=[BITCOIN_WIF_KEY_REDACTED]");
+    let broken = "5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTK";
+    assert_eq!(redact(&format!("import {} now", broken))?, format!("import {} now", broken));
+
+    assert_eq!(redact(&format!("seed: {}", MNEMONIC_12))?, "seed: [MNEMONIC_PHRASE_REDACTED]");
+    let near_miss = "seeds legal winner thank year wave sausage worth useful legal winner thank";
+    assert_eq!(redact(near_miss)?, near_miss);
+
+    let key = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+    assert_eq!(redact(&format!("ETH_PRIVATE_KEY=0x{}", key))?, "ETH_PRIVATE_KEY=[ETH_PRIVATE_KEY_REDACTED]");
+    assert_eq!(redact(&format!("{{\"privateKey\": \"{}\"}}", key))?, "{\"privateKey\": \"[ETH_PRIVATE_KEY_REDACTED]\"}");
+    let tx = format!("tx hash 0x{}", key);
+    assert_eq!(redact(&tx)?, tx, "a bare 64-hex value has no key context");
+    Ok(())
+}
+
+#[test]
+fn test_compiled_rules_expose_the_attached_validator() -> Result<()> {
+    let config = RedactionConfig::load_default_rules()?;
+    let compiled = compile_rules(config.rules)?;
+    let visa = compiled.find_rule("visa_card").expect("visa_card is a built-in rule");
+    assert_eq!(visa.validator().map(|v| v.name), Some("luhn"));
+    assert!(visa.passes_validation("4111111111111111"));
+    assert!(!visa.passes_validation("4111111111111112"));
+    assert!(compiled.find_rule("email").and_then(|rule| rule.validator()).is_none());
+    assert!(compiled.find_rule("no_such_rule").is_none());
+    Ok(())
+}
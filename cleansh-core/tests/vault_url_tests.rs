@@ -0,0 +1,35 @@
+// cleansh-core/tests/vault_url_tests.rs
+//! Picking the fingerprint vault from its URL.
+use anyhow::Result;
+use cleansh_core::remediation::fingerprint::SecretFingerprint;
+use cleansh_core::remediation::vault::from_url;
+
+#[tokio::test]
+async fn test_vault_url_scheme_picks_the_backend() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("fingerprints.jsonl");
+
+    let file = from_url(&format!("file://{}", path.display()), "acme").await?;
+    assert_eq!(file.name(), "File");
+    file.publish(SecretFingerprint::from_secret("sk_live_leak", "stripe", b"salt")).await?;
+    assert_eq!(file.fetch_all().await?.len(), 1);
+    assert!(path.exists());
+
+    // Redis connects lazily, so no server is needed to build one.
+    #[cfg(feature = "redis")]
+    {
+        assert_eq!(from_url("redis://127.0.0.1:6379/0", "acme").await?.name(), "Redis");
+        assert_eq!(from_url("rediss://cache.example.com", "acme").await?.name(), "Redis");
+    }
+    #[cfg(not(feature = "redis"))]
+    assert!(from_url("redis://127.0.0.1:6379/0", "acme").await.err().unwrap().to_string().contains("`redis` feature"));
+
+    for bad in ["ftp://host/path", "no-scheme", "file://"] {
+        assert!(from_url(bad, "acme").await.is_err(), "{} should be rejected", bad);
+    }
+    #[cfg(not(feature = "s3"))]
+    assert!(from_url("s3://bucket/key", "acme").await.err().unwrap().to_string().contains("`s3` feature"));
+    #[cfg(feature = "s3")]
+    assert!(from_url("s3://bucket", "acme").await.is_err(), "an S3 URL needs a key");
+    Ok(())
+}
@@ -0,0 +1,38 @@
+// cleansh-entropy/benches/entropy_scan.rs
+//! `EntropyEngine::scan` with 16, 24 and 64-byte windows over seeded log text,
+//! and `calculate_shannon_entropy` on its own, from the shared `bench_support`
+//! corpora so results compare across machines.
+
+use cleansh_entropy::bench_support::{log_corpus, random_bytes, DEFAULT_SEED};
+use cleansh_entropy::engine::EntropyEngine;
+use cleansh_entropy::entropy::calculate_shannon_entropy;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+fn window_sizes(c: &mut Criterion) {
+    let input = log_corpus(200, DEFAULT_SEED);
+    let lines: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+    let mut group = c.benchmark_group("entropy_scan_window");
+    group.throughput(Throughput::Bytes(input.len() as u64));
+    for window in [16, 24, 64] {
+        let engine = EntropyEngine::new(0.5, window);
+        group.bench_with_input(BenchmarkId::from_parameter(window), &lines, |b, lines| {
+            b.iter(|| lines.iter().map(|line| engine.scan(black_box(line)).len()).sum::<usize>())
+        });
+    }
+    group.finish();
+}
+
+fn shannon_entropy(c: &mut Criterion) {
+    let mut group = c.benchmark_group("calculate_shannon_entropy");
+    for len in [24, 256, 4096] {
+        let bytes = random_bytes(len, DEFAULT_SEED);
+        group.throughput(Throughput::Bytes(len as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(len), &bytes, |b, bytes| {
+            b.iter(|| calculate_shannon_entropy(black_box(bytes)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, window_sizes, shannon_entropy);
+criterion_main!(benches);
@@ -0,0 +1,119 @@
+// cleansh-entropy/src/bench_support.rs
+//! Deterministic corpora for the benchmarks and `cleansh bench`.
+//!
+//! Every generator takes a seed and draws from [`BenchRng`], a SplitMix64
+//! generator with no platform-dependent state, so the same seed produces the
+//! same bytes everywhere and timings from different machines compare like for
+//! like.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Seed the shipped benchmarks use.
+pub const DEFAULT_SEED: u64 = 0x5EED_C1EA_2025;
+
+const ALPHANUMERIC: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+const WORDS: &[&str] = &[
+    "request", "served", "user", "session", "cache", "refreshed", "worker", "started",
+    "queue", "drained", "connection", "opened", "closed", "region", "status", "ok",
+];
+const LEVELS: &[&str] = &["INFO", "INFO", "INFO", "DEBUG", "WARN", "ERROR"];
+const COLORS: &[&str] = &["\x1b[32m", "\x1b[33m", "\x1b[31m", "\x1b[1;34m", "\x1b[36m"];
+const RESET: &str = "\x1b[0m";
+
+/// SplitMix64: small, fast, and identical on every platform.
+#[derive(Debug, Clone)]
+pub struct BenchRng(u64);
+
+impl BenchRng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`; `n` must not be zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len())]
+    }
+
+    /// `len` random letters and digits.
+    pub fn token(&mut self, len: usize) -> String {
+        (0..len).map(|_| ALPHANUMERIC[self.below(ALPHANUMERIC.len())] as char).collect()
+    }
+}
+
+/// One log line: mostly prose, with an email, IP address, or secret (an AWS key
+/// ID, a GitHub token, or a bare random token) on roughly one line in ten.
+fn log_line(rng: &mut BenchRng, i: usize) -> String {
+    let level = rng.pick(LEVELS);
+    let words: Vec<&str> = (0..4 + rng.below(6)).map(|_| rng.pick(WORDS)).collect();
+    let mut line = format!("2025-01-01T00:00:{:02}Z {} id={} {}", i % 60, level, rng.next_u64() % 1_000_000, words.join(" "));
+    match rng.below(40) {
+        0 => line.push_str(&format!(" user{}@example.com", rng.below(1000))),
+        1 => line.push_str(&format!(" from 10.{}.{}.{}", rng.below(256), rng.below(256), rng.below(256))),
+        2 => line.push_str(&format!(" AWS_ACCESS_KEY_ID=AKIA{}", rng.token(16).to_ascii_uppercase())),
+        3 => line.push_str(&format!(" token=ghp_{}", rng.token(36))),
+        4 => line.push_str(&format!(" session={}", rng.token(32))),
+        _ => {}
+    }
+    line
+}
+
+/// `lines` log lines joined with `\n`.
+pub fn log_corpus(lines: usize, seed: u64) -> String {
+    let mut rng = BenchRng::new(seed);
+    (0..lines).map(|i| log_line(&mut rng, i)).collect::<Vec<_>>().join("\n")
+}
+
+/// Like [`log_corpus`], with every word wrapped in a color escape, as colored
+/// terminal output is.
+pub fn ansi_corpus(lines: usize, seed: u64) -> String {
+    let mut rng = BenchRng::new(seed);
+    (0..lines)
+        .map(|i| {
+            log_line(&mut rng, i)
+                .split(' ')
+                .map(|word| format!("{}{}{}", rng.pick(COLORS), word, RESET))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Random bytes, the worst case for entropy scoring.
+pub fn random_bytes(len: usize, seed: u64) -> Vec<u8> {
+    let mut rng = BenchRng::new(seed);
+    (0..len).map(|_| rng.next_u64() as u8).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corpus_is_deterministic_per_seed() {
+        assert_eq!(log_corpus(200, DEFAULT_SEED), log_corpus(200, DEFAULT_SEED));
+        assert_ne!(log_corpus(200, DEFAULT_SEED), log_corpus(200, DEFAULT_SEED + 1));
+        assert_eq!(log_corpus(200, DEFAULT_SEED).lines().count(), 200);
+    }
+
+    #[test]
+    fn test_ansi_corpus_wraps_words_in_escapes() {
+        let line = ansi_corpus(1, DEFAULT_SEED);
+        assert!(line.starts_with("\x1b["));
+        assert!(line.ends_with(RESET));
+    }
+}
@@ -0,0 +1,170 @@
+// cleansh-entropy/src/classifier/mod.rs
+//! Structural token classification.
+//!
+//! Short secrets (e.g. 16-char hex session IDs) rarely stand out statistically
+//! from their surroundings. This module recognises them by shape instead:
+//! the character set, length, and layout typical of encoded key material.
+
+use crate::entropy::calculate_shannon_entropy;
+
+/// Tokens below this Shannon entropy are treated as filler (`0000aaaa...`) regardless of shape.
+const MIN_SHAPE_ENTROPY: f64 = 3.0;
+
+/// The recognised shapes of encoded key material.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenShape {
+    /// `8-4-4-4-12` hexadecimal groups.
+    Uuid,
+    /// Hexadecimal of at least 16 characters mixing digits and letters.
+    Hex,
+    /// Padded base64 of at least 16 characters.
+    Base64,
+    /// Base58 (Bitcoin alphabet) of at least 20 characters mixing case and digits.
+    Base58,
+}
+
+impl TokenShape {
+    /// The bounded (0.0 - 1.0) confidence contribution for this shape.
+    pub fn confidence(self) -> f64 {
+        match self {
+            TokenShape::Uuid => 1.0,
+            TokenShape::Base64 => 0.8,
+            TokenShape::Hex => 0.7,
+            TokenShape::Base58 => 0.6,
+        }
+    }
+}
+
+/// Classifies a token by its charset composition, or returns `None` when
+/// it does not look like encoded key material.
+pub fn classify(token: &[u8]) -> Option<TokenShape> {
+    if calculate_shannon_entropy(token) < MIN_SHAPE_ENTROPY {
+        return None;
+    }
+    if is_uuid(token) {
+        Some(TokenShape::Uuid)
+    } else if is_hex(token) {
+        Some(TokenShape::Hex)
+    } else if is_padded_base64(token) {
+        Some(TokenShape::Base64)
+    } else if is_base58(token) {
+        Some(TokenShape::Base58)
+    } else {
+        None
+    }
+}
+
+/// The structural confidence contribution for a token (0.0 when unrecognised).
+pub fn structure_score(token: &[u8]) -> f64 {
+    classify(token).map_or(0.0, TokenShape::confidence)
+}
+
+fn is_uuid(token: &[u8]) -> bool {
+    const GROUPS: [usize; 5] = [8, 4, 4, 4, 12];
+    let mut groups = token.split(|&b| b == b'-');
+    GROUPS.iter().all(|&len| {
+        groups
+            .next()
+            .is_some_and(|g| g.len() == len && g.iter().all(u8::is_ascii_hexdigit))
+    }) && groups.next().is_none()
+}
+
+fn is_hex(token: &[u8]) -> bool {
+    token.len() >= 16
+        && token.iter().all(u8::is_ascii_hexdigit)
+        && token.iter().any(u8::is_ascii_digit)
+        && token.iter().any(u8::is_ascii_alphabetic)
+}
+
+fn is_padded_base64(token: &[u8]) -> bool {
+    let body_len = token.iter().rposition(|&b| b != b'=').map_or(0, |p| p + 1);
+    let padding = token.len() - body_len;
+    let body = &token[..body_len];
+
+    token.len() >= 16
+        && token.len().is_multiple_of(4)
+        && (1..=2).contains(&padding)
+        && body.iter().all(|&b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/')
+        && has_mixed_classes(body)
+}
+
+fn is_base58(token: &[u8]) -> bool {
+    token.len() >= 20
+        && token
+            .iter()
+            .all(|&b| b.is_ascii_alphanumeric() && !matches!(b, b'0' | b'O' | b'I' | b'l'))
+        && has_mixed_classes(token)
+}
+
+/// Natural-language words never mix upper case, lower case, and digits.
+fn has_mixed_classes(token: &[u8]) -> bool {
+    token.iter().any(u8::is_ascii_uppercase)
+        && token.iter().any(u8::is_ascii_lowercase)
+        && token.iter().any(u8::is_ascii_digit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::EntropyEngine;
+    extern crate alloc;
+    use alloc::vec::Vec;
+
+    fn found<'a>(engine: &EntropyEngine, text: &'a str) -> Vec<&'a str> {
+        engine
+            .scan(text.as_bytes())
+            .into_iter()
+            .map(|m| &text[m.start..m.end])
+            .collect()
+    }
+
+    #[test]
+    fn test_shapes() {
+        assert_eq!(classify(b"3f2504e0-4f89-11d3-9a0c-0305e82c3301"), Some(TokenShape::Uuid));
+        assert_eq!(classify(b"9f86d081884c7d65"), Some(TokenShape::Hex));
+        assert_eq!(classify(b"c2VjcmV0LXRva2VuLTE="), Some(TokenShape::Base64));
+        assert_eq!(classify(b"3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy"), Some(TokenShape::Base58));
+        assert_eq!(classify(b"00000000aaaaaaaa"), None);
+        assert_eq!(classify(b"internationalization"), None);
+    }
+
+    #[test]
+    fn test_uuid_in_prose_is_detected() {
+        let engine = EntropyEngine::new(0.5, 24);
+        let text = "The request was retried after the gateway accepted session 3f2504e0-4f89-11d3-9a0c-0305e82c3301 and the user moved on.";
+        assert_eq!(found(&engine, text), ["3f2504e0-4f89-11d3-9a0c-0305e82c3301"]);
+    }
+
+    #[test]
+    fn test_uuid_after_benign_label_falls_below_threshold() {
+        let engine = EntropyEngine::new(0.5, 24);
+        let uuid = "3f2504e0-4f89-11d3-9a0c-0305e82c3301";
+        assert!(found(&engine, "GET /orders 200 trace_id=3f2504e0-4f89-11d3-9a0c-0305e82c3301 done").is_empty());
+        assert_eq!(found(&engine, "GET /orders 200 api_key=3f2504e0-4f89-11d3-9a0c-0305e82c3301 done"), [uuid]);
+
+        // With no weight, the benign label no longer lowers it.
+        let lenient = EntropyEngine::new(0.5, 24).with_negative_keyword_weight(0.0);
+        assert_eq!(found(&lenient, "GET /orders 200 trace_id=3f2504e0-4f89-11d3-9a0c-0305e82c3301 done"), [uuid]);
+    }
+
+    #[test]
+    fn test_long_english_word_is_not_detected() {
+        let engine = EntropyEngine::new(0.5, 24);
+        let text = "The committee praised the internationalization effort during the quarterly review meeting.";
+        assert!(found(&engine, text).is_empty());
+    }
+
+    #[test]
+    fn test_git_sha_after_commit_keyword_depends_on_structural_scoring() {
+        // A 40-char SHA barely moves the z-score against prose of similar entropy,
+        // so whether it is flagged is decided by the structural pass.
+        let text = "Deployed the release build from commit 9fceb02d0ae598e95dc970b74767f19372d61af8 to staging today.";
+        let sha = "9fceb02d0ae598e95dc970b74767f19372d61af8";
+
+        let structural = EntropyEngine::new(0.5, 24);
+        assert!(found(&structural, text).contains(&sha));
+
+        let statistical = EntropyEngine::new(0.5, 24).with_structural_scoring(false);
+        assert!(found(&statistical, text).is_empty());
+    }
+}
@@ -1,57 +1,181 @@
 // cleansh-entropy/src/context/mod.rs
 use daachorse::DoubleArrayAhoCorasick;
 extern crate alloc;
-use alloc::vec;
+use alloc::vec::Vec;
 use core::fmt;
 
+/// What the text before a token says about it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextSignal {
+    /// The nearest keyword is a suspicious label such as `api_key`.
+    Positive,
+    /// The nearest keyword is a known-benign label such as `trace_id`.
+    Negative,
+    /// No keyword precedes the token.
+    None,
+}
+
 /// Scans for keywords surrounding a potential secret with word-boundary awareness.
 pub struct ContextScanner {
-    automaton: DoubleArrayAhoCorasick<usize>,
+    automaton: Option<DoubleArrayAhoCorasick<usize>>,
+    /// Demoting terms; see [`DEFAULT_NEGATIVE_KEYWORDS`].
+    negative: Option<DoubleArrayAhoCorasick<usize>>,
 }
 
 impl fmt::Debug for ContextScanner {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("ContextScanner")
          .field("automaton", &"<DoubleArrayAhoCorasick>")
+         .field("negative", &"<DoubleArrayAhoCorasick>")
          .finish()
     }
 }
 
+impl Default for ContextScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The keywords a scanner built with [`ContextScanner::new`] looks for.
+pub const DEFAULT_KEYWORDS: &[&str] = &[
+    "key", "api", "token", "secret", "password", "passwd", "pwd",
+    "auth", "bearer", "access", "id", "credential", "private",
+    "client", "aws", "gcp", "azure", "stripe", "ghp"
+];
+
+/// The known-benign labels a scanner built with [`ContextScanner::new`] demotes,
+/// such as the `id` in `request_id` that would otherwise count as suspicious.
+pub const DEFAULT_NEGATIVE_KEYWORDS: &[&str] = &[
+    "request_id", "trace_id", "build", "sha", "checksum"
+];
+
+/// Builds an automaton over the non-empty, distinct `patterns`, or `None` if there are none.
+fn build_automaton(patterns: &[&str]) -> Option<DoubleArrayAhoCorasick<usize>> {
+    let mut unique: Vec<&str> = Vec::with_capacity(patterns.len());
+    for pattern in patterns {
+        if !pattern.is_empty() && !unique.contains(pattern) {
+            unique.push(pattern);
+        }
+    }
+
+    if unique.is_empty() {
+        return None;
+    }
+
+    Some(DoubleArrayAhoCorasick::new(unique)
+        .expect("Failed to build Aho-Corasick automaton for context scanning"))
+}
+
+/// The end of the last keyword in `window` that sits on word boundaries.
+fn last_keyword(automaton: &DoubleArrayAhoCorasick<usize>, window: &[u8]) -> Option<usize> {
+    let mut last = None;
+    for matched in automaton.find_overlapping_iter(window) {
+        let m_start = matched.start();
+        let m_end = matched.end();
+
+        // Word boundary check: ensure keyword is not surrounded by alphanumeric chars
+        let prefix_ok = m_start == 0 || !window[m_start - 1].is_ascii_alphanumeric();
+        let suffix_ok = m_end == window.len() || !window[m_end].is_ascii_alphanumeric();
+
+        if prefix_ok && suffix_ok {
+            last = last.max(Some(m_end));
+        }
+    }
+    last
+}
+
+/// Whether `gap`, the bytes between a keyword and a scan position, holds only
+/// separators and then the start of a single token (sliding windows may begin
+/// inside the value).
+fn labels_token(gap: &[u8]) -> bool {
+    let value_start = gap.iter().position(u8::is_ascii_alphanumeric).unwrap_or(gap.len());
+    !gap[value_start..].iter().any(u8::is_ascii_whitespace)
+}
+
 impl ContextScanner {
-    /// Creates a new scanner with a default list of suspicious keywords.
+    /// Creates a new scanner with the default suspicious and benign keywords.
     pub fn new() -> Self {
-        let patterns = vec![
-            "key", "api", "token", "secret", "password", "passwd", "pwd", 
-            "auth", "bearer", "access", "id", "credential", "private", 
-            "client", "aws", "gcp", "azure", "stripe", "ghp"
-        ];
-        
-        let automaton = DoubleArrayAhoCorasick::new(patterns)
-            .expect("Failed to build Aho-Corasick automaton for context scanning");
-            
-        Self { automaton }
+        Self::with_keywords(DEFAULT_KEYWORDS)
+    }
+
+    /// Creates a scanner that looks for the given keywords instead of the defaults.
+    /// The default negative keywords still apply. Empty and repeated entries are
+    /// skipped; callers wanting to report them should filter the list beforehand.
+    pub fn with_keywords(patterns: &[&str]) -> Self {
+        Self {
+            automaton: build_automaton(patterns),
+            negative: build_automaton(DEFAULT_NEGATIVE_KEYWORDS),
+        }
+    }
+
+    /// Replaces the negative keywords, skipping empty and repeated entries.
+    pub fn with_negative_keywords(mut self, patterns: &[&str]) -> Self {
+        self.negative = build_automaton(patterns);
+        self
     }
 
     /// Scans the preceding context for keywords.
     /// Employs word-boundary checks to ensure "key" doesn't match "monkey".
-    pub fn scan_preceding_context(&self, text: &[u8], token_start: usize, window_size: usize) -> bool {
-        if token_start == 0 { return false; }
-        
+    ///
+    /// A negative keyword only counts as the token's own label, followed by
+    /// separators (`=`, `:`, quotes, spaces) and then the token, and outweighs any
+    /// positive one: `trace_id=` demotes its value even though it ends in `id`.
+    /// Positive keywords count anywhere in the window.
+    pub fn scan_preceding_context(&self, text: &[u8], token_start: usize, window_size: usize) -> ContextSignal {
+        if token_start == 0 { return ContextSignal::None; }
+
         let start = token_start.saturating_sub(window_size);
         let window = &text[start..token_start];
-        
-        for matched in self.automaton.find_iter(window) {
-            let m_start = matched.start();
-            let m_end = matched.end();
-
-            // Word boundary check: ensure keyword is not surrounded by alphanumeric chars
-            let prefix_ok = m_start == 0 || !window[m_start - 1].is_ascii_alphanumeric();
-            let suffix_ok = m_end == window.len() || !window[m_end].is_ascii_alphanumeric();
-
-            if prefix_ok && suffix_ok {
-                return true;
-            }
+
+        let labelled_benign = self.negative.as_ref()
+            .and_then(|a| last_keyword(a, window))
+            .is_some_and(|end| labels_token(&window[end..]));
+        if labelled_benign {
+            ContextSignal::Negative
+        } else if self.automaton.as_ref().and_then(|a| last_keyword(a, window)).is_some() {
+            ContextSignal::Positive
+        } else {
+            ContextSignal::None
         }
-        false
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_keywords_keep_word_boundaries() {
+        let scanner = ContextScanner::with_keywords(&["licencia", "", "licencia"]);
+        let hit = b"licencia=Zx81Qp";
+        let embedded = b"sublicencias=Zx81Qp";
+
+        assert_eq!(scanner.scan_preceding_context(hit, 9, 48), ContextSignal::Positive);
+        assert_eq!(scanner.scan_preceding_context(embedded, 13, 48), ContextSignal::None);
+        // The custom list replaces the defaults.
+        assert_eq!(scanner.scan_preceding_context(b"token=Zx81Qp", 6, 48), ContextSignal::None);
+    }
+
+    #[test]
+    fn test_empty_keyword_list_never_matches() {
+        let scanner = ContextScanner::with_keywords(&[""]).with_negative_keywords(&[]);
+        assert_eq!(scanner.scan_preceding_context(b"token=Zx81Qp", 6, 48), ContextSignal::None);
+        assert_eq!(scanner.scan_preceding_context(b"trace_id=Zx81Qp", 9, 48), ContextSignal::None);
+    }
+
+    #[test]
+    fn test_negative_keywords_demote_only_the_value_they_label() {
+        let scanner = ContextScanner::new();
+        // `trace_id` outweighs the `id` it ends with.
+        assert_eq!(scanner.scan_preceding_context(b"trace_id=Zx81Qp", 9, 48), ContextSignal::Negative);
+        assert_eq!(scanner.scan_preceding_context(b"trace_id: \"Zx81Qp", 11, 48), ContextSignal::Negative);
+        assert_eq!(scanner.scan_preceding_context(b"user_id=Zx81Qp", 8, 48), ContextSignal::Positive);
+        // Further away, a benign word no longer labels the token.
+        assert_eq!(scanner.scan_preceding_context(b"build 42 token=Zx81Qp", 15, 48), ContextSignal::Positive);
+        assert_eq!(scanner.scan_preceding_context(b"build from commit Zx81Qp", 18, 48), ContextSignal::None);
+        assert_eq!(scanner.scan_preceding_context(b"token for build=Zx81Qp", 16, 48), ContextSignal::Negative);
+        // Negative keywords keep word boundaries too.
+        assert_eq!(scanner.scan_preceding_context(b"rebuild=Zx81Qp", 8, 48), ContextSignal::None);
+    }
+}
@@ -8,9 +8,12 @@
 
 extern crate alloc;
 use alloc::vec::Vec;
-use crate::scanner::{scan_token_against_context, AnomalyScannerConfig};
-use crate::context::ContextScanner;
-use crate::scoring::{calculate_confidence, ScoringWeights};
+use crate::entropy::{calculate_shannon_entropy, WindowedEntropy};
+use crate::scanner::{AnomalyScannerConfig, ContextBaseline};
+use crate::context::{ContextScanner, ContextSignal};
+use crate::scoring::{calculate_confidence, LanguageAdjustments, ScoringWeights};
+use crate::statistics::{TextProfile, TokenLengths};
+use crate::classifier::structure_score;
 
 /// Represents a high-entropy anomaly found in text.
 #[derive(Debug, Clone)]
@@ -21,6 +24,118 @@ pub struct EntropyMatch {
     pub entropy: f64,
 }
 
+/// Which pass of `scan` scored a candidate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CandidateStage {
+    /// A sliding window (Pass 1). Rejected windows are reported once per run
+    /// between accepted ones, as the closest miss of the run.
+    Window,
+    /// A trimmed match re-scored for a benign label before it (Pass 3).
+    BenignLabel,
+    /// A delimited token scored by its shape (Pass 4).
+    TokenShape,
+}
+
+/// The numbers behind one threshold decision.
+#[derive(Debug, Clone)]
+pub struct CandidateScore {
+    pub stage: CandidateStage,
+    pub start: usize,
+    pub end: usize,
+    pub entropy: f64,
+    /// How far the candidate's entropy sits above its line's baseline; `None`
+    /// for benign-label re-scoring, which only adjusts the confidence.
+    pub z_score: Option<f64>,
+    pub context: ContextSignal,
+    pub confidence: f64,
+    pub threshold: f64,
+}
+
+impl CandidateScore {
+    /// Whether the candidate reached the threshold.
+    pub fn accepted(&self) -> bool {
+        self.confidence >= self.threshold
+    }
+}
+
+/// A decision reported by [`EntropyEngine::scan_traced`].
+#[derive(Debug, Clone)]
+pub enum ScanEvent {
+    /// A candidate was scored against the confidence threshold.
+    Scored(CandidateScore),
+    /// A candidate that reached the threshold fell outside the secret length
+    /// bounds and was dropped.
+    LengthRejected { start: usize, end: usize },
+}
+
+/// Matches shorter than this are dropped by default: secrets are rarely < 6 chars.
+pub const DEFAULT_MIN_SECRET_LEN: usize = 6;
+
+/// A `window_size` of zero makes `scan` size its windows from each input's token
+/// lengths instead of using one fixed size.
+pub const AUTO_WINDOW_SIZE: usize = 0;
+
+/// The window size used when none is configured, and by auto-sizing for inputs
+/// without any long token.
+pub const DEFAULT_WINDOW_SIZE: usize = 24;
+
+/// Smallest and largest windows auto-sizing picks. Tokens shorter than the
+/// minimum are ordinary words, which no window needs to fit; above the maximum a
+/// window dilutes all but the longest secrets.
+pub const MIN_AUTO_WINDOW: usize = 12;
+pub const MAX_AUTO_WINDOW: usize = 96;
+
+/// The window sizes an auto-sized scan of `text` uses, smallest first: the
+/// shortest, median, and longest lengths of its tokens of at least
+/// `MIN_AUTO_WINDOW` bytes, capped at `MAX_AUTO_WINDOW` and without duplicates,
+/// or just `DEFAULT_WINDOW_SIZE` when it has no such token. At most three, so an
+/// auto-sized scan costs at most three fixed-window passes.
+pub fn auto_window_sizes(text: &[u8]) -> Vec<usize> {
+    let lengths = TokenLengths::measure(text, MIN_AUTO_WINDOW);
+    if lengths.count == 0 {
+        return alloc::vec![DEFAULT_WINDOW_SIZE];
+    }
+    let mut sizes: Vec<usize> = [lengths.shortest, lengths.median, lengths.longest]
+        .into_iter()
+        .map(|len| len.min(MAX_AUTO_WINDOW))
+        .collect();
+    sizes.dedup();
+    sizes
+}
+
+/// Shrinks `m` to span from the first to the last token of at least
+/// `MIN_AUTO_WINDOW` bytes it overlaps; left as is when it overlaps none.
+fn snap_to_long_tokens(m: &mut EntropyMatch, text: &[u8]) {
+    let mut snapped: Option<(usize, usize)> = None;
+    let mut start = 0;
+    for token in text.split(|b| b.is_ascii_whitespace()) {
+        let end = start + token.len();
+        if start >= m.end {
+            break;
+        }
+        if token.len() >= MIN_AUTO_WINDOW && end > m.start {
+            snapped = Some(snapped.map_or((start, end), |(first, _)| (first, end)));
+        }
+        start = end + 1;
+    }
+    if let Some((start, end)) = snapped {
+        m.start = start;
+        m.end = end;
+    }
+}
+
+/// What happens to a match longer than the engine's maximum secret length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LongSecretPolicy {
+    /// Drop the match; long runs such as inline images are left untouched.
+    Skip,
+    /// Redact the whole match, as if there were no maximum.
+    #[default]
+    RedactAll,
+    /// Redact only the first `max` bytes of the match.
+    RedactPrefix,
+}
+
 /// The main engine responsible for identifying anomalies by combining
 /// statistical sliding-window entropy analysis with contextual keyword matching.
 #[derive(Debug)]
@@ -28,20 +143,119 @@ pub struct EntropyEngine {
     scanner_config: AnomalyScannerConfig,
     context_scanner: ContextScanner,
     scoring_weights: ScoringWeights,
+    language_adjustments: LanguageAdjustments,
     confidence_threshold: f64,
     window_size: usize,
+    structural_scoring: bool,
+    min_secret_len: usize,
+    max_secret_len: Option<usize>,
+    long_secret_policy: LongSecretPolicy,
 }
 
 impl EntropyEngine {
-    /// Initializes a new engine with specific detection parameters.
+    /// Initializes a new engine with specific detection parameters. A
+    /// `window_size` of [`AUTO_WINDOW_SIZE`] sizes the windows per input.
     pub fn new(threshold: f64, window_size: usize) -> Self {
         Self {
             scanner_config: AnomalyScannerConfig::default(),
             context_scanner: ContextScanner::new(),
             scoring_weights: ScoringWeights::default(),
+            language_adjustments: LanguageAdjustments::default(),
             confidence_threshold: threshold,
             window_size,
+            structural_scoring: true,
+            min_secret_len: DEFAULT_MIN_SECRET_LEN,
+            max_secret_len: None,
+            long_secret_policy: LongSecretPolicy::default(),
+        }
+    }
+
+    /// Replaces the keyword scanner used to boost windows preceded by suspicious labels.
+    pub fn with_context_scanner(mut self, context_scanner: ContextScanner) -> Self {
+        self.context_scanner = context_scanner;
+        self
+    }
+
+    /// Sets how much a known-benign label before a token lowers its confidence.
+    pub fn with_negative_keyword_weight(mut self, weight: f64) -> Self {
+        self.scoring_weights.negative_keyword_weight = weight;
+        self
+    }
+
+    /// Enables or disables the token-shape pass (hex, base64, base58, UUID).
+    pub fn with_structural_scoring(mut self, enabled: bool) -> Self {
+        self.structural_scoring = enabled;
+        self
+    }
+
+    /// Replaces the per-script multipliers applied to the scoring weights.
+    pub fn with_language_adjustments(mut self, adjustments: LanguageAdjustments) -> Self {
+        self.language_adjustments = adjustments;
+        self
+    }
+
+    /// Sets the shortest match kept and, optionally, the length above which
+    /// the long secret policy applies (both in bytes).
+    pub fn with_secret_len(mut self, min: usize, max: Option<usize>) -> Self {
+        self.min_secret_len = min;
+        self.max_secret_len = max;
+        self
+    }
+
+    /// Sets the fewest baseline chunks a z-score is computed from and the entropy
+    /// floor that judges windows with fewer (see [`AnomalyScannerConfig`]).
+    pub fn with_baseline_fallback(mut self, min_samples: usize, entropy_floor: f64) -> Self {
+        self.scanner_config.min_baseline_samples = min_samples;
+        self.scanner_config.absolute_entropy_floor = entropy_floor;
+        self
+    }
+
+    /// Sets what happens to matches longer than the maximum secret length.
+    pub fn with_long_secret_policy(mut self, policy: LongSecretPolicy) -> Self {
+        self.long_secret_policy = policy;
+        self
+    }
+
+    /// Applies the secret length bounds to the span `start..end`, returning the
+    /// span to redact or `None` when the match should be dropped.
+    pub fn bound_secret_len(&self, start: usize, end: usize) -> Option<(usize, usize)> {
+        let len = end.saturating_sub(start);
+        if len < self.min_secret_len {
+            return None;
         }
+        match self.max_secret_len {
+            Some(max) if len > max => match self.long_secret_policy {
+                LongSecretPolicy::Skip => None,
+                LongSecretPolicy::RedactAll => Some((start, end)),
+                LongSecretPolicy::RedactPrefix => Some((start, start + max)),
+            },
+            _ => Some((start, end)),
+        }
+    }
+
+    fn apply_secret_len(&self, mut m: EntropyMatch, trace: &mut dyn FnMut(ScanEvent)) -> Option<EntropyMatch> {
+        let Some((start, end)) = self.bound_secret_len(m.start, m.end) else {
+            trace(ScanEvent::LengthRejected { start: m.start, end: m.end });
+            return None;
+        };
+        m.start = start;
+        m.end = end;
+        Some(m)
+    }
+
+    /// Returns the confidence threshold a window must reach to count as a match.
+    pub fn threshold(&self) -> f64 {
+        self.confidence_threshold
+    }
+
+    /// Updates the confidence threshold in place; takes effect on the next `scan`.
+    pub fn set_threshold(&mut self, threshold: f64) {
+        self.confidence_threshold = threshold;
+    }
+
+    /// The fixed window size, or `None` when windows are sized per input.
+    pub fn window_size(&self) -> Option<usize> {
+        (self.window_size != AUTO_WINDOW_SIZE).then_some(self.window_size)
     }
 
     /// Scans a byte slice using a sliding-window approach and refines the boundaries.
@@ -49,49 +263,252 @@ impl EntropyEngine {
     /// This multi-stage pipeline first locates "heat" and then surgically extracts 
     /// the secret core to avoid redacting surrounding natural language.
     pub fn scan(&self, text: &[u8]) -> Vec<EntropyMatch> {
-        if text.len() < self.window_size {
+        self.scan_with_profile(text, &TextProfile::detect(text))
+    }
+
+    /// Like `scan`, but scores against a profile the caller already detected, so a
+    /// stream can keep the profile of its first chunk instead of re-sampling each line.
+    pub fn scan_with_profile(&self, text: &[u8], profile: &TextProfile) -> Vec<EntropyMatch> {
+        self.scan_inner(text, profile, &mut |_| {})
+    }
+
+    /// Like `scan`, but reports each threshold and length decision to `trace`,
+    /// for explaining why a span was or was not matched.
+    pub fn scan_traced(&self, text: &[u8], trace: &mut dyn FnMut(ScanEvent)) -> Vec<EntropyMatch> {
+        self.scan_inner(text, &TextProfile::detect(text), trace)
+    }
+
+    /// `scan_traced` against a profile the caller already detected.
+    pub fn scan_traced_with_profile(&self, text: &[u8], profile: &TextProfile, trace: &mut dyn FnMut(ScanEvent)) -> Vec<EntropyMatch> {
+        self.scan_inner(text, profile, trace)
+    }
+
+    fn scan_inner(&self, text: &[u8], profile: &TextProfile, trace: &mut dyn FnMut(ScanEvent)) -> Vec<EntropyMatch> {
+        if text.len() < self.window_size().unwrap_or(MIN_AUTO_WINDOW) {
             return Vec::new();
         }
+        let weights = self.scoring_weights.adjusted(self.language_adjustments.for_script(profile.script));
+        let baseline = ContextBaseline::new(text, &self.scanner_config);
+
+        // Pass 1: Statistical Locator (Sliding Window)
+        let raw_matches = match self.window_size() {
+            Some(window_size) => self.locate(text, window_size, &baseline, &weights, trace),
+            // Each size is scored against chunks of its own size: a short window
+            // cannot reach the entropy of a longer chunk of ordinary text.
+            None => {
+                let mut raw_matches = Vec::new();
+                for window_size in auto_window_sizes(text) {
+                    let config = AnomalyScannerConfig { window_chunk_size: window_size, ..self.scanner_config.clone() };
+                    raw_matches.extend(self.locate(text, window_size, &ContextBaseline::new(text, &config), &weights, trace));
+                }
+                raw_matches.sort_by_key(|m| m.start);
+                raw_matches
+            }
+        };
+        // Pass 2: Consolidate overlapping windows into single blocks
+        let mut consolidated = self.consolidate_matches(raw_matches);
+        if self.window_size().is_none() {
+            // Auto-sized windows are as long as the long tokens, so one that
+            // caught a token usually started in the prose before it.
+            for m in &mut consolidated {
+                snap_to_long_tokens(m, text);
+            }
+        }
+
+        // Pass 3: Entropy Gradient Extraction (Surgical Trim)
+        // Shrinks boundaries by walking back until entropy drops into natural language patterns.
+        let mut matches = Vec::new();
+        for m in consolidated {
+            let m = self.extract_secret_core(m, text);
+            if let Some(m) = self.demote_benign_label(m, text, &weights, trace).and_then(|m| self.apply_secret_len(m, trace)) {
+                matches.push(m);
+            }
+        }
+
+        // Pass 4: Structural Classifier
+        // Catches short encoded tokens that are too small to move the z-score.
+        // Token boundaries are exact, so these skip the surgical trim and replace
+        // any window match they overlap.
+        if self.structural_scoring {
+            let mut shaped = Vec::new();
+            for m in self.scan_token_shapes(text, &baseline, &weights, trace) {
+                shaped.extend(self.apply_secret_len(m, trace));
+            }
+            if !shaped.is_empty() {
+                matches.retain(|m| !shaped.iter().any(|s| s.start < m.end && m.start < s.end));
+                matches.extend(shaped);
+                matches.sort_by_key(|m| m.start);
+            }
+        }
+        matches
+    }
 
+    /// Slides a `window_size` window over `text` and returns the windows whose
+    /// confidence reaches the threshold, in order.
+    fn locate(
+        &self,
+        text: &[u8],
+        window_size: usize,
+        baseline: &ContextBaseline,
+        weights: &ScoringWeights,
+        trace: &mut dyn FnMut(ScanEvent),
+    ) -> Vec<EntropyMatch> {
         let mut raw_matches = Vec::new();
+        // The best-scoring rejected window since the last accepted one.
+        let mut closest_miss: Option<CandidateScore> = None;
+        if text.len() < window_size {
+            return raw_matches;
+        }
         let mut i = 0;
+        let mut window = WindowedEntropy::new(&text[..window_size]);
+        let mut window_start = 0;
 
-        // Pass 1: Statistical Locator (Sliding Window)
-        while i <= text.len() - self.window_size {
-            let window = &text[i..i + self.window_size];
-            let anomaly = scan_token_against_context(window, text, i, &self.scanner_config);
-            let has_context = self.context_scanner.scan_preceding_context(text, i, 48);
+        // The window's entropy is updated byte by byte rather than recomputed.
+        while i <= text.len() - window_size {
+            while window_start < i {
+                window.slide(text[window_start], text[window_start + window_size]);
+                window_start += 1;
+            }
+            let anomaly = baseline.score(window.entropy(), i, window_size, &self.scanner_config);
+            // A window can start anywhere, so benign labels are judged once the
+            // match is trimmed to its core (Pass 3).
+            let context = match self.context_scanner.scan_preceding_context(text, i, 48) {
+                ContextSignal::Negative => ContextSignal::None,
+                signal => signal,
+            };
 
-            let confidence = calculate_confidence(anomaly.z_score, has_context, &self.scoring_weights)
+            let confidence = calculate_confidence(anomaly.z_score, context, 0.0, weights)
                 .min(10.0);
 
-            if confidence >= self.confidence_threshold {
+            let score = CandidateScore {
+                stage: CandidateStage::Window,
+                start: i,
+                end: i + window_size,
+                entropy: anomaly.token_entropy,
+                z_score: Some(anomaly.z_score),
+                context,
+                confidence,
+                threshold: self.confidence_threshold,
+            };
+            if score.accepted() {
+                if let Some(miss) = closest_miss.take() {
+                    trace(ScanEvent::Scored(miss));
+                }
                 raw_matches.push(EntropyMatch {
                     start: i,
-                    end: i + self.window_size,
+                    end: i + window_size,
                     confidence,
                     entropy: anomaly.token_entropy,
                 });
+                trace(ScanEvent::Scored(score));
                 // Once we find heat, jump half a window to stay efficient
-                i += self.window_size / 2; 
+                i += window_size / 2; 
             } else {
+                if closest_miss.as_ref().is_none_or(|miss| score.confidence > miss.confidence) {
+                    closest_miss = Some(score);
+                }
                 i += 1;
             }
         }
+        if let Some(miss) = closest_miss {
+            trace(ScanEvent::Scored(miss));
+        }
+        raw_matches
+    }
 
-        // Pass 2: Consolidate overlapping windows into single blocks
-        let consolidated = self.consolidate_matches(raw_matches);
+    /// Scores each delimited token by its shape and keeps the ones that reach the threshold.
+    fn scan_token_shapes(
+        &self,
+        text: &[u8],
+        baseline: &ContextBaseline,
+        weights: &ScoringWeights,
+        trace: &mut dyn FnMut(ScanEvent),
+    ) -> Vec<EntropyMatch> {
+        // Encoded tokens are pure ASCII, so emoji and CJK text also end a token.
+        let is_delimiter = |b: u8| {
+            !b.is_ascii()
+                || b.is_ascii_whitespace()
+                || matches!(b, b'"' | b'\'' | b'`' | b',' | b';' | b':' | b'(' | b')' | b'[' | b']' | b'{' | b'}' | b'<' | b'>')
+        };
 
-        // Pass 3: Entropy Gradient Extraction (Surgical Trim)
-        // Shrinks boundaries by walking back until entropy drops into natural language patterns.
-        consolidated
-            .into_iter()
-            .map(|m| self.extract_secret_core(m, text))
-            .filter(|m| (m.end - m.start) >= 6) // Final sanity check: secrets are rarely < 6 chars
-            .collect()
+        let mut found = Vec::new();
+        let mut i = 0;
+        while i < text.len() {
+            if is_delimiter(text[i]) {
+                i += 1;
+                continue;
+            }
+            let token_end = text[i..].iter().position(|&b| is_delimiter(b)).map_or(text.len(), |p| i + p);
+
+            // Drop a `label=` prefix, but keep trailing base64 padding.
+            let mut start = i;
+            if let Some(eq) = text[i..token_end].iter().position(|&b| b == b'=') {
+                if text[i + eq..token_end].iter().any(|&b| b != b'=') {
+                    start = i + eq + 1;
+                }
+            }
+            let mut end = token_end;
+            while end > start && matches!(text[end - 1], b'.' | b'!' | b'?') {
+                end -= 1;
+            }
+
+            let token = &text[start..end];
+            let shape = structure_score(token);
+            if shape > 0.0 {
+                let anomaly = baseline.score(calculate_shannon_entropy(token), start, token.len(), &self.scanner_config);
+                let context = self.context_scanner.scan_preceding_context(text, start, 48);
+                // Short tokens cannot out-score longer baseline chunks, so a negative
+                // z-score is not evidence against a well-formed token.
+                let confidence = calculate_confidence(anomaly.z_score.max(0.0), context, shape, weights)
+                    .min(10.0);
+                let score = CandidateScore {
+                    stage: CandidateStage::TokenShape,
+                    start,
+                    end,
+                    entropy: anomaly.token_entropy,
+                    z_score: Some(anomaly.z_score),
+                    context,
+                    confidence,
+                    threshold: self.confidence_threshold,
+                };
+                if score.accepted() {
+                    found.push(EntropyMatch { start, end, confidence, entropy: anomaly.token_entropy });
+                }
+                trace(ScanEvent::Scored(score));
+            }
+            i = token_end;
+        }
+        found
+    }
+
+    /// Lowers the confidence of a trimmed match labelled by a negative keyword
+    /// (`trace_id=...`), dropping it if it no longer reaches the threshold.
+    fn demote_benign_label(
+        &self,
+        mut m: EntropyMatch,
+        text: &[u8],
+        weights: &ScoringWeights,
+        trace: &mut dyn FnMut(ScanEvent),
+    ) -> Option<EntropyMatch> {
+        if self.context_scanner.scan_preceding_context(text, m.start, 48) == ContextSignal::Negative {
+            m.confidence -= weights.negative_keyword_weight;
+            trace(ScanEvent::Scored(CandidateScore {
+                stage: CandidateStage::BenignLabel,
+                start: m.start,
+                end: m.end,
+                entropy: m.entropy,
+                z_score: None,
+                context: ContextSignal::Negative,
+                confidence: m.confidence,
+                threshold: self.confidence_threshold,
+            }));
+        }
+        (m.confidence >= self.confidence_threshold).then_some(m)
     }
 
     /// Merges overlapping ranges from the sliding window into single contiguous redactions.
+    /// `matches` must be sorted by start; with several window sizes a later window
+    /// can end before an earlier one.
     fn consolidate_matches(&self, matches: Vec<EntropyMatch>) -> Vec<EntropyMatch> {
         if matches.is_empty() { return matches; }
 
@@ -101,7 +518,7 @@ impl EntropyEngine {
         if let Some(mut current) = it.next() {
             for next in it {
                 if next.start <= current.end {
-                    current.end = next.end;
+                    current.end = current.end.max(next.end);
                     current.confidence = current.confidence.max(next.confidence);
                 } else {
                     merged.push(current);
@@ -25,6 +25,78 @@ pub fn calculate_shannon_entropy(data: &[u8]) -> f64 {
     entropy
 }
 
+/// Slides are applied to the running sum, whose rounding error grows with each one;
+/// it is rebuilt from the bucket counts after this many slides.
+const RESYNC_INTERVAL: u32 = 4096;
+
+/// `c * log2(c)`, with `0 * log2(0)` taken as 0.
+fn c_log_c(count: u32) -> f64 {
+    if count == 0 { 0.0 } else { let c = count as f64; c * log2(c) }
+}
+
+/// Shannon entropy of a fixed-size window that slides over a byte slice.
+///
+/// Keeps the byte frequencies of the window and the running sum of `c * log2(c)`
+/// over them, so moving the window by one byte costs four `log2` calls instead of
+/// a pass over the window and all 256 buckets. With `n` bytes in the window,
+/// `H = log2(n) - sum(c * log2(c)) / n`, which agrees with
+/// [`calculate_shannon_entropy`] on the same bytes to within floating-point noise.
+#[derive(Debug, Clone)]
+pub struct WindowedEntropy {
+    counts: [u32; 256],
+    len: usize,
+    sum_c_log_c: f64,
+    slides: u32,
+}
+
+impl WindowedEntropy {
+    /// Starts with `window` as the current window.
+    pub fn new(window: &[u8]) -> Self {
+        let mut counts = [0u32; 256];
+        for &byte in window {
+            counts[byte as usize] += 1;
+        }
+        let mut state = Self { counts, len: window.len(), sum_c_log_c: 0.0, slides: 0 };
+        state.resync();
+        state
+    }
+
+    /// Moves the window one byte forward: `leaving` drops out of the front and
+    /// `entering` joins at the back. `leaving` must currently be in the window.
+    pub fn slide(&mut self, leaving: u8, entering: u8) {
+        if leaving == entering {
+            return;
+        }
+        self.adjust(leaving as usize, -1);
+        self.adjust(entering as usize, 1);
+        self.slides += 1;
+        if self.slides >= RESYNC_INTERVAL {
+            self.resync();
+        }
+    }
+
+    /// The entropy of the current window, in bits per byte.
+    pub fn entropy(&self) -> f64 {
+        if self.len == 0 {
+            return 0.0;
+        }
+        let n = self.len as f64;
+        (log2(n) - self.sum_c_log_c / n).max(0.0)
+    }
+
+    fn adjust(&mut self, bucket: usize, delta: i32) {
+        let old = self.counts[bucket];
+        let new = old.checked_add_signed(delta).expect("byte left a window it was not in");
+        self.counts[bucket] = new;
+        self.sum_c_log_c += c_log_c(new) - c_log_c(old);
+    }
+
+    fn resync(&mut self) {
+        self.sum_c_log_c = self.counts.iter().map(|&c| c_log_c(c)).sum();
+        self.slides = 0;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -44,4 +116,55 @@ mod tests {
         let entropy = calculate_shannon_entropy(b"abcdefgh");
         assert!((entropy - 3.0).abs() < 1e-10);
     }
+
+    /// xorshift64*, so the property test needs no dev-dependencies.
+    struct TestRng(u64);
+
+    impl TestRng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 >> 12;
+            self.0 ^= self.0 << 25;
+            self.0 ^= self.0 >> 27;
+            self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+        }
+
+        fn below(&mut self, bound: usize) -> usize {
+            (self.next() % bound as u64) as usize
+        }
+    }
+
+    #[test]
+    fn test_windowed_entropy_matches_batch_on_random_inputs() {
+        let mut rng = TestRng(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..200 {
+            // Alternate narrow and full byte ranges so both repeated and distinct bytes occur.
+            let alphabet = [2, 16, 64, 256][rng.below(4)];
+            let text: alloc::vec::Vec<u8> = (0..1 + rng.below(600)).map(|_| rng.below(alphabet) as u8).collect();
+            let window = 1 + rng.below(text.len().min(64));
+
+            let mut windowed = WindowedEntropy::new(&text[..window]);
+            for start in 0..=text.len() - window {
+                if start > 0 {
+                    windowed.slide(text[start - 1], text[start + window - 1]);
+                }
+                let batch = calculate_shannon_entropy(&text[start..start + window]);
+                assert!((windowed.entropy() - batch).abs() < 1e-9, "window {} at {}", window, start);
+            }
+        }
+    }
+
+    #[test]
+    fn test_windowed_entropy_stays_accurate_across_resyncs() {
+        let mut rng = TestRng(42);
+        let text: alloc::vec::Vec<u8> = (0..3 * RESYNC_INTERVAL as usize).map(|_| rng.next() as u8).collect();
+        let window = 24;
+        let mut windowed = WindowedEntropy::new(&text[..window]);
+        for start in 1..=text.len() - window {
+            windowed.slide(text[start - 1], text[start + window - 1]);
+        }
+        let batch = calculate_shannon_entropy(&text[text.len() - window..]);
+        assert!((windowed.entropy() - batch).abs() < 1e-9);
+        assert_eq!(WindowedEntropy::new(b"").entropy(), 0.0);
+        assert_eq!(WindowedEntropy::new(b"aaaa").entropy(), 0.0);
+    }
 }
\ No newline at end of file
@@ -11,7 +11,10 @@ pub mod scanner;
 pub mod statistics;
 pub mod context;
 pub mod scoring;
+pub mod classifier;
 pub mod engine;
+#[cfg(feature = "bench")]
+pub mod bench_support;
 
 /// Common type definitions
 pub type EntropyScore = f64;
\ No newline at end of file
@@ -1,13 +1,20 @@
 // cleansh-entropy/src/scanner/mod.rs
 extern crate alloc;
 use crate::entropy::calculate_shannon_entropy;
-use crate::statistics::{compute_stats, EntropyStats};
+use crate::statistics::{compute_stats, EntropyStats, TextProfile};
 use alloc::vec::Vec;
 
 #[derive(Debug, Clone)]
 pub struct AnomalyScannerConfig {
     pub z_score_threshold: f64,
     pub window_chunk_size: usize, 
+    /// Fewest baseline chunks a z-score is computed from. With fewer left once the
+    /// candidate's own chunks are excluded (a secret filling a short line), the
+    /// candidate is judged against `absolute_entropy_floor` instead.
+    pub min_baseline_samples: usize,
+    /// Entropy, in bits per byte, at which a candidate without enough baseline
+    /// counts as an anomaly.
+    pub absolute_entropy_floor: f64,
 }
 
 impl Default for AnomalyScannerConfig {
@@ -15,6 +22,8 @@ impl Default for AnomalyScannerConfig {
         Self {
             z_score_threshold: 3.0,
             window_chunk_size: 32,
+            min_baseline_samples: 2,
+            absolute_entropy_floor: 4.0,
         }
     }
 }
@@ -28,18 +37,26 @@ pub struct AnomalyResult {
 }
 
 /// A high-level scanner that iterates over a string to find statistical anomalies.
-/// Now uses manual index tracking to avoid the "repeating token" find() bug.
+///
+/// Tokens are the whitespace-separated runs of the input. Each result carries its
+/// byte span, so a token that occurs several times is still located exactly; the
+/// span always falls on `char` boundaries, so `&input[start..end]` is valid on
+/// any UTF-8 text. Use [`Scanner::char_offsets`] for character positions instead.
 pub struct Scanner<'a> {
     input: &'a str,
-    remaining: &'a str,
     byte_offset: usize,
     config: AnomalyScannerConfig,
+    profile: TextProfile,
+    baseline: ContextBaseline,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ScanResult<'a> {
+    /// The token, borrowed from the input: `&input[start..end]`.
     pub token: &'a str,
+    /// Byte offset of the token's first byte.
     pub start: usize,
+    /// Byte offset just past the token.
     pub end: usize,
     pub is_anomaly: bool,
     pub z_score: f64,
@@ -47,46 +64,55 @@ pub struct ScanResult<'a> {
 
 impl<'a> Scanner<'a> {
     pub fn new(input: &'a str) -> Self {
+        let config = AnomalyScannerConfig::default();
         Self {
             input,
-            remaining: input,
             byte_offset: 0,
-            config: AnomalyScannerConfig::default(),
+            baseline: ContextBaseline::new(input.as_bytes(), &config),
+            config,
+            profile: TextProfile::detect(input.as_bytes()),
         }
     }
+
+    /// The script profile of the input, sampled once when the scanner was created.
+    pub fn profile(&self) -> &TextProfile {
+        &self.profile
+    }
+
+    /// Reports each token's span in characters rather than bytes, e.g. for
+    /// highlighting in a terminal.
+    pub fn char_offsets(self) -> CharOffsets<'a> {
+        CharOffsets { scanner: self, byte: 0, chars: 0 }
+    }
+
+    /// The byte span of the next whitespace-separated token at or after `from`.
+    fn next_token(&self, from: usize) -> Option<(usize, usize)> {
+        let rest = &self.input[from..];
+        let start = from + rest.char_indices().find(|(_, c)| !c.is_whitespace())?.0;
+        let end = self.input[start..].char_indices()
+            .find(|(_, c)| c.is_whitespace())
+            .map_or(self.input.len(), |(i, _)| start + i);
+        Some((start, end))
+    }
 }
 
 impl<'a> Iterator for Scanner<'a> {
     type Item = ScanResult<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        // 1. Skip leading whitespace
-        let trimmed_start = self.remaining.trim_start_matches(|c: char| c.is_whitespace());
-        let leading_whitespace_len = self.remaining.len() - trimmed_start.len();
-        self.byte_offset += leading_whitespace_len;
-        self.remaining = trimmed_start;
-
-        if self.remaining.is_empty() { return None; }
-
-        // 2. Extract the next token
-        let token_str = self.remaining.split_whitespace().next()?;
-        let start = self.byte_offset;
-        let end = start + token_str.len();
-
-        // 3. Update state for next call
+        let (start, end) = self.next_token(self.byte_offset)?;
         self.byte_offset = end;
-        self.remaining = &self.remaining[token_str.len()..];
 
-        // 4. Perform the scan
-        let result = scan_token_against_context(
-            token_str.as_bytes(),
-            self.input.as_bytes(),
+        let token = &self.input[start..end];
+        let result = self.baseline.score(
+            calculate_shannon_entropy(token.as_bytes()),
             start,
+            token.len(),
             &self.config
         );
 
         Some(ScanResult {
-            token: token_str,
+            token,
             start,
             end,
             is_anomaly: result.is_anomaly,
@@ -95,53 +121,190 @@ impl<'a> Iterator for Scanner<'a> {
     }
 }
 
+/// A [`Scanner`] that also yields each token's `(char_start, char_end)`, counted in
+/// `char`s from the start of the input. Built with [`Scanner::char_offsets`].
+pub struct CharOffsets<'a> {
+    scanner: Scanner<'a>,
+    byte: usize,
+    chars: usize,
+}
+
+impl<'a> CharOffsets<'a> {
+    /// Advances the running character count to byte offset `to` (never backwards).
+    fn chars_to(&mut self, to: usize) -> usize {
+        self.chars += self.scanner.input[self.byte..to].chars().count();
+        self.byte = to;
+        self.chars
+    }
+}
+
+impl<'a> Iterator for CharOffsets<'a> {
+    type Item = (usize, usize, ScanResult<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.scanner.next()?;
+        let char_start = self.chars_to(result.start);
+        let char_end = self.chars_to(result.end);
+        Some((char_start, char_end, result))
+    }
+}
+
+/// Scores `token`, found at `token_offset` in `context`, against the entropy of
+/// the context's chunks that do not overlap it, so the token never inflates its
+/// own baseline.
 pub fn scan_token_against_context(
     token: &[u8],
     context: &[u8],
     token_offset: usize,
     config: &AnomalyScannerConfig,
 ) -> AnomalyResult {
-    let token_entropy = calculate_shannon_entropy(token);
-    let token_len = token.len();
-    let token_end = token_offset + token_len;
-    
-    let mut context_entropies = Vec::with_capacity(128); 
-    let step = config.window_chunk_size.max(8);
-
-    for (i, chunk) in context.chunks(step).enumerate() {
-        let chunk_start = i * step;
-        let chunk_end = chunk_start + chunk.len();
-
-        // Strict Leave-One-Out: Skip chunks that overlap the candidate
-        if chunk_start < token_end && chunk_end > token_offset {
-            continue;
-        }
+    ContextBaseline::new(context, config).score(calculate_shannon_entropy(token), token_offset, token.len(), config)
+}
+
+/// Baseline samples are capped at this many chunks per candidate.
+const MAX_BASELINE_CHUNKS: usize = 128;
+
+/// The entropy of every baseline chunk of a context, computed once so that many
+/// candidates in the same text (e.g. every position of a sliding window) can be
+/// scored without re-reading the context.
+#[derive(Debug, Clone)]
+pub struct ContextBaseline {
+    step: usize,
+    context_len: usize,
+    chunk_entropies: Vec<f64>,
+}
 
-        if context_entropies.len() >= 128 { break; }
-        context_entropies.push(calculate_shannon_entropy(chunk));
+impl ContextBaseline {
+    pub fn new(context: &[u8], config: &AnomalyScannerConfig) -> Self {
+        let step = config.window_chunk_size.max(8);
+        Self {
+            step,
+            context_len: context.len(),
+            chunk_entropies: context.chunks(step).map(calculate_shannon_entropy).collect(),
+        }
     }
 
-    if context_entropies.is_empty() {
-        return AnomalyResult {
-            is_anomaly: false,
-            token_entropy,
-            baseline_stats: EntropyStats { mean: 0.0, std_dev: 0.0, sample_count: 0 },
-            z_score: 0.0,
+    /// Scores a candidate of `token_len` bytes at `token_offset` whose entropy the
+    /// caller already knows, against the chunks that do not overlap it.
+    ///
+    /// With fewer than `min_baseline_samples` such chunks the candidate is an
+    /// anomaly when its entropy reaches `absolute_entropy_floor`, and its z-score
+    /// is reported as `z_score_threshold` if so and 0 otherwise.
+    pub fn score(
+        &self,
+        token_entropy: f64,
+        token_offset: usize,
+        token_len: usize,
+        config: &AnomalyScannerConfig,
+    ) -> AnomalyResult {
+        let token_end = token_offset + token_len;
+
+        let mut context_entropies = Vec::with_capacity(MAX_BASELINE_CHUNKS);
+        for (i, &entropy) in self.chunk_entropies.iter().enumerate() {
+            let chunk_start = i * self.step;
+            let chunk_end = (chunk_start + self.step).min(self.context_len);
+
+            // Strict Leave-One-Out: Skip chunks that overlap the candidate
+            if chunk_start < token_end && chunk_end > token_offset {
+                continue;
+            }
+
+            if context_entropies.len() >= MAX_BASELINE_CHUNKS { break; }
+            context_entropies.push(entropy);
+        }
+
+        let stats = compute_stats(&context_entropies);
+        if context_entropies.len() < config.min_baseline_samples.max(1) {
+            let is_anomaly = token_entropy >= config.absolute_entropy_floor;
+            return AnomalyResult {
+                is_anomaly,
+                token_entropy,
+                baseline_stats: stats,
+                z_score: if is_anomaly { config.z_score_threshold } else { 0.0 },
+            };
+        }
+
+        let z_score = if stats.std_dev > 0.0 {
+            (token_entropy - stats.mean) / stats.std_dev
+        } else {
+            if token_entropy > stats.mean { 100.0 } else { 0.0 }
         };
+
+        AnomalyResult {
+            is_anomaly: z_score > config.z_score_threshold,
+            token_entropy,
+            baseline_stats: stats,
+            z_score,
+        }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
 
-    let stats = compute_stats(&context_entropies);
+    const SECRET: &str = "Zk9qW2xR7vBn4TmP1sLc8yHdQe3uA7fG";
 
-    let z_score = if stats.std_dev > 0.0 {
-        (token_entropy - stats.mean) / stats.std_dev
-    } else {
-        if token_entropy > stats.mean { 100.0 } else { 0.0 }
-    };
+    #[test]
+    fn test_secret_filling_a_short_line_falls_back_to_the_entropy_floor() {
+        let line = format!("API_KEY={}", SECRET);
+        assert_eq!(line.len(), 40);
+        let config = AnomalyScannerConfig::default();
+        let result = scan_token_against_context(SECRET.as_bytes(), line.as_bytes(), 8, &config);
+        assert_eq!(result.baseline_stats.sample_count, 0);
+        assert!(result.is_anomaly, "{:?}", result);
+        assert_eq!(result.z_score, config.z_score_threshold);
+
+        let strict = AnomalyScannerConfig { absolute_entropy_floor: 5.5, ..config };
+        assert!(!scan_token_against_context(SECRET.as_bytes(), line.as_bytes(), 8, &strict).is_anomaly);
+    }
 
-    AnomalyResult {
-        is_anomaly: z_score > config.z_score_threshold,
-        token_entropy,
-        baseline_stats: stats,
-        z_score,
+    #[test]
+    fn test_token_is_excluded_from_its_own_baseline() {
+        let prose = "the quick brown fox jumps over the lazy dog and keeps running far away ";
+        let line = format!("{}{}{}", prose, SECRET, prose);
+        let config = AnomalyScannerConfig { window_chunk_size: 8, ..Default::default() };
+        let result = scan_token_against_context(SECRET.as_bytes(), line.as_bytes(), prose.len(), &config);
+        assert!(result.is_anomaly, "{:?}", result);
+        // Every chunk but the ones covering the secret is in the baseline.
+        let overlapping = (prose.len() + SECRET.len()).div_ceil(8) - prose.len() / 8;
+        assert_eq!(result.baseline_stats.sample_count, line.len().div_ceil(8) - overlapping);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_token_in_high_entropy_context_is_not_an_anomaly() {
+        // Chunks of 32 distinct characters: as random as 32 bytes can be.
+        let alphabet = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+        let noise: alloc::string::String = (0..4).map(|i| &alphabet[i * 8..i * 8 + 32]).collect();
+        let line = format!("{}{}{}", noise, SECRET, noise);
+        let config = AnomalyScannerConfig::default();
+        let result = scan_token_against_context(SECRET.as_bytes(), line.as_bytes(), noise.len(), &config);
+        assert!(result.baseline_stats.sample_count >= config.min_baseline_samples);
+        assert!(!result.is_anomaly, "{:?}", result);
+    }
+
+    #[test]
+    fn test_repeated_token_gets_each_offset() {
+        let input = "key=Zx81Qp  ok\tkey=Zx81Qp\n";
+        let spans: Vec<(&str, usize, usize)> = Scanner::new(input).map(|r| (r.token, r.start, r.end)).collect();
+        assert_eq!(spans, [("key=Zx81Qp", 0, 10), ("ok", 12, 14), ("key=Zx81Qp", 15, 25)]);
+        for (token, start, end) in spans {
+            assert_eq!(&input[start..end], token);
+        }
+    }
+
+    #[test]
+    fn test_offsets_stay_on_char_boundaries() {
+        // U+3000 is a multi-byte whitespace character.
+        let input = "пароль\u{3000}ключ·7f3a ключ·7f3a";
+        let results: Vec<(usize, usize, ScanResult)> = Scanner::new(input).char_offsets().collect();
+        let tokens: Vec<&str> = results.iter().map(|(_, _, r)| r.token).collect();
+        assert_eq!(tokens, ["пароль", "ключ·7f3a", "ключ·7f3a"]);
+
+        let (_, _, second) = &results[1];
+        assert_eq!((second.start, second.end), (15, 29));
+        let chars: Vec<(usize, usize)> = results.iter().map(|(s, e, _)| (*s, *e)).collect();
+        assert_eq!(chars, [(0, 6), (7, 16), (17, 26)]);
+    }
+}
@@ -1,10 +1,21 @@
 // cleansh-entropy/src/scoring/mod.rs
+use crate::context::ContextSignal;
+use crate::statistics::Script;
+
+/// Default [`ScoringWeights::negative_keyword_weight`], as large as the keyword
+/// boost: a well-formed UUID labelled `trace_id=` drops below the default threshold.
+pub const DEFAULT_NEGATIVE_KEYWORD_WEIGHT: f64 = 2.0;
 
 /// Weights for the confidence calculation.
 #[derive(Debug, Clone)] // <--- Added Debug (just to be safe)
 pub struct ScoringWeights {
     pub z_score_weight: f64,
     pub keyword_match_weight: f64,
+    /// Subtracted when a known-benign label such as `trace_id` precedes the token.
+    pub negative_keyword_weight: f64,
+    pub structure_weight: f64,
+    /// The z-score that earns the full `z_score_weight`.
+    pub normalization_divisor: f64,
 }
 
 impl Default for ScoringWeights {
@@ -12,23 +23,115 @@ impl Default for ScoringWeights {
         Self {
             z_score_weight: 1.0,
             keyword_match_weight: 2.0, 
+            negative_keyword_weight: DEFAULT_NEGATIVE_KEYWORD_WEIGHT,
+            structure_weight: 1.0,
+            normalization_divisor: 5.0,
+        }
+    }
+}
+
+impl ScoringWeights {
+    /// Returns a copy with the z-score terms scaled by `adjustment`.
+    pub fn adjusted(&self, adjustment: &LanguageAdjustment) -> Self {
+        Self {
+            z_score_weight: self.z_score_weight * adjustment.z_score_weight,
+            normalization_divisor: self.normalization_divisor * adjustment.normalization_divisor,
+            ..self.clone()
+        }
+    }
+}
+
+/// Multipliers applied to `ScoringWeights` for documents of one script.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LanguageAdjustment {
+    pub z_score_weight: f64,
+    pub normalization_divisor: f64,
+}
+
+impl Default for LanguageAdjustment {
+    fn default() -> Self {
+        Self { z_score_weight: 1.0, normalization_divisor: 1.0 }
+    }
+}
+
+/// Per-script scoring multipliers, selected by the document's `TextProfile`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageAdjustments {
+    pub latin: LanguageAdjustment,
+    pub cyrillic: LanguageAdjustment,
+    pub cjk: LanguageAdjustment,
+    pub mixed: LanguageAdjustment,
+}
+
+impl Default for LanguageAdjustments {
+    /// Two- and three-byte scripts push ordinary words towards the z-scores of
+    /// ASCII secrets, so they need a larger z-score to earn the same confidence.
+    fn default() -> Self {
+        let multibyte = LanguageAdjustment { z_score_weight: 1.0, normalization_divisor: 1.5 };
+        Self {
+            latin: LanguageAdjustment::default(),
+            cyrillic: multibyte,
+            cjk: multibyte,
+            mixed: LanguageAdjustment { z_score_weight: 1.0, normalization_divisor: 1.25 },
+        }
+    }
+}
+
+impl LanguageAdjustments {
+    pub fn for_script(&self, script: Script) -> &LanguageAdjustment {
+        match script {
+            Script::Latin => &self.latin,
+            Script::Cyrillic => &self.cyrillic,
+            Script::Cjk => &self.cjk,
+            Script::Mixed => &self.mixed,
         }
     }
 }
 
 /// Calculates a confidence score (0.0 - 1.0+) for a candidate token.
+/// A negative context can take the score below zero.
 pub fn calculate_confidence(
     z_score: f64,
-    has_keyword_context: bool,
+    context: ContextSignal,
+    structure_score: f64,
     weights: &ScoringWeights,
 ) -> f64 {
-    let entropy_contribution = (z_score / 5.0).min(1.0) * weights.z_score_weight;
+    let entropy_contribution = (z_score / weights.normalization_divisor).min(1.0) * weights.z_score_weight;
     
-    let context_contribution = if has_keyword_context {
-        weights.keyword_match_weight
-    } else {
-        0.0
+    let context_contribution = match context {
+        ContextSignal::Positive => weights.keyword_match_weight,
+        ContextSignal::Negative => -weights.negative_keyword_weight,
+        ContextSignal::None => 0.0,
     };
 
-    entropy_contribution + context_contribution
-}
\ No newline at end of file
+    let structure_contribution = structure_score.clamp(0.0, 1.0) * weights.structure_weight;
+
+    entropy_contribution + context_contribution + structure_contribution
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjustment_scales_z_score_contribution() {
+        let weights = ScoringWeights::default();
+        let cyrillic = weights.adjusted(LanguageAdjustments::default().for_script(Script::Cyrillic));
+
+        let latin_score = calculate_confidence(3.0, ContextSignal::None, 0.0, &weights);
+        let cyrillic_score = calculate_confidence(3.0, ContextSignal::None, 0.0, &cyrillic);
+        assert!((latin_score - 0.6).abs() < 1e-10);
+        assert!((cyrillic_score - 0.4).abs() < 1e-10);
+        // Keyword and structure terms are not scaled.
+        assert_eq!(cyrillic.keyword_match_weight, weights.keyword_match_weight);
+    }
+
+    #[test]
+    fn test_negative_context_subtracts_its_weight() {
+        let weights = ScoringWeights { negative_keyword_weight: 0.25, ..ScoringWeights::default() };
+        let neutral = calculate_confidence(2.5, ContextSignal::None, 1.0, &weights);
+        assert!((neutral - 1.5).abs() < 1e-10);
+        assert!((calculate_confidence(2.5, ContextSignal::Negative, 1.0, &weights) - 1.25).abs() < 1e-10);
+        assert!((calculate_confidence(2.5, ContextSignal::Positive, 1.0, &weights) - 3.5).abs() < 1e-10);
+    }
+}
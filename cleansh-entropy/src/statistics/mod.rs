@@ -52,6 +52,139 @@ pub fn compute_stats(values: &[f64]) -> EntropyStats {
     }
 }
 
+/// How many leading bytes of the input `TextProfile::detect` samples.
+pub const PROFILE_SAMPLE_BYTES: usize = 4096;
+
+/// The dominant writing system of a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Script {
+    /// Mostly ASCII letters (English and other Latin-alphabet logs).
+    #[default]
+    Latin,
+    /// Mostly Cyrillic letters; each one is two UTF-8 bytes.
+    Cyrillic,
+    /// Mostly Han, Kana, or Hangul characters; each one is three UTF-8 bytes.
+    Cjk,
+    /// No script accounts for a clear majority of the letters.
+    Mixed,
+}
+
+/// Character class ratios over a sample of the input, used to tune the
+/// entropy baseline for non-English text.
+///
+/// Multi-byte scripts raise the byte-level entropy of ordinary words, so a
+/// baseline built from Cyrillic or CJK text needs different scoring than one
+/// built from ASCII.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TextProfile {
+    /// The script classification derived from the ratios below.
+    pub script: Script,
+    /// Share of sampled characters that are ASCII letters.
+    pub ascii_letters: f64,
+    /// Share of sampled characters that are Cyrillic letters.
+    pub cyrillic: f64,
+    /// Share of sampled characters that are CJK ideographs, Kana, or Hangul.
+    pub cjk: f64,
+    /// Share of sampled characters that are ASCII digits.
+    pub digits: f64,
+    /// The number of characters sampled.
+    pub sample_chars: usize,
+}
+
+impl TextProfile {
+    /// Profiles the first `PROFILE_SAMPLE_BYTES` of `text`. Invalid UTF-8 ends the sample.
+    pub fn detect(text: &[u8]) -> Self {
+        let sample = &text[..text.len().min(PROFILE_SAMPLE_BYTES)];
+        let sample = match core::str::from_utf8(sample) {
+            Ok(s) => s,
+            // A cut multi-byte character or binary data: keep the valid prefix.
+            Err(e) => core::str::from_utf8(&sample[..e.valid_up_to()]).unwrap_or_default(),
+        };
+
+        let (mut total, mut ascii, mut cyrillic, mut cjk, mut digits) = (0usize, 0usize, 0usize, 0usize, 0usize);
+        for c in sample.chars() {
+            total += 1;
+            match c {
+                'a'..='z' | 'A'..='Z' => ascii += 1,
+                '0'..='9' => digits += 1,
+                '\u{0400}'..='\u{04FF}' => cyrillic += 1,
+                '\u{3040}'..='\u{30FF}' | '\u{4E00}'..='\u{9FFF}' | '\u{AC00}'..='\u{D7AF}' => cjk += 1,
+                _ => {}
+            }
+        }
+        if total == 0 {
+            return Self::default();
+        }
+
+        let letters = ascii + cyrillic + cjk;
+        let script = if letters == 0 || ascii * 10 >= letters * 8 {
+            Script::Latin
+        } else if cyrillic * 2 > letters {
+            Script::Cyrillic
+        } else if cjk * 2 > letters {
+            Script::Cjk
+        } else {
+            Script::Mixed
+        };
+
+        let ratio = |count: usize| count as f64 / total as f64;
+        Self {
+            script,
+            ascii_letters: ratio(ascii),
+            cyrillic: ratio(cyrillic),
+            cjk: ratio(cjk),
+            digits: ratio(digits),
+            sample_chars: total,
+        }
+    }
+}
+
+/// Tokens longer than this count as this long in [`TokenLengths::measure`].
+pub const MAX_MEASURED_TOKEN_LEN: usize = 128;
+
+/// Length distribution of the whitespace-delimited tokens of an input that are
+/// at least some minimum length, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TokenLengths {
+    /// The number of tokens measured.
+    pub count: usize,
+    pub shortest: usize,
+    pub median: usize,
+    /// The longest token, capped at `MAX_MEASURED_TOKEN_LEN`.
+    pub longest: usize,
+}
+
+impl TokenLengths {
+    /// Builds a histogram of the lengths of the tokens of `text` that are at
+    /// least `min_len` bytes long, in one pass, and reads the quantiles off it.
+    pub fn measure(text: &[u8], min_len: usize) -> Self {
+        let mut histogram = [0usize; MAX_MEASURED_TOKEN_LEN + 1];
+        let mut count = 0;
+        for token in text.split(|b| b.is_ascii_whitespace()).filter(|t| t.len() >= min_len.max(1)) {
+            histogram[token.len().min(MAX_MEASURED_TOKEN_LEN)] += 1;
+            count += 1;
+        }
+        if count == 0 {
+            return Self::default();
+        }
+
+        // The length of the `rank`-th shortest token (0-based).
+        let quantile = |rank: usize| {
+            let mut seen = 0;
+            histogram.iter().position(|&n| {
+                seen += n;
+                seen > rank
+            }).unwrap_or(MAX_MEASURED_TOKEN_LEN)
+        };
+        Self {
+            count,
+            shortest: quantile(0),
+            median: quantile(count / 2),
+            longest: quantile(count - 1),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -69,4 +202,35 @@ mod tests {
         assert!(stats.std_dev > 2.13); // Confirms Bessel correction is active
         assert_eq!(stats.sample_count, 8);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_token_lengths_skip_short_tokens() {
+        let lengths = TokenLengths::measure(b"pin Xq7Rz2Lm9Vb4Tn8K and\tshortish_token_x plus aVeryLongTokenThatGoesOnAndOn", 12);
+        assert_eq!(lengths, TokenLengths { count: 3, shortest: 16, median: 16, longest: 29 });
+        assert_eq!(TokenLengths::measure(b"no long words here", 12).count, 0);
+    }
+
+    #[test]
+    fn test_detect_profile_scripts() {
+        let english = TextProfile::detect(b"[INFO] 2025-10-20 Service started successfully.");
+        assert_eq!(english.script, Script::Latin);
+        assert!(english.digits > 0.0);
+
+        let russian = TextProfile::detect("[INFO] 2025-10-20 Сервис успешно запущен.".as_bytes());
+        assert_eq!(russian.script, Script::Cyrillic);
+        assert!(russian.cyrillic > russian.ascii_letters);
+
+        assert_eq!(TextProfile::detect("東京のサーバーが起動しました".as_bytes()).script, Script::Cjk);
+        assert_eq!(TextProfile::detect("user logged in successfully пользователь вошёл".as_bytes()).script, Script::Mixed);
+        assert_eq!(TextProfile::detect(b"").sample_chars, 0);
+    }
+
+    #[test]
+    fn test_detect_profile_tolerates_cut_characters() {
+        let text = "Привет".as_bytes();
+        // Ends inside the last two-byte character.
+        let profile = TextProfile::detect(&text[..text.len() - 1]);
+        assert_eq!(profile.script, Script::Cyrillic);
+        assert_eq!(profile.sample_chars, 5);
+    }
+}
@@ -0,0 +1,41 @@
+// cleansh/src/commands/audit.rs
+//! Maintenance commands for audit log files.
+
+use anyhow::Result;
+use std::io::{self, Write};
+use std::path::Path;
+
+use cleansh_core::{decrypt_audit_log, migrate_audit_log, AuditKeySource, AUDIT_FORMAT_VERSION};
+
+/// Converts an audit file of any supported version to the current format.
+pub fn run_migrate(input: &Path, output: &Path) -> Result<()> {
+    let (from, entries) = migrate_audit_log(input, output)?;
+    println!(
+        "Migrated {} entries from {} (v{}) to {} (v{}).",
+        entries,
+        input.display(),
+        from as u32,
+        output.display(),
+        AUDIT_FORMAT_VERSION
+    );
+    Ok(())
+}
+
+/// Streams the decrypted entries of an encrypted audit file to stdout, reporting
+/// lines that could not be decrypted on stderr.
+pub fn run_decrypt(input: &Path, key_source: &AuditKeySource) -> Result<()> {
+    let key = key_source.resolve()?;
+    let mut stdout = io::stdout().lock();
+    let summary = decrypt_audit_log(input, &key, &mut stdout)?;
+    stdout.flush()?;
+    if !summary.skipped.is_empty() {
+        let lines: Vec<String> = summary.skipped.iter().map(usize::to_string).collect();
+        eprintln!(
+            "Warning: skipped {} entries of {} that could not be decrypted (lines {}).",
+            summary.skipped.len(),
+            input.display(),
+            lines.join(", ")
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,15 @@
+// cleansh/src/commands/corpus.rs
+//! `cleansh corpus run`: checks a directory of `<case>.input` / `<case>.expected`
+//! pairs against the selected engine, for keeping private regression corpora.
+
+use std::path::Path;
+
+use cleansh_core::corpus::run_corpus;
+use cleansh_core::engine::SanitizationEngine;
+
+/// Prints the report for the corpus in `dir` and returns whether every case passed.
+pub fn run(engine: &dyn SanitizationEngine, dir: &Path) -> bool {
+    let report = run_corpus(dir, engine);
+    println!("{}", report);
+    report.passed()
+}
@@ -0,0 +1,318 @@
+// cleansh/src/commands/diff_report.rs
+//! Compares two sets of redactions, e.g. golden-log scans before and after an
+//! upgrade or profile change.
+//!
+//! Either input may be a `cleansh scan --output-format json` report or a
+//! redaction map (a JSON array of serialized `RedactionMatch`es). Both are
+//! reduced to [`RedactedSpan`]s and compared by exact span, so a match whose
+//! boundaries moved shows up as removed from A and added in B.
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use comfy_table::{Cell, Color, Table};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::ui::output_format::ColorChoice;
+
+/// Output formats for `cleansh diff-report`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DiffFormat {
+    /// Human-readable tables.
+    Table,
+    /// The full comparison as JSON.
+    Json,
+}
+
+/// One redacted byte range, attributed to a rule.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct RedactedSpan {
+    /// The scanned file or stream the offsets refer to.
+    pub source: String,
+    pub start: u64,
+    pub end: u64,
+    pub rule_name: String,
+}
+
+/// A span both sides redacted, credited to different rules.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleChange {
+    pub source: String,
+    pub start: u64,
+    pub end: u64,
+    pub rule_a: String,
+    pub rule_b: String,
+}
+
+/// How often a rule fired on each side.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleDelta {
+    pub rule_name: String,
+    pub count_a: usize,
+    pub count_b: usize,
+    pub delta: i64,
+}
+
+/// The structured result of [`compare`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DiffReport {
+    pub only_in_a: Vec<RedactedSpan>,
+    pub only_in_b: Vec<RedactedSpan>,
+    pub rule_changes: Vec<RuleChange>,
+    /// Every rule seen on either side, including unchanged ones.
+    pub rule_deltas: Vec<RuleDelta>,
+}
+
+impl DiffReport {
+    /// Whether the two inputs redact anything differently.
+    pub fn has_changes(&self) -> bool {
+        !self.only_in_a.is_empty() || !self.only_in_b.is_empty() || !self.rule_changes.is_empty()
+    }
+}
+
+#[derive(Deserialize)]
+struct ReportEntry {
+    rule_name: String,
+    path: String,
+    start: u64,
+    end: u64,
+}
+
+#[derive(Deserialize)]
+struct MapEntry {
+    rule_name: String,
+    #[serde(default)]
+    source_id: String,
+    start: u64,
+    end: u64,
+    /// Offsets in the input as given; maps written before these existed lack them.
+    #[serde(default)]
+    original_start: Option<u64>,
+    #[serde(default)]
+    original_end: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DiffInput {
+    Report { findings: Vec<ReportEntry> },
+    Map(Vec<MapEntry>),
+}
+
+/// Parses a JSON report or redaction map into spans.
+pub fn parse_spans(json: &str) -> Result<Vec<RedactedSpan>> {
+    let input: DiffInput = serde_json::from_str(json)
+        .context("Expected a `cleansh scan --output-format json` report or a JSON array of redaction matches")?;
+    Ok(match input {
+        DiffInput::Report { findings } => findings
+            .into_iter()
+            .map(|f| RedactedSpan { source: f.path, start: f.start, end: f.end, rule_name: f.rule_name })
+            .collect(),
+        DiffInput::Map(entries) => entries
+            .into_iter()
+            .map(|m| RedactedSpan {
+                source: m.source_id,
+                start: m.original_start.unwrap_or(m.start),
+                end: m.original_end.unwrap_or(m.end),
+                rule_name: m.rule_name,
+            })
+            .collect(),
+    })
+}
+
+/// Reads and parses one side of the comparison.
+pub fn load_spans(path: &Path) -> Result<Vec<RedactedSpan>> {
+    let json = std::fs::read_to_string(path).with_context(|| format!("Failed to read '{}'", path.display()))?;
+    parse_spans(&json).with_context(|| format!("Failed to parse '{}'", path.display()))
+}
+
+type SpanKey = (String, u64, u64);
+
+fn group_by_span(spans: &[RedactedSpan]) -> BTreeMap<SpanKey, Vec<String>> {
+    let mut grouped: BTreeMap<SpanKey, Vec<String>> = BTreeMap::new();
+    for span in spans {
+        grouped.entry((span.source.clone(), span.start, span.end)).or_default().push(span.rule_name.clone());
+    }
+    for rules in grouped.values_mut() {
+        rules.sort();
+    }
+    grouped
+}
+
+fn count_by_rule(spans: &[RedactedSpan]) -> BTreeMap<&str, usize> {
+    let mut counts = BTreeMap::new();
+    for span in spans {
+        *counts.entry(span.rule_name.as_str()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Compares A against B.
+///
+/// Spans are matched on `(source, start, end)`. Where both sides redacted the same
+/// span, rules present on both cancel out and the leftovers are paired as rule
+/// changes; anything unpaired is reported as only in A or only in B.
+pub fn compare(a: &[RedactedSpan], b: &[RedactedSpan]) -> DiffReport {
+    let mut grouped_b = group_by_span(b);
+    let mut report = DiffReport::default();
+    let span = |(source, start, end): &SpanKey, rule_name: String| RedactedSpan {
+        source: source.clone(), start: *start, end: *end, rule_name,
+    };
+
+    for (key, mut rules_a) in group_by_span(a) {
+        let mut rules_b = grouped_b.remove(&key).unwrap_or_default();
+        rules_a.retain(|rule| match rules_b.iter().position(|r| r == rule) {
+            Some(i) => { rules_b.remove(i); false }
+            None => true,
+        });
+
+        let paired = rules_a.len().min(rules_b.len());
+        for (rule_a, rule_b) in rules_a.drain(..paired).zip(rules_b.drain(..paired)) {
+            report.rule_changes.push(RuleChange { source: key.0.clone(), start: key.1, end: key.2, rule_a, rule_b });
+        }
+        report.only_in_a.extend(rules_a.into_iter().map(|rule| span(&key, rule)));
+        report.only_in_b.extend(rules_b.into_iter().map(|rule| span(&key, rule)));
+    }
+    for (key, rules_b) in grouped_b {
+        report.only_in_b.extend(rules_b.into_iter().map(|rule| span(&key, rule)));
+    }
+
+    let (counts_a, counts_b) = (count_by_rule(a), count_by_rule(b));
+    let mut rules: Vec<&str> = counts_a.keys().chain(counts_b.keys()).copied().collect();
+    rules.sort_unstable();
+    rules.dedup();
+    report.rule_deltas = rules
+        .into_iter()
+        .map(|rule| {
+            let count_a = counts_a.get(rule).copied().unwrap_or(0);
+            let count_b = counts_b.get(rule).copied().unwrap_or(0);
+            RuleDelta { rule_name: rule.to_string(), count_a, count_b, delta: count_b as i64 - count_a as i64 }
+        })
+        .collect();
+    report
+}
+
+/// `text` in `fg` when `color` is set.
+fn colored(text: &str, fg: Color, color: bool) -> Cell {
+    if color { Cell::new(text).fg(fg) } else { Cell::new(text) }
+}
+
+/// Renders the span changes followed by per-rule counts. With `color`, removals
+/// are red, additions green, and rule changes yellow.
+pub fn render_table(report: &DiffReport, color: bool) -> String {
+    let mut changes = Table::new();
+    let mut counts = Table::new();
+    for table in [&mut changes, &mut counts] {
+        if color {
+            table.enforce_styling();
+        } else {
+            table.force_no_tty();
+        }
+    }
+    changes.set_header(vec!["Change", "Source", "Span", "Rule"]);
+    for s in &report.only_in_a {
+        changes.add_row(vec![colored("only in A", Color::Red, color), Cell::new(&s.source), Cell::new(format!("{}..{}", s.start, s.end)), Cell::new(&s.rule_name)]);
+    }
+    for s in &report.only_in_b {
+        changes.add_row(vec![colored("only in B", Color::Green, color), Cell::new(&s.source), Cell::new(format!("{}..{}", s.start, s.end)), Cell::new(&s.rule_name)]);
+    }
+    for c in &report.rule_changes {
+        changes.add_row(vec![
+            colored("rule changed", Color::Yellow, color),
+            Cell::new(&c.source),
+            Cell::new(format!("{}..{}", c.start, c.end)),
+            Cell::new(format!("{} -> {}", c.rule_a, c.rule_b)),
+        ]);
+    }
+
+    counts.set_header(vec!["Rule", "A", "B", "Delta"]);
+    for d in &report.rule_deltas {
+        let delta = format!("{:+}", d.delta);
+        let delta = match d.delta.signum() {
+            -1 => colored(&delta, Color::Red, color),
+            1 => colored(&delta, Color::Green, color),
+            _ => Cell::new(delta),
+        };
+        counts.add_row(vec![Cell::new(&d.rule_name), Cell::new(d.count_a), Cell::new(d.count_b), delta]);
+    }
+
+    if report.has_changes() {
+        format!("{}\n{}\n", changes, counts)
+    } else {
+        format!("No redaction differences.\n{}\n", counts)
+    }
+}
+
+/// Compares the two files and prints the result, coloring the tables as `color`
+/// resolves for stdout. Returns whether anything changed.
+pub fn run_diff_report(a: &Path, b: &Path, format: DiffFormat, color: ColorChoice) -> Result<bool> {
+    let report = compare(&load_spans(a)?, &load_spans(b)?);
+    match format {
+        DiffFormat::Table => print!("{}", render_table(&report, color.enabled_for(&std::io::stdout()))),
+        DiffFormat::Json => println!("{}", serde_json::to_string_pretty(&report).context("Failed to serialize diff report")?),
+    }
+    Ok(report.has_changes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(source: &str, start: u64, end: u64, rule_name: &str) -> RedactedSpan {
+        RedactedSpan { source: source.to_string(), start, end, rule_name: rule_name.to_string() }
+    }
+
+    #[test]
+    fn test_identical_inputs_have_no_changes() {
+        let spans = vec![span("a.log", 0, 5, "email"), span("a.log", 9, 20, "ipv4_address")];
+        let report = compare(&spans, &spans);
+        assert!(!report.has_changes());
+        assert!(report.rule_deltas.iter().all(|d| d.delta == 0));
+    }
+
+    #[test]
+    fn test_shifted_offsets_are_reported_on_both_sides() {
+        let a = vec![span("a.log", 10, 30, "high_entropy_secret")];
+        let b = vec![span("a.log", 12, 30, "high_entropy_secret")];
+        let report = compare(&a, &b);
+        assert_eq!(report.only_in_a, a);
+        assert_eq!(report.only_in_b, b);
+        assert!(report.rule_changes.is_empty());
+        assert_eq!(report.rule_deltas[0].delta, 0);
+    }
+
+    #[test]
+    fn test_renamed_rule_is_a_rule_change() {
+        let a = vec![span("a.log", 0, 40, "github_pat"), span("a.log", 50, 60, "email")];
+        let b = vec![span("a.log", 0, 40, "github_token"), span("a.log", 50, 60, "email")];
+        let report = compare(&a, &b);
+        assert!(report.only_in_a.is_empty() && report.only_in_b.is_empty());
+        assert_eq!(report.rule_changes, vec![RuleChange {
+            source: "a.log".to_string(), start: 0, end: 40,
+            rule_a: "github_pat".to_string(), rule_b: "github_token".to_string(),
+        }]);
+        let deltas: Vec<(&str, i64)> = report.rule_deltas.iter().map(|d| (d.rule_name.as_str(), d.delta)).collect();
+        assert_eq!(deltas, [("email", 0), ("github_pat", -1), ("github_token", 1)]);
+    }
+
+    #[test]
+    fn test_added_matches_and_sources_are_kept_apart() {
+        let a = vec![span("a.log", 0, 5, "email")];
+        let b = vec![span("a.log", 0, 5, "email"), span("b.log", 0, 5, "email"), span("a.log", 0, 5, "uk_nino")];
+        let report = compare(&a, &b);
+        assert_eq!(report.only_in_b, vec![span("a.log", 0, 5, "uk_nino"), span("b.log", 0, 5, "email")]);
+        assert!(report.only_in_a.is_empty() && report.rule_changes.is_empty());
+        assert_eq!(report.rule_deltas[0], RuleDelta { rule_name: "email".to_string(), count_a: 1, count_b: 2, delta: 1 });
+        assert!(render_table(&report, false).contains("only in B"));
+    }
+
+    #[test]
+    fn test_parses_reports_and_redaction_maps() -> Result<()> {
+        let report = r#"{"version":1,"findings":[{"rule_name":"email","path":"a.log","start":3,"end":18,"line":1,"column":4}]}"#;
+        let map = r#"[{"rule_name":"email","source_id":"a.log","start":3,"end":18,"original_string":"x","sanitized_string":"y"}]"#;
+        assert_eq!(parse_spans(report)?, parse_spans(map)?);
+        assert!(parse_spans(r#"{"unexpected":true}"#).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+// cleansh/src/commands/explain.rs
+//! `cleansh explain`: the decisions an engine made about one line, for working
+//! out why a secret was not caught (below the threshold, trimmed away, too
+//! short, ignored, or rejected by a validator).
+
+use anyhow::{Context, Result};
+use std::fmt::Write as _;
+use std::io::{self, BufRead};
+
+use cleansh_core::engine::SanitizationEngine;
+
+/// Reads one line from stdin and prints the trace of `engine`, which must be
+/// built with `EngineOptions::explain` set.
+pub fn run_explain(engine: &dyn SanitizationEngine) -> Result<()> {
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line).context("Failed to read a line from stdin")?;
+    print!("{}", explain_line(engine, line.trim_end_matches(['\r', '\n']))?);
+    Ok(())
+}
+
+/// One trace line per decision, in the order the engine made them, followed by
+/// the number of matches reported. Spans are byte offsets into `line`.
+pub fn explain_line(engine: &dyn SanitizationEngine, line: &str) -> Result<String> {
+    let matches = engine.find_matches_for_ui(line, "stdin")?;
+    let records = engine.get_options().take_explain_records();
+    let mut out = String::new();
+    if records.is_empty() {
+        out.push_str("No candidates were considered.\n");
+    }
+    for record in &records {
+        let _ = writeln!(out, "{}", record);
+    }
+    let _ = writeln!(out, "{} match(es) reported.", matches.len());
+    Ok(out)
+}
@@ -0,0 +1,148 @@
+// cleansh/src/commands/ignore.rs
+//! `cleansh ignore`: manage the `.cleanshignore` file of match hashes to leave
+//! unredacted and the ignore store the TUI adds to, and load the entries that
+//! are still in effect.
+
+use anyhow::{Context, Result, bail};
+use chrono::NaiveDate;
+use comfy_table::{Cell, Table};
+use std::collections::HashSet;
+use std::io::{self, Read};
+use std::path::Path;
+
+use cleansh_core::ignore_file::{IgnoreEntry, IgnoreFile, ignore_hash};
+use cleansh_core::ignore_store::IgnoreStore;
+
+/// Today's date in local time, which is what expiry dates are written in.
+pub fn today() -> NaiveDate {
+    chrono::Local::now().date_naive()
+}
+
+/// Loads the hashes in effect on `today`, printing a warning that lists any
+/// expired entries so they are not mistaken for active ones.
+pub fn load_ignored_hashes(path: &Path, today: NaiveDate) -> Result<HashSet<String>> {
+    let file = IgnoreFile::load(path)?;
+    let (active, expired) = file.active_hashes(today);
+    if !expired.is_empty() {
+        eprintln!(
+            "warning: {} expired entr{} in {} no longer apply (run `cleansh ignore prune` to remove them):",
+            expired.len(),
+            if expired.len() == 1 { "y" } else { "ies" },
+            path.display(),
+        );
+        for entry in expired {
+            eprintln!("  {}", describe(entry));
+        }
+    }
+    Ok(active)
+}
+
+fn describe(entry: &IgnoreEntry) -> String {
+    let expires = entry.expires.map(|d| format!(" (expired {})", d)).unwrap_or_default();
+    let reason = entry.reason.as_deref().map(|r| format!(": {}", r)).unwrap_or_default();
+    format!("{}{}{}", entry.hash, expires, reason)
+}
+
+/// Renders every entry with whether it is still active on `today`.
+pub fn entries_table(file: &IgnoreFile, today: NaiveDate) -> Table {
+    let mut table = Table::new();
+    table.set_header(vec!["Hash", "Expires", "Status", "Reason"]);
+    for entry in file.entries() {
+        table.add_row(vec![
+            Cell::new(&entry.hash),
+            Cell::new(entry.expires.map(|d| d.to_string()).unwrap_or_else(|| "never".to_string())),
+            Cell::new(if entry.is_expired(today) { "expired" } else { "active" }),
+            Cell::new(entry.reason.as_deref().unwrap_or("-")),
+        ]);
+    }
+    table
+}
+
+/// Prints the ignore file's entries, then the hashes in `store`.
+pub fn run_list(path: &Path, store: &IgnoreStore) -> Result<()> {
+    let file = IgnoreFile::load(path)?;
+    if file.entries().next().is_none() {
+        println!("No ignore entries in {}.", path.display());
+    } else {
+        println!("{}", entries_table(&file, today()));
+    }
+    let location = store.path().map_or_else(|| "the ignore store".to_string(), |p| p.display().to_string());
+    if store.is_empty() {
+        println!("No ignored sample hashes in {}.", location);
+    } else {
+        println!("Ignored sample hashes in {}:", location);
+        for hash in store.hashes() {
+            println!("  {}", hash);
+        }
+    }
+    Ok(())
+}
+
+/// Removes `hash` from the ignore file and the ignore store, so the value is
+/// flagged again. Returns where it was found.
+pub fn remove_hash(path: &Path, store: &IgnoreStore, hash: &str) -> Result<Vec<String>> {
+    let mut removed_from = Vec::new();
+    let mut file = IgnoreFile::load(path)?;
+    if file.remove(hash).is_some() {
+        file.save(path)?;
+        removed_from.push(path.display().to_string());
+    }
+    if store.remove(hash)? {
+        removed_from.push(store.path().map_or_else(|| "the ignore store".to_string(), |p| p.display().to_string()));
+    }
+    if removed_from.is_empty() {
+        bail!("{} is not in {} or the ignore store.", hash, path.display());
+    }
+    Ok(removed_from)
+}
+
+/// Removes `hash` wherever it is ignored.
+pub fn run_remove(path: &Path, store: &IgnoreStore, hash: &str) -> Result<()> {
+    for location in remove_hash(path, store, hash)? {
+        println!("Removed {} from {}.", hash, location);
+    }
+    Ok(())
+}
+
+/// Adds an entry for `value` and writes the file. Replaces any entry with the same hash.
+pub fn add_entry(path: &Path, value: &str, expires: Option<NaiveDate>, reason: Option<String>, today: NaiveDate) -> Result<IgnoreEntry> {
+    if value.is_empty() {
+        bail!("No value to ignore was given on stdin.");
+    }
+    if let Some(date) = expires.filter(|d| *d < today) {
+        bail!("Expiry date {} is already in the past.", date);
+    }
+    let mut file = IgnoreFile::load(path)?;
+    let entry = IgnoreEntry { hash: ignore_hash(value), expires, reason };
+    file.add(entry.clone());
+    file.save(path)?;
+    Ok(entry)
+}
+
+/// Reads the value to ignore from stdin, so it never appears in shell history, and
+/// adds its hash. One trailing newline is dropped, as `echo` and here-strings add one.
+pub fn run_add(path: &Path, expires: Option<NaiveDate>, reason: Option<String>) -> Result<()> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input).context("Failed to read the value to ignore from stdin")?;
+    let value = input.strip_suffix('\n').map(|v| v.strip_suffix('\r').unwrap_or(v)).unwrap_or(&input);
+
+    let entry = add_entry(path, value, expires, reason, today())?;
+    println!("Ignoring {} in {}.", entry.hash, path.display());
+    Ok(())
+}
+
+/// Removes expired entries, keeping comments and active entries as written.
+pub fn run_prune(path: &Path) -> Result<()> {
+    let mut file = IgnoreFile::load(path)?;
+    let removed = file.prune(today());
+    if removed.is_empty() {
+        println!("No expired entries in {}.", path.display());
+        return Ok(());
+    }
+    file.save(path)?;
+    println!("Removed {} expired entr{} from {}:", removed.len(), if removed.len() == 1 { "y" } else { "ies" }, path.display());
+    for entry in &removed {
+        println!("  {}", describe(entry));
+    }
+    Ok(())
+}
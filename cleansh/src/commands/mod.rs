@@ -1,9 +1,22 @@
 
-/// This module contains the main command logic for `cleansh`.
-/// It defines the various subcommands and their associated logic.
+// src/commands/mod.rs
 
+//! This module contains the main command logic for `cleansh`.
+//! It defines the various subcommands and their associated logic.
 
-/// src/commands/mod.rs
-
+pub mod audit;
+pub mod bench;
+pub mod corpus;
+pub mod diff_report;
+pub mod explain;
+pub mod ignore;
+pub mod profile;
+pub mod rules;
+pub mod sanitize;
+pub mod scan;
+pub mod serve;
+pub mod stats;
 pub mod uninstall;
+pub mod vault;
+pub mod watch;
 
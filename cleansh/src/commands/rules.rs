@@ -0,0 +1,165 @@
+// cleansh/src/commands/rules.rs
+//! `cleansh rules`: inspect the merged rule set, explain or try a rule against
+//! sample text, and add custom rules to the user's `rules.yaml`.
+
+use anyhow::{Context, Result, anyhow};
+use comfy_table::{Cell, Table};
+use std::fmt::Write as _;
+use std::io::{self, Read};
+use std::path::Path;
+
+use cleansh_core::config::{RedactionConfig, RedactionRule, append_rule};
+use cleansh_core::{compile_rules, RegexEngine, SanitizationEngine};
+use crate::ui::output_format::{format_text, Finding};
+
+/// Patterns longer than this are cut short in `rules list`.
+pub const LIST_PATTERN_WIDTH: usize = 48;
+
+fn truncate_pattern(pattern: &str) -> String {
+    if pattern.chars().count() <= LIST_PATTERN_WIDTH {
+        return pattern.to_string();
+    }
+    let cut: String = pattern.chars().take(LIST_PATTERN_WIDTH - 1).collect();
+    format!("{}…", cut)
+}
+
+/// Renders every rule in `config`, sorted by name.
+pub fn rules_table(config: &RedactionConfig) -> Table {
+    let mut rules: Vec<&RedactionRule> = config.rules.iter().collect();
+    rules.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut table = Table::new();
+    table.set_header(vec!["Rule", "Severity", "Opt-in", "Pattern"]);
+    for rule in rules {
+        table.add_row(vec![
+            Cell::new(&rule.name),
+            Cell::new(rule.severity.as_deref().unwrap_or("-")),
+            Cell::new(if rule.opt_in { "yes" } else { "no" }),
+            Cell::new(truncate_pattern(rule.pattern.as_deref().unwrap_or(""))),
+        ]);
+    }
+    table
+}
+
+/// Prints the merged rule set.
+pub fn run_list(config: &RedactionConfig) {
+    println!("{}", rules_table(config));
+}
+
+fn find_rule(config: &RedactionConfig, rule_name: &str) -> Result<RedactionRule> {
+    config
+        .rules
+        .iter()
+        .find(|r| r.name == rule_name)
+        .cloned()
+        .ok_or_else(|| anyhow!("No rule named '{}'. Run `cleansh rules list` to see the available rules.", rule_name))
+}
+
+/// Describes the named rule: its metadata and the validator attached to it. With
+/// `sample`, also every match in it, each capture group with its byte span, and
+/// whether the match passes the validator.
+pub fn explain_rule(config: &RedactionConfig, rule_name: &str, sample: Option<&str>) -> Result<String> {
+    let rule = find_rule(config, rule_name)?;
+    let compiled = compile_rules(vec![rule.clone()])?;
+    let compiled_rule = compiled
+        .find_rule(rule_name)
+        .ok_or_else(|| anyhow!("Rule '{}' has no pattern to explain.", rule_name))?;
+
+    let mut flags = Vec::new();
+    if rule.multiline {
+        flags.push("multiline");
+    }
+    if rule.dot_matches_new_line {
+        flags.push("dot_matches_new_line");
+    }
+    let or_none = |items: Vec<String>| if items.is_empty() { "-".to_string() } else { items.join(", ") };
+
+    let mut out = String::new();
+    writeln!(out, "Rule:          {}", rule.name)?;
+    if let Some(description) = &rule.description {
+        writeln!(out, "Description:   {}", description)?;
+    }
+    writeln!(out, "Pattern:       {}", rule.pattern.as_deref().unwrap_or_default())?;
+    writeln!(out, "Pattern type:  {}", rule.pattern_type)?;
+    writeln!(out, "Flags:         {}", or_none(flags.iter().map(|f| f.to_string()).collect()))?;
+    writeln!(out, "Replace with:  {}", rule.replace_with)?;
+    match compiled_rule.redact_group {
+        Some(group) => writeln!(out, "Redacts:       group {}", group)?,
+        None => writeln!(out, "Redacts:       whole match")?,
+    }
+    writeln!(out, "Severity:      {}", rule.severity.as_deref().unwrap_or("-"))?;
+    writeln!(out, "Tags:          {}", or_none(rule.tags.clone().unwrap_or_default()))?;
+    writeln!(out, "Opt-in:        {}", if rule.opt_in { "yes" } else { "no" })?;
+    writeln!(out, "Validator:     {}", compiled_rule.validator().map_or("-", |v| v.name))?;
+
+    let Some(sample) = sample else { return Ok(out) };
+    let names: Vec<Option<&str>> = compiled_rule.regex.capture_names().collect();
+    let mut count = 0;
+    for caps in compiled_rule.regex.captures_iter(sample) {
+        count += 1;
+        let whole = caps.get(0).expect("group 0 always participates");
+        writeln!(out, "\nMatch {} at {}..{}", count, whole.start(), whole.end())?;
+        for (i, name) in names.iter().enumerate() {
+            let label = match name {
+                Some(name) => format!("{} ({})", i, name),
+                None => i.to_string(),
+            };
+            match caps.get(i) {
+                Some(group) => writeln!(out, "  group {}: {}..{} {:?}", label, group.start(), group.end(), group.as_str())?,
+                None => writeln!(out, "  group {}: did not participate", label)?,
+            }
+        }
+        if compiled_rule.validator().is_some() {
+            let passed = compiled_rule.redacted_span(&caps).is_some_and(|m| compiled_rule.passes_validation(m.as_str()));
+            writeln!(out, "  validator: {}", if passed { "passed" } else { "failed" })?;
+        }
+    }
+    if count == 0 {
+        writeln!(out, "\nThe pattern does not match the sample.")?;
+    }
+    Ok(out)
+}
+
+/// Prints [`explain_rule`]'s description of the named rule.
+pub fn run_explain(config: &RedactionConfig, rule_name: &str, sample: Option<&str>) -> Result<()> {
+    print!("{}", explain_rule(config, rule_name, sample)?);
+    Ok(())
+}
+
+/// Runs the named rule alone over `sample`, regardless of whether it is opt-in or
+/// disabled, and returns its findings.
+pub fn test_rule(config: &RedactionConfig, rule_name: &str, sample: &str) -> Result<Vec<Finding>> {
+    let mut rule = find_rule(config, rule_name)?;
+    rule.enabled = Some(true);
+
+    let engine = RegexEngine::new(RedactionConfig { rules: vec![rule], ..Default::default() })?;
+    let matches = engine.find_matches_for_ui(sample, "stdin")?;
+    Ok(matches.iter().map(|m| Finding::from_match(m, "stdin", sample)).collect())
+}
+
+/// Reads sample text from stdin and reports where the rule matches.
+/// Returns the number of matches.
+pub fn run_test(config: &RedactionConfig, rule_name: &str) -> Result<usize> {
+    let mut sample = String::new();
+    io::stdin().read_to_string(&mut sample).context("Failed to read sample text from stdin")?;
+
+    let findings = test_rule(config, rule_name, &sample)?;
+    if findings.is_empty() {
+        println!("Rule '{}' did not match the sample.", rule_name);
+    } else {
+        print!("{}", format_text(&findings));
+        println!("Rule '{}' matched {} time(s).", rule_name, findings.len());
+    }
+    Ok(findings.len())
+}
+
+/// Validates `rule` and writes it to `path`. Unless `force` is set, a rule that
+/// already exists anywhere in the merged set is refused.
+pub fn run_add(config: &RedactionConfig, path: &Path, rule: RedactionRule, force: bool) -> Result<()> {
+    if !force && config.rules.iter().any(|r| r.name == rule.name) {
+        return Err(anyhow!("Rule '{}' already exists; use --force to override it.", rule.name));
+    }
+    append_rule(path, &rule, force)?;
+    println!("Added rule '{}' to {}.", rule.name, path.display());
+    Ok(())
+}
@@ -0,0 +1,121 @@
+// cleansh/src/commands/sanitize.rs
+//! Non-interactive sanitization: redacted text on stdout (or the `--sink`
+//! outputs), the per-rule summary on stderr or in a file, so CI can capture both.
+//! The input can also be the clipboard, which can then be overwritten with its
+//! sanitized form.
+
+use anyhow::{bail, Context, Result};
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+use cleansh_core::engine::{SanitizationEngine, SanitizeContext};
+use cleansh_core::config::merge_summary;
+use cleansh_core::RedactionSummaryItem;
+use crate::commands::scan::{read_inputs, warn_rule_timeouts};
+use crate::ui::output_format::ColorChoice;
+use crate::ui::redaction_summary::{write_summary, SummaryFormat};
+use crate::utils::clipboard::{ClipboardSink, ClipboardSource};
+use crate::utils::sink::OutputSink;
+
+/// Clipboard contents larger than this are only sanitized after confirmation or `--yes`.
+pub const LARGE_CLIPBOARD_BYTES: usize = 10 * 1024 * 1024;
+
+/// Where and how `cleansh sanitize` reports what it redacted.
+#[derive(Debug, Clone)]
+pub struct SummaryOptions {
+    pub format: SummaryFormat,
+    /// Include the distinct replacement texts of each rule.
+    pub samples: bool,
+    /// Write the summary here instead of stderr.
+    pub output: Option<PathBuf>,
+    /// Whether the summary on stderr is colored.
+    pub color: ColorChoice,
+}
+
+/// Sanitizes every input in order, returning the combined output and summary.
+pub fn sanitize_inputs(engine: &dyn SanitizationEngine, inputs: &[(String, String)]) -> Result<(String, Vec<RedactionSummaryItem>)> {
+    let mut output = String::new();
+    let mut summary = Vec::new();
+    for (label, content) in inputs {
+        let mut ctx = SanitizeContext::new(label).with_reason("sanitize").with_outcome("success");
+        let (sanitized, items) = engine.sanitize_with(content, &mut ctx)?;
+        output.push_str(&sanitized);
+        merge_summary(&mut summary, items, engine.get_options());
+    }
+    Ok((output, summary))
+}
+
+/// How `cleansh sanitize` uses the clipboard.
+#[derive(Debug, Clone, Default)]
+pub struct ClipboardOptions {
+    /// Sanitize the clipboard's text instead of files or stdin.
+    pub from_clipboard: bool,
+    /// Also put the sanitized text on the clipboard.
+    pub write_back: bool,
+    /// Sanitize clipboard contents over `LARGE_CLIPBOARD_BYTES` without asking.
+    pub yes: bool,
+}
+
+/// Reads and sanitizes the clipboard, writing the result back when asked to.
+/// Returns `None` when the clipboard is over `LARGE_CLIPBOARD_BYTES` and `confirm`
+/// (called with its size) declines; nothing is changed then.
+pub fn sanitize_clipboard<C: ClipboardSource + ClipboardSink>(
+    engine: &dyn SanitizationEngine,
+    clipboard: &mut C,
+    options: &ClipboardOptions,
+    confirm: impl FnOnce(usize) -> Result<bool>,
+) -> Result<Option<(String, Vec<RedactionSummaryItem>)>> {
+    let content = clipboard.get_text()?;
+    if content.is_empty() {
+        bail!("The clipboard is empty; there is nothing to sanitize");
+    }
+    if content.len() > LARGE_CLIPBOARD_BYTES && !options.yes && !confirm(content.len())? {
+        return Ok(None);
+    }
+    let (output, summary) = sanitize_inputs(engine, &[("clipboard".to_string(), content)])?;
+    if options.write_back {
+        clipboard.set_text(&output).context("Failed to write the sanitized text back to the clipboard")?;
+    }
+    Ok(Some((output, summary)))
+}
+
+/// Asks on stderr whether to sanitize `len` bytes of clipboard content.
+pub fn confirm_large_clipboard(len: usize) -> Result<bool> {
+    eprint!("The clipboard holds {:.1} MB. Sanitize it anyway? (y/N): ", len as f64 / (1024.0 * 1024.0));
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer).context("Failed to read confirmation input.")?;
+    Ok(answer.trim().eq_ignore_ascii_case("y"))
+}
+
+/// Writes the sanitized inputs to `out`, copying them to the clipboard too when
+/// asked, and writes the summary. Returns the number of redactions.
+pub fn run_sanitize<C: ClipboardSource + ClipboardSink>(
+    engine: &dyn SanitizationEngine,
+    paths: &[PathBuf],
+    summary_options: &SummaryOptions,
+    clipboard: &mut C,
+    clipboard_options: &ClipboardOptions,
+    out: &mut dyn OutputSink,
+) -> Result<usize> {
+    let (output, summary) = if clipboard_options.from_clipboard {
+        match sanitize_clipboard(engine, clipboard, clipboard_options, confirm_large_clipboard)? {
+            Some(result) => result,
+            None => {
+                eprintln!("Clipboard left unchanged.");
+                return Ok(0);
+            }
+        }
+    } else {
+        let (output, summary) = sanitize_inputs(engine, &read_inputs(paths)?)?;
+        if clipboard_options.write_back {
+            clipboard.set_text(&output).context("Failed to copy the sanitized text to the clipboard")?;
+        }
+        (output, summary)
+    };
+    out.write_all(output.as_bytes())?;
+    out.flush()?;
+    write_summary(&summary, summary_options.format, summary_options.samples, summary_options.output.as_deref(), summary_options.color)?;
+    warn_rule_timeouts(&engine.metrics());
+    Ok(summary.iter().map(|item| item.occurrences).sum())
+}
@@ -0,0 +1,134 @@
+// cleansh/src/commands/scan.rs
+//! Non-interactive scanning of files or stdin, for CI pipelines and hooks.
+
+use anyhow::{Context, Result};
+use comfy_table::{Cell, Table};
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+use cleansh_core::{LossyText, Severity};
+use cleansh_core::engine::SanitizationEngine;
+use cleansh_core::metrics::EngineMetricsSnapshot;
+use cleansh_core::sanitizers::structured::{find_structured_matches, merge_structured_matches, KeyMatcher};
+use crate::ui::output_format::{render, Finding, OutputFormat};
+
+/// Reads every path, or stdin when `paths` is empty or `-`, returning each input's
+/// label (`stdin` or the path) with its content. Invalid UTF-8 is replaced with
+/// U+FFFD, with a warning on stderr, so the rest of the input is still scanned.
+pub fn read_inputs(paths: &[PathBuf]) -> Result<Vec<(String, String)>> {
+    let stdin_only = [PathBuf::from("-")];
+    let paths = if paths.is_empty() { &stdin_only[..] } else { paths };
+    paths
+        .iter()
+        .map(|path| {
+            let (label, bytes) = if path.as_os_str() == "-" {
+                let mut buffer = Vec::new();
+                io::stdin().read_to_end(&mut buffer).context("Failed to read stdin")?;
+                ("stdin".to_string(), buffer)
+            } else {
+                let content = std::fs::read(path)
+                    .with_context(|| format!("Failed to read '{}'", path.display()))?;
+                (path.display().to_string(), content)
+            };
+            let content = LossyText::new(&bytes);
+            if !content.is_lossless() {
+                eprintln!(
+                    "Warning: {}: replaced {} invalid UTF-8 byte(s) with U+FFFD.",
+                    label,
+                    content.replaced_bytes(),
+                );
+            }
+            Ok((label, content.text))
+        })
+        .collect()
+}
+
+/// Scans every path (or stdin when `paths` is empty or `-`) and collects findings
+/// sorted by location. With `structured`, inputs that parse as JSON or YAML are
+/// also scanned by key, for values the engine alone would miss.
+pub fn collect_findings(engine: &dyn SanitizationEngine, paths: &[PathBuf], structured: bool) -> Result<Vec<Finding>> {
+    let matcher = structured.then(|| KeyMatcher::from_config(&engine.get_rules()));
+    let mut findings = Vec::new();
+    for (label, content) in read_inputs(paths)? {
+        let mut matches = engine.find_matches_for_ui(&content, &label)?;
+        if let Some(matcher) = &matcher {
+            merge_structured_matches(&mut matches, find_structured_matches(&content, &label, matcher));
+        }
+        let mut file_findings: Vec<Finding> = matches
+            .iter()
+            .map(|m| Finding::from_match(m, &label, &content))
+            .collect();
+        file_findings.sort_by_key(|f| (f.line, f.column));
+        findings.extend(file_findings);
+    }
+    Ok(findings)
+}
+
+/// Renders the per-rule counters, hottest rules first, followed by scan totals.
+/// A `Timeouts` column is added when a rule ran out of its time budget.
+pub fn metrics_table(snapshot: &EngineMetricsSnapshot) -> Table {
+    let mut rules: Vec<_> = snapshot.rules.iter().collect();
+    rules.sort_by(|a, b| b.matches.cmp(&a.matches).then(b.time_spent.cmp(&a.time_spent)));
+    let timeouts = snapshot.timed_out_rules().next().is_some();
+
+    let mut table = Table::new();
+    let mut header = vec!["Rule", "Matches", "Time (ms)"];
+    if timeouts {
+        header.push("Timeouts");
+    }
+    table.set_header(header);
+    for rule in rules {
+        let mut row = vec![
+            Cell::new(&rule.rule_name),
+            Cell::new(rule.matches),
+            Cell::new(format!("{:.3}", rule.time_spent.as_secs_f64() * 1000.0)),
+        ];
+        if timeouts {
+            row.push(Cell::new(rule.timeouts));
+        }
+        table.add_row(row);
+    }
+    table.add_row(vec![Cell::new("Total"), Cell::new(snapshot.total_matches()), Cell::new("")]);
+    table.add_row(vec![
+        Cell::new("Scanned"),
+        Cell::new(format!("{} lines", snapshot.lines_scanned)),
+        Cell::new(format!("{} bytes", snapshot.bytes_scanned)),
+    ]);
+    table
+}
+
+/// Warns on stderr about every rule that was stopped for exceeding
+/// `engines.rule_timeout_ms`, since some of its matches may have been missed.
+pub fn warn_rule_timeouts(snapshot: &EngineMetricsSnapshot) {
+    for rule in snapshot.timed_out_rules() {
+        eprintln!(
+            "Warning: rule '{}' ran out of its time budget on {} input(s); later matches of it were missed.",
+            rule.rule_name, rule.timeouts,
+        );
+    }
+}
+
+/// Exit code for a scan: without a threshold, `1` when anything was found; with
+/// `fail_on`, `2` when a finding is at or above that severity and `0` otherwise.
+pub fn scan_exit_code(findings: &[Finding], fail_on: Option<Severity>) -> i32 {
+    match fail_on {
+        None => i32::from(!findings.is_empty()),
+        Some(threshold) if findings.iter().any(|f| f.level >= threshold) => 2,
+        Some(_) => 0,
+    }
+}
+
+/// Runs the scan, prints the report to stdout and the metrics table to stderr,
+/// so machine-readable formats stay parseable. Returns the findings.
+pub fn run_scan(engine: &dyn SanitizationEngine, paths: &[PathBuf], format: OutputFormat, structured: bool) -> Result<Vec<Finding>> {
+    let findings = collect_findings(engine, paths, structured)?;
+    let report = render(format, &findings)?;
+    print!("{}", report);
+    if matches!(format, OutputFormat::GitlabCodequality | OutputFormat::Json) {
+        println!();
+    }
+    let metrics = engine.metrics();
+    eprintln!("{}", metrics_table(&metrics));
+    warn_rule_timeouts(&metrics);
+    Ok(findings)
+}
@@ -0,0 +1,385 @@
+// cleansh/src/commands/serve.rs
+//! `cleansh serve` and `cleansh client`: sanitization for shell hooks and editor
+//! plugins without paying process startup on every snippet.
+//!
+//! The server listens on a Unix domain socket (a named pipe on Windows) and keeps
+//! its engines for its whole life. The protocol is JSON Lines: each request line
+//! (a [`ServeRequest`]) gets one response line, a [`ServeResponse`] or an
+//! `{"error": ...}` frame. A bad request only fails itself; the connection stays
+//! open for the next one.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::Semaphore;
+
+use cleansh_core::config::RedactionConfig;
+use cleansh_core::engine::{SanitizationEngine, SanitizeContext};
+use cleansh_core::profiles::EngineOptions;
+use cleansh_core::{CleanshClient, EngineHandle, EngineKind};
+use crate::ui::redaction_summary::{summary_rows, SummaryRow};
+
+/// Default for `--max-connections`.
+pub const DEFAULT_MAX_CONNECTIONS: usize = 16;
+
+/// Default for `--timeout-secs`.
+pub const DEFAULT_CONNECTION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A request line longer than this is refused and its connection closed.
+pub const MAX_REQUEST_BYTES: usize = 16 * 1024 * 1024;
+
+/// How many engines built for requests' engine and profile choices are kept. Past
+/// this, the one built longest ago is dropped.
+pub const MAX_CACHED_ENGINES: usize = 16;
+
+/// `source_id` of requests that do not name one.
+pub const DEFAULT_SERVE_SOURCE_ID: &str = "client";
+
+/// One request line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServeRequest {
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_id: Option<String>,
+    /// `regex`, `entropy`, or `hybrid`; the server's engine when omitted, or the
+    /// profile's engine type when `profile` is given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub engine: Option<String>,
+    /// A profile, by name or path, applied to the server's rules for this request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+/// The reply to a request that was served.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServeResponse {
+    pub sanitized: String,
+    /// How many redactions were applied.
+    pub matches_count: usize,
+    pub summary: Vec<SummaryRow>,
+}
+
+/// A response line: the response, or why the request could not be served.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ServeReply {
+    Ok(ServeResponse),
+    Error { error: String },
+}
+
+/// Limits the server applies to its clients.
+#[derive(Debug, Clone)]
+pub struct ServeOptions {
+    /// Connections served at once; later ones wait until one closes.
+    pub max_connections: usize,
+    /// How long a connection may wait between requests before it is closed.
+    pub timeout: Duration,
+}
+
+impl Default for ServeOptions {
+    fn default() -> Self {
+        Self { max_connections: DEFAULT_MAX_CONNECTIONS, timeout: DEFAULT_CONNECTION_TIMEOUT }
+    }
+}
+
+/// An engine built for requests that ask for another engine or a profile.
+type OverrideKey = (Option<EngineKind>, Option<String>);
+
+/// The engines a server answers with: the one it was started with, shared through
+/// an [`EngineHandle`] so it can be replaced while the server runs, and the ones
+/// requests ask for, built on first use and kept, up to [`MAX_CACHED_ENGINES`].
+pub struct EnginePool {
+    default: EngineHandle,
+    default_kind: EngineKind,
+    /// The rules before any profile, which request profiles are applied to.
+    rules: RedactionConfig,
+    options: EngineOptions,
+    /// Oldest first.
+    overrides: Mutex<VecDeque<(OverrideKey, Arc<CleanshClient>)>>,
+}
+
+impl EnginePool {
+    pub fn new(default: EngineHandle, default_kind: EngineKind, rules: RedactionConfig, options: EngineOptions) -> Self {
+        Self { default, default_kind, rules, options, overrides: Mutex::new(VecDeque::new()) }
+    }
+
+    /// The handle to the default engine. A replacement takes effect from the next
+    /// request and must be of the same kind.
+    pub fn engine(&self) -> &EngineHandle {
+        &self.default
+    }
+
+    /// Sanitizes the request's content with the engine it asks for.
+    pub fn serve(&self, request: &ServeRequest) -> Result<ServeResponse> {
+        let engine = request.engine.as_deref().map(str::parse::<EngineKind>).transpose()?;
+        let source_id = request.source_id.as_deref().unwrap_or(DEFAULT_SERVE_SOURCE_ID);
+        match (engine, &request.profile) {
+            (None, None) => self.default.with(|engine| sanitize(engine, &request.content, source_id)),
+            (Some(kind), None) if kind == self.default_kind => self.default.with(|engine| sanitize(engine, &request.content, source_id)),
+            (engine, profile) => {
+                let client = self.client_for(engine, profile.clone())?;
+                sanitize(client.engine(), &request.content, source_id)
+            }
+        }
+    }
+
+    fn client_for(&self, engine: Option<EngineKind>, profile: Option<String>) -> Result<Arc<CleanshClient>> {
+        let mut overrides = self.overrides.lock().unwrap_or_else(PoisonError::into_inner);
+        let key = (engine, profile);
+        if let Some((_, client)) = overrides.iter().find(|(cached, _)| *cached == key) {
+            return Ok(client.clone());
+        }
+        let mut builder = CleanshClient::builder().with_rules(self.rules.clone()).with_options(self.options.clone());
+        if let Some(kind) = key.0 {
+            builder = builder.engine(kind);
+        }
+        if let Some(profile) = &key.1 {
+            builder = builder.with_profile(profile)?;
+        }
+        let client = Arc::new(builder.build()?);
+        if overrides.len() >= MAX_CACHED_ENGINES {
+            overrides.pop_front();
+        }
+        overrides.push_back((key, client.clone()));
+        Ok(client)
+    }
+
+    /// How many engines built for requests are kept.
+    pub fn cached_engines(&self) -> usize {
+        self.overrides.lock().unwrap_or_else(PoisonError::into_inner).len()
+    }
+}
+
+fn sanitize(engine: &dyn SanitizationEngine, content: &str, source_id: &str) -> Result<ServeResponse> {
+    let mut applied = Vec::new();
+    let mut ctx = SanitizeContext::new(source_id).with_reason("serve").with_applied_matches(&mut applied);
+    let (sanitized, summary) = engine.sanitize_with(content, &mut ctx)?;
+    Ok(ServeResponse { sanitized, matches_count: applied.len(), summary: summary_rows(&summary, false) })
+}
+
+async fn write_reply<W: AsyncWrite + Unpin>(out: &mut W, reply: &ServeReply) -> Result<()> {
+    let mut line = serde_json::to_vec(reply)?;
+    line.push(b'\n');
+    out.write_all(&line).await?;
+    out.flush().await?;
+    Ok(())
+}
+
+fn error_reply(error: impl std::fmt::Display) -> ServeReply {
+    ServeReply::Error { error: error.to_string() }
+}
+
+/// Answers the requests on one connection until the client closes it, goes quiet
+/// for `timeout`, or sends a request over [`MAX_REQUEST_BYTES`].
+pub async fn handle_connection<S>(stream: S, pool: Arc<EnginePool>, timeout: Duration) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (read, mut write) = tokio::io::split(stream);
+    let mut reader = BufReader::new(read);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let mut limited = (&mut reader).take(MAX_REQUEST_BYTES as u64 + 1);
+        let read = match tokio::time::timeout(timeout, limited.read_line(&mut line)).await {
+            Ok(read) => read,
+            Err(_) => return write_reply(&mut write, &error_reply("Connection timed out waiting for a request")).await,
+        };
+        match read {
+            Ok(0) => return Ok(()),
+            Ok(_) if line.len() > MAX_REQUEST_BYTES => {
+                let error = format!("Request exceeds {} bytes", MAX_REQUEST_BYTES);
+                return write_reply(&mut write, &error_reply(error)).await;
+            }
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                write_reply(&mut write, &error_reply("Malformed request: not valid UTF-8")).await?;
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = match serde_json::from_str::<ServeRequest>(&line) {
+            Ok(request) => {
+                let pool = pool.clone();
+                match tokio::task::spawn_blocking(move || pool.serve(&request)).await {
+                    Ok(Ok(response)) => ServeReply::Ok(response),
+                    Ok(Err(e)) => error_reply(format!("{:#}", e)),
+                    Err(e) => error_reply(format!("Request failed: {}", e)),
+                }
+            }
+            Err(e) => error_reply(format!("Malformed request: {}", e)),
+        };
+        write_reply(&mut write, &reply).await?;
+    }
+}
+
+/// Serves `pool` on the socket at `path` until `shutdown` completes, then removes
+/// the socket. A socket left behind by a server that is gone is replaced; one a
+/// server still answers on, or anything at `path` that is not a socket, is an
+/// error. The socket is only accessible to its owner: it is bound inside a
+/// directory only the owner can enter, restricted, and only then moved to `path`.
+#[cfg(unix)]
+pub async fn serve(path: &Path, pool: EnginePool, options: &ServeOptions, shutdown: impl Future<Output = ()>) -> Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+    use tokio::net::{UnixListener, UnixStream};
+
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            bail!("'{}' exists and is not a socket", path.display());
+        }
+        if UnixStream::connect(path).await.is_ok() {
+            bail!("Another cleansh server is already listening on '{}'", path.display());
+        }
+        std::fs::remove_file(path).with_context(|| format!("Failed to remove stale socket '{}'", path.display()))?;
+    }
+    let file_name = path.file_name().with_context(|| format!("'{}' is not a socket path", path.display()))?;
+    let private = path.with_file_name(format!(".{}.{}", file_name.to_string_lossy(), std::process::id()));
+    std::fs::DirBuilder::new().mode(0o700).create(&private)
+        .with_context(|| format!("Failed to create '{}'", private.display()))?;
+    let staged = private.join("socket");
+    let bound = UnixListener::bind(&staged)
+        .with_context(|| format!("Failed to listen on '{}'", path.display()))
+        .and_then(|listener| {
+            std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))
+                .with_context(|| format!("Failed to restrict access to '{}'", path.display()))?;
+            std::fs::rename(&staged, path).with_context(|| format!("Failed to move the socket to '{}'", path.display()))?;
+            Ok(listener)
+        });
+    let _ = std::fs::remove_file(&staged);
+    let _ = std::fs::remove_dir(&private);
+    let listener = bound?;
+
+    let result = accept_loop(
+        || async { listener.accept().await.map(|(stream, _)| stream) },
+        pool,
+        options,
+        shutdown,
+    ).await;
+    let _ = std::fs::remove_file(path);
+    result
+}
+
+/// Serves `pool` on the named pipe `path` (e.g. `\\.\pipe\cleansh`) until
+/// `shutdown` completes.
+#[cfg(windows)]
+pub async fn serve(path: &Path, pool: EnginePool, options: &ServeOptions, shutdown: impl Future<Output = ()>) -> Result<()> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let name = path.as_os_str().to_owned();
+    let first = ServerOptions::new()
+        .first_pipe_instance(true)
+        .create(&name)
+        .with_context(|| format!("Failed to create named pipe '{}'", path.display()))?;
+    let next = Mutex::new(Some(first));
+    accept_loop(
+        || async {
+            let server = next.lock().unwrap_or_else(PoisonError::into_inner).take();
+            let server = match server {
+                Some(server) => server,
+                None => ServerOptions::new().create(&name)?,
+            };
+            server.connect().await?;
+            *next.lock().unwrap_or_else(PoisonError::into_inner) = Some(ServerOptions::new().create(&name)?);
+            Ok(server)
+        },
+        pool,
+        options,
+        shutdown,
+    ).await
+}
+
+/// Hands each connection `accept` yields to [`handle_connection`], at most
+/// `options.max_connections` at once, until `shutdown` completes.
+async fn accept_loop<S, A, F>(mut accept: A, pool: EnginePool, options: &ServeOptions, shutdown: impl Future<Output = ()>) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    A: FnMut() -> F,
+    F: Future<Output = io::Result<S>>,
+{
+    let pool = Arc::new(pool);
+    let permits = Arc::new(Semaphore::new(options.max_connections.max(1)));
+    tokio::pin!(shutdown);
+    loop {
+        let permit = tokio::select! {
+            _ = &mut shutdown => return Ok(()),
+            permit = permits.clone().acquire_owned() => permit?,
+        };
+        let stream = tokio::select! {
+            _ = &mut shutdown => return Ok(()),
+            stream = accept() => match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    log::warn!("Failed to accept a connection: {}", e);
+                    continue;
+                }
+            },
+        };
+        let pool = pool.clone();
+        let timeout = options.timeout;
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, pool, timeout).await {
+                log::debug!("Connection closed with an error: {:#}", e);
+            }
+            drop(permit);
+        });
+    }
+}
+
+/// Sends `request` to the server at `path` and returns its response; an error
+/// frame becomes an error.
+pub async fn request(path: &Path, request: &ServeRequest) -> Result<ServeResponse> {
+    #[cfg(unix)]
+    let stream = tokio::net::UnixStream::connect(path).await;
+    #[cfg(windows)]
+    let stream = tokio::net::windows::named_pipe::ClientOptions::new().open(path.as_os_str());
+    let stream = stream.with_context(|| format!("Failed to connect to cleansh server at '{}'", path.display()))?;
+
+    let (read, mut write) = tokio::io::split(stream);
+    let mut line = serde_json::to_vec(request)?;
+    line.push(b'\n');
+    write.write_all(&line).await?;
+    write.flush().await?;
+
+    let mut reply = String::new();
+    BufReader::new(read).read_line(&mut reply).await.context("Failed to read the server's reply")?;
+    if reply.is_empty() {
+        bail!("The cleansh server closed the connection without replying");
+    }
+    match serde_json::from_str(&reply).context("The cleansh server sent an unreadable reply")? {
+        ServeReply::Ok(response) => Ok(response),
+        ServeReply::Error { error } => bail!("cleansh server: {}", error),
+    }
+}
+
+/// `cleansh client`: sends the file at `input` (stdin when `None` or `-`) to the
+/// server and prints the sanitized text.
+pub async fn run_client(path: &Path, input: Option<&Path>, engine: Option<&str>, profile: Option<&str>) -> Result<()> {
+    let (content, source_id) = match input.filter(|p| p.as_os_str() != "-") {
+        Some(file) => (
+            std::fs::read_to_string(file).with_context(|| format!("Failed to read '{}'", file.display()))?,
+            file.display().to_string(),
+        ),
+        None => {
+            let mut content = String::new();
+            io::stdin().read_to_string(&mut content).context("Failed to read stdin")?;
+            (content, "stdin".to_string())
+        }
+    };
+    let response = request(path, &ServeRequest {
+        content,
+        source_id: Some(source_id),
+        engine: engine.map(str::to_string),
+        profile: profile.map(str::to_string),
+    }).await?;
+    print!("{}", response.sanitized);
+    Ok(())
+}
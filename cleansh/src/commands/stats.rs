@@ -0,0 +1,170 @@
+// cleansh/src/commands/stats.rs
+//! `cleansh stats`: how often each rule matched, over how many distinct values,
+//! and on which lines, without printing the values themselves.
+
+use anyhow::{bail, Result};
+use comfy_table::{Cell, Table};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+
+use cleansh_core::engine::SanitizationEngine;
+use cleansh_core::config::merge_summary;
+use cleansh_core::redaction_match::pii_debug_allowed;
+use cleansh_core::Severity;
+use crate::commands::scan::read_inputs;
+use crate::ui::output_format::Finding;
+
+/// Default for `--top`: how many lines `--by-line` lists per rule.
+pub const DEFAULT_TOP_LINES: usize = 5;
+
+/// What `cleansh stats` reports besides the counts.
+#[derive(Debug, Clone)]
+pub struct StatsOptions {
+    /// List the lines with the most matches for each rule.
+    pub by_line: bool,
+    /// How many lines `by_line` lists per rule.
+    pub top: usize,
+    /// List each rule's distinct original values. Refused unless
+    /// `CLEANSH_ALLOW_DEBUG_PII=true` is set.
+    pub show_samples: bool,
+}
+
+impl Default for StatsOptions {
+    fn default() -> Self {
+        Self { by_line: false, top: DEFAULT_TOP_LINES, show_samples: false }
+    }
+}
+
+/// One rule's statistics.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleStats {
+    pub rule_name: String,
+    pub severity: Severity,
+    pub occurrences: usize,
+    /// Distinct matched values, told apart by sample hash.
+    pub unique: usize,
+    /// `(source, line, matches)` for every line the rule matched on, most matches
+    /// first, then in input order.
+    pub lines: Vec<(String, u64, usize)>,
+    /// Distinct original values, in order of first appearance. Only collected when
+    /// asked for, so secrets are not held otherwise.
+    pub samples: Vec<String>,
+    /// The text around the rule's first match, redacted, when the engine captures
+    /// context (see `--capture-context`).
+    pub context: Option<String>,
+}
+
+/// Analyzes every input and returns one entry per matched rule, most occurrences
+/// first. `with_samples` keeps the original values.
+pub fn collect_stats(engine: &dyn SanitizationEngine, inputs: &[(String, String)], with_samples: bool) -> Result<Vec<RuleStats>> {
+    let mut summary = Vec::new();
+    let mut hashes: HashMap<String, HashSet<String>> = HashMap::new();
+    // Keyed by input index so lines keep their input order.
+    let mut lines: HashMap<String, BTreeMap<(usize, u64), usize>> = HashMap::new();
+    let mut samples: HashMap<String, Vec<String>> = HashMap::new();
+    let mut contexts: HashMap<String, String> = HashMap::new();
+    for (index, (label, content)) in inputs.iter().enumerate() {
+        merge_summary(&mut summary, engine.analyze_for_stats(content, label)?, engine.get_options());
+        for m in engine.find_matches_for_ui(content, label)? {
+            let finding = Finding::from_match(&m, label, content);
+            hashes.entry(m.rule_name.clone()).or_default().insert(finding.sample_hash);
+            *lines.entry(m.rule_name.clone()).or_default().entry((index, finding.line)).or_default() += 1;
+            if let Some(context) = m.context_line() {
+                contexts.entry(m.rule_name.clone()).or_insert(context);
+            }
+            if with_samples {
+                let rule_samples = samples.entry(m.rule_name.clone()).or_default();
+                if !rule_samples.contains(&m.original_string) {
+                    rule_samples.push(m.original_string);
+                }
+            }
+        }
+    }
+
+    let mut stats: Vec<RuleStats> = summary
+        .into_iter()
+        .map(|item| {
+            let mut rule_lines: Vec<(String, u64, usize)> = lines
+                .remove(&item.rule_name)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|((index, line), count)| (inputs[index].0.clone(), line, count))
+                .collect();
+            // Stable, so equal counts stay in input order.
+            rule_lines.sort_by_key(|&(_, _, count)| std::cmp::Reverse(count));
+            RuleStats {
+                unique: hashes.get(&item.rule_name).map_or(0, HashSet::len),
+                samples: samples.remove(&item.rule_name).unwrap_or_default(),
+                context: contexts.remove(&item.rule_name),
+                lines: rule_lines,
+                rule_name: item.rule_name,
+                severity: item.severity,
+                occurrences: item.occurrences,
+            }
+        })
+        .collect();
+    stats.sort_by(|a, b| b.occurrences.cmp(&a.occurrences).then_with(|| a.rule_name.cmp(&b.rule_name)));
+    Ok(stats)
+}
+
+/// Renders `stats` as a table with each rule's share of all occurrences. Lines are
+/// shown as `source:line` when `multi_source` is set, otherwise as bare numbers.
+/// A `Context` column is added when any rule has one.
+pub fn stats_table(stats: &[RuleStats], options: &StatsOptions, multi_source: bool) -> Table {
+    let total: usize = stats.iter().map(|s| s.occurrences).sum();
+    let with_context = stats.iter().any(|s| s.context.is_some());
+    let mut header = vec!["Rule", "Severity", "Occurrences", "Unique", "Share"];
+    if options.by_line {
+        header.push("Top Lines");
+    }
+    if options.show_samples {
+        header.push("Samples");
+    }
+    if with_context {
+        header.push("Context");
+    }
+
+    let mut table = Table::new();
+    table.set_header(header);
+    for rule in stats {
+        let share = if total == 0 { 0.0 } else { rule.occurrences as f64 * 100.0 / total as f64 };
+        let mut row = vec![
+            Cell::new(&rule.rule_name),
+            Cell::new(rule.severity),
+            Cell::new(rule.occurrences),
+            Cell::new(rule.unique),
+            Cell::new(format!("{:.1}%", share)),
+        ];
+        if options.by_line {
+            let top: Vec<String> = rule.lines.iter().take(options.top).map(|(source, line, count)| {
+                let at = if multi_source { format!("{}:{}", source, line) } else { line.to_string() };
+                format!("{} ({})", at, count)
+            }).collect();
+            row.push(Cell::new(top.join(", ")));
+        }
+        if options.show_samples {
+            row.push(Cell::new(rule.samples.join(", ")));
+        }
+        if with_context {
+            row.push(Cell::new(rule.context.as_deref().unwrap_or_default()));
+        }
+        table.add_row(row);
+    }
+    table
+}
+
+/// Prints the statistics for `paths` (stdin when empty or `-`) and returns the
+/// total number of occurrences.
+pub fn run_stats(engine: &dyn SanitizationEngine, paths: &[PathBuf], options: &StatsOptions) -> Result<usize> {
+    if options.show_samples && !pii_debug_allowed() {
+        bail!("--show-samples prints matched secrets in full; set CLEANSH_ALLOW_DEBUG_PII=true to allow it");
+    }
+    let inputs = read_inputs(paths)?;
+    let stats = collect_stats(engine, &inputs, options.show_samples)?;
+    if stats.is_empty() {
+        println!("No matches.");
+        return Ok(0);
+    }
+    println!("{}", stats_table(&stats, options, inputs.len() > 1));
+    Ok(stats.iter().map(|s| s.occurrences).sum())
+}
@@ -0,0 +1,29 @@
+// cleansh/src/commands/vault.rs
+//! `cleansh vault`: maintain the local fingerprint cache built from the
+//! organization's fingerprint vault.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+use cleansh_core::remediation::fingerprint_cache::FingerprintCache;
+use cleansh_core::remediation::vault::FingerprintVault;
+
+/// Rebuilds the cache at `output` from everything in `vault`, replacing the old
+/// file atomically. Returns the number of distinct fingerprints written.
+pub async fn compact(vault: &dyn FingerprintVault, output: &Path) -> Result<usize> {
+    let fingerprints = vault.fetch_all().await.context("Failed to fetch fingerprints from the vault")?;
+    if let Some(dir) = output.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let cache = FingerprintCache::rebuild(output, &fingerprints)?;
+    Ok(cache.len())
+}
+
+/// Runs `compact` and reports what was written.
+pub async fn run_compact(vault: &dyn FingerprintVault, output: &Path) -> Result<()> {
+    let written = compact(vault, output).await?;
+    let bytes = fs::metadata(output).map(|m| m.len()).unwrap_or(0);
+    println!("Wrote {} fingerprint(s) ({} bytes) to {}.", written, bytes, output.display());
+    Ok(())
+}
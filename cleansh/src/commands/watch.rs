@@ -0,0 +1,273 @@
+// cleansh/src/commands/watch.rs
+//! `cleansh watch`: follows log files and sanitizes every line appended to them.
+//!
+//! Files are followed by path, like `tail -F`: a file that is truncated is read
+//! again from the start, and one that is replaced (rotated) is finished and then
+//! reopened. With the `notify` feature, file system events wake the follower as
+//! soon as something changes; polling at `poll_interval` is the fallback either way.
+
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use cleansh_core::BlockAssembler;
+use cleansh_core::engine::{SanitizationEngine, SanitizeContext};
+use crate::utils::lines::{write_terminated, InputLine, LineTerminator};
+use crate::utils::platform::file_identity;
+use crate::utils::sink::OutputSink;
+
+/// Default for `--poll-interval-ms`.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A line longer than this is emitted in pieces rather than buffered until its newline.
+const MAX_PARTIAL_LINE: usize = 1 << 20;
+
+/// How `cleansh watch` follows its files.
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// Wait for files that do not exist yet instead of failing.
+    pub wait: bool,
+    /// Read what the files already hold before following them.
+    pub from_start: bool,
+    pub poll_interval: Duration,
+    /// End each output line as its input line ended (`\r\n`, `\n`, or nothing)
+    /// instead of always with `\n`.
+    pub preserve_line_endings: bool,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self { wait: false, from_start: false, poll_interval: DEFAULT_POLL_INTERVAL, preserve_line_endings: false }
+    }
+}
+
+/// One followed file: the open handle, how far it has been read, and the start of
+/// a line whose newline has not been written yet.
+#[derive(Debug)]
+pub struct FileTail {
+    path: PathBuf,
+    file: Option<File>,
+    identity: Option<(u64, u64)>,
+    offset: u64,
+    partial: Vec<u8>,
+}
+
+impl FileTail {
+    /// Opens `path`, positioned at its end unless `from_start` is set. A missing file
+    /// is an error unless `wait` is set, in which case it is read from its start once
+    /// it appears.
+    pub fn open(path: &Path, options: &WatchOptions) -> Result<Self> {
+        let mut tail = Self { path: path.to_path_buf(), file: None, identity: None, offset: 0, partial: Vec::new() };
+        match tail.reopen() {
+            Ok(()) => {
+                if !options.from_start {
+                    tail.offset = tail.file.as_mut().map(|f| f.seek(SeekFrom::End(0))).transpose()?.unwrap_or(0);
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound && options.wait => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                bail!("'{}' does not exist (pass --wait to wait for it)", path.display())
+            }
+            Err(e) => return Err(e).with_context(|| format!("Failed to open '{}'", path.display())),
+        }
+        Ok(tail)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn reopen(&mut self) -> io::Result<()> {
+        let file = File::open(&self.path)?;
+        self.identity = file_identity(&file.metadata()?);
+        self.file = Some(file);
+        self.offset = 0;
+        Ok(())
+    }
+
+    /// Returns the complete lines appended since the last call. A trailing line
+    /// without a newline is held back until it is finished, or released unterminated
+    /// when the file is rotated.
+    pub fn poll(&mut self) -> Result<Vec<InputLine>> {
+        let mut lines = Vec::new();
+        match fs::metadata(&self.path) {
+            Ok(meta) if self.file.is_none() => {
+                if meta.is_file() {
+                    self.reopen().with_context(|| format!("Failed to open '{}'", self.path.display()))?;
+                }
+            }
+            Ok(meta) if file_identity(&meta) != self.identity => {
+                // Rotated: finish the old file, then start on its replacement.
+                self.read_appended(&mut lines)?;
+                self.flush_partial(&mut lines);
+                self.reopen().with_context(|| format!("Failed to reopen '{}'", self.path.display()))?;
+            }
+            Ok(meta) if meta.len() < self.offset => {
+                if let Some(file) = self.file.as_mut() {
+                    file.seek(SeekFrom::Start(0))?;
+                }
+                self.offset = 0;
+                self.partial.clear();
+            }
+            // Missing while open: keep reading the old handle until a new file appears.
+            Ok(_) | Err(_) => {}
+        }
+        self.read_appended(&mut lines)?;
+        Ok(lines)
+    }
+
+    fn read_appended(&mut self, lines: &mut Vec<InputLine>) -> Result<()> {
+        let Some(file) = self.file.as_mut() else { return Ok(()) };
+        let mut appended = Vec::new();
+        let read = file.read_to_end(&mut appended)
+            .with_context(|| format!("Failed to read '{}'", self.path.display()))?;
+        self.offset += read as u64;
+
+        self.partial.extend_from_slice(&appended);
+        let mut start = 0;
+        while let Some(newline) = self.partial[start..].iter().position(|&b| b == b'\n') {
+            lines.push(InputLine::from_bytes(&self.partial[start..start + newline], true));
+            start += newline + 1;
+        }
+        self.partial.drain(..start);
+        if self.partial.len() > MAX_PARTIAL_LINE {
+            self.flush_partial(lines);
+        }
+        Ok(())
+    }
+
+    fn flush_partial(&mut self, lines: &mut Vec<InputLine>) {
+        if !self.partial.is_empty() {
+            lines.push(InputLine::from_bytes(&self.partial, false));
+            self.partial.clear();
+        }
+    }
+}
+
+/// Follows several files, labelling each line with the file it came from.
+pub struct Follower {
+    tails: Vec<FileTail>,
+    poll_interval: Duration,
+    #[cfg(feature = "notify")]
+    events: Option<(notify::RecommendedWatcher, std::sync::mpsc::Receiver<()>)>,
+}
+
+impl Follower {
+    pub fn new(paths: &[PathBuf], options: &WatchOptions) -> Result<Self> {
+        if paths.is_empty() {
+            bail!("watch needs at least one file");
+        }
+        let tails = paths.iter().map(|path| FileTail::open(path, options)).collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            #[cfg(feature = "notify")]
+            events: watch_events(&tails),
+            tails,
+            poll_interval: options.poll_interval,
+        })
+    }
+
+    /// Lines appended to any file since the last call, as `(label, line)` pairs in
+    /// file order.
+    pub fn poll(&mut self) -> Result<Vec<(String, InputLine)>> {
+        let mut lines = Vec::new();
+        for tail in &mut self.tails {
+            let label = tail.path().display().to_string();
+            lines.extend(tail.poll()?.into_iter().map(|line| (label.clone(), line)));
+        }
+        Ok(lines)
+    }
+
+    /// Blocks until a file may have changed: a file system event or the poll interval.
+    pub fn wait(&self) {
+        #[cfg(feature = "notify")]
+        if let Some((_, rx)) = &self.events {
+            let _ = rx.recv_timeout(self.poll_interval);
+            while rx.try_recv().is_ok() {}
+            return;
+        }
+        std::thread::sleep(self.poll_interval);
+    }
+
+    /// Whether output lines need the file they came from in front of them.
+    pub fn is_multi(&self) -> bool {
+        self.tails.len() > 1
+    }
+}
+
+/// Watches the directories holding `tails`, so rotation and late creation are seen
+/// too. Returns `None` (polling only) when the platform watcher cannot start.
+#[cfg(feature = "notify")]
+fn watch_events(tails: &[FileTail]) -> Option<(notify::RecommendedWatcher, std::sync::mpsc::Receiver<()>)> {
+    use notify::Watcher;
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |_event: notify::Result<notify::Event>| {
+        let _ = tx.send(());
+    })
+    .map_err(|e| log::warn!("File system events unavailable, polling instead: {}", e))
+    .ok()?;
+    for tail in tails {
+        let dir = match tail.path().parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        if let Err(e) = watcher.watch(dir, notify::RecursiveMode::NonRecursive) {
+            log::warn!("Cannot watch '{}', polling instead: {}", dir.display(), e);
+        }
+    }
+    Some((watcher, rx))
+}
+
+/// Sanitizes one followed line, or a block of lines joined with `\n`, prefixing
+/// every output line with its file when several are followed.
+pub fn sanitize_line(engine: &dyn SanitizationEngine, label: &str, line: &str, prefix: bool) -> Result<String> {
+    let mut ctx = SanitizeContext::new(label).with_reason("watch").with_outcome("success");
+    let (sanitized, _) = engine.sanitize_with(line, &mut ctx)?;
+    if !prefix {
+        return Ok(sanitized);
+    }
+    Ok(sanitized.split('\n').map(|l| format!("{}: {}", label, l)).collect::<Vec<_>>().join("\n"))
+}
+
+/// Follows `paths` and writes every appended line, sanitized, to `out` until
+/// interrupted or `out` fails (stdout is closed). Blocks declared by
+/// `block_start`/`block_end` rules are buffered per file and sanitized whole.
+pub fn run_watch(engine: &dyn SanitizationEngine, paths: &[PathBuf], options: &WatchOptions, out: &mut dyn OutputSink) -> Result<()> {
+    let mut follower = Follower::new(paths, options)?;
+    let prefix = follower.is_multi();
+    let assembler = BlockAssembler::from_rules(&engine.get_rules().rules)?;
+    // Per file: its block assembler and the terminators of the lines it holds.
+    let mut assemblers: HashMap<String, (BlockAssembler, Vec<LineTerminator>)> = HashMap::new();
+    loop {
+        let lines = follower.poll()?;
+        if !lines.is_empty() {
+            // Each poll's lines go to the sink as one batch.
+            let mut batch = Vec::new();
+            for (label, line) in lines {
+                let (unit, terminators) = if assembler.is_empty() {
+                    (Some(line.text), vec![line.terminator])
+                } else {
+                    let (assembler, held) = assemblers.entry(label.clone()).or_insert_with(|| (assembler.clone(), Vec::new()));
+                    held.push(line.terminator);
+                    match assembler.push(line.text).pop() {
+                        Some(unit) => (Some(unit), std::mem::take(held)),
+                        None => (None, Vec::new()),
+                    }
+                };
+                let Some(unit) = unit else { continue };
+                let sanitized = sanitize_line(engine, &label, &unit, prefix)?;
+                if options.preserve_line_endings {
+                    write_terminated(&mut batch, &sanitized, &terminators)?;
+                } else {
+                    writeln!(batch, "{}", sanitized)?;
+                }
+            }
+            if out.write_all(&batch).and_then(|_| out.flush()).is_err() {
+                return Ok(());
+            }
+        }
+        follower.wait();
+    }
+}
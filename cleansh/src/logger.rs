@@ -1,6 +1,7 @@
 // src/logger.rs
 use env_logger::{Builder, Target};
-use log::LevelFilter;
+use log::{Level, LevelFilter};
+use owo_colors::OwoColorize;
 use std::io::Write;
 use std::env;
 
@@ -11,8 +12,9 @@ use std::env;
 /// environment variable for the 'cleansh' crate and set the global minimum.
 /// Otherwise, `RUST_LOG` will be parsed, defaulting to `LevelFilter::Warn`
 /// for the 'cleansh' crate and globally if `RUST_LOG` is not set.
-/// Logs are formatted to include level, module path, and message.
-pub fn init_logger(explicit_level: Option<LevelFilter>) {
+/// Logs are formatted to include level, module path, and message; with `color`
+/// the level is colored (errors red, warnings yellow, info green).
+pub fn init_logger(explicit_level: Option<LevelFilter>, color: bool) {
     let mut builder = Builder::new();
 
     // Always parse RUST_LOG from the environment first.
@@ -41,11 +43,22 @@ pub fn init_logger(explicit_level: Option<LevelFilter>) {
 
     builder
         .target(Target::Stderr)
-        .format(|buf, record| {
+        .format(move |buf, record| {
+            let level = record.level();
+            let level = if !color {
+                level.to_string()
+            } else {
+                match level {
+                    Level::Error => level.red().bold().to_string(),
+                    Level::Warn => level.yellow().to_string(),
+                    Level::Info => level.green().to_string(),
+                    Level::Debug | Level::Trace => level.dimmed().to_string(),
+                }
+            };
             writeln!(
                 buf,
                 "[{} {}] {}",
-                record.level(),
+                level,
                 record.module_path().unwrap_or(""),
                 record.args()
             )
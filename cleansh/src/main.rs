@@ -4,19 +4,47 @@
 //! Initializes the chosen sanitization engine and launches the TUI runner.
 
 use cleansh_core::{
-    EntropyEngine, RegexEngine, 
+    CompositeEngine, EngineHandle, EngineOptions, EntropyEngine, RegexEngine, StreamProfile, 
     engine::SanitizationEngine, 
-    config::RedactionConfig,
-    load_profile_by_name,
-    apply_profile_to_config
+    ConfigFormat,
+    AuditKeySource, DEFAULT_AUDIT_KEY_ENV,
+    config::{RedactionConfig, RedactionRule, load_saved_entropy_threshold, merge_rules},
+    load_profile_with_keys,
+    apply_profile_to_config,
+    ProfileKeys, Severity,
+    install_safe_panic_hook,
 };
-use cleansh::tui::run_tui;
+use cleansh::tui::{run_tui, TuiInput, DEFAULT_REMEDIATION_CHANNEL_CAPACITY};
+use cleansh::tui::record::{RecordOptions, SPEED_RANGE};
+use cleansh::tui::app::{AutoApprovePolicy, DEFAULT_MAX_TRACKED_MATCHES, EngineType as TuiEngineType};
 use cleansh::ui::theme::build_theme_map;
-use clap::{Parser, ValueEnum};
+use cleansh::commands::{audit::{run_decrypt, run_migrate}, scan::{run_scan, scan_exit_code}};
+use cleansh::commands::diff_report::{run_diff_report, DiffFormat};
+use cleansh::commands::{corpus, ignore, profile, rules, vault};
+use cleansh::commands::sanitize::{run_sanitize, ClipboardOptions, SummaryOptions};
+use cleansh::commands::explain::run_explain;
+use cleansh::commands::bench::{run_bench, DEFAULT_BENCH_LINES};
+use cleansh::commands::stats::{run_stats, StatsOptions, DEFAULT_TOP_LINES};
+use cleansh::utils::clipboard::SystemClipboard;
+use cleansh::utils::sink::{open_sinks, SinkSpec};
+use cleansh::commands::watch::{run_watch, WatchOptions, DEFAULT_POLL_INTERVAL};
+use cleansh::commands::serve::{run_client, serve, EnginePool, ServeOptions, DEFAULT_CONNECTION_TIMEOUT, DEFAULT_MAX_CONNECTIONS};
+use cleansh::ui::output_format::{print_warning, ColorChoice, OutputFormat};
+use cleansh::ui::redaction_summary::SummaryFormat;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+use std::time::Duration;
 use anyhow::{Result, Context};
 use cleansh::logger;
+use cleansh::utils::platform::{fingerprint_cache_path, ignore_file_path, ignore_store_path, user_config_path, user_remediation_path, user_rules_path};
+use cleansh_core::crash_report::{set_crash_context, CrashContext, CRASH_DIR_ENV};
+use cleansh_core::ignore_store::IgnoreStore;
+use cleansh_core::remediation::channel::RemediationTxMode;
+use cleansh_core::remediation::RemediationSettings;
+use cleansh_core::remediation::vault::file::FileVault;
+use chrono::NaiveDate;
 
-#[derive(Debug, Clone, ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 enum EngineType {
     /// Pattern-based matching (Fast, reliable for known secrets)
     Regex,
@@ -26,57 +54,793 @@ enum EngineType {
     Hybrid,
 }
 
+#[derive(Subcommand)]
+enum Command {
+    /// Scan files (or stdin) once and report findings instead of launching the TUI
+    Scan {
+        /// Files to scan; reads stdin when omitted or given as '-'
+        paths: Vec<PathBuf>,
+
+        /// Report format
+        #[arg(long, value_enum, default_value = "text")]
+        output_format: OutputFormat,
+
+        /// Also scan JSON and YAML inputs by key, flagging values of keys such as
+        /// `password` or `token` (or set `engines.structured.enabled` in the config)
+        #[arg(long)]
+        structured: bool,
+
+        /// Exit 2 only when a finding is at or above this severity (info, low,
+        /// medium, high or critical), and 0 otherwise
+        #[arg(long, value_name = "LEVEL")]
+        fail_on_severity: Option<Severity>,
+    },
+    /// Sanitize files (or stdin) to stdout and summarize what was redacted
+    Sanitize {
+        /// Files to sanitize; reads stdin when omitted or given as '-'
+        paths: Vec<PathBuf>,
+
+        /// Summary format
+        #[arg(long, value_enum, default_value = "text")]
+        summary_format: SummaryFormat,
+
+        /// Write the summary to this file instead of stderr
+        #[arg(long)]
+        summary_output: Option<PathBuf>,
+
+        /// Include each rule's replacement texts in the summary
+        #[arg(long)]
+        summary_samples: bool,
+
+        /// Sanitize the clipboard's text instead of files or stdin
+        #[arg(long, conflicts_with = "paths")]
+        from_clipboard: bool,
+
+        /// Also put the sanitized text on the clipboard
+        #[arg(long)]
+        clipboard: bool,
+
+        /// Sanitize clipboard contents over 10 MB without asking
+        #[arg(long, short = 'y')]
+        yes: bool,
+
+        #[command(flatten)]
+        sink: SinkArgs,
+    },
+    /// Count matches per rule in files (or stdin) without printing the matched values
+    Stats {
+        /// Files to analyze; reads stdin when omitted or given as '-'
+        paths: Vec<PathBuf>,
+
+        /// Another file to analyze, as if given as a path
+        #[arg(long, value_name = "PATH")]
+        input_file: Vec<PathBuf>,
+
+        /// Also list the lines with the most matches for each rule
+        #[arg(long)]
+        by_line: bool,
+
+        /// How many lines --by-line lists per rule
+        #[arg(long, default_value_t = DEFAULT_TOP_LINES)]
+        top: usize,
+
+        /// List the matched values themselves; requires CLEANSH_ALLOW_DEBUG_PII=true
+        #[arg(long)]
+        show_samples: bool,
+    },
+    /// Read one line from stdin and show why each part of it was or was not redacted
+    Explain,
+    /// Check the selected engine against snapshot corpora of expected output
+    Corpus {
+        #[command(subcommand)]
+        action: CorpusCommand,
+    },
+    /// Time the selected engine on a generated log corpus and print lines per second
+    #[command(hide = true)]
+    Bench {
+        /// How many lines to sanitize
+        #[arg(long, default_value_t = DEFAULT_BENCH_LINES)]
+        lines: usize,
+    },
+    /// Follow log files and sanitize every line appended to them
+    Watch {
+        /// Files to follow; with several, each line is prefixed with its file
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+
+        /// Wait for files that do not exist yet instead of failing
+        #[arg(long)]
+        wait: bool,
+
+        /// Also sanitize what the files already contain
+        #[arg(long)]
+        from_start: bool,
+
+        /// How often to check the files for new lines, in milliseconds
+        #[arg(long, default_value_t = DEFAULT_POLL_INTERVAL.as_millis() as u64)]
+        poll_interval_ms: u64,
+
+        /// End each printed line the way it ended in the file (CRLF, LF, or no
+        /// newline) instead of always with LF
+        #[arg(long)]
+        preserve_line_endings: bool,
+
+        /// Show the lines in the TUI instead of printing them
+        #[arg(long)]
+        tui: bool,
+
+        #[command(flatten)]
+        sink: SinkArgs,
+    },
+    /// Compare two JSON scan reports or redaction maps and summarize what changed
+    DiffReport {
+        /// Baseline report or redaction map
+        a: PathBuf,
+        /// Report or redaction map to compare against the baseline
+        b: PathBuf,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: DiffFormat,
+
+        /// Exit non-zero when the redactions differ
+        #[arg(long)]
+        fail_on_change: bool,
+    },
+    /// Sanitize requests from shell hooks and editors over a Unix socket (a named pipe on Windows)
+    Serve {
+        /// Socket path to listen on (on Windows, a pipe name such as \\.\pipe\cleansh)
+        #[arg(long)]
+        socket: PathBuf,
+
+        /// How many connections to serve at once; later ones wait
+        #[arg(long, default_value_t = DEFAULT_MAX_CONNECTIONS)]
+        max_connections: usize,
+
+        /// Close connections that send nothing for this many seconds
+        #[arg(long, default_value_t = DEFAULT_CONNECTION_TIMEOUT.as_secs())]
+        timeout_secs: u64,
+    },
+    /// Send a file (or stdin) to a running `cleansh serve` and print the sanitized text
+    Client {
+        /// Socket path (or pipe name) the server listens on
+        #[arg(long)]
+        socket: PathBuf,
+
+        /// File to sanitize; reads stdin when omitted or given as '-'
+        input: Option<PathBuf>,
+    },
+    /// List, try out, and add redaction rules
+    Rules {
+        #[command(subcommand)]
+        action: RulesCommand,
+    },
+    /// Manage the ignore file of match hashes to leave unredacted
+    Ignore {
+        #[command(subcommand)]
+        action: IgnoreCommand,
+    },
+    /// Sign, verify, and list redaction profiles
+    Profile {
+        #[command(subcommand)]
+        action: ProfileCommand,
+    },
+    /// Maintain the local cache of fingerprints from the organization vault
+    Vault {
+        #[command(subcommand)]
+        action: VaultCommand,
+    },
+    /// Inspect and maintain audit log files
+    Audit {
+        #[command(subcommand)]
+        action: AuditCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum RulesCommand {
+    /// Print the merged rule set (built-in rules plus your config and rules files)
+    List,
+    /// Run one rule over sample text read from stdin and show where it matches
+    Test {
+        /// Name of the rule to try
+        rule_name: String,
+    },
+    /// Show a rule's pattern, flags, metadata, and validator, and optionally the groups it captures from a sample
+    Explain {
+        /// Name of the rule to explain
+        rule_name: String,
+        /// Text to run the rule over, showing each match's capture groups and their spans
+        #[arg(long)]
+        sample: Option<String>,
+    },
+    /// Validate a new rule and append it to your rules file
+    Add {
+        /// Unique rule name
+        #[arg(long)]
+        name: String,
+        /// Regex pattern to match
+        #[arg(long)]
+        pattern: String,
+        /// Replacement text for matches
+        #[arg(long, default_value = "[REDACTED]")]
+        replace_with: String,
+        /// Human-readable description
+        #[arg(long)]
+        description: Option<String>,
+        /// Severity reported by `cleansh scan`
+        #[arg(long)]
+        severity: Option<String>,
+        /// Tags for profile and CLI filtering (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        tags: Vec<String>,
+        /// Only activate the rule when a profile or tag filter enables it
+        #[arg(long)]
+        opt_in: bool,
+        /// Replace an existing rule with the same name
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum CorpusCommand {
+    /// Sanitize every `<case>.input` in a directory and compare it with `<case>.expected`
+    Run {
+        /// Directory holding the case files
+        dir: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum IgnoreCommand {
+    /// Show every entry and whether it is still active
+    List,
+    /// Ignore the value read from stdin (only its hash is stored)
+    Add {
+        /// Last day the entry applies (YYYY-MM-DD)
+        #[arg(long)]
+        expires: Option<NaiveDate>,
+        /// Why the value is safe to leave unredacted
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Remove expired entries, keeping comments
+    Prune,
+    /// Stop ignoring a hash, from the ignore file or the TUI's ignore store
+    Remove {
+        /// The hash shown by `cleansh ignore list`
+        hash: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProfileCommand {
+    /// Sign a profile file in place with the HMAC key from the environment
+    Sign {
+        /// Profile file to sign
+        path: PathBuf,
+        /// Environment variable holding the hex-encoded HMAC key
+        #[arg(long, default_value = profile::DEFAULT_KEY_ENV)]
+        key_env: String,
+        /// Copy the file to `<path>.bak` before signing it
+        #[arg(long)]
+        backup: bool,
+    },
+    /// Check a profile file's signature; fails when it is unsigned, unverifiable, or tampered with
+    Verify {
+        /// Profile file to verify
+        path: PathBuf,
+        /// Environment variable holding the hex-encoded HMAC key
+        #[arg(long, default_value = profile::DEFAULT_KEY_ENV)]
+        key_env: String,
+    },
+    /// List the profiles found in the profile directories
+    List,
+}
+
+#[derive(Subcommand)]
+enum VaultCommand {
+    /// Rebuild the memory-mapped fingerprint cache from the vault
+    Compact {
+        /// Vault file (a JSON array of fingerprints)
+        #[arg(long)]
+        vault: PathBuf,
+        /// Where to write the cache (defaults to the platform cache directory)
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuditCommand {
+    /// Rewrite an older audit file in the current format
+    Migrate {
+        /// Audit file to read
+        input: PathBuf,
+        /// Where to write the migrated file (must not exist)
+        output: PathBuf,
+    },
+    /// Print the entries of an encrypted audit file as JSON Lines
+    Decrypt {
+        /// Encrypted audit file to read
+        input: PathBuf,
+        /// Environment variable holding the hex-encoded audit key
+        #[arg(long, default_value = DEFAULT_AUDIT_KEY_ENV, conflicts_with = "keyring")]
+        key_env: String,
+        /// Read the key from the OS keyring instead
+        #[arg(long)]
+        keyring: bool,
+    },
+}
+
 #[derive(Parser)]
 #[command(name = "cleansh", author, version, about)]
 struct Cli {
-    /// Select the sanitization engine
-    #[arg(long, short = 'e', value_enum, default_value = "entropy")]
-    engine: EngineType,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Select the sanitization engine [default: the profile's `engine.type`, else entropy]
+    #[arg(long, short = 'e', value_enum, global = true)]
+    engine: Option<EngineType>,
 
     /// Load specific security profile
-    #[arg(long, short = 'p', default_value = "default")]
+    #[arg(long, short = 'p', default_value = "default", global = true)]
     profile: String,
 
     /// Suppress internal logging
-    #[arg(long, short = 'q', default_value_t = true)]
+    #[arg(long, short = 'q', default_value_t = true, global = true)]
     quiet: bool,
+
+    /// On a crash, print a scrubbed message and write a sanitized crash report
+    /// (also on when CLEANSH_CRASH_DIR is set)
+    #[arg(long, global = true)]
+    crash_report: bool,
+
+    /// Treat rule replacement conflicts as errors instead of warnings
+    #[arg(long, global = true)]
+    strict_rules: bool,
+
+    /// Refuse to load profiles that are unsigned or whose signature cannot be verified
+    #[arg(long, global = true)]
+    require_signed_profiles: bool,
+
+    /// Validate the selected profile, report redundant overrides, and exit
+    #[arg(long)]
+    lint_profile: bool,
+
+    /// Only activate rules carrying one of these tags (comma-separated)
+    #[arg(long, value_delimiter = ',', global = true)]
+    include_tags: Vec<String>,
+
+    /// Deactivate rules carrying any of these tags (comma-separated)
+    #[arg(long, value_delimiter = ',', global = true)]
+    exclude_tags: Vec<String>,
+
+    /// Load only these built-in rule packs, e.g. `cloud,pii` (comma-separated) [default: all]
+    #[arg(long, value_delimiter = ',', global = true)]
+    rules_pack: Vec<String>,
+
+    /// Merge rules from this file on top of the built-in and user rules (repeatable)
+    #[arg(long = "rules", value_name = "PATH", global = true)]
+    rules_files: Vec<PathBuf>,
+
+    /// Activate these rules by name, including opt-in rules (comma-separated)
+    #[arg(long, value_delimiter = ',', global = true)]
+    enable_rules: Vec<String>,
+
+    /// Deactivate these rules by name (comma-separated)
+    #[arg(long, value_delimiter = ',', global = true)]
+    disable_rules: Vec<String>,
+
+    /// Format of config and rules files whose extension names none: yaml, toml, or json [default: yaml]
+    #[arg(long, value_name = "FORMAT", global = true)]
+    config_format: Option<ConfigFormat>,
+
+    /// Where the TUI forwards sanitized output
+    #[command(flatten)]
+    sink: SinkArgs,
+
+    /// When to color summaries, diff reports, and warnings: auto, always, or never.
+    /// `auto` honors NO_COLOR and CLICOLOR_FORCE, then checks for a terminal
+    #[arg(long, value_enum, value_name = "WHEN", default_value = "auto", global = true)]
+    color: ColorChoice,
+
+    /// Record the TUI's input lines and key presses to this JSON Lines file, with
+    /// secrets hashed
+    #[arg(long, value_name = "PATH")]
+    record: Option<PathBuf>,
+
+    /// Record input lines as received, secrets included
+    #[arg(long, requires = "record")]
+    record_raw: bool,
+
+    /// Feed the TUI from a recording made with --record instead of stdin
+    #[arg(long, value_name = "PATH")]
+    replay: Option<PathBuf>,
+
+    /// Replay this many times faster than recorded
+    #[arg(long, default_value_t = 1.0, requires = "replay")]
+    speed: f64,
+
+    /// Capture up to 40 characters on each side of every match, other secrets
+    /// redacted, for the match detail, JSON reports, and `stats`
+    #[arg(long, global = true)]
+    capture_context: bool,
+
+    /// Maximum matches kept on the dashboard before the oldest are evicted
+    #[arg(long, default_value_t = DEFAULT_MAX_TRACKED_MATCHES)]
+    max_tracked_matches: usize,
+
+    /// Approve matches on the dashboard without waiting for `a`: manual, high
+    /// (confidence High or above), or verified-live (confirmed live by a provider)
+    #[arg(long, value_name = "LEVEL", default_value = "manual")]
+    auto_approve: AutoApprovePolicy,
+
+    /// What to do with matches when the remediation channel is full: drop, block, or coalesce
+    #[arg(long, default_value = "drop")]
+    remediation_tx_mode: RemediationTxMode,
+
+    /// Capacity of the channel carrying matches from the engine to the dashboard
+    #[arg(long, default_value_t = DEFAULT_REMEDIATION_CHANNEL_CAPACITY)]
+    remediation_channel_capacity: usize,
+}
+
+/// `--engine` when given, otherwise the profile's `engine.type`, otherwise entropy.
+/// Overriding a profile that demands a different engine is allowed but warned about.
+fn select_engine(cli: Option<EngineType>, profile_engine: Option<&str>, color: ColorChoice) -> Result<EngineType> {
+    let from_profile = profile_engine
+        .map(|name| EngineType::from_str(name, true).map_err(|e| anyhow::anyhow!("Invalid profile engine type '{}': {}", name, e)))
+        .transpose()?;
+    match (cli, from_profile) {
+        (Some(cli), Some(profile)) if cli != profile => {
+            print_warning(color, format_args!(
+                "--engine {} overrides the profile's engine type '{}'.",
+                engine_name(cli), engine_name(profile)
+            ));
+            Ok(cli)
+        }
+        (cli, profile) => Ok(cli.or(profile).unwrap_or(EngineType::Entropy)),
+    }
+}
+
+fn engine_name(engine: EngineType) -> &'static str {
+    match engine {
+        EngineType::Regex => "regex",
+        EngineType::Entropy => "entropy",
+        EngineType::Hybrid => "hybrid",
+    }
+}
+
+/// The ignore store at `ignore_store_path`, or an in-memory one without a config directory.
+fn load_ignore_store() -> Result<IgnoreStore> {
+    ignore_store_path().map_or_else(|| Ok(IgnoreStore::default()), IgnoreStore::load)
+}
+
+/// `--sink` and `--pipe-to`, taken by the commands that write sanitized output:
+/// `sanitize`, `watch`, and the TUI.
+#[derive(clap::Args, Debug, Clone, Default)]
+struct SinkArgs {
+    /// Send sanitized output here instead of stdout: stdout, file:<path>,
+    /// tcp:<host:port>, unix:<path>, or cmd:<command> (repeatable)
+    #[arg(long = "sink", value_name = "URI")]
+    sinks: Vec<SinkSpec>,
+
+    /// Pipe sanitized output into this shell command's stdin; same as --sink cmd:<command>
+    #[arg(long, value_name = "COMMAND")]
+    pipe_to: Option<String>,
+}
+
+impl SinkArgs {
+    fn is_empty(&self) -> bool {
+        self.sinks.is_empty() && self.pipe_to.is_none()
+    }
+
+    /// The `--sink` values plus `--pipe-to`, in that order.
+    fn specs(&self) -> Vec<SinkSpec> {
+        let mut sinks = self.sinks.clone();
+        sinks.extend(self.pipe_to.iter().map(|command| SinkSpec::Command(command.clone())));
+        sinks
+    }
+}
+
+/// Builds the selected engine for the one-shot `scan` and `sanitize` commands.
+fn build_engine(engine: &EngineType, config: RedactionConfig, options: EngineOptions) -> Result<Box<dyn SanitizationEngine>> {
+    Ok(match engine {
+        EngineType::Regex => Box::new(RegexEngine::with_options(config, options)?),
+        EngineType::Entropy => Box::new(EntropyEngine::with_options(config, options)?),
+        EngineType::Hybrid => Box::new(CompositeEngine::with_options(config, options)?),
+    })
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Cli::parse();
+    // Panic messages can quote the line being sanitized; print them scrubbed.
+    if args.crash_report || std::env::var_os(CRASH_DIR_ENV).is_some() {
+        install_safe_panic_hook();
+    }
 
+    let log_color = args.color.enabled_for(&std::io::stderr());
     if args.quiet {
-        logger::init_logger(Some(log::LevelFilter::Off));
+        logger::init_logger(Some(log::LevelFilter::Off), log_color);
     } else {
-        logger::init_logger(Some(log::LevelFilter::Debug));
+        logger::init_logger(Some(log::LevelFilter::Debug), log_color);
+    }
+
+    if args.command.is_some() && !args.sink.is_empty() {
+        anyhow::bail!("--sink and --pipe-to go after the subcommand, and only sanitize and watch take them");
+    }
+
+    match &args.command {
+        Some(Command::Audit { action: AuditCommand::Migrate { input, output } }) => return run_migrate(input, output),
+        Some(Command::Audit { action: AuditCommand::Decrypt { input, key_env, keyring } }) => {
+            let source = if *keyring { AuditKeySource::Keyring } else { AuditKeySource::HexEnv(key_env.clone()) };
+            return run_decrypt(input, &source);
+        }
+        Some(Command::Ignore { action }) => {
+            let path = ignore_file_path();
+            let store = load_ignore_store()?;
+            return match action {
+                IgnoreCommand::List => ignore::run_list(&path, &store),
+                IgnoreCommand::Remove { hash } => ignore::run_remove(&path, &store, hash),
+                IgnoreCommand::Add { expires, reason } => ignore::run_add(&path, *expires, reason.clone()),
+                IgnoreCommand::Prune => ignore::run_prune(&path),
+            };
+        }
+        Some(Command::Profile { action }) => {
+            return match action {
+                ProfileCommand::Sign { path, key_env, backup } => profile::run_sign(path, key_env, *backup),
+                ProfileCommand::Verify { path, key_env } => profile::run_verify(path, key_env),
+                ProfileCommand::List => profile::run_list(),
+            };
+        }
+        Some(Command::Vault { action: VaultCommand::Compact { vault: vault_path, output } }) => {
+            let output = output.clone().or_else(fingerprint_cache_path)
+                .context("Could not determine a location for the fingerprint cache")?;
+            return vault::run_compact(&FileVault::new(vault_path), &output).await;
+        }
+        Some(Command::DiffReport { a, b, format, fail_on_change }) => {
+            if run_diff_report(a, b, *format, args.color)? && *fail_on_change {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(Command::Client { socket, input }) => {
+            let profile = (args.profile != "default").then_some(args.profile.as_str());
+            return run_client(socket, input.as_deref(), args.engine.map(engine_name), profile).await;
+        }
+        _ => {}
     }
 
     let theme_map = build_theme_map(None).context("Theme error")?;
     
     // 1. Load Base Rules
-    let mut config = RedactionConfig::load_default_rules()?;
+    let mut config = if args.rules_pack.is_empty() {
+        RedactionConfig::load_default_rules()?
+    } else {
+        RedactionConfig::load_rule_packs(&args.rules_pack).context("Invalid --rules-pack")?
+    };
+    // Only the threshold saved from the TUI is read from the user's config file.
+    let config_path = user_config_path();
+    if let Some(path) = config_path.as_ref().filter(|p| p.exists()) {
+        match load_saved_entropy_threshold(path) {
+            Ok(Some(threshold)) => config.engines.entropy.threshold = Some(threshold),
+            Ok(None) => {}
+            Err(e) => print_warning(args.color, format_args!("Ignoring the saved entropy threshold: {:#}", e)),
+        }
+    }
+    let rules_path = user_rules_path();
+    if let Some(path) = rules_path.as_ref().filter(|p| p.exists()) {
+        let user_rules = RedactionConfig::load_from_file_as(path, args.config_format)
+            .with_context(|| format!("Failed to load rules file '{}'", path.display()))?;
+        config = merge_rules(config, Some(user_rules));
+    }
+    for path in &args.rules_files {
+        let extra_rules = RedactionConfig::load_from_file_as(path, args.config_format)
+            .with_context(|| format!("Failed to load --rules file '{}'", path.display()))?;
+        config = merge_rules(config, Some(extra_rules));
+    }
+
+    // Rule authoring works on the unfiltered rule set, before any profile is applied.
+    if let Some(Command::Rules { action }) = &args.command {
+        match action {
+            RulesCommand::List => rules::run_list(&config),
+            RulesCommand::Test { rule_name } => {
+                if rules::run_test(&config, rule_name)? == 0 {
+                    std::process::exit(1);
+                }
+            }
+            RulesCommand::Explain { rule_name, sample } => rules::run_explain(&config, rule_name, sample.as_deref())?,
+            RulesCommand::Add { name, pattern, replace_with, description, severity, tags, opt_in, force } => {
+                let path = rules_path.context("Could not determine a location for the rules file")?;
+                let rule = RedactionRule {
+                    name: name.clone(),
+                    pattern: Some(pattern.clone()),
+                    replace_with: replace_with.clone(),
+                    description: description.clone(),
+                    severity: severity.clone(),
+                    tags: (!tags.is_empty()).then(|| tags.clone()),
+                    opt_in: *opt_in,
+                    ..Default::default()
+                };
+                rules::run_add(&config, &path, rule, *force)?;
+            }
+        }
+        return Ok(());
+    }
+
+    // Requests to `serve` may name their own profile, applied to the rules as they are now.
+    let serve_rules = matches!(args.command, Some(Command::Serve { .. })).then(|| config.clone());
 
     // 2. Apply Profile Override (The Fix)
+    let mut token_format = None;
+    let mut profile_engine = None;
+    let mut capture_context = args.capture_context;
     if args.profile != "default" {
         // This will error if the profile doesn't exist, fixing the test case
-        let profile_config = load_profile_by_name(&args.profile)
+        let keys = ProfileKeys::from_env()?.with_require_signed(args.require_signed_profiles);
+        let profile_config = load_profile_with_keys(&args.profile, &keys)
             .with_context(|| format!("Failed to load profile '{}'", args.profile))?;
+        profile_config.validate(&config)?;
+
+        if args.lint_profile {
+            let findings = profile_config.lint(&config);
+            for finding in &findings {
+                print_warning(args.color, finding);
+            }
+            if !findings.is_empty() {
+                std::process::exit(1);
+            }
+            println!("Profile '{}' is clean.", profile_config.profile_name);
+            return Ok(());
+        }
         
         config = apply_profile_to_config(&profile_config, config);
+        profile_engine = profile_config.engine_type().map(str::to_string);
+        capture_context |= profile_config.reporting.as_ref().is_some_and(|reporting| reporting.capture_context);
+        token_format = profile_config.post_processing
+            .filter(|pp| pp.replace_with_token)
+            .map(|pp| pp.effective_token_format().to_string());
+    }
+
+    if args.lint_profile {
+        anyhow::bail!("--lint-profile requires a profile to be selected with --profile");
+    }
+
+    let engine_type = select_engine(args.engine, profile_engine.as_deref(), args.color)?;
+    set_crash_context(CrashContext {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        engine: engine_name(engine_type).to_string(),
+        profile: (args.profile != "default").then(|| args.profile.clone()),
+    });
+
+    // CLI rule names override the profile; explicit enables also survive the tag filters below.
+    if !args.enable_rules.is_empty() || !args.disable_rules.is_empty() {
+        config.set_active_rules(&args.enable_rules, &args.disable_rules);
+    }
+
+    // CLI tag filters narrow whatever the profile left active.
+    if !args.include_tags.is_empty() || !args.exclude_tags.is_empty() {
+        config.set_active_rules_by_tags(&args.include_tags, &args.exclude_tags);
     }
 
+    // Cross-rule checks need the final enabled set, so they run after the profile is applied.
+    config.validate_replacements(token_format.as_deref(), args.strict_rules)?;
+
     // 3. Multi-Engine Bootstrapping
-    let engine: Box<dyn SanitizationEngine> = match args.engine {
-        EngineType::Regex => Box::new(RegexEngine::new(config)?),
-        EngineType::Entropy => Box::new(EntropyEngine::new(config)?),
-        EngineType::Hybrid => {
-            // Future: Implement a CompositeEngine to wrap both
-            Box::new(EntropyEngine::new(config)?)
+    let mut options = EngineOptions::default()
+        .with_ignored_hashes(ignore::load_ignored_hashes(&ignore_file_path(), ignore::today())?)
+        .with_remediation_tx_mode(args.remediation_tx_mode)
+        .with_ignore_store(load_ignore_store()?)
+        .with_capture_context(capture_context);
+    if args.profile != "default" {
+        options.profile_meta.profile_name = args.profile.clone();
+    }
+    if let Some(Command::Scan { paths, output_format, structured, fail_on_severity }) = &args.command {
+        let structured = *structured || config.engines.structured.enabled.unwrap_or(false);
+        let engine = build_engine(&engine_type, config, options)?;
+        let findings = run_scan(engine.as_ref(), paths, *output_format, structured)?;
+        let code = scan_exit_code(&findings, *fail_on_severity);
+        if code != 0 {
+            std::process::exit(code);
         }
-    };
+        return Ok(());
+    }
+    if let Some(Command::Sanitize { paths, summary_format, summary_output, summary_samples, from_clipboard, clipboard, yes, sink }) = &args.command {
+        let engine = build_engine(&engine_type, config, options)?;
+        let summary = SummaryOptions {
+            format: *summary_format,
+            samples: *summary_samples,
+            output: summary_output.clone(),
+            color: args.color,
+        };
+        let clipboard_options = ClipboardOptions { from_clipboard: *from_clipboard, write_back: *clipboard, yes: *yes };
+        let mut out = open_sinks(&sink.specs())?;
+        run_sanitize(engine.as_ref(), paths, &summary, &mut SystemClipboard, &clipboard_options, out.as_mut())?;
+        return Ok(());
+    }
+
+    if let Some(Command::Stats { paths, input_file, by_line, top, show_samples }) = &args.command {
+        let engine = build_engine(&engine_type, config, options)?;
+        let paths: Vec<PathBuf> = paths.iter().chain(input_file).cloned().collect();
+        let stats_options = StatsOptions { by_line: *by_line, top: *top, show_samples: *show_samples };
+        run_stats(engine.as_ref(), &paths, &stats_options)?;
+        return Ok(());
+    }
 
-    run_tui(engine, theme_map).await.context("TUI failure")?;
+    if let Some(Command::Explain) = &args.command {
+        let engine = build_engine(&engine_type, config, options.with_explain(true))?;
+        return run_explain(engine.as_ref());
+    }
+
+    if let Some(Command::Corpus { action: CorpusCommand::Run { dir } }) = &args.command {
+        let engine = build_engine(&engine_type, config, options)?;
+        if !corpus::run(engine.as_ref(), dir) {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Bench { lines }) = &args.command {
+        let engine = build_engine(&engine_type, config, options)?;
+        return run_bench(engine.as_ref(), engine_name(engine_type), *lines);
+    }
+
+    if let Some(Command::Serve { socket, max_connections, timeout_secs }) = &args.command {
+        let rules = serve_rules.unwrap_or_default();
+        let engine = build_engine(&engine_type, config, options.clone())?;
+        let pool = EnginePool::new(EngineHandle::new(engine.into()), engine_name(engine_type).parse()?, rules, options);
+        let serve_options = ServeOptions { max_connections: *max_connections, timeout: Duration::from_secs(*timeout_secs) };
+        eprintln!("Serving on {} (Ctrl-C to stop).", socket.display());
+        return serve(socket, pool, &serve_options, async {
+            let _ = tokio::signal::ctrl_c().await;
+        }).await;
+    }
+
+    let mut tui_input = TuiInput::Stdin;
+    let mut tui_sinks = args.sink.specs();
+    if let Some(Command::Watch { paths, wait, from_start, poll_interval_ms, preserve_line_endings, tui, sink }) = &args.command {
+        let watch_options = WatchOptions {
+            wait: *wait,
+            from_start: *from_start,
+            poll_interval: Duration::from_millis((*poll_interval_ms).max(1)),
+            preserve_line_endings: *preserve_line_endings,
+        };
+        if !*tui {
+            let engine = build_engine(&engine_type, config, options.with_stream_profile(StreamProfile::default()))?;
+            let mut out = open_sinks(&sink.specs())?;
+            return run_watch(engine.as_ref(), paths, &watch_options, out.as_mut());
+        }
+        tui_input = TuiInput::Watch(paths.clone(), watch_options);
+        tui_sinks = sink.specs();
+    }
+    if let Some(path) = &args.replay {
+        if matches!(tui_input, TuiInput::Watch(..)) {
+            anyhow::bail!("--replay cannot be combined with watch --tui");
+        }
+        if !SPEED_RANGE.contains(&args.speed) {
+            anyhow::bail!("--speed must be between {} and {}, got {}", SPEED_RANGE.start(), SPEED_RANGE.end(), args.speed);
+        }
+        tui_input = TuiInput::Replay(path.clone(), args.speed);
+    }
+    let record = args.record.clone().map(|path| RecordOptions { path, raw: args.record_raw });
+
+    // The TUI builds its own engine so it can wire in the remediation channel.
+    let tui_engine = match engine_type {
+        EngineType::Regex => TuiEngineType::Regex,
+        EngineType::Entropy => TuiEngineType::Entropy,
+        EngineType::Hybrid => TuiEngineType::Hybrid,
+    };
+    // Providers come from the remediation config, plus any named in the environment.
+    let mut remediation = match user_remediation_path().filter(|p| p.exists()) {
+        Some(path) => RemediationSettings::load_from_file(&path)?,
+        None => RemediationSettings::default(),
+    };
+    remediation.providers = remediation.providers.with_env();
+    // Lines arrive one at a time, so they are scored against the profile of the stream.
+    let options = options.with_stream_profile(StreamProfile::default());
+    run_tui(config, options, tui_engine, theme_map, config_path, args.max_tracked_matches, args.remediation_channel_capacity, tui_input, remediation, &tui_sinks, record, args.auto_approve).await.context("TUI failure")?;
 
     Ok(())
 }
\ No newline at end of file
@@ -3,12 +3,28 @@
 //!
 //! Manages buffers, security matches, and the dynamic engine switcher state.
 
-use cleansh_core::redaction_match::RedactionMatch;
+use cleansh_core::redaction_match::{RedactionMatch, canonical_sample_hash, ensure_match_hashes, redact_sensitive};
+use cleansh_core::config::DEFAULT_HEAT_BANDS;
+use cleansh_core::ignore_store::IgnoreStore;
+use cleansh_core::metrics::{DecodeMetrics, EngineMetricsSnapshot};
+use cleansh_core::remediation::journal::JournalEntry;
+use cleansh_core::remediation::{confidence_from, ConfidenceLevel};
+use cleansh_core::Severity;
+use cleansh_core::remediation::orchestrator::{ApprovalMessage, GovernorStatus, PlanRequest, ProviderChoice, ProviderPlan, APPROVAL_GRACE};
 use crate::tui::sync::SyncStats;
+use crate::tui::ui::confidence_label;
+use crate::utils::clipboard::{ClipboardSink, SystemClipboard};
+use crate::utils::lines::SourcedLine;
 use ratatui::widgets::ListState;
+use serde::Serialize;
 use std::collections::VecDeque;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum RemediationStatus {
     Pending,
     Approved,
@@ -16,6 +32,114 @@ pub enum RemediationStatus {
     Ignored,
 }
 
+impl RemediationStatus {
+    /// Revoked and Ignored matches need no further action, so their raw value can be dropped.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, RemediationStatus::Revoked | RemediationStatus::Ignored)
+    }
+}
+
+/// A status change that `u` can revert while it is younger than the undo window.
+#[derive(Debug, Clone)]
+pub struct UndoEntry {
+    pub sample_hash: String,
+    /// The match as it was before the change; an Ignore masks the live copy, so this
+    /// is the only plaintext left until the entry expires.
+    pub previous: RedactionMatch,
+    pub from: RemediationStatus,
+    pub to: RemediationStatus,
+    pub at: Instant,
+    /// Whether the auto-approve policy made the change rather than the operator.
+    pub auto: bool,
+}
+
+/// Which newly arrived matches are approved without waiting for `a`; cycled with `P`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AutoApprovePolicy {
+    /// Every match waits for the operator.
+    #[default]
+    Manual,
+    /// Matches whose confidence is High or above.
+    High,
+    /// Matches a remediation provider has confirmed are live.
+    VerifiedLive,
+}
+
+impl AutoApprovePolicy {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Manual => Self::High,
+            Self::High => Self::VerifiedLive,
+            Self::VerifiedLive => Self::Manual,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Manual => "manual",
+            Self::High => "auto \u{2265} high",
+            Self::VerifiedLive => "auto verified-live",
+        }
+    }
+
+    /// Whether `m` is approved as it arrives. Liveness is only known once a provider
+    /// has checked the secret, so `VerifiedLive` approves in [`App::mark_verified_live`].
+    pub fn admits(self, m: &RedactionMatch) -> bool {
+        self == Self::High && confidence_from(&m.rule, m.confidence, false) >= ConfidenceLevel::High
+    }
+}
+
+impl FromStr for AutoApprovePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "manual" | "off" => Ok(Self::Manual),
+            "high" => Ok(Self::High),
+            "verified-live" | "live" => Ok(Self::VerifiedLive),
+            other => Err(format!("unknown auto-approve level '{}' (expected manual, high, or verified-live)", other)),
+        }
+    }
+}
+
+/// Whether keystrokes drive the dashboard or edit the search filter.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum InputMode {
+    #[default]
+    Normal,
+    /// `/` was pressed; keys edit `filter` until Enter or Esc.
+    Search,
+}
+
+/// The match detail popup: which providers the orchestrator would route the match
+/// to, and the one the operator picked for approving it.
+#[derive(Debug, Clone)]
+pub struct MatchDetail {
+    /// `sample_hash` of the match, which stays valid while other matches are evicted.
+    pub sample_hash: String,
+    /// `None` when no orchestrator answered the plan query.
+    pub plans: Option<Vec<ProviderPlan>>,
+    /// Selection over `choices()`.
+    pub choice_state: ListState,
+}
+
+impl MatchDetail {
+    /// The provider submenu: each routed provider in order, then report-only.
+    pub fn choices(&self) -> Vec<ProviderChoice> {
+        self.plans.iter().flatten()
+            .map(|plan| ProviderChoice::Provider(plan.provider.clone()))
+            .chain(std::iter::once(ProviderChoice::ReportOnly))
+            .collect()
+    }
+
+    pub fn selected_choice(&self) -> ProviderChoice {
+        let choices = self.choices();
+        self.choice_state.selected()
+            .and_then(|i| choices.get(i).cloned())
+            .unwrap_or(ProviderChoice::ReportOnly)
+    }
+}
+
 /// Available engine choices for the dynamic switcher.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum EngineType {
@@ -24,25 +148,116 @@ pub enum EngineType {
     Hybrid = 2,
 }
 
+impl EngineType {
+    pub fn name(self) -> &'static str {
+        match self {
+            EngineType::Regex => "regex",
+            EngineType::Entropy => "entropy",
+            EngineType::Hybrid => "hybrid",
+        }
+    }
+}
+
 pub struct App {
-    pub raw_input_buffer: VecDeque<String>,
+    /// The raw lines behind `log_lines`, with their source and line number.
+    pub raw_input_buffer: VecDeque<SourcedLine>,
     pub log_lines: VecDeque<String>,
     pub matches: Vec<(RedactionMatch, RemediationStatus)>, 
     pub match_list_state: ListState,
     pub should_quit: bool,
     pub heat_map: Vec<f64>,
+    /// Entropy cutoffs for the heatmap's moderate, high, and critical colors.
+    pub heat_bands: [f64; 3],
     pub show_heatmap: bool,
     pub show_diff: bool,
+    /// Show the diff as original and redacted columns instead of one unified list.
+    pub diff_side_by_side: bool,
     pub show_remediation: bool,
     pub sync_stats: SyncStats,
+    /// Newest remediation journal entries, oldest first; empty when no journal is configured.
+    pub journal_entries: Vec<JournalEntry>,
+    /// Each provider's remediation rate limit; empty when no orchestrator reports them.
+    pub governor_status: Vec<GovernorStatus>,
+    /// Latest counters from the active engine, refreshed after every processed line.
+    pub engine_metrics: EngineMetricsSnapshot,
+    /// Invalid UTF-8 the input reader replaced, shared with the reader.
+    pub decode_metrics: Arc<DecodeMetrics>,
     pub max_history: usize,
+    /// The profile the session runs with, recorded in session exports.
+    pub profile_name: Option<String>,
+    /// Where `I` records matches to never flag again; `None` disables it.
+    pub ignore_store: Option<IgnoreStore>,
     
     // Engine Switcher State
     pub current_engine: EngineType,
     pub show_engine_menu: bool,
     pub engine_list_state: ListState,
+
+    // Live Threshold Tuning State
+    pub entropy_threshold: Option<f64>,
+    /// Redactions counted across the lines processed since the last replay.
+    pub redaction_count: usize,
+    /// Lines from the current replay that have not come back from the engine yet.
+    pub replay_pending: usize,
+    replay_baseline: Option<usize>,
+    /// `(matches, delta)` from the most recent completed replay.
+    pub last_replay: Option<(usize, i64)>,
+    pub confirm_threshold_write: bool,
+    pub status_message: Option<String>,
+    /// An error shown over the dashboard until the next key press.
+    pub error_popup: Option<String>,
+
+    // Match Retention
+    pub max_tracked_matches: usize,
+    /// Pending matches evicted because the cap was reached with nothing else to drop.
+    pub overflowed: usize,
+
+    // Auto-approval
+    pub auto_approve: AutoApprovePolicy,
+    /// Auto-approvals made this session and not undone.
+    pub auto_approved: usize,
+
+    // Undo
+    pub undo_stack: Vec<UndoEntry>,
+    pub undo_window: Duration,
+    /// Where approvals and their cancellations go; `None` until remediation is wired.
+    pub approval_tx: Option<mpsc::Sender<ApprovalMessage>>,
+    /// Where the detail popup asks which providers would handle a match.
+    pub plan_tx: Option<mpsc::Sender<PlanRequest>>,
+    /// Open match detail popup, if any.
+    pub detail: Option<MatchDetail>,
+
+    // Search
+    pub input_mode: InputMode,
+    /// Case-insensitive substring the stream and dashboard are narrowed to; empty shows
+    /// everything. Only the views are filtered, never the buffers.
+    pub filter: String,
+
+    // Copy
+    /// Position in `log_lines` of the stream line `y` copies; `None` follows the
+    /// newest visible line.
+    pub stream_selected: Option<usize>,
+    /// Where `y` and `Y` put sanitized text.
+    clipboard: Box<dyn ClipboardSink>,
 }
 
+/// Default for how long an Approve or Ignore stays undoable; matches the
+/// orchestrator's approval grace so an undone approval is still queued.
+pub const DEFAULT_UNDO_WINDOW: Duration = APPROVAL_GRACE;
+
+/// Default for `--max-tracked-matches`.
+pub const DEFAULT_MAX_TRACKED_MATCHES: usize = 500;
+
+/// How many journal entries the dashboard shows.
+pub const JOURNAL_PANEL_ENTRIES: usize = 5;
+
+/// Step used by the `+`/`-` threshold keys.
+pub const THRESHOLD_STEP: f64 = 0.1;
+/// Lowest threshold reachable from the TUI.
+pub const MIN_THRESHOLD: f64 = 0.1;
+/// Confidence scores are capped at 10.0, so a higher threshold would match nothing.
+pub const MAX_THRESHOLD: f64 = 10.0;
+
 impl App {
     pub fn new(max_history: usize, initial_engine: EngineType) -> Self {
         let mut engine_state = ListState::default();
@@ -55,23 +270,107 @@ impl App {
             match_list_state: ListState::default(),
             should_quit: false,
             heat_map: Vec::new(),
+            heat_bands: DEFAULT_HEAT_BANDS,
             show_heatmap: false,
             show_diff: false,
+            diff_side_by_side: false,
             show_remediation: false,
             sync_stats: SyncStats {
-                active: false,
-                total_hashes: 0,
-                last_sync_ms: 0,
                 provider: "Initializing...".to_string(),
+                ..SyncStats::offline()
             },
+            journal_entries: Vec::new(),
+            governor_status: Vec::new(),
+            engine_metrics: EngineMetricsSnapshot::default(),
+            decode_metrics: Arc::default(),
             max_history,
+            profile_name: None,
+            ignore_store: None,
             current_engine: initial_engine,
             show_engine_menu: false,
             engine_list_state: engine_state,
+            entropy_threshold: None,
+            redaction_count: 0,
+            replay_pending: 0,
+            replay_baseline: None,
+            last_replay: None,
+            confirm_threshold_write: false,
+            status_message: None,
+            error_popup: None,
+            max_tracked_matches: DEFAULT_MAX_TRACKED_MATCHES,
+            overflowed: 0,
+            auto_approve: AutoApprovePolicy::default(),
+            auto_approved: 0,
+            undo_stack: Vec::new(),
+            undo_window: DEFAULT_UNDO_WINDOW,
+            approval_tx: None,
+            plan_tx: None,
+            detail: None,
+            input_mode: InputMode::Normal,
+            filter: String::new(),
+            stream_selected: None,
+            clipboard: Box::new(SystemClipboard),
         }
     }
 
-    pub fn on_tick(&mut self) {}
+    /// Shares the counters the input reader records replaced UTF-8 in.
+    pub fn with_decode_metrics(mut self, decode_metrics: Arc<DecodeMetrics>) -> Self {
+        self.decode_metrics = decode_metrics;
+        self
+    }
+
+    /// Caps how many matches the dashboard retains (at least one).
+    pub fn with_max_tracked_matches(mut self, max_tracked_matches: usize) -> Self {
+        self.max_tracked_matches = max_tracked_matches.max(1);
+        self
+    }
+
+    /// Limits how long status changes remain undoable.
+    pub fn with_undo_window(mut self, undo_window: Duration) -> Self {
+        self.undo_window = undo_window;
+        self
+    }
+
+    /// Starts the session with `policy` deciding which matches skip the operator.
+    pub fn with_auto_approve(mut self, policy: AutoApprovePolicy) -> Self {
+        self.auto_approve = policy;
+        self
+    }
+
+    /// Sends approvals, and the cancellations produced by undoing them, to `tx`.
+    pub fn with_approval_tx(mut self, tx: mpsc::Sender<ApprovalMessage>) -> Self {
+        self.approval_tx = Some(tx);
+        self
+    }
+
+    /// Lets the detail popup query the orchestrator behind `tx` for provider plans.
+    pub fn with_plan_tx(mut self, tx: mpsc::Sender<PlanRequest>) -> Self {
+        self.plan_tx = Some(tx);
+        self
+    }
+
+    /// Records `profile_name` as the session's profile; empty means none.
+    pub fn with_profile_name(mut self, profile_name: impl Into<String>) -> Self {
+        let profile_name = profile_name.into();
+        self.profile_name = (!profile_name.is_empty()).then_some(profile_name);
+        self
+    }
+
+    /// Lets `I` add matches to `store`, which the engines consult.
+    pub fn with_ignore_store(mut self, store: IgnoreStore) -> Self {
+        self.ignore_store = Some(store);
+        self
+    }
+
+    /// Copies through `clipboard` instead of the system clipboard.
+    pub fn with_clipboard(mut self, clipboard: Box<dyn ClipboardSink>) -> Self {
+        self.clipboard = clipboard;
+        self
+    }
+
+    pub fn on_tick(&mut self) {
+        self.prune_undo_stack();
+    }
 
     pub fn toggle_engine_menu(&mut self) {
         self.show_engine_menu = !self.show_engine_menu;
@@ -85,41 +384,407 @@ impl App {
         self.engine_list_state.select(Some(i));
     }
 
-    pub fn push_log_pair(&mut self, raw: String, sanitized: String) {
+    /// Appends a raw line, keeping its source and line number, and its sanitized text.
+    pub fn push_log_pair(&mut self, raw: impl Into<SourcedLine>, sanitized: String) {
         if self.log_lines.len() >= self.max_history {
             self.log_lines.pop_front();
             self.raw_input_buffer.pop_front();
+            self.stream_selected = self.stream_selected.map(|i| i.saturating_sub(1));
         }
-        self.raw_input_buffer.push_back(raw);
+        self.raw_input_buffer.push_back(SourcedLine { replayed: false, ..raw.into() });
         self.log_lines.push_back(sanitized);
     }
 
-    pub fn add_match(&mut self, m: RedactionMatch) {
-        if !self.matches.iter().any(|(existing, _)| existing.original_string == m.original_string) {
-            self.matches.push((m, RemediationStatus::Pending));
-            if self.match_list_state.selected().is_none() {
-                self.match_list_state.select(Some(0));
+    /// Records a processed line together with the number of redactions it produced,
+    /// closing out the running replay once its last replayed line arrives. New
+    /// lines arriving meanwhile do not count towards the replay.
+    pub fn record_line(&mut self, raw: impl Into<SourcedLine>, sanitized: String, redactions: usize) {
+        let raw = raw.into();
+        let replayed = raw.replayed;
+        self.push_log_pair(raw, sanitized);
+        self.redaction_count += redactions;
+        if replayed && self.replay_pending > 0 {
+            self.replay_pending -= 1;
+            if self.replay_pending == 0 {
+                self.finish_replay();
             }
         }
     }
 
-    pub fn approve_current(&mut self) -> Option<RedactionMatch> {
-        if let Some(index) = self.match_list_state.selected() {
-            if let Some((m, status)) = self.matches.get_mut(index) {
-                if *status == RemediationStatus::Pending {
-                    *status = RemediationStatus::Approved;
-                    return Some(m.clone());
-                }
+    /// Records a block that was sanitized as one unit, one Live Stream line per raw
+    /// line so replays see the input as it arrived. A redacted block usually has
+    /// fewer lines than the original (a key collapses to its placeholder); the rest
+    /// show as blank. The block's redactions count once. Its lines are numbered on
+    /// from the block's first line.
+    pub fn record_block(&mut self, raw: &SourcedLine, sanitized: &str, redactions: usize) {
+        let raw_lines: Vec<&str> = raw.text.split('\n').collect();
+        let mut sanitized_lines: Vec<String> = sanitized.split('\n').map(str::to_string).collect();
+        if sanitized_lines.len() > raw_lines.len() {
+            let overflow = sanitized_lines.split_off(raw_lines.len() - 1).join(" ");
+            sanitized_lines.push(overflow);
+        }
+        let mut sanitized_lines = sanitized_lines.into_iter();
+        for (i, raw_line) in raw_lines.into_iter().enumerate() {
+            let count = if i == 0 { redactions } else { 0 };
+            self.record_line(raw.following(i as u64, raw_line), sanitized_lines.next().unwrap_or_default(), count);
+        }
+    }
+
+    /// Clears derived state and hands back the raw history so it can be re-scanned,
+    /// each line marked replayed. The lines keep their original source and line
+    /// numbers.
+    ///
+    /// Matches the operator already decided on keep their status; only Pending ones
+    /// are dropped, to be re-verified by whatever the replay detects.
+    pub fn begin_replay(&mut self) -> Vec<SourcedLine> {
+        let history: Vec<SourcedLine> = self.raw_input_buffer.drain(..).map(SourcedLine::into_replay).collect();
+        self.log_lines.clear();
+        self.stream_selected = None;
+        self.matches.retain(|(_, status)| *status != RemediationStatus::Pending);
+        self.match_list_state.select(None);
+        self.reconcile_selection();
+        self.heat_map.clear();
+        self.replay_baseline = Some(self.redaction_count);
+        self.redaction_count = 0;
+        self.replay_pending = history.len();
+        if history.is_empty() {
+            self.finish_replay();
+        }
+        history
+    }
+
+    fn finish_replay(&mut self) {
+        if let Some(baseline) = self.replay_baseline.take() {
+            let delta = self.redaction_count as i64 - baseline as i64;
+            self.last_replay = Some((self.redaction_count, delta));
+        }
+    }
+
+    /// Moves the entropy threshold by `delta`, rounded to one decimal and clamped to
+    /// `MIN_THRESHOLD..=MAX_THRESHOLD`. Returns the new value, or `None` when the active
+    /// engine has no threshold to tune.
+    pub fn adjust_threshold(&mut self, delta: f64) -> Option<f64> {
+        if self.current_engine == EngineType::Regex {
+            return None;
+        }
+        let current = self.entropy_threshold?;
+        let next = (((current + delta) * 10.0).round() / 10.0).clamp(MIN_THRESHOLD, MAX_THRESHOLD);
+        self.entropy_threshold = Some(next);
+        Some(next)
+    }
+
+    /// Header text for the threshold, e.g. `threshold 0.7: 12 matches (−5)`.
+    pub fn threshold_label(&self) -> Option<String> {
+        if self.current_engine == EngineType::Regex {
+            return None;
+        }
+        let threshold = self.entropy_threshold?;
+        let label = match self.last_replay {
+            _ if self.replay_pending > 0 => format!("threshold {:.1}: replaying…", threshold),
+            Some((count, delta)) => {
+                let signed = match delta {
+                    d if d > 0 => format!("+{}", d),
+                    d if d < 0 => format!("\u{2212}{}", -d),
+                    _ => "\u{b1}0".to_string(),
+                };
+                format!("threshold {:.1}: {} matches ({})", threshold, count, signed)
             }
+            None => format!("threshold {:.1}", threshold),
+        };
+        Some(label)
+    }
+
+    /// Tracks a new match unless the same secret is already listed, evicting the
+    /// oldest entries once `max_tracked_matches` is exceeded. A match the
+    /// auto-approve policy admits is listed as Approved and sent for remediation.
+    ///
+    /// A match at the place of one already decided on (same source, line, and an
+    /// overlapping span) is the same secret seen by another engine's rule, and is
+    /// not listed again.
+    pub fn add_match(&mut self, mut m: RedactionMatch) {
+        ensure_match_hashes(std::slice::from_mut(&mut m));
+        if self.matches.iter().any(|(existing, status)| {
+            existing.sample_hash == m.sample_hash || (*status != RemediationStatus::Pending && same_place(existing, &m))
+        }) {
+            return;
+        }
+        let admitted = self.auto_approve.admits(&m);
+        self.matches.push((m, RemediationStatus::Pending));
+        if admitted {
+            self.approve_at(self.matches.len() - 1, ProviderChoice::Auto, true);
+        }
+        while self.matches.len() > self.max_tracked_matches && self.evict_oldest() {}
+        if self.match_list_state.selected().is_none() {
+            self.reconcile_selection();
+        }
+    }
+
+    /// Drops the oldest terminal match, or failing that the oldest pending one.
+    /// Approved matches are mid-remediation and are never evicted.
+    fn evict_oldest(&mut self) -> bool {
+        let victim = self.matches.iter().position(|(_, status)| status.is_terminal())
+            .or_else(|| self.matches.iter().position(|(_, status)| *status == RemediationStatus::Pending));
+        let Some(index) = victim else { return false; };
+
+        let (_, status) = self.matches.remove(index);
+        if status == RemediationStatus::Pending {
+            self.overflowed += 1;
+            self.status_message = Some(format!(
+                "Match limit ({}) reached: dropped oldest pending match (overflowed: {})",
+                self.max_tracked_matches, self.overflowed
+            ));
+        }
+
+        if let Some(selected) = self.match_list_state.selected() {
+            let selected = if selected > index { selected - 1 } else { selected };
+            self.match_list_state.select((!self.matches.is_empty()).then(|| selected.min(self.matches.len() - 1)));
+            self.reconcile_selection();
         }
-        None
+        true
+    }
+
+    /// Opens the detail popup for the selected match with the orchestrator's
+    /// answer to its plan query, preselecting the provider `Auto` would pick.
+    pub fn open_detail(&mut self, plans: Option<Vec<ProviderPlan>>) {
+        let Some(sample_hash) = self.selected_match().and_then(|m| m.sample_hash.clone()) else { return };
+        let mut choice_state = ListState::default();
+        choice_state.select(Some(0));
+        self.detail = Some(MatchDetail { sample_hash, plans, choice_state });
+    }
+
+    pub fn selected_match(&self) -> Option<&RedactionMatch> {
+        self.match_list_state.selected().and_then(|i| self.matches.get(i)).map(|(m, _)| m)
+    }
+
+    /// The match shown in the detail popup, unless it has since been dropped.
+    pub fn detail_match(&self) -> Option<&(RedactionMatch, RemediationStatus)> {
+        let detail = self.detail.as_ref()?;
+        self.matches.iter().find(|(m, _)| m.sample_hash.as_deref() == Some(detail.sample_hash.as_str()))
+    }
+
+    pub fn close_detail(&mut self) {
+        self.detail = None;
+    }
+
+    /// Moves the provider submenu selection by one, wrapping around.
+    pub fn cycle_provider_choice(&mut self, forward: bool) {
+        let Some(detail) = &mut self.detail else { return };
+        let len = detail.choices().len();
+        let i = detail.choice_state.selected().unwrap_or(0);
+        detail.choice_state.select(Some(if forward { (i + 1) % len } else { (i + len - 1) % len }));
+    }
+
+    /// Approves the match shown in the detail popup with the submenu's choice and
+    /// closes the popup.
+    pub fn approve_from_detail(&mut self) -> Option<RedactionMatch> {
+        let approved = self.approve_in_detail();
+        self.detail = None;
+        approved
+    }
+
+    /// Selects the match shown in the detail popup in the dashboard list, returning
+    /// the submenu's choice.
+    fn select_detail_match(&mut self) -> Option<ProviderChoice> {
+        let detail = self.detail.as_ref()?;
+        let index = self.matches.iter().position(|(m, _)| m.sample_hash.as_deref() == Some(detail.sample_hash.as_str()))?;
+        let choice = detail.selected_choice();
+        self.match_list_state.select(Some(index));
+        Some(choice)
+    }
+
+    /// Approves the match shown in the detail popup with the submenu's choice,
+    /// leaving the popup open on it.
+    pub fn approve_in_detail(&mut self) -> Option<RedactionMatch> {
+        let choice = self.select_detail_match()?;
+        self.approve_current_with(choice)
+    }
+
+    /// Ignores the match shown in the detail popup, leaving the popup open on it.
+    pub fn ignore_in_detail(&mut self) {
+        if self.select_detail_match().is_some() {
+            self.ignore_current();
+        }
+    }
+
+    /// Copies the sample hash of the match in the detail popup to the clipboard.
+    pub fn copy_detail_hash(&mut self) -> bool {
+        let payload = self.detail.as_ref().map(|detail| detail.sample_hash.clone());
+        self.copy_masked("sample hash", payload)
+    }
+
+    pub fn approve_current(&mut self) -> Option<RedactionMatch> {
+        self.approve_current_with(ProviderChoice::Auto)
+    }
+
+    /// Approves the selected match for remediation by `choice`.
+    pub fn approve_current_with(&mut self, choice: ProviderChoice) -> Option<RedactionMatch> {
+        let index = self.match_list_state.selected()?;
+        self.approve_at(index, choice, false)
+    }
+
+    /// Approves the pending match at `index` and queues it with the orchestrator.
+    fn approve_at(&mut self, index: usize, choice: ProviderChoice, auto: bool) -> Option<RedactionMatch> {
+        let (m, status) = self.matches.get_mut(index)?;
+        if *status != RemediationStatus::Pending {
+            return None;
+        }
+        *status = RemediationStatus::Approved;
+        let approved = m.clone();
+        self.record_undo(&approved, RemediationStatus::Pending, RemediationStatus::Approved, auto);
+        if auto {
+            self.auto_approved += 1;
+        }
+        if let Some(tx) = &self.approval_tx {
+            let _ = tx.try_send(ApprovalMessage::Approve(Box::new(approved.clone()), choice));
+        }
+        Some(approved)
+    }
+
+    /// Switches to the next auto-approve policy. Matches already listed keep their
+    /// status; the policy applies to those that arrive from now on.
+    pub fn cycle_auto_approve(&mut self) {
+        self.auto_approve = self.auto_approve.next();
+        self.status_message = Some(format!("Auto-approve policy: {}", self.auto_approve.label()));
+    }
+
+    /// Records that a provider confirmed the secret with `sample_hash` is live,
+    /// approving it if it is pending and the policy is `VerifiedLive`. Returns
+    /// whether it was approved.
+    pub fn mark_verified_live(&mut self, sample_hash: &str) -> bool {
+        if self.auto_approve != AutoApprovePolicy::VerifiedLive {
+            return false;
+        }
+        let Some(index) = self.matches.iter().position(|(m, _)| m.sample_hash.as_deref() == Some(sample_hash)) else {
+            return false;
+        };
+        self.approve_at(index, ProviderChoice::Auto, true).is_some()
     }
 
     pub fn ignore_current(&mut self) {
-        if let Some(index) = self.match_list_state.selected() {
-            if let Some((_, status)) = self.matches.get_mut(index) {
-                *status = RemediationStatus::Ignored;
+        let Some(index) = self.match_list_state.selected() else { return };
+        let Some((m, status)) = self.matches.get(index) else { return };
+        if *status == RemediationStatus::Ignored {
+            return;
+        }
+        let (previous, from) = (m.clone(), status.clone());
+        self.set_terminal_status(index, RemediationStatus::Ignored);
+        self.record_undo(&previous, from, RemediationStatus::Ignored, false);
+    }
+
+    /// Ignores the selected match and adds its canonical sample hash to the ignore
+    /// store, so the engines stop flagging the secret from the next line on and in
+    /// later sessions. `u` restores the dashboard entry but keeps the stored hash;
+    /// `cleansh ignore remove` deletes it. Returns whether the hash was stored.
+    pub fn ignore_permanently(&mut self) -> bool {
+        let Some(store) = self.ignore_store.clone() else {
+            self.status_message = Some("No ignore store available".to_string());
+            return false;
+        };
+        let Some((m, status)) = self.match_list_state.selected().and_then(|i| self.matches.get(i)) else { return false };
+        if status.is_terminal() {
+            self.status_message = Some("This match's value is no longer held; undo first to ignore it for good".to_string());
+            return false;
+        }
+        let hash = canonical_sample_hash(&m.rule_name, &m.original_string);
+        if let Err(e) = store.insert(&hash) {
+            self.status_message = Some(format!("Failed to save ignore: {:#}", e));
+            return false;
+        }
+        self.ignore_current();
+        self.status_message = Some(format!("Never flagging {}… again", &hash[..12]));
+        true
+    }
+
+    fn record_undo(&mut self, previous: &RedactionMatch, from: RemediationStatus, to: RemediationStatus, auto: bool) {
+        self.prune_undo_stack();
+        self.undo_stack.push(UndoEntry {
+            sample_hash: previous.sample_hash.clone()
+                .unwrap_or_else(|| canonical_sample_hash(&previous.rule_name, &previous.original_string)),
+            previous: previous.clone(),
+            from,
+            to,
+            at: Instant::now(),
+            auto,
+        });
+    }
+
+    /// Drops entries older than `undo_window`, along with the plaintext they hold.
+    fn prune_undo_stack(&mut self) {
+        let window = self.undo_window;
+        self.undo_stack.retain(|entry| entry.at.elapsed() < window);
+    }
+
+    /// Reverts the most recent Approve or Ignore, including auto-approvals. Undoing an
+    /// Approve withdraws it from the approval queue; undoing an Ignore restores
+    /// Pending. Returns `false` when there is nothing left to undo, or when the
+    /// withdrawal cannot be queued, in which case the match stays Approved and the
+    /// undo can be tried again.
+    pub fn undo(&mut self) -> bool {
+        self.prune_undo_stack();
+        while let Some(entry) = self.undo_stack.pop() {
+            let Some(index) = self.matches.iter()
+                .position(|(m, _)| m.sample_hash.as_deref() == Some(entry.sample_hash.as_str()))
+            else {
+                continue;
+            };
+            if self.matches[index].1 != entry.to {
+                continue;
+            }
+            if entry.to == RemediationStatus::Approved
+                && let Some(tx) = &self.approval_tx
+                && tx.try_send(ApprovalMessage::CancelApproval(entry.sample_hash.clone())).is_err()
+            {
+                self.undo_stack.push(entry);
+                self.status_message = Some("Could not cancel approval; try again".to_string());
+                return false;
+            }
+            if entry.auto {
+                self.auto_approved = self.auto_approved.saturating_sub(1);
+                self.status_message = Some(format!("Undid auto-approval of {}", entry.previous.rule_name));
+            } else {
+                self.status_message = Some(format!("Undid {:?} of {}", entry.to, entry.previous.rule_name));
+            }
+            self.matches[index] = (entry.previous, entry.from);
+            return true;
+        }
+        self.status_message = Some("Nothing to undo".to_string());
+        false
+    }
+
+    /// Time left to undo the approval of the match with `sample_hash`, if any.
+    pub fn undo_remaining(&self, sample_hash: &str) -> Option<Duration> {
+        self.undo_stack.iter().rev()
+            .find(|entry| entry.sample_hash == sample_hash && entry.to == RemediationStatus::Approved)
+            .and_then(|entry| self.undo_window.checked_sub(entry.at.elapsed()))
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    /// Records that remediation revoked the secret at `index`.
+    pub fn mark_revoked(&mut self, index: usize) {
+        self.set_terminal_status(index, RemediationStatus::Revoked);
+    }
+
+    /// Records that remediation revoked the match with `sample_hash`. Returns
+    /// whether the dashboard still tracks it.
+    pub fn mark_revoked_by_hash(&mut self, sample_hash: &str) -> bool {
+        let Some(index) = self.matches.iter().position(|(m, _)| m.sample_hash.as_deref() == Some(sample_hash)) else {
+            return false;
+        };
+        self.mark_revoked(index);
+        true
+    }
+
+    /// Moves a match to a terminal status and replaces its raw value with a masked
+    /// preview plus hash prefix; only approval needs the plaintext.
+    fn set_terminal_status(&mut self, index: usize, terminal: RemediationStatus) {
+        if let Some((m, status)) = self.matches.get_mut(index) {
+            if !status.is_terminal() {
+                let hash = m.sample_hash.clone()
+                    .unwrap_or_else(|| canonical_sample_hash(&m.rule_name, &m.original_string));
+                m.original_string = format!("{} #{}", redact_sensitive(&m.original_string), &hash[..hash.len().min(8)]);
+                m.sample_hash = Some(hash);
             }
+            *status = terminal;
         }
     }
 
@@ -133,21 +798,216 @@ impl App {
         if self.show_diff { self.show_heatmap = false; }
     }
 
+    /// Switches the diff between unified and side-by-side. A closed diff is
+    /// opened side by side.
+    pub fn toggle_diff_layout(&mut self) {
+        if self.show_diff {
+            self.diff_side_by_side = !self.diff_side_by_side;
+        } else {
+            self.diff_side_by_side = true;
+            self.toggle_diff();
+        }
+    }
+
+    /// Selects the next visible match, wrapping around.
     pub fn next_match(&mut self) {
-        if self.matches.is_empty() { return; }
+        let visible = self.visible_match_indices();
+        if visible.is_empty() { return; }
         let i = match self.match_list_state.selected() {
-            Some(i) => if i >= self.matches.len() - 1 { 0 } else { i + 1 },
-            None => 0,
+            Some(i) => visible.iter().copied().find(|&v| v > i).unwrap_or(visible[0]),
+            None => visible[0],
         };
         self.match_list_state.select(Some(i));
     }
 
+    /// Selects the previous visible match, wrapping around.
     pub fn previous_match(&mut self) {
-        if self.matches.is_empty() { return; }
+        let visible = self.visible_match_indices();
+        if visible.is_empty() { return; }
         let i = match self.match_list_state.selected() {
-            Some(i) => if i == 0 { self.matches.len() - 1 } else { i - 1 },
-            None => 0,
+            Some(i) => visible.iter().rev().copied().find(|&v| v < i).unwrap_or(visible[visible.len() - 1]),
+            None => visible[0],
         };
         self.match_list_state.select(Some(i));
     }
-}
\ No newline at end of file
+
+    /// Opens the search prompt, keeping the current filter for editing.
+    pub fn begin_search(&mut self) {
+        self.input_mode = InputMode::Search;
+    }
+
+    pub fn push_search_char(&mut self, c: char) {
+        self.filter.push(c);
+        self.reconcile_selection();
+    }
+
+    pub fn pop_search_char(&mut self) {
+        self.filter.pop();
+        self.reconcile_selection();
+    }
+
+    /// Closes the prompt and keeps the filter applied.
+    pub fn confirm_search(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Closes the prompt and shows everything again.
+    pub fn clear_filter(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.filter.clear();
+        self.reconcile_selection();
+    }
+
+    fn filter_matches(&self, text: &str) -> bool {
+        self.filter.is_empty() || text.to_lowercase().contains(&self.filter.to_lowercase())
+    }
+
+    /// Sanitized stream lines that pass the filter.
+    pub fn visible_log_lines(&self) -> Vec<&str> {
+        self.visible_log_indices().into_iter().map(|i| self.log_lines[i].as_str()).collect()
+    }
+
+    /// Positions in `log_lines` that pass the filter.
+    pub fn visible_log_indices(&self) -> Vec<usize> {
+        self.log_lines.iter().enumerate()
+            .filter(|(_, line)| self.filter_matches(line))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// The stream line `y` copies: the selected one while it is visible, else the
+    /// newest visible line.
+    pub fn selected_stream_index(&self) -> Option<usize> {
+        let visible = self.visible_log_indices();
+        self.stream_selected.filter(|i| visible.contains(i)).or(visible.last().copied())
+    }
+
+    /// Moves the stream selection to the previous visible line, stopping at the oldest.
+    pub fn select_previous_line(&mut self) {
+        let Some(current) = self.selected_stream_index() else { return };
+        let visible = self.visible_log_indices();
+        self.stream_selected = Some(visible.iter().rev().copied().find(|&v| v < current).unwrap_or(current));
+    }
+
+    /// Moves the stream selection to the next visible line; moving past the newest
+    /// goes back to following new lines.
+    pub fn select_next_line(&mut self) {
+        let Some(current) = self.selected_stream_index() else { return };
+        self.stream_selected = self.visible_log_indices().into_iter().find(|&v| v > current);
+    }
+
+    /// Copies the selected stream line, in its sanitized form, to the clipboard.
+    pub fn copy_stream_line(&mut self) -> bool {
+        let payload = self.selected_stream_index().map(|i| self.log_lines[i].clone());
+        self.copy_masked("sanitized line", payload)
+    }
+
+    /// Copies the masked context block of the match in the detail popup, or else the
+    /// selected match, to the clipboard.
+    pub fn copy_match_context(&mut self) -> bool {
+        let entry = match self.detail {
+            Some(_) => self.detail_match(),
+            None => self.match_list_state.selected().and_then(|i| self.matches.get(i)),
+        };
+        let payload = entry.map(|(m, status)| masked_context(m, status));
+        self.copy_masked("match context", payload)
+    }
+
+    /// Puts `payload` on the clipboard unless it contains a secret the dashboard still
+    /// holds in plaintext, reporting the outcome in the status line. The payload is
+    /// built from sanitized fields only; the check makes sure of it.
+    fn copy_masked(&mut self, what: &str, payload: Option<String>) -> bool {
+        let Some(payload) = payload else {
+            self.status_message = Some("Nothing to copy".to_string());
+            return false;
+        };
+        let leaks = self.matches.iter()
+            .filter(|(_, status)| !status.is_terminal())
+            .map(|(m, _)| m)
+            .chain(self.undo_stack.iter().map(|entry| &entry.previous))
+            .any(|m| !m.original_string.is_empty() && payload.contains(&m.original_string));
+        if leaks {
+            self.status_message = Some(format!("Refused to copy {}: it contains an unmasked secret", what));
+            return false;
+        }
+        let (message, copied) = match self.clipboard.set_text(&payload) {
+            Ok(()) => (format!("Copied {} to clipboard", what), true),
+            Err(e) => (format!("Clipboard unavailable: {:#}", e), false),
+        };
+        self.status_message = Some(message);
+        copied
+    }
+
+    /// Positions in `matches` whose rule, replacement, or source passes the filter.
+    /// Raw values are not searched, so the filter cannot be used to probe for a secret.
+    pub fn visible_match_indices(&self) -> Vec<usize> {
+        self.matches.iter().enumerate()
+            .filter(|(_, (m, _))| {
+                self.filter_matches(&m.rule_name)
+                    || self.filter_matches(&m.sanitized_string)
+                    || self.filter_matches(&m.source_id)
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Keeps the selection on a visible match: the nearest one at or after the current
+    /// selection, else the last one, else nothing.
+    fn reconcile_selection(&mut self) {
+        let visible = self.visible_match_indices();
+        let current = self.match_list_state.selected().unwrap_or(0);
+        let next = visible.iter().copied().find(|&v| v >= current).or(visible.last().copied());
+        self.match_list_state.select(next);
+    }
+}
+
+/// Whether two numbered matches cover some of the same bytes of the same line.
+fn same_place(a: &RedactionMatch, b: &RedactionMatch) -> bool {
+    let (a_span, b_span) = (a.document_span(), b.document_span());
+    a.line_number.is_some()
+        && a.line_number == b.line_number
+        && a.source_id == b.source_id
+        && a_span.start < b_span.end
+        && b_span.start < a_span.end
+}
+
+/// The labelled fields of the match detail popup, built from sanitized fields only:
+/// the raw value appears as a masked preview, never in full.
+pub fn detail_fields(m: &RedactionMatch, status: &RemediationStatus) -> Vec<(&'static str, String)> {
+    let source = match m.line_number {
+        Some(line) => format!("{}:{}", m.source_id, line),
+        None => m.source_id.clone(),
+    };
+    // Terminal matches already hold a masked preview in place of the raw value.
+    let preview = if status.is_terminal() { m.original_string.clone() } else { redact_sensitive(&m.original_string) };
+    let mut fields = vec![("Rule", m.rule_name.clone())];
+    if let Some(description) = m.rule.description.as_deref().filter(|d| !d.is_empty()) {
+        fields.push(("About", description.to_string()));
+    }
+    fields.extend([
+        ("Severity", Severity::of_match(m).to_string()),
+        ("Source", source),
+        ("Offsets", format!("{:?}", m.document_span())),
+        ("Status", format!("{:?}", status)),
+        ("Value", m.sanitized_string.clone()),
+        ("Preview", preview),
+    ]);
+    if let Some(context) = m.context_line() {
+        fields.push(("Context", context));
+    }
+    fields.extend([
+        ("Hash", m.sample_hash.clone().unwrap_or_else(|| canonical_sample_hash(&m.rule_name, &m.original_string))),
+        ("Seen", m.timestamp.clone().unwrap_or_else(|| "n/a".to_string())),
+    ]);
+    fields
+}
+
+/// The match detail shown in the popup, as plain text built from sanitized fields only.
+pub fn masked_context(m: &RedactionMatch, status: &RemediationStatus) -> String {
+    let mut text: String = detail_fields(m, status).into_iter()
+        .map(|(label, value)| format!("{:<10}{}\n", format!("{}:", label), value))
+        .collect();
+    text.push_str(&confidence_label(m.confidence));
+    text.push('\n');
+    text
+}
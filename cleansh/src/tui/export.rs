@@ -0,0 +1,105 @@
+// cleansh/src/tui/export.rs
+//! Saves what the TUI saw: the sanitized Live Stream as `session-<ts>.log` and the
+//! tracked matches as `session-<ts>.matches.json`.
+//!
+//! A [`SessionSnapshot`] is copied out of the [`App`] while it is locked, so the
+//! files can be written on a blocking thread without holding up the UI.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use cleansh_core::redaction_match::{pii_debug_allowed, redact_sensitive};
+use cleansh_core::RedactionMatch;
+use crate::tui::app::{App, RemediationStatus};
+
+/// The header of a `.matches.json` export.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionHeader {
+    pub exported_at: String,
+    pub engine: String,
+    /// `None` when no profile was selected.
+    pub profile: Option<String>,
+    /// The Live Stream's line limit; older lines were already dropped.
+    pub max_history: usize,
+    pub lines: usize,
+}
+
+/// One tracked match and what the operator did with it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionMatch {
+    #[serde(flatten)]
+    pub redaction: RedactionMatch,
+    pub status: RemediationStatus,
+}
+
+/// Everything an export writes, copied out of the dashboard.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionSnapshot {
+    pub header: SessionHeader,
+    #[serde(skip)]
+    pub exported_at: DateTime<Utc>,
+    #[serde(skip)]
+    pub log_lines: Vec<String>,
+    pub matches: Vec<SessionMatch>,
+}
+
+impl SessionSnapshot {
+    /// Copies the sanitized Live Stream and the tracked matches out of `app`.
+    ///
+    /// Original values are masked as in debug logs unless `CLEANSH_ALLOW_DEBUG_PII=true`
+    /// is set; the export is meant to be shared, the secrets are not.
+    pub fn from_app(app: &App, exported_at: DateTime<Utc>) -> Self {
+        let log_lines: Vec<String> = app.log_lines.iter().cloned().collect();
+        let matches = app.matches.iter()
+            .map(|(m, status)| {
+                let mut redaction = m.clone();
+                if !pii_debug_allowed() {
+                    redaction.original_string = redact_sensitive(&redaction.original_string);
+                }
+                SessionMatch { redaction, status: status.clone() }
+            })
+            .collect();
+        Self {
+            header: SessionHeader {
+                exported_at: exported_at.to_rfc3339(),
+                engine: app.current_engine.name().to_string(),
+                profile: app.profile_name.clone(),
+                max_history: app.max_history,
+                lines: log_lines.len(),
+            },
+            exported_at,
+            log_lines,
+            matches,
+        }
+    }
+
+    /// The `.matches.json` document: the header and every match with its status.
+    pub fn matches_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("Failed to serialize session matches")
+    }
+
+    /// The `.log` document: one sanitized line per line.
+    pub fn log_text(&self) -> String {
+        let mut text = self.log_lines.join("\n");
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Writes `session-<ts>.log` and `session-<ts>.matches.json` into `dir`, named
+    /// after the export time, and returns their paths.
+    pub fn write_to(&self, dir: &Path) -> Result<(PathBuf, PathBuf)> {
+        let stamp = self.exported_at.format("%Y%m%d-%H%M%S");
+        let log_path = dir.join(format!("session-{}.log", stamp));
+        let matches_path = dir.join(format!("session-{}.matches.json", stamp));
+        fs::write(&log_path, self.log_text())
+            .with_context(|| format!("Failed to write {}", log_path.display()))?;
+        fs::write(&matches_path, self.matches_json()?)
+            .with_context(|| format!("Failed to write {}", matches_path.display()))?;
+        Ok((log_path, matches_path))
+    }
+}
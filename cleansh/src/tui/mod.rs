@@ -1,86 +1,550 @@
 // cleansh/src/tui/mod.rs
 pub mod app;
+pub mod export;
+pub mod record;
 pub mod ui;
 pub mod sync;
 
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::io;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::{mpsc, Mutex, Notify};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    cursor,
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
-use anyhow::{Result, Context};
-use crate::tui::app::{App, EngineType};
-use crate::tui::sync::start_sync_task;
-use cleansh_core::{EntropyEngine, RegexEngine, engine::SanitizationEngine, config::RedactionConfig};
+use anyhow::{bail, Result, Context};
+use crate::tui::app::{App, AutoApprovePolicy, EngineType, InputMode, THRESHOLD_STEP};
+use crate::tui::export::SessionSnapshot;
+use crate::tui::record::{record_lines, replay, KeyGate, KeySource, RecordOptions, Recorder, RecordingHeader, Recording, RECORDING_VERSION};
+use crate::tui::sync::{start_governor_task, start_journal_task, start_outcome_task, start_sync_task, start_verification_task};
+use crate::commands::watch::{Follower, WatchOptions};
+use crate::utils::lines::{InputLine, LineCounter, SourcedLine};
+use crate::utils::platform::fingerprint_cache_path;
+use crate::utils::sink::{open_sinks, OutputSink, SinkSpec};
+use cleansh_core::{BlockAssembler, CompositeEngine, DecodeMetrics, EngineHandle, EngineOptions, EntropyEngine, RegexEngine, LossyText, engine::{SanitizationEngine, SanitizeContext}, config::{self, RedactionConfig}};
+use cleansh_core::crash_report::{record_sanitized_line, set_crash_engine, CleanupGuard};
 use cleansh_core::redaction_match::RedactionMatch;
-use std::time::{Duration, Instant};
+use cleansh_core::remediation::fingerprint_cache::FingerprintCache;
+use cleansh_core::remediation::journal::{JsonlJournal, RemediationJournal};
+use cleansh_core::remediation::notifier::{notifiers_from_config, ChannelNotifier};
+use cleansh_core::remediation::orchestrator::{request_plan, SelfHealingEngine, APPROVAL_GRACE};
+use cleansh_core::remediation::providers::build_providers;
+use cleansh_core::remediation::RemediationSettings;
+use std::time::Duration;
 use futures::stream::StreamExt;
 
-pub async fn run_tui(mut engine: Box<dyn SanitizationEngine>, theme_map: crate::ui::theme::ThemeMap) -> Result<()> {
+/// How long the detail popup waits for the orchestrator's provider plan.
+const PLAN_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Where the TUI's Live Stream comes from.
+#[derive(Debug, Clone, Default)]
+pub enum TuiInput {
+    #[default]
+    Stdin,
+    /// Lines appended to these files, as `cleansh watch` follows them.
+    Watch(Vec<PathBuf>, WatchOptions),
+    /// The lines and keys of a `--record` recording, replayed this many times
+    /// faster than recorded (must be positive).
+    Replay(PathBuf, f64),
+}
+
+/// Default for `--remediation-channel-capacity`.
+pub const DEFAULT_REMEDIATION_CHANNEL_CAPACITY: usize = 100;
+
+/// Builds an engine of the given type with its matches teed to `tx`. `salt` is the
+/// organization salt, so the engine recognizes the fingerprints the vault holds.
+pub fn build_engine(
+    engine_type: EngineType,
+    config: RedactionConfig,
+    options: EngineOptions,
+    salt: &[u8],
+    tx: mpsc::Sender<RedactionMatch>,
+) -> Result<Arc<dyn SanitizationEngine>> {
+    Ok(match engine_type {
+        EngineType::Regex => Arc::new(RegexEngine::with_options(config, options)?.with_fingerprint_salt(salt.to_vec()).with_remediation_tx(tx)),
+        EngineType::Entropy => Arc::new(EntropyEngine::with_options(config, options)?.with_fingerprint_salt(salt.to_vec()).with_remediation_tx(tx)),
+        EngineType::Hybrid => Arc::new(CompositeEngine::with_options(config, options)?.with_fingerprint_salt(salt.to_vec()).with_remediation_tx(tx)),
+    })
+}
+
+/// The fingerprint cache at `path`, once `cleansh vault compact` or a vault sync
+/// has written one there. A cache that cannot be read is left for the next sync
+/// to rebuild.
+fn open_fingerprint_cache(path: Option<&Path>) -> Option<Arc<FingerprintCache>> {
+    let path = path.filter(|path| path.exists())?;
+    match FingerprintCache::open(path) {
+        Ok(cache) => Some(Arc::new(cache)),
+        Err(e) => {
+            log::debug!("Not using the fingerprint cache: {:#}", e);
+            None
+        }
+    }
+}
+
+/// One [`BlockAssembler`] per source, as `watch` keeps, so lines from different
+/// files never join one block. Replayed history is assembled apart from new input.
+/// Each remembers where its pushed lines came from, so a released unit, line or
+/// block, keeps the source and number of its first line.
+pub struct SourcedAssembler {
+    template: BlockAssembler,
+    sources: BTreeMap<(Arc<str>, bool), SourceAssembler>,
+}
+
+struct SourceAssembler {
+    assembler: BlockAssembler,
+    /// Origin of every pushed line not yet released, in order.
+    pending: VecDeque<SourcedLine>,
+}
+
+impl SourcedAssembler {
+    pub fn new(template: BlockAssembler) -> Self {
+        Self { template, sources: BTreeMap::new() }
+    }
+
+    /// Feeds one line to its source's assembler and returns the units it releases.
+    pub fn push(&mut self, mut line: SourcedLine) -> Vec<SourcedLine> {
+        if self.template.is_empty() {
+            return vec![line];
+        }
+        let key = (Arc::clone(&line.source_id), line.replayed);
+        let source = self.sources.entry(key).or_insert_with(|| SourceAssembler {
+            assembler: self.template.clone(),
+            pending: VecDeque::new(),
+        });
+        let text = std::mem::take(&mut line.text);
+        source.pending.push_back(line);
+        let units = source.assembler.push(text);
+        units.into_iter().map(|unit| source.tag(unit)).collect()
+    }
+
+    /// Releases the blocks still open in new input, which has ended.
+    pub fn finish_input(&mut self) -> Vec<SourcedLine> {
+        let mut units = Vec::new();
+        for ((_, replayed), source) in &mut self.sources {
+            if *replayed {
+                continue;
+            }
+            if let Some(unit) = source.assembler.flush() {
+                units.push(source.tag(unit));
+            }
+        }
+        units
+    }
+}
+
+impl SourceAssembler {
+    /// Pairs `unit` with its first line's origin, replayed or not, and drops the
+    /// origins of the rest.
+    fn tag(&mut self, unit: String) -> SourcedLine {
+        let lines = unit.matches('\n').count() + 1;
+        let origin = self.pending.pop_front();
+        for _ in 1..lines {
+            self.pending.pop_front();
+        }
+        match origin {
+            Some(origin) => SourcedLine { text: unit, ..origin },
+            None => SourcedLine::from(unit),
+        }
+    }
+}
+
+/// Sanitizes one input line, or a block of lines joined with `\n` by the
+/// [`BlockAssembler`], and records it on the dashboard.
+///
+/// Matches carry the line's source id and, when it is known, its line number. The
+/// line is processed start to finish by a single engine, even if the handle is
+/// swapped meanwhile, so its sanitized text, heat map, and metrics always agree.
+/// The engine runs on the blocking pool, and the dashboard is only locked to
+/// record the result.
+///
+/// Returns the sanitized text, or `None` when the line was a replay of history
+/// (after a threshold change or engine switch) rather than new input.
+pub async fn process_line(engine: &EngineHandle, app: &Mutex<App>, raw_line: impl Into<SourcedLine>) -> Result<Option<String>> {
+    let raw_line = raw_line.into();
+    let replayed = raw_line.replayed;
+    let engine = engine.load();
+    let (raw_line, sanitized, redactions, heat_map, metrics) = tokio::task::spawn_blocking(move || -> Result<_> {
+        let mut ctx = SanitizeContext::new(&raw_line.source_id).with_reason("tui").with_outcome("success");
+        if raw_line.line_no > 0 {
+            ctx = ctx.with_first_line(raw_line.line_no);
+        }
+        let (sanitized, summary) = engine.sanitize_with(&raw_line.text, &mut ctx)
+            .context("Engine failure")?;
+        let redactions: usize = summary.iter().map(|item| item.occurrences).sum();
+        let mut heat_map = engine.get_heat_scores(&raw_line.text);
+        // The heat panel shows the newest line, which for a block is its last one.
+        if let Some(last_start) = raw_line.text.rfind('\n').map(|i| i + 1) {
+            heat_map = heat_map.get(last_start..).map(<[f64]>::to_vec).unwrap_or_default();
+        }
+        let metrics = engine.metrics();
+        Ok((raw_line, sanitized, redactions, heat_map, metrics))
+    })
+    .await
+    .context("Engine task panicked")??;
+
+    let mut app_write = app.lock().await;
+    app_write.heat_map = heat_map;
+    app_write.engine_metrics = metrics;
+    if !replayed {
+        record_sanitized_line(&sanitized);
+    }
+    if raw_line.text.contains('\n') {
+        app_write.record_block(&raw_line, &sanitized, redactions);
+    } else {
+        app_write.record_line(raw_line, sanitized.clone(), redactions);
+    }
+    Ok((!replayed).then_some(sanitized))
+}
+
+/// Leaves raw mode and the alternate screen and shows the cursor. Harmless when
+/// the terminal is already restored.
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, cursor::Show);
+}
+
+/// Sanitizes new lines from `rx_input` and replayed history from `rx_history` in
+/// arrival order until the history channel closes. When the input ends, blocks
+/// still open in it are released as they are. It runs apart from the draw loop, so
+/// a slow line never holds up key handling. A line the engine fails on is dropped
+/// and the error shown in a popup. `redraw` is notified after every line. New (not
+/// replayed) sanitized lines are also sent to `forward`, when the output is
+/// forwarded to sinks.
+async fn process_lines(
+    engine: EngineHandle,
+    app: Arc<Mutex<App>>,
+    mut assembler: SourcedAssembler,
+    mut rx_input: mpsc::Receiver<SourcedLine>,
+    mut rx_history: mpsc::Receiver<SourcedLine>,
+    redraw: Arc<Notify>,
+    forward: Option<mpsc::Sender<String>>,
+) {
+    let mut input_open = true;
+    loop {
+        let units = tokio::select! {
+            line = rx_input.recv(), if input_open => match line {
+                Some(line) => assembler.push(line),
+                None => {
+                    input_open = false;
+                    assembler.finish_input()
+                }
+            },
+            line = rx_history.recv() => match line {
+                Some(line) => assembler.push(line),
+                None => return,
+            },
+        };
+        for unit in units {
+            match process_line(&engine, &app, unit).await {
+                Ok(Some(sanitized)) => {
+                    if let Some(forward) = &forward {
+                        let _ = forward.send(sanitized).await;
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => app.lock().await.error_popup = Some(format!("{:#}", e)),
+            }
+            redraw.notify_one();
+        }
+    }
+}
+
+/// Writes each sanitized line from `rx` to `sink`, one line per message, until
+/// the channel closes.
+fn forward_lines(mut sink: Box<dyn OutputSink>, mut rx: mpsc::Receiver<String>) {
+    while let Some(mut line) = rx.blocking_recv() {
+        line.push('\n');
+        if sink.write_all(line.as_bytes()).and_then(|_| sink.flush()).is_err() {
+            return;
+        }
+    }
+}
+
+/// Unbuffered byte-level stdin reader: bypasses the standard library's internal
+/// buffering so each line reaches the engine as soon as its newline arrives.
+/// Lines are numbered from 1 under the `stdin` source id.
+fn read_stdin_lines(tx_stdin: mpsc::Sender<SourcedLine>, decode: Arc<DecodeMetrics>) {
+    read_lines(std::io::stdin().lock(), "stdin", tx_stdin, &decode);
+}
+
+/// Sends each line of `reader` as soon as its newline arrives, numbered from 1
+/// under `source_id`, until the input ends or nobody is listening. Reads a byte at
+/// a time, so `reader` should be unbuffered for the lines to arrive promptly.
+/// Invalid UTF-8 is replaced with U+FFFD rather than losing the line, and counted
+/// in `decode`.
+pub fn read_lines(mut reader: impl io::Read, source_id: &str, tx: mpsc::Sender<SourcedLine>, decode: &DecodeMetrics) {
+    let mut buffer = [0u8; 1]; // Read 1 byte at a time for instant reaction
+    let mut line_acc = Vec::with_capacity(1024);
+    let mut counter = LineCounter::new(source_id);
+    let mut send = |line: &[u8], newline: bool| {
+        let text = LossyText::new(line);
+        decode.record(&text);
+        tx.blocking_send(counter.next_line(InputLine::from_line(text.text, newline).text)).is_ok()
+    };
+
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => break, // EOF
+            Ok(_) => {
+                let byte = buffer[0];
+                if byte == b'\n' {
+                    // Flush accumulated line immediately upon hitting newline
+                    if !send(&line_acc, true) {
+                        return;
+                    }
+                    line_acc.clear();
+                } else {
+                    line_acc.push(byte);
+                }
+            }
+            Err(_) => break,
+        }
+    }
+
+    // Flush any remaining data (if the stream ends without a newline)
+    if !line_acc.is_empty() {
+        send(&line_acc, false);
+    }
+}
+
+/// Sends every line appended to the followed files, prefixed with its file when
+/// there are several, until the TUI stops listening. Each file's lines are
+/// numbered from 1 as they arrive, under the file's label.
+fn follow_files(mut follower: Follower, tx: mpsc::Sender<SourcedLine>) {
+    let prefix = follower.is_multi();
+    let mut counters: HashMap<String, LineCounter> = HashMap::new();
+    loop {
+        match follower.poll() {
+            Ok(lines) => {
+                for (label, line) in lines {
+                    let text = if prefix { format!("{}: {}", label, line.text) } else { line.text };
+                    let counter = counters.entry(label).or_insert_with_key(|label| LineCounter::new(label));
+                    if tx.blocking_send(counter.next_line(text)).is_err() {
+                        return;
+                    }
+                }
+            }
+            Err(e) => {
+                log::error!("Stopped following files: {:#}", e);
+                return;
+            }
+        }
+        follower.wait();
+    }
+}
+
+/// Runs the interactive TUI until the user quits.
+///
+/// The engine is built here so it can be wired to the dashboard's remediation
+/// channel, and is then shared through an [`EngineHandle`]. `user_config_path` is
+/// where a tuned entropy threshold is written back when the user confirms `W`;
+/// `None` disables write-back. `max_tracked_matches` caps how many matches the
+/// dashboard keeps in memory, and `remediation_capacity` bounds the channel that
+/// carries matches from the engine to the dashboard (see
+/// `EngineOptions::remediation_tx_mode` for what happens when it is full). `input`
+/// selects what feeds the Live Stream.
+///
+/// When `remediation` enables any provider, an orchestrator is started with them:
+/// every match is also sent to it, and the dashboard's approvals, provider plans,
+/// and governor panel talk to it. Secrets it revokes are marked Revoked on the
+/// dashboard and redacted as `[REVOKED_SECRET]` from then on, through
+/// `options.remediation_state`. It never prompts, since the terminal is the
+/// dashboard's.
+///
+/// With a vault configured (`CLEANSH_VAULT_URL`, or `CLEANSH_REDIS_URL`), the
+/// engine is fed that vault's fingerprints for `remediation.org` every minute and
+/// as they are pushed, the Ubiquity panel reports the sync, and the orchestrator
+/// publishes what it remediates there. Every engine fingerprints with
+/// `remediation.org_salt`, so they agree with other agents'. Without a vault the
+/// panel shows OFFLINE.
+///
+/// Sanitized lines are also written to `sinks`, when any are given; stdout cannot
+/// be one of them, since the dashboard is drawn there.
+///
+/// With `record`, every input line and key press is also written to a recording
+/// (see [`record`]); a replayed session shows a warning in the status bar when it
+/// was recorded under another profile or the recording is cut short.
+///
+/// `auto_approve` is the policy the session starts with for approving matches
+/// without the operator (`P` cycles it); `VerifiedLive` only acts when a provider
+/// confirms a secret live, so it approves nothing without an orchestrator.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_tui(
+    config: RedactionConfig,
+    options: EngineOptions,
+    initial_engine: EngineType,
+    theme_map: crate::ui::theme::ThemeMap,
+    user_config_path: Option<PathBuf>,
+    max_tracked_matches: usize,
+    remediation_capacity: usize,
+    input: TuiInput,
+    remediation: RemediationSettings,
+    sinks: &[SinkSpec],
+    record: Option<RecordOptions>,
+    auto_approve: AutoApprovePolicy,
+) -> Result<()> {
+    if sinks.contains(&SinkSpec::Stdout) {
+        bail!("--sink stdout cannot be used with the TUI, which draws on stdout");
+    }
+    let sink = if sinks.is_empty() { None } else { Some(open_sinks(sinks)?) };
+    // Missing files are reported before the terminal is taken over.
+    let (follower, recording) = match &input {
+        TuiInput::Stdin => (None, None),
+        TuiInput::Watch(paths, watch_options) => (Some(Follower::new(paths, watch_options)?), None),
+        TuiInput::Replay(path, speed) => (None, Some((Recording::load(path)?, *speed))),
+    };
+    let profile = options.profile_meta.profile_name.clone();
+    let profile = (!profile.is_empty()).then_some(profile);
+    let mut recorder = None;
+    if let Some(record) = &record {
+        let header = RecordingHeader {
+            version: RECORDING_VERSION,
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+            engine: initial_engine.name().to_string(),
+            profile: profile.clone(),
+            raw: record.raw,
+        };
+        // Secrets are found for hashing by an engine of their own, with no dashboard attached.
+        let hasher: Option<Arc<dyn SanitizationEngine>> = match record.raw {
+            true => None,
+            false => Some(Arc::new(CompositeEngine::new(config.clone())?)),
+        };
+        recorder = Some(Recorder::create(&record.path, &header, hasher)?);
+    }
+    let journal: Option<Arc<dyn RemediationJournal>> = match JsonlJournal::from_env()? {
+        Some(journal) => Some(Arc::new(journal)),
+        None => None,
+    };
+    let vault = remediation.vault_from_env().await?;
+    let salt = remediation.org_salt.clone().unwrap_or_default().into_bytes();
+    let providers = build_providers(&remediation.providers)?;
+    // Besides the configured notifiers, the orchestrator tells the dashboard which
+    // secrets its providers confirm live, for the verified-live auto-approve policy.
+    let (orchestrator, rx_events) = if providers.is_empty() {
+        (None, None)
+    } else {
+        let (tx_events, rx_events) = mpsc::channel(remediation_capacity.max(1));
+        let mut notifiers = notifiers_from_config(&config.notifications)?;
+        notifiers.push(Arc::new(ChannelNotifier::new(tx_events)));
+        let mut orchestrator = SelfHealingEngine::new(providers, vault.clone(), remediation.governor(), false, salt.clone(), remediation.dry_run)
+            .with_notifiers(notifiers)
+            .with_remediation_state(options.remediation_state.clone());
+        if let Some(journal) = &journal {
+            orchestrator = orchestrator.with_journal(Arc::clone(journal));
+        }
+        (Some(Arc::new(orchestrator)), Some(rx_events))
+    };
+    let assembler = SourcedAssembler::new(BlockAssembler::from_rules(&config.rules)?);
+
     enable_raw_mode()?;
+    // Restores the terminal on every way out: return, error, or panic.
+    let terminal_guard = CleanupGuard::new(restore_terminal);
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // Create the main data channel
-    let (tx_line, mut rx_line) = mpsc::channel::<String>(1000);
-    let (tx_match, mut rx_match) = mpsc::channel::<RedactionMatch>(100);
-
-    engine.set_remediation_tx(tx_match.clone());
-
-    // Clone sender for the background thread
-    let tx_stdin = tx_line.clone();
-
-    // NEW: Unbuffered Byte-Level Stdin Reader
-    // This bypasses the standard library's internal buffering to ensure zero-latency streaming.
-    tokio::task::spawn_blocking(move || {
-        use std::io::Read;
-        let stdin = std::io::stdin();
-        let mut handle = stdin.lock();
-        let mut buffer = [0u8; 1]; // Read 1 byte at a time for instant reaction
-        let mut line_acc = Vec::with_capacity(1024);
-
-        loop {
-            match handle.read(&mut buffer) {
-                Ok(0) => break, // EOF
-                Ok(_) => {
-                    let byte = buffer[0];
-                    if byte == b'\n' {
-                        // Flush accumulated line immediately upon hitting newline
-                        if let Ok(s) = String::from_utf8(line_acc.clone()) {
-                            // Trim Windows CR (\r) if present
-                            let clean_s = s.trim_end_matches('\r').to_string();
-                            if tx_stdin.blocking_send(clean_s).is_err() { break; }
-                        }
-                        line_acc.clear();
-                    } else {
-                        line_acc.push(byte);
-                    }
-                }
-                Err(_) => break,
-            }
+    // New input, and history replayed after a threshold change or engine switch.
+    let (tx_line, rx_line) = mpsc::channel::<SourcedLine>(1000);
+    let (tx_history, rx_history) = mpsc::channel::<SourcedLine>(1000);
+    let (tx_match, mut rx_match) = mpsc::channel::<RedactionMatch>(remediation_capacity.max(1));
+
+    let heat_bands = config.engines.entropy.resolved_heat_bands();
+    // Kept with the profile already applied, and with the tuned threshold, so switching
+    // engines does not fall back to the defaults.
+    let mut session_config = config.clone();
+    let engine = EngineHandle::new(build_engine(initial_engine, config, options.clone(), &salt, tx_match.clone())?);
+    // Known leaks start from the on-disk cache; the vault sync keeps it current.
+    let cache_path = fingerprint_cache_path();
+    if let Some(cache) = open_fingerprint_cache(cache_path.as_deref()) {
+        engine.with(|e| e.set_fingerprint_cache(cache));
+    }
+
+    // Feed the Live Stream from stdin, the followed files, or a recording, through
+    // the recorder when there is one.
+    let tx_input = match &recorder {
+        Some((recorder, _)) => {
+            let (tx_recorded, rx_recorded) = mpsc::channel::<SourcedLine>(1000);
+            tokio::spawn(record_lines(rx_recorded, tx_line, recorder.clone()));
+            tx_recorded
         }
-        
-        // Flush any remaining data (if the stream ends without a newline)
-        if !line_acc.is_empty() {
-            if let Ok(s) = String::from_utf8(line_acc) {
-                let clean_s = s.trim_end_matches('\r').to_string();
-                let _ = tx_stdin.blocking_send(clean_s);
+        None => tx_line,
+    };
+    let (tx_key, mut rx_key) = mpsc::channel::<KeyEvent>(64);
+    let decode_metrics = Arc::new(DecodeMetrics::default());
+    let mut replay_warnings = Vec::new();
+    match (follower, recording) {
+        (Some(follower), _) => { tokio::task::spawn_blocking(move || follow_files(follower, tx_input)); }
+        (None, Some((recording, speed))) => {
+            replay_warnings.extend(recording.profile_warning(profile.as_deref()));
+            if recording.truncated {
+                replay_warnings.push(format!("Recording is cut short; replaying its first {} events.", recording.events.len()));
             }
+            tokio::spawn(replay(recording.events, speed, tx_input, tx_key));
         }
-    });
+        (None, None) => {
+            let decode = Arc::clone(&decode_metrics);
+            tokio::task::spawn_blocking(move || read_stdin_lines(tx_input, decode));
+        }
+    }
 
-    let app = Arc::new(Mutex::new(App::new(1000, EngineType::Entropy)));
-    start_sync_task(Arc::clone(&app), 60).await?;
+    let mut app = App::new(1000, initial_engine)
+        .with_decode_metrics(decode_metrics)
+        .with_max_tracked_matches(max_tracked_matches)
+        .with_profile_name(options.profile_meta.profile_name.clone())
+        .with_ignore_store(options.ignore_store.clone())
+        .with_auto_approve(auto_approve);
+    // Matches are copied to the orchestrator; the dashboard keeps the originals.
+    let mut tx_orchestrator = None;
+    if let Some(orchestrator) = &orchestrator {
+        let (tx_detect, rx_detect) = mpsc::channel(remediation_capacity.max(1));
+        let (tx_plan, rx_plan) = mpsc::channel(16);
+        let (tx_approval, rx_approval) = mpsc::channel(16);
+        Arc::clone(orchestrator).listen(rx_detect);
+        Arc::clone(orchestrator).listen_plans(rx_plan);
+        Arc::clone(orchestrator).listen_approvals(rx_approval, APPROVAL_GRACE);
+        app = app.with_plan_tx(tx_plan).with_approval_tx(tx_approval);
+        tx_orchestrator = Some(tx_detect);
+    }
+    let app = Arc::new(Mutex::new(app));
+    {
+        let mut app_init = app.lock().await;
+        app_init.entropy_threshold = engine.with(|e| e.entropy_threshold());
+        app_init.heat_bands = heat_bands;
+        for warning in &replay_warnings {
+            log::warn!("{}", warning);
+        }
+        if !replay_warnings.is_empty() {
+            app_init.status_message = Some(replay_warnings.join(" "));
+        }
+    }
+    start_sync_task(Arc::clone(&app), vault, engine.clone(), Duration::from_secs(60), cache_path.clone()).await?;
+    if let Some(journal) = journal {
+        start_journal_task(Arc::clone(&app), journal, Duration::from_secs(2)).await?;
+    }
+    let redraw = Arc::new(Notify::new());
+    let forward = sink.map(|sink| {
+        let (tx_forward, rx_forward) = mpsc::channel::<String>(1000);
+        tokio::task::spawn_blocking(move || forward_lines(sink, rx_forward));
+        tx_forward
+    });
+    tokio::spawn(process_lines(engine.clone(), Arc::clone(&app), assembler, rx_line, rx_history, Arc::clone(&redraw), forward));
+    if let Some(orchestrator) = orchestrator {
+        start_outcome_task(Arc::clone(&app), orchestrator.subscribe_outcomes()).await?;
+        if let Some(rx_events) = rx_events {
+            start_verification_task(Arc::clone(&app), rx_events).await?;
+        }
+        start_governor_task(Arc::clone(&app), orchestrator, Duration::from_secs(1)).await?;
+    }
 
     let mut event_stream = event::EventStream::new();
-    let mut last_input = Instant::now();
-    let debounce = Duration::from_millis(200);
+    let mut key_gate = KeyGate::new(Duration::from_millis(200), recorder.as_ref().map(|(recorder, _)| recorder.clone()));
 
     loop {
         {
@@ -89,98 +553,222 @@ pub async fn run_tui(mut engine: Box<dyn SanitizationEngine>, theme_map: crate::
             terminal.draw(|f| ui::draw(f, &app_lock, &theme_map))?;
         }
 
-        tokio::select! {
-            Some(Ok(evt)) = event_stream.next() => {
-                if let Event::Key(key) = evt {
-                    let now = Instant::now();
-                    if now.duration_since(last_input) < debounce { continue; }
-                    last_input = now;
-
-                    let mut app_write = app.lock().await;
-                    
-                    // --- GLOBAL KEYS (Work anytime unless menu is open) ---
-                    if !app_write.show_engine_menu {
-                        match key.code {
-                            KeyCode::Char('q') | KeyCode::Esc => app_write.should_quit = true,
-                            KeyCode::Char('h') => app_write.toggle_heatmap(),
-                            KeyCode::Char('d') => app_write.toggle_diff(),
-                            KeyCode::Char('e') => app_write.toggle_engine_menu(),
-                            
-                            // Remediation Actions
-                            KeyCode::Char('a') => { app_write.approve_current(); },
-                            KeyCode::Char('i') => { app_write.ignore_current(); },
-                            
-                            KeyCode::Down => app_write.next_match(),
-                            KeyCode::Up => app_write.previous_match(),
-                            _ => {}
-                        }
-                    } else {
-                        // --- MENU KEYS (Only work when menu is open) ---
-                        match key.code {
-                            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('e') => app_write.toggle_engine_menu(),
-                            KeyCode::Enter => {
-                                let selected = app_write.engine_list_state.selected().unwrap_or(1);
-                                let new_type = match selected {
-                                    0 => EngineType::Regex,
-                                    1 => EngineType::Entropy,
-                                    _ => EngineType::Hybrid,
-                                };
-                                
-                                // 1. Swap Engine
-                                let config = RedactionConfig::load_default_rules().unwrap();
-                                engine = match new_type {
-                                    EngineType::Regex => Box::new(RegexEngine::new(config).unwrap()),
-                                    _ => Box::new(EntropyEngine::new(config).unwrap()),
-                                };
-                                engine.set_remediation_tx(tx_match.clone());
-                                
-                                // 2. RETROACTIVE SCANNING
-                                let history: Vec<String> = app_write.raw_input_buffer.drain(..).collect();
-                                app_write.log_lines.clear();
-                                app_write.matches.clear();
-                                app_write.heat_map.clear();
-                                app_write.current_engine = new_type;
-                                app_write.show_engine_menu = false;
-
-                                // Clone the *original* tx_line which is still valid here
-                                let tx_replay = tx_line.clone();
-                                tokio::spawn(async move {
-                                    for line in history {
-                                        let _ = tx_replay.send(line).await;
-                                    }
-                                });
-                            },
-                            KeyCode::Down => app_write.next_engine(),
-                            KeyCode::Up => {
-                                let i = match app_write.engine_list_state.selected() {
-                                    Some(i) => if i == 0 { 2 } else { i - 1 },
-                                    None => 0,
-                                };
-                                app_write.engine_list_state.select(Some(i));
-                            },
-                            _ => {}
-                        }
-                    }
-                }
-            }
+        let (key, source) = tokio::select! {
+            Some(Ok(evt)) = event_stream.next() => match evt {
+                Event::Key(key) => (key, KeySource::Typed),
+                _ => continue,
+            },
+            // Keys from a replayed recording, handled as if typed.
+            Some(key) = rx_key.recv() => (key, KeySource::Replayed),
 
             // Engine Processing Loop
             Some(m) = rx_match.recv() => {
+                let dropped = tx_orchestrator.as_ref().is_some_and(|tx| tx.try_send(m.clone()).is_err());
                 let mut app_write = app.lock().await;
+                // Logging would draw over the dashboard, so this goes to the status bar.
+                if dropped {
+                    app_write.status_message = Some(format!("Remediation queue full; match {} not sent to the orchestrator.", m.rule_name));
+                }
                 app_write.add_match(m);
+                continue;
             }
-            Some(raw_line) = rx_line.recv() => {
-                let (sanitized, _) = engine.sanitize(&raw_line, "tui", "v02", "", "user", "auto", "proc", None)
-                    .context("Engine failure")?;
-                let mut app_write = app.lock().await;
-                app_write.heat_map = engine.get_heat_scores(&raw_line);
-                app_write.push_log_pair(raw_line, sanitized);
+            _ = redraw.notified() => continue,
+        };
+
+        // --- SEARCH PROMPT (Typed keys are not debounced) ---
+        {
+            let mut app_write = app.lock().await;
+            if app_write.input_mode == InputMode::Search {
+                key_gate.admit(key.code, source, false);
+                match key.code {
+                    KeyCode::Esc => app_write.clear_filter(),
+                    KeyCode::Enter => app_write.confirm_search(),
+                    KeyCode::Backspace => app_write.pop_search_char(),
+                    KeyCode::Char(c) => app_write.push_search_char(c),
+                    _ => {}
+                }
+                continue;
+            }
+        }
+
+        if !key_gate.admit(key.code, source, true) { continue; }
+
+        let mut app_write = app.lock().await;
+
+        // --- ERROR POPUP (Swallows the next key) ---
+        if app_write.error_popup.take().is_some() {
+            continue;
+        }
+
+        // --- WRITE-BACK CONFIRMATION (Swallows the next key) ---
+        if app_write.confirm_threshold_write {
+            app_write.confirm_threshold_write = false;
+            app_write.status_message = match (key.code, app_write.entropy_threshold, &user_config_path) {
+                (KeyCode::Char('y') | KeyCode::Char('Y'), Some(threshold), Some(path)) => {
+                    match config::save_entropy_threshold(path, threshold) {
+                        Ok(()) => Some(format!("Saved threshold {:.1} to {}", threshold, path.display())),
+                        Err(e) => Some(format!("Failed to save threshold: {:#}", e)),
+                    }
+                }
+                _ => Some("Threshold write cancelled".to_string()),
+            };
+            continue;
+        }
+        
+        // --- MATCH DETAIL POPUP (Provider submenu) ---
+        if app_write.detail.is_some() {
+            match key.code {
+                KeyCode::Down => app_write.cycle_provider_choice(true),
+                KeyCode::Up => app_write.cycle_provider_choice(false),
+                KeyCode::Enter => { app_write.approve_from_detail(); },
+                KeyCode::Char('a') => { app_write.approve_in_detail(); },
+                KeyCode::Char('i') => app_write.ignore_in_detail(),
+                KeyCode::Char('c') => { app_write.copy_detail_hash(); },
+                KeyCode::Char('y') | KeyCode::Char('Y') => { app_write.copy_match_context(); },
+                KeyCode::Esc | KeyCode::Char('q') => app_write.close_detail(),
+                _ => {}
+            }
+            continue;
+        }
+
+        // --- GLOBAL KEYS (Work anytime unless menu is open) ---
+        if !app_write.show_engine_menu {
+            match key.code {
+                // Live Threshold Tuning
+                KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Char('-') => {
+                    let step = if key.code == KeyCode::Char('-') { -THRESHOLD_STEP } else { THRESHOLD_STEP };
+                    if let Some(threshold) = app_write.adjust_threshold(step)
+                        && engine.with(|e| e.set_entropy_threshold(threshold))
+                    {
+                        session_config.engines.entropy.threshold = Some(threshold);
+                        let history = app_write.begin_replay();
+                        let tx_replay = tx_history.clone();
+                        tokio::spawn(async move {
+                            for line in history {
+                                let _ = tx_replay.send(line).await;
+                            }
+                        });
+                    }
+                }
+                KeyCode::Char('W') | KeyCode::Char('w') => {
+                    app_write.status_message = match (app_write.entropy_threshold, &user_config_path) {
+                        (Some(threshold), Some(path)) if app_write.current_engine != EngineType::Regex => {
+                            app_write.confirm_threshold_write = true;
+                            Some(format!("Write threshold {:.1} to {}? [y/N]", threshold, path.display()))
+                        }
+                        (_, None) => Some("No user config path available".to_string()),
+                        _ => Some("Active engine has no entropy threshold".to_string()),
+                    };
+                }
+
+                KeyCode::Char('s') | KeyCode::Char('S') => {
+                    let snapshot = SessionSnapshot::from_app(&app_write, chrono::Utc::now());
+                    app_write.status_message = Some("Saving session…".to_string());
+                    let app_status = Arc::clone(&app);
+                    tokio::spawn(async move {
+                        let written = tokio::task::spawn_blocking(move || snapshot.write_to(Path::new(".")))
+                            .await
+                            .map_err(anyhow::Error::from)
+                            .and_then(|written| written);
+                        app_status.lock().await.status_message = Some(match written {
+                            Ok((log_path, matches_path)) => format!("Saved session to {} and {}", log_path.display(), matches_path.display()),
+                            Err(e) => format!("Failed to save session: {:#}", e),
+                        });
+                    });
+                }
+
+                KeyCode::Esc if !app_write.filter.is_empty() => app_write.clear_filter(),
+                KeyCode::Char('q') | KeyCode::Esc => app_write.should_quit = true,
+                KeyCode::Char('/') => app_write.begin_search(),
+                KeyCode::Char('n') => app_write.next_match(),
+                KeyCode::Char('N') => app_write.previous_match(),
+                KeyCode::Char('h') => app_write.toggle_heatmap(),
+                KeyCode::Char('d') => app_write.toggle_diff(),
+                KeyCode::Char('D') => app_write.toggle_diff_layout(),
+                KeyCode::Char('e') => app_write.toggle_engine_menu(),
+                KeyCode::Enter => {
+                    let query = app_write.plan_tx.clone().zip(app_write.selected_match().cloned());
+                    let plans = match query {
+                        Some((tx, m)) => tokio::time::timeout(PLAN_TIMEOUT, request_plan(&tx, m)).await.ok().flatten(),
+                        None => None,
+                    };
+                    app_write.open_detail(plans);
+                }
+                
+                // Remediation Actions
+                KeyCode::Char('a') => { app_write.approve_current(); },
+                KeyCode::Char('i') => { app_write.ignore_current(); },
+                KeyCode::Char('I') => { app_write.ignore_permanently(); },
+                KeyCode::Char('u') => { app_write.undo(); },
+                KeyCode::Char('P') => app_write.cycle_auto_approve(),
+
+                // Copy (sanitized text only)
+                KeyCode::Char('y') => { app_write.copy_stream_line(); },
+                KeyCode::Char('Y') => { app_write.copy_match_context(); },
+                KeyCode::Char('k') => app_write.select_previous_line(),
+                KeyCode::Char('j') => app_write.select_next_line(),
+                
+                KeyCode::Down => app_write.next_match(),
+                KeyCode::Up => app_write.previous_match(),
+                _ => {}
+            }
+        } else {
+            // --- MENU KEYS (Only work when menu is open) ---
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('e') => app_write.toggle_engine_menu(),
+                KeyCode::Enter => {
+                    let selected = app_write.engine_list_state.selected().unwrap_or(1);
+                    let new_type = match selected {
+                        0 => EngineType::Regex,
+                        1 => EngineType::Entropy,
+                        _ => EngineType::Hybrid,
+                    };
+                    
+                    app_write.show_engine_menu = false;
+
+                    // 1. Swap Engine
+                    match build_engine(new_type, session_config.clone(), options.clone(), &salt, tx_match.clone()) {
+                        Ok(new_engine) => {
+                            if let Some(cache) = open_fingerprint_cache(cache_path.as_deref()) {
+                                new_engine.set_fingerprint_cache(cache);
+                            }
+                            engine.swap(new_engine);
+                            set_crash_engine(new_type.name());
+                        }
+                        Err(e) => {
+                            app_write.error_popup = Some(format!("Could not start the {} engine: {:#}", new_type.name(), e));
+                            continue;
+                        }
+                    }
+
+                    // 2. RETROACTIVE SCANNING (decided matches keep their status)
+                    let history = app_write.begin_replay();
+                    app_write.current_engine = new_type;
+                    app_write.entropy_threshold = engine.with(|e| e.entropy_threshold());
+
+                    let tx_replay = tx_history.clone();
+                    tokio::spawn(async move {
+                        for line in history {
+                            let _ = tx_replay.send(line).await;
+                        }
+                    });
+                },
+                KeyCode::Down => app_write.next_engine(),
+                KeyCode::Up => {
+                    let i = match app_write.engine_list_state.selected() {
+                        Some(i) => if i == 0 { 2 } else { i - 1 },
+                        None => 0,
+                    };
+                    app_write.engine_list_state.select(Some(i));
+                },
+                _ => {}
             }
         }
     }
 
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
-    terminal.show_cursor()?;
+    drop(terminal_guard);
+    if let Some((recorder, task)) = recorder {
+        task.finish(recorder).await?;
+    }
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,375 @@
+// cleansh/src/tui/record.rs
+//! Session recordings, for reproducing what a user saw in the TUI.
+//!
+//! `--record` writes every Live Stream line the TUI receives and every key it
+//! handles, with the milliseconds since the session started, to a JSON Lines
+//! file: a [`RecordingHeader`] first, then one [`RecordedEvent`] per line.
+//! `--replay` feeds such a file back in place of stdin at the recorded pace, so
+//! layouts, match ordering, and engine switches play out again. Typed keys are
+//! debounced before they are recorded, and replayed keys are not debounced again
+//! (see [`KeyGate`]), so a replay at any speed handles the same keys.
+//!
+//! Unless the recording is raw, every secret the hybrid engine finds in a line is
+//! written as `[rule:hash]` instead, so recordings can be attached to bug reports.
+//! The hash is an HMAC under a key drawn for that recording and never written, so
+//! repeats of a secret can be told apart from other secrets, but no value can be
+//! guessed back from its hash.
+//! That matching and the writing happen on a blocking thread, never on the
+//! engine's path.
+
+use anyhow::{bail, Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc as std_mpsc, Arc};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use ring::hmac;
+use ring::rand::SystemRandom;
+
+use cleansh_core::engine::SanitizationEngine;
+use crate::utils::lines::SourcedLine;
+
+/// Format version written in every header.
+pub const RECORDING_VERSION: u32 = 1;
+
+/// Bytes of a secret's HMAC kept, as hex, in its `[rule:hash]` placeholder.
+const HASH_PREFIX_BYTES: usize = 4;
+
+/// The slowest and fastest `--speed` a replay accepts.
+pub const SPEED_RANGE: std::ops::RangeInclusive<f64> = 0.001..=1_000_000.0;
+
+/// Where `--record` writes, and whether lines are kept as received (`--record-raw`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordOptions {
+    pub path: PathBuf,
+    pub raw: bool,
+}
+
+/// The first line of a recording.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordingHeader {
+    pub version: u32,
+    pub recorded_at: String,
+    /// The engine the session started with.
+    pub engine: String,
+    /// `None` when no profile was selected.
+    pub profile: Option<String>,
+    /// Whether lines were written as received rather than with secrets hashed.
+    pub raw: bool,
+}
+
+/// One line of a recording after the header.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordedEvent {
+    /// A Live Stream line as it arrived, before block assembly.
+    Line { t_ms: u64, source_id: String, line_no: u64, text: String },
+    /// A key press, encoded by [`encode_key`].
+    Key { t_ms: u64, key: String },
+}
+
+impl RecordedEvent {
+    /// Milliseconds since the session started.
+    pub fn t_ms(&self) -> u64 {
+        match self {
+            Self::Line { t_ms, .. } | Self::Key { t_ms, .. } => *t_ms,
+        }
+    }
+}
+
+/// A recording read back from disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recording {
+    pub header: RecordingHeader,
+    pub events: Vec<RecordedEvent>,
+    /// Whether reading stopped at a line that was cut off or unreadable, as when
+    /// the recording session crashed; `events` holds everything before it.
+    pub truncated: bool,
+}
+
+impl Recording {
+    /// Parses a recording. Only a missing or unreadable header is an error.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = text.lines();
+        let header: RecordingHeader = lines.next()
+            .and_then(|line| serde_json::from_str(line).ok())
+            .context("Not a cleansh recording: the header line is missing or unreadable")?;
+        if header.version > RECORDING_VERSION {
+            bail!("Recording format version {} is newer than this cleansh supports ({})", header.version, RECORDING_VERSION);
+        }
+        let mut events = Vec::new();
+        let mut truncated = false;
+        for line in lines.filter(|line| !line.trim().is_empty()) {
+            match serde_json::from_str(line) {
+                Ok(event) => events.push(event),
+                Err(_) => {
+                    truncated = true;
+                    break;
+                }
+            }
+        }
+        Ok(Self { header, events, truncated })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read recording '{}'", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Failed to load recording '{}'", path.display()))
+    }
+
+    /// A warning when the recording was made with a profile other than `profile`
+    /// (`None` for no profile), since its lines may then be redacted differently.
+    pub fn profile_warning(&self, profile: Option<&str>) -> Option<String> {
+        (self.header.profile.as_deref() != profile).then(|| format!(
+            "Recording was made with profile '{}' but this session uses '{}'; redactions may differ.",
+            self.header.profile.as_deref().unwrap_or("default"),
+            profile.unwrap_or("default"),
+        ))
+    }
+}
+
+/// The key as recorded: the character itself for a character key, otherwise its
+/// name (`Enter`, `Esc`, `Up`, ...). Keys the TUI never handles are not recorded.
+pub fn encode_key(code: KeyCode) -> Option<String> {
+    Some(match code {
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        _ => return None,
+    })
+}
+
+/// The inverse of [`encode_key`].
+pub fn decode_key(key: &str) -> Option<KeyCode> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+    Some(match key {
+        "Enter" => KeyCode::Enter,
+        "Esc" => KeyCode::Esc,
+        "Backspace" => KeyCode::Backspace,
+        "Tab" => KeyCode::Tab,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        _ => return None,
+    })
+}
+
+/// `text` with every secret `engine` finds replaced by `[rule:hash]`, where the
+/// hash is keyed by `key`. If the engine fails the whole line is replaced, so
+/// nothing unchecked is written.
+pub fn hash_secrets(engine: &dyn SanitizationEngine, key: &hmac::Key, text: &str, source_id: &str) -> String {
+    let hashed = |rule: &str, value: &str| {
+        let mut ctx = hmac::Context::with_key(key);
+        ctx.update(rule.as_bytes());
+        ctx.update(b"\0");
+        ctx.update(value.as_bytes());
+        let tag = ctx.sign();
+        let hex: String = tag.as_ref()[..HASH_PREFIX_BYTES].iter().map(|b| format!("{:02x}", b)).collect();
+        format!("[{}:{}]", rule, hex)
+    };
+    let mut matches = match engine.find_matches_for_ui(text, source_id) {
+        Ok(matches) => matches,
+        Err(_) => return hashed("line", text),
+    };
+    matches.sort_by_key(|m| m.document_span().start);
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    for m in &matches {
+        let span = m.document_span();
+        let Some(value) = text.get(span.clone()) else { continue };
+        if span.start < copied {
+            continue;
+        }
+        out.push_str(&text[copied..span.start]);
+        out.push_str(&hashed(&m.rule_name, value));
+        copied = span.end;
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+/// Where a key the dashboard receives comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeySource {
+    Typed,
+    Replayed,
+}
+
+/// Decides which keys the dashboard handles, and records the typed ones it lets
+/// through.
+///
+/// A typed key within `debounce` of the last handled key is dropped. Replayed keys
+/// passed this check when they were recorded, and at a faster `--speed` their gaps
+/// shrink below `debounce`, so they are always handled.
+#[derive(Debug)]
+pub struct KeyGate {
+    debounce: Duration,
+    last: Option<Instant>,
+    recorder: Option<Recorder>,
+}
+
+impl KeyGate {
+    pub fn new(debounce: Duration, recorder: Option<Recorder>) -> Self {
+        Self { debounce, last: None, recorder }
+    }
+
+    /// Whether the dashboard should handle `code`. Keys typed into the search
+    /// prompt are not debounced (`debounced == false`) and do not restart the
+    /// debounce window.
+    pub fn admit(&mut self, code: KeyCode, source: KeySource, debounced: bool) -> bool {
+        if debounced {
+            let now = Instant::now();
+            let bounced = source == KeySource::Typed
+                && self.last.is_some_and(|last| now.duration_since(last) < self.debounce);
+            if bounced {
+                return false;
+            }
+            self.last = Some(now);
+        }
+        if let (KeySource::Typed, Some(recorder)) = (source, &self.recorder) {
+            recorder.key(code);
+        }
+        true
+    }
+}
+
+/// Appends events to a recording from any thread. Cloning shares the file.
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    /// `None` stops the writer.
+    tx: std_mpsc::Sender<Option<RecordedEvent>>,
+    started: Instant,
+}
+
+/// The writer task behind a [`Recorder`]; [`finish`](Self::finish) stops it.
+#[derive(Debug)]
+pub struct RecorderTask(JoinHandle<Result<()>>);
+
+impl Recorder {
+    /// Creates (or truncates) the recording at `path`, writes `header`, and starts
+    /// the writer task. With `hasher`, secrets in recorded lines are hashed by it.
+    /// Must be called inside a Tokio runtime.
+    pub fn create(path: &Path, header: &RecordingHeader, hasher: Option<Arc<dyn SanitizationEngine>>) -> Result<(Self, RecorderTask)> {
+        let file = File::create(path).with_context(|| format!("Failed to create recording '{}'", path.display()))?;
+        let mut out = BufWriter::new(file);
+        serde_json::to_writer(&mut out, header)?;
+        out.write_all(b"\n")?;
+        out.flush()?;
+        let (tx, rx) = std_mpsc::channel();
+        let task = tokio::task::spawn_blocking(move || write_events(out, rx, hasher));
+        Ok((Self { tx, started: Instant::now() }, RecorderTask(task)))
+    }
+
+    fn t_ms(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64
+    }
+
+    pub fn line(&self, line: &SourcedLine) {
+        let _ = self.tx.send(Some(RecordedEvent::Line {
+            t_ms: self.t_ms(),
+            source_id: line.source_id.to_string(),
+            line_no: line.line_no,
+            text: line.text.clone(),
+        }));
+    }
+
+    pub fn key(&self, code: KeyCode) {
+        if let Some(key) = encode_key(code) {
+            let _ = self.tx.send(Some(RecordedEvent::Key { t_ms: self.t_ms(), key }));
+        }
+    }
+}
+
+impl RecorderTask {
+    /// Stops the writer once everything `recorder` and its clones have sent so far
+    /// is on disk. Later events are dropped.
+    pub async fn finish(self, recorder: Recorder) -> Result<()> {
+        let _ = recorder.tx.send(None);
+        self.0.await.context("Recorder task panicked")?
+    }
+}
+
+/// Writes events until stopped or every sender is gone, flushing whenever the
+/// queue runs dry so a crash loses as little as possible.
+fn write_events(
+    mut out: BufWriter<File>,
+    rx: std_mpsc::Receiver<Option<RecordedEvent>>,
+    hasher: Option<Arc<dyn SanitizationEngine>>,
+) -> Result<()> {
+    let key = match hasher {
+        Some(_) => Some(hmac::Key::generate(hmac::HMAC_SHA256, &SystemRandom::new())
+            .map_err(|_| anyhow::anyhow!("Failed to generate the recording's hash key"))?),
+        None => None,
+    };
+    while let Ok(mut next) = rx.recv() {
+        let stopped = loop {
+            let Some(mut event) = next else { break true };
+            if let (Some(engine), Some(key), RecordedEvent::Line { text, source_id, .. }) = (&hasher, &key, &mut event) {
+                *text = hash_secrets(engine.as_ref(), key, text, source_id);
+            }
+            serde_json::to_writer(&mut out, &event)?;
+            out.write_all(b"\n")?;
+            match rx.try_recv() {
+                Ok(queued) => next = queued,
+                Err(_) => break false,
+            }
+        };
+        out.flush().context("Failed to write recording")?;
+        if stopped {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Sends `line` to `tx` after recording it.
+pub async fn record_lines(mut rx: mpsc::Receiver<SourcedLine>, tx: mpsc::Sender<SourcedLine>, recorder: Recorder) {
+    while let Some(line) = rx.recv().await {
+        recorder.line(&line);
+        if tx.send(line).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Plays `events` back: lines into `tx_line` and keys into `tx_key`, keeping the
+/// recorded gaps between them divided by `speed`, which must lie in
+/// [`SPEED_RANGE`]. Stops early if both receivers
+/// are gone.
+pub async fn replay(events: Vec<RecordedEvent>, speed: f64, tx_line: mpsc::Sender<SourcedLine>, tx_key: mpsc::Sender<KeyEvent>) {
+    let mut previous = 0;
+    for event in events {
+        let gap = event.t_ms().saturating_sub(previous);
+        previous = event.t_ms();
+        if gap > 0 {
+            tokio::time::sleep(Duration::from_secs_f64(gap as f64 / 1000.0 / speed)).await;
+        }
+        let sent = match event {
+            RecordedEvent::Line { source_id, line_no, text, .. } => {
+                tx_line.send(SourcedLine::new(Arc::from(source_id), line_no, text)).await.is_ok()
+            }
+            RecordedEvent::Key { key, .. } => match decode_key(&key) {
+                Some(code) => tx_key.send(KeyEvent::new(code, KeyModifiers::NONE)).await.is_ok(),
+                None => true,
+            },
+        };
+        if !sent && tx_line.is_closed() && tx_key.is_closed() {
+            return;
+        }
+    }
+}
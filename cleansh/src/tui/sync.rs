@@ -1,56 +1,206 @@
 // cleansh/src/tui/sync.rs
-//! Background synchronization for S3Vault.
-//! 
-//! Handles the periodic sync of redaction fingerprints and revocation 
-//! status to ensure organizational ubiquity.
+//! Background synchronization with the fingerprint vault.
+//!
+//! Handles the periodic sync of redaction fingerprints and revocation
+//! status to ensure organizational ubiquity, and the refresh of the
+//! remediation journal, rate limits, and remediation outcomes shown on the
+//! dashboard, and passes liveness confirmations to the auto-approve policy.
 
-use tokio::time::{self, Duration};
+use tokio::time::{self, Duration, Instant};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use crate::tui::app::App;
+use async_trait::async_trait;
+use crate::tui::app::{App, JOURNAL_PANEL_ENTRIES};
+use cleansh_core::EngineHandle;
+use cleansh_core::remediation::fingerprint::SecretFingerprint;
+use cleansh_core::remediation::journal::RemediationJournal;
+use cleansh_core::remediation::orchestrator::{OutcomeEvent, SelfHealingEngine};
+use cleansh_core::remediation::sync::{source_for, spawn_sync_loop};
+use cleansh_core::remediation::vault::{FingerprintStream, FingerprintVault};
+use cleansh_core::remediation::notifier::{RemediationEvent, RemediationEventKind};
+use tokio::sync::{broadcast, mpsc};
 use anyhow::Result;
 
+/// What the Ubiquity panel shows when no vault is configured.
+pub const NO_VAULT: &str = "No vault configured";
+
 /// Statistics sent from the background sync task to the UI.
 #[derive(Debug, Clone)]
 pub struct SyncStats {
+    /// Whether the last fetch from the vault succeeded.
     pub active: bool,
     pub total_hashes: usize,
     pub last_sync_ms: u128,
     pub provider: String,
+    /// Why the last fetch failed, while `active` is false.
+    pub error: Option<String>,
+}
+
+impl SyncStats {
+    /// Stats for a dashboard without a vault.
+    pub fn offline() -> Self {
+        Self { active: false, total_hashes: 0, last_sync_ms: 0, provider: NO_VAULT.to_string(), error: None }
+    }
 }
 
-/// Orchestrates the background S3 synchronization loop.
+/// Syncs the engine `engine` holds with `vault` through the core sync loop: a
+/// full fetch every `interval`, starting now, plus pushed fingerprints when the
+/// vault can push them. Each fetch's hash count and latency go to the dashboard; a
+/// failed fetch marks the sync inactive with its error until the next one
+/// succeeds. Without a vault the dashboard is marked offline and nothing runs.
+///
+/// With a `cache_path`, each fetch is also written to the fingerprint cache there
+/// and the engine looks known leaks up in it, so engines swapped in later start
+/// from it.
 pub async fn start_sync_task(
     app: Arc<Mutex<App>>,
-    interval_secs: u64,
+    vault: Option<Arc<dyn FingerprintVault>>,
+    engine: EngineHandle,
+    interval: Duration,
+    cache_path: Option<PathBuf>,
 ) -> Result<()> {
-    let mut interval = time::interval(Duration::from_secs(interval_secs));
+    let Some(vault) = vault else {
+        app.lock().await.sync_stats = SyncStats::offline();
+        return Ok(());
+    };
+    // Without a directory for it, fingerprints are only kept in memory.
+    let cache_path = cache_path.filter(|path| path.parent().is_none_or(|dir| std::fs::create_dir_all(dir).is_ok()));
+    let source = source_for(Arc::new(ReportingVault { inner: vault, app }), interval).await;
+    spawn_sync_loop(engine, source, cache_path);
+    Ok(())
+}
+
+/// A vault that reports each full fetch on the dashboard.
+struct ReportingVault {
+    inner: Arc<dyn FingerprintVault>,
+    app: Arc<Mutex<App>>,
+}
+
+#[async_trait]
+impl FingerprintVault for ReportingVault {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn publish(&self, fingerprint: SecretFingerprint) -> Result<()> {
+        self.inner.publish(fingerprint).await
+    }
+
+    async fn publish_batch(&self, fingerprints: Vec<SecretFingerprint>) -> Result<()> {
+        self.inner.publish_batch(fingerprints).await
+    }
+
+    async fn fetch_all(&self) -> Result<Vec<SecretFingerprint>> {
+        let started = Instant::now();
+        let fetched = self.inner.fetch_all().await;
+        let last_sync_ms = started.elapsed().as_millis();
+        let provider = self.inner.name().to_string();
+        let mut app = self.app.lock().await;
+        app.sync_stats = match &fetched {
+            Ok(fingerprints) => SyncStats { active: true, total_hashes: fingerprints.len(), last_sync_ms, provider, error: None },
+            Err(e) => SyncStats {
+                active: false,
+                total_hashes: app.sync_stats.total_hashes,
+                last_sync_ms,
+                provider,
+                error: Some(format!("{:#}", e)),
+            },
+        };
+        fetched
+    }
+
+    async fn subscribe(&self) -> Result<Option<FingerprintStream>> {
+        self.inner.subscribe().await
+    }
+}
+
+/// Reloads the newest journal entries into the dashboard every `interval`. The
+/// journal may be written by another process, so it is re-read rather than watched.
+pub async fn start_journal_task(
+    app: Arc<Mutex<App>>,
+    journal: Arc<dyn RemediationJournal>,
+    interval: Duration,
+) -> Result<()> {
+    let mut interval = time::interval(interval);
 
     tokio::spawn(async move {
         loop {
             interval.tick().await;
-
-            let start = std::time::Instant::now();
-            
-            // --- SYNC LOGIC START ---
-            // 1. Fetch latest fingerprints from S3 bucket
-            // 2. Merge with local Heat-Seeker cache
-            // 3. Update the engine state
-            // (Mocking actual network call for orchestration layout)
-            let mock_hashes = 1250; 
-            let duration = start.elapsed().as_millis();
-            // --- SYNC LOGIC END ---
-
-            // Update App State atomically
-            let mut app_lock = app.lock().await;
-            app_lock.sync_stats = SyncStats {
-                active: true,
-                total_hashes: mock_hashes,
-                last_sync_ms: duration,
-                provider: "AWS S3".to_string(),
+            let reader = Arc::clone(&journal);
+            let entries = match tokio::task::spawn_blocking(move || reader.recent(JOURNAL_PANEL_ENTRIES)).await {
+                Ok(Ok(entries)) => entries,
+                Ok(Err(e)) => {
+                    log::warn!("Failed to read remediation journal: {:#}", e);
+                    continue;
+                }
+                Err(_) => continue,
             };
+            app.lock().await.journal_entries = entries;
+        }
+    });
+
+    Ok(())
+}
+
+/// Copies each provider's remaining remediation budget from `orchestrator` into
+/// the dashboard every `interval`.
+pub async fn start_governor_task(
+    app: Arc<Mutex<App>>,
+    orchestrator: Arc<SelfHealingEngine>,
+    interval: Duration,
+) -> Result<()> {
+    let mut interval = time::interval(interval);
+
+    tokio::spawn(async move {
+        loop {
+            interval.tick().await;
+            let status = orchestrator.governor_status().await;
+            app.lock().await.governor_status = status;
         }
     });
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Marks a match Revoked on the dashboard as soon as the orchestrator reports a
+/// successful remediation for it. Outcomes missed by a lagging receiver are
+/// skipped; the task ends when the orchestrator is dropped.
+pub async fn start_outcome_task(
+    app: Arc<Mutex<App>>,
+    mut outcomes: broadcast::Receiver<OutcomeEvent>,
+) -> Result<()> {
+    tokio::spawn(async move {
+        loop {
+            match outcomes.recv().await {
+                Ok(event) if event.outcome.successful => {
+                    app.lock().await.mark_revoked_by_hash(&event.sample_hash);
+                }
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    log::warn!("Dashboard missed {} remediation outcomes.", missed);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Hands every secret a provider confirms live to the auto-approve policy, which
+/// approves it under `VerifiedLive`. The task ends when the orchestrator is dropped.
+pub async fn start_verification_task(
+    app: Arc<Mutex<App>>,
+    mut events: mpsc::Receiver<RemediationEvent>,
+) -> Result<()> {
+    tokio::spawn(async move {
+        while let Some(event) = events.recv().await {
+            if event.event == RemediationEventKind::Verified {
+                app.lock().await.mark_verified_live(&event.sample_hash);
+            }
+        }
+    });
+
+    Ok(())
+}
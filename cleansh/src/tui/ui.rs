@@ -4,18 +4,41 @@
 //! Implements multi-panel layouts including Live Stream, Diff Analysis,
 //! and the Shannon Entropy character-map with enhanced visual aesthetics.
 
+use std::borrow::Cow;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect, Alignment},
     style::{Color, Style, Stylize},
     text::{Line, Span},
-    widgets::{Block, Borders, BorderType, List, ListItem, Paragraph, Wrap, Clear, Gauge, Padding},
+    widgets::{Block, Borders, BorderType, List, ListItem, ListState, Paragraph, Wrap, Clear, Gauge, Padding},
     Frame,
 };
-use crate::tui::app::{App, RemediationStatus};
-use crate::ui::diff_viewer::generate_diff_lines;
+use crate::tui::app::{detail_fields, App, InputMode, RemediationStatus};
+use cleansh_core::remediation::journal::{JournalEntry, JournalOutcome};
+use cleansh_core::remediation::orchestrator::{GovernorStatus, ProviderChoice};
+use cleansh_core::engines::StrippedIndexMapper;
+use cleansh_core::Severity;
+use crate::ui::diff_viewer::{generate_diff_columns, generate_diff_lines, get_theme_style};
+use crate::ui::theme::{ThemeEntry, ThemeMap};
+
+/// Longest line, in bytes, the Live Stream, diff, and heatmap draw in full.
+pub const MAX_DISPLAY_LINE_BYTES: usize = 4096;
+
+/// `line` cut to `MAX_DISPLAY_LINE_BYTES` on a character boundary, with a marker
+/// counting the bytes left out. Only drawing is truncated; the engine sees the
+/// whole line.
+pub fn truncate_for_display(line: &str) -> Cow<'_, str> {
+    if line.len() <= MAX_DISPLAY_LINE_BYTES {
+        return Cow::Borrowed(line);
+    }
+    let mut end = MAX_DISPLAY_LINE_BYTES;
+    while !line.is_char_boundary(end) {
+        end -= 1;
+    }
+    Cow::Owned(format!("{}… [+{} bytes]", &line[..end], line.len() - end))
+}
 
 /// Main draw cycle.
-pub fn draw(f: &mut Frame, app: &App, theme_map: &crate::ui::theme::ThemeMap) {
+pub fn draw(f: &mut Frame, app: &App, theme_map: &ThemeMap) {
     let main_constraints = if app.show_diff || app.show_heatmap {
         vec![Constraint::Percentage(50), Constraint::Percentage(50)]
     } else {
@@ -39,17 +62,42 @@ pub fn draw(f: &mut Frame, app: &App, theme_map: &crate::ui::theme::ThemeMap) {
         Span::styled("[Q] Quit ", Style::default().fg(Color::Red)),
         Span::styled("[H] Heatmap ", Style::default().fg(Color::Yellow)),
         Span::styled("[D] Diff ", Style::default().fg(Color::Magenta)),
+        Span::styled("[Shift+D] Split ", Style::default().fg(Color::Magenta)),
         Span::styled("[E] Engine ", Style::default().fg(Color::Blue)),
         Span::styled("[A] Approve ", Style::default().fg(Color::Green)),
         Span::styled("[I] Ignore ", Style::default().fg(Color::DarkGray)),
+        Span::styled("[Enter] Detail ", Style::default().fg(Color::LightCyan)),
+        Span::styled("[U] Undo ", Style::default().fg(Color::Gray)),
+        Span::styled("[Shift+P] Policy ", Style::default().fg(Color::LightYellow)),
+        Span::styled("[Y] Copy ", Style::default().fg(Color::LightGreen)),
+        Span::styled("[/] Search ", Style::default().fg(Color::White)),
+        Span::styled("[+/-] Threshold ", Style::default().fg(Color::LightRed)),
+        Span::styled("[W] Save ", Style::default().fg(Color::Cyan)),
+        Span::styled("[S] Export ", Style::default().fg(Color::LightBlue)),
     ]);
+
+    let mut header_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Color::DarkGray));
+    header_block = header_block.title(Span::styled(
+        format!(" Policy: {} ({} auto-approved) ", app.auto_approve.label(), app.auto_approved),
+        Style::default().fg(Color::LightYellow),
+    ));
+    if let Some(label) = app.threshold_label() {
+        header_block = header_block.title(
+            Line::from(Span::styled(format!(" {} ", label), Style::default().fg(Color::Yellow))).right_aligned(),
+        );
+    }
+    if let Some(status) = &app.status_message {
+        header_block = header_block.title_bottom(
+            Line::from(Span::styled(format!(" {} ", status), Style::default().fg(Color::Cyan))).right_aligned(),
+        );
+    }
     
     let header = Paragraph::new(header_text)
         .alignment(Alignment::Center)
-        .block(Block::default()
-            .borders(Borders::ALL)
-            .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(Color::DarkGray)));
+        .block(header_block);
     f.render_widget(header, vertical_chunks[0]);
 
     // 2. Main Content
@@ -58,33 +106,41 @@ pub fn draw(f: &mut Frame, app: &App, theme_map: &crate::ui::theme::ThemeMap) {
         .constraints(main_constraints)
         .split(vertical_chunks[1]);
 
-    let logs: Vec<ListItem> = app.log_lines.iter()
-        .map(|l| ListItem::new(l.as_str()))
-        .collect();
+    let visible_lines = app.visible_log_indices();
+    let log_title = search_title(" 📡 Live Stream", app, visible_lines.len(), "lines");
+    let logs: Vec<ListItem> = visible_lines.iter().map(|&i| ListItem::new(truncate_for_display(&app.log_lines[i]))).collect();
     
     let log_block = Block::default()
-        .title(" 📡 Live Stream ")
+        .title(log_title)
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
         .border_style(Style::default().fg(Color::White))
         .padding(Padding::new(1, 1, 0, 0)); 
 
-    let log_list = List::new(logs).block(log_block);
-    f.render_widget(log_list, content_chunks[0]);
+    // Only a line picked with j/k is highlighted (and scrolled into view).
+    let log_list = List::new(logs).block(log_block)
+        .highlight_style(Style::default().bg(Color::Rgb(40,40,40)).bold());
+    let mut log_state = ListState::default();
+    log_state.select(app.stream_selected.and_then(|selected| visible_lines.iter().position(|&i| i == selected)));
+    f.render_stateful_widget(log_list, content_chunks[0], &mut log_state);
 
     if app.show_diff {
-        let orig = app.raw_input_buffer.iter().rev().take(15).cloned().collect::<Vec<_>>().join("\n");
-        let sanit = app.log_lines.iter().rev().take(15).cloned().collect::<Vec<_>>().join("\n");
-        let diff_lines = generate_diff_lines(&orig, &sanit, theme_map);
-        let diff_para = Paragraph::new(diff_lines)
-            .block(Block::default()
-                .title(" 🔍 Redaction Diff ")
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded))
-            .wrap(Wrap { trim: false });
-        f.render_widget(diff_para, content_chunks[1]);
+        let orig = app.raw_input_buffer.iter().rev().take(15).map(|line| truncate_for_display(&line.text)).collect::<Vec<_>>().join("\n");
+        let sanit = app.log_lines.iter().rev().take(15).map(|line| truncate_for_display(line)).collect::<Vec<_>>().join("\n");
+        if app.diff_side_by_side {
+            render_diff_columns(f, &orig, &sanit, content_chunks[1], theme_map);
+        } else {
+            let diff_lines = generate_diff_lines(&orig, &sanit, theme_map);
+            let diff_para = Paragraph::new(diff_lines)
+                .block(Block::default()
+                    .title(" 🔍 Redaction Diff ")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded))
+                .wrap(Wrap { trim: false });
+            f.render_widget(diff_para, content_chunks[1]);
+        }
     } else if app.show_heatmap {
-        render_heatmap(f, app, content_chunks[1]);
+        render_heatmap(f, app, content_chunks[1], theme_map);
     }
 
     render_dashboard(f, app, vertical_chunks[2]);
@@ -93,9 +149,33 @@ pub fn draw(f: &mut Frame, app: &App, theme_map: &crate::ui::theme::ThemeMap) {
     if app.show_engine_menu {
         render_engine_dropdown(f, app);
     }
+    if app.detail.is_some() {
+        render_match_detail(f, app);
+    }
+    if let Some(error) = &app.error_popup {
+        render_error_popup(f, error);
+    }
+}
+
+/// The diff as two panes, original on the left. Lines are not wrapped, so the
+/// rows of the two panes stay aligned.
+fn render_diff_columns(f: &mut Frame, original: &str, sanitized: &str, area: Rect, theme_map: &ThemeMap) {
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    let (left, right) = generate_diff_columns(original, sanitized, theme_map);
+    for (lines, title, pane) in [(left, " 🔍 Original ", panes[0]), (right, " 🔍 Redacted ", panes[1])] {
+        let para = Paragraph::new(lines)
+            .block(Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded));
+        f.render_widget(para, pane);
+    }
 }
 
-fn render_heatmap(f: &mut Frame, app: &App, area: Rect) {
+fn render_heatmap(f: &mut Frame, app: &App, area: Rect, theme_map: &ThemeMap) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Min(3), Constraint::Length(3)])
@@ -103,13 +183,8 @@ fn render_heatmap(f: &mut Frame, app: &App, area: Rect) {
 
     let mut spans = Vec::new();
     if let Some(last_raw) = app.raw_input_buffer.back() {
-        for (i, c) in last_raw.chars().enumerate() {
-            let score = app.heat_map.get(i).unwrap_or(&0.0);
-            let color = if *score > 4.5 { Color::Red } 
-                        else if *score > 3.5 { Color::LightRed }
-                        else if *score > 2.5 { Color::Yellow }
-                        else { Color::DarkGray };
-            spans.push(Span::styled(c.to_string(), Style::default().fg(color)));
+        for (c, score) in heat_cells(&truncate_for_display(&last_raw.text), &app.heat_map) {
+            spans.push(Span::styled(c.to_string(), get_theme_style(heat_band(score, app.heat_bands), theme_map)));
         }
     }
     let heatmap_para = Paragraph::new(Line::from(spans))
@@ -130,6 +205,40 @@ fn render_heatmap(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(gauge, chunks[1]);
 }
 
+/// Theme entry for an entropy score: above `bands[2]` is critical, above `bands[1]`
+/// high, above `bands[0]` moderate, and anything else low.
+pub fn heat_band(score: f64, bands: [f64; 3]) -> ThemeEntry {
+    if score > bands[2] {
+        ThemeEntry::HeatmapCritical
+    } else if score > bands[1] {
+        ThemeEntry::HeatmapHigh
+    } else if score > bands[0] {
+        ThemeEntry::HeatmapModerate
+    } else {
+        ThemeEntry::HeatmapLow
+    }
+}
+
+/// Pairs each character of `raw` that is drawn (everything but ANSI escapes) with
+/// its heat. Scores are indexed by byte of `raw`, so each character takes the
+/// hottest score among the bytes it occupies there.
+pub fn heat_cells(raw: &str, heat: &[f64]) -> Vec<(char, f64)> {
+    let stripped_bytes = strip_ansi_escapes::strip(raw.as_bytes());
+    let stripped = String::from_utf8_lossy(&stripped_bytes);
+    let mapper = StrippedIndexMapper::new(raw);
+    stripped.char_indices()
+        .map(|(at, c)| {
+            let span = mapper.map_range(raw, at..at + c.len_utf8());
+            let score = heat.get(span.start.min(heat.len())..span.end.min(heat.len()))
+                .unwrap_or_default()
+                .iter()
+                .copied()
+                .fold(0.0, f64::max);
+            (c, score)
+        })
+        .collect()
+}
+
 fn render_engine_dropdown(f: &mut Frame, app: &App) {
     let area = centered_rect(40, 25, f.area());
     f.render_widget(Clear, area);
@@ -150,13 +259,147 @@ fn render_engine_dropdown(f: &mut Frame, app: &App) {
     f.render_stateful_widget(dropdown, area, &mut state);
 }
 
+fn render_error_popup(f: &mut Frame, error: &str) {
+    let area = centered_rect(50, 20, f.area());
+    f.render_widget(Clear, area);
+    let popup = Paragraph::new(error.to_string())
+        .wrap(Wrap { trim: true })
+        .block(Block::default()
+            .title(" Error ")
+            .title_bottom(Line::from(" [Any key] Dismiss ").right_aligned())
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .border_style(Style::default().fg(Color::Red).bg(Color::Black)));
+    f.render_widget(popup, area);
+}
+
+/// Match details, the providers the orchestrator would route it to, and the
+/// submenu used to pick one of them (or report-only) for the approval.
+fn render_match_detail(f: &mut Frame, app: &App) {
+    let (Some(detail), Some((m, status))) = (&app.detail, app.detail_match()) else { return };
+    let area = centered_rect(60, 60, f.area());
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .title(" Match Detail ")
+        .title_bottom(Line::from(" [↑/↓] Provider  [Enter] Approve & Close  [A/I] Approve/Ignore  [C] Copy Hash  [Y] Copy  [Esc] Close ").right_aligned())
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .border_style(Style::default().fg(Color::Cyan).bg(Color::Black));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let mut lines: Vec<Line> = detail_fields(m, status).into_iter().map(|(label, value)| {
+        let style = match label {
+            "Rule" => Style::default().fg(Color::Yellow),
+            "Hash" | "Seen" => Style::default().dim(),
+            _ => Style::default(),
+        };
+        Line::from(vec![Span::raw(format!("{:<10}", format!("{}:", label))), Span::styled(value, style)])
+    }).collect();
+    lines.push(Line::from(Span::styled(confidence_label(m.confidence), confidence_style(m.confidence))));
+    lines.push(Line::from(""));
+    match &detail.plans {
+        None => lines.push(Line::from(Span::styled("Routing:  remediation is not connected", Style::default().dim()))),
+        Some(plans) if plans.is_empty() => lines.push(Line::from(Span::styled("Routing:  no provider handles this match", Style::default().dim()))),
+        Some(plans) => {
+            let routed = plans.iter().map(|p| format!("{} (auto at {:?})", p.provider, p.auto_remediation_threshold)).collect::<Vec<_>>();
+            lines.push(Line::from(vec![Span::raw("Routing:  "), Span::styled(routed.join(" → "), Style::default().fg(Color::Green))]));
+        }
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(lines.len() as u16), Constraint::Min(3)])
+        .split(inner);
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), chunks[0]);
+
+    let items: Vec<ListItem> = detail.choices().into_iter().map(|choice| match choice {
+        ProviderChoice::Provider(name) => ListItem::new(format!(" Remediate with {}", name)),
+        ProviderChoice::ReportOnly | ProviderChoice::Auto => ListItem::new(" Report only (no remediation)"),
+    }).collect();
+    let submenu = List::new(items)
+        .block(Block::default().title(" Approve With ").borders(Borders::TOP))
+        .highlight_style(Style::default().bg(Color::Cyan).fg(Color::Black).bold())
+        .highlight_symbol(" ▶ ");
+    let mut state = detail.choice_state.clone();
+    f.render_stateful_widget(submenu, chunks[1], &mut state);
+}
+
+/// Dashboard text for a match's confidence, e.g. `CONFIDENCE: 0.85`.
+pub fn confidence_label(confidence: Option<f64>) -> String {
+    match confidence {
+        Some(c) => format!("CONFIDENCE: {:.2}", c),
+        None => "CONFIDENCE: n/a".to_string(),
+    }
+}
+
+/// One dashboard row for a journal entry: time, provider, rule and what happened.
+pub fn journal_line(entry: &JournalEntry) -> String {
+    let time = entry.timestamp.get(11..19).unwrap_or(&entry.timestamp);
+    let what = match &entry.outcome {
+        JournalOutcome::WouldRemediate => "DRY would remediate".to_string(),
+        JournalOutcome::WouldSkip { reason } => format!("DRY skip: {}", reason),
+        JournalOutcome::Remediated { .. } => "remediated".to_string(),
+        JournalOutcome::Failed { error } => format!("FAILED: {}", error),
+        JournalOutcome::Throttled { requeued: true } => "throttled, retrying".to_string(),
+        JournalOutcome::Throttled { requeued: false } => "throttled, dropped".to_string(),
+    };
+    format!("{} {} {} {}", time, entry.provider, entry.rule_name, what)
+}
+
+/// The Ubiquity panel's budget line: actions left per provider, and when an
+/// exhausted one frees a slot, e.g. `github 0/5 (42s), webhook 5/5`.
+pub fn governor_line(status: &[GovernorStatus]) -> String {
+    status.iter()
+        .map(|s| match s.resets_in {
+            Some(wait) if s.remaining == 0 => format!("{} 0/{} ({}s)", s.provider, s.max_actions, wait.as_millis().div_ceil(1000)),
+            _ => format!("{} {}/{}", s.provider, s.remaining, s.max_actions),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Colors a confidence by band: red from 1.0 (validated or keyword-backed), yellow
+/// from 0.7, dimmed below that or when unknown.
+pub fn confidence_style(confidence: Option<f64>) -> Style {
+    match confidence {
+        Some(c) if c >= 1.0 => Style::default().fg(Color::LightRed),
+        Some(c) if c >= 0.7 => Style::default().fg(Color::Yellow),
+        _ => Style::default().dim(),
+    }
+}
+
+/// Colors a match's severity tag: critical and high in red (critical bold), medium
+/// yellow, low cyan, info dimmed.
+pub fn severity_style(severity: Severity) -> Style {
+    match severity {
+        Severity::Critical => Style::default().fg(Color::LightRed).bold(),
+        Severity::High => Style::default().fg(Color::LightRed),
+        Severity::Medium => Style::default().fg(Color::Yellow),
+        Severity::Low => Style::default().fg(Color::Cyan),
+        Severity::Info => Style::default().dim(),
+    }
+}
+
+/// Panel title with the active filter and how many entries pass it, e.g.
+/// ` 📡 Live Stream [/timeout: 3 lines] `. While the prompt is open a cursor is shown.
+pub fn search_title(base: &str, app: &App, count: usize, noun: &str) -> String {
+    let cursor = if app.input_mode == InputMode::Search { "_" } else { "" };
+    if app.filter.is_empty() && cursor.is_empty() {
+        format!("{} ", base)
+    } else {
+        format!("{} [/{}{}: {} {}] ", base, app.filter, cursor, count, noun)
+    }
+}
+
 fn render_dashboard(f: &mut Frame, app: &App, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
         .split(area);
 
-    let match_items: Vec<ListItem> = app.matches.iter().enumerate().map(|(i, (m, status))| {
+    let visible = app.visible_match_indices();
+    let match_items: Vec<ListItem> = visible.iter().map(|&i| (i, &app.matches[i])).map(|(i, (m, status))| {
         let prefix = if app.match_list_state.selected() == Some(i) { ">" } else { " " };
         let (st, style) = match status {
             RemediationStatus::Pending => ("🔒 PENDING", Style::default().fg(Color::Yellow)),
@@ -171,37 +414,104 @@ fn render_dashboard(f: &mut Frame, app: &App, area: Rect) {
             Style::default()
         };
 
-        ListItem::new(Line::from(vec![
+        let severity = Severity::of_match(m);
+        let mut spans = vec![
             Span::styled(format!("{} {:<10} ", prefix, st), style),
+            Span::styled(format!("[{}] ", severity.as_str().to_ascii_uppercase()), severity_style(severity)),
             Span::raw(format!("Match: {}", m.rule_name)),
-            Span::styled(" (CONFIDENCE: 99%) ", Style::default().dim()),
-        ])).style(selection_style)
+            Span::styled(format!(" ({}) ", confidence_label(m.confidence)), confidence_style(m.confidence)),
+        ];
+        if let Some(remaining) = m.sample_hash.as_deref().and_then(|hash| app.undo_remaining(hash)) {
+            spans.push(Span::styled(
+                format!("(undoable for {}s)", remaining.as_millis().div_ceil(1000)),
+                Style::default().fg(Color::DarkGray).italic(),
+            ));
+        }
+
+        ListItem::new(Line::from(spans)).style(selection_style)
     }).collect();
 
     let remediation_list = List::new(match_items)
         .block(Block::default()
-            .title(" 🛡️ Self-Healing Dashboard ")
+            .title(if app.overflowed > 0 {
+                search_title(&format!(" 🛡️ Self-Healing Dashboard (overflowed: {})", app.overflowed), app, visible.len(), "matches")
+            } else {
+                search_title(" 🛡️ Self-Healing Dashboard", app, visible.len(), "matches")
+            })
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .border_style(Style::default().fg(if !app.matches.is_empty() { Color::LightRed } else { Color::Green })));
     
+    // The list only holds visible rows, so select by position among them.
     let mut state = app.match_list_state.clone();
+    state.select(app.match_list_state.selected().and_then(|selected| visible.iter().position(|&i| i == selected)));
     f.render_stateful_widget(remediation_list, chunks[0], &mut state);
 
     let stats = &app.sync_stats;
-    let sync_info = vec![
-        Line::from(vec![Span::raw("Status:   "), Span::styled(" ONLINE ●", Style::default().fg(Color::Green))]),
+    let status = match (stats.active, &stats.error) {
+        (true, _) => Span::styled(format!(" ONLINE ● {}ms", stats.last_sync_ms), Style::default().fg(Color::Green)),
+        (false, Some(_)) => Span::styled(" ERROR ●", Style::default().fg(Color::LightRed)),
+        (false, None) => Span::styled(" OFFLINE ○", Style::default().fg(Color::DarkGray)),
+    };
+    let mut sync_info = vec![
+        Line::from(vec![Span::raw("Status:   "), status]),
         Line::from(vec![Span::raw("Provider: "), Span::styled(&stats.provider, Style::default().fg(Color::Cyan))]),
         Line::from(vec![Span::raw("Engine:   "), Span::styled(format!("{:?}", app.current_engine), Style::default().fg(Color::Magenta))]),
         Line::from(vec![Span::raw("Cache:    "), Span::styled(format!("{} hashes", stats.total_hashes), Style::default().fg(Color::Yellow))]),
+        Line::from(vec![Span::raw("Rate:     "), Span::styled(format!("{:.1} lines/s", app.engine_metrics.lines_per_sec()), Style::default().fg(Color::White))]),
+        Line::from(vec![Span::raw("Matches:  "), Span::styled(app.engine_metrics.total_matches().to_string(), Style::default().fg(Color::LightRed))]),
     ];
+    let decoded = app.decode_metrics.snapshot();
+    if decoded.lines > 0 {
+        sync_info.push(Line::from(vec![
+            Span::raw("Invalid:  "),
+            Span::styled(format!("{} bytes in {} lines", decoded.replaced_bytes, decoded.lines), Style::default().fg(Color::Yellow)),
+        ]));
+    }
+    if let Some(error) = stats.error.as_ref().filter(|_| !stats.active) {
+        sync_info.push(Line::from(vec![Span::raw("Error:    "), Span::styled(error, Style::default().fg(Color::LightRed))]));
+    }
+    if !app.governor_status.is_empty() {
+        let exhausted = app.governor_status.iter().any(|s| s.remaining == 0);
+        sync_info.push(Line::from(vec![
+            Span::raw("Budget:   "),
+            Span::styled(governor_line(&app.governor_status), Style::default().fg(if exhausted { Color::LightRed } else { Color::Green })),
+        ]));
+    }
+    let sync_height = sync_info.len() as u16 + 2;
     let sync_panel = Paragraph::new(sync_info)
         .block(Block::default()
             .title(" 🌐 Ubiquity ")
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .style(Style::default().fg(Color::Cyan)));
-    f.render_widget(sync_panel, chunks[1]);
+    if app.journal_entries.is_empty() {
+        f.render_widget(sync_panel, chunks[1]);
+        return;
+    }
+
+    let side = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(sync_height), Constraint::Min(3)])
+        .split(chunks[1]);
+    f.render_widget(sync_panel, side[0]);
+
+    let journal_items: Vec<ListItem> = app.journal_entries.iter().rev().map(|entry| {
+        let color = match entry.outcome {
+            JournalOutcome::WouldRemediate | JournalOutcome::WouldSkip { .. } => Color::DarkGray,
+            JournalOutcome::Remediated { .. } => Color::Green,
+            JournalOutcome::Failed { .. } => Color::LightRed,
+            JournalOutcome::Throttled { .. } => Color::Yellow,
+        };
+        ListItem::new(journal_line(entry)).style(Style::default().fg(color))
+    }).collect();
+    let journal_panel = List::new(journal_items)
+        .block(Block::default()
+            .title(" 📓 Journal ")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .style(Style::default().fg(Color::Cyan)));
+    f.render_widget(journal_panel, side[1]);
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -3,74 +3,269 @@
 //!
 //! Generates Ratatui-compatible Spans and Lines to visualize redactions.
 
-use crate::ui::theme::{ThemeEntry, ThemeMap};
+use crate::ui::theme::{ThemeColor, ThemeEntry, ThemeMap, ThemeStyle};
 use ratatui::text::{Line, Span};
 use ratatui::style::{Style, Color, Modifier};
 use diffy::{create_patch, Line as DiffLine};
 
+/// Lines longer than this many tokens, multiplied together, are marked changed
+/// as a whole instead of being diffed token by token.
+const MAX_TOKEN_DIFF_CELLS: usize = 1_000_000;
+
+/// One row of a line diff: a line kept, or a removed line with the line that
+/// replaced it, either of which may be missing.
+enum DiffRow {
+    Context(String),
+    Changed(Option<String>, Option<String>),
+}
+
+/// Diffs `original` against `sanitized` line by line, pairing each run of removed
+/// lines with the run of added lines that follows it.
+fn diff_rows(original: &str, sanitized: &str) -> Vec<DiffRow> {
+    let patch = create_patch(original, sanitized);
+    let mut rows = Vec::new();
+    for hunk in patch.hunks() {
+        let mut deleted = Vec::new();
+        let mut inserted = Vec::new();
+        for line_change in hunk.lines() {
+            match line_change {
+                DiffLine::Delete(s) => deleted.push(trim_newline(s).to_string()),
+                DiffLine::Insert(s) => inserted.push(trim_newline(s).to_string()),
+                DiffLine::Context(s) => {
+                    pair_changes(&mut rows, &mut deleted, &mut inserted);
+                    rows.push(DiffRow::Context(trim_newline(s).to_string()));
+                }
+            }
+        }
+        pair_changes(&mut rows, &mut deleted, &mut inserted);
+    }
+    rows
+}
+
+fn pair_changes(rows: &mut Vec<DiffRow>, deleted: &mut Vec<String>, inserted: &mut Vec<String>) {
+    let len = deleted.len().max(inserted.len());
+    let mut deleted = deleted.drain(..);
+    let mut inserted = inserted.drain(..);
+    for _ in 0..len {
+        rows.push(DiffRow::Changed(deleted.next(), inserted.next()));
+    }
+}
+
+fn trim_newline(line: &str) -> &str {
+    line.strip_suffix('\n').map_or(line, |l| l.strip_suffix('\r').unwrap_or(l))
+}
+
+/// Splits a line into words (runs of alphanumerics and `_`) and single other
+/// characters, so a redacted token differs from its replacement as a unit.
+fn tokenize(line: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut word_start = None;
+    for (i, c) in line.char_indices() {
+        if c.is_alphanumeric() || c == '_' {
+            word_start.get_or_insert(i);
+            continue;
+        }
+        if let Some(start) = word_start.take() {
+            tokens.push(&line[start..i]);
+        }
+        tokens.push(&line[i..i + c.len_utf8()]);
+    }
+    if let Some(start) = word_start {
+        tokens.push(&line[start..]);
+    }
+    tokens
+}
+
+/// The tokens of `old` and `new`, each flagged as changed or shared, merged into
+/// runs: `(old_runs, new_runs)`.
+type TokenRuns = Vec<(bool, String)>;
+
+fn token_diff(old: &str, new: &str) -> (TokenRuns, TokenRuns) {
+    let a = tokenize(old);
+    let b = tokenize(new);
+    if a.len().saturating_mul(b.len()) > MAX_TOKEN_DIFF_CELLS {
+        return (vec![(true, old.to_string())], vec![(true, new.to_string())]);
+    }
+
+    // Longest common subsequence, suffix table: lcs[i][j] for a[i..] and b[j..].
+    let width = b.len() + 1;
+    let mut lcs = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i * width + j] = if a[i] == b[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut old_runs = Vec::new();
+    let mut new_runs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            push_run(&mut old_runs, false, a[i]);
+            push_run(&mut new_runs, false, b[j]);
+            i += 1;
+            j += 1;
+        } else if j == b.len() || (i < a.len() && lcs[(i + 1) * width + j] >= lcs[i * width + j + 1]) {
+            push_run(&mut old_runs, true, a[i]);
+            i += 1;
+        } else {
+            push_run(&mut new_runs, true, b[j]);
+            j += 1;
+        }
+    }
+    (old_runs, new_runs)
+}
+
+fn push_run(runs: &mut Vec<(bool, String)>, changed: bool, token: &str) {
+    match runs.last_mut() {
+        Some((last_changed, text)) if *last_changed == changed => text.push_str(token),
+        _ => runs.push((changed, token.to_string())),
+    }
+}
+
+/// Spans for `runs`, with only the changed runs in `changed_style`.
+fn run_spans(runs: TokenRuns, changed_style: Style) -> Vec<Span<'static>> {
+    runs.into_iter()
+        .map(|(changed, text)| if changed { Span::styled(text, changed_style) } else { Span::raw(text) })
+        .collect()
+}
+
+/// The removed and added halves of a changed row. A line with a partner is
+/// highlighted token by token; a line without one is highlighted whole.
+fn changed_spans(
+    old: Option<String>,
+    new: Option<String>,
+    theme_map: &ThemeMap,
+) -> (Option<Vec<Span<'static>>>, Option<Vec<Span<'static>>>) {
+    let removed = get_theme_style(ThemeEntry::DiffRemoved, theme_map);
+    let added = get_theme_style(ThemeEntry::DiffAdded, theme_map);
+    match (old, new) {
+        (Some(old), Some(new)) => {
+            let (old_runs, new_runs) = token_diff(&old, &new);
+            (Some(run_spans(old_runs, removed)), Some(run_spans(new_runs, added)))
+        }
+        (old, new) => (
+            old.map(|s| vec![Span::styled(s, removed)]),
+            new.map(|s| vec![Span::styled(s, added)]),
+        ),
+    }
+}
+
+fn prefixed(prefix: &'static str, style: Style, mut spans: Vec<Span<'static>>) -> Line<'static> {
+    spans.insert(0, Span::styled(prefix, style));
+    Line::from(spans)
+}
+
+fn header(text: &'static str) -> Line<'static> {
+    Line::from(Span::styled(text, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)))
+}
+
+const NO_CHANGES: &str = "No changes detected in this segment.";
+
 /// Generates a list of Lines for a Ratatui List or Paragraph widget.
-/// This highlights exactly what was removed (red) and what was added (green).
+/// This highlights exactly what was removed (red) and what was added (green):
+/// when a line was replaced, only the tokens that differ are styled, so a single
+/// redacted secret stands out in a long log line.
 pub fn generate_diff_lines<'a>(
     original: &'a str,
     sanitized: &'a str,
     theme_map: &ThemeMap,
 ) -> Vec<Line<'a>> {
-    let patch = create_patch(original, sanitized);
-    let mut lines = Vec::new();
-
-    // Add Header Line
-    lines.push(Line::from(Span::styled(
-        "--- Diff Analysis ---",
-        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
-    )));
+    let mut lines = vec![header("--- Diff Analysis ---")];
 
-    for hunk in patch.hunks() {
-        for line_change in hunk.lines() {
-            match line_change {
-                DiffLine::Delete(s) => {
-                    lines.push(Line::from(vec![
-                        Span::styled("- ", Style::default().fg(Color::Red)),
-                        Span::styled(s.to_string(), get_theme_style(ThemeEntry::DiffRemoved, theme_map)),
-                    ]));
+    for row in diff_rows(original, sanitized) {
+        match row {
+            DiffRow::Context(s) => lines.push(Line::from(vec![Span::raw("  "), Span::raw(s)])),
+            DiffRow::Changed(old, new) => {
+                let (old, new) = changed_spans(old, new, theme_map);
+                if let Some(spans) = old {
+                    lines.push(prefixed("- ", Style::default().fg(Color::Red), spans));
                 }
-                DiffLine::Insert(s) => {
-                    lines.push(Line::from(vec![
-                        Span::styled("+ ", Style::default().fg(Color::Green)),
-                        Span::styled(s.to_string(), get_theme_style(ThemeEntry::DiffAdded, theme_map)),
-                    ]));
-                }
-                DiffLine::Context(s) => {
-                    lines.push(Line::from(vec![
-                        Span::raw("  "),
-                        Span::raw(s.to_string()),
-                    ]));
+                if let Some(spans) = new {
+                    lines.push(prefixed("+ ", Style::default().fg(Color::Green), spans));
                 }
             }
         }
     }
 
     if lines.len() <= 1 {
-        lines.push(Line::from(Span::raw("No changes detected in this segment.")));
+        lines.push(Line::from(Span::raw(NO_CHANGES)));
     }
 
     lines
 }
 
+/// Generates the original (left) and sanitized (right) columns of a side-by-side
+/// diff. The columns always have the same length: row `i` of one lines up with
+/// row `i` of the other, with a blank line opposite a line that has no partner.
+pub fn generate_diff_columns<'a>(
+    original: &'a str,
+    sanitized: &'a str,
+    theme_map: &ThemeMap,
+) -> (Vec<Line<'a>>, Vec<Line<'a>>) {
+    let mut left = vec![header("--- Original ---")];
+    let mut right = vec![header("--- Redacted ---")];
+
+    for row in diff_rows(original, sanitized) {
+        match row {
+            DiffRow::Context(s) => {
+                right.push(Line::from(s.clone()));
+                left.push(Line::from(s));
+            }
+            DiffRow::Changed(old, new) => {
+                let (old, new) = changed_spans(old, new, theme_map);
+                left.push(old.map(Line::from).unwrap_or_default());
+                right.push(new.map(Line::from).unwrap_or_default());
+            }
+        }
+    }
+
+    if left.len() <= 1 {
+        left.push(Line::from(Span::raw(NO_CHANGES)));
+        right.push(Line::default());
+    }
+
+    (left, right)
+}
+
 /// Helper to map our ThemeMap entries to Ratatui Styles.
-fn get_theme_style(entry: ThemeEntry, theme_map: &ThemeMap) -> Style {
-    if let Some(theme_style) = theme_map.get(&entry) {
-        if let Some(color) = &theme_style.fg {
-            return Style::default().fg(color.to_ansi_color_ratatui());
+pub fn get_theme_style(entry: ThemeEntry, theme_map: &ThemeMap) -> Style {
+    theme_map.get(&entry).map_or_else(Style::default, ThemeStyle::to_ratatui_style)
+}
+
+/// Extension trait for ThemeStyle to support Ratatui types.
+impl ThemeStyle {
+    pub fn to_ratatui_style(&self) -> Style {
+        let mut style = Style::default();
+        if let Some(fg) = &self.fg {
+            style = style.fg(fg.to_ansi_color_ratatui());
+        }
+        if let Some(bg) = &self.bg {
+            style = style.bg(bg.to_ansi_color_ratatui());
+        }
+        for (name, modifier) in [
+            ("bold", Modifier::BOLD),
+            ("italic", Modifier::ITALIC),
+            ("underline", Modifier::UNDERLINED),
+            ("dim", Modifier::DIM),
+        ] {
+            if self.has_modifier(name) {
+                style = style.add_modifier(modifier);
+            }
         }
+        style
     }
-    Style::default()
 }
 
 /// Extension trait for ThemeColor to support Ratatui types.
-impl crate::ui::theme::ThemeColor {
+impl ThemeColor {
     pub fn to_ansi_color_ratatui(&self) -> Color {
         match self {
-            crate::ui::theme::ThemeColor::Named(name) => match name.as_str() {
+            ThemeColor::Named(name) => match name.as_str() {
                 "black" => Color::Black,
                 "red" => Color::Red,
                 "green" => Color::Green,
@@ -89,6 +284,75 @@ impl crate::ui::theme::ThemeColor {
                 "brightwhite" => Color::White,
                 _ => Color::Reset,
             },
+            ThemeColor::Indexed(index) => Color::Indexed(*index),
+            ThemeColor::Rgb(r, g, b) => Color::Rgb(*r, *g, *b),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGINAL: &str = "2024-05-01 INFO deploy started by ci token=ghp_abc123 region=eu-west-1\nunchanged tail\n";
+    const SANITIZED: &str = "2024-05-01 INFO deploy started by ci token=[GITHUB_TOKEN] region=eu-west-1\nunchanged tail\n";
+
+    /// `(text, styled)` for each span after the `- `/`+ ` prefix.
+    fn styled_segments(line: &Line<'_>, style: Style) -> Vec<(String, bool)> {
+        line.spans.iter().skip(1).map(|span| (span.content.to_string(), span.style == style)).collect()
+    }
+
+    #[test]
+    fn only_the_secret_is_styled_in_a_replaced_line() {
+        let theme = ThemeStyle::default_theme_map();
+        let lines = generate_diff_lines(ORIGINAL, SANITIZED, &theme);
+        let removed = lines.iter().find(|l| l.spans[0].content == "- ").unwrap();
+        let added = lines.iter().find(|l| l.spans[0].content == "+ ").unwrap();
+
+        let removed_style = get_theme_style(ThemeEntry::DiffRemoved, &theme);
+        assert_eq!(styled_segments(removed, removed_style), [
+            ("2024-05-01 INFO deploy started by ci token=".to_string(), false),
+            ("ghp_abc123".to_string(), true),
+            (" region=eu-west-1".to_string(), false),
+        ]);
+        let added_style = get_theme_style(ThemeEntry::DiffAdded, &theme);
+        assert_eq!(styled_segments(added, added_style), [
+            ("2024-05-01 INFO deploy started by ci token=".to_string(), false),
+            ("[GITHUB_TOKEN]".to_string(), true),
+            (" region=eu-west-1".to_string(), false),
+        ]);
+        assert!(lines.iter().any(|l| l.to_string() == "  unchanged tail"));
+    }
+
+    #[test]
+    fn unpaired_lines_are_styled_whole() {
+        let theme = ThemeStyle::default_theme_map();
+        let lines = generate_diff_lines("a\n", "a\nadded line\n", &theme);
+        let added = lines.iter().find(|l| l.spans[0].content == "+ ").unwrap();
+        let added_style = get_theme_style(ThemeEntry::DiffAdded, &theme);
+        assert_eq!(styled_segments(added, added_style), [("added line".to_string(), true)]);
+    }
+
+    #[test]
+    fn columns_stay_aligned() {
+        let theme = ThemeStyle::default_theme_map();
+        let (left, right) = generate_diff_columns("keep\nsecret=abc\ngone\n", "keep\nsecret=[REDACTED]\n", &theme);
+        assert_eq!(left.len(), right.len());
+        let text = |lines: &[Line<'_>]| lines.iter().skip(1).map(|l| l.to_string()).collect::<Vec<_>>();
+        assert_eq!(text(&left), ["keep", "secret=abc", "gone"]);
+        assert_eq!(text(&right), ["keep", "secret=[REDACTED]", ""]);
+
+        let removed_style = get_theme_style(ThemeEntry::DiffRemoved, &theme);
+        let styled: Vec<_> = left[2].spans.iter().filter(|s| s.style == removed_style).map(|s| s.content.to_string()).collect();
+        assert_eq!(styled, ["abc"]);
+    }
+
+    #[test]
+    fn identical_input_reports_no_changes() {
+        let theme = ThemeStyle::default_theme_map();
+        let lines = generate_diff_lines("same\n", "same\n", &theme);
+        assert_eq!(lines.last().unwrap().to_string(), NO_CHANGES);
+        let (left, right) = generate_diff_columns("same\n", "same\n", &theme);
+        assert_eq!(left.len(), right.len());
+    }
+}
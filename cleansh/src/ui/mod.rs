@@ -4,4 +4,10 @@
 /// Theme types and loading logic.
 pub mod theme;
 
-pub mod diff_viewer;
\ No newline at end of file
+pub mod diff_viewer;
+
+/// CI-oriented renderings (GitHub annotations, GitLab Code Quality) of scan findings.
+pub mod output_format;
+
+/// Per-rule redaction summaries (text, JSON, CSV) for `cleansh sanitize`.
+pub mod redaction_summary;
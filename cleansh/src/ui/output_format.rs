@@ -0,0 +1,380 @@
+//! Machine-readable renderings of scan findings for CI systems.
+//!
+//! Every formatter consumes the same [`Finding`] intermediate, built once per
+//! [`RedactionMatch`], so line numbers, masking, and fingerprints stay
+//! consistent no matter which output the pipeline asks for. Raw secret
+//! values never reach the rendered output.
+//!
+//! [`ColorChoice`] decides, for every human-readable writer alike, whether
+//! that output is colored.
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::io::IsTerminal;
+
+use cleansh_core::redaction_match::{canonical_sample_hash, offset_from_index};
+use cleansh_core::{RedactionMatch, Severity, redact_sensitive};
+
+/// GitHub only renders the first ten error annotations of a step; the rest are summarized.
+pub const GITHUB_ANNOTATION_LIMIT: usize = 10;
+
+/// The output formats supported by `cleansh scan`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable `path:line:column` lines.
+    Text,
+    /// GitHub Actions `::error` workflow commands.
+    Github,
+    /// GitLab Code Quality JSON report.
+    GitlabCodequality,
+    /// Versioned JSON report with exact spans, for `cleansh diff-report`.
+    Json,
+}
+
+/// When to color human-readable output, set by the global `--color` flag.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// Color when `NO_COLOR` is unset and either `CLICOLOR_FORCE` is set or the
+    /// output is a terminal.
+    #[default]
+    Auto,
+    /// Always color, e.g. when piping into `less -R`.
+    Always,
+    /// Never color.
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether output written to `stream` is colored.
+    pub fn enabled_for(self, stream: &impl IsTerminal) -> bool {
+        self.resolve(|name| std::env::var_os(name), stream.is_terminal())
+    }
+
+    /// The decision behind [`enabled_for`](Self::enabled_for), with the environment
+    /// read through `env`: an explicit `always` or `never` wins, then a non-empty
+    /// `NO_COLOR`, then a `CLICOLOR_FORCE` other than empty or `0`, then `is_terminal`.
+    pub fn resolve(self, env: impl Fn(&str) -> Option<OsString>, is_terminal: bool) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto if env("NO_COLOR").is_some_and(|v| !v.is_empty()) => false,
+            Self::Auto if env("CLICOLOR_FORCE").is_some_and(|v| !v.is_empty() && v != "0") => true,
+            Self::Auto => is_terminal,
+        }
+    }
+}
+
+/// Prints `warning: <message>` to stderr, with the prefix in bold yellow when
+/// `color` resolves to colored stderr.
+pub fn print_warning(color: ColorChoice, message: impl Display) {
+    if color.enabled_for(&std::io::stderr()) {
+        eprintln!("{} {}", "warning:".yellow().bold(), message);
+    } else {
+        eprintln!("warning: {}", message);
+    }
+}
+
+/// Schema version of the `json` report.
+pub const REPORT_VERSION: u32 = 1;
+
+/// A single finding, decoupled from the engine's match representation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    pub rule_name: String,
+    pub path: String,
+    /// Byte offsets of the match within the scanned content.
+    pub start: u64,
+    pub end: u64,
+    /// 1-based line of the match start.
+    pub line: u64,
+    /// 1-based byte column of the match start.
+    pub column: u64,
+    pub severity: Option<String>,
+    /// `severity` as an ordered level, falling back to the match's confidence.
+    #[serde(skip)]
+    pub level: Severity,
+    /// Stable digest of the secret; never the secret itself.
+    pub sample_hash: String,
+    /// A masked description of the match, safe to print in CI logs.
+    pub message: String,
+    /// Text around the match with other secrets redacted, with `--capture-context`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_before: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_after: Option<String>,
+}
+
+impl Finding {
+    /// Builds a finding from an engine match over `content`, the text that was scanned.
+    pub fn from_match(m: &RedactionMatch, path: &str, content: &str) -> Self {
+        let span = m.document_span();
+        let offset = span.start.min(content.len());
+        let preceding = &content.as_bytes()[..offset];
+        let line = m.line_number.unwrap_or_else(|| {
+            preceding.iter().filter(|&&b| b == b'\n').count() as u64 + 1
+        });
+        let line_start = preceding.iter().rposition(|&b| b == b'\n').map_or(0, |p| p + 1);
+
+        let sample_hash = m
+            .sample_hash
+            .clone()
+            .unwrap_or_else(|| canonical_sample_hash(&m.rule_name, &m.original_string));
+
+        Self {
+            rule_name: m.rule_name.clone(),
+            path: path.to_string(),
+            start: offset_from_index(span.start),
+            end: offset_from_index(span.end),
+            line,
+            column: offset_from_index(offset - line_start) + 1,
+            severity: m.rule.severity.clone(),
+            level: Severity::of_match(m),
+            sample_hash,
+            message: format!("Possible {} found: {}", m.rule_name, redact_sensitive(&m.original_string)),
+            context_before: m.context_before.clone(),
+            context_after: m.context_after.clone(),
+        }
+    }
+}
+
+/// Renders findings in the requested format.
+pub fn render(format: OutputFormat, findings: &[Finding]) -> Result<String> {
+    match format {
+        OutputFormat::Text => Ok(format_text(findings)),
+        OutputFormat::Github => Ok(format_github(findings, GITHUB_ANNOTATION_LIMIT)),
+        OutputFormat::GitlabCodequality => format_gitlab_codequality(findings),
+        OutputFormat::Json => format_json(findings),
+    }
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    version: u32,
+    findings: &'a [Finding],
+}
+
+/// Emits `{"version": 1, "findings": [...]}` with one entry per finding.
+pub fn format_json(findings: &[Finding]) -> Result<String> {
+    serde_json::to_string_pretty(&JsonReport { version: REPORT_VERSION, findings })
+        .context("Failed to serialize JSON report")
+}
+
+/// One `path:line:column: rule: message` line per finding.
+pub fn format_text(findings: &[Finding]) -> String {
+    findings
+        .iter()
+        .map(|f| format!("{}:{}:{}: {}: {}\n", f.path, f.line, f.column, f.rule_name, f.message))
+        .collect()
+}
+
+/// Emits up to `limit` `::error` workflow commands, followed by a single
+/// `::warning` summarizing anything that did not fit.
+pub fn format_github(findings: &[Finding], limit: usize) -> String {
+    let mut out = String::new();
+    for f in findings.iter().take(limit) {
+        out.push_str(&format!(
+            "::error file={},line={},col={},title={}::{}\n",
+            escape_github_property(&f.path),
+            f.line,
+            f.column,
+            escape_github_property(&f.rule_name),
+            escape_github_data(&f.message),
+        ));
+    }
+    if findings.len() > limit {
+        out.push_str(&format!(
+            "::warning title=cleansh::{} more finding(s) not annotated ({} total)\n",
+            findings.len() - limit,
+            findings.len(),
+        ));
+    }
+    out
+}
+
+#[derive(Serialize)]
+struct CodeQualityIssue<'a> {
+    description: &'a str,
+    check_name: &'a str,
+    fingerprint: String,
+    severity: &'static str,
+    location: CodeQualityLocation<'a>,
+}
+
+#[derive(Serialize)]
+struct CodeQualityLocation<'a> {
+    path: &'a str,
+    lines: CodeQualityLines,
+}
+
+#[derive(Serialize)]
+struct CodeQualityLines {
+    begin: u64,
+}
+
+/// Emits a GitLab Code Quality report.
+///
+/// Fingerprints hash the rule, path, and `sample_hash` together with the
+/// occurrence index of that secret within the file, so re-running on the same
+/// tree reproduces them even when unrelated lines move.
+pub fn format_gitlab_codequality(findings: &[Finding]) -> Result<String> {
+    let mut seen: HashMap<(&str, &str, &str), usize> = HashMap::new();
+    let issues: Vec<CodeQualityIssue> = findings
+        .iter()
+        .map(|f| {
+            let occurrence = seen
+                .entry((f.rule_name.as_str(), f.path.as_str(), f.sample_hash.as_str()))
+                .or_insert(0);
+            let mut hasher = Sha256::new();
+            hasher.update(f.rule_name.as_bytes());
+            hasher.update(b"\0");
+            hasher.update(f.path.as_bytes());
+            hasher.update(b"\0");
+            hasher.update(f.sample_hash.as_bytes());
+            hasher.update(b"\0");
+            hasher.update(occurrence.to_string().as_bytes());
+            *occurrence += 1;
+
+            CodeQualityIssue {
+                description: &f.message,
+                check_name: &f.rule_name,
+                fingerprint: hex::encode(hasher.finalize()),
+                severity: codequality_severity(f.level),
+                location: CodeQualityLocation { path: &f.path, lines: CodeQualityLines { begin: f.line } },
+            }
+        })
+        .collect();
+    serde_json::to_string_pretty(&issues).context("Failed to serialize Code Quality report")
+}
+
+/// Maps rule severities onto GitLab's fixed severity vocabulary.
+fn codequality_severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "info",
+        Severity::Low => "minor",
+        Severity::Medium => "major",
+        Severity::High => "critical",
+        Severity::Critical => "blocker",
+    }
+}
+
+/// Escapes workflow command message data.
+fn escape_github_data(s: &str) -> String {
+    s.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Escapes workflow command property values, which additionally reserve `:` and `,`.
+fn escape_github_property(s: &str) -> String {
+    escape_github_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cleansh_core::config::{RedactionConfig, RedactionRule};
+    use cleansh_core::{RegexEngine, SanitizationEngine};
+
+    fn api_key_engine() -> RegexEngine {
+        let config = RedactionConfig {
+            rules: vec![RedactionRule {
+                name: "api_key".to_string(),
+                pattern: Some(r"sk_[a-z0-9]{12}".to_string()),
+                pattern_type: "regex".to_string(),
+                replace_with: "[API_KEY]".to_string(),
+                severity: Some("high".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        RegexEngine::new(config).expect("engine builds")
+    }
+
+    fn findings_for(path: &str, content: &str) -> Vec<Finding> {
+        let engine = api_key_engine();
+        engine
+            .find_matches_for_ui(content, path)
+            .expect("scan succeeds")
+            .iter()
+            .map(|m| Finding::from_match(m, path, content))
+            .collect()
+    }
+
+    #[test]
+    fn test_color_choice_precedence() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| OsString::from(v))
+        };
+        assert!(!ColorChoice::Auto.resolve(env(&[]), false));
+        assert!(ColorChoice::Auto.resolve(env(&[]), true));
+        assert!(!ColorChoice::Auto.resolve(env(&[("NO_COLOR", "1")]), true));
+        assert!(ColorChoice::Auto.resolve(env(&[("NO_COLOR", "")]), true));
+        assert!(!ColorChoice::Auto.resolve(env(&[("NO_COLOR", "1"), ("CLICOLOR_FORCE", "1")]), true));
+        assert!(ColorChoice::Auto.resolve(env(&[("CLICOLOR_FORCE", "1")]), false));
+        assert!(!ColorChoice::Auto.resolve(env(&[("CLICOLOR_FORCE", "0")]), false));
+        assert!(ColorChoice::Always.resolve(env(&[("NO_COLOR", "1")]), false));
+        assert!(!ColorChoice::Never.resolve(env(&[("CLICOLOR_FORCE", "1")]), true));
+    }
+
+    #[test]
+    fn test_finding_positions_and_masking() {
+        let findings = findings_for("app.env", "# config\nKEY=sk_abcdef123456\n");
+        assert_eq!(findings.len(), 1);
+        assert_eq!((findings[0].line, findings[0].column), (2, 5));
+        assert!(!findings[0].message.contains("sk_abcdef123456"));
+    }
+
+    #[test]
+    fn test_github_escapes_special_characters_in_paths() {
+        let findings = findings_for("dir,odd:name/100%\nfile.txt", "sk_abcdef123456");
+        let out = format_github(&findings, GITHUB_ANNOTATION_LIMIT);
+        assert_eq!(
+            out,
+            "::error file=dir%2Codd%3Aname/100%25%0Afile.txt,line=1,col=1,title=api_key::Possible api_key found: [REDACTED: 15 chars]\n"
+        );
+    }
+
+    #[test]
+    fn test_github_caps_annotations_and_summarizes_remainder() {
+        let content = "sk_aaaaaaaaaaa1\nsk_aaaaaaaaaaa2\nsk_aaaaaaaaaaa3\n";
+        let out = format_github(&findings_for("a.txt", content), 2);
+        assert_eq!(out.matches("::error ").count(), 2);
+        assert!(out.ends_with("::warning title=cleansh::1 more finding(s) not annotated (3 total)\n"));
+    }
+
+    #[test]
+    fn test_json_report_carries_exact_spans() -> Result<()> {
+        let report: serde_json::Value = serde_json::from_str(&format_json(&findings_for("app.env", "KEY=sk_abcdef123456\n"))?)?;
+        assert_eq!(report["version"], REPORT_VERSION);
+        assert_eq!((report["findings"][0]["start"].as_u64(), report["findings"][0]["end"].as_u64()), (Some(4), Some(19)));
+        assert!(!report.to_string().contains("sk_abcdef123456"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_codequality_fingerprints_are_stable_across_runs() -> Result<()> {
+        let content = "a=sk_abcdef123456\nb=sk_abcdef123456\nc=sk_zzzzzz999999\n";
+        let first: serde_json::Value = serde_json::from_str(&format_gitlab_codequality(&findings_for("src/a.rs", content))?)?;
+        let second: serde_json::Value = serde_json::from_str(&format_gitlab_codequality(&findings_for("src/a.rs", content))?)?;
+        assert_eq!(first, second);
+
+        let fingerprints: Vec<&str> = first.as_array().unwrap().iter()
+            .map(|issue| issue["fingerprint"].as_str().unwrap())
+            .collect();
+        assert_eq!(fingerprints.len(), 3);
+        assert_ne!(fingerprints[0], fingerprints[1], "repeated secrets still get distinct fingerprints");
+        assert_eq!(first[0]["severity"], "critical");
+        assert_eq!(first[0]["location"]["lines"]["begin"], 1);
+
+        // Shifting the file down does not change the fingerprints.
+        let shifted = format!("\n\n{}", content);
+        let moved: serde_json::Value = serde_json::from_str(&format_gitlab_codequality(&findings_for("src/a.rs", &shifted))?)?;
+        assert_eq!(moved[0]["fingerprint"], first[0]["fingerprint"]);
+        assert_eq!(moved[0]["location"]["lines"]["begin"], 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,220 @@
+// cleansh/src/ui/redaction_summary.rs
+//! The per-rule redaction summary printed by `cleansh sanitize`, as a text table
+//! for people or as JSON/CSV for CI.
+//!
+//! Only sanitized samples are ever written; `original_texts` stays in memory.
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use comfy_table::{Attribute, Cell, Color, Table};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use cleansh_core::{RedactionSummaryItem, Severity};
+use crate::ui::output_format::ColorChoice;
+
+/// Output formats for the redaction summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SummaryFormat {
+    /// A table of rules and occurrence counts.
+    Text,
+    /// A JSON array with one object per rule.
+    Json,
+    /// `rule_name,severity,occurrences[,sanitized_samples]` with a header row.
+    Csv,
+}
+
+/// One rule's line of the summary.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SummaryRow {
+    pub rule_name: String,
+    pub severity: Severity,
+    pub occurrences: usize,
+    /// Distinct replacement texts, in order of first appearance.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sanitized_samples: Option<Vec<String>>,
+}
+
+/// One row per rule, sorted by name; samples are included when `with_samples` is set.
+pub fn summary_rows(summary: &[RedactionSummaryItem], with_samples: bool) -> Vec<SummaryRow> {
+    let mut rows: Vec<SummaryRow> = summary
+        .iter()
+        .map(|item| SummaryRow {
+            rule_name: item.rule_name.clone(),
+            severity: item.severity,
+            occurrences: item.occurrences,
+            sanitized_samples: with_samples.then(|| {
+                let mut samples: Vec<String> = Vec::new();
+                for text in &item.sanitized_texts {
+                    if !samples.contains(text) {
+                        samples.push(text.clone());
+                    }
+                }
+                samples
+            }),
+        })
+        .collect();
+    rows.sort_by(|a, b| a.rule_name.cmp(&b.rule_name));
+    rows
+}
+
+/// The severity cell, colored like the TUI dashboard's severity tags when `color` is set.
+fn severity_cell(severity: Severity, color: bool) -> Cell {
+    let cell = Cell::new(severity);
+    if !color {
+        return cell;
+    }
+    match severity {
+        Severity::Critical => cell.fg(Color::Red).add_attribute(Attribute::Bold),
+        Severity::High => cell.fg(Color::Red),
+        Severity::Medium => cell.fg(Color::Yellow),
+        Severity::Low => cell.fg(Color::Cyan),
+        Severity::Info => cell.add_attribute(Attribute::Dim),
+    }
+}
+
+fn render_text(rows: &[SummaryRow], with_samples: bool, color: bool) -> String {
+    if rows.is_empty() {
+        return "No redactions.\n".to_string();
+    }
+    let mut table = Table::new();
+    if color {
+        table.enforce_styling();
+    } else {
+        table.force_no_tty();
+    }
+    let mut header = vec!["Rule", "Severity", "Occurrences"];
+    if with_samples {
+        header.push("Samples");
+    }
+    table.set_header(header);
+    for row in rows {
+        let mut cells = vec![Cell::new(&row.rule_name), severity_cell(row.severity, color), Cell::new(row.occurrences)];
+        if let Some(samples) = &row.sanitized_samples {
+            cells.push(Cell::new(samples.join("\n")));
+        }
+        table.add_row(cells);
+    }
+    format!("{}\n", table)
+}
+
+fn render_csv(rows: &[SummaryRow], with_samples: bool) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let mut header = vec!["rule_name", "severity", "occurrences"];
+    if with_samples {
+        header.push("sanitized_samples");
+    }
+    writer.write_record(&header)?;
+    for row in rows {
+        let mut record = vec![row.rule_name.clone(), row.severity.to_string(), row.occurrences.to_string()];
+        if let Some(samples) = &row.sanitized_samples {
+            // Several samples share one field, one per line; the writer quotes it.
+            record.push(samples.join("\n"));
+        }
+        writer.write_record(&record)?;
+    }
+    let bytes = writer.into_inner().context("Failed to flush CSV summary")?;
+    String::from_utf8(bytes).context("CSV summary is not valid UTF-8")
+}
+
+/// Renders the summary in `format`. `color` only affects the text table.
+pub fn render_summary(summary: &[RedactionSummaryItem], format: SummaryFormat, with_samples: bool, color: bool) -> Result<String> {
+    let rows = summary_rows(summary, with_samples);
+    match format {
+        SummaryFormat::Text => Ok(render_text(&rows, with_samples, color)),
+        SummaryFormat::Json => {
+            let json = serde_json::to_string_pretty(&rows).context("Failed to serialize summary")?;
+            Ok(format!("{}\n", json))
+        }
+        SummaryFormat::Csv => render_csv(&rows, with_samples),
+    }
+}
+
+/// Writes the summary to `output`, or to stderr so it never mixes with sanitized
+/// stdout. Only the stderr summary is colored, as `color` resolves for stderr.
+pub fn write_summary(
+    summary: &[RedactionSummaryItem],
+    format: SummaryFormat,
+    with_samples: bool,
+    output: Option<&Path>,
+    color: ColorChoice,
+) -> Result<()> {
+    match output {
+        Some(path) => std::fs::write(path, render_summary(summary, format, with_samples, false)?)
+            .with_context(|| format!("Failed to write summary to '{}'", path.display())),
+        None => {
+            eprint!("{}", render_summary(summary, format, with_samples, color.enabled_for(&std::io::stderr()))?);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cleansh_core::config::merge_summary;
+    use cleansh_core::profiles::EngineOptions;
+
+    fn item(rule_name: &str, sanitized: &[&str]) -> RedactionSummaryItem {
+        RedactionSummaryItem {
+            rule_name: rule_name.to_string(),
+            occurrences: sanitized.len(),
+            original_texts: sanitized.iter().map(|_| "secret".to_string()).collect(),
+            sanitized_texts: sanitized.iter().map(|s| s.to_string()).collect(),
+            truncated: false,
+            deduplicated: 0,
+            severity: Severity::Medium,
+        }
+    }
+
+    #[test]
+    fn test_csv_round_trips_fields_with_commas_quotes_and_newlines() -> Result<()> {
+        let mut summary = vec![item("email", &["[EMAIL, REDACTED]", "[EMAIL, REDACTED]"])];
+        merge_summary(&mut summary, vec![item("email", &["say \"hi\""]), item("aws_key", &["line1\nline2"])], &EngineOptions::default());
+
+        let csv_text = render_summary(&summary, SummaryFormat::Csv, true, false)?;
+        let mut reader = csv::Reader::from_reader(csv_text.as_bytes());
+        assert_eq!(reader.headers()?, vec!["rule_name", "severity", "occurrences", "sanitized_samples"]);
+        let records: Vec<csv::StringRecord> = reader.records().collect::<Result<_, _>>()?;
+        let parsed: Vec<(&str, &str, usize, &str)> = records
+            .iter()
+            .map(|r| (&r[0], &r[1], r[2].parse().unwrap(), &r[3]))
+            .collect();
+        assert_eq!(parsed, [
+            ("aws_key", "medium", 1, "line1\nline2"),
+            ("email", "medium", 3, "[EMAIL, REDACTED]\nsay \"hi\""),
+        ]);
+        assert!(!csv_text.contains("secret"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_samples_are_omitted_unless_requested() -> Result<()> {
+        let summary = vec![item("email", &["[EMAIL]"])];
+        let csv_text = render_summary(&summary, SummaryFormat::Csv, false, false)?;
+        assert_eq!(csv_text, "rule_name,severity,occurrences\nemail,medium,1\n");
+
+        let json: serde_json::Value = serde_json::from_str(&render_summary(&summary, SummaryFormat::Json, false, false)?)?;
+        assert_eq!(json, serde_json::json!([{ "rule_name": "email", "severity": "medium", "occurrences": 1 }]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_text_table_is_colored_only_on_request() -> Result<()> {
+        let summary = vec![item("email", &["[EMAIL]"])];
+        assert!(!render_summary(&summary, SummaryFormat::Text, false, false)?.contains('\x1b'));
+        assert!(render_summary(&summary, SummaryFormat::Text, false, true)?.contains("\x1b["));
+        assert!(!render_summary(&summary, SummaryFormat::Csv, false, true)?.contains('\x1b'));
+        Ok(())
+    }
+
+    #[test]
+    fn test_merged_rule_keeps_its_highest_severity() {
+        let mut summary = vec![item("token", &["[T]"])];
+        let mut critical = item("token", &["[T]"]);
+        critical.severity = Severity::Critical;
+        merge_summary(&mut summary, vec![critical, item("token", &["[T]"])], &EngineOptions::default());
+        assert_eq!(summary.len(), 1);
+        assert_eq!(summary[0].severity, Severity::Critical);
+    }
+}
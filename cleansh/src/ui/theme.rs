@@ -1,17 +1,20 @@
 //! Module for managing the application's command-line interface (CLI) theme.
 //!
 //! This module defines the structure for theme configuration, allowing users
-//! to customize the colors of various output elements. It supports 16-color
-//! ANSI named colors for foreground styling and provides functionality to
-//! load themes from YAML files and manage default theme settings.
+//! to customize the colors of various output elements. Each entry takes a
+//! foreground and background color (a 16-color ANSI name, a 256-color index such
+//! as `"123"`, or `"#ff8800"`) and modifiers (`bold`, `italic`, `underline`,
+//! `dim`). It provides functionality to load themes from YAML files and manage
+//! default theme settings.
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 use std::fmt;
 use std::path::{Path, PathBuf}; // Added PathBuf
 use std::str::FromStr;
-use anyhow::{Context, Result};
-use owo_colors::AnsiColors;
+use anyhow::{bail, Context, Result};
+use owo_colors::{AnsiColors, DynColors, XtermColors};
+use cleansh_core::ConfigFormat;
 
 /// Type alias for the theme map, providing a consistent type definition.
 pub type ThemeMap = HashMap<ThemeEntry, ThemeStyle>;
@@ -58,25 +61,52 @@ pub enum ThemeEntry {
 }
 
 /// Represents an ANSI color that can be used in the theme.
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-#[serde(untagged)]
+///
+/// Written as a color name, a 256-color index (`123` or `"123"`), or a `#rrggbb`
+/// hex string.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ThemeColor {
     /// A named ANSI color (e.g., "red", "brightgreen").
     Named(String),
+    /// An entry of the 256-color palette.
+    Indexed(u8),
+    /// A 24-bit color.
+    Rgb(u8, u8, u8),
 }
 
+/// The 16 ANSI color names with their xterm RGB values, in palette order.
+const ANSI_PALETTE: [(&str, (u8, u8, u8)); 16] = [
+    ("black", (0, 0, 0)),
+    ("red", (205, 0, 0)),
+    ("green", (0, 205, 0)),
+    ("yellow", (205, 205, 0)),
+    ("blue", (0, 0, 238)),
+    ("magenta", (205, 0, 205)),
+    ("cyan", (0, 205, 205)),
+    ("white", (229, 229, 229)),
+    ("brightblack", (127, 127, 127)),
+    ("brightred", (255, 0, 0)),
+    ("brightgreen", (0, 255, 0)),
+    ("brightyellow", (255, 255, 0)),
+    ("brightblue", (92, 92, 255)),
+    ("brightmagenta", (255, 0, 255)),
+    ("brightcyan", (0, 255, 255)),
+    ("brightwhite", (255, 255, 255)),
+];
+
+/// Modifiers a `ThemeStyle` may list.
+pub const THEME_MODIFIERS: [&str; 4] = ["bold", "italic", "underline", "dim"];
+
 /// Error type for parsing an invalid `ThemeColor` string.
 #[derive(Debug, Clone)]
-pub struct ParseThemeColorError;
+pub struct ParseThemeColorError {
+    input: String,
+    reason: &'static str,
+}
 
 impl fmt::Display for ParseThemeColorError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "Invalid theme color; expected one of: black, red, green, yellow, blue, \
-            magenta, cyan, white, brightblack, brightred, brightgreen, brightyellow, \
-            brightblue, brightmagenta, brightcyan, brightwhite."
-        )
+        write!(f, "Invalid theme color '{}': {}.", self.input, self.reason)
     }
 }
 
@@ -87,39 +117,131 @@ impl FromStr for ThemeColor {
 
     /// Attempts to parse a string into a `ThemeColor`.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lower = s.to_lowercase();
-        match lower.as_str() {
-            "black" | "red" | "green" | "yellow" | "blue" | "magenta" | "cyan" | "white" |
-            "brightblack" | "brightred" | "brightgreen" | "brightyellow" | "brightblue" | 
-            "brightmagenta" | "brightcyan" | "brightwhite" => Ok(ThemeColor::Named(lower)),
-            _ => Err(ParseThemeColorError),
+        let error = |reason| ParseThemeColorError { input: s.to_string(), reason };
+        let lower = s.trim().to_lowercase();
+        if let Some(hex) = lower.strip_prefix('#') {
+            let channel = |i: usize| hex.get(i..i + 2).and_then(|c| u8::from_str_radix(c, 16).ok());
+            return match (hex.len(), channel(0), channel(2), channel(4)) {
+                (6, Some(r), Some(g), Some(b)) => Ok(ThemeColor::Rgb(r, g, b)),
+                _ => Err(error("expected a hex color of the form #rrggbb")),
+            };
+        }
+        if !lower.is_empty() && lower.bytes().all(|b| b.is_ascii_digit()) {
+            return lower.parse().map(ThemeColor::Indexed).map_err(|_| error("a 256-color index must be 0-255"));
+        }
+        if ANSI_PALETTE.iter().any(|(name, _)| *name == lower) {
+            return Ok(ThemeColor::Named(lower));
+        }
+        Err(error(
+            "expected one of: black, red, green, yellow, blue, magenta, cyan, white, \
+            brightblack, brightred, brightgreen, brightyellow, brightblue, brightmagenta, \
+            brightcyan, brightwhite; a 256-color index (0-255); or #rrggbb",
+        ))
+    }
+}
+
+impl fmt::Display for ThemeColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeColor::Named(name) => f.write_str(name),
+            ThemeColor::Indexed(index) => write!(f, "{}", index),
+            ThemeColor::Rgb(r, g, b) => write!(f, "#{:02x}{:02x}{:02x}", r, g, b),
+        }
+    }
+}
+
+impl Serialize for ThemeColor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ThemeColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // YAML and TOML read a bare `123` as a number.
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum ThemeColorValue {
+            Index(u64),
+            Text(String),
         }
+        let text = match ThemeColorValue::deserialize(deserializer)? {
+            ThemeColorValue::Index(index) => index.to_string(),
+            ThemeColorValue::Text(text) => text,
+        };
+        text.parse().map_err(serde::de::Error::custom)
     }
 }
 
 impl ThemeColor {
+    /// The color as RGB. Indexed colors use the xterm palette.
+    pub fn to_rgb(&self) -> (u8, u8, u8) {
+        match self {
+            ThemeColor::Named(name) => ANSI_PALETTE.iter()
+                .find(|(n, _)| n == name)
+                .map_or((229, 229, 229), |(_, rgb)| *rgb),
+            ThemeColor::Indexed(index @ 0..=15) => ANSI_PALETTE[*index as usize].1,
+            ThemeColor::Indexed(index @ 16..=231) => {
+                let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+                let i = index - 16;
+                (level(i / 36), level(i / 6 % 6), level(i % 6))
+            }
+            ThemeColor::Indexed(index) => {
+                let gray = 8 + (index - 232) * 10;
+                (gray, gray, gray)
+            }
+            ThemeColor::Rgb(r, g, b) => (*r, *g, *b),
+        }
+    }
+
+    /// The name of the 16-color ANSI color: the color's own, or the nearest one for
+    /// indexed and RGB colors.
+    pub fn ansi_name(&self) -> &str {
+        match self {
+            ThemeColor::Named(name) => name,
+            ThemeColor::Indexed(index @ 0..=15) => ANSI_PALETTE[*index as usize].0,
+            _ => {
+                let (r, g, b) = self.to_rgb();
+                let distance = |&(_, (pr, pg, pb)): &(&str, (u8, u8, u8))| {
+                    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+                    d(r, pr) + d(g, pg) + d(b, pb)
+                };
+                ANSI_PALETTE.iter().min_by_key(|entry| distance(entry)).map_or("white", |(name, _)| name)
+            }
+        }
+    }
+
+    /// The exact `owo_colors` color, for terminals with 256-color or truecolor support.
+    pub fn to_dyn_color(&self) -> DynColors {
+        match self {
+            ThemeColor::Named(_) => DynColors::Ansi(self.to_ansi_color()),
+            ThemeColor::Indexed(index) => DynColors::Xterm(XtermColors::from(*index)),
+            ThemeColor::Rgb(r, g, b) => DynColors::Rgb(*r, *g, *b),
+        }
+    }
+
     /// Converts the `ThemeColor` enum variant into its corresponding `owo_colors::AnsiColors`.
+    /// Indexed and RGB colors map to the nearest of the 16 ANSI colors; use
+    /// [`Self::to_dyn_color`] to keep them exact.
     pub fn to_ansi_color(&self) -> AnsiColors {
-        match self {
-            ThemeColor::Named(name) => match name.as_str() {
-                "black" => AnsiColors::Black,
-                "red" => AnsiColors::Red,
-                "green" => AnsiColors::Green,
-                "yellow" => AnsiColors::Yellow,
-                "blue" => AnsiColors::Blue,
-                "magenta" => AnsiColors::Magenta,
-                "cyan" => AnsiColors::Cyan,
-                "white" => AnsiColors::White,
-                "brightblack" => AnsiColors::BrightBlack,
-                "brightred" => AnsiColors::BrightRed,
-                "brightgreen" => AnsiColors::BrightGreen,
-                "brightyellow" => AnsiColors::BrightYellow,
-                "brightblue" => AnsiColors::BrightBlue,
-                "brightmagenta" => AnsiColors::BrightMagenta,
-                "brightcyan" => AnsiColors::BrightCyan,
-                "brightwhite" => AnsiColors::BrightWhite,
-                _ => AnsiColors::White,
-            },
+        match self.ansi_name() {
+            "black" => AnsiColors::Black,
+            "red" => AnsiColors::Red,
+            "green" => AnsiColors::Green,
+            "yellow" => AnsiColors::Yellow,
+            "blue" => AnsiColors::Blue,
+            "magenta" => AnsiColors::Magenta,
+            "cyan" => AnsiColors::Cyan,
+            "white" => AnsiColors::White,
+            "brightblack" => AnsiColors::BrightBlack,
+            "brightred" => AnsiColors::BrightRed,
+            "brightgreen" => AnsiColors::BrightGreen,
+            "brightyellow" => AnsiColors::BrightYellow,
+            "brightblue" => AnsiColors::BrightBlue,
+            "brightmagenta" => AnsiColors::BrightMagenta,
+            "brightcyan" => AnsiColors::BrightCyan,
+            "brightwhite" => AnsiColors::BrightWhite,
+            _ => AnsiColors::White,
         }
     }
 }
@@ -129,6 +251,12 @@ impl ThemeColor {
 pub struct ThemeStyle {
     /// An optional `ThemeColor` to apply as the foreground color.
     pub fg: Option<ThemeColor>,
+    /// An optional `ThemeColor` to apply as the background color.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bg: Option<ThemeColor>,
+    /// Modifiers to apply, from [`THEME_MODIFIERS`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modifiers: Option<Vec<String>>,
 }
 
 /// Loads a theme configuration from a YAML file or returns the default theme.
@@ -141,14 +269,61 @@ pub fn build_theme_map(theme_path: Option<&PathBuf>) -> Result<ThemeMap> {
 }
 
 impl ThemeStyle {
-    /// Loads a theme configuration from a YAML file on disk and merges it with default styles.
+    /// A style with only a named foreground color.
+    fn named(color: &str) -> Self {
+        ThemeStyle { fg: Some(ThemeColor::Named(color.into())), ..Default::default() }
+    }
+
+    /// Whether `modifier` is listed, ignoring case.
+    pub fn has_modifier(&self, modifier: &str) -> bool {
+        self.modifiers.iter().flatten().any(|m| m.eq_ignore_ascii_case(modifier))
+    }
+
+    /// The style for `owo_colors` output, with exact 256-color and RGB colors.
+    pub fn to_owo_style(&self) -> owo_colors::Style {
+        let mut style = owo_colors::Style::new();
+        if let Some(fg) = &self.fg {
+            style = style.color(fg.to_dyn_color());
+        }
+        if let Some(bg) = &self.bg {
+            style = style.on_color(bg.to_dyn_color());
+        }
+        if self.has_modifier("bold") {
+            style = style.bold();
+        }
+        if self.has_modifier("italic") {
+            style = style.italic();
+        }
+        if self.has_modifier("underline") {
+            style = style.underline();
+        }
+        if self.has_modifier("dim") {
+            style = style.dimmed();
+        }
+        style
+    }
+
+    /// Loads a theme configuration from a YAML, TOML, or JSON file on disk (see
+    /// [`ConfigFormat`]) and merges it with default styles.
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<ThemeMap> {
         let path = path.as_ref();
         let text = std::fs::read_to_string(path)
             .with_context(|| format!("Failed to read theme file {}", path.display()))?;
-        let mut custom: ThemeMap = serde_yaml::from_str(&text)
-            .with_context(|| format!("Failed to parse theme file {}", path.display()))?;
-        
+        let format = ConfigFormat::detect(path, None);
+        let mut custom: ThemeMap = format.parse(&text)
+            .with_context(|| format!("Failed to parse theme file {} as {}", path.display(), format.name()))?;
+        for (entry, style) in &custom {
+            for modifier in style.modifiers.iter().flatten() {
+                if !THEME_MODIFIERS.iter().any(|m| m.eq_ignore_ascii_case(modifier)) {
+                    bail!(
+                        "Unknown modifier '{}' for {:?} in theme file {}; expected one of: {}",
+                        modifier, entry, path.display(), THEME_MODIFIERS.join(", ")
+                    );
+                }
+            }
+        }
+
+
         for entry in [
             ThemeEntry::Header, ThemeEntry::Success, ThemeEntry::Info, ThemeEntry::Warn,
             ThemeEntry::Error, ThemeEntry::RedactedText, ThemeEntry::DiffAdded,
@@ -157,7 +332,7 @@ impl ThemeStyle {
             ThemeEntry::HeatmapCritical, ThemeEntry::HeatmapHigh,
             ThemeEntry::HeatmapModerate, ThemeEntry::HeatmapLow,
         ] {
-            custom.entry(entry).or_insert_with(|| ThemeStyle { fg: Some(ThemeColor::Named("white".into())) });
+            custom.entry(entry).or_insert_with(|| ThemeStyle::named("white"));
         }
         Ok(custom)
     }
@@ -165,21 +340,21 @@ impl ThemeStyle {
     /// Returns a default theme map with predefined color mappings.
     pub fn default_theme_map() -> ThemeMap {
         let mut default_theme = HashMap::new();
-        default_theme.insert(ThemeEntry::DiffAdded, ThemeStyle { fg: Some(ThemeColor::Named("green".into())) });
-        default_theme.insert(ThemeEntry::DiffRemoved, ThemeStyle { fg: Some(ThemeColor::Named("red".into())) });
+        default_theme.insert(ThemeEntry::DiffAdded, ThemeStyle::named("green"));
+        default_theme.insert(ThemeEntry::DiffRemoved, ThemeStyle::named("red"));
 
         // Default Heatmap Colors
-        default_theme.insert(ThemeEntry::HeatmapCritical, ThemeStyle { fg: Some(ThemeColor::Named("brightred".into())) });
-        default_theme.insert(ThemeEntry::HeatmapHigh, ThemeStyle { fg: Some(ThemeColor::Named("red".into())) });
-        default_theme.insert(ThemeEntry::HeatmapModerate, ThemeStyle { fg: Some(ThemeColor::Named("yellow".into())) });
-        default_theme.insert(ThemeEntry::HeatmapLow, ThemeStyle { fg: Some(ThemeColor::Named("brightblack".into())) });
+        default_theme.insert(ThemeEntry::HeatmapCritical, ThemeStyle::named("brightred"));
+        default_theme.insert(ThemeEntry::HeatmapHigh, ThemeStyle::named("red"));
+        default_theme.insert(ThemeEntry::HeatmapModerate, ThemeStyle::named("yellow"));
+        default_theme.insert(ThemeEntry::HeatmapLow, ThemeStyle::named("brightblack"));
 
         for entry in [
             ThemeEntry::Header, ThemeEntry::Success, ThemeEntry::Info, ThemeEntry::Warn,
             ThemeEntry::Error, ThemeEntry::RedactedText, ThemeEntry::DiffHeader,
             ThemeEntry::SummaryRuleName, ThemeEntry::SummaryOccurrences, ThemeEntry::Prompt,
         ] {
-            default_theme.entry(entry).or_insert_with(|| ThemeStyle { fg: Some(ThemeColor::Named("white".into())) });
+            default_theme.entry(entry).or_insert_with(|| ThemeStyle::named("white"));
         }
         default_theme
     }
@@ -203,4 +378,77 @@ mod tests {
         let tc: ThemeColor = "brightmagenta".parse().unwrap();
         assert_eq!(tc.to_ansi_color(), AnsiColors::BrightMagenta);
     }
+
+    #[test]
+    fn parse_indexed_and_hex_colors() {
+        assert_eq!("123".parse::<ThemeColor>().unwrap(), ThemeColor::Indexed(123));
+        assert_eq!("#FF8800".parse::<ThemeColor>().unwrap(), ThemeColor::Rgb(0xff, 0x88, 0x00));
+        assert!("256".parse::<ThemeColor>().is_err());
+        // Written back in the forms they are read in.
+        assert_eq!(ThemeColor::Indexed(7).to_string(), "7");
+        assert_eq!(ThemeColor::Rgb(0xff, 0x88, 0).to_string(), "#ff8800");
+    }
+
+    #[test]
+    fn invalid_hex_colors_are_rejected() {
+        for bad in ["#ff88", "#ff88zz", "#ff880011", "#"] {
+            let err = bad.parse::<ThemeColor>().unwrap_err().to_string();
+            assert!(err.contains(bad) && err.contains("#rrggbb"), "{}", err);
+        }
+        let err = ConfigFormat::Yaml.parse::<ThemeStyle>("fg: \"#12345g\"").unwrap_err().to_string();
+        assert!(err.contains("#rrggbb"), "{}", err);
+    }
+
+    #[test]
+    fn styles_read_every_color_form_and_modifiers() {
+        let yaml = "fg: 208\nbg: \"#102030\"\nmodifiers: [bold, Underline]\n";
+        let style: ThemeStyle = ConfigFormat::Yaml.parse(yaml).unwrap();
+        assert_eq!(style.fg, Some(ThemeColor::Indexed(208)));
+        assert_eq!(style.bg, Some(ThemeColor::Rgb(0x10, 0x20, 0x30)));
+        assert!(style.has_modifier("underline") && !style.has_modifier("dim"));
+
+        // Entries written before backgrounds and modifiers still load.
+        let style: ThemeStyle = ConfigFormat::Yaml.parse("fg: \"124\"").unwrap();
+        assert_eq!(style, ThemeStyle { fg: Some(ThemeColor::Indexed(124)), ..Default::default() });
+    }
+
+    #[test]
+    fn extended_colors_fall_back_to_the_nearest_ansi_color() {
+        assert_eq!(ThemeColor::Indexed(9).to_ansi_color(), AnsiColors::BrightRed);
+        assert_eq!(ThemeColor::Indexed(196).to_ansi_color(), AnsiColors::BrightRed);
+        assert_eq!(ThemeColor::Rgb(0, 0, 230).to_ansi_color(), AnsiColors::Blue);
+        assert_eq!(ThemeColor::Indexed(232).to_rgb(), (8, 8, 8));
+    }
+
+    #[test]
+    fn modifiers_reach_the_ratatui_style() {
+        use crate::ui::diff_viewer::get_theme_style;
+        use ratatui::style::{Color, Modifier};
+
+        let mut theme = ThemeStyle::default_theme_map();
+        theme.insert(ThemeEntry::RedactedText, ThemeStyle {
+            fg: Some(ThemeColor::Rgb(255, 136, 0)),
+            bg: Some(ThemeColor::Indexed(236)),
+            modifiers: Some(vec!["bold".into(), "italic".into()]),
+        });
+        let style = get_theme_style(ThemeEntry::RedactedText, &theme);
+        assert_eq!(style.fg, Some(Color::Rgb(255, 136, 0)));
+        assert_eq!(style.bg, Some(Color::Indexed(236)));
+        assert_eq!(style.add_modifier, Modifier::BOLD | Modifier::ITALIC);
+    }
+
+    #[test]
+    fn unknown_modifiers_are_rejected_and_missing_entries_defaulted() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("theme.yaml");
+        std::fs::write(&path, "redacted_text:\n  fg: \"#ff8800\"\n  modifiers: [bold]\n")?;
+        let theme = build_theme_map(Some(&path))?;
+        assert!(theme[&ThemeEntry::RedactedText].has_modifier("bold"));
+        assert_eq!(theme[&ThemeEntry::Header], ThemeStyle::named("white"));
+
+        std::fs::write(&path, "header:\n  modifiers: [blink]\n")?;
+        let err = build_theme_map(Some(&path)).unwrap_err().to_string();
+        assert!(err.contains("blink") && err.contains("bold, italic, underline, dim"), "{}", err);
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -23,7 +23,7 @@ const LOCAL_KEY_FILENAME: &str = "state_key.b64";
 const AES_NONCE_LEN: usize = 12;
 const STATE_FILE_TMP_SUFFIX: &str = ".tmp";
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct AppState {
     pub usage_count: u64,
     pub stats_only_usage_count: u64,
@@ -31,17 +31,6 @@ pub struct AppState {
     pub donation_prompts_disabled: bool,
 }
 
-impl Default for AppState {
-    fn default() -> Self {
-        AppState {
-            usage_count: 0,
-            stats_only_usage_count: 0,
-            last_prompt_timestamp: None,
-            donation_prompts_disabled: false,
-        }
-    }
-}
-
 impl AppState {
     pub fn new() -> Self {
         Self::default()
@@ -96,12 +85,9 @@ impl AppState {
 }
 
 fn get_or_create_state_key(state_path: &Path) -> Result<Vec<u8>> {
-    match KeyringEntry::new(KEYRING_SERVICE, KEYRING_USERNAME).and_then(|entry| entry.get_password()) {
-        Ok(s) => {
-            let decoded = general_purpose::STANDARD.decode(s)?;
-            if decoded.len() == 32 { return Ok(decoded); }
-        },
-        _ => {}
+    if let Ok(s) = KeyringEntry::new(KEYRING_SERVICE, KEYRING_USERNAME).and_then(|entry| entry.get_password()) {
+        let decoded = general_purpose::STANDARD.decode(s)?;
+        if decoded.len() == 32 { return Ok(decoded); }
     }
 
     let key_file = if let Some(parent) = state_path.parent() {
@@ -119,7 +105,7 @@ fn get_or_create_state_key(state_path: &Path) -> Result<Vec<u8>> {
     let mut key = [0u8; 32];
     OsRng.fill_bytes(&mut key);
 
-    let b64 = general_purpose::STANDARD.encode(&key);
+    let b64 = general_purpose::STANDARD.encode(key);
     let _ = KeyringEntry::new(KEYRING_SERVICE, KEYRING_USERNAME).and_then(|entry| entry.set_password(&b64));
 
     Ok(key.to_vec())
@@ -138,7 +124,7 @@ fn encrypt_state_blob(plaintext: &[u8], state_path: &Path) -> Result<Vec<u8>> {
 
     let out_str = format!(
         "v1.{}.{}",
-        general_purpose::STANDARD.encode(&nonce_bytes),
+        general_purpose::STANDARD.encode(nonce_bytes),
         general_purpose::STANDARD.encode(&ciphertext)
     );
     Ok(out_str.into_bytes())
@@ -1,17 +1,73 @@
 //src/utils/clipboard.rs
 //! This module provides functionality to interact with the system clipboard.
 //! It allows copying sanitized content to the clipboard, which can be useful
-//! for quick access or further processing without needing to write to a file.
+//! for quick access or further processing without needing to write to a file,
+//! and reading the clipboard so its content can be sanitized before it is pasted.
 
 
-use anyhow::{Result, Context};
-use arboard;
+use anyhow::Result;
+#[cfg(feature = "clipboard")]
+use anyhow::Context;
 use log::debug;
 
+/// Somewhere copied text can be placed. The TUI copies through this so tests can
+/// capture the payload instead of touching the system clipboard.
+pub trait ClipboardSink: Send {
+    fn set_text(&mut self, content: &str) -> Result<()>;
+}
+
+/// Somewhere text to sanitize can be read from; see [`ClipboardSink`].
+pub trait ClipboardSource: Send {
+    fn get_text(&mut self) -> Result<String>;
+}
+
+/// The system clipboard, acquired afresh for every copy.
+#[derive(Debug, Default)]
+pub struct SystemClipboard;
+
+impl ClipboardSink for SystemClipboard {
+    fn set_text(&mut self, content: &str) -> Result<()> {
+        copy_to_clipboard(content)
+    }
+}
+
+impl ClipboardSource for SystemClipboard {
+    fn get_text(&mut self) -> Result<String> {
+        read_from_clipboard()
+    }
+}
+
+#[cfg(feature = "clipboard")]
 pub fn copy_to_clipboard(content: &str) -> Result<()> {
     debug!("Attempting to acquire clipboard.");
     let mut clipboard = arboard::Clipboard::new().context("Failed to initialize clipboard")?;
     debug!("Setting clipboard text.");
     clipboard.set_text(content.to_string()).context("Failed to set clipboard text")?;
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Reads the clipboard's text. A clipboard holding only an image or files is an error.
+#[cfg(feature = "clipboard")]
+pub fn read_from_clipboard() -> Result<String> {
+    debug!("Attempting to acquire clipboard for reading.");
+    let mut clipboard = arboard::Clipboard::new().context("Failed to initialize clipboard")?;
+    match clipboard.get_text() {
+        Ok(text) => Ok(text),
+        Err(arboard::Error::ContentNotAvailable) => {
+            anyhow::bail!("The clipboard is empty or holds no text (an image or files, perhaps)")
+        }
+        Err(e) => Err(e).context("Failed to read clipboard text"),
+    }
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn read_from_clipboard() -> Result<String> {
+    debug!("Clipboard support is not compiled in.");
+    anyhow::bail!("cleansh was built without clipboard support")
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn copy_to_clipboard(_content: &str) -> Result<()> {
+    debug!("Clipboard support is not compiled in.");
+    anyhow::bail!("cleansh was built without clipboard support")
+}
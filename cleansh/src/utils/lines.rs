@@ -0,0 +1,181 @@
+// cleansh/src/utils/lines.rs
+//! Input lines that remember how they ended, so line-by-line output can reproduce
+//! the input's `\r\n`, `\n`, or missing final newline exactly.
+
+use std::io::{self, Write};
+use std::sync::Arc;
+
+/// What ended an input line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineTerminator {
+    CrLf,
+    #[default]
+    Lf,
+    /// The last line of an input that does not end with a newline.
+    None,
+}
+
+impl LineTerminator {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::CrLf => "\r\n",
+            Self::Lf => "\n",
+            Self::None => "",
+        }
+    }
+}
+
+/// One line of input, without its terminator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputLine {
+    pub text: String,
+    pub terminator: LineTerminator,
+}
+
+impl InputLine {
+    pub fn new(text: impl Into<String>, terminator: LineTerminator) -> Self {
+        Self { text: text.into(), terminator }
+    }
+
+    /// Splits a line read up to its `\n` (`newline`), or up to the end of input,
+    /// into its text and terminator.
+    pub fn from_line(mut text: String, newline: bool) -> Self {
+        let terminator = if !newline {
+            LineTerminator::None
+        } else if text.ends_with('\r') {
+            text.pop();
+            LineTerminator::CrLf
+        } else {
+            LineTerminator::Lf
+        };
+        Self { text, terminator }
+    }
+
+    /// Like [`Self::from_line`] for raw bytes, replacing invalid UTF-8.
+    pub fn from_bytes(line: &[u8], newline: bool) -> Self {
+        Self::from_line(String::from_utf8_lossy(line).into_owned(), newline)
+    }
+}
+
+impl From<String> for InputLine {
+    /// A line ended by `\n`.
+    fn from(text: String) -> Self {
+        Self::new(text, LineTerminator::Lf)
+    }
+}
+
+/// Source id of TUI lines that did not come from a numbered input.
+pub const UNNUMBERED_SOURCE_ID: &str = "tui";
+
+/// A Live Stream line with where it came from, so its matches can be traced back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourcedLine {
+    /// `stdin`, or the followed file's label.
+    pub source_id: Arc<str>,
+    /// 1-based position of the line in its source; `0` when unknown.
+    pub line_no: u64,
+    /// The line without its terminator, or a block's lines joined with `\n`.
+    pub text: String,
+    /// Whether the line is history re-scanned after a threshold change or engine
+    /// switch, rather than new input.
+    pub replayed: bool,
+}
+
+impl SourcedLine {
+    pub fn new(source_id: Arc<str>, line_no: u64, text: impl Into<String>) -> Self {
+        Self { source_id, line_no, text: text.into(), replayed: false }
+    }
+
+    /// This line, marked as a replay of history.
+    pub fn into_replay(self) -> Self {
+        Self { replayed: true, ..self }
+    }
+
+    /// The `offset`-th line after this one, from the same source, and replayed if
+    /// this one is.
+    pub fn following(&self, offset: u64, text: impl Into<String>) -> Self {
+        let line_no = if self.line_no == 0 { 0 } else { self.line_no + offset };
+        Self { replayed: self.replayed, ..Self::new(Arc::clone(&self.source_id), line_no, text) }
+    }
+}
+
+impl From<String> for SourcedLine {
+    /// An unnumbered line from [`UNNUMBERED_SOURCE_ID`].
+    fn from(text: String) -> Self {
+        Self::new(Arc::from(UNNUMBERED_SOURCE_ID), 0, text)
+    }
+}
+
+/// Numbers the lines of one source as they are read.
+#[derive(Debug, Clone)]
+pub struct LineCounter {
+    source_id: Arc<str>,
+    next: u64,
+}
+
+impl LineCounter {
+    pub fn new(source_id: &str) -> Self {
+        Self { source_id: Arc::from(source_id), next: 1 }
+    }
+
+    /// Tags `text` with the next line number.
+    pub fn next_line(&mut self, text: String) -> SourcedLine {
+        let line = SourcedLine::new(Arc::clone(&self.source_id), self.next, text);
+        self.next += 1;
+        line
+    }
+}
+
+/// Writes `sanitized`, the output for one or more input lines joined with `\n`,
+/// ending its lines with `terminators` (those of the input lines, in order).
+///
+/// When the sanitized text has fewer or more lines than went in, its inner lines
+/// take the first input line's terminator and its last line the last one's.
+pub fn write_terminated(out: &mut impl Write, sanitized: &str, terminators: &[LineTerminator]) -> io::Result<()> {
+    let last = terminators.last().copied().unwrap_or_default();
+    let inner = match terminators.first() {
+        Some(LineTerminator::None) | None => LineTerminator::Lf,
+        Some(&first) => first,
+    };
+    let lines: Vec<&str> = sanitized.split('\n').collect();
+    let same_shape = lines.len() == terminators.len();
+    for (i, line) in lines.iter().enumerate() {
+        let terminator = if i + 1 == lines.len() {
+            last
+        } else if same_shape {
+            terminators[i]
+        } else {
+            inner
+        };
+        out.write_all(line.as_bytes())?;
+        out.write_all(terminator.as_str().as_bytes())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_split_off_their_terminators() {
+        assert_eq!(InputLine::from_line("a\r".to_string(), true), InputLine::new("a", LineTerminator::CrLf));
+        assert_eq!(InputLine::from_line("b".to_string(), true), InputLine::new("b", LineTerminator::Lf));
+        // Without a newline a trailing `\r` is part of the text.
+        assert_eq!(InputLine::from_bytes(b"c\r", false), InputLine::new("c\r", LineTerminator::None));
+    }
+
+    #[test]
+    fn test_blocks_keep_each_lines_terminator_or_fall_back_to_the_first() -> io::Result<()> {
+        let terminators = [LineTerminator::CrLf, LineTerminator::Lf, LineTerminator::None];
+        let mut out = Vec::new();
+        write_terminated(&mut out, "one\ntwo\nthree", &terminators)?;
+        assert_eq!(out, b"one\r\ntwo\nthree");
+
+        // Redacted down to two lines: the inner one takes the first terminator.
+        out.clear();
+        write_terminated(&mut out, "[KEY]\ndone", &terminators)?;
+        assert_eq!(out, b"[KEY]\r\ndone");
+        Ok(())
+    }
+}
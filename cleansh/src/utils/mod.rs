@@ -4,4 +4,6 @@
 pub mod app_state;
 pub mod platform;
 pub mod clipboard;
+pub mod lines;
+pub mod sink;
 // pub mod license; <--- Deleted
\ No newline at end of file
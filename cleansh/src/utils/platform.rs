@@ -2,6 +2,8 @@
 //! This module provides platform-specific utilities and information.
 //! It uses Rust's `cfg!` macro to handle OS-specific logic at compile time.
 
+use cleansh_core::config_format::find_config_file;
+
 /// Returns the correct End-of-File (EOF) key combination for the current platform.
 ///
 /// This is used for displaying the correct prompt to the user when reading from stdin
@@ -18,4 +20,86 @@ pub fn eof_key_combo() -> &'static str {
     } else {
         "Ctrl+D"
     }
-}
\ No newline at end of file
+}
+
+/// Returns the path of the user's `cleansh` configuration file, if a config
+/// directory can be determined for this platform. The first of `config.yaml`,
+/// `config.yml`, `config.toml`, and `config.json` that exists is used, else
+/// `config.yaml`:
+///
+/// * `~/.config/cleansh/config.yaml` on Linux.
+/// * `%APPDATA%\cleansh\config.yaml` on Windows.
+/// * The platform config directory (via `dirs::config_dir`) elsewhere.
+pub fn user_config_path() -> Option<std::path::PathBuf> {
+    dirs::config_dir().map(|dir| find_config_file(&dir.join("cleansh"), "config"))
+}
+
+/// Returns the path of the user's custom rules file, written by `cleansh rules add`.
+///
+/// `CLEANSH_RULES_FILE` overrides the location; otherwise this is `rules.yaml`
+/// (or the first existing `rules.yml`, `rules.toml`, or `rules.json`) next to
+/// [`user_config_path`].
+pub fn user_rules_path() -> Option<std::path::PathBuf> {
+    if let Some(path) = std::env::var_os("CLEANSH_RULES_FILE") {
+        return Some(path.into());
+    }
+    dirs::config_dir().map(|dir| find_config_file(&dir.join("cleansh"), "rules"))
+}
+
+/// Returns the path of the ignore file managed by `cleansh ignore`.
+///
+/// `CLEANSH_IGNORE_FILE` overrides the location; otherwise this is
+/// `.cleanshignore` in the working directory.
+pub fn ignore_file_path() -> std::path::PathBuf {
+    match std::env::var_os("CLEANSH_IGNORE_FILE") {
+        Some(path) => path.into(),
+        None => cleansh_core::ignore_file::IGNORE_FILE_NAME.into(),
+    }
+}
+
+/// Returns the path of the ignore store that the TUI's `I` key writes to.
+///
+/// `CLEANSH_IGNORE_STORE` overrides the location; otherwise this is
+/// `ignored_hashes.json` next to [`user_config_path`].
+pub fn ignore_store_path() -> Option<std::path::PathBuf> {
+    if let Some(path) = std::env::var_os("CLEANSH_IGNORE_STORE") {
+        return Some(path.into());
+    }
+    dirs::config_dir().map(|dir| dir.join("cleansh").join(cleansh_core::ignore_store::IGNORE_STORE_FILE_NAME))
+}
+
+/// Returns the path of the fingerprint cache written by `cleansh vault compact`.
+///
+/// `CLEANSH_FINGERPRINT_CACHE` overrides the location; otherwise this is
+/// `fingerprints.cache` in the platform cache directory (via `dirs::cache_dir`).
+pub fn fingerprint_cache_path() -> Option<std::path::PathBuf> {
+    if let Some(path) = std::env::var_os("CLEANSH_FINGERPRINT_CACHE") {
+        return Some(path.into());
+    }
+    dirs::cache_dir().map(|dir| dir.join("cleansh").join("fingerprints.cache"))
+}
+
+/// Returns the path of the remediation config that enables the TUI's providers.
+///
+/// `CLEANSH_REMEDIATION_CONFIG` overrides the location; otherwise this is
+/// `remediation.yaml` (or the first existing `remediation.yml`,
+/// `remediation.toml`, or `remediation.json`) next to [`user_config_path`].
+pub fn user_remediation_path() -> Option<std::path::PathBuf> {
+    if let Some(path) = std::env::var_os("CLEANSH_REMEDIATION_CONFIG") {
+        return Some(path.into());
+    }
+    dirs::config_dir().map(|dir| find_config_file(&dir.join("cleansh"), "remediation"))
+}
+
+/// Identifies the file behind a path, so a rotated file is told apart from the one
+/// that replaced it. Platforms without inodes return `None`.
+#[cfg(unix)]
+pub fn file_identity(meta: &std::fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+pub fn file_identity(_meta: &std::fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
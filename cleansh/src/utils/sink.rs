@@ -0,0 +1,672 @@
+// cleansh/src/utils/sink.rs
+//! Where sanitized output goes: stdout, a file, a socket, or another program's
+//! stdin, chosen with `--sink` URIs such as `file:/var/log/app.clean.log`.
+//!
+//! Only stdout ends the stream when it fails (the reader went away). The other
+//! sinks log the error, drop what they cannot deliver, and try again after a
+//! backoff: a file is reopened, a socket reconnected, a command restarted. A file
+//! is also reopened when it is rotated away from under the sink. A socket or
+//! command that stops reading fails the write after [`WRITE_TIMEOUT`] instead of
+//! stalling the stream.
+
+use anyhow::{bail, Context, Result};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::utils::platform::file_identity;
+
+/// First wait before a failed sink is retried; it doubles up to [`MAX_BACKOFF`].
+pub const INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Longest wait between retries of a failed sink.
+pub const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// How long a socket sink waits to connect, and a socket or command sink waits for
+/// a write to go through, before treating the other end as failed.
+pub const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Somewhere sanitized output is written, a batch of whole lines at a time.
+pub trait OutputSink: Send {
+    /// Writes `data`. An error means the stream should stop.
+    fn write_all(&mut self, data: &[u8]) -> io::Result<()>;
+
+    fn flush(&mut self) -> io::Result<()>;
+}
+
+/// A parsed `--sink` value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SinkSpec {
+    /// `stdout` or `-`.
+    Stdout,
+    /// `file:<path>`, appended to.
+    File(PathBuf),
+    /// `tcp:<host>:<port>`, one line per message.
+    Tcp(String),
+    /// `unix:<path>`, one line per message (Unix only).
+    Unix(PathBuf),
+    /// `cmd:<command>`, run by the shell with the output on its stdin.
+    Command(String),
+}
+
+impl FromStr for SinkSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "stdout" || s == "-" {
+            return Ok(SinkSpec::Stdout);
+        }
+        let (scheme, target) = s.split_once(':').unwrap_or((s, ""));
+        if target.is_empty() {
+            return Err(format!("sink '{}' needs a target, e.g. file:/var/log/app.clean.log", s));
+        }
+        match scheme {
+            "file" => Ok(SinkSpec::File(PathBuf::from(target))),
+            "tcp" => Ok(SinkSpec::Tcp(target.to_string())),
+            "unix" => Ok(SinkSpec::Unix(PathBuf::from(target))),
+            "cmd" => Ok(SinkSpec::Command(target.to_string())),
+            _ => Err(format!(
+                "unknown sink '{}': expected stdout, file:<path>, tcp:<host:port>, unix:<path>, or cmd:<command>",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SinkSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SinkSpec::Stdout => f.write_str("stdout"),
+            SinkSpec::File(path) => write!(f, "file:{}", path.display()),
+            SinkSpec::Tcp(addr) => write!(f, "tcp:{}", addr),
+            SinkSpec::Unix(path) => write!(f, "unix:{}", path.display()),
+            SinkSpec::Command(command) => write!(f, "cmd:{}", command),
+        }
+    }
+}
+
+impl SinkSpec {
+    /// Opens the sink. A file that cannot be opened or a command that cannot be
+    /// started is an error; a socket nobody listens on yet is connected later.
+    pub fn open(&self) -> Result<Box<dyn OutputSink>> {
+        Ok(match self {
+            SinkSpec::Stdout => Box::new(StdoutSink),
+            SinkSpec::File(path) => Box::new(FileSink::open(path.clone())?),
+            SinkSpec::Tcp(addr) => Box::new(SocketSink::connect(SocketAddr::Tcp(addr.clone()))),
+            SinkSpec::Unix(path) => {
+                if !cfg!(unix) {
+                    bail!("unix sockets are not supported on this platform: {}", self);
+                }
+                Box::new(SocketSink::connect(SocketAddr::Unix(path.clone())))
+            }
+            SinkSpec::Command(command) => Box::new(CommandSink::spawn(command.clone())?),
+        })
+    }
+}
+
+/// Opens every sink in `specs`, writing to all of them; stdout when there are none.
+pub fn open_sinks(specs: &[SinkSpec]) -> Result<Box<dyn OutputSink>> {
+    match specs {
+        [] => Ok(Box::new(StdoutSink)),
+        [spec] => spec.open(),
+        _ => Ok(Box::new(TeeSink(specs.iter().map(SinkSpec::open).collect::<Result<_>>()?))),
+    }
+}
+
+/// The process's stdout.
+#[derive(Debug, Default)]
+pub struct StdoutSink;
+
+impl OutputSink for StdoutSink {
+    fn write_all(&mut self, data: &[u8]) -> io::Result<()> {
+        io::stdout().lock().write_all(data)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stdout().flush()
+    }
+}
+
+/// Writes every batch to each of several sinks.
+pub struct TeeSink(pub Vec<Box<dyn OutputSink>>);
+
+impl OutputSink for TeeSink {
+    /// Every sink gets the batch; the first error is returned afterwards.
+    fn write_all(&mut self, data: &[u8]) -> io::Result<()> {
+        self.0.iter_mut().map(|sink| sink.write_all(data)).fold(Ok(()), Result::and)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.iter_mut().map(|sink| sink.flush()).fold(Ok(()), Result::and)
+    }
+}
+
+/// When a failed sink may try again, and how much it dropped meanwhile.
+#[derive(Debug)]
+struct Backoff {
+    delay: Duration,
+    retry_at: Option<Instant>,
+    dropped_bytes: u64,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self { delay: INITIAL_BACKOFF, retry_at: None, dropped_bytes: 0 }
+    }
+}
+
+impl Backoff {
+    /// Whether a reconnect may be attempted now.
+    fn ready(&self) -> bool {
+        self.retry_at.is_none_or(|at| Instant::now() >= at)
+    }
+
+    fn failed(&mut self, sink: &str, error: &dyn fmt::Display) {
+        log::warn!("Output sink {} failed, retrying in {:?}: {}", sink, self.delay, error);
+        self.retry_at = Some(Instant::now() + self.delay);
+        self.delay = (self.delay * 2).min(MAX_BACKOFF);
+    }
+
+    fn dropped(&mut self, len: usize) {
+        self.dropped_bytes += len as u64;
+    }
+
+    fn recovered(&mut self, sink: &str) {
+        if self.dropped_bytes > 0 {
+            log::warn!("Output sink {} recovered; {} bytes were dropped while it was down", sink, self.dropped_bytes);
+        }
+        *self = Self::default();
+    }
+}
+
+/// Appends to a file, reopening it (and creating it again) when it is rotated or
+/// deleted, or after a write error.
+#[derive(Debug)]
+pub struct FileSink {
+    path: PathBuf,
+    file: Option<File>,
+    identity: Option<(u64, u64)>,
+    backoff: Backoff,
+}
+
+impl FileSink {
+    /// Opens `path` for appending, creating it if needed.
+    pub fn open(path: PathBuf) -> Result<Self> {
+        let mut sink = Self { path, file: None, identity: None, backoff: Backoff::default() };
+        sink.reopen().with_context(|| format!("Failed to open output file '{}'", sink.path.display()))?;
+        Ok(sink)
+    }
+
+    fn reopen(&mut self) -> io::Result<()> {
+        self.file = None;
+        let file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.identity = file_identity(&file.metadata()?);
+        self.file = Some(file);
+        Ok(())
+    }
+
+    /// Whether the path now names a different file, or none, than the open one.
+    /// Platforms without inodes only notice deletion.
+    fn rotated(&self) -> bool {
+        match fs::metadata(&self.path) {
+            Ok(meta) => file_identity(&meta) != self.identity,
+            Err(_) => true,
+        }
+    }
+
+    fn name(&self) -> String {
+        format!("file:{}", self.path.display())
+    }
+}
+
+impl OutputSink for FileSink {
+    fn write_all(&mut self, data: &[u8]) -> io::Result<()> {
+        if self.file.is_some() && self.rotated() {
+            log::debug!("{} was rotated; reopening it", self.path.display());
+            self.file = None;
+        }
+        if self.file.is_none() {
+            if !self.backoff.ready() {
+                self.backoff.dropped(data.len());
+                return Ok(());
+            }
+            if let Err(e) = self.reopen() {
+                self.backoff.failed(&self.name(), &e);
+                self.backoff.dropped(data.len());
+                return Ok(());
+            }
+            self.backoff.recovered(&self.name());
+        }
+        if let Some(file) = self.file.as_mut()
+            && let Err(e) = file.write_all(data)
+        {
+            self.file = None;
+            self.backoff.failed(&self.name(), &e);
+            self.backoff.dropped(data.len());
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some(file) = self.file.as_mut() {
+            let _ = file.flush();
+        }
+        Ok(())
+    }
+}
+
+/// Where a [`SocketSink`] connects.
+#[derive(Debug, Clone)]
+enum SocketAddr {
+    Tcp(String),
+    #[cfg_attr(not(unix), allow(dead_code))]
+    Unix(PathBuf),
+}
+
+impl fmt::Display for SocketAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SocketAddr::Tcp(addr) => write!(f, "tcp:{}", addr),
+            SocketAddr::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+impl SocketAddr {
+    /// Connects within `timeout`, to the first address a TCP host resolves to that
+    /// accepts, and makes writes that take longer than `timeout` fail.
+    fn connect(&self, timeout: Duration) -> io::Result<Box<dyn Write + Send>> {
+        match self {
+            SocketAddr::Tcp(addr) => {
+                let mut last_error = io::Error::new(io::ErrorKind::NotFound, format!("{} resolves to no address", addr));
+                for resolved in addr.to_socket_addrs()? {
+                    match TcpStream::connect_timeout(&resolved, timeout) {
+                        Ok(stream) => {
+                            stream.set_write_timeout(Some(timeout))?;
+                            return Ok(Box::new(stream));
+                        }
+                        Err(e) => last_error = e,
+                    }
+                }
+                Err(last_error)
+            }
+            #[cfg(unix)]
+            SocketAddr::Unix(path) => {
+                let stream = std::os::unix::net::UnixStream::connect(path)?;
+                stream.set_write_timeout(Some(timeout))?;
+                Ok(Box::new(stream))
+            }
+            #[cfg(not(unix))]
+            SocketAddr::Unix(_) => Err(io::Error::new(io::ErrorKind::Unsupported, "unix sockets are not supported on this platform")),
+        }
+    }
+}
+
+/// Sends the lines to a TCP or Unix socket listener, reconnecting after errors. A
+/// listener that stops reading fails writes after [`WRITE_TIMEOUT`] rather than
+/// stalling the stream. A line a failed write cut off is ended with a newline as
+/// soon as the sink reconnects, so the listener never runs it into the next line.
+pub struct SocketSink {
+    addr: SocketAddr,
+    stream: Option<Box<dyn Write + Send>>,
+    backoff: Backoff,
+    timeout: Duration,
+    /// Whether the last connection failed partway through a line.
+    torn: bool,
+}
+
+impl SocketSink {
+    /// Connects now if the listener is up; otherwise the first writes retry.
+    fn connect(addr: SocketAddr) -> Self {
+        Self::connect_with_timeout(addr, WRITE_TIMEOUT)
+    }
+
+    fn connect_with_timeout(addr: SocketAddr, timeout: Duration) -> Self {
+        let mut sink = Self { addr, stream: None, backoff: Backoff::default(), timeout, torn: false };
+        match sink.addr.connect(timeout) {
+            Ok(stream) => sink.stream = Some(stream),
+            Err(e) => sink.backoff.failed(&sink.addr.to_string(), &e),
+        }
+        sink
+    }
+}
+
+impl OutputSink for SocketSink {
+    fn write_all(&mut self, data: &[u8]) -> io::Result<()> {
+        if self.stream.is_none() {
+            if !self.backoff.ready() {
+                self.backoff.dropped(data.len());
+                return Ok(());
+            }
+            let connected = self.addr.connect(self.timeout).and_then(|mut stream| {
+                if self.torn {
+                    stream.write_all(b"\n")?;
+                }
+                Ok(stream)
+            });
+            match connected {
+                Ok(stream) => {
+                    self.stream = Some(stream);
+                    self.torn = false;
+                    self.backoff.recovered(&self.addr.to_string());
+                }
+                Err(e) => {
+                    self.backoff.failed(&self.addr.to_string(), &e);
+                    self.backoff.dropped(data.len());
+                    return Ok(());
+                }
+            }
+        }
+        if let Some(stream) = self.stream.as_mut() {
+            let mut written = 0;
+            let result = loop {
+                if written == data.len() {
+                    break stream.flush();
+                }
+                match stream.write(&data[written..]) {
+                    Ok(0) => break Err(io::ErrorKind::WriteZero.into()),
+                    Ok(n) => written += n,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) => break Err(e),
+                }
+            };
+            if let Err(e) = result {
+                self.stream = None;
+                self.torn = written > 0 && data[written - 1] != b'\n';
+                self.backoff.failed(&self.addr.to_string(), &e);
+                self.backoff.dropped(data.len() - written);
+            }
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A started command whose stdin is written on a thread of its own, so a write
+/// to a command that stops reading can be given up on.
+struct RunningCommand {
+    child: Child,
+    /// Dropping it closes the command's stdin once the pending batch is written.
+    batches: SyncSender<Vec<u8>>,
+    written: Receiver<io::Result<()>>,
+    writer: JoinHandle<()>,
+}
+
+impl RunningCommand {
+    fn new(child: Child, mut stdin: ChildStdin) -> Self {
+        let (batches, pending) = mpsc::sync_channel::<Vec<u8>>(1);
+        let (done, written) = mpsc::channel();
+        let writer = thread::spawn(move || {
+            for batch in pending {
+                let result = stdin.write_all(&batch).and_then(|_| stdin.flush());
+                let failed = result.is_err();
+                if done.send(result).is_err() || failed {
+                    break;
+                }
+            }
+        });
+        Self { child, batches, written, writer }
+    }
+
+    /// Writes `data`, failing when the command has not taken it within `timeout`.
+    fn write(&mut self, data: &[u8], timeout: Duration) -> io::Result<()> {
+        self.batches.send(data.to_vec()).map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+        match self.written.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("the command did not read its input within {:?}", timeout),
+            )),
+            Err(RecvTimeoutError::Disconnected) => Err(io::ErrorKind::BrokenPipe.into()),
+        }
+    }
+
+    /// Closes the command's stdin and waits for it to exit.
+    fn close(self) {
+        drop(self.batches);
+        let _ = self.writer.join();
+        let mut child = self.child;
+        let _ = child.wait();
+    }
+
+    /// Kills a command that stopped reading. The writer thread is left to end
+    /// when the pipe closes, since a process the command started may still hold it.
+    fn kill(self) {
+        let mut child = self.child;
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+/// Feeds the lines to a command's stdin (`--pipe-to "logger -t app"`), starting
+/// it again if it exits. The command is run by `sh -c` (`cmd /C` on Windows). A
+/// command that does not read a batch within [`WRITE_TIMEOUT`] is killed and
+/// restarted like one that exited.
+pub struct CommandSink {
+    command: String,
+    child: Option<RunningCommand>,
+    backoff: Backoff,
+    timeout: Duration,
+}
+
+impl CommandSink {
+    pub fn spawn(command: String) -> Result<Self> {
+        Self::spawn_with_timeout(command, WRITE_TIMEOUT)
+    }
+
+    fn spawn_with_timeout(command: String, timeout: Duration) -> Result<Self> {
+        let mut sink = Self { command, child: None, backoff: Backoff::default(), timeout };
+        sink.child = Some(sink.start().with_context(|| format!("Failed to start '{}'", sink.command))?);
+        Ok(sink)
+    }
+
+    fn start(&self) -> io::Result<RunningCommand> {
+        let mut command = if cfg!(windows) {
+            let mut command = Command::new("cmd");
+            command.arg("/C");
+            command
+        } else {
+            let mut command = Command::new("sh");
+            command.arg("-c");
+            command
+        };
+        let mut child = command.arg(&self.command).stdin(Stdio::piped()).spawn()?;
+        let stdin = child.stdin.take().ok_or_else(|| io::Error::other("child has no stdin"))?;
+        Ok(RunningCommand::new(child, stdin))
+    }
+
+    fn name(&self) -> String {
+        format!("cmd:{}", self.command)
+    }
+}
+
+impl OutputSink for CommandSink {
+    fn write_all(&mut self, data: &[u8]) -> io::Result<()> {
+        if self.child.is_none() {
+            if !self.backoff.ready() {
+                self.backoff.dropped(data.len());
+                return Ok(());
+            }
+            match self.start() {
+                Ok(child) => {
+                    self.child = Some(child);
+                    self.backoff.recovered(&self.name());
+                }
+                Err(e) => {
+                    self.backoff.failed(&self.name(), &e);
+                    self.backoff.dropped(data.len());
+                    return Ok(());
+                }
+            }
+        }
+        if let Some(child) = self.child.as_mut()
+            && let Err(e) = child.write(data, self.timeout)
+        {
+            if let Some(child) = self.child.take() {
+                child.kill();
+            }
+            self.backoff.failed(&self.name(), &e);
+            self.backoff.dropped(data.len());
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for CommandSink {
+    /// Lets the command see end of input and finish its output before cleansh exits.
+    fn drop(&mut self) {
+        if let Some(child) = self.child.take() {
+            child.close();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_sink_specs_parse_their_scheme() {
+        assert_eq!("-".parse(), Ok(SinkSpec::Stdout));
+        assert_eq!("file:/var/log/app.clean.log".parse(), Ok(SinkSpec::File(PathBuf::from("/var/log/app.clean.log"))));
+        assert_eq!("tcp:127.0.0.1:5140".parse(), Ok(SinkSpec::Tcp("127.0.0.1:5140".to_string())));
+        assert_eq!("cmd:logger -t app".parse(), Ok(SinkSpec::Command("logger -t app".to_string())));
+        assert!("file:".parse::<SinkSpec>().is_err());
+        assert!("ftp:host".parse::<SinkSpec>().unwrap_err().contains("unknown sink"));
+    }
+
+    #[test]
+    fn test_file_sink_reopens_a_rotated_file() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("app.clean.log");
+        let mut sink = FileSink::open(path.clone())?;
+        sink.write_all(b"one\n")?;
+        fs::rename(&path, dir.path().join("app.clean.log.1"))?;
+        sink.write_all(b"two\n")?;
+        sink.flush()?;
+        assert_eq!(fs::read_to_string(dir.path().join("app.clean.log.1"))?, "one\n");
+        assert_eq!(fs::read_to_string(&path)?, "two\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_tcp_sink_sends_lines_to_the_listener() -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let mut sink = SinkSpec::Tcp(listener.local_addr()?.to_string()).open()?;
+        let (stream, _) = listener.accept()?;
+        sink.write_all(b"user=[EMAIL_REDACTED]\n")?;
+        sink.write_all(b"done\n")?;
+        let lines: Vec<String> = BufReader::new(stream).lines().take(2).collect::<io::Result<_>>()?;
+        assert_eq!(lines, vec!["user=[EMAIL_REDACTED]", "done"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tcp_sink_write_times_out_when_the_listener_stops_reading() -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = SocketAddr::Tcp(listener.local_addr()?.to_string());
+        let mut sink = SocketSink::connect_with_timeout(addr, Duration::from_millis(200));
+        let (_stream, _) = listener.accept()?;
+        // Far more than the socket buffers hold; nothing on the other end reads it.
+        let chunk = vec![b'x'; 1 << 20];
+        let started = Instant::now();
+        while sink.stream.is_some() {
+            sink.write_all(&chunk)?;
+            assert!(started.elapsed() < Duration::from_secs(30), "the write never timed out");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_tcp_sink_ends_a_torn_line_when_it_reconnects() -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = SocketAddr::Tcp(listener.local_addr()?.to_string());
+        let mut sink = SocketSink::connect_with_timeout(addr, Duration::from_millis(200));
+        let (mut first, _) = listener.accept()?;
+        // One line far larger than the socket buffers: the write times out partway.
+        let mut line = vec![b'x'; 32 << 20];
+        line.push(b'\n');
+        sink.write_all(&line)?;
+        assert!(sink.stream.is_none() && sink.torn);
+
+        let mut cut = Vec::new();
+        io::Read::read_to_end(&mut first, &mut cut)?;
+        assert!(!cut.is_empty() && cut.iter().all(|&b| b == b'x'), "the first connection ends mid-line");
+
+        std::thread::sleep(INITIAL_BACKOFF * 2);
+        sink.write_all(b"next\n")?;
+        let (mut second, _) = listener.accept()?;
+        drop(sink);
+        let mut resumed = String::new();
+        io::Read::read_to_string(&mut second, &mut resumed)?;
+        assert_eq!(resumed, "\nnext\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_socket_sink_without_a_listener_backs_off_instead_of_failing() -> Result<()> {
+        let port = TcpListener::bind("127.0.0.1:0")?.local_addr()?.port();
+        let mut sink = SinkSpec::Tcp(format!("127.0.0.1:{}", port)).open()?;
+        sink.write_all(b"lost\n")?;
+        sink.flush()?;
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_sink_sends_lines_to_the_listener() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("clean.sock");
+        let listener = std::os::unix::net::UnixListener::bind(&path)?;
+        let mut sink = SinkSpec::Unix(path).open()?;
+        let (stream, _) = listener.accept()?;
+        sink.write_all(b"line\n")?;
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line)?;
+        assert_eq!(line, "line\n");
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_sink_pipes_into_the_command() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("piped.log");
+        let mut sink = CommandSink::spawn(format!("cat > '{}'", path.display()))?;
+        sink.write_all(b"first\nsecond\n")?;
+        // Dropping the sink closes the pipe and waits for `cat` to finish.
+        drop(sink);
+        assert_eq!(fs::read_to_string(&path)?, "first\nsecond\n");
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_sink_gives_up_on_a_command_that_stops_reading() -> Result<()> {
+        let mut sink = CommandSink::spawn_with_timeout("exec sleep 30".to_string(), Duration::from_millis(200))?;
+        // More than the pipe holds; `sleep` never reads it.
+        let started = Instant::now();
+        sink.write_all(&vec![b'x'; 1 << 20])?;
+        assert!(sink.child.is_none(), "the stalled command is dropped");
+        assert!(started.elapsed() < Duration::from_secs(5), "the write waited {:?}", started.elapsed());
+        drop(sink);
+        assert!(started.elapsed() < Duration::from_secs(5), "dropping the sink does not wait on the command");
+        Ok(())
+    }
+}
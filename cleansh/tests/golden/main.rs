@@ -0,0 +1,247 @@
+// cleansh/tests/golden/main.rs
+//! Golden-output tests that drive the real `cleansh` binary.
+//!
+//! Every directory under `tests/golden/cases/` is one case:
+//!
+//! * `cmd.txt` — the arguments, split on whitespace; `'…'` and `"…"` group words.
+//! * `stdin.txt` — piped to the process (optional; stdin is empty otherwise).
+//! * `stdout.txt`, `stderr.pattern` — the expected streams (a missing file means empty).
+//! * `exitcode` — the expected exit status (optional; defaults to `0`).
+//! * `env.txt` — extra `KEY=VALUE` lines for the process environment (optional).
+//!
+//! Any other file is a fixture: it is copied into a fresh working directory the
+//! command runs in, so relative paths in `cmd.txt` resolve to it and anything
+//! the command writes stays out of the source tree. The user's config, rules,
+//! ignore, and cache locations are pointed into that directory too.
+//!
+//! Expectations are literal text with placeholders for what varies between runs:
+//! `{{ANY}}` (rest of a line), `{{...}}` (any text, across lines), `{{NUM}}`,
+//! `{{HEX}}`, `{{UUID}}`, and `{{TIMESTAMP}}` (RFC 3339).
+//!
+//! Run with `CLEANSH_GOLDEN_REGENERATE=1` to rewrite the expectations of failing
+//! cases from the actual output. UUIDs and timestamps are replaced by their
+//! placeholders; expectations that still match are left alone, so hand-written
+//! placeholders survive.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use assert_cmd::Command;
+use regex::Regex;
+
+const REGENERATE_ENV: &str = "CLEANSH_GOLDEN_REGENERATE";
+
+/// Files that describe a case rather than being copied in as fixtures.
+const RESERVED: &[&str] = &["cmd.txt", "stdin.txt", "stdout.txt", "stderr.pattern", "exitcode", "env.txt"];
+
+const PLACEHOLDERS: &[(&str, &str)] = &[
+    ("{{ANY}}", r"[^\n]*"),
+    ("{{...}}", r"(?s:.*?)"),
+    ("{{NUM}}", r"[0-9]+"),
+    ("{{HEX}}", r"[0-9a-f]+"),
+    ("{{UUID}}", r"[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}"),
+    ("{{TIMESTAMP}}", r"[0-9]{4}-[0-9]{2}-[0-9]{2}T[0-9]{2}:[0-9]{2}:[0-9]{2}(?:\.[0-9]+)?(?:Z|[+-][0-9]{2}:[0-9]{2})"),
+];
+
+/// Builds an anchored regex from an expectation: literal text plus placeholders.
+fn expectation_regex(expected: &str) -> Result<Regex> {
+    let mut pattern = String::from("^");
+    let mut rest = expected;
+    while !rest.is_empty() {
+        let next = PLACEHOLDERS.iter()
+            .filter_map(|(name, regex)| rest.find(name).map(|at| (at, *name, *regex)))
+            .min_by_key(|(at, _, _)| *at);
+        match next {
+            Some((at, name, regex)) => {
+                pattern.push_str(&regex::escape(&rest[..at]));
+                pattern.push_str(regex);
+                rest = &rest[at + name.len()..];
+            }
+            None => {
+                pattern.push_str(&regex::escape(rest));
+                rest = "";
+            }
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern).context("invalid expectation")
+}
+
+/// Replaces the run-specific parts of actual output with placeholders.
+fn normalize(actual: &str) -> String {
+    let mut normalized = actual.to_string();
+    for name in ["{{TIMESTAMP}}", "{{UUID}}"] {
+        let (_, regex) = PLACEHOLDERS.iter().find(|(n, _)| *n == name).expect("known placeholder");
+        normalized = Regex::new(regex).expect("valid placeholder").replace_all(&normalized, name).into_owned();
+    }
+    normalized
+}
+
+/// Splits `cmd.txt` into arguments, honoring single and double quotes.
+fn split_args(line: &str) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote = None;
+    for c in line.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.get_or_insert_with(String::new).push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                current.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => args.extend(current.take()),
+            (None, c) => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quote.is_some() {
+        bail!("unterminated quote in {:?}", line);
+    }
+    args.extend(current);
+    Ok(args)
+}
+
+fn read_optional(path: &Path) -> Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(Some(text)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("reading {}", path.display())),
+    }
+}
+
+struct Outcome {
+    stdout: String,
+    stderr: String,
+    code: i32,
+}
+
+fn run_case(case: &Path) -> Result<Outcome> {
+    let work = tempfile::tempdir()?;
+    for entry in fs::read_dir(case)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if entry.file_type()?.is_file() && !RESERVED.iter().any(|r| name == *r) {
+            fs::copy(entry.path(), work.path().join(&name))?;
+        }
+    }
+
+    let cmd = fs::read_to_string(case.join("cmd.txt")).context("every case needs a cmd.txt")?;
+    let home = work.path().join(".home");
+    let mut command = Command::new(assert_cmd::cargo_bin!("cleansh"));
+    command
+        .current_dir(work.path())
+        .args(split_args(&cmd)?)
+        .env_remove("RUST_BACKTRACE")
+        .env_remove("RUST_LIB_BACKTRACE")
+        .env_remove("RUST_LOG")
+        .env("HOME", &home)
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .env("XDG_CACHE_HOME", home.join(".cache"))
+        .env("XDG_DATA_HOME", home.join(".local/share"))
+        .env("APPDATA", home.join("AppData/Roaming"))
+        .env("LOCALAPPDATA", home.join("AppData/Local"))
+        .env("CLEANSH_RULES_FILE", home.join("rules.yaml"))
+        .env("CLEANSH_IGNORE_FILE", home.join(".cleanshignore"))
+        .env("CLEANSH_FINGERPRINT_CACHE", home.join("fingerprints.cache"))
+        .env("NO_COLOR", "1")
+        .write_stdin(read_optional(&case.join("stdin.txt"))?.unwrap_or_default());
+    for line in read_optional(&case.join("env.txt"))?.unwrap_or_default().lines().filter(|l| !l.trim().is_empty()) {
+        let (key, value) = line.split_once('=').with_context(|| format!("env.txt line {:?} is not KEY=VALUE", line))?;
+        command.env(key.trim(), value.trim());
+    }
+
+    let output = command.output()?;
+    Ok(Outcome {
+        stdout: String::from_utf8_lossy(&output.stdout).replace("\r\n", "\n"),
+        stderr: String::from_utf8_lossy(&output.stderr).replace("\r\n", "\n"),
+        code: output.status.code().unwrap_or(-1),
+    })
+}
+
+/// Compares one stream, returning a readable diff on mismatch. With `regenerate`
+/// set, a mismatching expectation is rewritten instead.
+fn check_stream(case: &Path, file: &str, actual: &str, regenerate: bool) -> Result<Option<String>> {
+    let path = case.join(file);
+    let expected = read_optional(&path)?.unwrap_or_default();
+    if expectation_regex(&expected).with_context(|| path.display().to_string())?.is_match(actual) {
+        return Ok(None);
+    }
+    if regenerate {
+        fs::write(&path, normalize(actual))?;
+        return Ok(None);
+    }
+    let patch = diffy::create_patch(&expected, actual);
+    Ok(Some(format!("{} differs (- expected, + actual):\n{}", file, patch)))
+}
+
+fn check_case(case: &Path, regenerate: bool) -> Result<Vec<String>> {
+    let outcome = run_case(case)?;
+    let mut problems = Vec::new();
+    problems.extend(check_stream(case, "stdout.txt", &outcome.stdout, regenerate)?);
+    problems.extend(check_stream(case, "stderr.pattern", &outcome.stderr, regenerate)?);
+
+    let exit_path = case.join("exitcode");
+    let expected_code: i32 = match read_optional(&exit_path)? {
+        Some(text) => text.trim().parse().with_context(|| format!("{} is not a number", exit_path.display()))?,
+        None => 0,
+    };
+    if outcome.code != expected_code {
+        if regenerate {
+            fs::write(&exit_path, format!("{}\n", outcome.code))?;
+        } else {
+            problems.push(format!("exit code {} (expected {})", outcome.code, expected_code));
+        }
+    }
+    Ok(problems)
+}
+
+fn case_dirs() -> Result<Vec<PathBuf>> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden/cases");
+    let mut cases: Vec<PathBuf> = fs::read_dir(&root)
+        .with_context(|| format!("reading {}", root.display()))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<_>>()?;
+    cases.retain(|path| path.is_dir());
+    cases.sort();
+    Ok(cases)
+}
+
+#[test]
+fn golden_cases() -> Result<()> {
+    let regenerate = std::env::var_os(REGENERATE_ENV).is_some();
+    let cases = case_dirs()?;
+    assert!(!cases.is_empty(), "no golden cases found");
+
+    let mut failures = Vec::new();
+    for case in &cases {
+        let name = case.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        match check_case(case, regenerate) {
+            Ok(problems) if problems.is_empty() => {}
+            Ok(problems) => failures.push(format!("[{}]\n{}", name, problems.join("\n"))),
+            Err(e) => failures.push(format!("[{}] could not run: {:#}", name, e)),
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} of {} golden cases failed (rerun with {}=1 to accept the new output):\n\n{}",
+        failures.len(),
+        cases.len(),
+        REGENERATE_ENV,
+        failures.join("\n\n")
+    );
+    Ok(())
+}
+
+#[test]
+fn expectations_support_placeholders() -> Result<()> {
+    let regex = expectation_regex("run {{UUID}} at {{TIMESTAMP}}\n{{...}}done in {{NUM}}ms ({{ANY}})\n")?;
+    assert!(regex.is_match("run 0f8fad5b-d9cb-469f-a165-70867728950e at 2025-10-20T10:00:00Z\nline\nline\ndone in 12ms (fast)\n"));
+    assert!(!regex.is_match("run 0f8fad5b at 2025-10-20T10:00:00Z\ndone in 12ms (fast)\n"));
+    assert!(expectation_regex("a.b [x]")?.is_match("a.b [x]"));
+    assert!(!expectation_regex("a.b")?.is_match("axb"));
+
+    assert_eq!(split_args(r#"scan  --format 'a b' "c d"e"#)?, ["scan", "--format", "a b", "c de"]);
+    assert_eq!(normalize("at 2025-10-20T10:00:00.123+02:00"), "at {{TIMESTAMP}}");
+    Ok(())
+}
@@ -0,0 +1,172 @@
+// cleansh/tests/serve_tests.rs
+//! `cleansh serve` run in-process on a temporary socket.
+#![cfg(unix)]
+
+use anyhow::Result;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+use tokio::sync::oneshot;
+
+use cleansh::commands::serve::{request, serve, EnginePool, ServeOptions, ServeReply, ServeRequest, MAX_CACHED_ENGINES};
+use cleansh_core::config::RedactionConfig;
+use cleansh_core::profiles::EngineOptions;
+use cleansh_core::{EngineHandle, EngineKind, RegexEngine};
+
+/// Starts a regex-engine server on `path`; sending on the returned channel stops it.
+async fn start_server(path: &Path, options: ServeOptions) -> Result<(oneshot::Sender<()>, tokio::task::JoinHandle<Result<()>>)> {
+    let rules = RedactionConfig::load_default_rules()?;
+    let engine = EngineHandle::from_engine(RegexEngine::new(rules.clone())?);
+    let pool = EnginePool::new(engine, EngineKind::Regex, rules, EngineOptions::default());
+    let (stop, stopped) = oneshot::channel::<()>();
+    let socket = path.to_path_buf();
+    let server = tokio::spawn(async move {
+        serve(&socket, pool, &options, async {
+            let _ = stopped.await;
+        }).await
+    });
+    for _ in 0..200 {
+        if UnixStream::connect(path).await.is_ok() {
+            return Ok((stop, server));
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    anyhow::bail!("server did not start listening");
+}
+
+async fn send_line(reader: &mut BufReader<UnixStream>, line: &str) -> Result<ServeReply> {
+    reader.get_mut().write_all(format!("{}\n", line).as_bytes()).await?;
+    let mut reply = String::new();
+    reader.read_line(&mut reply).await?;
+    Ok(serde_json::from_str(&reply)?)
+}
+
+#[tokio::test]
+async fn test_serve_round_trips_requests_and_survives_malformed_json() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("cleansh.sock");
+    let (stop, server) = start_server(&path, ServeOptions::default()).await?;
+
+    let response = request(&path, &ServeRequest {
+        content: "contact admin@example.com".to_string(),
+        source_id: Some("prompt".to_string()),
+        engine: None,
+        profile: None,
+    }).await?;
+    assert_eq!(response.sanitized, "contact [EMAIL_REDACTED]");
+    assert_eq!(response.matches_count, 1);
+    assert_eq!(response.summary[0].rule_name, "email");
+
+    let mut conn = BufReader::new(UnixStream::connect(&path).await?);
+    match send_line(&mut conn, "{not json").await? {
+        ServeReply::Error { error } => assert!(error.contains("Malformed request"), "{}", error),
+        other => panic!("expected an error frame, got {:?}", other),
+    }
+    match send_line(&mut conn, r#"{"content": "x", "engine": "quantum"}"#).await? {
+        ServeReply::Error { error } => assert!(error.contains("unknown engine type 'quantum'"), "{}", error),
+        other => panic!("expected an error frame, got {:?}", other),
+    }
+    // The same connection, and the server, still serve requests.
+    match send_line(&mut conn, r#"{"content": "from 10.0.0.1"}"#).await? {
+        ServeReply::Ok(response) => assert_eq!(response.sanitized, "from [IPV4_REDACTED]"),
+        other => panic!("expected a response, got {:?}", other),
+    }
+    let hybrid = request(&path, &ServeRequest {
+        content: "mail bob@example.com".to_string(),
+        source_id: None,
+        engine: Some("hybrid".to_string()),
+        profile: None,
+    }).await?;
+    assert_eq!(hybrid.sanitized, "mail [EMAIL_REDACTED]");
+
+    use std::os::unix::fs::PermissionsExt;
+    assert_eq!(std::fs::metadata(&path)?.permissions().mode() & 0o777, 0o600);
+    assert_eq!(std::fs::read_dir(dir.path())?.count(), 1, "only the socket is left in its directory");
+
+    let _ = stop.send(());
+    server.await??;
+    assert!(!path.exists(), "the socket is removed on shutdown");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_serve_refuses_to_replace_a_file_that_is_not_a_socket() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("notes.txt");
+    std::fs::write(&path, "keep me\n")?;
+    let rules = RedactionConfig::load_default_rules()?;
+    let engine = EngineHandle::from_engine(RegexEngine::new(rules.clone())?);
+    let pool = EnginePool::new(engine, EngineKind::Regex, rules, EngineOptions::default());
+
+    let err = serve(&path, pool, &ServeOptions::default(), async {}).await.unwrap_err();
+    assert!(err.to_string().contains("exists and is not a socket"), "{}", err);
+    assert_eq!(std::fs::read_to_string(&path)?, "keep me\n");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_serve_closes_idle_connections_after_the_timeout() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("cleansh.sock");
+    let options = ServeOptions { max_connections: 1, timeout: Duration::from_millis(50) };
+    let (stop, server) = start_server(&path, options).await?;
+
+    let mut idle = BufReader::new(UnixStream::connect(&path).await?);
+    let mut reply = String::new();
+    idle.read_line(&mut reply).await?;
+    assert!(reply.contains("timed out"), "{}", reply);
+
+    // With the only permit released, the next connection is served.
+    let response = request(&path, &ServeRequest {
+        content: "contact admin@example.com".to_string(),
+        source_id: None,
+        engine: None,
+        profile: None,
+    }).await?;
+    assert_eq!(response.matches_count, 1);
+
+    let _ = stop.send(());
+    server.await??;
+    Ok(())
+}
+
+#[test]
+fn test_serve_answers_with_the_engine_swapped_into_its_handle() -> Result<()> {
+    let rules = RedactionConfig::load_default_rules()?;
+    let pool = EnginePool::new(EngineHandle::from_engine(RegexEngine::new(rules.clone())?), EngineKind::Regex, rules.clone(), EngineOptions::default());
+    let ask = |pool: &EnginePool| pool.serve(&ServeRequest {
+        content: "contact admin@example.com".to_string(),
+        source_id: None,
+        engine: None,
+        profile: None,
+    });
+    assert_eq!(ask(&pool)?.sanitized, "contact [EMAIL_REDACTED]");
+
+    let mut tagged = rules.clone();
+    tagged.rules.iter_mut().filter(|rule| rule.name == "email").for_each(|rule| rule.replace_with = "[MAIL]".to_string());
+    pool.engine().swap(Arc::new(RegexEngine::new(tagged)?));
+    assert_eq!(ask(&pool)?.sanitized, "contact [MAIL]");
+    Ok(())
+}
+
+#[test]
+fn test_serve_keeps_a_bounded_number_of_request_engines() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let rules = RedactionConfig::load_default_rules()?;
+    let pool = EnginePool::new(EngineHandle::from_engine(RegexEngine::new(rules.clone())?), EngineKind::Regex, rules, EngineOptions::default());
+    for i in 0..MAX_CACHED_ENGINES + 4 {
+        let profile = dir.path().join(format!("p{i}.yaml"));
+        std::fs::write(&profile, format!("profile_name: p{i}\nversion: \"1\"\n"))?;
+        let response = pool.serve(&ServeRequest {
+            content: "contact admin@example.com".to_string(),
+            source_id: None,
+            engine: None,
+            profile: Some(profile.display().to_string()),
+        })?;
+        assert_eq!(response.matches_count, 1);
+    }
+    assert_eq!(pool.cached_engines(), MAX_CACHED_ENGINES);
+    Ok(())
+}
@@ -21,4 +21,67 @@ fn test_engine_analysis_mode() -> Result<()> {
     assert_eq!(ip_stats.occurrences, 1);
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// A short application log: three emails (two distinct) and one IP address.
+const APP_LOG: &str = "\
+2025-10-20T10:00:00Z login alice@example.com
+2025-10-20T10:00:01Z retry alice@example.com from bob@example.com
+2025-10-20T10:00:02Z connect 10.20.30.40
+2025-10-20T10:00:03Z healthy
+";
+
+#[test]
+fn test_stats_count_unique_values_and_lines_per_rule() -> Result<()> {
+    use cleansh::commands::stats::collect_stats;
+
+    let engine = RegexEngine::new(RedactionConfig::load_default_rules()?)?;
+    let stats = collect_stats(&engine, &[("app.log".to_string(), APP_LOG.to_string())], false)?;
+
+    let names: Vec<&str> = stats.iter().map(|s| s.rule_name.as_str()).collect();
+    assert_eq!(names, ["email", "ipv4_address"], "sorted by occurrences");
+    let email = &stats[0];
+    assert_eq!((email.occurrences, email.unique), (3, 2));
+    assert_eq!(email.lines, [("app.log".to_string(), 2, 2), ("app.log".to_string(), 1, 1)]);
+    assert!(email.samples.is_empty(), "values are only kept when asked for");
+    Ok(())
+}
+
+#[test]
+fn test_cli_stats_prints_counts_but_no_secrets() -> Result<()> {
+    use assert_cmd::Command;
+    use predicates::prelude::*;
+
+    let dir = tempfile::tempdir()?;
+    let log = dir.path().join("app.log");
+    std::fs::write(&log, APP_LOG)?;
+    let stats = |args: &[&str]| {
+        let mut cmd = Command::new(assert_cmd::cargo_bin!("cleansh"));
+        cmd.env("HOME", dir.path())
+            .env("CLEANSH_IGNORE_FILE", dir.path().join(".cleanshignore"))
+            .env_remove("CLEANSH_ALLOW_DEBUG_PII")
+            .args(["--engine", "regex", "stats"])
+            .args(args)
+            .arg("--input-file").arg(&log);
+        cmd
+    };
+
+    let output = stats(&["--by-line", "--top", "1"]).assert().success().get_output().stdout.clone();
+    let table = String::from_utf8(output)?;
+    let email_row = table.lines().find(|l| l.contains("email")).expect("email row");
+    let cells: Vec<&str> = email_row.split('|').map(str::trim).filter(|c| !c.is_empty()).collect();
+    assert_eq!(cells, ["email", "medium", "3", "2", "75.0%", "2 (2)"]);
+    assert!(table.contains("25.0%"), "{}", table);
+    assert!(!table.contains("example.com") && !table.contains("10.20.30.40"), "{}", table);
+
+    stats(&["--show-samples"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("set CLEANSH_ALLOW_DEBUG_PII=true"));
+    stats(&["--show-samples"])
+        .env("CLEANSH_ALLOW_DEBUG_PII", "true")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("alice@example.com, bob@example.com"));
+    Ok(())
+}